@@ -1,13 +1,26 @@
-//! Correctness analysis for ownership rules and memory safety
+//! Correctness analysis for ownership rules, memory safety and contracts
 
 use crate::{Analyzer, AnalysisCategory, AprokoResult, Finding, Severity, CategoryConfig};
-use oviec::ast::{AstNode, Statement, Expression, Literal};
+use oviec::ast::{AstNode, Statement, Expression, Literal, BinaryOperator, UnaryOperator, ContractClause, Parameter};
 use std::collections::{HashMap, HashSet};
 
-/// Analyzer for ownership correctness and memory safety
+/// Analyzer for ownership correctness, memory safety and contracts
 pub struct CorrectnessAnalyzer {
     enforce_ownership: bool,
     check_state_transitions: bool,
+    check_contracts: bool,
+}
+
+/// Values known at compile time, by name; `None` shadows an outer constant with an unknown value
+type Constants = HashMap<String, Option<Literal>>;
+
+/// The `requires` clauses of each function and the invariants of each struct in a program
+#[derive(Default)]
+struct Contracts<'a> {
+    requires: HashMap<&'a str, (&'a [Parameter], &'a [ContractClause])>,
+    invariants: HashMap<&'a str, &'a [ContractClause]>,
+    /// Module-level constants, the only names besides its own that a clause can use
+    constants: Constants,
 }
 
 /// Represents the ownership state of a variable
@@ -111,6 +124,7 @@ impl CorrectnessAnalyzer {
         Self {
             enforce_ownership: true,
             check_state_transitions: true,
+            check_contracts: true,
         }
     }
 
@@ -126,8 +140,8 @@ impl CorrectnessAnalyzer {
         
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_statement_ownership(statement, &mut tracker, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_statement_ownership(statement, &mut tracker));
                 }
             }
         }
@@ -136,16 +150,19 @@ impl CorrectnessAnalyzer {
     }
 
     /// Check ownership rules for individual statements
-    fn check_statement_ownership(&self, statement: &Statement, tracker: &mut OwnershipTracker, line: usize) -> Vec<Finding> {
+    fn check_statement_ownership(&self, statement: &Statement, tracker: &mut OwnershipTracker) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
-            Statement::Assignment { identifier, value, mutable } => {
+            Statement::VariableDeclaration { identifier, value, mutable, .. }
+            | Statement::Assignment { identifier, value, mutable, .. } => {
                 // Check if the value expression uses any moved variables
-                findings.extend(self.check_expression_ownership(value, tracker, line));
+                findings.extend(self.check_expression_ownership(value, tracker));
                 
-                // Declare or reassign the variable
-                if tracker.variables.contains_key(identifier) {
+                // Declare or reassign the variable; `let` always declares a new one
+                let declares = matches!(statement, Statement::VariableDeclaration { .. });
+                if !declares && tracker.variables.contains_key(identifier) {
                     // Reassignment - check if variable is mutable
                     if let Some(state) = tracker.get_state(identifier) {
                         match state {
@@ -155,7 +172,7 @@ impl CorrectnessAnalyzer {
                                     severity: Severity::Error,
                                     message: format!("Cannot assign to moved variable '{}'", identifier),
                                     suggestion: Some("Variable has been moved and cannot be used".to_string()),
-                                    location: (line, 1),
+                                    location: (span.line, span.column),
                                     span_length: identifier.len(),
                                     rule_id: "use_after_move".to_string(),
                                 });
@@ -166,7 +183,7 @@ impl CorrectnessAnalyzer {
                                     severity: Severity::Error,
                                     message: format!("Cannot assign to borrowed variable '{}'", identifier),
                                     suggestion: Some("Wait for borrows to end before reassigning".to_string()),
-                                    location: (line, 1),
+                                    location: (span.line, span.column),
                                     span_length: identifier.len(),
                                     rule_id: "assign_to_borrowed".to_string(),
                                 });
@@ -181,14 +198,14 @@ impl CorrectnessAnalyzer {
 
                 // Check if the assignment moves the value
                 if self.is_move_expression(value) {
-                    if let Expression::Identifier(moved_var) = value {
+                    if let Expression::Identifier(moved_var, moved_span) = value {
                         if !tracker.move_variable(moved_var) {
                             findings.push(Finding {
                                 category: AnalysisCategory::Correctness,
                                 severity: Severity::Error,
                                 message: format!("Cannot move from variable '{}'", moved_var),
                                 suggestion: Some("Variable is not in a movable state".to_string()),
-                                location: (line, 1),
+                                location: (moved_span.line, moved_span.column),
                                 span_length: moved_var.len(),
                                 rule_id: "invalid_move".to_string(),
                             });
@@ -196,64 +213,80 @@ impl CorrectnessAnalyzer {
                     }
                 }
             }
+            Statement::PlaceAssignment { value, .. } => {
+                // The assigned value moves into the place
+                findings.extend(self.check_expression_ownership(value, tracker));
+            }
             Statement::Function { parameters, body, .. } => {
                 // Create new scope for function
                 let mut function_tracker = tracker.clone();
                 
                 // Parameters are owned in function scope
                 for param in parameters {
-                    function_tracker.declare_variable(param.clone(), true);
+                    function_tracker.declare_variable(param.name.clone(), true);
                 }
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_ownership(stmt, &mut function_tracker, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_ownership(stmt, &mut function_tracker));
                 }
             }
-            Statement::If { condition, then_block, else_block } => {
-                findings.extend(self.check_expression_ownership(condition, tracker, line));
+            Statement::If { condition, then_block, else_block, .. } => {
+                findings.extend(self.check_expression_ownership(condition, tracker));
                 
                 // Each branch gets its own tracker state
                 let mut then_tracker = tracker.clone();
-                for (i, stmt) in then_block.iter().enumerate() {
-                    findings.extend(self.check_statement_ownership(stmt, &mut then_tracker, line + i + 1));
+                for stmt in then_block {
+                    findings.extend(self.check_statement_ownership(stmt, &mut then_tracker));
                 }
                 
                 if let Some(else_stmts) = else_block {
                     let mut else_tracker = tracker.clone();
-                    for (i, stmt) in else_stmts.iter().enumerate() {
-                        findings.extend(self.check_statement_ownership(stmt, &mut else_tracker, line + i + 1));
+                    for stmt in else_stmts {
+                        findings.extend(self.check_statement_ownership(stmt, &mut else_tracker));
                     }
                 }
             }
-            Statement::While { condition, body } => {
-                findings.extend(self.check_expression_ownership(condition, tracker, line));
+            Statement::Unsafe { body, .. } => {
+                for stmt in body {
+                    findings.extend(self.check_statement_ownership(stmt, tracker));
+                }
+            }
+            Statement::Impl { methods, .. } => {
+                for method in methods {
+                    findings.extend(self.check_statement_ownership(method, tracker));
+                }
+            }
+            Statement::While { condition, body, .. } => {
+                findings.extend(self.check_expression_ownership(condition, tracker));
                 
                 // Loop body can modify tracker state
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_ownership(stmt, tracker, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_ownership(stmt, tracker));
                 }
             }
-            Statement::For { identifier, iterable, body } => {
-                findings.extend(self.check_expression_ownership(iterable, tracker, line));
+            Statement::For { pattern, iterable, body, .. } => {
+                findings.extend(self.check_expression_ownership(iterable, tracker));
                 
-                // Iterator variable is owned in loop scope
+                // Iterator variables are owned in loop scope
                 let mut loop_tracker = tracker.clone();
-                loop_tracker.declare_variable(identifier.clone(), false);
+                for name in pattern.bindings() {
+                    loop_tracker.declare_variable(name.to_string(), false);
+                }
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_ownership(stmt, &mut loop_tracker, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_ownership(stmt, &mut loop_tracker));
                 }
             }
-            Statement::Return { value } => {
+            Statement::Return { value, .. } => {
                 if let Some(expr) = value {
-                    findings.extend(self.check_expression_ownership(expr, tracker, line));
+                    findings.extend(self.check_expression_ownership(expr, tracker));
                 }
             }
-            Statement::Print { expression } => {
-                findings.extend(self.check_expression_ownership(expression, tracker, line));
+            Statement::Print { expression, .. } => {
+                findings.extend(self.check_expression_ownership(expression, tracker));
             }
-            Statement::Expression { expression } => {
-                findings.extend(self.check_expression_ownership(expression, tracker, line));
+            Statement::Expression { expression, .. } => {
+                findings.extend(self.check_expression_ownership(expression, tracker));
             }
             _ => {}
         }
@@ -262,11 +295,12 @@ impl CorrectnessAnalyzer {
     }
 
     /// Check ownership rules for expressions
-    fn check_expression_ownership(&self, expression: &Expression, tracker: &mut OwnershipTracker, line: usize) -> Vec<Finding> {
+    fn check_expression_ownership(&self, expression: &Expression, tracker: &mut OwnershipTracker) -> Vec<Finding> {
+        let span = expression.span();
         let mut findings = Vec::new();
 
         match expression {
-            Expression::Identifier(name) => {
+            Expression::Identifier(name, _) => {
                 if !tracker.is_usable(name) {
                     if let Some(state) = tracker.get_state(name) {
                         match state {
@@ -276,7 +310,7 @@ impl CorrectnessAnalyzer {
                                     severity: Severity::Error,
                                     message: format!("Use of moved variable '{}'", name),
                                     suggestion: Some("Variable has been moved and cannot be used".to_string()),
-                                    location: (line, 1),
+                                    location: (span.line, span.column),
                                     span_length: name.len(),
                                     rule_id: "use_after_move".to_string(),
                                 });
@@ -287,7 +321,7 @@ impl CorrectnessAnalyzer {
                                     severity: Severity::Error,
                                     message: format!("Use of uninitialized variable '{}'", name),
                                     suggestion: Some("Initialize the variable before using it".to_string()),
-                                    location: (line, 1),
+                                    location: (span.line, span.column),
                                     span_length: name.len(),
                                     rule_id: "use_uninitialized".to_string(),
                                 });
@@ -300,7 +334,7 @@ impl CorrectnessAnalyzer {
                             severity: Severity::Error,
                             message: format!("Use of undeclared variable '{}'", name),
                             suggestion: Some("Declare the variable before using it".to_string()),
-                            location: (line, 1),
+                            location: (span.line, span.column),
                             span_length: name.len(),
                             rule_id: "use_undeclared".to_string(),
                         });
@@ -308,26 +342,26 @@ impl CorrectnessAnalyzer {
                 }
             }
             Expression::Binary { left, right, .. } => {
-                findings.extend(self.check_expression_ownership(left, tracker, line));
-                findings.extend(self.check_expression_ownership(right, tracker, line));
+                findings.extend(self.check_expression_ownership(left, tracker));
+                findings.extend(self.check_expression_ownership(right, tracker));
             }
-            Expression::Unary { operand, .. } => {
-                findings.extend(self.check_expression_ownership(operand, tracker, line));
+            Expression::Unary { operand, .. } | Expression::Try { operand, .. } => {
+                findings.extend(self.check_expression_ownership(operand, tracker));
             }
             Expression::Call { arguments, .. } => {
                 for arg in arguments {
-                    findings.extend(self.check_expression_ownership(arg, tracker, line));
+                    findings.extend(self.check_expression_ownership(arg, tracker));
                     
                     // Function calls might move arguments
                     if self.is_move_expression(arg) {
-                        if let Expression::Identifier(moved_var) = arg {
+                        if let Expression::Identifier(moved_var, _) = arg {
                             if !tracker.move_variable(moved_var) {
                                 findings.push(Finding {
                                     category: AnalysisCategory::Correctness,
                                     severity: Severity::Error,
                                     message: format!("Cannot move variable '{}' in function call", moved_var),
                                     suggestion: Some("Variable is not in a movable state".to_string()),
-                                    location: (line, 1),
+                                    location: (span.line, span.column),
                                     span_length: moved_var.len(),
                                     rule_id: "invalid_move_call".to_string(),
                                 });
@@ -337,32 +371,71 @@ impl CorrectnessAnalyzer {
                 }
             }
             Expression::FieldAccess { object, .. } => {
-                findings.extend(self.check_expression_ownership(object, tracker, line));
+                findings.extend(self.check_expression_ownership(object, tracker));
             }
             Expression::StructInstantiation { fields, .. } => {
                 for field in fields {
-                    findings.extend(self.check_expression_ownership(&field.value, tracker, line));
+                    findings.extend(self.check_expression_ownership(&field.value, tracker));
                 }
             }
-            Expression::Range { start, end } => {
-                findings.extend(self.check_expression_ownership(start, tracker, line));
-                findings.extend(self.check_expression_ownership(end, tracker, line));
+            Expression::Range { start, end, .. } => {
+                findings.extend(self.check_expression_ownership(start, tracker));
+                findings.extend(self.check_expression_ownership(end, tracker));
             }
-            Expression::EnumVariantConstruction { data, .. } => {
-                if let Some(data_expr) = data {
-                    findings.extend(self.check_expression_ownership(data_expr, tracker, line));
+            Expression::EnumVariantConstruction { payload, .. } => {
+                for value in payload.values() {
+                    findings.extend(self.check_expression_ownership(value, tracker));
                 }
             }
-            Expression::Index { object, index } => {
-                findings.extend(self.check_expression_ownership(object, tracker, line));
-                findings.extend(self.check_expression_ownership(index, tracker, line));
+            Expression::Index { object, index, .. } => {
+                findings.extend(self.check_expression_ownership(object, tracker));
+                findings.extend(self.check_expression_ownership(index, tracker));
             }
-            Expression::ArrayLiteral { elements } => {
+            Expression::ArrayLiteral { elements, .. } | Expression::Tuple { elements, .. } => {
                 for element in elements {
-                    findings.extend(self.check_expression_ownership(element, tracker, line));
+                    findings.extend(self.check_expression_ownership(element, tracker));
                 }
             }
-            Expression::Literal(_) => {
+            Expression::Match { scrutinee, arms, .. } => {
+                findings.extend(self.check_expression_ownership(scrutinee, tracker));
+
+                // Each arm gets its own tracker state, with the pattern's names owned in it
+                for arm in arms {
+                    let mut arm_tracker = tracker.clone();
+                    for name in arm.pattern.bindings() {
+                        arm_tracker.declare_variable(name.to_string(), false);
+                    }
+                    for stmt in &arm.body {
+                        findings.extend(self.check_statement_ownership(stmt, &mut arm_tracker));
+                    }
+                    if let Some(value) = &arm.value {
+                        findings.extend(self.check_expression_ownership(value, &mut arm_tracker));
+                    }
+                }
+            }
+            Expression::Closure { parameters, body, .. } => {
+                // Closures copy what they capture; their body gets its own scope
+                let mut closure_tracker = tracker.clone();
+                for param in parameters {
+                    closure_tracker.declare_variable(param.name.clone(), true);
+                }
+                for stmt in body {
+                    findings.extend(self.check_statement_ownership(stmt, &mut closure_tracker));
+                }
+            }
+            Expression::IndirectCall { callee, arguments, .. } => {
+                findings.extend(self.check_expression_ownership(callee, tracker));
+                for arg in arguments {
+                    findings.extend(self.check_expression_ownership(arg, tracker));
+                }
+            }
+            Expression::MethodCall { receiver, arguments, .. } => {
+                findings.extend(self.check_expression_ownership(receiver, tracker));
+                for arg in arguments {
+                    findings.extend(self.check_expression_ownership(arg, tracker));
+                }
+            }
+            Expression::Literal(..) => {
                 // Literals don't have ownership issues
             }
         }
@@ -373,7 +446,7 @@ impl CorrectnessAnalyzer {
     /// Check if an expression represents a move operation
     fn is_move_expression(&self, expression: &Expression) -> bool {
         match expression {
-            Expression::Identifier(_) => true, // Simple identifier access is a move
+            Expression::Identifier(..) => true, // Simple identifier access is a move
             Expression::Call { .. } => true,   // Function calls can move arguments
             _ => false,
         }
@@ -392,8 +465,8 @@ impl CorrectnessAnalyzer {
         
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_statement_state_transitions(statement, &mut state_tracker, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_statement_state_transitions(statement, &mut state_tracker));
                 }
             }
         }
@@ -402,11 +475,13 @@ impl CorrectnessAnalyzer {
     }
 
     /// Check state transitions in statements
-    fn check_statement_state_transitions(&self, statement: &Statement, state_tracker: &mut HashMap<String, String>, line: usize) -> Vec<Finding> {
+    fn check_statement_state_transitions(&self, statement: &Statement, state_tracker: &mut HashMap<String, String>) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
-            Statement::Assignment { identifier, value, .. } => {
+            Statement::VariableDeclaration { identifier, value, .. }
+            | Statement::Assignment { identifier, value, .. } => {
                 // Track state changes
                 let new_state = self.infer_state_from_expression(value);
                 
@@ -417,7 +492,7 @@ impl CorrectnessAnalyzer {
                             severity: Severity::Warning,
                             message: format!("Invalid state transition for '{}': {} -> {}", identifier, old_state, new_state),
                             suggestion: Some("Ensure state transitions follow valid patterns".to_string()),
-                            location: (line, 1),
+                            location: (span.line, span.column),
                             span_length: identifier.len(),
                             rule_id: "invalid_state_transition".to_string(),
                         });
@@ -429,27 +504,27 @@ impl CorrectnessAnalyzer {
             Statement::Function { body, .. } => {
                 // Functions have their own state scope
                 let mut function_state = state_tracker.clone();
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_state_transitions(stmt, &mut function_state, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_state_transitions(stmt, &mut function_state));
                 }
             }
             Statement::If { then_block, else_block, .. } => {
                 // Check both branches
                 let mut then_state = state_tracker.clone();
-                for (i, stmt) in then_block.iter().enumerate() {
-                    findings.extend(self.check_statement_state_transitions(stmt, &mut then_state, line + i + 1));
+                for stmt in then_block {
+                    findings.extend(self.check_statement_state_transitions(stmt, &mut then_state));
                 }
                 
                 if let Some(else_stmts) = else_block {
                     let mut else_state = state_tracker.clone();
-                    for (i, stmt) in else_stmts.iter().enumerate() {
-                        findings.extend(self.check_statement_state_transitions(stmt, &mut else_state, line + i + 1));
+                    for stmt in else_stmts {
+                        findings.extend(self.check_statement_state_transitions(stmt, &mut else_state));
                     }
                 }
             }
-            Statement::While { body, .. } | Statement::For { body, .. } => {
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_state_transitions(stmt, state_tracker, line + i + 1));
+            Statement::While { body, .. } | Statement::For { body, .. } | Statement::Unsafe { body, .. } => {
+                for stmt in body {
+                    findings.extend(self.check_statement_state_transitions(stmt, state_tracker));
                 }
             }
             _ => {}
@@ -461,12 +536,14 @@ impl CorrectnessAnalyzer {
     /// Infer state from expression value
     fn infer_state_from_expression(&self, expression: &Expression) -> String {
         match expression {
-            Expression::Literal(Literal::Boolean(true)) => "active".to_string(),
-            Expression::Literal(Literal::Boolean(false)) => "inactive".to_string(),
-            Expression::Literal(Literal::Number(n)) if *n == 0.0 => "empty".to_string(),
-            Expression::Literal(Literal::Number(_)) => "filled".to_string(),
-            Expression::Literal(Literal::String(s)) if s.is_empty() => "empty".to_string(),
-            Expression::Literal(Literal::String(_)) => "filled".to_string(),
+            Expression::Literal(Literal::Boolean(true), _) => "active".to_string(),
+            Expression::Literal(Literal::Boolean(false), _) => "inactive".to_string(),
+            Expression::Literal(Literal::Number(n), _) if *n == 0.0 => "empty".to_string(),
+            Expression::Literal(Literal::Number(_), _) => "filled".to_string(),
+            Expression::Literal(Literal::Integer(0), _) => "empty".to_string(),
+            Expression::Literal(Literal::Integer(_), _) => "filled".to_string(),
+            Expression::Literal(Literal::String(s), _) if s.is_empty() => "empty".to_string(),
+            Expression::Literal(Literal::String(_), _) => "filled".to_string(),
             Expression::Call { .. } => "initialized".to_string(),
             _ => "unknown".to_string(),
         }
@@ -498,13 +575,11 @@ impl CorrectnessAnalyzer {
         
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
+                for statement in statements {
                     findings.extend(self.check_memory_safety_statement(
                         statement, 
                         &mut allocated_resources, 
-                        &mut freed_resources, 
-                        i + 1
-                    ));
+                        &mut freed_resources));
                 }
             }
         }
@@ -532,13 +607,14 @@ impl CorrectnessAnalyzer {
         &self, 
         statement: &Statement, 
         allocated: &mut HashSet<String>, 
-        freed: &mut HashSet<String>, 
-        line: usize
+        freed: &mut HashSet<String>
     ) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
-            Statement::Assignment { identifier, value, .. } => {
+            Statement::VariableDeclaration { identifier, value, .. }
+            | Statement::Assignment { identifier, value, .. } => {
                 // Check for potential allocation patterns
                 if let Expression::Call { function, .. } = value {
                     if function.contains("alloc") || function.contains("new") || function.contains("create") {
@@ -550,7 +626,7 @@ impl CorrectnessAnalyzer {
                                 severity: Severity::Error,
                                 message: format!("Attempting to free unallocated resource '{}'", identifier),
                                 suggestion: Some("Only free resources that have been allocated".to_string()),
-                                location: (line, 1),
+                                location: (span.line, span.column),
                                 span_length: identifier.len(),
                                 rule_id: "free_unallocated".to_string(),
                             });
@@ -560,7 +636,7 @@ impl CorrectnessAnalyzer {
                                 severity: Severity::Error,
                                 message: format!("Double free of resource '{}'", identifier),
                                 suggestion: Some("Resources should only be freed once".to_string()),
-                                location: (line, 1),
+                                location: (span.line, span.column),
                                 span_length: identifier.len(),
                                 rule_id: "double_free".to_string(),
                             });
@@ -575,20 +651,361 @@ impl CorrectnessAnalyzer {
                 let mut func_allocated = allocated.clone();
                 let mut func_freed = freed.clone();
                 
-                for (i, stmt) in body.iter().enumerate() {
+                for stmt in body {
                     findings.extend(self.check_memory_safety_statement(
                         stmt, 
                         &mut func_allocated, 
-                        &mut func_freed, 
-                        line + i + 1
-                    ));
+                        &mut func_freed));
+                }
+            }
+            _ => {}
+        }
+
+        findings
+    }
+}
+
+impl CorrectnessAnalyzer {
+    /// Evaluate contract clauses whose inputs are known at compile time: a clause that
+    /// always fails is an error, one that always holds needs no run-time check
+    fn discharge_contracts(&self, ast: &AstNode) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if !self.check_contracts {
+            return findings;
+        }
+
+        let AstNode::Program(statements) = ast;
+        let mut contracts = Contracts::default();
+        for statement in statements {
+            match statement {
+                Statement::Function { name, parameters, requires, .. } if !requires.is_empty() => {
+                    contracts.requires.insert(name, (parameters, requires));
+                }
+                Statement::Struct { name, invariants, .. } if !invariants.is_empty() => {
+                    contracts.invariants.insert(name, invariants);
+                }
+                Statement::Const { name, value, .. } => {
+                    let value = fold_constant(value, &contracts.constants);
+                    contracts.constants.insert(name.clone(), value);
+                }
+                _ => {}
+            }
+        }
+
+        let mut constants = contracts.constants.clone();
+        for statement in statements {
+            findings.extend(self.discharge_statement_contracts(statement, &contracts, &mut constants, &[]));
+        }
+
+        findings
+    }
+
+    /// Discharge the contracts of the calls and struct values in a statement; `ensures`
+    /// are the postconditions of the function the statement belongs to
+    fn discharge_statement_contracts(
+        &self,
+        statement: &Statement,
+        contracts: &Contracts,
+        constants: &mut Constants,
+        ensures: &[ContractClause],
+    ) -> Vec<Finding> {
+        let span = statement.span();
+        let mut findings = Vec::new();
+
+        match statement {
+            Statement::VariableDeclaration { mutable, identifier, value, .. }
+            | Statement::Assignment { mutable, identifier, value, .. } => {
+                findings.extend(self.discharge_expression_contracts(value, contracts, constants));
+
+                // Only the first binding of an immutable variable is a constant; a `mut`
+                // variable, or one assigned again, may hold anything
+                let value = match constants.get(identifier) {
+                    Some(None) => None,
+                    _ if *mutable => None,
+                    Some(Some(_)) if matches!(statement, Statement::Assignment { .. }) => None,
+                    _ => fold_constant(value, constants),
+                };
+                constants.insert(identifier.clone(), value);
+            }
+            Statement::Destructure { pattern, value, .. } => {
+                findings.extend(self.discharge_expression_contracts(value, contracts, constants));
+                for name in pattern.bindings() {
+                    constants.insert(name.to_string(), None);
+                }
+            }
+            Statement::PlaceAssignment { target, value, .. } => {
+                findings.extend(self.discharge_expression_contracts(target, contracts, constants));
+                findings.extend(self.discharge_expression_contracts(value, contracts, constants));
+            }
+            Statement::Function { parameters, ensures, body, .. } => {
+                let mut function_constants = contracts.constants.clone();
+                for param in parameters {
+                    function_constants.insert(param.name.clone(), None);
+                }
+                for stmt in body {
+                    findings.extend(self.discharge_statement_contracts(stmt, contracts, &mut function_constants, ensures));
+                }
+            }
+            Statement::Impl { methods, .. } => {
+                for method in methods {
+                    findings.extend(self.discharge_statement_contracts(method, contracts, constants, &[]));
+                }
+            }
+            Statement::Return { value: Some(value), .. } => {
+                findings.extend(self.discharge_expression_contracts(value, contracts, constants));
+
+                if !ensures.is_empty() {
+                    if let Some(result) = fold_constant(value, constants) {
+                        let mut clause_constants = contracts.constants.clone();
+                        clause_constants.insert("result".to_string(), Some(result));
+                        findings.extend(self.check_clauses(ensures, "Postcondition", &clause_constants, (span.line, span.column), "return".len()));
+                    }
+                }
+            }
+            Statement::If { condition, then_block, else_block, .. } => {
+                findings.extend(self.discharge_expression_contracts(condition, contracts, constants));
+                for block in std::iter::once(then_block).chain(else_block) {
+                    let mut block_constants = constants.clone();
+                    for stmt in block {
+                        findings.extend(self.discharge_statement_contracts(stmt, contracts, &mut block_constants, ensures));
+                    }
+                }
+            }
+            Statement::While { condition, body, .. } => {
+                findings.extend(self.discharge_expression_contracts(condition, contracts, constants));
+                let mut loop_constants = constants.clone();
+                for stmt in body {
+                    findings.extend(self.discharge_statement_contracts(stmt, contracts, &mut loop_constants, ensures));
+                }
+            }
+            Statement::For { pattern, iterable, body, .. } => {
+                findings.extend(self.discharge_expression_contracts(iterable, contracts, constants));
+                let mut loop_constants = constants.clone();
+                for name in pattern.bindings() {
+                    loop_constants.insert(name.to_string(), None);
+                }
+                for stmt in body {
+                    findings.extend(self.discharge_statement_contracts(stmt, contracts, &mut loop_constants, ensures));
+                }
+            }
+            Statement::Unsafe { body, .. } => {
+                for stmt in body {
+                    findings.extend(self.discharge_statement_contracts(stmt, contracts, constants, ensures));
                 }
             }
+            Statement::Print { expression, .. } | Statement::Expression { expression, .. } => {
+                findings.extend(self.discharge_expression_contracts(expression, contracts, constants));
+            }
             _ => {}
         }
 
         findings
     }
+
+    /// Discharge the contracts of the calls and struct values in an expression
+    fn discharge_expression_contracts(&self, expression: &Expression, contracts: &Contracts, constants: &Constants) -> Vec<Finding> {
+        let span = expression.span();
+        let mut findings = Vec::new();
+
+        match expression {
+            Expression::Call { function, arguments, .. } => {
+                for arg in arguments {
+                    findings.extend(self.discharge_expression_contracts(arg, contracts, constants));
+                }
+
+                if let Some((parameters, requires)) = contracts.requires.get(function.as_str()) {
+                    let mut clause_constants = contracts.constants.clone();
+                    for (param, arg) in parameters.iter().zip(arguments) {
+                        clause_constants.insert(param.name.clone(), fold_constant(arg, constants));
+                    }
+                    let subject = format!("Precondition of '{}'", function);
+                    findings.extend(self.check_clauses(requires, &subject, &clause_constants, (span.line, span.column), function.len()));
+                }
+            }
+            Expression::StructInstantiation { struct_name, fields, .. } => {
+                for field in fields {
+                    findings.extend(self.discharge_expression_contracts(&field.value, contracts, constants));
+                }
+
+                if let Some(invariants) = contracts.invariants.get(struct_name.as_str()) {
+                    let mut clause_constants = contracts.constants.clone();
+                    for field in fields {
+                        clause_constants.insert(format!("self.{}", field.name), fold_constant(&field.value, constants));
+                    }
+                    let subject = format!("Invariant of '{}'", struct_name);
+                    findings.extend(self.check_clauses(invariants, &subject, &clause_constants, (span.line, span.column), struct_name.len()));
+                }
+            }
+            Expression::Binary { left, right, .. } | Expression::Range { start: left, end: right, .. }
+            | Expression::Index { object: left, index: right, .. } => {
+                findings.extend(self.discharge_expression_contracts(left, contracts, constants));
+                findings.extend(self.discharge_expression_contracts(right, contracts, constants));
+            }
+            Expression::Unary { operand, .. } | Expression::Try { operand, .. }
+            | Expression::FieldAccess { object: operand, .. } => {
+                findings.extend(self.discharge_expression_contracts(operand, contracts, constants));
+            }
+            Expression::EnumVariantConstruction { payload, .. } => {
+                for value in payload.values() {
+                    findings.extend(self.discharge_expression_contracts(value, contracts, constants));
+                }
+            }
+            Expression::ArrayLiteral { elements, .. } | Expression::Tuple { elements, .. } => {
+                for element in elements {
+                    findings.extend(self.discharge_expression_contracts(element, contracts, constants));
+                }
+            }
+            Expression::Match { scrutinee, arms, .. } => {
+                findings.extend(self.discharge_expression_contracts(scrutinee, contracts, constants));
+                for arm in arms {
+                    let mut arm_constants = constants.clone();
+                    for name in arm.pattern.bindings() {
+                        arm_constants.insert(name.to_string(), None);
+                    }
+                    for stmt in &arm.body {
+                        findings.extend(self.discharge_statement_contracts(stmt, contracts, &mut arm_constants, &[]));
+                    }
+                    if let Some(value) = &arm.value {
+                        findings.extend(self.discharge_expression_contracts(value, contracts, &arm_constants));
+                    }
+                }
+            }
+            Expression::Closure { parameters, body, .. } => {
+                let mut closure_constants = constants.clone();
+                for param in parameters {
+                    closure_constants.insert(param.name.clone(), None);
+                }
+                for stmt in body {
+                    findings.extend(self.discharge_statement_contracts(stmt, contracts, &mut closure_constants, &[]));
+                }
+            }
+            Expression::IndirectCall { callee: receiver, arguments, .. }
+            | Expression::MethodCall { receiver, arguments, .. } => {
+                findings.extend(self.discharge_expression_contracts(receiver, contracts, constants));
+                for arg in arguments {
+                    findings.extend(self.discharge_expression_contracts(arg, contracts, constants));
+                }
+            }
+            Expression::Identifier(..) | Expression::Literal(..) => {}
+        }
+
+        findings
+    }
+
+    /// Report each clause that evaluates to a constant with the given names in scope
+    fn check_clauses(
+        &self,
+        clauses: &[ContractClause],
+        subject: &str,
+        constants: &Constants,
+        location: (usize, usize),
+        span_length: usize,
+    ) -> Vec<Finding> {
+        clauses.iter().filter_map(|clause| match fold_constant(&clause.condition, constants) {
+            Some(Literal::Boolean(false)) => Some(Finding {
+                category: AnalysisCategory::Correctness,
+                severity: Severity::Error,
+                message: format!("{} '{}' does not hold", subject, clause.text),
+                suggestion: Some("The run-time check of this contract always fails here".to_string()),
+                location,
+                span_length,
+                rule_id: "contract_violation".to_string(),
+            }),
+            Some(Literal::Boolean(true)) => Some(Finding {
+                category: AnalysisCategory::Correctness,
+                severity: Severity::Info,
+                message: format!("{} '{}' always holds here", subject, clause.text),
+                suggestion: None,
+                location,
+                span_length,
+                rule_id: "contract_discharged".to_string(),
+            }),
+            _ => None,
+        }).collect()
+    }
+}
+
+/// Evaluate an expression over literals and known constants, if every value it uses is
+/// known; `name.field` is looked up as the constant `"name.field"`
+fn fold_constant(expression: &Expression, constants: &Constants) -> Option<Literal> {
+    match expression {
+        Expression::Literal(literal, _) => Some(literal.clone()),
+        Expression::Identifier(name, _) => constants.get(name).cloned().flatten(),
+        Expression::FieldAccess { object, field, .. } => match object.as_ref() {
+            Expression::Identifier(name, _) => constants.get(&format!("{}.{}", name, field)).cloned().flatten(),
+            _ => None,
+        },
+        Expression::Unary { operator, operand, .. } => match (operator, fold_constant(operand, constants)?) {
+            (UnaryOperator::Not, Literal::Boolean(b)) => Some(Literal::Boolean(!b)),
+            (UnaryOperator::Negate, Literal::Integer(n)) => n.checked_neg().map(Literal::Integer),
+            (UnaryOperator::Negate, Literal::Number(n)) => Some(Literal::Number(-n)),
+            _ => None,
+        },
+        // `false && x` and `true || x` are known whatever `x` is
+        Expression::Binary { left, operator: BinaryOperator::And, right, .. } => {
+            match (fold_constant(left, constants), fold_constant(right, constants)) {
+                (Some(Literal::Boolean(false)), _) | (_, Some(Literal::Boolean(false))) => Some(Literal::Boolean(false)),
+                (Some(Literal::Boolean(true)), Some(Literal::Boolean(true))) => Some(Literal::Boolean(true)),
+                _ => None,
+            }
+        }
+        Expression::Binary { left, operator: BinaryOperator::Or, right, .. } => {
+            match (fold_constant(left, constants), fold_constant(right, constants)) {
+                (Some(Literal::Boolean(true)), _) | (_, Some(Literal::Boolean(true))) => Some(Literal::Boolean(true)),
+                (Some(Literal::Boolean(false)), Some(Literal::Boolean(false))) => Some(Literal::Boolean(false)),
+                _ => None,
+            }
+        }
+        Expression::Binary { left, operator, right, .. } => {
+            fold_binary(operator, fold_constant(left, constants)?, fold_constant(right, constants)?)
+        }
+        _ => None,
+    }
+}
+
+/// Apply an arithmetic or comparison operator to two constants of the same type
+fn fold_binary(operator: &BinaryOperator, left: Literal, right: Literal) -> Option<Literal> {
+    match (left, right) {
+        (Literal::Integer(a), Literal::Integer(b)) => match operator {
+            BinaryOperator::Add => a.checked_add(b).map(Literal::Integer),
+            BinaryOperator::Subtract => a.checked_sub(b).map(Literal::Integer),
+            BinaryOperator::Multiply => a.checked_mul(b).map(Literal::Integer),
+            BinaryOperator::Divide => a.checked_div(b).map(Literal::Integer),
+            BinaryOperator::Modulo => a.checked_rem(b).map(Literal::Integer),
+            _ => fold_comparison(operator, a.cmp(&b)),
+        },
+        (Literal::Number(a), Literal::Number(b)) => match operator {
+            BinaryOperator::Add => Some(Literal::Number(a + b)),
+            BinaryOperator::Subtract => Some(Literal::Number(a - b)),
+            BinaryOperator::Multiply => Some(Literal::Number(a * b)),
+            BinaryOperator::Divide if b != 0.0 => Some(Literal::Number(a / b)),
+            BinaryOperator::Divide | BinaryOperator::Modulo => None,
+            _ => fold_comparison(operator, a.partial_cmp(&b)?),
+        },
+        (Literal::String(a), Literal::String(b)) => match operator {
+            BinaryOperator::Add => Some(Literal::String(a + &b)),
+            _ => fold_comparison(operator, a.cmp(&b)),
+        },
+        (Literal::Boolean(a), Literal::Boolean(b)) if matches!(operator, BinaryOperator::Equal | BinaryOperator::NotEqual) => {
+            fold_comparison(operator, a.cmp(&b))
+        }
+        _ => None,
+    }
+}
+
+/// Result of a comparison operator given how its operands compare
+fn fold_comparison(operator: &BinaryOperator, ordering: std::cmp::Ordering) -> Option<Literal> {
+    let holds = match operator {
+        BinaryOperator::Equal => ordering.is_eq(),
+        BinaryOperator::NotEqual => ordering.is_ne(),
+        BinaryOperator::Less => ordering.is_lt(),
+        BinaryOperator::LessEqual => ordering.is_le(),
+        BinaryOperator::Greater => ordering.is_gt(),
+        BinaryOperator::GreaterEqual => ordering.is_ge(),
+        _ => return None,
+    };
+    Some(Literal::Boolean(holds))
 }
 
 impl Analyzer for CorrectnessAnalyzer {
@@ -603,6 +1020,9 @@ impl Analyzer for CorrectnessAnalyzer {
         
         // Detect memory safety violations
         findings.extend(self.detect_memory_safety_violations(ast));
+
+        // Discharge contracts that can be decided statically
+        findings.extend(self.discharge_contracts(ast));
         
         Ok(findings)
     }
@@ -619,6 +1039,10 @@ impl Analyzer for CorrectnessAnalyzer {
         if let Some(check_state_transitions) = config.settings.get("check_state_transitions") {
             self.check_state_transitions = check_state_transitions == "true";
         }
+
+        if let Some(check_contracts) = config.settings.get("check_contracts") {
+            self.check_contracts = check_contracts == "true";
+        }
         
         Ok(())
     }
@@ -28,8 +28,8 @@ impl LogicAnalyzer {
 
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_statement_reachability(statement, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_statement_reachability(statement));
                 }
             }
         }
@@ -38,14 +38,15 @@ impl LogicAnalyzer {
     }
 
     /// Check reachability of individual statements
-    fn check_statement_reachability(&self, statement: &Statement, line: usize) -> Vec<Finding> {
+    fn check_statement_reachability(&self, statement: &Statement) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
             Statement::Function { body, .. } => {
-                findings.extend(self.check_function_reachability(body, line));
+                findings.extend(self.check_function_reachability(body));
             }
-            Statement::If { condition, then_block, else_block } => {
+            Statement::If { condition, then_block, else_block, .. } => {
                 // Check for always true/false conditions
                 if let Some(always_result) = self.evaluate_condition_statically(condition) {
                     if always_result {
@@ -56,7 +57,7 @@ impl LogicAnalyzer {
                                     severity: Severity::Warning,
                                     message: "Else block is unreachable due to always-true condition".to_string(),
                                     suggestion: Some("Remove the else block or fix the condition".to_string()),
-                                    location: (line, 1),
+                                    location: (span.line, span.column),
                                     span_length: 0,
                                     rule_id: "unreachable_else".to_string(),
                                 });
@@ -69,7 +70,7 @@ impl LogicAnalyzer {
                                 severity: Severity::Warning,
                                 message: "If block is unreachable due to always-false condition".to_string(),
                                 suggestion: Some("Remove the if block or fix the condition".to_string()),
-                                location: (line, 1),
+                                location: (span.line, span.column),
                                 span_length: 0,
                                 rule_id: "unreachable_if".to_string(),
                             });
@@ -78,26 +79,26 @@ impl LogicAnalyzer {
                 }
 
                 // Recursively check blocks
-                for (i, stmt) in then_block.iter().enumerate() {
-                    findings.extend(self.check_statement_reachability(stmt, line + i + 1));
+                for stmt in then_block {
+                    findings.extend(self.check_statement_reachability(stmt));
                 }
                 
                 if let Some(else_stmts) = else_block {
-                    for (i, stmt) in else_stmts.iter().enumerate() {
-                        findings.extend(self.check_statement_reachability(stmt, line + i + 1));
+                    for stmt in else_stmts {
+                        findings.extend(self.check_statement_reachability(stmt));
                     }
                 }
             }
-            Statement::While { condition, body } => {
+            Statement::While { label, condition, body, .. } => {
                 // Check for infinite loops
                 if let Some(always_result) = self.evaluate_condition_statically(condition) {
-                    if always_result {
+                    if always_result && !Self::breaks_out_of(body, label, false) {
                         findings.push(Finding {
                             category: AnalysisCategory::Logic,
                             severity: Severity::Warning,
                             message: "Potential infinite loop detected".to_string(),
                             suggestion: Some("Ensure the loop condition can become false".to_string()),
-                            location: (line, 1),
+                            location: (span.line, span.column),
                             span_length: 0,
                             rule_id: "infinite_loop".to_string(),
                         });
@@ -107,20 +108,20 @@ impl LogicAnalyzer {
                             severity: Severity::Warning,
                             message: "While loop body is unreachable due to always-false condition".to_string(),
                             suggestion: Some("Fix the condition or remove the loop".to_string()),
-                            location: (line, 1),
+                            location: (span.line, span.column),
                             span_length: 0,
                             rule_id: "unreachable_while_body".to_string(),
                         });
                     }
                 }
 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_reachability(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_reachability(stmt));
                 }
             }
             Statement::For { body, .. } => {
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_reachability(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_reachability(stmt));
                 }
             }
             _ => {}
@@ -130,20 +131,19 @@ impl LogicAnalyzer {
     }
 
     /// Check function for unreachable code after return statements
-    fn check_function_reachability(&self, body: &[Statement], start_line: usize) -> Vec<Finding> {
+    fn check_function_reachability(&self, body: &[Statement]) -> Vec<Finding> {
         let mut findings = Vec::new();
         let mut found_return = false;
 
-        for (i, statement) in body.iter().enumerate() {
-            let line = start_line + i;
-            
+        for statement in body {
             if found_return {
+                let span = statement.span();
                 findings.push(Finding {
                     category: AnalysisCategory::Logic,
                     severity: Severity::Warning,
                     message: "Unreachable code after return statement".to_string(),
                     suggestion: Some("Remove unreachable code or restructure function".to_string()),
-                    location: (line, 1),
+                    location: (span.line, span.column),
                     span_length: 0,
                     rule_id: "unreachable_after_return".to_string(),
                 });
@@ -154,7 +154,7 @@ impl LogicAnalyzer {
                 found_return = true;
             }
 
-            findings.extend(self.check_statement_reachability(statement, line));
+            findings.extend(self.check_statement_reachability(statement));
         }
 
         findings
@@ -173,8 +173,8 @@ impl LogicAnalyzer {
         
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_variable_usage(statement, &mut declared_vars, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_variable_usage(statement, &mut declared_vars));
                 }
             }
         }
@@ -183,73 +183,101 @@ impl LogicAnalyzer {
     }
 
     /// Check variable usage patterns
-    fn check_variable_usage(&self, statement: &Statement, declared_vars: &mut HashSet<String>, line: usize) -> Vec<Finding> {
+    fn check_variable_usage(&self, statement: &Statement, declared_vars: &mut HashSet<String>) -> Vec<Finding> {
         let mut findings = Vec::new();
 
         match statement {
-            Statement::Assignment { identifier, value, .. } => {
+            Statement::VariableDeclaration { identifier, value, .. }
+            | Statement::Assignment { identifier, value, .. } => {
                 // Check if value uses undeclared variables
-                findings.extend(self.check_expression_variables(value, declared_vars, line));
+                findings.extend(self.check_expression_variables(value, declared_vars));
                 
                 // Declare this variable
                 declared_vars.insert(identifier.clone());
             }
-            Statement::Print { expression } => {
-                findings.extend(self.check_expression_variables(expression, declared_vars, line));
+            Statement::Destructure { pattern, value, .. } => {
+                findings.extend(self.check_expression_variables(value, declared_vars));
+                declared_vars.extend(pattern.bindings().into_iter().map(str::to_string));
+            }
+            Statement::Const { name, value, .. } => {
+                findings.extend(self.check_expression_variables(value, declared_vars));
+                declared_vars.insert(name.clone());
+            }
+            Statement::PlaceAssignment { target, value, .. } => {
+                // The target must already exist; assigning to it declares nothing
+                findings.extend(self.check_expression_variables(value, declared_vars));
+                findings.extend(self.check_expression_variables(target, declared_vars));
+            }
+            Statement::Print { expression, .. } => {
+                findings.extend(self.check_expression_variables(expression, declared_vars));
             }
-            Statement::Function { parameters, body, .. } => {
+            Statement::Function { name, parameters, body, .. } => {
+                // Functions can be used as values
+                declared_vars.insert(name.clone());
+
                 // Function parameters are declared in function scope
                 let mut function_vars = declared_vars.clone();
                 for param in parameters {
-                    function_vars.insert(param.clone());
+                    function_vars.insert(param.name.clone());
                 }
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_variable_usage(stmt, &mut function_vars, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_variable_usage(stmt, &mut function_vars));
                 }
             }
-            Statement::If { condition, then_block, else_block } => {
-                findings.extend(self.check_expression_variables(condition, declared_vars, line));
+            Statement::If { condition, then_block, else_block, .. } => {
+                findings.extend(self.check_expression_variables(condition, declared_vars));
                 
                 // Check blocks with current variable scope
                 let mut then_vars = declared_vars.clone();
-                for (i, stmt) in then_block.iter().enumerate() {
-                    findings.extend(self.check_variable_usage(stmt, &mut then_vars, line + i + 1));
+                for stmt in then_block {
+                    findings.extend(self.check_variable_usage(stmt, &mut then_vars));
                 }
                 
                 if let Some(else_stmts) = else_block {
                     let mut else_vars = declared_vars.clone();
-                    for (i, stmt) in else_stmts.iter().enumerate() {
-                        findings.extend(self.check_variable_usage(stmt, &mut else_vars, line + i + 1));
+                    for stmt in else_stmts {
+                        findings.extend(self.check_variable_usage(stmt, &mut else_vars));
                     }
                 }
             }
-            Statement::While { condition, body } => {
-                findings.extend(self.check_expression_variables(condition, declared_vars, line));
+            Statement::Impl { methods, .. } => {
+                for method in methods {
+                    findings.extend(self.check_variable_usage(method, &mut declared_vars.clone()));
+                }
+            }
+            Statement::Unsafe { body, .. } => {
+                let mut block_vars = declared_vars.clone();
+                for stmt in body {
+                    findings.extend(self.check_variable_usage(stmt, &mut block_vars));
+                }
+            }
+            Statement::While { condition, body, .. } => {
+                findings.extend(self.check_expression_variables(condition, declared_vars));
                 
                 let mut loop_vars = declared_vars.clone();
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_variable_usage(stmt, &mut loop_vars, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_variable_usage(stmt, &mut loop_vars));
                 }
             }
-            Statement::For { identifier, iterable, body } => {
-                findings.extend(self.check_expression_variables(iterable, declared_vars, line));
+            Statement::For { pattern, iterable, body, .. } => {
+                findings.extend(self.check_expression_variables(iterable, declared_vars));
                 
-                // Iterator variable is declared in loop scope
+                // Iterator variables are declared in loop scope
                 let mut loop_vars = declared_vars.clone();
-                loop_vars.insert(identifier.clone());
+                loop_vars.extend(pattern.bindings().into_iter().map(str::to_string));
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_variable_usage(stmt, &mut loop_vars, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_variable_usage(stmt, &mut loop_vars));
                 }
             }
-            Statement::Return { value } => {
+            Statement::Return { value, .. } => {
                 if let Some(expr) = value {
-                    findings.extend(self.check_expression_variables(expr, declared_vars, line));
+                    findings.extend(self.check_expression_variables(expr, declared_vars));
                 }
             }
-            Statement::Expression { expression } => {
-                findings.extend(self.check_expression_variables(expression, declared_vars, line));
+            Statement::Expression { expression, .. } => {
+                findings.extend(self.check_expression_variables(expression, declared_vars));
             }
             _ => {}
         }
@@ -258,62 +286,100 @@ impl LogicAnalyzer {
     }
 
     /// Check if expression uses undeclared variables
-    fn check_expression_variables(&self, expression: &Expression, declared_vars: &HashSet<String>, line: usize) -> Vec<Finding> {
+    fn check_expression_variables(&self, expression: &Expression, declared_vars: &HashSet<String>) -> Vec<Finding> {
+        let span = expression.span();
         let mut findings = Vec::new();
 
         match expression {
-            Expression::Identifier(name) => {
+            Expression::Identifier(name, _) => {
                 if !declared_vars.contains(name) && !self.is_builtin_identifier(name) {
                     findings.push(Finding {
                         category: AnalysisCategory::Logic,
                         severity: Severity::Error,
                         message: format!("Variable '{}' used before declaration", name),
                         suggestion: Some("Declare the variable before using it".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: name.len(),
                         rule_id: "undeclared_variable".to_string(),
                     });
                 }
             }
             Expression::Binary { left, right, .. } => {
-                findings.extend(self.check_expression_variables(left, declared_vars, line));
-                findings.extend(self.check_expression_variables(right, declared_vars, line));
+                findings.extend(self.check_expression_variables(left, declared_vars));
+                findings.extend(self.check_expression_variables(right, declared_vars));
             }
-            Expression::Unary { operand, .. } => {
-                findings.extend(self.check_expression_variables(operand, declared_vars, line));
+            Expression::Unary { operand, .. } | Expression::Try { operand, .. } => {
+                findings.extend(self.check_expression_variables(operand, declared_vars));
             }
             Expression::Call { arguments, .. } => {
                 for arg in arguments {
-                    findings.extend(self.check_expression_variables(arg, declared_vars, line));
+                    findings.extend(self.check_expression_variables(arg, declared_vars));
                 }
             }
             Expression::FieldAccess { object, .. } => {
-                findings.extend(self.check_expression_variables(object, declared_vars, line));
+                findings.extend(self.check_expression_variables(object, declared_vars));
             }
             Expression::StructInstantiation { fields, .. } => {
                 for field in fields {
-                    findings.extend(self.check_expression_variables(&field.value, declared_vars, line));
+                    findings.extend(self.check_expression_variables(&field.value, declared_vars));
                 }
             }
-            Expression::Range { start, end } => {
-                findings.extend(self.check_expression_variables(start, declared_vars, line));
-                findings.extend(self.check_expression_variables(end, declared_vars, line));
+            Expression::Range { start, end, .. } => {
+                findings.extend(self.check_expression_variables(start, declared_vars));
+                findings.extend(self.check_expression_variables(end, declared_vars));
             }
-            Expression::EnumVariantConstruction { data, .. } => {
-                if let Some(data_expr) = data {
-                    findings.extend(self.check_expression_variables(data_expr, declared_vars, line));
+            Expression::EnumVariantConstruction { payload, .. } => {
+                for value in payload.values() {
+                    findings.extend(self.check_expression_variables(value, declared_vars));
                 }
             }
-            Expression::Index { object, index } => {
-                findings.extend(self.check_expression_variables(object, declared_vars, line));
-                findings.extend(self.check_expression_variables(index, declared_vars, line));
+            Expression::Index { object, index, .. } => {
+                findings.extend(self.check_expression_variables(object, declared_vars));
+                findings.extend(self.check_expression_variables(index, declared_vars));
             }
-            Expression::ArrayLiteral { elements } => {
+            Expression::ArrayLiteral { elements, .. } | Expression::Tuple { elements, .. } => {
                 for element in elements {
-                    findings.extend(self.check_expression_variables(element, declared_vars, line));
+                    findings.extend(self.check_expression_variables(element, declared_vars));
+                }
+            }
+            Expression::Match { scrutinee, arms, .. } => {
+                findings.extend(self.check_expression_variables(scrutinee, declared_vars));
+
+                // Names bound by a pattern are declared in the arm's scope
+                for arm in arms {
+                    let mut arm_vars = declared_vars.clone();
+                    arm_vars.extend(arm.pattern.bindings().into_iter().map(str::to_string));
+                    for stmt in &arm.body {
+                        findings.extend(self.check_variable_usage(stmt, &mut arm_vars));
+                    }
+                    if let Some(value) = &arm.value {
+                        findings.extend(self.check_expression_variables(value, &arm_vars));
+                    }
                 }
             }
-            Expression::Literal(_) => {
+            Expression::Closure { parameters, body, .. } => {
+                // Closure parameters are declared in the closure's scope
+                let mut closure_vars = declared_vars.clone();
+                for param in parameters {
+                    closure_vars.insert(param.name.clone());
+                }
+                for stmt in body {
+                    findings.extend(self.check_variable_usage(stmt, &mut closure_vars));
+                }
+            }
+            Expression::IndirectCall { callee, arguments, .. } => {
+                findings.extend(self.check_expression_variables(callee, declared_vars));
+                for arg in arguments {
+                    findings.extend(self.check_expression_variables(arg, declared_vars));
+                }
+            }
+            Expression::MethodCall { receiver, arguments, .. } => {
+                findings.extend(self.check_expression_variables(receiver, declared_vars));
+                for arg in arguments {
+                    findings.extend(self.check_expression_variables(arg, declared_vars));
+                }
+            }
+            Expression::Literal(..) => {
                 // Literals don't reference variables
             }
         }
@@ -327,12 +393,32 @@ impl LogicAnalyzer {
         matches!(name, "seeAm" | "true" | "false")
     }
 
+    /// Whether `body` contains a `break` that leaves the loop labeled `label`.
+    /// Inside a `nested` loop, only a break naming the label leaves it.
+    fn breaks_out_of(body: &[Statement], label: &Option<String>, nested: bool) -> bool {
+        body.iter().any(|statement| match statement {
+            Statement::Break { label: None, .. } => !nested,
+            Statement::Break { label: target, .. } => target == label,
+            Statement::If { then_block, else_block, .. } => {
+                Self::breaks_out_of(then_block, label, nested)
+                    || else_block.as_ref().map_or(false, |block| Self::breaks_out_of(block, label, nested))
+            }
+            Statement::Unsafe { body, .. } => Self::breaks_out_of(body, label, nested),
+            Statement::Expression { expression: Expression::Match { arms, .. }, .. } => {
+                arms.iter().any(|arm| Self::breaks_out_of(&arm.body, label, nested))
+            }
+            Statement::While { body, .. } | Statement::For { body, .. } => Self::breaks_out_of(body, label, true),
+            _ => false,
+        })
+    }
+
     /// Try to statically evaluate a condition to detect always true/false
     fn evaluate_condition_statically(&self, condition: &Expression) -> Option<bool> {
         match condition {
-            Expression::Literal(Literal::Boolean(b)) => Some(*b),
-            Expression::Literal(Literal::Number(n)) => Some(*n != 0.0),
-            Expression::Literal(Literal::String(s)) => Some(!s.is_empty()),
+            Expression::Literal(Literal::Boolean(b), _) => Some(*b),
+            Expression::Literal(Literal::Number(n), _) => Some(*n != 0.0),
+            Expression::Literal(Literal::Integer(n), _) => Some(*n != 0),
+            Expression::Literal(Literal::String(s), _) => Some(!s.is_empty()),
             _ => None, // Can't statically evaluate complex expressions
         }
     }
@@ -27,8 +27,8 @@ impl PerformanceAnalyzer {
 
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_statement_complexity(statement, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_statement_complexity(statement));
                 }
             }
         }
@@ -37,7 +37,8 @@ impl PerformanceAnalyzer {
     }
 
     /// Check complexity of individual statements
-    fn check_statement_complexity(&self, statement: &Statement, line: usize) -> Vec<Finding> {
+    fn check_statement_complexity(&self, statement: &Statement) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
@@ -49,21 +50,21 @@ impl PerformanceAnalyzer {
                         severity: Severity::Warning,
                         message: format!("High cyclomatic complexity: {} (max: {})", complexity, self.max_complexity),
                         suggestion: Some("Consider breaking this function into smaller functions".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "high_complexity".to_string(),
                     });
                 }
 
                 // Check for nested loops
-                findings.extend(self.check_nested_loops(body, line, 0));
+                findings.extend(self.check_nested_loops(body, 0));
                 
                 // Recursively check body statements
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_complexity(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_complexity(stmt));
                 }
             }
-            Statement::While { condition, body } => {
+            Statement::While { condition, body, .. } => {
                 // Check for potential infinite loops with complex conditions
                 if self.is_complex_condition(condition) {
                     findings.push(Finding {
@@ -71,45 +72,45 @@ impl PerformanceAnalyzer {
                         severity: Severity::Info,
                         message: "Complex condition in while loop may impact performance".to_string(),
                         suggestion: Some("Consider simplifying the condition or caching the result".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "complex_loop_condition".to_string(),
                     });
                 }
 
-                findings.extend(self.check_nested_loops(body, line, 1));
+                findings.extend(self.check_nested_loops(body, 1));
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_complexity(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_complexity(stmt));
                 }
             }
             Statement::For { body, .. } => {
-                findings.extend(self.check_nested_loops(body, line, 1));
+                findings.extend(self.check_nested_loops(body, 1));
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_complexity(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_complexity(stmt));
                 }
             }
-            Statement::If { condition, then_block, else_block } => {
+            Statement::If { condition, then_block, else_block, .. } => {
                 if self.is_complex_condition(condition) {
                     findings.push(Finding {
                         category: AnalysisCategory::Performance,
                         severity: Severity::Info,
                         message: "Complex condition in if statement".to_string(),
                         suggestion: Some("Consider simplifying the condition or using early returns".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "complex_if_condition".to_string(),
                     });
                 }
 
-                for (i, stmt) in then_block.iter().enumerate() {
-                    findings.extend(self.check_statement_complexity(stmt, line + i + 1));
+                for stmt in then_block {
+                    findings.extend(self.check_statement_complexity(stmt));
                 }
                 
                 if let Some(else_stmts) = else_block {
-                    for (i, stmt) in else_stmts.iter().enumerate() {
-                        findings.extend(self.check_statement_complexity(stmt, line + i + 1));
+                    for stmt in else_stmts {
+                        findings.extend(self.check_statement_complexity(stmt));
                     }
                 }
             }
@@ -147,7 +148,7 @@ impl PerformanceAnalyzer {
             Statement::For { body, .. } => {
                 1 + self.calculate_cyclomatic_complexity(body) // +1 for the for
             }
-            Statement::Function { body, .. } => {
+            Statement::Function { body, .. } | Statement::Unsafe { body, .. } => {
                 self.calculate_cyclomatic_complexity(body)
             }
             _ => 0, // Other statements don't add complexity
@@ -155,12 +156,12 @@ impl PerformanceAnalyzer {
     }
 
     /// Check for nested loops that could cause performance issues
-    fn check_nested_loops(&self, statements: &[Statement], start_line: usize, nesting_level: usize) -> Vec<Finding> {
+    fn check_nested_loops(&self, statements: &[Statement], nesting_level: usize) -> Vec<Finding> {
         let mut findings = Vec::new();
 
-        for (i, statement) in statements.iter().enumerate() {
-            let line = start_line + i;
-            
+        for statement in statements {
+            let span = statement.span();
+
             match statement {
                 Statement::While { body, .. } | Statement::For { body, .. } => {
                     let new_nesting = nesting_level + 1;
@@ -171,7 +172,7 @@ impl PerformanceAnalyzer {
                             severity: Severity::Warning,
                             message: format!("Deeply nested loop (level {})", new_nesting),
                             suggestion: Some("Consider extracting inner loops into separate functions".to_string()),
-                            location: (line, 1),
+                            location: (span.line, span.column),
                             span_length: 0,
                             rule_id: "deep_nesting".to_string(),
                         });
@@ -181,23 +182,23 @@ impl PerformanceAnalyzer {
                             severity: Severity::Info,
                             message: "Nested loop detected - consider algorithmic optimization".to_string(),
                             suggestion: Some("Review if the nested loop can be optimized or avoided".to_string()),
-                            location: (line, 1),
+                            location: (span.line, span.column),
                             span_length: 0,
                             rule_id: "nested_loop".to_string(),
                         });
                     }
 
-                    findings.extend(self.check_nested_loops(body, line + 1, new_nesting));
+                    findings.extend(self.check_nested_loops(body, new_nesting));
                 }
                 Statement::If { then_block, else_block, .. } => {
-                    findings.extend(self.check_nested_loops(then_block, line + 1, nesting_level));
+                    findings.extend(self.check_nested_loops(then_block, nesting_level));
                     if let Some(else_stmts) = else_block {
-                        findings.extend(self.check_nested_loops(else_stmts, line + 1, nesting_level));
+                        findings.extend(self.check_nested_loops(else_stmts, nesting_level));
                     }
                 }
                 Statement::Function { body, .. } => {
                     // Functions reset nesting level
-                    findings.extend(self.check_nested_loops(body, line + 1, 0));
+                    findings.extend(self.check_nested_loops(body, 0));
                 }
                 _ => {}
             }
@@ -217,7 +218,7 @@ impl PerformanceAnalyzer {
             Expression::Binary { left, right, .. } => {
                 1 + self.count_expression_operations(left) + self.count_expression_operations(right)
             }
-            Expression::Unary { operand, .. } => {
+            Expression::Unary { operand, .. } | Expression::Try { operand, .. } => {
                 1 + self.count_expression_operations(operand)
             }
             Expression::Call { arguments, .. } => {
@@ -229,19 +230,36 @@ impl PerformanceAnalyzer {
             Expression::StructInstantiation { fields, .. } => {
                 fields.iter().map(|field| self.count_expression_operations(&field.value)).sum()
             }
-            Expression::Range { start, end } => {
+            Expression::Range { start, end, .. } => {
                 self.count_expression_operations(start) + self.count_expression_operations(end)
             }
-            Expression::EnumVariantConstruction { data, .. } => {
-                data.as_ref().map(|d| self.count_expression_operations(d)).unwrap_or(0)
+            Expression::EnumVariantConstruction { payload, .. } => {
+                payload.values().into_iter().map(|v| self.count_expression_operations(v)).sum()
             }
-            Expression::Index { object, index } => {
+            Expression::Index { object, index, .. } => {
                 1 + self.count_expression_operations(object) + self.count_expression_operations(index)
             }
-            Expression::ArrayLiteral { elements } => {
+            Expression::ArrayLiteral { elements, .. } | Expression::Tuple { elements, .. } => {
                 elements.iter().map(|e| self.count_expression_operations(e)).sum()
             }
-            Expression::Identifier(_) | Expression::Literal(_) => 0,
+            Expression::Match { scrutinee, arms, .. } => {
+                1 + self.count_expression_operations(scrutinee)
+                    + arms.iter()
+                        .filter_map(|arm| arm.value.as_ref())
+                        .map(|value| self.count_expression_operations(value))
+                        .sum::<usize>()
+            }
+            Expression::IndirectCall { callee, arguments, .. } => {
+                1 + self.count_expression_operations(callee)
+                    + arguments.iter().map(|arg| self.count_expression_operations(arg)).sum::<usize>()
+            }
+            Expression::MethodCall { receiver, arguments, .. } => {
+                1 + self.count_expression_operations(receiver)
+                    + arguments.iter().map(|arg| self.count_expression_operations(arg)).sum::<usize>()
+            }
+            // Creating a closure does not run its body
+            Expression::Closure { .. } => 1,
+            Expression::Identifier(..) | Expression::Literal(..) => 0,
         }
     }
 
@@ -251,8 +269,8 @@ impl PerformanceAnalyzer {
 
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_statement_optimizations(statement, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_statement_optimizations(statement));
                 }
             }
         }
@@ -261,7 +279,8 @@ impl PerformanceAnalyzer {
     }
 
     /// Check optimization opportunities in statements
-    fn check_statement_optimizations(&self, statement: &Statement, line: usize) -> Vec<Finding> {
+    fn check_statement_optimizations(&self, statement: &Statement) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
@@ -273,13 +292,13 @@ impl PerformanceAnalyzer {
                         severity: Severity::Info,
                         message: "Potentially expensive operation in assignment".to_string(),
                         suggestion: Some("Consider caching the result if used multiple times".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "expensive_assignment".to_string(),
                     });
                 }
             }
-            Statement::While { condition, body } => {
+            Statement::While { condition, body, .. } => {
                 // Check for invariant computations in loop conditions
                 if self.has_loop_invariant_computation(condition) {
                     findings.push(Finding {
@@ -287,34 +306,34 @@ impl PerformanceAnalyzer {
                         severity: Severity::Info,
                         message: "Loop condition contains computation that could be moved outside".to_string(),
                         suggestion: Some("Consider computing invariant values before the loop".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "loop_invariant".to_string(),
                     });
                 }
 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_optimizations(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_optimizations(stmt));
                 }
             }
             Statement::For { body, .. } => {
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_optimizations(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_optimizations(stmt));
                 }
             }
             Statement::Function { body, .. } => {
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_optimizations(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_optimizations(stmt));
                 }
             }
             Statement::If { then_block, else_block, .. } => {
-                for (i, stmt) in then_block.iter().enumerate() {
-                    findings.extend(self.check_statement_optimizations(stmt, line + i + 1));
+                for stmt in then_block {
+                    findings.extend(self.check_statement_optimizations(stmt));
                 }
                 
                 if let Some(else_stmts) = else_block {
-                    for (i, stmt) in else_stmts.iter().enumerate() {
-                        findings.extend(self.check_statement_optimizations(stmt, line + i + 1));
+                    for stmt in else_stmts {
+                        findings.extend(self.check_statement_optimizations(stmt));
                     }
                 }
             }
@@ -27,8 +27,8 @@ impl SecurityAnalyzer {
 
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_statement_safety(statement, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_statement_safety(statement));
                 }
             }
         }
@@ -37,13 +37,14 @@ impl SecurityAnalyzer {
     }
 
     /// Check safety of individual statements
-    fn check_statement_safety(&self, statement: &Statement, line: usize) -> Vec<Finding> {
+    fn check_statement_safety(&self, statement: &Statement) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
             Statement::Assignment { identifier, value, .. } => {
                 // Check for potentially unsafe assignments
-                findings.extend(self.check_expression_safety(value, line));
+                findings.extend(self.check_expression_safety(value));
                 
                 // Check for sensitive variable names
                 if self.is_sensitive_variable_name(identifier) {
@@ -52,13 +53,17 @@ impl SecurityAnalyzer {
                         severity: Severity::Warning,
                         message: format!("Potentially sensitive variable name: '{}'", identifier),
                         suggestion: Some("Consider using a more generic name for sensitive data".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: identifier.len(),
                         rule_id: "sensitive_variable_name".to_string(),
                     });
                 }
             }
-            Statement::Print { expression } => {
+            Statement::PlaceAssignment { target, value, .. } => {
+                findings.extend(self.check_expression_safety(target));
+                findings.extend(self.check_expression_safety(value));
+            }
+            Statement::Print { expression, .. } => {
                 // Check for potential information disclosure
                 if self.might_disclose_sensitive_info(expression) {
                     findings.push(Finding {
@@ -66,15 +71,15 @@ impl SecurityAnalyzer {
                         severity: Severity::Warning,
                         message: "Potential information disclosure in print statement".to_string(),
                         suggestion: Some("Ensure no sensitive information is being printed".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "information_disclosure".to_string(),
                     });
                 }
                 
-                findings.extend(self.check_expression_safety(expression, line));
+                findings.extend(self.check_expression_safety(expression));
             }
-            Statement::Function { name, parameters, body } => {
+            Statement::Function { name, parameters, body, .. } => {
                 // Check for unsafe function patterns
                 if name.contains("unsafe") {
                     findings.push(Finding {
@@ -82,7 +87,7 @@ impl SecurityAnalyzer {
                         severity: Severity::Critical,
                         message: "Function name suggests unsafe operations".to_string(),
                         suggestion: Some("Ensure proper safety checks and documentation".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: name.len(),
                         rule_id: "unsafe_function_name".to_string(),
                     });
@@ -95,39 +100,59 @@ impl SecurityAnalyzer {
                         severity: Severity::Info,
                         message: "Function has many parameters, which may lead to confusion".to_string(),
                         suggestion: Some("Consider using a struct to group related parameters".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "many_parameters".to_string(),
                     });
                 }
 
                 // Recursively check body
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_safety(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_safety(stmt));
                 }
             }
-            Statement::If { condition, then_block, else_block } => {
-                findings.extend(self.check_expression_safety(condition, line));
+            Statement::If { condition, then_block, else_block, .. } => {
+                findings.extend(self.check_expression_safety(condition));
                 
-                for (i, stmt) in then_block.iter().enumerate() {
-                    findings.extend(self.check_statement_safety(stmt, line + i + 1));
+                for stmt in then_block {
+                    findings.extend(self.check_statement_safety(stmt));
                 }
                 
                 if let Some(else_stmts) = else_block {
-                    for (i, stmt) in else_stmts.iter().enumerate() {
-                        findings.extend(self.check_statement_safety(stmt, line + i + 1));
+                    for stmt in else_stmts {
+                        findings.extend(self.check_statement_safety(stmt));
                     }
                 }
             }
-            Statement::While { condition, body } => {
-                findings.extend(self.check_expression_safety(condition, line));
+            Statement::Unsafe { body, .. } => {
+                findings.push(Finding {
+                    category: AnalysisCategory::Security,
+                    severity: Severity::Info,
+                    message: "Unsafe block opts out of compiler safety checks".to_string(),
+                    suggestion: Some("Keep unsafe blocks small and document why they are sound".to_string()),
+                    location: (span.line, span.column),
+                    span_length: 0,
+                    rule_id: "unsafe_block".to_string(),
+                });
+
+                for stmt in body {
+                    findings.extend(self.check_statement_safety(stmt));
+                }
+            }
+            Statement::Impl { methods, .. } => {
+                for method in methods {
+                    findings.extend(self.check_statement_safety(method));
+                }
+            }
+            Statement::While { condition, body, .. } => {
+                findings.extend(self.check_expression_safety(condition));
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_safety(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_safety(stmt));
                 }
             }
-            Statement::For { identifier: _, iterable, body } => {
-                findings.extend(self.check_expression_safety(iterable, line));
+            Statement::For { iterable, body, .. } => {
+                findings.extend(self.check_expression_safety(iterable));
                 
                 // Check for potential iterator issues
                 if self.is_potentially_unsafe_iterator(iterable) {
@@ -136,23 +161,23 @@ impl SecurityAnalyzer {
                         severity: Severity::Warning,
                         message: "Potentially unsafe iterator in for loop".to_string(),
                         suggestion: Some("Validate iterator bounds and safety".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "unsafe_iterator".to_string(),
                     });
                 }
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_safety(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_safety(stmt));
                 }
             }
-            Statement::Return { value } => {
+            Statement::Return { value, .. } => {
                 if let Some(expr) = value {
-                    findings.extend(self.check_expression_safety(expr, line));
+                    findings.extend(self.check_expression_safety(expr));
                 }
             }
-            Statement::Expression { expression } => {
-                findings.extend(self.check_expression_safety(expression, line));
+            Statement::Expression { expression, .. } => {
+                findings.extend(self.check_expression_safety(expression));
             }
             _ => {}
         }
@@ -161,24 +186,25 @@ impl SecurityAnalyzer {
     }
 
     /// Check safety of expressions
-    fn check_expression_safety(&self, expression: &Expression, line: usize) -> Vec<Finding> {
+    fn check_expression_safety(&self, expression: &Expression) -> Vec<Finding> {
+        let span = expression.span();
         let mut findings = Vec::new();
 
         match expression {
-            Expression::Identifier(name) => {
+            Expression::Identifier(name, _) => {
                 if self.is_sensitive_variable_name(name) {
                     findings.push(Finding {
                         category: AnalysisCategory::Security,
                         severity: Severity::Info,
                         message: format!("Reference to potentially sensitive variable: '{}'", name),
                         suggestion: Some("Ensure proper handling of sensitive data".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: name.len(),
                         rule_id: "sensitive_variable_reference".to_string(),
                     });
                 }
             }
-            Expression::Literal(Literal::String(s)) => {
+            Expression::Literal(Literal::String(s), _) => {
                 // Check for hardcoded sensitive information
                 if self.contains_sensitive_pattern(s) {
                     findings.push(Finding {
@@ -186,20 +212,20 @@ impl SecurityAnalyzer {
                         severity: Severity::Critical,
                         message: "Potential hardcoded sensitive information in string literal".to_string(),
                         suggestion: Some("Move sensitive data to configuration or environment variables".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: s.len() + 2, // +2 for quotes
                         rule_id: "hardcoded_sensitive_data".to_string(),
                     });
                 }
             }
             Expression::Binary { left, right, .. } => {
-                findings.extend(self.check_expression_safety(left, line));
-                findings.extend(self.check_expression_safety(right, line));
+                findings.extend(self.check_expression_safety(left));
+                findings.extend(self.check_expression_safety(right));
             }
-            Expression::Unary { operand, .. } => {
-                findings.extend(self.check_expression_safety(operand, line));
+            Expression::Unary { operand, .. } | Expression::Try { operand, .. } => {
+                findings.extend(self.check_expression_safety(operand));
             }
-            Expression::Call { function, arguments } => {
+            Expression::Call { function, arguments, .. } => {
                 // Check for potentially unsafe function calls
                 if self.is_potentially_unsafe_function(function) {
                     findings.push(Finding {
@@ -207,18 +233,18 @@ impl SecurityAnalyzer {
                         severity: Severity::Warning,
                         message: format!("Call to potentially unsafe function: '{}'", function),
                         suggestion: Some("Ensure proper input validation and error handling".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: function.len(),
                         rule_id: "unsafe_function_call".to_string(),
                     });
                 }
 
                 for arg in arguments {
-                    findings.extend(self.check_expression_safety(arg, line));
+                    findings.extend(self.check_expression_safety(arg));
                 }
             }
-            Expression::FieldAccess { object, field } => {
-                findings.extend(self.check_expression_safety(object, line));
+            Expression::FieldAccess { object, field, .. } => {
+                findings.extend(self.check_expression_safety(object));
                 
                 if self.is_sensitive_field_name(field) {
                     findings.push(Finding {
@@ -226,32 +252,32 @@ impl SecurityAnalyzer {
                         severity: Severity::Info,
                         message: format!("Access to potentially sensitive field: '{}'", field),
                         suggestion: Some("Ensure proper access controls for sensitive fields".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: field.len(),
                         rule_id: "sensitive_field_access".to_string(),
                     });
                 }
             }
-            Expression::StructInstantiation { struct_name, fields } => {
+            Expression::StructInstantiation { struct_name, fields, .. } => {
                 if self.is_sensitive_struct_name(struct_name) {
                     findings.push(Finding {
                         category: AnalysisCategory::Security,
                         severity: Severity::Info,
                         message: format!("Instantiation of potentially sensitive struct: '{}'", struct_name),
                         suggestion: Some("Ensure proper initialization of sensitive data structures".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: struct_name.len(),
                         rule_id: "sensitive_struct_instantiation".to_string(),
                     });
                 }
 
                 for field in fields {
-                    findings.extend(self.check_expression_safety(&field.value, line));
+                    findings.extend(self.check_expression_safety(&field.value));
                 }
             }
-            Expression::Range { start, end } => {
-                findings.extend(self.check_expression_safety(start, line));
-                findings.extend(self.check_expression_safety(end, line));
+            Expression::Range { start, end, .. } => {
+                findings.extend(self.check_expression_safety(start));
+                findings.extend(self.check_expression_safety(end));
                 
                 // Check for potential integer overflow in ranges
                 if self.might_cause_overflow(start, end) {
@@ -260,27 +286,55 @@ impl SecurityAnalyzer {
                         severity: Severity::Warning,
                         message: "Range expression might cause integer overflow".to_string(),
                         suggestion: Some("Validate range bounds to prevent overflow".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "range_overflow".to_string(),
                     });
                 }
             }
-            Expression::EnumVariantConstruction { data, .. } => {
-                if let Some(data_expr) = data {
-                    findings.extend(self.check_expression_safety(data_expr, line));
+            Expression::EnumVariantConstruction { payload, .. } => {
+                for value in payload.values() {
+                    findings.extend(self.check_expression_safety(value));
                 }
             }
-            Expression::Index { object, index } => {
-                findings.extend(self.check_expression_safety(object, line));
-                findings.extend(self.check_expression_safety(index, line));
+            Expression::Index { object, index, .. } => {
+                findings.extend(self.check_expression_safety(object));
+                findings.extend(self.check_expression_safety(index));
             }
-            Expression::ArrayLiteral { elements } => {
+            Expression::ArrayLiteral { elements, .. } | Expression::Tuple { elements, .. } => {
                 for element in elements {
-                    findings.extend(self.check_expression_safety(element, line));
+                    findings.extend(self.check_expression_safety(element));
+                }
+            }
+            Expression::Match { scrutinee, arms, .. } => {
+                findings.extend(self.check_expression_safety(scrutinee));
+                for arm in arms {
+                    for stmt in &arm.body {
+                        findings.extend(self.check_statement_safety(stmt));
+                    }
+                    if let Some(value) = &arm.value {
+                        findings.extend(self.check_expression_safety(value));
+                    }
                 }
             }
-            Expression::Literal(_) => {
+            Expression::Closure { body, .. } => {
+                for stmt in body {
+                    findings.extend(self.check_statement_safety(stmt));
+                }
+            }
+            Expression::IndirectCall { callee, arguments, .. } => {
+                findings.extend(self.check_expression_safety(callee));
+                for arg in arguments {
+                    findings.extend(self.check_expression_safety(arg));
+                }
+            }
+            Expression::MethodCall { receiver, arguments, .. } => {
+                findings.extend(self.check_expression_safety(receiver));
+                for arg in arguments {
+                    findings.extend(self.check_expression_safety(arg));
+                }
+            }
+            Expression::Literal(..) => {
                 // Other literals are generally safe
             }
         }
@@ -365,8 +419,8 @@ impl SecurityAnalyzer {
     /// Check if expression might disclose sensitive information
     fn might_disclose_sensitive_info(&self, expr: &Expression) -> bool {
         match expr {
-            Expression::Identifier(name) => self.is_sensitive_variable_name(name),
-            Expression::Literal(Literal::String(s)) => self.contains_sensitive_pattern(s),
+            Expression::Identifier(name, _) => self.is_sensitive_variable_name(name),
+            Expression::Literal(Literal::String(s), _) => self.contains_sensitive_pattern(s),
             Expression::FieldAccess { field, .. } => self.is_sensitive_field_name(field),
             Expression::Binary { left, right, .. } => {
                 self.might_disclose_sensitive_info(left) || self.might_disclose_sensitive_info(right)
@@ -379,10 +433,13 @@ impl SecurityAnalyzer {
     /// Check if range might cause overflow
     fn might_cause_overflow(&self, start: &Expression, end: &Expression) -> bool {
         match (start, end) {
-            (Expression::Literal(Literal::Number(s)), Expression::Literal(Literal::Number(e))) => {
+            (Expression::Literal(Literal::Number(s), _), Expression::Literal(Literal::Number(e), _)) => {
                 // Check for very large ranges
                 (e - s).abs() > 1_000_000.0
             }
+            (Expression::Literal(Literal::Integer(s), _), Expression::Literal(Literal::Integer(e), _)) => {
+                e.abs_diff(*s) > 1_000_000
+            }
             _ => false, // Can't statically determine for complex expressions
         }
     }
@@ -405,8 +462,8 @@ impl SecurityAnalyzer {
 
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_memory_safety_statement(statement, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_memory_safety_statement(statement));
                 }
             }
         }
@@ -415,7 +472,8 @@ impl SecurityAnalyzer {
     }
 
     /// Check memory safety of statements
-    fn check_memory_safety_statement(&self, statement: &Statement, line: usize) -> Vec<Finding> {
+    fn check_memory_safety_statement(&self, statement: &Statement) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         // This is a simplified memory safety check
@@ -428,15 +486,15 @@ impl SecurityAnalyzer {
                         severity: Severity::Info,
                         message: "Variable name suggests pointer/reference usage".to_string(),
                         suggestion: Some("Ensure proper memory management for pointer-like variables".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: identifier.len(),
                         rule_id: "pointer_variable".to_string(),
                     });
                 }
             }
             Statement::Function { body, .. } => {
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_memory_safety_statement(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_memory_safety_statement(stmt));
                 }
             }
             _ => {}
@@ -1,7 +1,7 @@
 //! Style analysis for code style and best practices
 
 use crate::{Analyzer, AnalysisCategory, AprokoResult, Finding, Severity, CategoryConfig};
-use oviec::ast::{AstNode, Statement, Expression, Literal};
+use oviec::ast::{AstNode, Statement, Expression, Literal, SourceSpan};
 
 /// Analyzer for code style and best practices
 pub struct StyleAnalyzer {
@@ -29,8 +29,8 @@ impl StyleAnalyzer {
 
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_statement_naming(statement, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_statement_naming(statement));
                 }
             }
         }
@@ -39,51 +39,59 @@ impl StyleAnalyzer {
     }
 
     /// Check naming conventions for statements
-    fn check_statement_naming(&self, statement: &Statement, line: usize) -> Vec<Finding> {
+    fn check_statement_naming(&self, statement: &Statement) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
             Statement::Assignment { identifier, .. } => {
-                findings.extend(self.check_variable_naming(identifier, line));
+                findings.extend(self.check_variable_naming(identifier, span));
             }
-            Statement::Function { name, parameters, body } => {
-                findings.extend(self.check_function_naming(name, line));
+            Statement::Function { name, parameters, body, .. } => {
+                findings.extend(self.check_function_naming(name, span));
                 
                 for param in parameters {
-                    findings.extend(self.check_parameter_naming(param, line));
+                    findings.extend(self.check_parameter_naming(&param.name, param.span));
                 }
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_naming(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_naming(stmt));
                 }
             }
             Statement::Struct { name, .. } => {
-                findings.extend(self.check_type_naming(name, line));
+                findings.extend(self.check_type_naming(name, span));
             }
-            Statement::Enum { name, .. } => {
-                findings.extend(self.check_type_naming(name, line));
+            Statement::Enum { name, .. } | Statement::Trait { name, .. } => {
+                findings.extend(self.check_type_naming(name, span));
             }
-            Statement::For { identifier, body, .. } => {
-                findings.extend(self.check_variable_naming(identifier, line));
+            Statement::For { pattern, body, .. } => {
+                for name in pattern.bindings() {
+                    findings.extend(self.check_variable_naming(name, span));
+                }
                 
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_naming(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_naming(stmt));
                 }
             }
             Statement::If { then_block, else_block, .. } => {
-                for (i, stmt) in then_block.iter().enumerate() {
-                    findings.extend(self.check_statement_naming(stmt, line + i + 1));
+                for stmt in then_block {
+                    findings.extend(self.check_statement_naming(stmt));
                 }
                 
                 if let Some(else_stmts) = else_block {
-                    for (i, stmt) in else_stmts.iter().enumerate() {
-                        findings.extend(self.check_statement_naming(stmt, line + i + 1));
+                    for stmt in else_stmts {
+                        findings.extend(self.check_statement_naming(stmt));
                     }
                 }
             }
-            Statement::While { body, .. } => {
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_naming(stmt, line + i + 1));
+            Statement::While { body, .. } | Statement::Unsafe { body, .. } => {
+                for stmt in body {
+                    findings.extend(self.check_statement_naming(stmt));
+                }
+            }
+            Statement::Impl { methods, .. } => {
+                for method in methods {
+                    findings.extend(self.check_statement_naming(method));
                 }
             }
             _ => {}
@@ -93,7 +101,7 @@ impl StyleAnalyzer {
     }
 
     /// Check variable naming conventions
-    fn check_variable_naming(&self, name: &str, line: usize) -> Vec<Finding> {
+    fn check_variable_naming(&self, name: &str, span: SourceSpan) -> Vec<Finding> {
         let mut findings = Vec::new();
 
         // Check for camelCase convention
@@ -103,7 +111,7 @@ impl StyleAnalyzer {
                 severity: Severity::Warning,
                 message: format!("Variable '{}' should use camelCase or snake_case", name),
                 suggestion: Some("Use camelCase (myVariable) or snake_case (my_variable)".to_string()),
-                location: (line, 1),
+                location: (span.line, span.column),
                 span_length: name.len(),
                 rule_id: "variable_naming_convention".to_string(),
             });
@@ -116,7 +124,7 @@ impl StyleAnalyzer {
                 severity: Severity::Info,
                 message: format!("Variable '{}' has a very short name", name),
                 suggestion: Some("Consider using a more descriptive name".to_string()),
-                location: (line, 1),
+                location: (span.line, span.column),
                 span_length: name.len(),
                 rule_id: "short_variable_name".to_string(),
             });
@@ -129,7 +137,7 @@ impl StyleAnalyzer {
                 severity: Severity::Info,
                 message: format!("Variable '{}' has a very long name", name),
                 suggestion: Some("Consider using a shorter, more concise name".to_string()),
-                location: (line, 1),
+                location: (span.line, span.column),
                 span_length: name.len(),
                 rule_id: "long_variable_name".to_string(),
             });
@@ -142,7 +150,7 @@ impl StyleAnalyzer {
                 severity: Severity::Warning,
                 message: format!("Variable '{}' appears to use Hungarian notation", name),
                 suggestion: Some("Avoid Hungarian notation; use descriptive names instead".to_string()),
-                location: (line, 1),
+                location: (span.line, span.column),
                 span_length: name.len(),
                 rule_id: "hungarian_notation".to_string(),
             });
@@ -152,7 +160,7 @@ impl StyleAnalyzer {
     }
 
     /// Check function naming conventions
-    fn check_function_naming(&self, name: &str, line: usize) -> Vec<Finding> {
+    fn check_function_naming(&self, name: &str, span: SourceSpan) -> Vec<Finding> {
         let mut findings = Vec::new();
 
         // Functions should use camelCase or snake_case
@@ -162,7 +170,7 @@ impl StyleAnalyzer {
                 severity: Severity::Warning,
                 message: format!("Function '{}' should use camelCase or snake_case", name),
                 suggestion: Some("Use camelCase (myFunction) or snake_case (my_function)".to_string()),
-                location: (line, 1),
+                location: (span.line, span.column),
                 span_length: name.len(),
                 rule_id: "function_naming_convention".to_string(),
             });
@@ -175,7 +183,7 @@ impl StyleAnalyzer {
                 severity: Severity::Info,
                 message: format!("Function '{}' should start with a verb", name),
                 suggestion: Some("Function names should describe what they do (e.g., calculateSum, getUserData)".to_string()),
-                location: (line, 1),
+                location: (span.line, span.column),
                 span_length: name.len(),
                 rule_id: "function_verb_naming".to_string(),
             });
@@ -185,13 +193,13 @@ impl StyleAnalyzer {
     }
 
     /// Check parameter naming conventions
-    fn check_parameter_naming(&self, name: &str, line: usize) -> Vec<Finding> {
+    fn check_parameter_naming(&self, name: &str, span: SourceSpan) -> Vec<Finding> {
         // Parameters follow same rules as variables
-        self.check_variable_naming(name, line)
+        self.check_variable_naming(name, span)
     }
 
     /// Check type naming conventions (structs, enums)
-    fn check_type_naming(&self, name: &str, line: usize) -> Vec<Finding> {
+    fn check_type_naming(&self, name: &str, span: SourceSpan) -> Vec<Finding> {
         let mut findings = Vec::new();
 
         // Types should use PascalCase
@@ -201,7 +209,7 @@ impl StyleAnalyzer {
                 severity: Severity::Warning,
                 message: format!("Type '{}' should use PascalCase", name),
                 suggestion: Some("Use PascalCase for type names (e.g., MyStruct, UserData)".to_string()),
-                location: (line, 1),
+                location: (span.line, span.column),
                 span_length: name.len(),
                 rule_id: "type_naming_convention".to_string(),
             });
@@ -214,7 +222,7 @@ impl StyleAnalyzer {
                 severity: Severity::Info,
                 message: format!("Type '{}' should be a noun or noun phrase", name),
                 suggestion: Some("Type names should describe what they represent (e.g., User, DatabaseConnection)".to_string()),
-                location: (line, 1),
+                location: (span.line, span.column),
                 span_length: name.len(),
                 rule_id: "type_noun_naming".to_string(),
             });
@@ -298,8 +306,8 @@ impl StyleAnalyzer {
 
         match ast {
             AstNode::Program(statements) => {
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_statement_best_practices(statement, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_statement_best_practices(statement));
                 }
             }
         }
@@ -308,7 +316,8 @@ impl StyleAnalyzer {
     }
 
     /// Check best practices for statements
-    fn check_statement_best_practices(&self, statement: &Statement, line: usize) -> Vec<Finding> {
+    fn check_statement_best_practices(&self, statement: &Statement) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
@@ -320,7 +329,7 @@ impl StyleAnalyzer {
                         severity: Severity::Warning,
                         message: "Function is very long".to_string(),
                         suggestion: Some("Consider breaking this function into smaller functions".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "long_function".to_string(),
                     });
@@ -333,18 +342,18 @@ impl StyleAnalyzer {
                         severity: Severity::Warning,
                         message: "Function has too many parameters".to_string(),
                         suggestion: Some("Consider using a struct to group related parameters".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "too_many_parameters".to_string(),
                     });
                 }
 
                 // Recursively check body
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_best_practices(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_best_practices(stmt));
                 }
             }
-            Statement::If { condition, then_block, else_block } => {
+            Statement::If { condition, then_block, else_block, .. } => {
                 // Check for complex conditions
                 if self.is_complex_condition(condition) {
                     findings.push(Finding {
@@ -352,7 +361,7 @@ impl StyleAnalyzer {
                         severity: Severity::Info,
                         message: "Complex condition in if statement".to_string(),
                         suggestion: Some("Consider extracting condition to a well-named variable".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "complex_condition".to_string(),
                     });
@@ -366,33 +375,38 @@ impl StyleAnalyzer {
                         severity: Severity::Warning,
                         message: "Deeply nested code block".to_string(),
                         suggestion: Some("Consider using early returns or extracting functions to reduce nesting".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "deep_nesting".to_string(),
                     });
                 }
 
                 // Recursively check blocks
-                for (i, stmt) in then_block.iter().enumerate() {
-                    findings.extend(self.check_statement_best_practices(stmt, line + i + 1));
+                for stmt in then_block {
+                    findings.extend(self.check_statement_best_practices(stmt));
                 }
                 
                 if let Some(else_stmts) = else_block {
-                    for (i, stmt) in else_stmts.iter().enumerate() {
-                        findings.extend(self.check_statement_best_practices(stmt, line + i + 1));
+                    for stmt in else_stmts {
+                        findings.extend(self.check_statement_best_practices(stmt));
                     }
                 }
             }
             Statement::Assignment { value, .. } => {
                 // Check for magic numbers
-                if let Expression::Literal(Literal::Number(n)) = value {
-                    if self.is_magic_number(*n) {
+                let number = match value {
+                    Expression::Literal(Literal::Number(n), _) => Some(*n),
+                    Expression::Literal(Literal::Integer(n), _) => Some(*n as f64),
+                    _ => None,
+                };
+                if let Some(n) = number {
+                    if self.is_magic_number(n) {
                         findings.push(Finding {
                             category: AnalysisCategory::Style,
                             severity: Severity::Info,
                             message: format!("Magic number {} should be a named constant", n),
                             suggestion: Some("Define this number as a named constant".to_string()),
-                            location: (line, 1),
+                            location: (span.line, span.column),
                             span_length: 0,
                             rule_id: "magic_number".to_string(),
                         });
@@ -510,7 +524,7 @@ impl StyleAnalyzer {
                     let else_depth = else_block.as_ref().map_or(0, |stmts| self.calculate_nesting_depth(stmts));
                     1 + then_depth.max(else_depth)
                 }
-                Statement::While { body, .. } | Statement::For { body, .. } => {
+                Statement::While { body, .. } | Statement::For { body, .. } | Statement::Unsafe { body, .. } => {
                     1 + self.calculate_nesting_depth(body)
                 }
                 Statement::Function { body, .. } => {
@@ -41,8 +41,8 @@ impl SyntaxAnalyzer {
                 }
 
                 // Check each statement for syntax issues
-                for (i, statement) in statements.iter().enumerate() {
-                    findings.extend(self.check_statement_syntax(statement, i + 1));
+                for statement in statements {
+                    findings.extend(self.check_statement_syntax(statement));
                 }
             }
         }
@@ -51,14 +51,15 @@ impl SyntaxAnalyzer {
     }
 
     /// Check syntax of individual statements
-    fn check_statement_syntax(&self, statement: &Statement, line: usize) -> Vec<Finding> {
+    fn check_statement_syntax(&self, statement: &Statement) -> Vec<Finding> {
+        let span = statement.span();
         let mut findings = Vec::new();
 
         match statement {
-            Statement::Print { expression } => {
-                findings.extend(self.check_expression_syntax(expression, line));
+            Statement::Print { expression, .. } => {
+                findings.extend(self.check_expression_syntax(expression));
             }
-            Statement::Assignment { identifier, value, mutable } => {
+            Statement::Assignment { identifier, value, mutable, .. } => {
                 // Check identifier naming
                 if identifier.is_empty() {
                     findings.push(Finding {
@@ -66,7 +67,7 @@ impl SyntaxAnalyzer {
                         severity: Severity::Error,
                         message: "Empty identifier in assignment".to_string(),
                         suggestion: Some("Provide a valid identifier name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_identifier".to_string(),
                     });
@@ -79,7 +80,7 @@ impl SyntaxAnalyzer {
                         severity: Severity::Error,
                         message: format!("Reserved keyword '{}' used as identifier", identifier),
                         suggestion: Some("Use a different identifier name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: identifier.len(),
                         rule_id: "reserved_keyword_identifier".to_string(),
                     });
@@ -92,15 +93,19 @@ impl SyntaxAnalyzer {
                         severity: Severity::Warning,
                         message: "Mutable identifier contains special characters".to_string(),
                         suggestion: Some("Use only alphanumeric characters and underscores".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: identifier.len(),
                         rule_id: "invalid_mutable_identifier".to_string(),
                     });
                 }
 
-                findings.extend(self.check_expression_syntax(value, line));
+                findings.extend(self.check_expression_syntax(value));
             }
-            Statement::VariableDeclaration { identifier, value, mutable } => {
+            Statement::PlaceAssignment { target, value, .. } => {
+                findings.extend(self.check_expression_syntax(target));
+                findings.extend(self.check_expression_syntax(value));
+            }
+            Statement::VariableDeclaration { identifier, value, mutable, .. } => {
                 // Check identifier naming
                 if identifier.is_empty() {
                     findings.push(Finding {
@@ -108,7 +113,7 @@ impl SyntaxAnalyzer {
                         severity: Severity::Error,
                         message: "Empty identifier in variable declaration".to_string(),
                         suggestion: Some("Provide a valid identifier name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_identifier".to_string(),
                     });
@@ -121,7 +126,7 @@ impl SyntaxAnalyzer {
                         severity: Severity::Error,
                         message: format!("Reserved keyword '{}' used as identifier", identifier),
                         suggestion: Some("Use a different identifier name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: identifier.len(),
                         rule_id: "reserved_keyword_identifier".to_string(),
                     });
@@ -134,15 +139,18 @@ impl SyntaxAnalyzer {
                         severity: Severity::Warning,
                         message: "Mutable variable identifier contains special characters".to_string(),
                         suggestion: Some("Use only alphanumeric characters and underscores".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: identifier.len(),
                         rule_id: "invalid_mutable_identifier".to_string(),
                     });
                 }
 
-                findings.extend(self.check_expression_syntax(value, line));
+                findings.extend(self.check_expression_syntax(value));
+            }
+            Statement::Destructure { value, .. } | Statement::Const { value, .. } => {
+                findings.extend(self.check_expression_syntax(value));
             }
-            Statement::Function { name, parameters, body } => {
+            Statement::Function { name, parameters, body, .. } => {
                 // Check function name
                 if name.is_empty() {
                     findings.push(Finding {
@@ -150,7 +158,7 @@ impl SyntaxAnalyzer {
                         severity: Severity::Error,
                         message: "Empty function name".to_string(),
                         suggestion: Some("Provide a valid function name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_function_name".to_string(),
                     });
@@ -158,13 +166,13 @@ impl SyntaxAnalyzer {
 
                 // Check parameter names
                 for param in parameters {
-                    if param.is_empty() {
+                    if param.name.is_empty() {
                         findings.push(Finding {
                             category: AnalysisCategory::Syntax,
                             severity: Severity::Error,
                             message: "Empty parameter name".to_string(),
                             suggestion: Some("Provide valid parameter names".to_string()),
-                            location: (line, 1),
+                            location: (param.span.line, param.span.column),
                             span_length: 0,
                             rule_id: "empty_parameter_name".to_string(),
                         });
@@ -178,18 +186,18 @@ impl SyntaxAnalyzer {
                         severity: Severity::Warning,
                         message: "Empty function body".to_string(),
                         suggestion: Some("Consider adding function implementation or return statement".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_function_body".to_string(),
                     });
                 }
 
                 // Recursively check body statements
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_syntax(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_syntax(stmt));
                 }
             }
-            Statement::FunctionDeclaration { name, parameters, body } => {
+            Statement::FunctionDeclaration { name, parameters, body, .. } => {
                 // Check function name
                 if name.is_empty() {
                     findings.push(Finding {
@@ -197,7 +205,7 @@ impl SyntaxAnalyzer {
                         severity: Severity::Error,
                         message: "Empty function name in declaration".to_string(),
                         suggestion: Some("Provide a valid function name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_function_name".to_string(),
                     });
@@ -205,13 +213,13 @@ impl SyntaxAnalyzer {
 
                 // Check parameter names
                 for param in parameters {
-                    if param.is_empty() {
+                    if param.name.is_empty() {
                         findings.push(Finding {
                             category: AnalysisCategory::Syntax,
                             severity: Severity::Error,
                             message: "Empty parameter name in function declaration".to_string(),
                             suggestion: Some("Provide valid parameter names".to_string()),
-                            location: (line, 1),
+                            location: (param.span.line, param.span.column),
                             span_length: 0,
                             rule_id: "empty_parameter_name".to_string(),
                         });
@@ -225,19 +233,19 @@ impl SyntaxAnalyzer {
                         severity: Severity::Warning,
                         message: "Empty function body in declaration".to_string(),
                         suggestion: Some("Consider adding function implementation or return statement".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_function_body".to_string(),
                     });
                 }
 
                 // Recursively check body statements
-                for (i, stmt) in body.iter().enumerate() {
-                    findings.extend(self.check_statement_syntax(stmt, line + i + 1));
+                for stmt in body {
+                    findings.extend(self.check_statement_syntax(stmt));
                 }
             }
-            Statement::If { condition, then_block, else_block } => {
-                findings.extend(self.check_expression_syntax(condition, line));
+            Statement::If { condition, then_block, else_block, .. } => {
+                findings.extend(self.check_expression_syntax(condition));
                 
                 // Check then block
                 if then_block.is_empty() {
@@ -246,7 +254,7 @@ impl SyntaxAnalyzer {
                         severity: Severity::Warning,
                         message: "Empty if block".to_string(),
                         suggestion: Some("Add statements to the if block".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_if_block".to_string(),
                     });
@@ -260,15 +268,15 @@ impl SyntaxAnalyzer {
                             severity: Severity::Warning,
                             message: "Empty else block".to_string(),
                             suggestion: Some("Add statements to the else block or remove it".to_string()),
-                            location: (line, 1),
+                            location: (span.line, span.column),
                             span_length: 0,
                             rule_id: "empty_else_block".to_string(),
                         });
                     }
                 }
             }
-            Statement::While { condition, body } => {
-                findings.extend(self.check_expression_syntax(condition, line));
+            Statement::While { condition, body, .. } => {
+                findings.extend(self.check_expression_syntax(condition));
                 
                 if body.is_empty() {
                     findings.push(Finding {
@@ -276,26 +284,26 @@ impl SyntaxAnalyzer {
                         severity: Severity::Warning,
                         message: "Empty while loop body".to_string(),
                         suggestion: Some("Add statements to the while loop body".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_while_body".to_string(),
                     });
                 }
             }
-            Statement::For { identifier, iterable, body } => {
-                if identifier.is_empty() {
+            Statement::For { pattern, iterable, body, .. } => {
+                if pattern.bindings().iter().any(|name| name.is_empty()) {
                     findings.push(Finding {
                         category: AnalysisCategory::Syntax,
                         severity: Severity::Error,
                         message: "Empty iterator variable in for loop".to_string(),
                         suggestion: Some("Provide a valid iterator variable name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_for_iterator".to_string(),
                     });
                 }
 
-                findings.extend(self.check_expression_syntax(iterable, line));
+                findings.extend(self.check_expression_syntax(iterable));
                 
                 if body.is_empty() {
                     findings.push(Finding {
@@ -303,19 +311,46 @@ impl SyntaxAnalyzer {
                         severity: Severity::Warning,
                         message: "Empty for loop body".to_string(),
                         suggestion: Some("Add statements to the for loop body".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_for_body".to_string(),
                     });
                 }
             }
-            Statement::Return { value } => {
+            Statement::Unsafe { body, .. } => {
+                if body.is_empty() {
+                    findings.push(Finding {
+                        category: AnalysisCategory::Syntax,
+                        severity: Severity::Warning,
+                        message: "Empty unsafe block".to_string(),
+                        suggestion: Some("Remove the unsafe block".to_string()),
+                        location: (span.line, span.column),
+                        span_length: 0,
+                        rule_id: "empty_unsafe_block".to_string(),
+                    });
+                }
+
+                for stmt in body {
+                    findings.extend(self.check_statement_syntax(stmt));
+                }
+            }
+            Statement::Impl { methods, .. } => {
+                for method in methods {
+                    findings.extend(self.check_statement_syntax(method));
+                }
+            }
+            Statement::Trait { methods, .. } => {
+                for stmt in methods.iter().filter_map(|method| method.body.as_ref()).flatten() {
+                    findings.extend(self.check_statement_syntax(stmt));
+                }
+            }
+            Statement::Return { value, .. } => {
                 if let Some(expr) = value {
-                    findings.extend(self.check_expression_syntax(expr, line));
+                    findings.extend(self.check_expression_syntax(expr));
                 }
             }
-            Statement::Expression { expression } => {
-                findings.extend(self.check_expression_syntax(expression, line));
+            Statement::Expression { expression, .. } => {
+                findings.extend(self.check_expression_syntax(expression));
             }
             Statement::Struct { name, .. } => {
                 if name.is_empty() {
@@ -324,7 +359,7 @@ impl SyntaxAnalyzer {
                         severity: Severity::Error,
                         message: "Empty struct name".to_string(),
                         suggestion: Some("Provide a valid struct name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_struct_name".to_string(),
                     });
@@ -337,102 +372,112 @@ impl SyntaxAnalyzer {
                         severity: Severity::Error,
                         message: "Empty enum name".to_string(),
                         suggestion: Some("Provide a valid enum name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_enum_name".to_string(),
                     });
                 }
             }
+            Statement::Break { .. } | Statement::Continue { .. } => {
+                // Loop labels are checked by the compiler
+            }
+            Statement::Use { .. } | Statement::Module { .. } => {
+                // Imports are checked when the module graph is resolved
+            }
+            Statement::Error { .. } => {
+                // Already reported by the parser
+            }
         }
 
         findings
     }
 
     /// Check syntax of expressions
-    fn check_expression_syntax(&self, expression: &Expression, line: usize) -> Vec<Finding> {
+    fn check_expression_syntax(&self, expression: &Expression) -> Vec<Finding> {
+        let span = expression.span();
         let mut findings = Vec::new();
 
         match expression {
-            Expression::Identifier(name) => {
+            Expression::Identifier(name, _) => {
                 if name.is_empty() {
                     findings.push(Finding {
                         category: AnalysisCategory::Syntax,
                         severity: Severity::Error,
                         message: "Empty identifier in expression".to_string(),
                         suggestion: Some("Provide a valid identifier".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_expression_identifier".to_string(),
                     });
                 }
             }
             Expression::Binary { left, right, .. } => {
-                findings.extend(self.check_expression_syntax(left, line));
-                findings.extend(self.check_expression_syntax(right, line));
+                findings.extend(self.check_expression_syntax(left));
+                findings.extend(self.check_expression_syntax(right));
             }
-            Expression::Unary { operand, .. } => {
-                findings.extend(self.check_expression_syntax(operand, line));
+            Expression::Unary { operand, .. } | Expression::Try { operand, .. } => {
+                findings.extend(self.check_expression_syntax(operand));
             }
-            Expression::Call { function, arguments } => {
+            Expression::Call { function, arguments, .. } => {
                 if function.is_empty() {
                     findings.push(Finding {
                         category: AnalysisCategory::Syntax,
                         severity: Severity::Error,
                         message: "Empty function name in call".to_string(),
                         suggestion: Some("Provide a valid function name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_function_call".to_string(),
                     });
                 }
 
                 for arg in arguments {
-                    findings.extend(self.check_expression_syntax(arg, line));
+                    findings.extend(self.check_expression_syntax(arg));
                 }
             }
-            Expression::FieldAccess { object, field } => {
-                findings.extend(self.check_expression_syntax(object, line));
+            Expression::FieldAccess { object, field, .. } => {
+                findings.extend(self.check_expression_syntax(object));
                 if field.is_empty() {
                     findings.push(Finding {
                         category: AnalysisCategory::Syntax,
                         severity: Severity::Error,
                         message: "Empty field name in field access".to_string(),
                         suggestion: Some("Provide a valid field name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_field_access".to_string(),
                     });
                 }
             }
-            Expression::StructInstantiation { struct_name, fields } => {
+            Expression::StructInstantiation { struct_name, fields, .. } => {
                 if struct_name.is_empty() {
                     findings.push(Finding {
                         category: AnalysisCategory::Syntax,
                         severity: Severity::Error,
                         message: "Empty struct name in instantiation".to_string(),
                         suggestion: Some("Provide a valid struct name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_struct_instantiation".to_string(),
                     });
                 }
 
                 for field in fields {
-                    findings.extend(self.check_expression_syntax(&field.value, line));
+                    findings.extend(self.check_expression_syntax(&field.value));
                 }
             }
-            Expression::Range { start, end } => {
-                findings.extend(self.check_expression_syntax(start, line));
-                findings.extend(self.check_expression_syntax(end, line));
+            Expression::Range { start, end, .. } => {
+                findings.extend(self.check_expression_syntax(start));
+                findings.extend(self.check_expression_syntax(end));
             }
-            Expression::EnumVariantConstruction { enum_name, variant_name, data } => {
+            Expression::EnumVariantConstruction { enum_name, variant_name, payload, .. } => {
                 if enum_name.is_empty() {
                     findings.push(Finding {
                         category: AnalysisCategory::Syntax,
                         severity: Severity::Error,
                         message: "Empty enum name in variant construction".to_string(),
                         suggestion: Some("Provide a valid enum name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_enum_variant_construction".to_string(),
                     });
@@ -443,25 +488,53 @@ impl SyntaxAnalyzer {
                         severity: Severity::Error,
                         message: "Empty variant name in enum construction".to_string(),
                         suggestion: Some("Provide a valid variant name".to_string()),
-                        location: (line, 1),
+                        location: (span.line, span.column),
                         span_length: 0,
                         rule_id: "empty_enum_variant_name".to_string(),
                     });
                 }
-                if let Some(data_expr) = data {
-                    findings.extend(self.check_expression_syntax(data_expr, line));
+                for value in payload.values() {
+                    findings.extend(self.check_expression_syntax(value));
                 }
             }
-            Expression::Index { object, index } => {
-                findings.extend(self.check_expression_syntax(object, line));
-                findings.extend(self.check_expression_syntax(index, line));
+            Expression::Index { object, index, .. } => {
+                findings.extend(self.check_expression_syntax(object));
+                findings.extend(self.check_expression_syntax(index));
             }
-            Expression::ArrayLiteral { elements } => {
+            Expression::ArrayLiteral { elements, .. } | Expression::Tuple { elements, .. } => {
                 for element in elements {
-                    findings.extend(self.check_expression_syntax(element, line));
+                    findings.extend(self.check_expression_syntax(element));
+                }
+            }
+            Expression::Match { scrutinee, arms, .. } => {
+                findings.extend(self.check_expression_syntax(scrutinee));
+                for arm in arms {
+                    for stmt in &arm.body {
+                        findings.extend(self.check_statement_syntax(stmt));
+                    }
+                    if let Some(value) = &arm.value {
+                        findings.extend(self.check_expression_syntax(value));
+                    }
+                }
+            }
+            Expression::Closure { body, .. } => {
+                for stmt in body {
+                    findings.extend(self.check_statement_syntax(stmt));
+                }
+            }
+            Expression::IndirectCall { callee, arguments, .. } => {
+                findings.extend(self.check_expression_syntax(callee));
+                for arg in arguments {
+                    findings.extend(self.check_expression_syntax(arg));
+                }
+            }
+            Expression::MethodCall { receiver, arguments, .. } => {
+                findings.extend(self.check_expression_syntax(receiver));
+                for arg in arguments {
+                    findings.extend(self.check_expression_syntax(arg));
                 }
             }
-            Expression::Literal(_) => {
+            Expression::Literal(..) => {
                 // Literals are generally fine syntactically
             }
         }
@@ -24,6 +24,8 @@ pub enum DiagnosticCategory {
     MemoryError,
     /// Logic errors and potential bugs
     LogicError,
+    /// Contract clauses decided at compile time
+    ContractError,
     /// Performance warnings and optimization opportunities
     PerformanceWarning,
     /// Security vulnerabilities
@@ -200,11 +202,7 @@ pub struct DiagnosticStats {
 impl DiagnosticEngine {
     /// Create a new diagnostic engine with default configuration
     pub fn new() -> Self {
-        Self {
-            config: DiagnosticConfig::default(),
-            rules: HashMap::new(),
-            stats: DiagnosticStats::default(),
-        }
+        Self::with_config(DiagnosticConfig::default())
     }
 
     /// Create a diagnostic engine with custom configuration
@@ -231,9 +229,9 @@ impl DiagnosticEngine {
             config_options: HashMap::new(),
             examples: vec![
                 RuleExample {
-                    description: "Missing semicolon".to_string(),
-                    code: "let x = 5\nlet y = 10".to_string(),
-                    expected_message: "Expected ';' after expression".to_string(),
+                    description: "Two statements on one line without a semicolon".to_string(),
+                    code: "let x = 5 let y = 10".to_string(),
+                    expected_message: "Expected ';' or newline after variable declaration".to_string(),
                     is_violation: true,
                 }
             ],
@@ -277,6 +275,42 @@ impl DiagnosticEngine {
             ],
         });
 
+        // Contract rules
+        self.register_rule(DiagnosticRule {
+            id: "contract_violation".to_string(),
+            name: "Contract Violation".to_string(),
+            description: "A requires or invariant clause is false for values known at compile time, so its run-time check always fails".to_string(),
+            category: DiagnosticCategory::ContractError,
+            default_severity: Severity::Error,
+            enabled: true,
+            config_options: HashMap::new(),
+            examples: vec![
+                RuleExample {
+                    description: "Call breaking a precondition".to_string(),
+                    code: "fn half(n: Int) -> Int requires n >= 0 { return n / 2 }\nseeAm half(-4)".to_string(),
+                    expected_message: "Precondition of 'half' 'n >= 0' does not hold".to_string(),
+                    is_violation: true,
+                }
+            ],
+        });
+        self.register_rule(DiagnosticRule {
+            id: "contract_discharged".to_string(),
+            name: "Contract Discharged".to_string(),
+            description: "A requires or invariant clause is true for values known at compile time, so its run-time check always passes".to_string(),
+            category: DiagnosticCategory::ContractError,
+            default_severity: Severity::Info,
+            enabled: true,
+            config_options: HashMap::new(),
+            examples: vec![
+                RuleExample {
+                    description: "Call meeting a precondition".to_string(),
+                    code: "fn half(n: Int) -> Int requires n >= 0 { return n / 2 }\nseeAm half(4)".to_string(),
+                    expected_message: "Precondition of 'half' 'n >= 0' always holds here".to_string(),
+                    is_violation: false,
+                }
+            ],
+        });
+
         // Performance warning rules
         self.register_rule(DiagnosticRule {
             id: "W001".to_string(),
@@ -469,6 +503,7 @@ impl Default for DiagnosticConfig {
                 DiagnosticCategory::OwnershipError,
                 DiagnosticCategory::MemoryError,
                 DiagnosticCategory::LogicError,
+                DiagnosticCategory::ContractError,
                 DiagnosticCategory::PerformanceWarning,
                 DiagnosticCategory::SecurityWarning,
                 DiagnosticCategory::StyleWarning,
@@ -508,6 +543,7 @@ impl fmt::Display for DiagnosticCategory {
             DiagnosticCategory::OwnershipError => write!(f, "ownership-error"),
             DiagnosticCategory::MemoryError => write!(f, "memory-error"),
             DiagnosticCategory::LogicError => write!(f, "logic-error"),
+            DiagnosticCategory::ContractError => write!(f, "contract-error"),
             DiagnosticCategory::PerformanceWarning => write!(f, "performance-warning"),
             DiagnosticCategory::SecurityWarning => write!(f, "security-warning"),
             DiagnosticCategory::StyleWarning => write!(f, "style-warning"),
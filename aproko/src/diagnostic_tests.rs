@@ -279,6 +279,7 @@ mod tests {
         assert_eq!(DiagnosticCategory::OwnershipError.to_string(), "ownership-error");
         assert_eq!(DiagnosticCategory::MemoryError.to_string(), "memory-error");
         assert_eq!(DiagnosticCategory::LogicError.to_string(), "logic-error");
+        assert_eq!(DiagnosticCategory::ContractError.to_string(), "contract-error");
         assert_eq!(DiagnosticCategory::PerformanceWarning.to_string(), "performance-warning");
         assert_eq!(DiagnosticCategory::SecurityWarning.to_string(), "security-warning");
         assert_eq!(DiagnosticCategory::StyleWarning.to_string(), "style-warning");
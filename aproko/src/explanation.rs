@@ -364,6 +364,127 @@ impl ExplanationEngine {
             confidence: 0.92,
         });
 
+        // Ownership error explanations
+        self.add_explanation("E003", Explanation {
+            explanation_type: ExplanationType::DiagnosticExplanation,
+            summary: "Value used after it was moved".to_string(),
+            detailed_explanation: "Assigning a value to another variable or passing it to a function moves it. The original variable no longer owns the value, so reading it afterwards is rejected.".to_string(),
+            code_examples: vec![
+                CodeExample {
+                    description: "Use after move".to_string(),
+                    code: "let x = [1, 2, 3];\nlet y = x;\nseeAm x;  // Error: x was moved into y".to_string(),
+                    language: "ovie".to_string(),
+                    is_good_example: false,
+                    notes: None,
+                },
+                CodeExample {
+                    description: "Use the new owner".to_string(),
+                    code: "let x = [1, 2, 3];\nlet y = x;\nseeAm y;".to_string(),
+                    language: "ovie".to_string(),
+                    is_good_example: true,
+                    notes: None,
+                },
+            ],
+            fix_suggestions: vec![
+                FixSuggestion {
+                    title: "Read the value through its new owner".to_string(),
+                    description: "Replace uses of the moved variable with the variable that now owns the value".to_string(),
+                    steps: vec![
+                        FixStep {
+                            step_number: 1,
+                            description: "Use the new owner".to_string(),
+                            code_change: Some(CodeChange {
+                                change_type: ChangeType::Replace,
+                                original_code: Some("seeAm x;".to_string()),
+                                new_code: "seeAm y;".to_string(),
+                                location: None,
+                            }),
+                            notes: None,
+                        },
+                    ],
+                    confidence: 0.7,
+                    difficulty: DifficultyLevel::Easy,
+                    auto_applicable: false,
+                },
+            ],
+            related_topics: vec!["ownership".to_string(), "moves".to_string()],
+            external_resources: vec![],
+            confidence: 0.85,
+        });
+
+        // Contract explanations
+        self.add_explanation("contract_violation", Explanation {
+            explanation_type: ExplanationType::DiagnosticExplanation,
+            summary: "A contract clause can never hold here".to_string(),
+            detailed_explanation: "Every value the clause depends on is known at compile time, and the clause evaluates to false. The run-time check generated for the clause would abort the program every time this code runs.".to_string(),
+            code_examples: vec![
+                CodeExample {
+                    description: "Call breaking a precondition".to_string(),
+                    code: "fn half(n: Int) -> Int requires n >= 0 { return n / 2 }\nseeAm half(-4)  // Error: 'n >= 0' is false".to_string(),
+                    language: "ovie".to_string(),
+                    is_good_example: false,
+                    notes: Some("The argument is a constant, so the precondition is decided at compile time".to_string()),
+                },
+                CodeExample {
+                    description: "Call meeting the precondition".to_string(),
+                    code: "fn half(n: Int) -> Int requires n >= 0 { return n / 2 }\nseeAm half(4)".to_string(),
+                    language: "ovie".to_string(),
+                    is_good_example: true,
+                    notes: None,
+                },
+            ],
+            fix_suggestions: vec![
+                FixSuggestion {
+                    title: "Pass values that satisfy the contract".to_string(),
+                    description: "Change the arguments or field values so the clause holds".to_string(),
+                    steps: vec![
+                        FixStep {
+                            step_number: 1,
+                            description: "Read the clause named in the diagnostic".to_string(),
+                            code_change: None,
+                            notes: Some("The message quotes the clause and the function or type it belongs to".to_string()),
+                        },
+                        FixStep {
+                            step_number: 2,
+                            description: "Replace the offending value".to_string(),
+                            code_change: Some(CodeChange {
+                                change_type: ChangeType::Replace,
+                                original_code: Some("half(-4)".to_string()),
+                                new_code: "half(4)".to_string(),
+                                location: None,
+                            }),
+                            notes: None,
+                        },
+                    ],
+                    confidence: 0.8,
+                    difficulty: DifficultyLevel::Easy,
+                    auto_applicable: false,
+                },
+            ],
+            related_topics: vec!["contracts".to_string(), "requires".to_string(), "invariant".to_string()],
+            external_resources: vec![],
+            confidence: 0.9,
+        });
+
+        self.add_explanation("contract_discharged", Explanation {
+            explanation_type: ExplanationType::CompilerDecision,
+            summary: "A contract clause always holds here".to_string(),
+            detailed_explanation: "Every value the clause depends on is known at compile time, and the clause evaluates to true. The run-time check for the clause can never fail at this site.".to_string(),
+            code_examples: vec![
+                CodeExample {
+                    description: "Call meeting a precondition".to_string(),
+                    code: "fn half(n: Int) -> Int requires n >= 0 { return n / 2 }\nseeAm half(4)  // 'n >= 0' always holds".to_string(),
+                    language: "ovie".to_string(),
+                    is_good_example: true,
+                    notes: None,
+                },
+            ],
+            fix_suggestions: vec![],
+            related_topics: vec!["contracts".to_string(), "requires".to_string(), "invariant".to_string()],
+            external_resources: vec![],
+            confidence: 0.9,
+        });
+
         // Performance warning explanations
         self.add_explanation("W001", Explanation {
             explanation_type: ExplanationType::OptimizationTip,
@@ -3,7 +3,7 @@
 #[cfg(test)]
 mod tests {
     use crate::*;
-    use oviec::ast::{AstNode, Statement, Expression, Literal};
+    use oviec::ast::{AstNode, Statement, Expression, Literal, SourceSpan, Parameter};
 
     #[test]
     fn test_aproko_engine_with_diagnostic_integration() {
@@ -13,11 +13,13 @@ mod tests {
         let ast = AstNode::Program(vec![
             Statement::Assignment {
                 identifier: "".to_string(), // Empty identifier should trigger diagnostic
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                 mutable: false,
+                span: SourceSpan::default(),
             },
             Statement::Print {
-                expression: Expression::Identifier("undefined_var".to_string()),
+                expression: Expression::Identifier("undefined_var".to_string(), SourceSpan::default()),
+                span: SourceSpan::default(),
             },
         ]);
 
@@ -53,6 +55,89 @@ mod tests {
         assert_eq!(analysis_results.findings.len(), analysis_results.diagnostics.len());
     }
 
+    #[test]
+    fn test_findings_point_at_source_spans() {
+        let engine = AprokoEngine::new();
+        let source = "a = 1;\nseeAm a;\nseeAm missing;";
+
+        let mut lexer = oviec::Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = oviec::Parser::new(tokens);
+        let ast = parser.parse().unwrap();
+
+        let results = engine.analyze(source, &ast).unwrap();
+        let finding = results.findings.iter()
+            .find(|f| f.rule_id == "undeclared_variable")
+            .expect("Expected an undeclared variable finding");
+
+        assert_eq!(finding.location, (3, 7));
+        assert_eq!(finding.span_length, "missing".len());
+    }
+
+    #[test]
+    fn test_let_declarations_are_tracked() {
+        let engine = AprokoEngine::new();
+        let source = "let p = 8\nseeAm p\nmut q = p\nq = 2\nseeAm q\nseeAm missing";
+
+        let mut lexer = oviec::Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = oviec::Parser::new(tokens);
+        let ast = parser.parse().unwrap();
+
+        let results = engine.analyze(source, &ast).unwrap();
+        let mut undeclared: Vec<_> = results.findings.iter()
+            .filter(|f| f.rule_id == "undeclared_variable" || f.rule_id == "use_undeclared")
+            .map(|f| (f.rule_id.as_str(), f.location))
+            .collect();
+        undeclared.sort();
+
+        // Only the variable that was never declared is reported
+        assert_eq!(undeclared, vec![("undeclared_variable", (6, 7)), ("use_undeclared", (6, 7))]);
+    }
+
+    #[test]
+    fn test_contracts_discharged_statically() {
+        let engine = AprokoEngine::new();
+        let source = "const LIMIT: Int = 10\n\
+            struct Interval { lo: Int, hi: Int, invariant self.lo <= self.hi }\n\
+            fn half(n: Int) -> Int requires n >= 0 requires n < LIMIT { return n / 2 }\n\
+            let four = 2 * 2\n\
+            seeAm half(four)\n\
+            seeAm half(0 - four)\n\
+            let gap = Interval { lo: four, hi: 1 }";
+
+        let mut lexer = oviec::Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = oviec::Parser::new(tokens);
+        let ast = parser.parse().unwrap();
+
+        let results = engine.analyze(source, &ast).unwrap();
+        let discharged: Vec<_> = results.findings.iter()
+            .filter(|f| f.rule_id == "contract_discharged")
+            .map(|f| f.location)
+            .collect();
+        let violated: Vec<_> = results.findings.iter()
+            .filter(|f| f.rule_id == "contract_violation")
+            .map(|f| (f.location, f.message.as_str()))
+            .collect();
+
+        // Both preconditions hold for `half(4)`; the second one also holds for `half(-4)`
+        assert_eq!(discharged, vec![(5, 7), (5, 7), (6, 7)]);
+        assert_eq!(violated, vec![
+            ((6, 7), "Precondition of 'half' 'n >= 0' does not hold"),
+            ((7, 11), "Invariant of 'Interval' 'self.lo <= self.hi' does not hold"),
+        ]);
+
+        let contract_diagnostics: Vec<_> = results.diagnostics.iter()
+            .filter(|d| d.rule_id.starts_with("contract_"))
+            .map(|d| (d.category, d.severity))
+            .collect();
+        assert_eq!(contract_diagnostics.len(), 5);
+        assert!(contract_diagnostics.iter().all(|(category, _)| *category == DiagnosticCategory::ContractError));
+        assert_eq!(contract_diagnostics.iter().filter(|(_, severity)| *severity == Severity::Error).count(), 2);
+        assert_eq!(contract_diagnostics.iter().filter(|(_, severity)| *severity == Severity::Info).count(), 3);
+    }
+
     #[test]
     fn test_diagnostic_engine_rule_based_categorization() {
         let engine = AprokoEngine::new();
@@ -87,18 +172,29 @@ mod tests {
         let ast = AstNode::Program(vec![
                 Statement::Assignment {
                     identifier: "fn".to_string(), // Reserved keyword
-                    value: Expression::Literal(Literal::Number(42.0)),
+                    value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                     mutable: false,
+                    span: SourceSpan::default(),
                 },
                 Statement::Function {
+                    public: false,
+                    is_const: false,
+                    is_unsafe: false,
                     name: "".to_string(), // Empty function name
-                    parameters: vec!["param1".to_string()],
-                    body: vec![], // Empty body
+                    type_parameters: Vec::new(),
+                    bounds: Vec::new(),
+                    parameters: vec![Parameter::untyped("param1", SourceSpan::default())],
+                    return_type: None,
+                    requires: Vec::new(),
+                    ensures: Vec::new(),
+                    body: vec![], // Empty body,
+                    span: SourceSpan::default(),
                 },
                 Statement::If {
-                    condition: Expression::Literal(Literal::Boolean(true)),
+                    condition: Expression::Literal(Literal::Boolean(true), SourceSpan::default()),
                     then_block: vec![], // Empty if block
-                    else_block: Some(vec![]), // Empty else block
+                    else_block: Some(vec![]), // Empty else block,
+                    span: SourceSpan::default(),
                 },
             ]);
 
@@ -137,13 +233,23 @@ mod tests {
         let ast = AstNode::Program(vec![
                 Statement::Assignment {
                     identifier: "".to_string(), // Error: empty identifier
-                    value: Expression::Literal(Literal::Number(42.0)),
+                    value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                     mutable: false,
+                    span: SourceSpan::default(),
                 },
                 Statement::Function {
+                    public: false,
+                    is_const: false,
+                    is_unsafe: false,
                     name: "test_func".to_string(),
+                    type_parameters: Vec::new(),
+                    bounds: Vec::new(),
                     parameters: vec![],
-                    body: vec![], // Warning: empty function body
+                    return_type: None,
+                    requires: Vec::new(),
+                    ensures: Vec::new(),
+                    body: vec![], // Warning: empty function body,
+                    span: SourceSpan::default(),
                 },
             ]);
 
@@ -173,8 +279,10 @@ mod tests {
                     value: Expression::Call {
                         function: "".to_string(), // Empty function name
                         arguments: vec![],
+                        span: SourceSpan::default(),
                     },
                     mutable: false,
+                    span: SourceSpan::default(),
                 },
             ]);
 
@@ -200,6 +308,7 @@ mod tests {
                 crate::diagnostic::DiagnosticCategory::OwnershipError |
                 crate::diagnostic::DiagnosticCategory::MemoryError |
                 crate::diagnostic::DiagnosticCategory::LogicError |
+                crate::diagnostic::DiagnosticCategory::ContractError |
                 crate::diagnostic::DiagnosticCategory::PerformanceWarning |
                 crate::diagnostic::DiagnosticCategory::SecurityWarning |
                 crate::diagnostic::DiagnosticCategory::StyleWarning |
@@ -218,18 +327,29 @@ mod tests {
         let ast = AstNode::Program(vec![
                 Statement::Assignment {
                     identifier: "".to_string(), // Error
-                    value: Expression::Literal(Literal::Number(42.0)),
+                    value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                     mutable: false,
+                    span: SourceSpan::default(),
                 },
                 Statement::Assignment {
                     identifier: "valid_var".to_string(),
-                    value: Expression::Literal(Literal::String("test".to_string())),
+                    value: Expression::Literal(Literal::String("test".to_string()), SourceSpan::default()),
                     mutable: false,
+                    span: SourceSpan::default(),
                 },
                 Statement::Function {
+                    public: false,
+                    is_const: false,
+                    is_unsafe: false,
                     name: "test_func".to_string(),
+                    type_parameters: Vec::new(),
+                    bounds: Vec::new(),
                     parameters: vec![],
-                    body: vec![], // Warning
+                    return_type: None,
+                    requires: Vec::new(),
+                    ensures: Vec::new(),
+                    body: vec![], // Warning,
+                    span: SourceSpan::default(),
                 },
             ]);
 
@@ -258,8 +378,9 @@ mod tests {
         let ast = AstNode::Program(vec![
                 Statement::Assignment {
                     identifier: "".to_string(), // Empty identifier should trigger diagnostic
-                    value: Expression::Literal(Literal::Number(42.0)),
+                    value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                     mutable: false,
+                    span: SourceSpan::default(),
                 },
             ]);
 
@@ -295,13 +416,23 @@ mod tests {
         let ast = AstNode::Program(vec![
                 Statement::Assignment {
                     identifier: "fn".to_string(), // Reserved keyword
-                    value: Expression::Literal(Literal::Number(42.0)),
+                    value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                     mutable: false,
+                    span: SourceSpan::default(),
                 },
                 Statement::Function {
+                    public: false,
+                    is_const: false,
+                    is_unsafe: false,
                     name: "test_func".to_string(),
+                    type_parameters: Vec::new(),
+                    bounds: Vec::new(),
                     parameters: vec![],
-                    body: vec![], // Empty body
+                    return_type: None,
+                    requires: Vec::new(),
+                    ensures: Vec::new(),
+                    body: vec![], // Empty body,
+                    span: SourceSpan::default(),
                 },
             ]);
 
@@ -6,7 +6,7 @@
 use crate::*;
 use crate::diagnostic::*;
 use crate::explanation::*;
-use oviec::ast::{AstNode, Statement, Expression, Literal};
+use oviec::ast::{AstNode, Statement, Expression, Literal, SourceSpan, Parameter};
 use std::collections::{HashMap, HashSet};
 
 /// Property 11: Aproko Diagnostic Completeness
@@ -107,13 +107,15 @@ fn property_analysis_deterministic() {
     let ast = AstNode::Program(vec![
             Statement::Assignment {
                 identifier: "".to_string(), // Should trigger diagnostic
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                 mutable: false,
+                span: SourceSpan::default(),
             },
             Statement::Assignment {
                 identifier: "valid_var".to_string(),
-                value: Expression::Literal(Literal::String("test".to_string())),
+                value: Expression::Literal(Literal::String("test".to_string()), SourceSpan::default()),
                 mutable: false,
+                span: SourceSpan::default(),
             },
         ]);
     
@@ -178,18 +180,29 @@ fn property_all_diagnostics_explainable() {
     let ast = AstNode::Program(vec![
             Statement::Assignment {
                 identifier: "".to_string(), // Empty identifier
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                 mutable: false,
+                span: SourceSpan::default(),
             },
             Statement::Assignment {
                 identifier: "fn".to_string(), // Reserved keyword
-                value: Expression::Literal(Literal::String("test".to_string())),
+                value: Expression::Literal(Literal::String("test".to_string()), SourceSpan::default()),
                 mutable: false,
+                span: SourceSpan::default(),
             },
             Statement::Function {
+                public: false,
+                is_const: false,
+                is_unsafe: false,
                 name: "".to_string(), // Empty function name
+                type_parameters: Vec::new(),
+                bounds: Vec::new(),
                 parameters: vec![],
-                body: vec![], // Empty body
+                return_type: None,
+                requires: Vec::new(),
+                ensures: Vec::new(),
+                body: vec![], // Empty body,
+                span: SourceSpan::default(),
             },
         ]);
     
@@ -286,18 +299,29 @@ fn property_statistics_accurate() {
     let ast = AstNode::Program(vec![
             Statement::Assignment {
                 identifier: "".to_string(), // Error
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                 mutable: false,
+                span: SourceSpan::default(),
             },
             Statement::Function {
+                public: false,
+                is_const: false,
+                is_unsafe: false,
                 name: "test_func".to_string(),
+                type_parameters: Vec::new(),
+                bounds: Vec::new(),
                 parameters: vec![],
-                body: vec![], // Warning
+                return_type: None,
+                requires: Vec::new(),
+                ensures: Vec::new(),
+                body: vec![], // Warning,
+                span: SourceSpan::default(),
             },
             Statement::Assignment {
                 identifier: "valid_var".to_string(), // No issue
-                value: Expression::Literal(Literal::String("test".to_string())),
+                value: Expression::Literal(Literal::String("test".to_string()), SourceSpan::default()),
                 mutable: false,
+                span: SourceSpan::default(),
             },
         ]);
     
@@ -354,8 +378,9 @@ fn property_handles_edge_cases() {
     let long_ast = AstNode::Program(vec![
             Statement::Assignment {
                 identifier: long_identifier,
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                 mutable: false,
+                span: SourceSpan::default(),
             },
         ]);
     let long_result = engine.analyze("let a... = 42", &long_ast);
@@ -365,8 +390,9 @@ fn property_handles_edge_cases() {
     let many_statements: Vec<Statement> = (0..100)
         .map(|i| Statement::Assignment {
             identifier: format!("var_{}", i),
-            value: Expression::Literal(Literal::Number(i as f64)),
+            value: Expression::Literal(Literal::Number(i as f64), SourceSpan::default()),
             mutable: false,
+            span: SourceSpan::default(),
         })
         .collect();
     
@@ -466,8 +492,9 @@ fn property_consistent_under_config_changes() {
     let ast = AstNode::Program(vec![
             Statement::Assignment {
                 identifier: "".to_string(),
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
                 mutable: false,
+                span: SourceSpan::default(),
             },
         ]);
     
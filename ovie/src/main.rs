@@ -43,6 +43,9 @@ enum Commands {
         /// Enable debug output
         #[arg(long)]
         debug: bool,
+        /// Release build: leave out the run-time checks of contract clauses
+        #[arg(long)]
+        release: bool,
         /// Enable deterministic builds
         #[arg(long)]
         deterministic: bool,
@@ -63,6 +66,9 @@ enum Commands {
         /// Enable debug output
         #[arg(long)]
         debug: bool,
+        /// Release build: leave out the run-time checks of contract clauses
+        #[arg(long)]
+        release: bool,
     },
     /// Run tests
     Test {
@@ -81,6 +87,14 @@ enum Commands {
         #[arg(long)]
         debug: bool,
     },
+    /// Apply the suggested fixes for misspelled names
+    Fix {
+        /// Source file to fix
+        file: Option<String>,
+        /// Show the fixes without changing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Format source code
     Fmt {
         /// Files to format (defaults to all .ov files)
@@ -324,9 +338,10 @@ fn main() {
 
     let result = match cli.command {
         Commands::New { name, path } => cmd_new(name, path),
-        Commands::Build { file, backend, target, output, debug, deterministic, object, assembly } => cmd_build(file, backend, target, output, debug, deterministic, object, assembly),
-        Commands::Run { file, backend, debug } => cmd_run(file, backend, debug),
+        Commands::Build { file, backend, target, output, debug, release, deterministic, object, assembly } => cmd_build(file, backend, target, output, debug, release, deterministic, object, assembly),
+        Commands::Run { file, backend, debug, release } => cmd_run(file, backend, debug, release),
         Commands::Check { file, debug } => cmd_check(file, debug),
+        Commands::Fix { file, dry_run } => cmd_fix(file, dry_run),
         Commands::Test { pattern, debug } => cmd_test(pattern, debug),
         Commands::Fmt { files, check } => cmd_fmt(files, check),
         Commands::Update { dependency } => cmd_update(dependency),
@@ -441,27 +456,28 @@ Thumbs.db
     Ok(())
 }
 
-fn cmd_build(file: Option<String>, backend: String, target: Option<String>, output: Option<String>, debug: bool, deterministic: bool, object: bool, assembly: bool) -> OvieResult<()> {
+fn cmd_build(file: Option<String>, backend: String, target: Option<String>, output: Option<String>, debug: bool, release: bool, deterministic: bool, object: bool, assembly: bool) -> OvieResult<()> {
     let source_file = file.unwrap_or_else(|| "src/main.ov".to_string());
     
     if !Path::new(&source_file).exists() {
         return Err(oviec::OvieError::io_error(format!("Source file '{}' not found", source_file)));
     }
 
-    let source = fs::read_to_string(&source_file)?;
+    let entry = Path::new(&source_file);
     let mut compiler = if deterministic {
         Compiler::new_deterministic()
     } else {
         Compiler::new()
     };
     compiler.debug = debug;
+    compiler.set_check_contracts(!release);
 
     let backend_enum = Backend::from_str(&backend)
         .ok_or_else(|| oviec::OvieError::generic(format!("Unknown backend: {}", backend)))?;
 
     match backend_enum {
         Backend::Wasm => {
-            let wasm_bytes = compiler.compile_to_wasm(&source)?;
+            let wasm_bytes = compiler.compile_file_to_wasm(entry)?;
             let output_file = output.unwrap_or_else(|| "output.wasm".to_string());
             fs::write(&output_file, wasm_bytes)?;
             println!("Built {} -> {} (WASM, {} bytes)", source_file, output_file, fs::metadata(&output_file)?.len());
@@ -501,7 +517,7 @@ fn cmd_build(file: Option<String>, backend: String, target: Option<String>, outp
                 }
             }
             
-            let llvm_ir = compiler.compile_to_llvm(&source)?;
+            let llvm_ir = compiler.compile_file_to_llvm(entry)?;
             
             if object || assembly {
                 println!("Note: Object and assembly file generation requires LLVM target machine initialization");
@@ -529,18 +545,18 @@ fn cmd_build(file: Option<String>, backend: String, target: Option<String>, outp
         }
         Backend::Interpreter | Backend::IrInterpreter => {
             // For interpreters, we just validate the compilation
-            let _ast = compiler.compile_to_ast(&source)?;
+            let _modules = compiler.load_modules(entry)?;
             println!("Validated {} ({})", source_file, backend_enum.name());
         }
         Backend::Hir => {
-            let hir = compiler.compile_to_hir(&source)?;
+            let hir = compiler.compile_file_to_hir(entry)?;
             let output_file = output.unwrap_or_else(|| "output.hir.json".to_string());
             let hir_json = hir.to_json().unwrap_or_else(|_| "Failed to serialize HIR".to_string());
             fs::write(&output_file, hir_json)?;
             println!("Built {} -> {} (HIR)", source_file, output_file);
         }
         Backend::Mir => {
-            let mir = compiler.compile_to_mir(&source)?;
+            let mir = compiler.compile_file_to_mir(entry)?;
             let output_file = output.unwrap_or_else(|| "output.mir.json".to_string());
             let mir_json = mir.to_json().unwrap_or_else(|_| "Failed to serialize MIR".to_string());
             fs::write(&output_file, mir_json)?;
@@ -551,16 +567,16 @@ fn cmd_build(file: Option<String>, backend: String, target: Option<String>, outp
     Ok(())
 }
 
-fn cmd_run(file: Option<String>, backend: String, debug: bool) -> OvieResult<()> {
+fn cmd_run(file: Option<String>, backend: String, debug: bool, release: bool) -> OvieResult<()> {
     let source_file = file.unwrap_or_else(|| "src/main.ov".to_string());
     
     if !Path::new(&source_file).exists() {
         return Err(oviec::OvieError::io_error(format!("Source file '{}' not found", source_file)));
     }
 
-    let source = fs::read_to_string(&source_file)?;
     let mut compiler = Compiler::new();
     compiler.debug = debug;
+    compiler.set_check_contracts(!release);
 
     let backend_enum = Backend::from_str(&backend)
         .ok_or_else(|| oviec::OvieError::generic(format!("Unknown backend: {}", backend)))?;
@@ -569,7 +585,7 @@ fn cmd_run(file: Option<String>, backend: String, debug: bool) -> OvieResult<()>
         println!("Running {} with {} backend", source_file, backend_enum.name());
     }
 
-    compiler.compile_and_run_with_backend(&source, backend_enum)?;
+    compiler.compile_file_and_run_with_backend(Path::new(&source_file), backend_enum)?;
 
     Ok(())
 }
@@ -589,13 +605,108 @@ fn cmd_check(file: Option<String>, debug: bool) -> OvieResult<()> {
         println!("Checking {} for errors...", source_file);
     }
 
-    // Compile to HIR to check for semantic errors
-    let _hir = compiler.compile_to_hir(&source)?;
+    // Report every syntax error at once before checking semantics
+    let (_, diagnostics) = compiler.parse_with_diagnostics(&source)?;
+    if !diagnostics.is_empty() {
+        let mut reporter = oviec::ErrorReporter::new();
+        for mut diagnostic in diagnostics {
+            diagnostic.location.file = Some(source_file.clone());
+            reporter.add_diagnostic(diagnostic);
+        }
+        eprint!("{}", reporter.to_human_readable());
+        return Err(oviec::OvieError::compile_error(format!(
+            "{} has {} syntax error(s)", source_file, reporter.error_count()
+        )));
+    }
+
+    // Compile to HIR, with every imported module, reporting every semantic error at once
+    let diagnostics = compiler.check_file_with_diagnostics(Path::new(&source_file))?;
+    if !diagnostics.is_empty() {
+        let mut reporter = oviec::ErrorReporter::new();
+        for mut diagnostic in diagnostics {
+            diagnostic.location.file = Some(source_file.clone());
+            reporter.add_diagnostic(diagnostic);
+        }
+        eprint!("{}", reporter.to_human_readable());
+        return Err(oviec::OvieError::compile_error(format!(
+            "{} has {} error(s)", source_file, reporter.error_count()
+        )));
+    }
     
     println!("✓ {} - No errors found", source_file);
     Ok(())
 }
 
+fn cmd_fix(file: Option<String>, dry_run: bool) -> OvieResult<()> {
+    let source_file = file.unwrap_or_else(|| "src/main.ov".to_string());
+
+    if !Path::new(&source_file).exists() {
+        return Err(oviec::OvieError::io_error(format!("Source file '{}' not found", source_file)));
+    }
+
+    let source = fs::read_to_string(&source_file)?;
+    let mut compiler = Compiler::new();
+
+    let (_, syntax_errors) = compiler.parse_with_diagnostics(&source)?;
+    if !syntax_errors.is_empty() {
+        return Err(oviec::OvieError::compile_error(format!(
+            "{} has {} syntax error(s); run `ovie check` to see them", source_file, syntax_errors.len()
+        )));
+    }
+
+    let diagnostics = compiler.check_file_with_diagnostics(Path::new(&source_file))?;
+    let mut edits = Vec::new();
+    let mut unfixed = 0;
+    for diagnostic in &diagnostics {
+        match suggested_edit(&source, diagnostic) {
+            Some(edit) => {
+                println!("{}:{}:{}: {}", source_file, diagnostic.location.line, diagnostic.location.column, edit.2);
+                edits.push(edit);
+            }
+            None => unfixed += 1,
+        }
+    }
+
+    if edits.is_empty() {
+        println!("No fixes to apply to {}", source_file);
+    } else if dry_run {
+        println!("\n{} fix(es) would be applied to {}", edits.len(), source_file);
+    } else {
+        // Replace from the end of the file, so earlier offsets stay valid
+        let mut fixed = source.clone();
+        edits.sort_by_key(|(range, _, _)| std::cmp::Reverse(range.start));
+        for (range, new_text, _) in &edits {
+            fixed.replace_range(range.clone(), new_text);
+        }
+        fs::write(&source_file, fixed)?;
+        println!("\n✓ Applied {} fix(es) to {}", edits.len(), source_file);
+    }
+    if unfixed > 0 {
+        println!("{} error(s) have no fix to apply; run `ovie check` to see them", unfixed);
+    }
+    Ok(())
+}
+
+/// The edit of the fix `diagnostic` suggests for a misspelled name: the byte range of
+/// `source` to replace, the text to replace it with, and a description of the fix.
+///
+/// Only a fix whose suggestion is better than every other is applied, and only if the
+/// name is where the fix expects it.
+fn suggested_edit(source: &str, diagnostic: &oviec::Diagnostic) -> Option<(std::ops::Range<usize>, String, String)> {
+    let name = diagnostic.context.get("name")?;
+    let (best, rest) = diagnostic.suggestions.split_first()?;
+    if rest.iter().any(|other| other.confidence >= best.confidence) {
+        return None;
+    }
+    let fix = best.code_fix.as_ref()?;
+    let [replacement] = fix.replacements.as_slice() else { return None };
+    let range = replacement.start.offset..replacement.end.offset;
+    if source.get(range.clone()) != Some(name.as_str()) {
+        return None;
+    }
+    Some((range, replacement.new_text.clone(), fix.description.clone()))
+}
+
 fn cmd_analyze(file: Option<String>, debug: bool, format: String, output: Option<String>) -> OvieResult<()> {
     let source_file = file.unwrap_or_else(|| "src/main.ov".to_string());
     
@@ -611,8 +722,8 @@ fn cmd_analyze(file: Option<String>, debug: bool, format: String, output: Option
         println!("Analyzing {} with Aproko...", source_file);
     }
 
-    // Compile to AST for analysis
-    let ast = compiler.compile_to_ast(&source)?;
+    // Parse with recovery so the rest of the file is analyzed even with syntax errors
+    let (ast, syntax_errors) = compiler.parse_with_diagnostics(&source)?;
     
     // Run Aproko analysis
     let mut aproko_engine = aproko::AprokoEngine::new();
@@ -628,7 +739,23 @@ fn cmd_analyze(file: Option<String>, debug: bool, format: String, output: Option
     report.push_str(&format!("  Analysis duration: {}ms\n", analysis_result.stats.duration_ms));
     report.push_str(&format!("  Total findings: {}\n", analysis_result.findings.len()));
     report.push_str(&format!("  Total diagnostics: {}\n", analysis_result.diagnostics.len()));
+    report.push_str(&format!("  Syntax errors: {}\n", syntax_errors.len()));
     report.push_str("\n");
+
+    // Syntax errors found while parsing
+    if !syntax_errors.is_empty() {
+        report.push_str("Syntax Errors:\n");
+        for error in &syntax_errors {
+            report.push_str(&format!("  [{}] {} ({}:{}:{})\n",
+                error.code,
+                error.message,
+                source_file,
+                error.location.line,
+                error.location.column
+            ));
+        }
+        report.push_str("\n");
+    }
     
     // Findings by severity
     if !analysis_result.stats.findings_by_severity.is_empty() {
@@ -1669,21 +1796,68 @@ fn format_ast(ast: &AstNode) -> String {
     output
 }
 
+fn format_type_parameters(type_parameters: &[String]) -> String {
+    format_bounded_type_parameters(type_parameters, &[])
+}
+
+fn format_bounded_type_parameters(type_parameters: &[String], bounds: &[oviec::ast::TypeBound]) -> String {
+    if type_parameters.is_empty() {
+        return String::new();
+    }
+    let params: Vec<String> = type_parameters.iter().map(|param| {
+        match bounds.iter().find(|bound| &bound.parameter == param) {
+            Some(bound) => format!("{}: {}", param, bound.traits.join(" + ")),
+            None => param.clone(),
+        }
+    }).collect();
+    format!("<{}>", params.join(", "))
+}
+
+fn format_signature(name: &str, type_parameters: &[String], bounds: &[oviec::ast::TypeBound], parameters: &[oviec::ast::Parameter], return_type: Option<&oviec::ast::TypeAnnotation>) -> String {
+    format!("fn {}{}{}", name, format_bounded_type_parameters(type_parameters, bounds), format_parameters(parameters, return_type))
+}
+
+fn format_parameters(parameters: &[oviec::ast::Parameter], return_type: Option<&oviec::ast::TypeAnnotation>) -> String {
+    let params: Vec<String> = parameters.iter().map(|param| {
+        let mut_keyword = if param.mutable { "mut " } else { "" };
+        match &param.type_annotation {
+            Some(annotation) => format!("{}: {}{}", param.name, mut_keyword, annotation),
+            None => format!("{}{}", mut_keyword, param.name),
+        }
+    }).collect();
+    let returns = return_type.map(|t| format!(" -> {}", t)).unwrap_or_default();
+    format!("({}){}", params.join(", "), returns)
+}
+
 fn format_statement(stmt: &Statement, output: &mut String, indent_level: usize) {
     let indent = "    ".repeat(indent_level);
     
     match stmt {
-        Statement::Print { expression } => {
+        Statement::Print { expression, .. } => {
             output.push_str(&format!("{}seeAm {};", indent, format_expression(expression)));
         }
-        Statement::Assignment { mutable: _, identifier, value } => {
-            output.push_str(&format!("{}{} = {};", indent, identifier, format_expression(value)));
+        Statement::Assignment { mutable, identifier, value, .. } => {
+            let mut_keyword = if *mutable { "mut " } else { "" };
+            output.push_str(&format!("{}{}{} = {};", indent, mut_keyword, identifier, format_expression(value)));
+        }
+        Statement::PlaceAssignment { target, operator, value, .. } => {
+            let operator = operator.as_ref().map(|op| op.to_string()).unwrap_or_default();
+            output.push_str(&format!("{}{} {}= {};", indent, format_expression(target), operator, format_expression(value)));
         }
-        Statement::VariableDeclaration { mutable, identifier, value } => {
+        Statement::VariableDeclaration { mutable, identifier, type_annotation, value, .. } => {
             let mut_keyword = if *mutable { "mut " } else { "" };
-            output.push_str(&format!("{}let {}{} = {};", indent, mut_keyword, identifier, format_expression(value)));
+            let annotation = type_annotation.as_ref().map(|t| format!(": {}", t)).unwrap_or_default();
+            output.push_str(&format!("{}let {}{}{} = {};", indent, mut_keyword, identifier, annotation, format_expression(value)));
+        }
+        Statement::Destructure { mutable, pattern, type_annotation, value, .. } => {
+            let mut_keyword = if *mutable { "mut " } else { "" };
+            let annotation = type_annotation.as_ref().map(|t| format!(": {}", t)).unwrap_or_default();
+            output.push_str(&format!("{}let {}{}{} = {};", indent, mut_keyword, format_pattern(pattern), annotation, format_expression(value)));
+        }
+        Statement::Const { public, name, type_annotation, value, .. } => {
+            output.push_str(&format!("{}{}const {}: {} = {};", indent, format_visibility(*public), name, type_annotation, format_expression(value)));
         }
-        Statement::If { condition, then_block, else_block } => {
+        Statement::If { condition, then_block, else_block, .. } => {
             output.push_str(&format!("{}if {} {{", indent, format_expression(condition)));
             for then_stmt in then_block {
                 output.push('\n');
@@ -1702,8 +1876,8 @@ fn format_statement(stmt: &Statement, output: &mut String, indent_level: usize)
                 output.push_str(&format!("{}}}", indent));
             }
         }
-        Statement::While { condition, body } => {
-            output.push_str(&format!("{}while {} {{", indent, format_expression(condition)));
+        Statement::While { label, condition, body, .. } => {
+            output.push_str(&format!("{}{}while {} {{", indent, format_label(label), format_expression(condition)));
             for body_stmt in body {
                 output.push('\n');
                 format_statement(body_stmt, output, indent_level + 1);
@@ -1711,8 +1885,8 @@ fn format_statement(stmt: &Statement, output: &mut String, indent_level: usize)
             output.push('\n');
             output.push_str(&format!("{}}}", indent));
         }
-        Statement::For { identifier, iterable, body } => {
-            output.push_str(&format!("{}for {} in {} {{", indent, identifier, format_expression(iterable)));
+        Statement::Unsafe { body, .. } => {
+            output.push_str(&format!("{}unsafe {{", indent));
             for body_stmt in body {
                 output.push('\n');
                 format_statement(body_stmt, output, indent_level + 1);
@@ -1720,9 +1894,8 @@ fn format_statement(stmt: &Statement, output: &mut String, indent_level: usize)
             output.push('\n');
             output.push_str(&format!("{}}}", indent));
         }
-        Statement::Function { name, parameters, body } => {
-            let params = parameters.join(", ");
-            output.push_str(&format!("{}fn {}({}) {{", indent, name, params));
+        Statement::For { label, pattern, iterable, body, .. } => {
+            output.push_str(&format!("{}{}for {} in {} {{", indent, format_label(label), format_pattern(pattern), format_expression(iterable)));
             for body_stmt in body {
                 output.push('\n');
                 format_statement(body_stmt, output, indent_level + 1);
@@ -1730,9 +1903,12 @@ fn format_statement(stmt: &Statement, output: &mut String, indent_level: usize)
             output.push('\n');
             output.push_str(&format!("{}}}", indent));
         }
-        Statement::FunctionDeclaration { name, parameters, body } => {
-            let params = parameters.join(", ");
-            output.push_str(&format!("{}fn {}({}) {{", indent, name, params));
+        Statement::Function { public, is_const, is_unsafe, name, type_parameters, bounds, parameters, return_type, requires, ensures, body, .. } => {
+            let qualifier = if *is_const { "const " } else if *is_unsafe { "unsafe " } else { "" };
+            let contracts: String = requires.iter().map(|clause| format!(" requires {}", format_expression(&clause.condition)))
+                .chain(ensures.iter().map(|clause| format!(" ensures {}", format_expression(&clause.condition))))
+                .collect();
+            output.push_str(&format!("{}{}{}{}{} {{", indent, format_visibility(*public), qualifier, format_signature(name, type_parameters, bounds, parameters, return_type.as_ref()), contracts));
             for body_stmt in body {
                 output.push('\n');
                 format_statement(body_stmt, output, indent_level + 1);
@@ -1740,65 +1916,214 @@ fn format_statement(stmt: &Statement, output: &mut String, indent_level: usize)
             output.push('\n');
             output.push_str(&format!("{}}}", indent));
         }
-        Statement::Return { value } => {
+        Statement::FunctionDeclaration { name, type_parameters, parameters, return_type, body, .. } => {
+            output.push_str(&format!("{}{} {{", indent, format_signature(name, type_parameters, &[], parameters, return_type.as_ref())));
+            for body_stmt in body {
+                output.push('\n');
+                format_statement(body_stmt, output, indent_level + 1);
+            }
+            output.push('\n');
+            output.push_str(&format!("{}}}", indent));
+        }
+        Statement::Return { value, .. } => {
             if let Some(val) = value {
                 output.push_str(&format!("{}return {};", indent, format_expression(val)));
             } else {
                 output.push_str(&format!("{}return;", indent));
             }
         }
-        Statement::Expression { expression } => {
+        Statement::Break { label, .. } => {
+            output.push_str(&format!("{}break{};", indent, label.as_ref().map(|l| format!(" {}", l)).unwrap_or_default()));
+        }
+        Statement::Continue { label, .. } => {
+            output.push_str(&format!("{}continue{};", indent, label.as_ref().map(|l| format!(" {}", l)).unwrap_or_default()));
+        }
+        Statement::Expression { expression, .. } => {
             output.push_str(&format!("{}{};", indent, format_expression(expression)));
         }
-        Statement::Struct { name, fields: _ } => {
+        Statement::Struct { public, name, type_parameters, fields: _, .. } => {
             // Basic struct formatting - can be expanded later
-            output.push_str(&format!("{}struct {} {{ /* fields */ }}", indent, name));
+            output.push_str(&format!("{}{}struct {}{} {{ /* fields */ }}", indent, format_visibility(*public), name, format_type_parameters(type_parameters)));
         }
-        Statement::Enum { name, variants: _ } => {
+        Statement::Enum { public, name, type_parameters, variants: _, .. } => {
             // Basic enum formatting - can be expanded later
-            output.push_str(&format!("{}enum {} {{ /* variants */ }}", indent, name));
+            output.push_str(&format!("{}{}enum {}{} {{ /* variants */ }}", indent, format_visibility(*public), name, format_type_parameters(type_parameters)));
+        }
+        Statement::Impl { trait_name, type_name, type_parameters, bounds, methods, .. } => {
+            let trait_name = trait_name.as_ref().map(|name| format!("{} for ", name)).unwrap_or_default();
+            output.push_str(&format!("{}impl {}{}{} {{", indent, trait_name, type_name, format_bounded_type_parameters(type_parameters, bounds)));
+            for method in methods {
+                output.push('\n');
+                format_statement(method, output, indent_level + 1);
+            }
+            output.push('\n');
+            output.push_str(&format!("{}}}", indent));
+        }
+        Statement::Trait { public, name, methods, .. } => {
+            output.push_str(&format!("{}{}trait {} {{", indent, format_visibility(*public), name));
+            let method_indent = "    ".repeat(indent_level + 1);
+            for method in methods {
+                output.push('\n');
+                let signature = format_signature(&method.name, &method.type_parameters, &method.bounds, &method.parameters, method.return_type.as_ref());
+                match &method.body {
+                    Some(body) => {
+                        output.push_str(&format!("{}{} {{", method_indent, signature));
+                        for body_stmt in body {
+                            output.push('\n');
+                            format_statement(body_stmt, output, indent_level + 2);
+                        }
+                        output.push('\n');
+                        output.push_str(&format!("{}}}", method_indent));
+                    }
+                    None => output.push_str(&format!("{}{};", method_indent, signature)),
+                }
+            }
+            output.push('\n');
+            output.push_str(&format!("{}}}", indent));
+        }
+        Statement::Use { path, names, .. } => {
+            output.push_str(&format!("{}use {}::{{{}}};", indent, path.join("::"), names.join(", ")));
+        }
+        Statement::Module { name, .. } => {
+            output.push_str(&format!("{}mod {};", indent, name));
+        }
+        Statement::Error { .. } => {
+            // Only files that parse cleanly are formatted
         }
     }
 }
 
+fn format_visibility(public: bool) -> &'static str {
+    if public { "pub " } else { "" }
+}
+
+fn format_label(label: &Option<String>) -> String {
+    label.as_ref().map(|l| format!("{}: ", l)).unwrap_or_default()
+}
+
 fn format_expression(expr: &Expression) -> String {
     match expr {
-        Expression::Literal(lit) => format_literal(lit),
-        Expression::Identifier(name) => name.clone(),
-        Expression::Binary { left, operator, right } => {
+        Expression::Literal(lit, _) => format_literal(lit),
+        Expression::Identifier(name, _) => name.clone(),
+        Expression::Binary { left, operator, right, .. } => {
             format!("{} {} {}", format_expression(left), format_operator(operator), format_expression(right))
         }
-        Expression::Unary { operator, operand } => {
+        Expression::Unary { operator, operand, .. } => {
             format!("{}{}", format_unary_operator(operator), format_expression(operand))
         }
-        Expression::Call { function, arguments } => {
+        Expression::Call { function, arguments, .. } => {
             let args: Vec<String> = arguments.iter().map(format_expression).collect();
             format!("{}({})", function, args.join(", "))
         }
-        Expression::FieldAccess { object, field } => {
+        Expression::FieldAccess { object, field, .. } => {
             format!("{}.{}", format_expression(object), field)
         }
-        Expression::Range { start, end } => {
+        Expression::Range { start, end, .. } => {
             format!("{}..{}", format_expression(start), format_expression(end))
         }
-        Expression::StructInstantiation { struct_name, fields: _ } => {
+        Expression::StructInstantiation { struct_name, fields: _, .. } => {
             // Basic struct instantiation formatting - can be expanded later
             format!("{} {{ /* fields */ }}", struct_name)
         }
-        Expression::EnumVariantConstruction { enum_name, variant_name, data } => {
-            if let Some(data_expr) = data {
-                format!("{}::{}({})", enum_name, variant_name, format_expression(data_expr))
-            } else {
-                format!("{}::{}", enum_name, variant_name)
-            }
+        Expression::EnumVariantConstruction { enum_name, variant_name, payload, .. } => {
+            format!("{}::{}{}", enum_name, variant_name, format_variant_payload(payload, format_expression))
         }
-        Expression::Index { object, index } => {
+        Expression::Index { object, index, .. } => {
             format!("{}[{}]", format_expression(object), format_expression(index))
         }
-        Expression::ArrayLiteral { elements } => {
+        Expression::ArrayLiteral { elements, .. } => {
             let elems: Vec<String> = elements.iter().map(format_expression).collect();
             format!("[{}]", elems.join(", "))
         }
+        Expression::Tuple { elements, .. } => {
+            let elems: Vec<String> = elements.iter().map(format_expression).collect();
+            format_tuple(&elems)
+        }
+        Expression::Match { scrutinee, arms, .. } => {
+            let arms: Vec<String> = arms.iter().map(|arm| {
+                let body = match &arm.value {
+                    Some(value) => format_expression(value),
+                    None => {
+                        let statements: Vec<String> = arm.body.iter().map(|stmt| {
+                            let mut formatted = String::new();
+                            format_statement(stmt, &mut formatted, 0);
+                            formatted
+                        }).collect();
+                        format!("{{ {} }}", statements.join(" "))
+                    }
+                };
+                format!("{} => {}", format_pattern(&arm.pattern), body)
+            }).collect();
+            format!("match {} {{ {} }}", format_expression(scrutinee), arms.join(", "))
+        }
+        Expression::Closure { parameters, return_type, body, .. } => {
+            let statements: Vec<String> = body.iter().map(|stmt| {
+                let mut formatted = String::new();
+                format_statement(stmt, &mut formatted, 0);
+                formatted
+            }).collect();
+            format!("fn{} {{ {} }}", format_parameters(parameters, return_type.as_ref()), statements.join(" "))
+        }
+        Expression::IndirectCall { callee, arguments, .. } => {
+            let args: Vec<String> = arguments.iter().map(format_expression).collect();
+            format!("{}({})", format_expression(callee), args.join(", "))
+        }
+        Expression::MethodCall { receiver, method, arguments, .. } => {
+            let args: Vec<String> = arguments.iter().map(format_expression).collect();
+            format!("{}.{}({})", format_expression(receiver), method, args.join(", "))
+        }
+        Expression::Try { operand, .. } => format!("{}?", format_expression(operand)),
+    }
+}
+
+fn format_pattern(pattern: &oviec::ast::Pattern) -> String {
+    use oviec::ast::{Pattern, VariantPayload};
+
+    match pattern {
+        Pattern::Wildcard(_) => "_".to_string(),
+        Pattern::Binding(name, _) => name.clone(),
+        Pattern::Literal(lit, _) => format_literal(lit),
+        Pattern::Variant { enum_name, variant_name, payload: VariantPayload::Struct(fields), .. } => {
+            // `field` is shorthand for `field: field`
+            let fields: Vec<String> = fields.iter().map(|(field, pattern)| match pattern {
+                Pattern::Binding(name, _) if name == field => field.clone(),
+                _ => format!("{}: {}", field, format_pattern(pattern)),
+            }).collect();
+            format!("{}.{} {{ {} }}", enum_name, variant_name, fields.join(", "))
+        }
+        Pattern::Variant { enum_name, variant_name, payload, .. } => {
+            format!("{}.{}{}", enum_name, variant_name, format_variant_payload(payload, format_pattern))
+        }
+        Pattern::Tuple(elements, _) => {
+            let elems: Vec<String> = elements.iter().map(format_pattern).collect();
+            format_tuple(&elems)
+        }
+    }
+}
+
+/// Payload of an enum variant: nothing, `(a, b)` or ` { field: value }`
+fn format_variant_payload<T>(payload: &oviec::ast::VariantPayload<T>, format: impl Fn(&T) -> String) -> String {
+    use oviec::ast::VariantPayload;
+
+    match payload {
+        VariantPayload::Unit => String::new(),
+        VariantPayload::Tuple(values) => {
+            let values: Vec<String> = values.iter().map(format).collect();
+            format!("({})", values.join(", "))
+        }
+        VariantPayload::Struct(fields) => {
+            let fields: Vec<String> = fields.iter().map(|(field, value)| format!("{}: {}", field, format(value))).collect();
+            format!(" {{ {} }}", fields.join(", "))
+        }
+    }
+}
+
+/// A one-element tuple keeps its trailing comma so it is not read back as a parenthesized value
+fn format_tuple(elements: &[String]) -> String {
+    if elements.len() == 1 {
+        format!("({},)", elements[0])
+    } else {
+        format!("({})", elements.join(", "))
     }
 }
 
@@ -1807,13 +2132,15 @@ fn format_literal(lit: &oviec::ast::Literal) -> String {
     
     match lit {
         Literal::String(s) => format!("\"{}\"", s),
+        // Keep the fractional part so the literal stays a Number
         Literal::Number(n) => {
             if n.fract() == 0.0 {
-                format!("{}", *n as i64)
+                format!("{:.1}", n)
             } else {
                 format!("{}", n)
             }
         }
+        Literal::Integer(n) => n.to_string(),
         Literal::Boolean(b) => b.to_string(),
     }
 }
@@ -154,6 +154,30 @@ mod cli_tests {
         }
     }
 
+    /// `ovie run` rejects every program `ovie check` rejects, on each backend
+    #[test]
+    fn test_run_rejects_what_check_rejects() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let programs = [
+            ("immutable.ov", "let x = 1;\nx = 2;\nseeAm x;\n", "not mutable"),
+            ("mismatch.ov", "let s: String = 5;\nseeAm s;\n", "Type mismatch"),
+            ("unresolved.ov", "struct P { x: Int }\nlet p = P { x: 1 };\nseeAm p.xpso;\n", "Field 'xpso' not found"),
+        ];
+
+        for (name, source, expected) in programs {
+            let file = temp_dir.path().join(name);
+            fs::write(&file, source).expect("Failed to write test file");
+            let file = file.to_str().unwrap().to_string();
+
+            assert!(crate::cmd_check(Some(file.clone()), false).is_err(), "{} should fail `ovie check`", name);
+            for backend in ["interpreter", "ir"] {
+                let error = crate::cmd_run(Some(file.clone()), backend.to_string(), false, false)
+                    .expect_err(&format!("{} should fail `ovie run` on the {} backend", name, backend));
+                assert!(error.to_string().contains(expected), "{}: {}", name, error);
+            }
+        }
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(5))]
         
@@ -2,7 +2,7 @@
 
 use std::fmt;
 use serde::{Deserialize, Serialize};
-use crate::error::{OvieError, OvieResult};
+use crate::error::{OvieError, OvieResult, SourcePosition, SourceRange};
 
 /// AST invariant validation trait
 pub trait AstInvariantValidation {
@@ -29,6 +29,66 @@ impl fmt::Display for InvariantError {
 
 impl std::error::Error for InvariantError {}
 
+/// Source location of a syntax node: a byte range plus the line and column of its start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceSpan {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Self { start, end, line, column }
+    }
+
+    /// Span covering both `self` and `other`, keeping the earlier start position
+    pub fn to(&self, other: &SourceSpan) -> SourceSpan {
+        let (first, last) = if self.start <= other.start { (self, other) } else { (other, self) };
+        SourceSpan {
+            start: first.start,
+            end: first.end.max(last.end),
+            line: first.line,
+            column: first.column,
+        }
+    }
+
+    /// Length of the span in bytes
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Whether the span covers no source text
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Start of the span as a diagnostic position
+    pub fn start_position(&self) -> SourcePosition {
+        SourcePosition::new(None, self.line, self.column, self.start)
+    }
+
+    /// Convert to a diagnostic range; the end column is only exact for single-line spans
+    pub fn to_source_range(&self) -> SourceRange {
+        let mut end = self.start_position();
+        end.column += self.len();
+        end.offset = self.end;
+        SourceRange::new(self.start_position(), end)
+    }
+}
+
+impl Default for SourceSpan {
+    fn default() -> Self {
+        Self {
+            start: 0,
+            end: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
 /// Root AST node representing a complete Ovie program
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AstNode {
@@ -75,23 +135,40 @@ impl AstNode {
             Statement::Assignment { value, .. } => {
                 self.validate_expression_invariants(value)?;
             }
-            Statement::VariableDeclaration { value, .. } => {
+            Statement::PlaceAssignment { target, value, .. } => {
+                self.validate_expression_invariants(target)?;
+                self.validate_expression_invariants(value)?;
+            }
+            Statement::VariableDeclaration { value, .. } | Statement::Destructure { value, .. } | Statement::Const { value, .. } => {
                 self.validate_expression_invariants(value)?;
             }
-            Statement::Function { body, .. } => {
+            Statement::Function { requires, ensures, body, .. } => {
+                for clause in requires.iter().chain(ensures) {
+                    self.validate_expression_invariants(&clause.condition)?;
+                }
                 for stmt in body {
                     self.validate_statement_invariants(stmt)?;
                 }
             }
-            Statement::FunctionDeclaration { body, .. } => {
+            Statement::FunctionDeclaration { body, .. } | Statement::Unsafe { body, .. } => {
                 for stmt in body {
                     self.validate_statement_invariants(stmt)?;
                 }
             }
-            Statement::Print { expression } => {
+            Statement::Impl { methods, .. } => {
+                for method in methods {
+                    self.validate_statement_invariants(method)?;
+                }
+            }
+            Statement::Trait { methods, .. } => {
+                for stmt in methods.iter().filter_map(|method| method.body.as_ref()).flatten() {
+                    self.validate_statement_invariants(stmt)?;
+                }
+            }
+            Statement::Print { expression, .. } => {
                 self.validate_expression_invariants(expression)?;
             }
-            Statement::If { condition, then_block, else_block } => {
+            Statement::If { condition, then_block, else_block, .. } => {
                 self.validate_expression_invariants(condition)?;
                 for stmt in then_block {
                     self.validate_statement_invariants(stmt)?;
@@ -102,7 +179,7 @@ impl AstNode {
                     }
                 }
             }
-            Statement::While { condition, body } => {
+            Statement::While { condition, body, .. } => {
                 self.validate_expression_invariants(condition)?;
                 for stmt in body {
                     self.validate_statement_invariants(stmt)?;
@@ -114,30 +191,41 @@ impl AstNode {
                     self.validate_statement_invariants(stmt)?;
                 }
             }
-            Statement::Return { value } => {
+            Statement::Return { value, .. } => {
                 if let Some(expr) = value {
                     self.validate_expression_invariants(expr)?;
                 }
             }
-            Statement::Expression { expression } => {
+            Statement::Break { .. } | Statement::Continue { .. } => {
+                // Loop labels are resolved in HIR
+            }
+            Statement::Expression { expression, .. } => {
                 self.validate_expression_invariants(expression)?;
             }
-            Statement::Struct { .. } => {
-                // Struct definitions are valid at AST level
+            Statement::Struct { invariants, .. } => {
+                for clause in invariants {
+                    self.validate_expression_invariants(&clause.condition)?;
+                }
             }
             Statement::Enum { .. } => {
                 // Enum definitions are valid at AST level
             }
+            Statement::Use { .. } | Statement::Module { .. } => {
+                // Imports are resolved against the module graph, not the AST
+            }
+            Statement::Error { .. } => {
+                // Error nodes only mark where the parser recovered
+            }
         }
         Ok(())
     }
 
     fn validate_expression_invariants(&self, expression: &Expression) -> Result<(), InvariantError> {
         match expression {
-            Expression::Literal(_) => {
+            Expression::Literal(..) => {
                 // Literals are always valid at AST level
             }
-            Expression::Identifier(_) => {
+            Expression::Identifier(..) => {
                 // Identifiers should NOT be resolved at AST level
                 // This is correct - identifiers are just strings at this stage
             }
@@ -161,24 +249,55 @@ impl AstNode {
                     self.validate_expression_invariants(&field.value)?;
                 }
             }
-            Expression::Range { start, end } => {
+            Expression::Range { start, end, .. } => {
                 self.validate_expression_invariants(start)?;
                 self.validate_expression_invariants(end)?;
             }
-            Expression::EnumVariantConstruction { data, .. } => {
-                if let Some(data_expr) = data {
-                    self.validate_expression_invariants(data_expr)?;
+            Expression::EnumVariantConstruction { payload, .. } => {
+                for value in payload.values() {
+                    self.validate_expression_invariants(value)?;
                 }
             }
-            Expression::Index { object, index } => {
+            Expression::Index { object, index, .. } => {
                 self.validate_expression_invariants(object)?;
                 self.validate_expression_invariants(index)?;
             }
-            Expression::ArrayLiteral { elements } => {
+            Expression::ArrayLiteral { elements, .. } | Expression::Tuple { elements, .. } => {
                 for element in elements {
                     self.validate_expression_invariants(element)?;
                 }
             }
+            Expression::Match { scrutinee, arms, .. } => {
+                self.validate_expression_invariants(scrutinee)?;
+                for arm in arms {
+                    for stmt in &arm.body {
+                        self.validate_statement_invariants(stmt)?;
+                    }
+                    if let Some(value) = &arm.value {
+                        self.validate_expression_invariants(value)?;
+                    }
+                }
+            }
+            Expression::Closure { body, .. } => {
+                for stmt in body {
+                    self.validate_statement_invariants(stmt)?;
+                }
+            }
+            Expression::IndirectCall { callee, arguments, .. } => {
+                self.validate_expression_invariants(callee)?;
+                for arg in arguments {
+                    self.validate_expression_invariants(arg)?;
+                }
+            }
+            Expression::MethodCall { receiver, arguments, .. } => {
+                self.validate_expression_invariants(receiver)?;
+                for arg in arguments {
+                    self.validate_expression_invariants(arg)?;
+                }
+            }
+            Expression::Try { operand, .. } => {
+                self.validate_expression_invariants(operand)?;
+            }
         }
         Ok(())
     }
@@ -192,32 +311,72 @@ pub enum Statement {
         mutable: bool,
         identifier: String,
         value: Expression,
+        span: SourceSpan,
+    },
+
+    /// Assignment to an existing place: target = expression, or target op= expression.
+    /// The target is a variable, field access or index expression; `operator` is the
+    /// arithmetic operator of `+=`, `-=`, `*=`, `/=` or `%=`
+    PlaceAssignment {
+        target: Expression,
+        operator: Option<BinaryOperator>,
+        value: Expression,
+        span: SourceSpan,
     },
 
-    /// Variable declaration: let [mut] identifier = expression
+    /// Variable declaration: let [mut] identifier[: Type] = expression
     VariableDeclaration {
         mutable: bool,
         identifier: String,
+        type_annotation: Option<TypeAnnotation>,
+        value: Expression,
+        span: SourceSpan,
+    },
+
+    /// Destructuring declaration: let [mut] (a, b, ...)[: Type] = expression
+    Destructure {
+        mutable: bool,
+        pattern: Pattern,
+        type_annotation: Option<TypeAnnotation>,
         value: Expression,
+        span: SourceSpan,
     },
 
-    /// Function definition: fn identifier(params) { body }
+    /// Function definition: [pub] [const | unsafe] fn identifier[<T: Trait, ...>](params) [-> Type]
+    /// [requires condition]* [ensures condition]* { body }.
+    /// A `const fn` can also be called while evaluating a constant
     Function {
+        public: bool,
+        is_const: bool,
+        is_unsafe: bool,
         name: String,
-        parameters: Vec<String>,
+        type_parameters: Vec<String>,
+        /// Traits the type arguments must implement
+        bounds: Vec<TypeBound>,
+        parameters: Vec<Parameter>,
+        return_type: Option<TypeAnnotation>,
+        /// Preconditions, over the parameters
+        requires: Vec<ContractClause>,
+        /// Postconditions, over the parameters and the returned value `result`
+        ensures: Vec<ContractClause>,
         body: Vec<Statement>,
+        span: SourceSpan,
     },
 
-    /// Function declaration: fn identifier(params) { body }
+    /// Function declaration: fn identifier[<T, ...>](params) [-> Type] { body }
     FunctionDeclaration {
         name: String,
-        parameters: Vec<String>,
+        type_parameters: Vec<String>,
+        parameters: Vec<Parameter>,
+        return_type: Option<TypeAnnotation>,
         body: Vec<Statement>,
+        span: SourceSpan,
     },
 
     /// Print statement: seeAm expression
     Print {
         expression: Expression,
+        span: SourceSpan,
     },
 
     /// If statement: if condition { then_block } [else { else_block }]
@@ -225,41 +384,122 @@ pub enum Statement {
         condition: Expression,
         then_block: Vec<Statement>,
         else_block: Option<Vec<Statement>>,
+        span: SourceSpan,
     },
 
-    /// While loop: while condition { body }
+    /// While loop: [label:] while condition { body }
     While {
+        label: Option<String>,
         condition: Expression,
         body: Vec<Statement>,
+        span: SourceSpan,
     },
 
-    /// For loop: for identifier in expression { body }
+    /// For loop: [label:] for pattern in expression { body }; the pattern is a
+    /// name, `_` or a tuple of those
     For {
-        identifier: String,
+        label: Option<String>,
+        pattern: Pattern,
         iterable: Expression,
         body: Vec<Statement>,
+        span: SourceSpan,
     },
 
     /// Return statement: return [expression]
     Return {
         value: Option<Expression>,
+        span: SourceSpan,
+    },
+
+    /// Leave the innermost loop, or the loop with the label: break [label]
+    Break {
+        label: Option<String>,
+        span: SourceSpan,
+    },
+
+    /// Start the next iteration of the innermost loop, or the loop with the label: continue [label]
+    Continue {
+        label: Option<String>,
+        span: SourceSpan,
     },
 
     /// Expression statement: expression;
     Expression {
         expression: Expression,
+        span: SourceSpan,
     },
 
-    /// Struct definition: struct Name { fields }
+    /// Struct definition: [pub] struct Name[<T, ...>] { fields [, invariant condition]* }
     Struct {
+        public: bool,
         name: String,
+        type_parameters: Vec<String>,
         fields: Vec<StructField>,
+        /// Conditions every value of the struct satisfies, over its fields as `self.field`
+        invariants: Vec<ContractClause>,
+        span: SourceSpan,
     },
 
-    /// Enum definition: enum Name { variants }
+    /// Enum definition: [pub] enum Name[<T, ...>] { variants }
     Enum {
+        public: bool,
         name: String,
+        type_parameters: Vec<String>,
         variants: Vec<EnumVariant>,
+        span: SourceSpan,
+    },
+
+    /// Module-level constant: [pub] const NAME: Type = expression; the value is computed at compile time
+    Const {
+        public: bool,
+        name: String,
+        type_annotation: TypeAnnotation,
+        value: Expression,
+        span: SourceSpan,
+    },
+
+    /// Method block: impl [Trait for] Type[<T: Trait, ...>] { [unsafe] fn method(self, ...) { ... } ... }
+    Impl {
+        /// Trait the block implements; `None` for methods of the type itself
+        trait_name: Option<String>,
+        type_name: String,
+        type_parameters: Vec<String>,
+        bounds: Vec<TypeBound>,
+        /// `Statement::Function` definitions; those whose first parameter is `self` are methods
+        methods: Vec<Statement>,
+        span: SourceSpan,
+    },
+
+    /// Trait definition: [pub] trait Name { fn method(self, ...) [-> Type] [{ default body }] ... }
+    Trait {
+        public: bool,
+        name: String,
+        methods: Vec<TraitMethod>,
+        span: SourceSpan,
+    },
+
+    /// Import: use path::name; or use path::{name, ...};
+    Use {
+        path: Vec<String>,
+        names: Vec<String>,
+        span: SourceSpan,
+    },
+
+    /// Module declaration: mod name;
+    Module {
+        name: String,
+        span: SourceSpan,
+    },
+
+    /// Unsafe block: unsafe { body }
+    Unsafe {
+        body: Vec<Statement>,
+        span: SourceSpan,
+    },
+
+    /// Placeholder for a statement the parser could not parse
+    Error {
+        span: SourceSpan,
     },
 }
 
@@ -267,72 +507,250 @@ pub enum Statement {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Expression {
     /// Literal values
-    Literal(Literal),
+    Literal(Literal, SourceSpan),
 
     /// Variable reference
-    Identifier(String),
+    Identifier(String, SourceSpan),
 
     /// Binary operations: left op right
     Binary {
         left: Box<Expression>,
         operator: BinaryOperator,
         right: Box<Expression>,
+        span: SourceSpan,
     },
 
     /// Unary operations: op expression
     Unary {
         operator: UnaryOperator,
         operand: Box<Expression>,
+        span: SourceSpan,
     },
 
     /// Function call: identifier(args)
     Call {
         function: String,
         arguments: Vec<Expression>,
+        span: SourceSpan,
     },
 
-    /// Field access: expression.field
+    /// Field access: expression.field, or expression.0 for a tuple element
     FieldAccess {
         object: Box<Expression>,
         field: String,
+        span: SourceSpan,
     },
 
     /// Struct instantiation: StructName { field: value, ... }
     StructInstantiation {
         struct_name: String,
         fields: Vec<FieldInitializer>,
+        span: SourceSpan,
     },
 
     /// Range expression: start..end
     Range {
         start: Box<Expression>,
         end: Box<Expression>,
+        span: SourceSpan,
     },
 
-    /// Enum variant construction: EnumName.VariantName or EnumName.VariantName(data)
+    /// Enum variant construction: EnumName.VariantName, EnumName.VariantName(a, b)
+    /// or EnumName.VariantName { field: value }
     EnumVariantConstruction {
         enum_name: String,
         variant_name: String,
-        data: Option<Box<Expression>>,
+        payload: VariantPayload<Expression>,
+        span: SourceSpan,
     },
 
     /// Array/String indexing: array[index] or string[index]
     Index {
         object: Box<Expression>,
         index: Box<Expression>,
+        span: SourceSpan,
     },
 
     /// Array literal: [element1, element2, ...]
     ArrayLiteral {
         elements: Vec<Expression>,
+        span: SourceSpan,
+    },
+
+    /// Tuple literal: (element1, element2, ...) or (element,)
+    Tuple {
+        elements: Vec<Expression>,
+        span: SourceSpan,
     },
+
+    /// Match expression: match scrutinee { pattern => arm, ... }
+    Match {
+        scrutinee: Box<Expression>,
+        arms: Vec<MatchArm>,
+        span: SourceSpan,
+    },
+
+    /// Closure: fn(params) [-> Type] { body }
+    Closure {
+        parameters: Vec<Parameter>,
+        return_type: Option<TypeAnnotation>,
+        body: Vec<Statement>,
+        span: SourceSpan,
+    },
+
+    /// Call of a computed function value: expression(args)
+    IndirectCall {
+        callee: Box<Expression>,
+        arguments: Vec<Expression>,
+        span: SourceSpan,
+    },
+
+    /// Method call: receiver.method(args), or Type.function(args) when the
+    /// receiver names a type
+    MethodCall {
+        receiver: Box<Expression>,
+        method: String,
+        arguments: Vec<Expression>,
+        span: SourceSpan,
+    },
+
+    /// Error propagation: expression? gives the value of an `Ok` or `Some`,
+    /// and returns an `Err` or `None` from the enclosing function
+    Try {
+        operand: Box<Expression>,
+        span: SourceSpan,
+    },
+}
+
+impl Statement {
+    /// Source span of the whole statement
+    pub fn span(&self) -> SourceSpan {
+        match self {
+            Statement::Assignment { span, .. }
+            | Statement::PlaceAssignment { span, .. }
+            | Statement::VariableDeclaration { span, .. }
+            | Statement::Destructure { span, .. }
+            | Statement::Function { span, .. }
+            | Statement::FunctionDeclaration { span, .. }
+            | Statement::Print { span, .. }
+            | Statement::If { span, .. }
+            | Statement::While { span, .. }
+            | Statement::For { span, .. }
+            | Statement::Return { span, .. }
+            | Statement::Break { span, .. }
+            | Statement::Continue { span, .. }
+            | Statement::Expression { span, .. }
+            | Statement::Struct { span, .. }
+            | Statement::Enum { span, .. }
+            | Statement::Const { span, .. }
+            | Statement::Use { span, .. }
+            | Statement::Module { span, .. }
+            | Statement::Impl { span, .. }
+            | Statement::Trait { span, .. }
+            | Statement::Unsafe { span, .. }
+            | Statement::Error { span } => *span,
+        }
+    }
+
+    /// Whether the statement declares an item, which may appear at the top level of a
+    /// module and is visible to the whole module
+    pub fn is_item(&self) -> bool {
+        matches!(self,
+            Statement::Function { .. } | Statement::Struct { .. } | Statement::Enum { .. } | Statement::Impl { .. }
+            | Statement::Trait { .. } | Statement::Const { .. } | Statement::Use { .. } | Statement::Module { .. }
+        )
+    }
+}
+
+impl Expression {
+    /// Source span of the whole expression
+    pub fn span(&self) -> SourceSpan {
+        match self {
+            Expression::Literal(_, span) | Expression::Identifier(_, span) => *span,
+            Expression::Binary { span, .. }
+            | Expression::Unary { span, .. }
+            | Expression::Call { span, .. }
+            | Expression::FieldAccess { span, .. }
+            | Expression::StructInstantiation { span, .. }
+            | Expression::Range { span, .. }
+            | Expression::EnumVariantConstruction { span, .. }
+            | Expression::Index { span, .. }
+            | Expression::ArrayLiteral { span, .. }
+            | Expression::Tuple { span, .. }
+            | Expression::Match { span, .. }
+            | Expression::Closure { span, .. }
+            | Expression::IndirectCall { span, .. }
+            | Expression::MethodCall { span, .. }
+            | Expression::Try { span, .. } => *span,
+        }
+    }
+}
+
+/// Arm of a match expression: `pattern => expression` or `pattern => { statements }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    /// Statements of a block arm
+    pub body: Vec<Statement>,
+    /// Value of an expression arm
+    pub value: Option<Expression>,
+    pub span: SourceSpan,
+}
+
+/// Pattern in a match arm
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Pattern {
+    /// `_`, matches any value
+    Wildcard(SourceSpan),
+
+    /// Matches any value and binds it to a name
+    Binding(String, SourceSpan),
+
+    /// Matches a literal value
+    Literal(Literal, SourceSpan),
+
+    /// Enum variant: EnumName.VariantName, EnumName.VariantName(a, b) or
+    /// EnumName.VariantName { field, field: pattern }
+    Variant {
+        enum_name: String,
+        variant_name: String,
+        payload: VariantPayload<Pattern>,
+        span: SourceSpan,
+    },
+
+    /// Tuple of patterns, one per element: (a, _, b); only used to destructure
+    /// in `let` and `for`
+    Tuple(Vec<Pattern>, SourceSpan),
+}
+
+impl Pattern {
+    /// Source span of the whole pattern
+    pub fn span(&self) -> SourceSpan {
+        match self {
+            Pattern::Wildcard(span) | Pattern::Binding(_, span) | Pattern::Literal(_, span) => *span,
+            Pattern::Variant { span, .. } | Pattern::Tuple(_, span) => *span,
+        }
+    }
+
+    /// Names the pattern binds
+    pub fn bindings(&self) -> Vec<&str> {
+        match self {
+            Pattern::Binding(name, _) => vec![name.as_str()],
+            Pattern::Variant { payload, .. } => payload.values().into_iter().flat_map(|field| field.bindings()).collect(),
+            Pattern::Tuple(elements, _) => elements.iter().flat_map(|element| element.bindings()).collect(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 /// Literal value types
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Literal {
     String(String),
+    /// Literal with a fractional part, e.g. `2.5`
     Number(f64),
+    /// Literal without a fractional part, e.g. `42`
+    Integer(i64),
     Boolean(bool),
 }
 
@@ -366,18 +784,145 @@ pub enum UnaryOperator {
     Negate,
 }
 
+/// Type written in source code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TypeAnnotation {
+    /// Named type with optional type arguments: `Number`, `Point`, `Result<T, E>`
+    Named {
+        name: String,
+        arguments: Vec<TypeAnnotation>,
+    },
+
+    /// Function type: `fn(Number, String) -> Boolean`
+    Function {
+        parameters: Vec<TypeAnnotation>,
+        return_type: Box<TypeAnnotation>,
+    },
+
+    /// Array type: `[Number]`
+    Array(Box<TypeAnnotation>),
+
+    /// Tuple type: `(Number, String)`, or `(Number,)` with one element
+    Tuple(Vec<TypeAnnotation>),
+
+    /// The unit type `()`
+    Unit,
+}
+
+impl TypeAnnotation {
+    /// Named type without type arguments
+    pub fn named(name: impl Into<String>) -> Self {
+        TypeAnnotation::Named {
+            name: name.into(),
+            arguments: Vec::new(),
+        }
+    }
+}
+
+/// Function parameter: [mut] name[: Type]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    pub type_annotation: Option<TypeAnnotation>,
+    pub mutable: bool,
+    pub span: SourceSpan,
+}
+
+impl Parameter {
+    /// Untyped, immutable parameter
+    pub fn untyped(name: impl Into<String>, span: SourceSpan) -> Self {
+        Self {
+            name: name.into(),
+            type_annotation: None,
+            mutable: false,
+            span,
+        }
+    }
+}
+
+/// Bound on a type parameter: `T: Trait + ...`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeBound {
+    pub parameter: String,
+    pub traits: Vec<String>,
+    pub span: SourceSpan,
+}
+
+/// Method of a trait: fn name[<T, ...>](params) [-> Type], with a body if the
+/// trait provides a default that implementations may leave out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraitMethod {
+    pub name: String,
+    pub type_parameters: Vec<String>,
+    pub bounds: Vec<TypeBound>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<TypeAnnotation>,
+    pub body: Option<Vec<Statement>>,
+    pub span: SourceSpan,
+}
+
 /// Struct field definition
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StructField {
     pub name: String,
-    pub type_annotation: String,
+    pub type_annotation: TypeAnnotation,
 }
 
-/// Enum variant definition
+/// `requires`, `ensures` or `invariant` clause
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractClause {
+    pub condition: Expression,
+    /// Source text of the condition, quoted when a check fails
+    pub text: String,
+}
+
+/// Enum variant definition: Name, Name(Type, ...) or Name { field: Type, ... }
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EnumVariant {
     pub name: String,
-    pub data_type: Option<String>,
+    pub payload: VariantPayload<TypeAnnotation>,
+}
+
+/// Payload of an enum variant, shared by its declaration, construction and patterns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum VariantPayload<T> {
+    /// No payload: `Shape.Empty`
+    Unit,
+    /// Positional fields: `Msg.Move(x, y)`
+    Tuple(Vec<T>),
+    /// Named fields: `Shape.Rect { w: 1, h: 2 }`
+    Struct(Vec<(String, T)>),
+}
+
+impl<T> VariantPayload<T> {
+    /// Fields in order with their names; positional fields are named by index: "0", "1", ...
+    pub fn fields(&self) -> Vec<(String, &T)> {
+        match self {
+            VariantPayload::Unit => Vec::new(),
+            VariantPayload::Tuple(values) => values.iter().enumerate()
+                .map(|(index, value)| (index.to_string(), value))
+                .collect(),
+            VariantPayload::Struct(fields) => fields.iter().map(|(name, value)| (name.clone(), value)).collect(),
+        }
+    }
+
+    /// Field values in order
+    pub fn values(&self) -> Vec<&T> {
+        match self {
+            VariantPayload::Unit => Vec::new(),
+            VariantPayload::Tuple(values) => values.iter().collect(),
+            VariantPayload::Struct(fields) => fields.iter().map(|(_, value)| value).collect(),
+        }
+    }
+
+    /// Mutable field values in order
+    pub fn values_mut(&mut self) -> Vec<&mut T> {
+        match self {
+            VariantPayload::Unit => Vec::new(),
+            VariantPayload::Tuple(values) => values.iter_mut().collect(),
+            VariantPayload::Struct(fields) => fields.iter_mut().map(|(_, value)| value).collect(),
+        }
+    }
 }
 
 /// Field initializer for struct instantiation
@@ -385,6 +930,34 @@ pub struct EnumVariant {
 pub struct FieldInitializer {
     pub name: String,
     pub value: Expression,
+    /// Span of the field name
+    pub span: SourceSpan,
+}
+
+impl fmt::Display for TypeAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeAnnotation::Named { name, arguments } => {
+                write!(f, "{}", name)?;
+                if !arguments.is_empty() {
+                    let arguments: Vec<String> = arguments.iter().map(|a| a.to_string()).collect();
+                    write!(f, "<{}>", arguments.join(", "))?;
+                }
+                Ok(())
+            }
+            TypeAnnotation::Function { parameters, return_type } => {
+                let parameters: Vec<String> = parameters.iter().map(|p| p.to_string()).collect();
+                write!(f, "fn({}) -> {}", parameters.join(", "), return_type)
+            }
+            TypeAnnotation::Array(element) => write!(f, "[{}]", element),
+            TypeAnnotation::Tuple(elements) if elements.len() == 1 => write!(f, "({},)", elements[0]),
+            TypeAnnotation::Tuple(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "({})", elements.join(", "))
+            }
+            TypeAnnotation::Unit => write!(f, "()"),
+        }
+    }
 }
 
 impl fmt::Display for BinaryOperator {
@@ -423,6 +996,7 @@ impl fmt::Display for Literal {
         match self {
             Literal::String(s) => write!(f, "\"{}\"", s),
             Literal::Number(n) => write!(f, "{}", n),
+            Literal::Integer(n) => write!(f, "{}", n),
             Literal::Boolean(b) => write!(f, "{}", b),
         }
     }
@@ -0,0 +1,555 @@
+//! Borrow checking of MIR
+//!
+//! Checks each function against the ownership and borrowing rules of the memory
+//! model: a moved value is not used again until it is reassigned, a place is not
+//! borrowed mutably while another borrow of it is in use, and no borrow is in use
+//! after the storage it borrows is gone.
+//!
+//! A borrow is in use while a local holding it is live, so both analyses run on
+//! the dataflow framework: liveness of locals backward over the control flow
+//! graph, and the places that may have been moved out and the borrows that may
+//! be held forward. Each statement is then checked against the states before it.
+
+use crate::dataflow::{self, Analysis, Direction, JoinSemiLattice, Liveness, Location};
+use crate::dataflow::{operand_locals, rvalue_locals, rvalue_operands};
+use crate::error::{BorrowErrorKind, OvieError};
+use crate::mir::{
+    LocalId, MirBasicBlock, MirBorrowKind, MirFunction, MirOperand, MirPlace, MirProgram, MirProjectionElem,
+    MirRvalue, MirStatement, MirStatementKind, MirTerminator, MirType, MirTypeDef,
+};
+use crate::hir::SourceSpan;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Borrow check every function of `program`, returning the errors in source order
+pub fn check_program(program: &MirProgram) -> Vec<OvieError> {
+    let mut function_ids: Vec<_> = program.functions.keys().copied().collect();
+    function_ids.sort();
+    let mut errors: Vec<OvieError> = function_ids.into_iter()
+        .flat_map(|id| FunctionChecker::new(program, &program.functions[&id]).check())
+        .collect();
+    errors.sort_by_key(|error| error.location().map(|position| (position.line, position.column)));
+    errors
+}
+
+/// One step of a place, comparable so that places can be keys
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Step {
+    Deref,
+    Field(u32),
+    /// Any element, as the index is not known until run time
+    Index,
+    Subslice(u32, u32),
+    Downcast(u32),
+}
+
+/// A place as a local and the steps from it
+type PlaceKey = (LocalId, Vec<Step>);
+
+fn place_key(place: &MirPlace) -> PlaceKey {
+    let steps = place.projection.iter().map(|element| match element {
+        MirProjectionElem::Deref => Step::Deref,
+        MirProjectionElem::Field(index) => Step::Field(*index),
+        MirProjectionElem::Index(_) => Step::Index,
+        MirProjectionElem::Subslice { from, to } => Step::Subslice(*from, *to),
+        MirProjectionElem::Downcast(variant) => Step::Downcast(*variant),
+    }).collect();
+    (place.local, steps)
+}
+
+/// Whether two places may share memory: one contains the other, or they may be
+/// the same element of an array
+fn overlaps(a: &PlaceKey, b: &PlaceKey) -> bool {
+    a.0 == b.0 && a.1.iter().zip(&b.1).all(|(a, b)| match (a, b) {
+        (Step::Field(a), Step::Field(b)) | (Step::Downcast(a), Step::Downcast(b)) => a == b,
+        _ => true,
+    })
+}
+
+/// Whether `place` is reached through a reference, so its storage is not the local's
+fn is_through_reference(place: &MirPlace) -> bool {
+    place.projection.iter().any(|element| matches!(element, MirProjectionElem::Deref))
+}
+
+/// A borrow created by a `Ref` rvalue
+struct Loan {
+    place: MirPlace,
+    mutable: bool,
+    span: SourceSpan,
+}
+
+/// What may have happened to the places of a function on the way to a point
+#[derive(Debug, Clone, Default, PartialEq)]
+struct State {
+    /// Places that may have been moved out, with the place as written and the
+    /// operand that moved it
+    moved: BTreeMap<PlaceKey, (MirPlace, SourceSpan)>,
+    /// Loans that may be held, with the locals holding them
+    loans: BTreeMap<usize, BTreeSet<LocalId>>,
+}
+
+/// Add what may have happened on another path to this point
+impl JoinSemiLattice for State {
+    fn join(&mut self, other: &State) -> bool {
+        let before = self.clone();
+        for (key, moved) in &other.moved {
+            self.moved.entry(key.clone()).or_insert_with(|| moved.clone());
+        }
+        for (loan, holders) in &other.loans {
+            self.loans.entry(*loan).or_default().extend(holders);
+        }
+        *self != before
+    }
+}
+
+impl State {
+    /// Loans held by the locals `locals` read
+    fn loans_held_by(&self, locals: &[LocalId]) -> Vec<usize> {
+        self.loans.iter()
+            .filter(|(_, holders)| locals.iter().any(|local| holders.contains(local)))
+            .map(|(loan, _)| *loan)
+            .collect()
+    }
+
+    /// `local` is overwritten or its storage is gone, so it holds no loan any more
+    fn release(&mut self, local: LocalId) {
+        for holders in self.loans.values_mut() {
+            holders.remove(&local);
+        }
+        self.loans.retain(|_, holders| !holders.is_empty());
+    }
+
+    fn record_move(&mut self, operand: &MirOperand) {
+        if let MirOperand::Move(place, span) = operand {
+            self.moved.entry(place_key(place)).or_insert_with(|| (place.clone(), *span));
+        }
+    }
+
+    /// `place` is assigned a new value, so whatever was moved out of it is back
+    fn record_write(&mut self, place: &MirPlace) {
+        let key = place_key(place);
+        self.moved.retain(|moved, _| !(moved.0 == key.0 && moved.1.starts_with(&key.1)));
+        if place.projection.is_empty() {
+            self.release(place.local);
+        }
+    }
+}
+
+/// The places that may have been moved out and the loans that may be held, forward
+struct MovesAndLoans {
+    /// Loan created by the statement at each location
+    loan_at: HashMap<Location, usize>,
+}
+
+impl Analysis for MovesAndLoans {
+    type Domain = State;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom(&self, _function: &MirFunction) -> State {
+        State::default()
+    }
+
+    fn boundary(&self, _function: &MirFunction) -> State {
+        State::default()
+    }
+
+    fn apply_statement(&self, state: &mut State, statement: &MirStatement, location: Location) {
+        match &statement.kind {
+            MirStatementKind::Assign { place, rvalue } => {
+                // Values read through references carry their loans to the place written
+                let carried = match rvalue {
+                    MirRvalue::Ref { .. } => Vec::new(),
+                    _ => state.loans_held_by(&rvalue_locals(rvalue)),
+                };
+                for operand in rvalue_operands(rvalue) {
+                    state.record_move(operand);
+                }
+                state.record_write(place);
+                for loan in carried {
+                    state.loans.entry(loan).or_default().insert(place.local);
+                }
+                if let Some(&loan) = self.loan_at.get(&location) {
+                    state.loans.entry(loan).or_default().insert(place.local);
+                }
+            }
+            MirStatementKind::StorageDead(local) => {
+                state.release(*local);
+                state.moved.retain(|(moved, _), _| moved != local);
+            }
+            MirStatementKind::StorageLive(local) => {
+                state.moved.retain(|(moved, _), _| moved != local);
+            }
+            MirStatementKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator(&self, state: &mut State, block: &MirBasicBlock, _location: Location) {
+        match &block.terminator {
+            MirTerminator::SwitchInt { discriminant: operand, .. } | MirTerminator::Assert { condition: operand, .. } => {
+                state.record_move(operand);
+            }
+            MirTerminator::Call { func, args, destination, .. } => {
+                // A reference passed in may come back out
+                let operands: Vec<&MirOperand> = std::iter::once(func).chain(args).collect();
+                let mut read = Vec::new();
+                for operand in &operands {
+                    operand_locals(operand, &mut read);
+                }
+                let carried = state.loans_held_by(&read);
+                for operand in operands {
+                    state.record_move(operand);
+                }
+                state.record_write(destination);
+                for loan in carried {
+                    state.loans.entry(loan).or_default().insert(destination.local);
+                }
+            }
+            MirTerminator::Return { .. } | MirTerminator::Goto { .. } | MirTerminator::Drop { .. } | MirTerminator::Unreachable => {}
+        }
+    }
+}
+
+/// Checks one function
+struct FunctionChecker<'a> {
+    program: &'a MirProgram,
+    function: &'a MirFunction,
+    loans: Vec<Loan>,
+    errors: Vec<OvieError>,
+}
+
+impl<'a> FunctionChecker<'a> {
+    fn new(program: &'a MirProgram, function: &'a MirFunction) -> Self {
+        Self { program, function, loans: Vec::new(), errors: Vec::new() }
+    }
+
+    fn check(mut self) -> Vec<OvieError> {
+        let mut blocks: Vec<_> = self.function.basic_blocks.keys().copied().collect();
+        blocks.sort();
+
+        let mut loan_at = HashMap::new();
+        for &block_id in &blocks {
+            for (index, statement) in self.function.basic_blocks[&block_id].statements.iter().enumerate() {
+                if let MirStatementKind::Assign { rvalue: MirRvalue::Ref { place, borrow_kind, .. }, .. } = &statement.kind {
+                    loan_at.insert(Location { block: block_id, statement_index: index }, self.loans.len());
+                    self.loans.push(Loan {
+                        place: place.clone(),
+                        mutable: !matches!(borrow_kind, MirBorrowKind::Shared),
+                        span: statement.span,
+                    });
+                }
+            }
+        }
+
+        let liveness = dataflow::solve(Liveness, self.function);
+        let flow = dataflow::solve(MovesAndLoans { loan_at }, self.function);
+        for block_id in blocks {
+            let block = &self.function.basic_blocks[&block_id];
+            let live = liveness.states_in_block(self.function, block_id);
+            let states = flow.states_in_block(self.function, block_id);
+            for (index, statement) in block.statements.iter().enumerate() {
+                self.check_statement(&states[index], &live[index], statement);
+            }
+            let index = block.statements.len();
+            self.check_terminator(&states[index], &live[index], block);
+        }
+        self.errors
+    }
+
+    fn check_statement(&mut self, state: &State, live: &BTreeSet<LocalId>, statement: &MirStatement) {
+        let span = statement.span;
+        match &statement.kind {
+            MirStatementKind::Assign { place, rvalue } => {
+                for operand in rvalue_operands(rvalue) {
+                    self.check_operand(state, live, operand, span);
+                }
+                match rvalue {
+                    MirRvalue::Ref { place: borrowed, borrow_kind, .. } => {
+                        let mutable = !matches!(borrow_kind, MirBorrowKind::Shared);
+                        self.check_borrow(state, live, borrowed, mutable, span);
+                    }
+                    MirRvalue::Len(read) | MirRvalue::Discriminant(read) => {
+                        self.check_read(state, live, read, false, span);
+                    }
+                    _ => {}
+                }
+                self.check_write(state, live, place, span);
+            }
+            MirStatementKind::StorageDead(local) => self.check_storage_dead(state, live, *local, span),
+            MirStatementKind::StorageLive(_) | MirStatementKind::Nop => {}
+        }
+    }
+
+    fn check_terminator(&mut self, state: &State, live: &BTreeSet<LocalId>, block: &MirBasicBlock) {
+        let span = block.terminator_span;
+        match &block.terminator {
+            MirTerminator::Return { value } => {
+                if let Some(operand) = value {
+                    self.check_operand(state, live, operand, span);
+                    self.check_returned(state, operand, span);
+                }
+            }
+            MirTerminator::SwitchInt { discriminant: operand, .. } | MirTerminator::Assert { condition: operand, .. } => {
+                self.check_operand(state, live, operand, span);
+            }
+            MirTerminator::Call { func, args, destination, .. } => {
+                for operand in std::iter::once(func).chain(args) {
+                    self.check_operand(state, live, operand, span);
+                }
+                self.check_write(state, live, destination, span);
+            }
+            MirTerminator::Goto { .. } | MirTerminator::Drop { .. } | MirTerminator::Unreachable => {}
+        }
+    }
+
+    /// Loans that may be held by a local still to be read at the point `live` describes
+    fn loans_in_use<'s>(&'s self, state: &'s State, live: &'s BTreeSet<LocalId>) -> impl Iterator<Item = &'s Loan> + 's {
+        state.loans.iter()
+            .filter(|(_, holders)| holders.iter().any(|holder| live.contains(holder)))
+            .map(|(loan, _)| &self.loans[*loan])
+    }
+
+    fn check_operand(&mut self, state: &State, live: &BTreeSet<LocalId>, operand: &MirOperand, span: SourceSpan) {
+        match operand {
+            MirOperand::Copy(place) => self.check_read(state, live, place, false, span),
+            MirOperand::Move(place, _) => self.check_read(state, live, place, true, span),
+            MirOperand::Constant(_) => {}
+        }
+    }
+
+    /// A read of `place`, which moves out of it when `is_move`
+    fn check_read(&mut self, state: &State, live: &BTreeSet<LocalId>, place: &MirPlace, is_move: bool, span: SourceSpan) {
+        if let Some((moved, moved_at)) = self.moved_part(state, place) {
+            let (name, used) = (self.describe(&moved), self.describe(place));
+            let message = if name == used {
+                format!("Use of moved value '{}'", name)
+            } else if moved.projection.len() > place.projection.len() {
+                format!("Use of '{}' after '{}' was moved out of it", used, name)
+            } else {
+                format!("Use of '{}' after '{}' was moved", used, name)
+            };
+            self.report(BorrowErrorKind::UseAfterMove, place, message, span, moved_at, format!("'{}' was moved here", name));
+            return;
+        }
+        let key = place_key(place);
+        let conflict = self.loans_in_use(state, live)
+            .find(|loan| overlaps(&place_key(&loan.place), &key) && (loan.mutable || is_move))
+            .map(|loan| (loan.mutable, loan.span, self.describe(&loan.place)));
+        if let Some((mutable, borrowed_at, borrowed)) = conflict {
+            let message = if is_move {
+                format!("Cannot move out of '{}' because it is borrowed", self.describe(place))
+            } else {
+                format!("Cannot use '{}' because it is mutably borrowed", self.describe(place))
+            };
+            let note = if mutable {
+                format!("'{}' is mutably borrowed here", borrowed)
+            } else {
+                format!("'{}' is borrowed here", borrowed)
+            };
+            self.report(BorrowErrorKind::AccessWhileBorrowed, place, message, span, borrowed_at, note);
+        }
+    }
+
+    fn check_borrow(&mut self, state: &State, live: &BTreeSet<LocalId>, place: &MirPlace, mutable: bool, span: SourceSpan) {
+        if let Some((moved, moved_at)) = self.moved_part(state, place) {
+            let name = self.describe(&moved);
+            let message = format!("Borrow of moved value '{}'", name);
+            self.report(BorrowErrorKind::BorrowOfMovedValue, place, message, span, moved_at, format!("'{}' was moved here", name));
+            return;
+        }
+        let key = place_key(place);
+        let conflict = self.loans_in_use(state, live)
+            .find(|loan| overlaps(&place_key(&loan.place), &key) && (loan.mutable || mutable))
+            .map(|loan| (loan.mutable, loan.span));
+        let Some((first_mutable, first_at)) = conflict else { return };
+        let name = self.describe(place);
+        let (kind, message, note) = match (mutable, first_mutable) {
+            (true, true) => (
+                BorrowErrorKind::MultipleMutableBorrows,
+                format!("Cannot borrow '{}' as mutable more than once at a time", name),
+                format!("first mutable borrow of '{}' is here", name),
+            ),
+            (true, false) => (
+                BorrowErrorKind::MutableBorrowWhileImmutableBorrowExists,
+                format!("Cannot borrow '{}' as mutable because it is also borrowed as shared", name),
+                format!("shared borrow of '{}' is here", name),
+            ),
+            (false, _) => (
+                BorrowErrorKind::ImmutableBorrowWhileMutableBorrowExists,
+                format!("Cannot borrow '{}' as shared because it is also borrowed as mutable", name),
+                format!("mutable borrow of '{}' is here", name),
+            ),
+        };
+        self.report(kind, place, message, span, first_at, note);
+    }
+
+    fn check_write(&mut self, state: &State, live: &BTreeSet<LocalId>, place: &MirPlace, span: SourceSpan) {
+        let key = place_key(place);
+        let conflict = self.loans_in_use(state, live)
+            .find(|loan| overlaps(&place_key(&loan.place), &key))
+            .map(|loan| (loan.span, self.describe(&loan.place)));
+        if let Some((borrowed_at, borrowed)) = conflict {
+            let message = format!("Cannot assign to '{}' because it is borrowed", self.describe(place));
+            self.report(BorrowErrorKind::AccessWhileBorrowed, place, message, span, borrowed_at, format!("'{}' is borrowed here", borrowed));
+        }
+    }
+
+    /// The storage of `local` ends, so no borrow of it may be in use after
+    fn check_storage_dead(&mut self, state: &State, live: &BTreeSet<LocalId>, local: LocalId, span: SourceSpan) {
+        let dangling = self.loans_in_use(state, live)
+            .find(|loan| loan.place.local == local && !is_through_reference(&loan.place))
+            .map(|loan| (loan.span, loan.place.clone()));
+        if let Some((borrowed_at, place)) = dangling {
+            let name = self.describe(&MirPlace::from_local(local));
+            let message = format!("'{}' does not live long enough; it is still borrowed when its storage ends", name);
+            self.report(BorrowErrorKind::DanglingReference, &place, message, borrowed_at, span, format!("'{}' goes out of scope here", name));
+        }
+    }
+
+    /// A reference returned from a function must not borrow one of its locals
+    fn check_returned(&mut self, state: &State, operand: &MirOperand, span: SourceSpan) {
+        let mut read = Vec::new();
+        operand_locals(operand, &mut read);
+        let dangling = state.loans_held_by(&read).into_iter()
+            .map(|loan| &self.loans[loan])
+            .find(|loan| !is_through_reference(&loan.place))
+            .map(|loan| (loan.span, loan.place.clone()));
+        if let Some((borrowed_at, place)) = dangling {
+            let name = self.describe(&place);
+            let message = format!("Cannot return a reference to local '{}'", name);
+            self.report(BorrowErrorKind::DanglingReference, &place, message, span, borrowed_at, format!("'{}' is borrowed here", name));
+        }
+    }
+
+    /// The moved place overlapping `place`, and the operand that moved it
+    fn moved_part(&self, state: &State, place: &MirPlace) -> Option<(MirPlace, SourceSpan)> {
+        let key = place_key(place);
+        state.moved.iter()
+            .find(|(moved, _)| overlaps(moved, &key))
+            .map(|(_, moved)| moved.clone())
+    }
+
+    fn report(&mut self, kind: BorrowErrorKind, place: &MirPlace, message: String, span: SourceSpan, related: SourceSpan, note: String) {
+        let error = OvieError::kind_error(0, 0, kind, &self.describe(place), message)
+            .located_at(span.start_position())
+            .with_related_location(related.start_position(), note);
+        self.errors.push(error);
+    }
+
+    /// Source-like name of a place: `p.x`, `items[..]`, `*r`; temporaries are `_N`
+    fn describe(&self, place: &MirPlace) -> String {
+        let local = self.function.locals.iter().find(|local| local.id == place.local);
+        let mut name = local.and_then(|local| local.name.clone()).unwrap_or_else(|| format!("_{}", place.local));
+        let mut ty = local.map(|local| local.ty.clone());
+        let mut variant = None;
+        for element in &place.projection {
+            match element {
+                MirProjectionElem::Deref => {
+                    name = format!("*{}", name);
+                    ty = match ty {
+                        Some(MirType::Ref { ty, .. }) => Some(*ty),
+                        _ => None,
+                    };
+                }
+                MirProjectionElem::Field(index) => {
+                    let field = ty.as_ref().and_then(|ty| self.field(ty, variant, *index));
+                    match field {
+                        Some((field_name, field_type)) => {
+                            name = format!("{}.{}", name, field_name);
+                            ty = Some(field_type);
+                        }
+                        None => {
+                            name = format!("{}.{}", name, index);
+                            ty = None;
+                        }
+                    }
+                    variant = None;
+                }
+                MirProjectionElem::Index(_) | MirProjectionElem::Subslice { .. } => {
+                    name = format!("{}[..]", name);
+                    ty = match ty {
+                        Some(MirType::Array { element_type, .. }) | Some(MirType::Slice(element_type)) => Some(*element_type),
+                        _ => None,
+                    };
+                }
+                MirProjectionElem::Downcast(index) => variant = Some(*index),
+            }
+        }
+        name
+    }
+
+    /// Name and type of field `index` of a value of type `ty`, in `variant` for an enum
+    fn field(&self, ty: &MirType, variant: Option<u32>, index: u32) -> Option<(String, MirType)> {
+        match ty {
+            MirType::Tuple(types) => types.get(index as usize).map(|ty| (index.to_string(), ty.clone())),
+            MirType::Adt { name, .. } => {
+                let fields = match (self.program.type_definitions.get(name)?, variant) {
+                    (MirTypeDef::Struct { fields }, _) => fields,
+                    (MirTypeDef::Enum { variants }, Some(variant)) => &variants.get(variant as usize)?.fields,
+                    (MirTypeDef::Enum { .. }, None) => return None,
+                };
+                fields.get(index as usize).map(|field| (field.name.clone(), field.ty.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OvieError;
+
+    /// The first borrow error in `source`: message, location, and the note with its location
+    fn first_error(source: &str) -> Option<(String, (usize, usize), Option<(String, (usize, usize))>)> {
+        match crate::Compiler::new().compile_to_mir(source) {
+            Ok(_) => None,
+            Err(OvieError::Diagnostic { diagnostic }) => Some((
+                diagnostic.message.clone(),
+                (diagnostic.location.line, diagnostic.location.column),
+                diagnostic.related_locations.first().map(|(position, note)| (note.clone(), (position.line, position.column))),
+            )),
+            Err(other) => panic!("Expected a diagnostic for {:?}, found {:?}", source, other),
+        }
+    }
+
+    #[test]
+    fn test_partial_moves_name_the_moved_place() {
+        let person = "struct Person { name: String, age: Int }\nlet p = Person { name: \"Ada\", age: 36 }\n";
+
+        // Moving a field leaves the rest usable, but not the whole
+        let (message, at, note) = first_error(&format!("{}let n = p.name\nseeAm p.age\nlet q = p", person)).unwrap();
+        assert_eq!(message, "Use of 'p' after 'p.name' was moved out of it");
+        assert_eq!(at, (5, 1));
+        assert_eq!(note, Some(("'p.name' was moved here".to_string(), (3, 9))));
+
+        // Moving the whole leaves no field usable; the note names what was moved
+        let (message, at, note) = first_error(&format!("{}let q = p\nseeAm p.age", person)).unwrap();
+        assert_eq!(message, "Use of 'p.age' after 'p' was moved");
+        assert_eq!(at, (4, 1));
+        assert_eq!(note, Some(("'p' was moved here".to_string(), (3, 9))));
+
+        // The note points at the moved operand, not at the statement around it
+        let (message, _, note) = first_error(&format!("{}fn keep(s: String) {{ seeAm s; }}\nkeep(p.name)\nseeAm p.name", person)).unwrap();
+        assert_eq!(message, "Use of moved value 'p.name'");
+        assert_eq!(note, Some(("'p.name' was moved here".to_string(), (4, 6))));
+
+        assert_eq!(first_error(&format!("{}let n = p.name\nlet a = p.age\nseeAm a", person)), None);
+    }
+
+    #[test]
+    fn test_moves_in_loops() {
+        // A value moved in one iteration is gone in the next
+        let (message, at, note) = first_error("let s = \"a\"\nmut i = 0\nwhile i < 2 {\n    let t = s\n    i = i + 1\n}").unwrap();
+        assert_eq!(message, "Use of moved value 's'");
+        assert_eq!(at, (4, 5));
+        assert_eq!(note, Some(("'s' was moved here".to_string(), (4, 13))));
+
+        // and after the loop
+        let (message, at, note) = first_error("let s = \"a\"\nwhile true {\n    let t = s\n    break\n}\nseeAm s").unwrap();
+        assert_eq!(message, "Use of moved value 's'");
+        assert_eq!(at, (6, 1));
+        assert_eq!(note, Some(("'s' was moved here".to_string(), (3, 13))));
+
+        // unless it is assigned again before the next use
+        assert_eq!(first_error("mut s = \"a\"\nfor i in 0..3 {\n    let t = s\n    s = \"b\"\n}\nseeAm s"), None);
+    }
+}
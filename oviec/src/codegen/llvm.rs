@@ -712,6 +712,16 @@ impl<'ctx> LlvmBackend<'ctx> {
                     int_val.into()
                 ], "printf_call");
             }
+            Value::Constant(Constant::Integer(n)) => {
+                let format_str = "%lld\n\0";
+                let global_string = self.builder.build_global_string_ptr(format_str, "int_fmt");
+                let int_val = self.context.i64_type().const_int(*n as u64, true);
+
+                self.builder.build_call(printf_fn, &[
+                    global_string.as_pointer_value().into(),
+                    int_val.into()
+                ], "printf_call");
+            }
             _ => {
                 // For other types, just print a placeholder
                 let format_str = "value\n\0";
@@ -745,6 +755,10 @@ impl<'ctx> LlvmBackend<'ctx> {
                         let int_val = self.context.i32_type().const_int(*n as u64, false);
                         Ok(int_val.into())
                     }
+                    Constant::Integer(n) => {
+                        let int_val = self.context.i64_type().const_int(*n as u64, true);
+                        Ok(int_val.into())
+                    }
                     Constant::Float(f) => {
                         let float_val = self.context.f64_type().const_float(*f);
                         Ok(float_val.into())
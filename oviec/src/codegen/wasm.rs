@@ -1,10 +1,11 @@
 //! WebAssembly code generation backend with enhanced optimizations
 
-use crate::ir::{Program, Function, BasicBlock, Instruction, Terminator, Opcode, Value, Constant, IrType};
+use crate::ir::{Program, Function, BasicBlock, BlockId, Instruction, Terminator, Opcode, Value, ValueId, Constant, IrType};
 use crate::error::{OvieError, OvieResult};
 use super::CodegenBackend;
 use wasm_encoder::*;
-use std::collections::HashMap;
+use wasm_encoder::Instruction as WasmInstruction;
+use std::collections::{HashMap, HashSet};
 
 /// WebAssembly optimization configuration
 #[derive(Debug, Clone)]
@@ -137,14 +138,46 @@ impl Default for WasmMemoryConfig {
     }
 }
 
+/// Host functions imported from the `env` module, in import order
+const IMPORT_PRINT: u32 = 0;
+const IMPORT_PRINT_NUMBER: u32 = 1;
+const IMPORT_PRINT_BOOLEAN: u32 = 2;
+const IMPORT_PRINT_INT: u32 = 3;
+const IMPORT_ABORT: u32 = 4;
+const IMPORT_COUNT: u32 = 5;
+
+/// Linear memory address where string constants start (0 is kept as a null pointer)
+const DATA_START: u32 = 8;
+
+/// Size of one field, element or header slot in a heap aggregate
+const SLOT_SIZE: u32 = 8;
+
+/// Heap pointer global used by the bump allocator
+const HEAP_POINTER_GLOBAL: u32 = 0;
+
+/// Indices of the runtime support functions emitted after the user functions
+#[derive(Debug, Clone, Default)]
+struct RuntimeFunctions {
+    alloc: u32,
+    string_concat: u32,
+    string_eq: u32,
+    array_copy: u32,
+}
+
 /// Enhanced WebAssembly code generation backend
+///
+/// Numbers are `f64`, Ints are `i64`, booleans are `i32`, and strings and aggregates are `i32`
+/// pointers into linear memory. Strings are length-prefixed; aggregates use
+/// 8-byte slots, with the discriminant (enums) or length (arrays) in slot 0.
 pub struct WasmBackend {
     /// Module being built
     module: Module,
-    /// Function type indices
-    function_types: HashMap<String, u32>,
+    /// Type index for each distinct function signature
+    function_types: HashMap<(Vec<ValType>, Vec<ValType>), u32>,
+    /// Signatures in type index order
+    type_signatures: Vec<(Vec<ValType>, Vec<ValType>)>,
     /// Function indices
-    function_indices: HashMap<u32, u32>, // IR function ID -> WASM function index
+    function_indices: HashMap<String, u32>, // IR function name -> WASM function index
     /// Next available type index
     next_type_index: u32,
     /// Next available function index
@@ -156,62 +189,52 @@ pub struct WasmBackend {
     /// Target configuration
     target_config: WasmTargetConfig,
     /// Local variable indices for current function
-    local_indices: HashMap<String, u32>,
+    local_indices: HashMap<ValueId, u32>,
+    /// Types of the values defined in the current function
+    value_types: HashMap<ValueId, IrType>,
     /// Next available local index
     next_local_index: u32,
-    /// Constant pool for optimization
-    constant_pool: HashMap<i32, u32>,
+    /// Linear memory address of each string constant
+    string_addresses: HashMap<String, u32>,
+    /// Global indices and types of IR globals
+    global_indices: HashMap<String, (u32, IrType)>,
+    /// Runtime support function indices
+    runtime: RuntimeFunctions,
 }
 
 impl WasmBackend {
     /// Create a new WASM backend with default configuration
     pub fn new() -> Self {
-        Self {
-            module: Module::new(),
-            function_types: HashMap::new(),
-            function_indices: HashMap::new(),
-            next_type_index: 0,
-            next_function_index: 0,
-            deterministic_mode: false,
-            optimization_config: WasmOptimizationConfig::default(),
-            target_config: WasmTargetConfig::default(),
-            local_indices: HashMap::new(),
-            next_local_index: 0,
-            constant_pool: HashMap::new(),
-        }
+        Self::new_with_config(WasmOptimizationConfig::default(), WasmTargetConfig::default())
     }
 
     /// Create a new WASM backend with specific optimization configuration
     pub fn new_with_optimization(optimization_config: WasmOptimizationConfig) -> Self {
-        Self {
-            module: Module::new(),
-            function_types: HashMap::new(),
-            function_indices: HashMap::new(),
-            next_type_index: 0,
-            next_function_index: 0,
-            deterministic_mode: false,
-            optimization_config,
-            target_config: WasmTargetConfig::default(),
-            local_indices: HashMap::new(),
-            next_local_index: 0,
-            constant_pool: HashMap::new(),
-        }
+        Self::new_with_config(optimization_config, WasmTargetConfig::default())
     }
 
     /// Create a new WASM backend with specific target configuration
     pub fn new_with_target(target_config: WasmTargetConfig) -> Self {
+        Self::new_with_config(WasmOptimizationConfig::default(), target_config)
+    }
+
+    fn new_with_config(optimization_config: WasmOptimizationConfig, target_config: WasmTargetConfig) -> Self {
         Self {
             module: Module::new(),
             function_types: HashMap::new(),
+            type_signatures: Vec::new(),
             function_indices: HashMap::new(),
             next_type_index: 0,
             next_function_index: 0,
             deterministic_mode: false,
-            optimization_config: WasmOptimizationConfig::default(),
+            optimization_config,
             target_config,
             local_indices: HashMap::new(),
+            value_types: HashMap::new(),
             next_local_index: 0,
-            constant_pool: HashMap::new(),
+            string_addresses: HashMap::new(),
+            global_indices: HashMap::new(),
+            runtime: RuntimeFunctions::default(),
         }
     }
 
@@ -230,93 +253,252 @@ impl WasmBackend {
         self.target_config = config;
     }
 
-    /// Apply constant folding optimization
-    fn apply_constant_folding(&self, instruction: &Instruction) -> Option<i32> {
-        if !self.optimization_config.constant_folding {
-            return None;
+    /// Reset per-module state so the backend can be reused
+    fn reset(&mut self) {
+        self.module = Module::new();
+        self.function_types.clear();
+        self.type_signatures.clear();
+        self.function_indices.clear();
+        self.next_type_index = 0;
+        self.next_function_index = 0;
+        self.string_addresses.clear();
+        self.global_indices.clear();
+    }
+
+    /// Check if instruction should be eliminated (dead code elimination)
+    fn should_eliminate_instruction(&self, instruction: &Instruction, used_values: &HashSet<ValueId>) -> bool {
+        if !self.optimization_config.dead_code_elimination || used_values.contains(&instruction.id) {
+            return false;
         }
 
-        match instruction.opcode {
-            Opcode::Add => {
-                if instruction.operands.len() >= 2 {
-                    if let (Value::Constant(Constant::Number(a)), Value::Constant(Constant::Number(b))) = 
-                        (&instruction.operands[0], &instruction.operands[1]) {
-                        return Some((a + b) as i32);
+        // Only instructions without side effects or traps can be dropped
+        !matches!(
+            instruction.opcode,
+            Opcode::Call | Opcode::CallIndirect | Opcode::Print | Opcode::Assert | Opcode::Abort | Opcode::Store | Opcode::Alloca |
+            Opcode::SetField | Opcode::SetElement | Opcode::GetElement
+        )
+    }
+
+    /// Generate WASM module from IR with enhanced optimizations
+    fn generate_module(&mut self, ir: &Program) -> OvieResult<Vec<u8>> {
+        self.reset();
+
+        // Sort functions deterministically
+        let mut functions: Vec<_> = ir.functions.values().collect();
+        if self.deterministic_mode {
+            functions.sort_by(|a, b| a.name.cmp(&b.name));
+        } else {
+            functions.sort_by_key(|function| function.id);
+        }
+
+        let data = self.layout_strings(ir, &functions);
+        let heap_start = (DATA_START + data.len() as u32 + SLOT_SIZE - 1) & !(SLOT_SIZE - 1);
+
+        // Imports come first in the function index space
+        let print_type = self.type_index(vec![ValType::I32], vec![]);
+        let print_number_type = self.type_index(vec![ValType::F64], vec![]);
+        let print_int_type = self.type_index(vec![ValType::I64], vec![]);
+        self.next_function_index = IMPORT_COUNT;
+
+        let mut function_type_indices = Vec::new();
+        for function in &functions {
+            let params = function.parameters.iter()
+                .filter_map(|param| Self::val_type(&param.param_type))
+                .collect();
+            let results = Self::val_type(&function.return_type).into_iter().collect();
+            function_type_indices.push(self.type_index(params, results));
+            self.function_indices.insert(function.name.clone(), self.next_function_index);
+            self.next_function_index += 1;
+        }
+
+        let alloc_type = self.type_index(vec![ValType::I32], vec![ValType::I32]);
+        let binary_type = self.type_index(vec![ValType::I32, ValType::I32], vec![ValType::I32]);
+        self.runtime = RuntimeFunctions {
+            alloc: self.next_function_index,
+            string_concat: self.next_function_index + 1,
+            string_eq: self.next_function_index + 2,
+            array_copy: self.next_function_index + 3,
+        };
+        self.next_function_index += 4;
+
+        // Signatures called through the function table need a type before the type section is emitted
+        let mut has_indirect_calls = false;
+        for function in &functions {
+            let result_types: HashMap<ValueId, &IrType> = function.basic_blocks.values()
+                .flat_map(|block| &block.instructions)
+                .map(|instruction| (instruction.id, &instruction.result_type))
+                .collect();
+            for block in Self::ordered_blocks(function) {
+                for instruction in &block.instructions {
+                    if let Opcode::CallIndirect = instruction.opcode {
+                        has_indirect_calls = true;
+                        if let Some(Value::Instruction(id)) = instruction.operands.first() {
+                            if let Some(IrType::Function { params, return_type }) = result_types.get(id) {
+                                let (params, results) = Self::indirect_signature(params, return_type);
+                                self.type_index(params, results);
+                            }
+                        }
                     }
                 }
             }
-            Opcode::Sub => {
-                if instruction.operands.len() >= 2 {
-                    if let (Value::Constant(Constant::Number(a)), Value::Constant(Constant::Number(b))) = 
-                        (&instruction.operands[0], &instruction.operands[1]) {
-                        return Some((a - b) as i32);
-                    }
-                }
+        }
+
+        // Type section
+        let mut types = TypeSection::new();
+        for (params, results) in &self.type_signatures {
+            types.function(params.iter().copied(), results.iter().copied());
+        }
+        self.module.section(&types);
+
+        // Import section
+        let mut imports = ImportSection::new();
+        imports.import("env", "print", EntityType::Function(print_type));
+        imports.import("env", "print_number", EntityType::Function(print_number_type));
+        imports.import("env", "print_boolean", EntityType::Function(print_type));
+        imports.import("env", "print_int", EntityType::Function(print_int_type));
+        imports.import("env", "abort", EntityType::Function(print_type));
+        self.module.section(&imports);
+
+        // Function section
+        let mut function_section = FunctionSection::new();
+        for type_index in &function_type_indices {
+            function_section.function(*type_index);
+        }
+        function_section.function(alloc_type);
+        function_section.function(binary_type);
+        function_section.function(binary_type);
+        function_section.function(alloc_type);
+        self.module.section(&function_section);
+
+        // Function values are indices into a table holding every function at its own index
+        if has_indirect_calls {
+            let mut tables = TableSection::new();
+            tables.table(TableType {
+                element_type: RefType::FUNCREF,
+                minimum: self.next_function_index,
+                maximum: Some(self.next_function_index),
+            });
+            self.module.section(&tables);
+        }
+
+        self.add_memory();
+        self.add_globals(ir, heap_start)?;
+        self.add_exports(ir);
+
+        if has_indirect_calls {
+            let indices: Vec<u32> = (0..self.next_function_index).collect();
+            let mut elements = ElementSection::new();
+            elements.active(None, &ConstExpr::i32_const(0), Elements::Functions(&indices));
+            self.module.section(&elements);
+        }
+
+        // Code section
+        let mut code = CodeSection::new();
+        for function in &functions {
+            let body = self.generate_function_body(function)?;
+            code.function(&body);
+        }
+        code.function(&Self::generate_alloc());
+        code.function(&self.generate_string_concat());
+        code.function(&self.generate_string_eq());
+        code.function(&self.generate_array_copy());
+        self.module.section(&code);
+
+        // Data section
+        if !data.is_empty() {
+            let mut data_section = DataSection::new();
+            data_section.active(0, &ConstExpr::i32_const(DATA_START as i32), data);
+            self.module.section(&data_section);
+        }
+
+        let module = std::mem::replace(&mut self.module, Module::new());
+        Ok(module.finish())
+    }
+
+    /// WASM signature of the function behind a function value of IR type
+    /// `params -> return_type`; it takes the closure record first
+    fn indirect_signature(params: &[IrType], return_type: &IrType) -> (Vec<ValType>, Vec<ValType>) {
+        let params = std::iter::once(ValType::I32)
+            .chain(params.iter().filter_map(Self::val_type))
+            .collect();
+        (params, Self::val_type(return_type).into_iter().collect())
+    }
+
+    /// Get or create the type index for a signature
+    fn type_index(&mut self, params: Vec<ValType>, results: Vec<ValType>) -> u32 {
+        let key = (params, results);
+        if let Some(&index) = self.function_types.get(&key) {
+            return index;
+        }
+
+        let index = self.next_type_index;
+        self.next_type_index += 1;
+        self.type_signatures.push(key.clone());
+        self.function_types.insert(key, index);
+        index
+    }
+
+    /// Assign every string constant an address and return the data segment bytes
+    fn layout_strings(&mut self, ir: &Program, functions: &[&Function]) -> Vec<u8> {
+        let mut strings = Vec::new();
+        let mut global_names: Vec<_> = ir.globals.keys().collect();
+        global_names.sort();
+        for name in global_names {
+            if let Some(Constant::String(s)) = &ir.globals[name].initializer {
+                strings.push(s.clone());
             }
-            Opcode::Mul => {
-                if instruction.operands.len() >= 2 {
-                    if let (Value::Constant(Constant::Number(a)), Value::Constant(Constant::Number(b))) = 
-                        (&instruction.operands[0], &instruction.operands[1]) {
-                        return Some((a * b) as i32);
-                    }
+        }
+
+        for function in functions {
+            for block in Self::ordered_blocks(function) {
+                let mut values: Vec<&Value> = block.instructions.iter()
+                    .flat_map(|instruction| instruction.operands.iter())
+                    .collect();
+                match &block.terminator {
+                    Terminator::Return { value: Some(value) } => values.push(value),
+                    Terminator::ConditionalBranch { condition, .. } => values.push(condition),
+                    _ => {}
                 }
-            }
-            Opcode::Div => {
-                if instruction.operands.len() >= 2 {
-                    if let (Value::Constant(Constant::Number(a)), Value::Constant(Constant::Number(b))) = 
-                        (&instruction.operands[0], &instruction.operands[1]) {
-                        if *b != 0.0 {
-                            return Some((a / b) as i32);
-                        }
+
+                for value in values {
+                    if let Value::Constant(Constant::String(s)) = value {
+                        strings.push(s.clone());
                     }
                 }
             }
-            _ => {}
         }
 
-        None
-    }
+        let mut data = Vec::new();
+        for s in strings {
+            if self.string_addresses.contains_key(&s) {
+                continue;
+            }
 
-    /// Check if instruction should be eliminated (dead code elimination)
-    fn should_eliminate_instruction(&self, _instruction: &Instruction) -> bool {
-        // Simple dead code elimination - for now, don't eliminate anything
-        // In a full implementation, this would track variable usage
-        !self.optimization_config.dead_code_elimination
+            self.string_addresses.insert(s.clone(), DATA_START + data.len() as u32);
+            data.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            data.extend_from_slice(s.as_bytes());
+            while data.len() % 4 != 0 {
+                data.push(0);
+            }
+        }
+
+        data
     }
 
-    /// Generate WASM module from IR with enhanced optimizations
-    fn generate_module(&mut self, ir: &Program) -> OvieResult<Vec<u8>> {
-        // Add memory section if needed
-        if self.target_config.memory_config.initial_pages > 0 {
-            self.add_memory()?;
-        }
-        
-        // Add type section
-        self.add_types(ir)?;
-        
-        // Add import section (for print function)
-        self.add_imports()?;
-        
-        // Add function section
-        self.add_functions(ir)?;
-        
-        // Add export section
-        self.add_exports(ir)?;
-        
-        // Add code section with optimizations
-        self.add_code_with_optimizations(ir)?;
-        
-        // Clone the module to avoid move issues
-        let module = std::mem::replace(&mut self.module, Module::new());
-        Ok(module.finish())
+    /// Entry block first, then the remaining blocks in id order
+    fn ordered_blocks(function: &Function) -> Vec<&BasicBlock> {
+        let mut blocks: Vec<_> = function.basic_blocks.values().collect();
+        blocks.sort_by_key(|block| (block.id != function.entry_block, block.id));
+        blocks
     }
 
     /// Add memory section
-    fn add_memory(&mut self) -> OvieResult<()> {
+    fn add_memory(&mut self) {
         let mut memories = MemorySection::new();
         
         let memory_type = MemoryType {
-            minimum: self.target_config.memory_config.initial_pages as u64,
+            // String constants and the heap always need at least one page
+            minimum: self.target_config.memory_config.initial_pages.max(1) as u64,
             maximum: self.target_config.memory_config.maximum_pages.map(|p| p as u64),
             memory64: self.target_config.memory_config.memory64,
             shared: self.target_config.memory_config.shared,
@@ -324,306 +506,920 @@ impl WasmBackend {
         
         memories.memory(memory_type);
         self.module.section(&memories);
-        Ok(())
     }
 
-    /// Add type definitions
-    fn add_types(&mut self, ir: &Program) -> OvieResult<()> {
-        let mut types = TypeSection::new();
-        
-        // Add print function type: (i32) -> ()
-        types.function([ValType::I32], []);
-        self.function_types.insert("print".to_string(), self.next_type_index);
-        self.next_type_index += 1;
-        
-        // Sort functions deterministically if in deterministic mode
-        let mut functions: Vec<_> = ir.functions.iter().collect();
-        if self.deterministic_mode {
-            functions.sort_by_key(|(_, func)| &func.name);
-        }
-        
-        // Add function types
-        for (ir_id, function) in functions {
-            let params = vec![ValType::I32; function.parameters.len()];
-            let results = if function.return_type != IrType::Void { vec![ValType::I32] } else { vec![] };
-            
-            types.function(params, results);
-            self.function_types.insert(function.name.clone(), self.next_type_index);
-            self.next_type_index += 1;
-        }
-        
-        self.module.section(&types);
-        Ok(())
-    }
+    /// Add the heap pointer and the IR globals
+    fn add_globals(&mut self, ir: &Program, heap_start: u32) -> OvieResult<()> {
+        let mut globals = GlobalSection::new();
+        globals.global(
+            GlobalType { val_type: ValType::I32, mutable: true },
+            &ConstExpr::i32_const(heap_start as i32),
+        );
 
-    /// Add import section
-    fn add_imports(&mut self) -> OvieResult<()> {
-        let mut imports = ImportSection::new();
-        
-        // Import print function from host
-        let print_type_index = *self.function_types.get("print").unwrap();
-        imports.import("env", "print", EntityType::Function(print_type_index));
-        
-        self.module.section(&imports);
-        Ok(())
-    }
+        let mut names: Vec<_> = ir.globals.keys().collect();
+        names.sort();
+        for (offset, name) in names.into_iter().enumerate() {
+            let global = &ir.globals[name];
+            let val_type = Self::val_type(&global.global_type).ok_or_else(|| OvieError::CodegenError {
+                message: format!("Global '{}' has no runtime representation", name),
+            })?;
 
-    /// Add function declarations
-    fn add_functions(&mut self, ir: &Program) -> OvieResult<()> {
-        let mut functions = FunctionSection::new();
-        
-        // Sort functions deterministically if in deterministic mode
-        let mut ir_functions: Vec<_> = ir.functions.iter().collect();
-        if self.deterministic_mode {
-            ir_functions.sort_by_key(|(_, func)| &func.name);
-        }
-        
-        for (ir_id, function) in ir_functions {
-            let type_index = *self.function_types.get(&function.name).unwrap();
-            functions.function(type_index);
-            self.function_indices.insert(*ir_id, self.next_function_index);
-            self.next_function_index += 1;
+            let init = match (&global.initializer, val_type) {
+                (Some(Constant::Number(n)), _) => ConstExpr::f64_const(*n),
+                (Some(Constant::Integer(n)), _) => ConstExpr::i64_const(*n),
+                (Some(Constant::Boolean(b)), _) => ConstExpr::i32_const(*b as i32),
+                (Some(Constant::String(s)), _) => ConstExpr::i32_const(self.string_addresses[s] as i32),
+                (_, ValType::F64) => ConstExpr::f64_const(0.0),
+                (_, ValType::I64) => ConstExpr::i64_const(0),
+                _ => ConstExpr::i32_const(0),
+            };
+
+            globals.global(GlobalType { val_type, mutable: global.is_mutable }, &init);
+            self.global_indices.insert(name.clone(), (offset as u32 + 1, global.global_type.clone()));
         }
-        
-        self.module.section(&functions);
+
+        self.module.section(&globals);
         Ok(())
     }
 
     /// Add exports
-    fn add_exports(&mut self, ir: &Program) -> OvieResult<()> {
+    fn add_exports(&mut self, ir: &Program) {
         let mut exports = ExportSection::new();
         
         // Export main function if it exists
-        if let Some(entry_id) = ir.entry_point {
-            if let Some(&wasm_index) = self.function_indices.get(&entry_id) {
-                exports.export("main", ExportKind::Func, wasm_index + 1); // +1 for imported print
+        if let Some(entry) = ir.entry_point.and_then(|id| ir.functions.get(&id)) {
+            if let Some(&wasm_index) = self.function_indices.get(&entry.name) {
+                exports.export("main", ExportKind::Func, wasm_index);
             }
         }
+
+        // The host reads strings out of linear memory
+        exports.export("memory", ExportKind::Memory, 0);
         
         self.module.section(&exports);
-        Ok(())
     }
 
-    /// Add code section with function bodies and optimizations
-    fn add_code_with_optimizations(&mut self, ir: &Program) -> OvieResult<()> {
-        let mut code = CodeSection::new();
-        
-        // Sort functions deterministically if in deterministic mode
-        let mut functions: Vec<_> = ir.functions.iter().collect();
-        if self.deterministic_mode {
-            functions.sort_by_key(|(_, func)| &func.name);
+    /// WASM value type used to represent an IR type, `None` for void
+    fn val_type(ir_type: &IrType) -> Option<ValType> {
+        match ir_type {
+            IrType::Void => None,
+            IrType::Number => Some(ValType::F64),
+            IrType::Int => Some(ValType::I64),
+            IrType::Boolean | IrType::String | IrType::Pointer(_) | IrType::Function { .. } |
+            IrType::Struct(_) | IrType::Enum(_) | IrType::Array(_) => Some(ValType::I32),
         }
-        
-        for (ir_id, function) in functions {
-            self.local_indices.clear();
-            self.next_local_index = 0;
-            
-            // Determine local variables needed
-            let locals = self.analyze_function_locals(function)?;
-            let mut func_body = wasm_encoder::Function::new(locals);
-            
-            // Generate optimized code for the function
-            self.generate_optimized_function_body(&mut func_body, function)?;
-            
-            code.function(&func_body);
-        }
-        
-        self.module.section(&code);
-        Ok(())
     }
 
-    /// Analyze function to determine local variables needed
-    fn analyze_function_locals(&mut self, ir_func: &Function) -> OvieResult<Vec<(u32, ValType)>> {
+    /// Generate the body of an IR function.
+    ///
+    /// Basic blocks are dispatched through a loop around nested blocks and a
+    /// `br_table` on a program counter local, which supports arbitrary CFGs.
+    fn generate_function_body(&mut self, ir_func: &Function) -> OvieResult<wasm_encoder::Function> {
+        self.local_indices.clear();
+        self.value_types.clear();
+        self.next_local_index = 0;
+
+        // Parameters occupy the first locals
+        for param in &ir_func.parameters {
+            self.value_types.insert(param.value_id, param.param_type.clone());
+            if Self::val_type(&param.param_type).is_some() {
+                self.local_indices.insert(param.value_id, self.next_local_index);
+                self.next_local_index += 1;
+            }
+        }
+
+        let blocks = Self::ordered_blocks(ir_func);
         let mut locals = Vec::new();
-        
-        // For now, just add one i32 local per parameter
-        for _ in &ir_func.parameters {
-            locals.push((1, ValType::I32));
+        for block in &blocks {
+            for instruction in &block.instructions {
+                self.value_types.insert(instruction.id, instruction.result_type.clone());
+
+                // A stack slot becomes a local holding the slot's contents
+                let stored_type = match (&instruction.opcode, &instruction.result_type) {
+                    (Opcode::Alloca, IrType::Pointer(inner)) => inner,
+                    (_, result_type) => result_type,
+                };
+                if let Some(val_type) = Self::val_type(stored_type) {
+                    locals.push((1, val_type));
+                    self.local_indices.insert(instruction.id, self.next_local_index);
+                    self.next_local_index += 1;
+                }
+            }
         }
-        
-        Ok(locals)
-    }
 
-    /// Generate optimized WASM code for a function body
-    fn generate_optimized_function_body(&mut self, func: &mut wasm_encoder::Function, ir_func: &Function) -> OvieResult<()> {
-        // Get the entry block
-        let entry_block = ir_func.basic_blocks.get(&ir_func.entry_block)
-            .ok_or_else(|| OvieError::CodegenError { message: "Entry block not found".to_string() })?;
-        
-        // Generate code for the entry block
-        self.generate_optimized_block_code(func, entry_block)?;
-        
-        Ok(())
-    }
+        let pc = self.next_local_index;
+        let scratch = pc + 1;
+        let scratch_index = pc + 2;
+        locals.push((3, ValType::I32));
+        let scratch_locals = ScratchLocals { pc, pointer: scratch, index: scratch_index };
+
+        let used_values = Self::used_values(ir_func);
+        let block_positions: HashMap<BlockId, u32> = blocks.iter()
+            .enumerate()
+            .map(|(position, block)| (block.id, position as u32))
+            .collect();
+        let block_count = blocks.len() as u32;
+
+        let mut func = wasm_encoder::Function::new(locals);
+        func.instruction(&WasmInstruction::Loop(BlockType::Empty));
+        for _ in 0..block_count {
+            func.instruction(&WasmInstruction::Block(BlockType::Empty));
+        }
+        func.instruction(&WasmInstruction::LocalGet(pc));
+        func.instruction(&WasmInstruction::BrTable((0..block_count).collect::<Vec<_>>().into(), 0));
 
-    /// Generate optimized WASM code for a basic block
-    fn generate_optimized_block_code(&mut self, func: &mut wasm_encoder::Function, block: &BasicBlock) -> OvieResult<()> {
-        // Generate instructions with optimizations
-        for instruction in &block.instructions {
-            // Apply constant folding if enabled
-            if self.optimization_config.constant_folding {
-                if let Some(folded_value) = self.apply_constant_folding(instruction) {
-                    func.instruction(&wasm_encoder::Instruction::I32Const(folded_value));
+        for (position, block) in blocks.iter().enumerate() {
+            func.instruction(&WasmInstruction::End);
+
+            for instruction in &block.instructions {
+                if self.should_eliminate_instruction(instruction, &used_values) {
                     continue;
                 }
+                self.generate_instruction(&mut func, instruction, &scratch_locals)?;
             }
-            
-            // Skip dead code if elimination is enabled
-            if self.optimization_config.dead_code_elimination && self.should_eliminate_instruction(instruction) {
-                continue;
+
+            // Blocks after this one are still open between it and the dispatch loop
+            let loop_depth = block_count - 1 - position as u32;
+            self.generate_terminator(&mut func, ir_func, &block.terminator, &block_positions, &scratch_locals, loop_depth)?;
+        }
+
+        func.instruction(&WasmInstruction::End);
+        func.instruction(&WasmInstruction::Unreachable);
+        func.instruction(&WasmInstruction::End);
+        Ok(func)
+    }
+
+    /// Ids of all values read by instructions or terminators in a function
+    fn used_values(ir_func: &Function) -> HashSet<ValueId> {
+        let mut used = HashSet::new();
+        for block in ir_func.basic_blocks.values() {
+            let mut values: Vec<&Value> = block.instructions.iter()
+                .flat_map(|instruction| instruction.operands.iter())
+                .collect();
+            match &block.terminator {
+                Terminator::Return { value: Some(value) } => values.push(value),
+                Terminator::ConditionalBranch { condition, .. } => values.push(condition),
+                _ => {}
+            }
+
+            for value in values {
+                if let Value::Instruction(id) = value {
+                    used.insert(*id);
+                }
             }
-            
-            self.generate_enhanced_instruction_code(func, instruction)?;
         }
-        
-        // Generate terminator
-        self.generate_enhanced_terminator_code(func, &block.terminator)?;
-        
-        Ok(())
+        used
     }
 
-    /// Generate enhanced WASM code for an instruction
-    fn generate_enhanced_instruction_code(&mut self, func: &mut wasm_encoder::Function, instruction: &Instruction) -> OvieResult<()> {
+    /// Generate WASM code for an instruction
+    fn generate_instruction(
+        &mut self,
+        func: &mut wasm_encoder::Function,
+        instruction: &Instruction,
+        scratch: &ScratchLocals,
+    ) -> OvieResult<()> {
+        let operand = |index: usize| instruction.operands.get(index).ok_or_else(|| OvieError::CodegenError {
+            message: format!("Instruction {} is missing operand {}", instruction.id, index),
+        });
+
         match instruction.opcode {
-            Opcode::Print => {
-                // Generate code for operands
-                for operand in &instruction.operands {
-                    self.generate_enhanced_value_code(func, operand)?;
+            Opcode::Alloca => return Ok(()),
+            Opcode::Load => match operand(0)? {
+                Value::Instruction(slot) => match self.local_indices.get(slot) {
+                    Some(&local) => {
+                        func.instruction(&WasmInstruction::LocalGet(local));
+                    }
+                    None => return Ok(()),
+                },
+                other => self.generate_value(func, other)?,
+            },
+            Opcode::Store => {
+                let target = match operand(0)? {
+                    Value::Instruction(slot) => self.local_indices.get(slot).map(|&local| WasmInstruction::LocalSet(local)),
+                    Value::Global(name) => self.global_indices.get(name).map(|(index, _)| WasmInstruction::GlobalSet(*index)),
+                    _ => None,
+                };
+                if let Some(target) = target {
+                    self.generate_value(func, operand(1)?)?;
+                    func.instruction(&target);
                 }
-                // Call imported print function
-                func.instruction(&wasm_encoder::Instruction::Call(0)); // print is function index 0
+                return Ok(());
             }
-            Opcode::Add => {
-                // Generate code for operands
-                for operand in &instruction.operands {
-                    self.generate_enhanced_value_code(func, operand)?;
-                }
-                func.instruction(&wasm_encoder::Instruction::I32Add);
+            // Int division and remainder trap on a zero divisor, and division on MIN / -1
+            Opcode::Add | Opcode::Sub | Opcode::Mul | Opcode::Div | Opcode::Mod
+                if instruction.result_type == IrType::Int =>
+            {
+                self.generate_value(func, operand(0)?)?;
+                self.generate_value(func, operand(1)?)?;
+                func.instruction(&match instruction.opcode {
+                    Opcode::Add => WasmInstruction::I64Add,
+                    Opcode::Sub => WasmInstruction::I64Sub,
+                    Opcode::Mul => WasmInstruction::I64Mul,
+                    Opcode::Div => WasmInstruction::I64DivS,
+                    _ => WasmInstruction::I64RemS,
+                });
+            }
+            Opcode::Add | Opcode::Sub | Opcode::Mul | Opcode::Div => {
+                self.generate_value(func, operand(0)?)?;
+                self.generate_value(func, operand(1)?)?;
+                func.instruction(&match instruction.opcode {
+                    Opcode::Add => WasmInstruction::F64Add,
+                    Opcode::Sub => WasmInstruction::F64Sub,
+                    Opcode::Mul => WasmInstruction::F64Mul,
+                    _ => WasmInstruction::F64Div,
+                });
+            }
+            Opcode::Mod => {
+                // a - b * trunc(a / b)
+                let (left, right) = (operand(0)?, operand(1)?);
+                self.generate_value(func, left)?;
+                self.generate_value(func, left)?;
+                self.generate_value(func, right)?;
+                func.instruction(&WasmInstruction::F64Div);
+                func.instruction(&WasmInstruction::F64Trunc);
+                self.generate_value(func, right)?;
+                func.instruction(&WasmInstruction::F64Mul);
+                func.instruction(&WasmInstruction::F64Sub);
             }
-            Opcode::Sub => {
-                // Generate code for operands
-                for operand in &instruction.operands {
-                    self.generate_enhanced_value_code(func, operand)?;
+            Opcode::Neg if instruction.result_type == IrType::Int => {
+                func.instruction(&WasmInstruction::I64Const(0));
+                self.generate_value(func, operand(0)?)?;
+                func.instruction(&WasmInstruction::I64Sub);
+            }
+            Opcode::Neg => {
+                self.generate_value(func, operand(0)?)?;
+                func.instruction(&WasmInstruction::F64Neg);
+            }
+            Opcode::Not => {
+                self.generate_value(func, operand(0)?)?;
+                func.instruction(&WasmInstruction::I32Eqz);
+            }
+            Opcode::And | Opcode::Or => {
+                self.generate_value(func, operand(0)?)?;
+                self.generate_value(func, operand(1)?)?;
+                func.instruction(&if let Opcode::And = instruction.opcode {
+                    WasmInstruction::I32And
+                } else {
+                    WasmInstruction::I32Or
+                });
+            }
+            Opcode::Eq | Opcode::Ne | Opcode::Lt | Opcode::Le | Opcode::Gt | Opcode::Ge => {
+                self.generate_comparison(func, &instruction.opcode, operand(0)?, operand(1)?)?;
+            }
+            Opcode::Call => {
+                let callee = match operand(0)? {
+                    Value::Function(name) => self.function_indices.get(name).copied().ok_or_else(|| OvieError::CodegenError {
+                        message: format!("Call to unknown function '{}'", name),
+                    })?,
+                    _ => {
+                        return Err(OvieError::CodegenError {
+                            message: "Indirect calls are not supported by the WASM backend yet".to_string(),
+                        });
+                    }
+                };
+                for argument in &instruction.operands[1..] {
+                    self.generate_value(func, argument)?;
                 }
-                func.instruction(&wasm_encoder::Instruction::I32Sub);
+                func.instruction(&WasmInstruction::Call(callee));
             }
-            Opcode::Mul => {
-                // Generate code for operands
-                for operand in &instruction.operands {
-                    self.generate_enhanced_value_code(func, operand)?;
+            Opcode::CallIndirect => {
+                let signature = match self.value_type(operand(0)?) {
+                    IrType::Function { params, return_type } => Self::indirect_signature(&params, &return_type),
+                    other => {
+                        return Err(OvieError::CodegenError {
+                            message: format!("Cannot call a value of type {:?}", other),
+                        });
+                    }
+                };
+                let ty = self.function_types.get(&signature).copied().ok_or_else(|| OvieError::CodegenError {
+                    message: format!("Indirect call {} has no function type", instruction.id),
+                })?;
+                for argument in &instruction.operands[1..] {
+                    self.generate_value(func, argument)?;
                 }
-                func.instruction(&wasm_encoder::Instruction::I32Mul);
+                self.generate_value(func, operand(0)?)?;
+                func.instruction(&WasmInstruction::CallIndirect { ty, table: 0 });
             }
-            Opcode::Div => {
-                // Generate code for operands
-                for operand in &instruction.operands {
-                    self.generate_enhanced_value_code(func, operand)?;
+            Opcode::Cast => {
+                let value = operand(0)?;
+                let source_type = self.value_type(value);
+                self.generate_value(func, value)?;
+                match (&source_type, &instruction.result_type) {
+                    (source, target) if source == target => {}
+                    (IrType::Boolean, IrType::Number) => {
+                        func.instruction(&WasmInstruction::F64ConvertI32U);
+                    }
+                    (IrType::Number, IrType::Boolean) => {
+                        func.instruction(&WasmInstruction::F64Const(0.0));
+                        func.instruction(&WasmInstruction::F64Ne);
+                    }
+                    (IrType::Int, IrType::Number) => {
+                        func.instruction(&WasmInstruction::F64ConvertI64S);
+                    }
+                    // Traps on NaN and on values outside the range of Int
+                    (IrType::Number, IrType::Int) => {
+                        func.instruction(&WasmInstruction::I64TruncF64S);
+                    }
+                    (source, target) => {
+                        return Err(OvieError::CodegenError {
+                            message: format!("Conversion from {:?} to {:?} is not supported by the WASM backend yet", source, target),
+                        });
+                    }
                 }
-                func.instruction(&wasm_encoder::Instruction::I32DivS);
             }
-            Opcode::Load => {
-                // Generate code for address operand
-                if let Some(operand) = instruction.operands.first() {
-                    self.generate_enhanced_value_code(func, operand)?;
+            Opcode::StringConcat => {
+                self.generate_value(func, operand(0)?)?;
+                self.generate_value(func, operand(1)?)?;
+                func.instruction(&WasmInstruction::Call(self.runtime.string_concat));
+            }
+            Opcode::Print => {
+                let value = operand(0)?;
+                let printer = match self.value_type(value) {
+                    IrType::String => IMPORT_PRINT,
+                    IrType::Number => IMPORT_PRINT_NUMBER,
+                    IrType::Int => IMPORT_PRINT_INT,
+                    IrType::Boolean => IMPORT_PRINT_BOOLEAN,
+                    other => {
+                        return Err(OvieError::CodegenError {
+                            message: format!("Printing {:?} values is not supported by the WASM backend yet", other),
+                        });
+                    }
+                };
+                self.generate_value(func, value)?;
+                func.instruction(&WasmInstruction::Call(printer));
+                return Ok(());
+            }
+            Opcode::Assert => {
+                // A false condition prints the message and traps
+                self.generate_value(func, operand(0)?)?;
+                func.instruction(&WasmInstruction::I32Eqz);
+                func.instruction(&WasmInstruction::If(BlockType::Empty));
+                self.generate_value(func, operand(1)?)?;
+                func.instruction(&WasmInstruction::Call(IMPORT_PRINT));
+                func.instruction(&WasmInstruction::Unreachable);
+                func.instruction(&WasmInstruction::End);
+                return Ok(());
+            }
+            Opcode::Abort => {
+                // The host reports the message; the trap stops the program if the host returns
+                self.generate_value(func, operand(0)?)?;
+                func.instruction(&WasmInstruction::Call(IMPORT_ABORT));
+                func.instruction(&WasmInstruction::Unreachable);
+                return Ok(());
+            }
+            Opcode::MakeStruct | Opcode::MakeEnum | Opcode::MakeArray => {
+                self.generate_aggregate(func, instruction, scratch)?;
+            }
+            Opcode::GetField | Opcode::SetField => {
+                let aggregate = operand(0)?;
+                let index = match operand(1)? {
+                    Value::Constant(Constant::Number(n)) => *n as u32,
+                    _ => {
+                        return Err(OvieError::CodegenError {
+                            message: format!("Field index of instruction {} must be a constant", instruction.id),
+                        });
+                    }
+                };
+
+                // Enum payloads follow the discriminant slot
+                let slot = match self.value_type(aggregate) {
+                    IrType::Enum(_) => index + 1,
+                    _ => index,
+                };
+
+                self.generate_value(func, aggregate)?;
+                if let Opcode::GetField = instruction.opcode {
+                    Self::generate_load(func, &instruction.result_type, (slot * SLOT_SIZE) as u64)?;
+                } else {
+                    let value = operand(2)?;
+                    self.generate_value(func, value)?;
+                    Self::generate_store(func, &self.value_type(value), (slot * SLOT_SIZE) as u64)?;
+                    self.generate_value(func, aggregate)?;
                 }
-                func.instruction(&wasm_encoder::Instruction::I32Load(wasm_encoder::MemArg {
-                    offset: 0,
-                    align: 2,
-                    memory_index: 0,
-                }));
             }
-            Opcode::Store => {
-                // Generate code for address and value operands
-                for operand in &instruction.operands {
-                    self.generate_enhanced_value_code(func, operand)?;
+            Opcode::GetElement | Opcode::SetElement => {
+                // Arrays are values: an element is stored into a copy of the array
+                self.generate_value(func, operand(0)?)?;
+                if let Opcode::SetElement = instruction.opcode {
+                    func.instruction(&WasmInstruction::Call(self.runtime.array_copy));
                 }
-                func.instruction(&wasm_encoder::Instruction::I32Store(wasm_encoder::MemArg {
-                    offset: 0,
-                    align: 2,
-                    memory_index: 0,
-                }));
-            }
-            _ => {
-                // For other opcodes, generate basic code
-                for operand in &instruction.operands {
-                    self.generate_enhanced_value_code(func, operand)?;
+                func.instruction(&WasmInstruction::LocalSet(scratch.pointer));
+
+                self.generate_element_address(func, operand(1)?, scratch)?;
+                if let Opcode::GetElement = instruction.opcode {
+                    Self::generate_load(func, &instruction.result_type, SLOT_SIZE as u64)?;
+                } else {
+                    let value = operand(2)?;
+                    self.generate_value(func, value)?;
+                    Self::generate_store(func, &self.value_type(value), SLOT_SIZE as u64)?;
+                    func.instruction(&WasmInstruction::LocalGet(scratch.pointer));
+                }
+            }
+            Opcode::Length | Opcode::Discriminant => {
+                self.generate_value(func, operand(0)?)?;
+                func.instruction(&WasmInstruction::I32Load(Self::mem_arg(0, 2)));
+                func.instruction(&if instruction.result_type == IrType::Int {
+                    WasmInstruction::I64ExtendI32U
+                } else {
+                    WasmInstruction::F64ConvertI32U
+                });
+            }
+        }
+
+        if let Some(&local) = self.local_indices.get(&instruction.id) {
+            func.instruction(&WasmInstruction::LocalSet(local));
+        }
+        Ok(())
+    }
+
+    /// Generate a comparison, picking the instruction from the operand type
+    fn generate_comparison(
+        &mut self,
+        func: &mut wasm_encoder::Function,
+        opcode: &Opcode,
+        left: &Value,
+        right: &Value,
+    ) -> OvieResult<()> {
+        let operand_type = self.value_type(left);
+        self.generate_value(func, left)?;
+        self.generate_value(func, right)?;
+
+        let instruction = match (&operand_type, opcode) {
+            (IrType::Number, Opcode::Eq) => WasmInstruction::F64Eq,
+            (IrType::Number, Opcode::Ne) => WasmInstruction::F64Ne,
+            (IrType::Number, Opcode::Lt) => WasmInstruction::F64Lt,
+            (IrType::Number, Opcode::Le) => WasmInstruction::F64Le,
+            (IrType::Number, Opcode::Gt) => WasmInstruction::F64Gt,
+            (IrType::Number, Opcode::Ge) => WasmInstruction::F64Ge,
+            (IrType::Int, Opcode::Eq) => WasmInstruction::I64Eq,
+            (IrType::Int, Opcode::Ne) => WasmInstruction::I64Ne,
+            (IrType::Int, Opcode::Lt) => WasmInstruction::I64LtS,
+            (IrType::Int, Opcode::Le) => WasmInstruction::I64LeS,
+            (IrType::Int, Opcode::Gt) => WasmInstruction::I64GtS,
+            (IrType::Int, Opcode::Ge) => WasmInstruction::I64GeS,
+            (IrType::String, Opcode::Eq | Opcode::Ne) => {
+                func.instruction(&WasmInstruction::Call(self.runtime.string_eq));
+                if let Opcode::Ne = opcode {
+                    func.instruction(&WasmInstruction::I32Eqz);
                 }
+                return Ok(());
+            }
+            (_, Opcode::Eq) => WasmInstruction::I32Eq,
+            (_, Opcode::Ne) => WasmInstruction::I32Ne,
+            (other, _) => {
+                return Err(OvieError::CodegenError {
+                    message: format!("Ordering comparison on {:?} is not supported by the WASM backend", other),
+                });
             }
+        };
+
+        func.instruction(&instruction);
+        Ok(())
+    }
+
+    /// Allocate a heap aggregate and initialize its slots
+    fn generate_aggregate(
+        &mut self,
+        func: &mut wasm_encoder::Function,
+        instruction: &Instruction,
+        scratch: &ScratchLocals,
+    ) -> OvieResult<()> {
+        let (header, fields) = match instruction.opcode {
+            Opcode::MakeEnum => match instruction.operands.split_first() {
+                Some((Value::Constant(Constant::Number(discriminant)), payload)) => (Some(*discriminant as i32), payload),
+                _ => {
+                    return Err(OvieError::CodegenError {
+                        message: format!("Enum construction {} needs a constant discriminant", instruction.id),
+                    });
+                }
+            },
+            Opcode::MakeArray => (Some(instruction.operands.len() as i32), instruction.operands.as_slice()),
+            _ => (None, instruction.operands.as_slice()),
+        };
+
+        let first_slot = header.is_some() as u32;
+        let size = (fields.len() as u32 + first_slot) * SLOT_SIZE;
+        func.instruction(&WasmInstruction::I32Const(size as i32));
+        func.instruction(&WasmInstruction::Call(self.runtime.alloc));
+        func.instruction(&WasmInstruction::LocalSet(scratch.pointer));
+
+        if let Some(header) = header {
+            func.instruction(&WasmInstruction::LocalGet(scratch.pointer));
+            func.instruction(&WasmInstruction::I32Const(header));
+            func.instruction(&WasmInstruction::I32Store(Self::mem_arg(0, 2)));
+        }
+
+        for (index, field) in fields.iter().enumerate() {
+            func.instruction(&WasmInstruction::LocalGet(scratch.pointer));
+            self.generate_value(func, field)?;
+            let offset = (index as u32 + first_slot) * SLOT_SIZE;
+            Self::generate_store(func, &self.value_type(field), offset as u64)?;
         }
+
+        func.instruction(&WasmInstruction::LocalGet(scratch.pointer));
         Ok(())
     }
 
-    /// Generate enhanced WASM code for a value with optimizations
-    fn generate_enhanced_value_code(&mut self, func: &mut wasm_encoder::Function, value: &Value) -> OvieResult<()> {
+    /// Push the address of an element (minus the length slot) of the array in the
+    /// scratch pointer, trapping when out of bounds
+    fn generate_element_address(
+        &mut self,
+        func: &mut wasm_encoder::Function,
+        index: &Value,
+        scratch: &ScratchLocals,
+    ) -> OvieResult<()> {
+        self.generate_value(func, index)?;
+        func.instruction(&if self.value_type(index) == IrType::Int {
+            WasmInstruction::I32WrapI64
+        } else {
+            WasmInstruction::I32TruncF64S
+        });
+        func.instruction(&WasmInstruction::LocalSet(scratch.index));
+
+        func.instruction(&WasmInstruction::LocalGet(scratch.index));
+        func.instruction(&WasmInstruction::LocalGet(scratch.pointer));
+        func.instruction(&WasmInstruction::I32Load(Self::mem_arg(0, 2)));
+        func.instruction(&WasmInstruction::I32GeU);
+        func.instruction(&WasmInstruction::If(BlockType::Empty));
+        func.instruction(&WasmInstruction::Unreachable);
+        func.instruction(&WasmInstruction::End);
+
+        func.instruction(&WasmInstruction::LocalGet(scratch.pointer));
+        func.instruction(&WasmInstruction::LocalGet(scratch.index));
+        func.instruction(&WasmInstruction::I32Const(SLOT_SIZE.trailing_zeros() as i32));
+        func.instruction(&WasmInstruction::I32Shl);
+        func.instruction(&WasmInstruction::I32Add);
+        Ok(())
+    }
+
+    /// Load a value of `ir_type` from the address on the stack
+    fn generate_load(func: &mut wasm_encoder::Function, ir_type: &IrType, offset: u64) -> OvieResult<()> {
+        match Self::val_type(ir_type) {
+            Some(ValType::F64) => func.instruction(&WasmInstruction::F64Load(Self::mem_arg(offset, 3))),
+            Some(ValType::I64) => func.instruction(&WasmInstruction::I64Load(Self::mem_arg(offset, 3))),
+            Some(_) => func.instruction(&WasmInstruction::I32Load(Self::mem_arg(offset, 2))),
+            None => {
+                return Err(OvieError::CodegenError {
+                    message: "Cannot load a void value from memory".to_string(),
+                });
+            }
+        };
+        Ok(())
+    }
+
+    /// Store a value of `ir_type` to the address below it on the stack
+    fn generate_store(func: &mut wasm_encoder::Function, ir_type: &IrType, offset: u64) -> OvieResult<()> {
+        match Self::val_type(ir_type) {
+            Some(ValType::F64) => func.instruction(&WasmInstruction::F64Store(Self::mem_arg(offset, 3))),
+            Some(ValType::I64) => func.instruction(&WasmInstruction::I64Store(Self::mem_arg(offset, 3))),
+            Some(_) => func.instruction(&WasmInstruction::I32Store(Self::mem_arg(offset, 2))),
+            None => {
+                return Err(OvieError::CodegenError {
+                    message: "Cannot store a void value to memory".to_string(),
+                });
+            }
+        };
+        Ok(())
+    }
+
+    fn mem_arg(offset: u64, align: u32) -> MemArg {
+        MemArg {
+            offset,
+            align,
+            memory_index: 0,
+        }
+    }
+
+    /// Type of an IR value in the current function
+    fn value_type(&self, value: &Value) -> IrType {
+        match value {
+            Value::Constant(Constant::String(_)) => IrType::String,
+            Value::Constant(Constant::Number(_)) => IrType::Number,
+            Value::Constant(Constant::Integer(_)) => IrType::Int,
+            Value::Constant(Constant::Boolean(_)) => IrType::Boolean,
+            Value::Constant(Constant::Void) => IrType::Void,
+            Value::Instruction(id) | Value::Parameter(id) => self.value_types.get(id).cloned().unwrap_or(IrType::Void),
+            Value::Global(name) => self.global_indices.get(name).map(|(_, ty)| ty.clone()).unwrap_or(IrType::Void),
+            // Function references are table indices
+            Value::Function(_) => IrType::Function { params: Vec::new(), return_type: Box::new(IrType::Void) },
+        }
+    }
+
+    /// Push an IR value onto the WASM stack
+    fn generate_value(&self, func: &mut wasm_encoder::Function, value: &Value) -> OvieResult<()> {
         match value {
             Value::Constant(constant) => {
                 match constant {
                     Constant::Number(n) => {
-                        // Use constant pool if enabled
-                        if self.optimization_config.constant_pooling {
-                            if let Some(&pool_index) = self.constant_pool.get(&(*n as i32)) {
-                                func.instruction(&wasm_encoder::Instruction::I32Const(pool_index as i32));
-                                return Ok(());
-                            } else {
-                                let pool_index = self.constant_pool.len() as u32;
-                                self.constant_pool.insert(*n as i32, pool_index);
-                            }
-                        }
-                        func.instruction(&wasm_encoder::Instruction::I32Const(*n as i32));
+                        func.instruction(&WasmInstruction::F64Const(*n));
+                    }
+                    Constant::Integer(n) => {
+                        func.instruction(&WasmInstruction::I64Const(*n));
                     }
                     Constant::String(s) => {
-                        // For strings, we'll use the length as a simple representation
-                        func.instruction(&wasm_encoder::Instruction::I32Const(s.len() as i32));
+                        let address = self.string_addresses.get(s).copied().ok_or_else(|| OvieError::CodegenError {
+                            message: format!("String constant {:?} was not laid out", s),
+                        })?;
+                        func.instruction(&WasmInstruction::I32Const(address as i32));
                     }
                     Constant::Boolean(b) => {
-                        func.instruction(&wasm_encoder::Instruction::I32Const(if *b { 1 } else { 0 }));
-                    }
-                    Constant::Void => {
-                        func.instruction(&wasm_encoder::Instruction::I32Const(0));
+                        func.instruction(&WasmInstruction::I32Const(*b as i32));
                     }
+                    Constant::Void => {}
                 }
             }
-            Value::Instruction(value_id) => {
-                // For now, just push a placeholder value
-                func.instruction(&wasm_encoder::Instruction::I32Const(*value_id as i32));
+            Value::Instruction(value_id) | Value::Parameter(value_id) => {
+                if let Some(&local) = self.local_indices.get(value_id) {
+                    func.instruction(&WasmInstruction::LocalGet(local));
+                }
             }
             Value::Global(name) => {
-                // For now, just push a placeholder value
-                func.instruction(&wasm_encoder::Instruction::I32Const(name.len() as i32));
+                let (index, _) = self.global_indices.get(name).ok_or_else(|| OvieError::CodegenError {
+                    message: format!("Unknown global '{}'", name),
+                })?;
+                func.instruction(&WasmInstruction::GlobalGet(*index));
             }
-            Value::Parameter(param_id) => {
-                // For now, just push a placeholder value
-                func.instruction(&wasm_encoder::Instruction::I32Const(*param_id as i32));
+            Value::Function(name) => {
+                let index = self.function_indices.get(name).copied().ok_or_else(|| OvieError::CodegenError {
+                    message: format!("Reference to unknown function '{}'", name),
+                })?;
+                func.instruction(&WasmInstruction::I32Const(index as i32));
             }
         }
         Ok(())
     }
 
-    /// Generate enhanced WASM code for a terminator
-    fn generate_enhanced_terminator_code(&mut self, func: &mut wasm_encoder::Function, terminator: &Terminator) -> OvieResult<()> {
+    /// Generate WASM code for a terminator
+    fn generate_terminator(
+        &mut self,
+        func: &mut wasm_encoder::Function,
+        ir_func: &Function,
+        terminator: &Terminator,
+        block_positions: &HashMap<BlockId, u32>,
+        scratch: &ScratchLocals,
+        loop_depth: u32,
+    ) -> OvieResult<()> {
+        let position = |target: &BlockId| block_positions.get(target).copied().ok_or_else(|| OvieError::CodegenError {
+            message: format!("Branch to unknown block {} in function {}", target, ir_func.name),
+        });
+
         match terminator {
             Terminator::Return { value } => {
-                if let Some(val) = value {
-                    self.generate_enhanced_value_code(func, val)?;
+                match (Self::val_type(&ir_func.return_type), value) {
+                    (Some(_), Some(value)) => self.generate_value(func, value)?,
+                    (Some(ValType::F64), None) => {
+                        func.instruction(&WasmInstruction::F64Const(0.0));
+                    }
+                    (Some(ValType::I64), None) => {
+                        func.instruction(&WasmInstruction::I64Const(0));
+                    }
+                    (Some(_), None) => {
+                        func.instruction(&WasmInstruction::I32Const(0));
+                    }
+                    (None, _) => {}
                 }
-                func.instruction(&wasm_encoder::Instruction::Return);
+                func.instruction(&WasmInstruction::Return);
             }
-            Terminator::Branch { target: _ } => {
-                // For now, just return (simplified)
-                func.instruction(&wasm_encoder::Instruction::Return);
+            Terminator::Branch { target } => {
+                func.instruction(&WasmInstruction::I32Const(position(target)? as i32));
+                func.instruction(&WasmInstruction::LocalSet(scratch.pc));
+                func.instruction(&WasmInstruction::Br(loop_depth));
             }
-            Terminator::ConditionalBranch { condition, true_target: _, false_target: _ } => {
-                // Generate condition code
-                self.generate_enhanced_value_code(func, condition)?;
-                // For now, just return (simplified)
-                func.instruction(&wasm_encoder::Instruction::Return);
+            Terminator::ConditionalBranch { condition, true_target, false_target } => {
+                func.instruction(&WasmInstruction::I32Const(position(true_target)? as i32));
+                func.instruction(&WasmInstruction::I32Const(position(false_target)? as i32));
+                self.generate_value(func, condition)?;
+                match self.value_type(condition) {
+                    IrType::Number => {
+                        func.instruction(&WasmInstruction::F64Const(0.0));
+                        func.instruction(&WasmInstruction::F64Ne);
+                    }
+                    IrType::Int => {
+                        func.instruction(&WasmInstruction::I64Const(0));
+                        func.instruction(&WasmInstruction::I64Ne);
+                    }
+                    _ => {}
+                }
+                func.instruction(&WasmInstruction::Select);
+                func.instruction(&WasmInstruction::LocalSet(scratch.pc));
+                func.instruction(&WasmInstruction::Br(loop_depth));
             }
             Terminator::Unreachable => {
-                func.instruction(&wasm_encoder::Instruction::Unreachable);
+                func.instruction(&WasmInstruction::Unreachable);
             }
         }
         Ok(())
     }
+
+    /// `alloc(size) -> ptr`: bump allocator that grows memory on demand
+    fn generate_alloc() -> wasm_encoder::Function {
+        let (size, pointer) = (0, 1);
+        let mut func = wasm_encoder::Function::new([(1, ValType::I32)]);
+        let page_end = |func: &mut wasm_encoder::Function| {
+            func.instruction(&WasmInstruction::MemorySize(0));
+            func.instruction(&WasmInstruction::I32Const(16));
+            func.instruction(&WasmInstruction::I32Shl);
+        };
+
+        func.instruction(&WasmInstruction::GlobalGet(HEAP_POINTER_GLOBAL));
+        func.instruction(&WasmInstruction::LocalSet(pointer));
+
+        // Bump the heap pointer, keeping it slot aligned
+        func.instruction(&WasmInstruction::GlobalGet(HEAP_POINTER_GLOBAL));
+        func.instruction(&WasmInstruction::LocalGet(size));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::I32Const(SLOT_SIZE as i32 - 1));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::I32Const(-(SLOT_SIZE as i32)));
+        func.instruction(&WasmInstruction::I32And);
+        func.instruction(&WasmInstruction::GlobalSet(HEAP_POINTER_GLOBAL));
+
+        // Grow memory by the missing number of pages, trapping if that fails
+        func.instruction(&WasmInstruction::GlobalGet(HEAP_POINTER_GLOBAL));
+        page_end(&mut func);
+        func.instruction(&WasmInstruction::I32GtU);
+        func.instruction(&WasmInstruction::If(BlockType::Empty));
+        func.instruction(&WasmInstruction::GlobalGet(HEAP_POINTER_GLOBAL));
+        page_end(&mut func);
+        func.instruction(&WasmInstruction::I32Sub);
+        func.instruction(&WasmInstruction::I32Const(0xFFFF));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::I32Const(16));
+        func.instruction(&WasmInstruction::I32ShrU);
+        func.instruction(&WasmInstruction::MemoryGrow(0));
+        func.instruction(&WasmInstruction::I32Const(-1));
+        func.instruction(&WasmInstruction::I32Eq);
+        func.instruction(&WasmInstruction::If(BlockType::Empty));
+        func.instruction(&WasmInstruction::Unreachable);
+        func.instruction(&WasmInstruction::End);
+        func.instruction(&WasmInstruction::End);
+
+        func.instruction(&WasmInstruction::LocalGet(pointer));
+        func.instruction(&WasmInstruction::End);
+        func
+    }
+
+    /// `string_concat(a, b) -> ptr`: allocate a new string holding `a` followed by `b`
+    fn generate_string_concat(&self) -> wasm_encoder::Function {
+        let (left, right, left_len, right_len, result, dst, src, counter) = (0, 1, 2, 3, 4, 5, 6, 7);
+        let mut func = wasm_encoder::Function::new([(6, ValType::I32)]);
+
+        func.instruction(&WasmInstruction::LocalGet(left));
+        func.instruction(&WasmInstruction::I32Load(Self::mem_arg(0, 2)));
+        func.instruction(&WasmInstruction::LocalSet(left_len));
+        func.instruction(&WasmInstruction::LocalGet(right));
+        func.instruction(&WasmInstruction::I32Load(Self::mem_arg(0, 2)));
+        func.instruction(&WasmInstruction::LocalSet(right_len));
+
+        func.instruction(&WasmInstruction::I32Const(4));
+        func.instruction(&WasmInstruction::LocalGet(left_len));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::LocalGet(right_len));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::Call(self.runtime.alloc));
+        func.instruction(&WasmInstruction::LocalSet(result));
+
+        func.instruction(&WasmInstruction::LocalGet(result));
+        func.instruction(&WasmInstruction::LocalGet(left_len));
+        func.instruction(&WasmInstruction::LocalGet(right_len));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::I32Store(Self::mem_arg(0, 2)));
+
+        for (source, length, after_left) in [(left, left_len, false), (right, right_len, true)] {
+            func.instruction(&WasmInstruction::LocalGet(result));
+            func.instruction(&WasmInstruction::I32Const(4));
+            func.instruction(&WasmInstruction::I32Add);
+            if after_left {
+                func.instruction(&WasmInstruction::LocalGet(left_len));
+                func.instruction(&WasmInstruction::I32Add);
+            }
+            func.instruction(&WasmInstruction::LocalSet(dst));
+            func.instruction(&WasmInstruction::LocalGet(source));
+            func.instruction(&WasmInstruction::I32Const(4));
+            func.instruction(&WasmInstruction::I32Add);
+            func.instruction(&WasmInstruction::LocalSet(src));
+            self.generate_memory_copy(&mut func, dst, src, length, counter);
+        }
+
+        func.instruction(&WasmInstruction::LocalGet(result));
+        func.instruction(&WasmInstruction::End);
+        func
+    }
+
+    /// Copy `len` bytes from `src` to `dst` (all locals), using bulk memory when enabled
+    fn generate_memory_copy(&self, func: &mut wasm_encoder::Function, dst: u32, src: u32, len: u32, counter: u32) {
+        if self.target_config.bulk_memory {
+            func.instruction(&WasmInstruction::LocalGet(dst));
+            func.instruction(&WasmInstruction::LocalGet(src));
+            func.instruction(&WasmInstruction::LocalGet(len));
+            func.instruction(&WasmInstruction::MemoryCopy { src_mem: 0, dst_mem: 0 });
+            return;
+        }
+
+        func.instruction(&WasmInstruction::I32Const(0));
+        func.instruction(&WasmInstruction::LocalSet(counter));
+        func.instruction(&WasmInstruction::Block(BlockType::Empty));
+        func.instruction(&WasmInstruction::Loop(BlockType::Empty));
+        func.instruction(&WasmInstruction::LocalGet(counter));
+        func.instruction(&WasmInstruction::LocalGet(len));
+        func.instruction(&WasmInstruction::I32GeU);
+        func.instruction(&WasmInstruction::BrIf(1));
+        func.instruction(&WasmInstruction::LocalGet(dst));
+        func.instruction(&WasmInstruction::LocalGet(counter));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::LocalGet(src));
+        func.instruction(&WasmInstruction::LocalGet(counter));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::I32Load8U(Self::mem_arg(0, 0)));
+        func.instruction(&WasmInstruction::I32Store8(Self::mem_arg(0, 0)));
+        func.instruction(&WasmInstruction::LocalGet(counter));
+        func.instruction(&WasmInstruction::I32Const(1));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::LocalSet(counter));
+        func.instruction(&WasmInstruction::Br(0));
+        func.instruction(&WasmInstruction::End);
+        func.instruction(&WasmInstruction::End);
+    }
+
+    /// `array_copy(a) -> ptr`: allocate a copy of array `a`, length slot included
+    fn generate_array_copy(&self) -> wasm_encoder::Function {
+        let (array, size, result, counter) = (0, 1, 2, 3);
+        let mut func = wasm_encoder::Function::new([(3, ValType::I32)]);
+
+        func.instruction(&WasmInstruction::LocalGet(array));
+        func.instruction(&WasmInstruction::I32Load(Self::mem_arg(0, 2)));
+        func.instruction(&WasmInstruction::I32Const(1));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::I32Const(SLOT_SIZE.trailing_zeros() as i32));
+        func.instruction(&WasmInstruction::I32Shl);
+        func.instruction(&WasmInstruction::LocalSet(size));
+
+        func.instruction(&WasmInstruction::LocalGet(size));
+        func.instruction(&WasmInstruction::Call(self.runtime.alloc));
+        func.instruction(&WasmInstruction::LocalSet(result));
+        self.generate_memory_copy(&mut func, result, array, size, counter);
+
+        func.instruction(&WasmInstruction::LocalGet(result));
+        func.instruction(&WasmInstruction::End);
+        func
+    }
+
+    /// `string_eq(a, b) -> i32`: byte-wise string equality
+    fn generate_string_eq(&self) -> wasm_encoder::Function {
+        let (left, right, length, counter) = (0, 1, 2, 3);
+        let mut func = wasm_encoder::Function::new([(2, ValType::I32)]);
+
+        func.instruction(&WasmInstruction::LocalGet(left));
+        func.instruction(&WasmInstruction::I32Load(Self::mem_arg(0, 2)));
+        func.instruction(&WasmInstruction::LocalSet(length));
+        func.instruction(&WasmInstruction::LocalGet(length));
+        func.instruction(&WasmInstruction::LocalGet(right));
+        func.instruction(&WasmInstruction::I32Load(Self::mem_arg(0, 2)));
+        func.instruction(&WasmInstruction::I32Ne);
+        func.instruction(&WasmInstruction::If(BlockType::Empty));
+        func.instruction(&WasmInstruction::I32Const(0));
+        func.instruction(&WasmInstruction::Return);
+        func.instruction(&WasmInstruction::End);
+
+        func.instruction(&WasmInstruction::Block(BlockType::Empty));
+        func.instruction(&WasmInstruction::Loop(BlockType::Empty));
+        func.instruction(&WasmInstruction::LocalGet(counter));
+        func.instruction(&WasmInstruction::LocalGet(length));
+        func.instruction(&WasmInstruction::I32GeU);
+        func.instruction(&WasmInstruction::BrIf(1));
+        for string in [left, right] {
+            func.instruction(&WasmInstruction::LocalGet(string));
+            func.instruction(&WasmInstruction::LocalGet(counter));
+            func.instruction(&WasmInstruction::I32Add);
+            func.instruction(&WasmInstruction::I32Load8U(Self::mem_arg(4, 0)));
+        }
+        func.instruction(&WasmInstruction::I32Ne);
+        func.instruction(&WasmInstruction::If(BlockType::Empty));
+        func.instruction(&WasmInstruction::I32Const(0));
+        func.instruction(&WasmInstruction::Return);
+        func.instruction(&WasmInstruction::End);
+        func.instruction(&WasmInstruction::LocalGet(counter));
+        func.instruction(&WasmInstruction::I32Const(1));
+        func.instruction(&WasmInstruction::I32Add);
+        func.instruction(&WasmInstruction::LocalSet(counter));
+        func.instruction(&WasmInstruction::Br(0));
+        func.instruction(&WasmInstruction::End);
+        func.instruction(&WasmInstruction::End);
+
+        func.instruction(&WasmInstruction::I32Const(1));
+        func.instruction(&WasmInstruction::End);
+        func
+    }
+}
+
+/// Scratch locals every generated function reserves after its value locals
+struct ScratchLocals {
+    /// Index of the basic block to run next
+    pc: u32,
+    /// Pointer to an aggregate under construction, or to the array being indexed
+    pointer: u32,
+    /// Array index being bounds-checked
+    index: u32,
 }
 
 impl CodegenBackend for WasmBackend {
@@ -658,7 +1454,6 @@ impl Default for WasmBackend {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{IrBuilder, IrType};
 
     #[test]
     fn test_wasm_backend_creation() {
@@ -760,21 +1555,29 @@ mod tests {
     }
 
     #[test]
-    fn test_constant_folding() {
-        let backend = WasmBackend::new_with_optimization(WasmOptimizationConfig::optimize_for_speed());
-        
-        // Create a simple add instruction with constants
-        let instruction = Instruction {
-            opcode: Opcode::Add,
-            operands: vec![
-                Value::Constant(Constant::Number(5)),
-                Value::Constant(Constant::Number(3)),
-            ],
-            result: None,
-        };
-        
-        let folded = backend.apply_constant_folding(&instruction);
-        assert_eq!(folded, Some(8));
+    fn test_generated_module_validates() {
+        let source = r#"
+            fn greet() {
+                seeAm "hello " + "world";
+            }
+
+            fn main() {
+                greet();
+                mut total = 0;
+                for i in 0..3 {
+                    total = total + i;
+                }
+                if total > 2 {
+                    seeAm "big";
+                } else {
+                    seeAm total;
+                }
+            }
+        "#;
+        let mut compiler = crate::Compiler::new();
+
+        let wasm_bytes = compiler.compile_to_wasm(source).unwrap();
+        assert!(wasmparser::Validator::new().validate_all(&wasm_bytes).is_ok());
     }
 
     #[test]
@@ -0,0 +1,852 @@
+//! Compile-time evaluation of constants
+//!
+//! Runs after type checking, over the HIR of the `const` items and the
+//! `const fn`s they call. Values are HIR literals, so an evaluated constant
+//! is simply a literal initializer by the time the program reaches MIR.
+
+use crate::error::{ConstEvalErrorKind, OvieError, OvieResult};
+use crate::hir::{
+    HirBinaryOp, HirBlock, HirExpression, HirExpressionKind, HirFunction, HirItem, HirLiteral,
+    HirPattern, HirPlaceKind, HirStatement, HirStatementKind, HirType, HirUnaryOp, SourceSpan, Symbol,
+};
+use std::collections::HashMap;
+
+/// Expressions and loop iterations evaluated before evaluation gives up
+const STEP_LIMIT: usize = 1_000_000;
+
+/// Nested `const fn` calls allowed before evaluation gives up
+const CALL_DEPTH_LIMIT: usize = 128;
+
+/// Builtin functions that can be called while evaluating a constant
+const CONST_BUILTINS: &[&str] = &["to_number", "to_int"];
+
+/// Pending work of the evaluator
+///
+/// Evaluation runs off an explicit stack of tasks instead of recursing, so
+/// deeply nested `const fn` calls are bounded by `CALL_DEPTH_LIMIT` rather
+/// than by the native stack of the compiler thread.
+enum Task<'a> {
+    /// Evaluate an expression, pushing its value
+    Expression(&'a HirExpression),
+    /// Run a statement
+    Statement(&'a HirStatement),
+    /// Push a scope and run a block in it
+    EnterBlock(&'a HirBlock),
+    /// Run the statements of an entered block from the given one on, then pop its scope
+    Block(&'a HirBlock, usize),
+    /// Pop the scope holding a `for` loop binding
+    PopScope,
+    /// Combine the values of the operands of a binary operator
+    Binary(&'a HirBinaryOp, SourceSpan),
+    /// Evaluate the right operand of `&&` or `||` unless the left one decides
+    ShortCircuit(&'a HirBinaryOp, &'a HirExpression, SourceSpan),
+    /// Check that the operand of `&&` or `||` at the span is a boolean
+    Condition(SourceSpan),
+    Unary(&'a HirUnaryOp, SourceSpan),
+    Index(SourceSpan),
+    /// Call a function with the given number of evaluated arguments
+    Call(&'a str, usize, SourceSpan),
+    /// End of a `const fn` body; pops its frame
+    Return,
+    /// Bind a value to a new local
+    Local(&'a Symbol),
+    Assign(&'a HirPlaceKind, SourceSpan),
+    /// Combine the current value of a local with the evaluated right-hand side
+    CompoundAssign(&'a Symbol, &'a HirBinaryOp, HirLiteral, SourceSpan),
+    /// Drop the value of an expression statement
+    Discard,
+    /// Leave the innermost call with the evaluated value
+    ReturnValue,
+    /// Run one of the branches of an `if` on the evaluated condition
+    If(&'a HirBlock, Option<&'a HirBlock>, SourceSpan),
+    /// Run the body of a `while` loop if the evaluated condition holds
+    While(&'a HirStatement, SourceSpan),
+    /// End of an iteration of a `while` loop; checks the condition again
+    WhileNext(&'a HirStatement),
+    /// Start a `for` loop over the evaluated bounds of its range
+    For(&'a HirStatement, SourceSpan, SourceSpan),
+    /// Run the next iteration of a `for` loop over `start..end`
+    ForNext(&'a HirStatement, i64, i64),
+}
+
+/// How a `break`, `continue` or `return` leaves the statements around it
+enum Exit {
+    Break(Option<Symbol>),
+    Continue(Option<Symbol>),
+    Return,
+}
+
+/// Evaluates the constants of one program
+pub struct ConstEvaluator<'a> {
+    /// `const fn`s by qualified name
+    functions: HashMap<&'a str, &'a HirFunction>,
+    /// Initializers of the program's constants
+    initializers: HashMap<&'a str, &'a HirExpression>,
+    /// Values of the constants evaluated so far
+    values: HashMap<Symbol, HirLiteral>,
+    /// Constants being evaluated, outermost first
+    in_progress: Vec<Symbol>,
+    /// Block scopes of each `const fn` call being evaluated, innermost last
+    frames: Vec<Vec<HashMap<Symbol, HirLiteral>>>,
+    /// `const fn` whose body is being checked
+    checking: Option<Symbol>,
+    /// Work left for the constant being evaluated, next task last
+    tasks: Vec<Task<'a>>,
+    /// Values of the expressions evaluated and not used yet
+    operands: Vec<HirLiteral>,
+    steps: usize,
+}
+
+impl<'a> ConstEvaluator<'a> {
+    /// Evaluator for the constants and `const fn`s among `items`
+    pub fn new(items: &'a [HirItem]) -> Self {
+        let mut functions = HashMap::new();
+        let mut initializers = HashMap::new();
+        for item in items {
+            match item {
+                HirItem::Function(function) if function.is_const => {
+                    functions.insert(function.name.as_str(), function);
+                }
+                HirItem::Global(global) if global.is_const => {
+                    if let Some(initializer) = &global.initializer {
+                        initializers.insert(global.name.as_str(), initializer);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self {
+            functions,
+            initializers,
+            values: HashMap::new(),
+            in_progress: Vec::new(),
+            frames: Vec::new(),
+            checking: None,
+            tasks: Vec::new(),
+            operands: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    /// Value of the constant `name`, evaluating it and the constants it uses if needed
+    pub fn evaluate(&mut self, name: &str) -> OvieResult<HirLiteral> {
+        if let Some(value) = self.values.get(name) {
+            return Ok(value.clone());
+        }
+        let initializer = self.initializers.get(name).copied().ok_or_else(|| OvieError::kind_error(
+            0, 0,
+            ConstEvalErrorKind::NonConstant,
+            name,
+            format!("'{}' is not a constant", name)
+        ))?;
+
+        self.in_progress.push(name.to_string());
+        // Each constant starts with its own budget and no enclosing calls
+        let outer_frames = std::mem::take(&mut self.frames);
+        let outer_tasks = std::mem::replace(&mut self.tasks, vec![Task::Expression(initializer)]);
+        let outer_operands = std::mem::take(&mut self.operands);
+        let outer_steps = std::mem::replace(&mut self.steps, 0);
+        let value = self.run().map(|_| self.operand());
+        self.frames = outer_frames;
+        self.tasks = outer_tasks;
+        self.operands = outer_operands;
+        self.steps = outer_steps;
+        self.in_progress.pop();
+
+        let value = value?;
+        self.values.insert(name.to_string(), value.clone());
+        Ok(value)
+    }
+
+    /// Check that the body of a `const fn` only uses what can be evaluated at compile time
+    pub fn check_function(&mut self, function: &HirFunction) -> OvieResult<()> {
+        self.checking = Some(function.name.clone());
+        let checked = self.check_signature(function).and_then(|_| self.check_block(&function.body));
+        self.checking = None;
+        checked
+    }
+
+    fn check_signature(&self, function: &HirFunction) -> OvieResult<()> {
+        for parameter in &function.parameters {
+            if !is_const_type(&parameter.param_type) {
+                return Err(self.error(
+                    parameter.span,
+                    ConstEvalErrorKind::NonConstant,
+                    format!("Parameter '{}' of const fn '{}' has type {}; only Int, Number, String and Boolean values exist at compile time",
+                        parameter.name, function.name, parameter.param_type)
+                ));
+            }
+        }
+        if !is_const_type(&function.return_type) {
+            return Err(self.error(
+                function.span,
+                ConstEvalErrorKind::NonConstant,
+                format!("const fn '{}' returns {}; only Int, Number, String and Boolean values exist at compile time",
+                    function.name, function.return_type)
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_block(&self, block: &HirBlock) -> OvieResult<()> {
+        block.statements.iter().try_for_each(|statement| self.check_statement(statement))
+    }
+
+    fn check_statement(&self, statement: &HirStatement) -> OvieResult<()> {
+        match &statement.kind {
+            HirStatementKind::Local { name, var_type, initializer, .. } => {
+                if !is_const_type(var_type) {
+                    return Err(self.error(
+                        statement.span,
+                        ConstEvalErrorKind::NonConstant,
+                        format!("Variable '{}' has type {}, which cannot be used in a const fn", name, var_type)
+                    ));
+                }
+                initializer.iter().try_for_each(|value| self.check_expression(value))
+            }
+            HirStatementKind::Assign { target, value } | HirStatementKind::CompoundAssign { target, value, .. } => {
+                if !matches!(target.kind, HirPlaceKind::Local(_)) {
+                    return Err(self.error(statement.span, ConstEvalErrorKind::NonConstant, format!("Assignment to '{}' cannot be evaluated at compile time", target)));
+                }
+                self.check_expression(value)
+            }
+            HirStatementKind::Expression(value) | HirStatementKind::Return(Some(value)) => self.check_expression(value),
+            HirStatementKind::Return(None) | HirStatementKind::Break { .. } | HirStatementKind::Continue { .. } => Ok(()),
+            HirStatementKind::If { condition, then_block, else_block } => {
+                self.check_expression(condition)?;
+                self.check_block(then_block)?;
+                else_block.iter().try_for_each(|block| self.check_block(block))
+            }
+            HirStatementKind::While { condition, body, .. } => {
+                self.check_expression(condition)?;
+                self.check_block(body)
+            }
+            HirStatementKind::For { pattern: HirPattern::Binding(_) | HirPattern::Wildcard, iterable: HirExpression { kind: HirExpressionKind::Range { start, end }, .. }, body, .. } => {
+                self.check_expression(start)?;
+                self.check_expression(end)?;
+                self.check_block(body)
+            }
+            _ => Err(self.error(
+                statement.span,
+                ConstEvalErrorKind::NonConstant,
+                format!("{} cannot be evaluated at compile time", describe_statement(&statement.kind))
+            )),
+        }
+    }
+
+    fn check_expression(&self, expression: &HirExpression) -> OvieResult<()> {
+        match &expression.kind {
+            HirExpressionKind::Literal(_) | HirExpressionKind::Variable(_) => Ok(()),
+            HirExpressionKind::Binary { left, right, .. } | HirExpressionKind::Index { object: left, index: right } => {
+                self.check_expression(left)?;
+                self.check_expression(right)
+            }
+            HirExpressionKind::Unary { operand, .. } => self.check_expression(operand),
+            HirExpressionKind::Call { function, arguments, .. } => {
+                self.check_callee(function, expression.span)?;
+                arguments.iter().try_for_each(|argument| self.check_expression(argument))
+            }
+            kind => Err(self.error(
+                expression.span,
+                ConstEvalErrorKind::NonConstant,
+                format!("{} cannot be evaluated at compile time", describe_expression(kind))
+            )),
+        }
+    }
+
+    /// Only `const fn`s and the conversion builtins can be called at compile time
+    fn check_callee(&self, function: &str, span: SourceSpan) -> OvieResult<()> {
+        if self.functions.contains_key(function) || CONST_BUILTINS.contains(&function) {
+            return Ok(());
+        }
+        Err(self.error(
+            span,
+            ConstEvalErrorKind::NonConstant,
+            format!("Call to '{}' cannot be evaluated at compile time; only 'const fn' functions can be called in a constant", function)
+        ))
+    }
+
+    /// Perform tasks until the ones pushed for the current constant are done
+    fn run(&mut self) -> OvieResult<()> {
+        while let Some(task) = self.tasks.pop() {
+            self.perform(task)?;
+        }
+        Ok(())
+    }
+
+    fn perform(&mut self, task: Task<'a>) -> OvieResult<()> {
+        match task {
+            Task::Expression(expression) => self.expression(expression)?,
+            Task::Statement(statement) => self.statement(statement)?,
+            Task::EnterBlock(block) => {
+                self.scopes().push(HashMap::new());
+                self.tasks.push(Task::Block(block, 0));
+            }
+            Task::Block(block, next) => match block.statements.get(next) {
+                Some(statement) => {
+                    self.tasks.push(Task::Block(block, next + 1));
+                    self.tasks.push(Task::Statement(statement));
+                }
+                None => {
+                    self.scopes().pop();
+                }
+            },
+            Task::PopScope => {
+                self.scopes().pop();
+            }
+            Task::Binary(op, span) => {
+                let right = self.operand();
+                let left = self.operand();
+                let value = self.binary(left, op, right, span)?;
+                self.operands.push(value);
+            }
+            Task::ShortCircuit(op, right, span) => {
+                let left = self.operand();
+                let left = self.boolean(left, span)?;
+                match op {
+                    HirBinaryOp::And if !left => self.operands.push(HirLiteral::Boolean(false)),
+                    HirBinaryOp::Or if left => self.operands.push(HirLiteral::Boolean(true)),
+                    _ => {
+                        self.tasks.push(Task::Condition(right.span));
+                        self.tasks.push(Task::Expression(right));
+                    }
+                }
+            }
+            Task::Condition(span) => {
+                let value = self.operand();
+                let value = self.boolean(value, span)?;
+                self.operands.push(HirLiteral::Boolean(value));
+            }
+            Task::Unary(op, span) => {
+                let value = match (op, self.operand()) {
+                    (HirUnaryOp::Not, HirLiteral::Boolean(b)) => HirLiteral::Boolean(!b),
+                    (HirUnaryOp::Neg, HirLiteral::Number(n)) => HirLiteral::Number(-n),
+                    (HirUnaryOp::Neg, HirLiteral::Integer(n)) => n.checked_neg().map(HirLiteral::Integer)
+                        .ok_or_else(|| self.error(span, ConstEvalErrorKind::IntegerOverflow, format!("Negating {} overflows Int", n)))?,
+                    (_, value) => return Err(self.mismatch(span, &value)),
+                };
+                self.operands.push(value);
+            }
+            Task::Index(span) => {
+                let index = self.operand();
+                let object = self.operand();
+                let value = self.index(object, index, span)?;
+                self.operands.push(value);
+            }
+            Task::Call(function, count, span) => {
+                let arguments = self.operands.split_off(self.operands.len() - count);
+                self.call(function, arguments, span)?;
+            }
+            Task::Return => {
+                self.frames.pop();
+                self.operands.push(HirLiteral::Unit);
+            }
+            Task::Local(name) => {
+                let value = self.operand();
+                self.scopes().last_mut().expect("a const fn body has a scope").insert(name.clone(), value);
+            }
+            Task::Assign(target, span) => {
+                let value = self.operand();
+                self.assign(target, span, value)?;
+            }
+            Task::CompoundAssign(name, op, current, span) => {
+                let value = self.operand();
+                let value = self.binary(current, op, value, span)?;
+                self.assign(&HirPlaceKind::Local(name.clone()), span, value)?;
+            }
+            Task::Discard => {
+                self.operand();
+            }
+            Task::ReturnValue => self.unwind(Exit::Return),
+            Task::If(then_block, else_block, span) => {
+                let condition = self.operand();
+                if self.boolean(condition, span)? {
+                    self.tasks.push(Task::EnterBlock(then_block));
+                } else if let Some(else_block) = else_block {
+                    self.tasks.push(Task::EnterBlock(else_block));
+                }
+            }
+            Task::While(statement, span) => {
+                let condition = self.operand();
+                if self.boolean(condition, span)? {
+                    let HirStatementKind::While { body, .. } = &statement.kind else {
+                        unreachable!("While tasks are pushed for while loops");
+                    };
+                    self.step(statement.span)?;
+                    self.tasks.push(Task::WhileNext(statement));
+                    self.tasks.push(Task::EnterBlock(body));
+                }
+            }
+            Task::WhileNext(statement) => self.tasks.push(Task::Statement(statement)),
+            Task::For(statement, start_span, end_span) => {
+                let end = self.operand();
+                let end = self.integer(end, end_span)?;
+                let start = self.operand();
+                let start = self.integer(start, start_span)?;
+                self.tasks.push(Task::ForNext(statement, start, end));
+            }
+            Task::ForNext(statement, value, end) => {
+                let HirStatementKind::For { pattern, body, .. } = &statement.kind else {
+                    unreachable!("ForNext tasks are pushed for for loops");
+                };
+                if value < end {
+                    self.step(statement.span)?;
+                    self.tasks.push(Task::ForNext(statement, value + 1, end));
+                    let mut scope = HashMap::new();
+                    if let HirPattern::Binding(name) = pattern {
+                        scope.insert(name.clone(), HirLiteral::Integer(value));
+                    }
+                    self.scopes().push(scope);
+                    self.tasks.push(Task::PopScope);
+                    self.tasks.push(Task::EnterBlock(body));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &'a HirStatement) -> OvieResult<()> {
+        match &statement.kind {
+            HirStatementKind::Local { name, initializer, .. } => {
+                self.tasks.push(Task::Local(name));
+                self.value_of(initializer.as_ref());
+            }
+            HirStatementKind::Assign { target, value } => {
+                self.tasks.push(Task::Assign(&target.kind, statement.span));
+                self.tasks.push(Task::Expression(value));
+            }
+            HirStatementKind::CompoundAssign { target, op, value } => {
+                let HirPlaceKind::Local(name) = &target.kind else {
+                    return Err(self.non_constant(statement.span, &format!("Assignment to '{}'", target)));
+                };
+                let current = self.variable(name, statement.span)?;
+                self.tasks.push(Task::CompoundAssign(name, op, current, statement.span));
+                self.tasks.push(Task::Expression(value));
+            }
+            HirStatementKind::Expression(value) => {
+                self.tasks.push(Task::Discard);
+                self.tasks.push(Task::Expression(value));
+            }
+            HirStatementKind::Return(value) => {
+                self.tasks.push(Task::ReturnValue);
+                self.value_of(value.as_ref());
+            }
+            HirStatementKind::If { condition, then_block, else_block } => {
+                self.tasks.push(Task::If(then_block, else_block.as_ref(), condition.span));
+                self.tasks.push(Task::Expression(condition));
+            }
+            HirStatementKind::While { condition, .. } => {
+                self.tasks.push(Task::While(statement, condition.span));
+                self.tasks.push(Task::Expression(condition));
+            }
+            HirStatementKind::For { iterable: HirExpression { kind: HirExpressionKind::Range { start, end }, .. }, .. } => {
+                self.tasks.push(Task::For(statement, start.span, end.span));
+                self.tasks.push(Task::Expression(end));
+                self.tasks.push(Task::Expression(start));
+            }
+            HirStatementKind::Break { label } => self.unwind(Exit::Break(label.clone())),
+            HirStatementKind::Continue { label } => self.unwind(Exit::Continue(label.clone())),
+            kind => return Err(self.non_constant(statement.span, &describe_statement(kind))),
+        }
+        Ok(())
+    }
+
+    /// Push the value of an optional expression, or Unit without one
+    fn value_of(&mut self, value: Option<&'a HirExpression>) {
+        match value {
+            Some(value) => self.tasks.push(Task::Expression(value)),
+            None => self.operands.push(HirLiteral::Unit),
+        }
+    }
+
+    /// Drop the tasks a `break`, `continue` or `return` skips, popping the scopes they entered
+    fn unwind(&mut self, exit: Exit) {
+        while let Some(task) = self.tasks.pop() {
+            match task {
+                Task::Block(..) | Task::PopScope => {
+                    self.scopes().pop();
+                }
+                Task::WhileNext(statement) | Task::ForNext(statement, ..) => {
+                    let (HirStatementKind::While { label, .. } | HirStatementKind::For { label, .. }) = &statement.kind else {
+                        unreachable!("loop tasks are pushed for loops");
+                    };
+                    match &exit {
+                        Exit::Break(target) if target.is_none() || target == label => return,
+                        Exit::Continue(target) if target.is_none() || target == label => {
+                            self.tasks.push(task);
+                            return;
+                        }
+                        _ => {}
+                    }
+                }
+                Task::Return => {
+                    self.frames.pop();
+                    // The value of `return` is on top; anything else leaves the call with Unit
+                    if !matches!(exit, Exit::Return) {
+                        self.operands.push(HirLiteral::Unit);
+                    }
+                    return;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn expression(&mut self, expression: &'a HirExpression) -> OvieResult<()> {
+        self.step(expression.span)?;
+        let span = expression.span;
+        match &expression.kind {
+            HirExpressionKind::Literal(literal) => self.operands.push(literal.clone()),
+            HirExpressionKind::Variable(name) => {
+                let value = self.variable(name, span)?;
+                self.operands.push(value);
+            }
+            HirExpressionKind::Binary { left, op: op @ (HirBinaryOp::And | HirBinaryOp::Or), right } => {
+                self.tasks.push(Task::ShortCircuit(op, right, left.span));
+                self.tasks.push(Task::Expression(left));
+            }
+            HirExpressionKind::Binary { left, op, right } => {
+                self.tasks.push(Task::Binary(op, span));
+                self.tasks.push(Task::Expression(right));
+                self.tasks.push(Task::Expression(left));
+            }
+            HirExpressionKind::Unary { op, operand } => {
+                self.tasks.push(Task::Unary(op, span));
+                self.tasks.push(Task::Expression(operand));
+            }
+            HirExpressionKind::Index { object, index } => {
+                self.tasks.push(Task::Index(span));
+                self.tasks.push(Task::Expression(index));
+                self.tasks.push(Task::Expression(object));
+            }
+            HirExpressionKind::Call { function, arguments, .. } => {
+                self.tasks.push(Task::Call(function, arguments.len(), span));
+                self.tasks.extend(arguments.iter().rev().map(Task::Expression));
+            }
+            kind => return Err(self.non_constant(span, &describe_expression(kind))),
+        }
+        Ok(())
+    }
+
+    fn index(&self, object: HirLiteral, index: HirLiteral, span: SourceSpan) -> OvieResult<HirLiteral> {
+        let HirLiteral::String(s) = object else {
+            return Err(self.mismatch(span, &object));
+        };
+        let position = match index {
+            HirLiteral::Integer(n) => n,
+            HirLiteral::Number(n) if n.fract() == 0.0 => n as i64,
+            other => return Err(self.mismatch(span, &other)),
+        };
+        usize::try_from(position).ok()
+            .and_then(|position| s.chars().nth(position))
+            .map(|c| HirLiteral::String(c.to_string()))
+            .ok_or_else(|| self.error(
+                span,
+                ConstEvalErrorKind::IndexOutOfBounds,
+                format!("Index {} is out of bounds for a string of length {}", position, s.chars().count())
+            ))
+    }
+
+    /// Push the value of a builtin call, or enter the body of a `const fn`
+    fn call(&mut self, function: &str, arguments: Vec<HirLiteral>, span: SourceSpan) -> OvieResult<()> {
+        let value = match (function, arguments.as_slice()) {
+            ("to_number", [HirLiteral::Integer(n)]) => Some(HirLiteral::Number(*n as f64)),
+            ("to_number", [HirLiteral::Number(n)]) => Some(HirLiteral::Number(*n)),
+            ("to_int", [HirLiteral::Integer(n)]) => Some(HirLiteral::Integer(*n)),
+            // Truncates toward zero, like the conversion at run time
+            ("to_int", [HirLiteral::Number(n)]) => {
+                if n.is_nan() || *n < i64::MIN as f64 || *n >= i64::MAX as f64 {
+                    return Err(self.error(span, ConstEvalErrorKind::IntegerOverflow, format!("Cannot convert {} to Int", n)));
+                }
+                Some(HirLiteral::Integer(n.trunc() as i64))
+            }
+            _ => None,
+        };
+        if let Some(value) = value {
+            self.operands.push(value);
+            return Ok(());
+        }
+
+        self.check_callee(function, span)?;
+        let Some(&callee) = self.functions.get(function) else {
+            return Err(OvieError::compile_error(format!("Unexpected arguments to '{}' while evaluating a constant", function)));
+        };
+        if self.frames.len() >= CALL_DEPTH_LIMIT {
+            return Err(self.error(
+                span,
+                ConstEvalErrorKind::LimitExceeded,
+                format!("Calls nest more than {} deep while evaluating a constant", CALL_DEPTH_LIMIT)
+            ));
+        }
+
+        let parameters = callee.parameters.iter().map(|parameter| parameter.name.clone()).zip(arguments).collect();
+        self.frames.push(vec![parameters]);
+        self.tasks.push(Task::Return);
+        self.tasks.push(Task::EnterBlock(&callee.body));
+        Ok(())
+    }
+
+    fn binary(&self, left: HirLiteral, op: &HirBinaryOp, right: HirLiteral, span: SourceSpan) -> OvieResult<HirLiteral> {
+        use HirLiteral::*;
+        let overflow = || self.error(span, ConstEvalErrorKind::IntegerOverflow, format!("{} {} {} overflows Int", literal_text(&left), operator_text(op), literal_text(&right)));
+        let value = match (&left, op, &right) {
+            (Integer(_), HirBinaryOp::Div | HirBinaryOp::Mod, Integer(0)) => {
+                return Err(self.error(span, ConstEvalErrorKind::DivisionByZero, "Int division by zero"));
+            }
+            (Integer(a), HirBinaryOp::Add, Integer(b)) => Integer(a.checked_add(*b).ok_or_else(overflow)?),
+            (Integer(a), HirBinaryOp::Sub, Integer(b)) => Integer(a.checked_sub(*b).ok_or_else(overflow)?),
+            (Integer(a), HirBinaryOp::Mul, Integer(b)) => Integer(a.checked_mul(*b).ok_or_else(overflow)?),
+            (Integer(a), HirBinaryOp::Div, Integer(b)) => Integer(a.checked_div(*b).ok_or_else(overflow)?),
+            (Integer(a), HirBinaryOp::Mod, Integer(b)) => Integer(a.checked_rem(*b).ok_or_else(overflow)?),
+            (Number(a), HirBinaryOp::Add, Number(b)) => Number(a + b),
+            (Number(a), HirBinaryOp::Sub, Number(b)) => Number(a - b),
+            (Number(a), HirBinaryOp::Mul, Number(b)) => Number(a * b),
+            (Number(a), HirBinaryOp::Div, Number(b)) => Number(a / b),
+            (Number(a), HirBinaryOp::Mod, Number(b)) => Number(a % b),
+            (String(a), HirBinaryOp::Add, String(b)) => String(format!("{}{}", a, b)),
+            (Integer(a), HirBinaryOp::Lt, Integer(b)) => Boolean(a < b),
+            (Integer(a), HirBinaryOp::Le, Integer(b)) => Boolean(a <= b),
+            (Integer(a), HirBinaryOp::Gt, Integer(b)) => Boolean(a > b),
+            (Integer(a), HirBinaryOp::Ge, Integer(b)) => Boolean(a >= b),
+            (Number(a), HirBinaryOp::Lt, Number(b)) => Boolean(a < b),
+            (Number(a), HirBinaryOp::Le, Number(b)) => Boolean(a <= b),
+            (Number(a), HirBinaryOp::Gt, Number(b)) => Boolean(a > b),
+            (Number(a), HirBinaryOp::Ge, Number(b)) => Boolean(a >= b),
+            (_, HirBinaryOp::Eq, _) => Boolean(self.equal(&left, &right, span)?),
+            (_, HirBinaryOp::Ne, _) => Boolean(!self.equal(&left, &right, span)?),
+            _ => return Err(self.mismatch(span, &left)),
+        };
+        Ok(value)
+    }
+
+    fn equal(&self, left: &HirLiteral, right: &HirLiteral, span: SourceSpan) -> OvieResult<bool> {
+        match (left, right) {
+            (HirLiteral::Integer(a), HirLiteral::Integer(b)) => Ok(a == b),
+            (HirLiteral::Number(a), HirLiteral::Number(b)) => Ok(a == b),
+            (HirLiteral::String(a), HirLiteral::String(b)) => Ok(a == b),
+            (HirLiteral::Boolean(a), HirLiteral::Boolean(b)) => Ok(a == b),
+            (HirLiteral::Unit, HirLiteral::Unit) => Ok(true),
+            _ => Err(self.mismatch(span, left)),
+        }
+    }
+
+    /// Value of a local of the `const fn` being evaluated, or of another constant
+    fn variable(&mut self, name: &str, span: SourceSpan) -> OvieResult<HirLiteral> {
+        let local = self.frames.last()
+            .and_then(|scopes| scopes.iter().rev().find_map(|scope| scope.get(name)));
+        if let Some(value) = local {
+            return Ok(value.clone());
+        }
+        if !self.initializers.contains_key(name) {
+            return Err(self.non_constant(span, &format!("Use of '{}'", name)));
+        }
+        if self.in_progress.iter().any(|constant| constant == name) {
+            let cycle: Vec<_> = self.in_progress.iter()
+                .skip_while(|constant| *constant != name)
+                .map(String::as_str)
+                .chain(std::iter::once(name))
+                .collect();
+            return Err(self.error(
+                span,
+                ConstEvalErrorKind::CircularDependency,
+                format!("Constant '{}' depends on itself: {}", name, cycle.join(" -> "))
+            ));
+        }
+        self.evaluate(name)
+            .map_err(|e| e.located_at(span.start_position()))
+    }
+
+    fn assign(&mut self, target: &HirPlaceKind, span: SourceSpan, value: HirLiteral) -> OvieResult<()> {
+        let HirPlaceKind::Local(name) = target else {
+            return Err(self.non_constant(span, "Assignment to a field or element"));
+        };
+        let slot = self.scopes().iter_mut().rev().find_map(|scope| scope.get_mut(name));
+        match slot {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(self.non_constant(span, &format!("Assignment to '{}'", name))),
+        }
+    }
+
+    fn boolean(&self, value: HirLiteral, span: SourceSpan) -> OvieResult<bool> {
+        match value {
+            HirLiteral::Boolean(b) => Ok(b),
+            other => Err(self.mismatch(span, &other)),
+        }
+    }
+
+    fn integer(&self, value: HirLiteral, span: SourceSpan) -> OvieResult<i64> {
+        match value {
+            HirLiteral::Integer(n) => Ok(n),
+            other => Err(self.mismatch(span, &other)),
+        }
+    }
+
+    /// Value left by the last evaluated expression
+    fn operand(&mut self) -> HirLiteral {
+        self.operands.pop().expect("an evaluated expression left its value")
+    }
+
+    /// Block scopes of the innermost call
+    fn scopes(&mut self) -> &mut Vec<HashMap<Symbol, HirLiteral>> {
+        if self.frames.is_empty() {
+            self.frames.push(Vec::new());
+        }
+        self.frames.last_mut().expect("a frame was just pushed")
+    }
+
+    fn step(&mut self, span: SourceSpan) -> OvieResult<()> {
+        self.steps += 1;
+        if self.steps > STEP_LIMIT {
+            return Err(self.error(
+                span,
+                ConstEvalErrorKind::LimitExceeded,
+                format!("Evaluation took more than {} steps; constants must finish quickly", STEP_LIMIT)
+            ));
+        }
+        Ok(())
+    }
+
+    fn non_constant(&self, span: SourceSpan, what: &str) -> OvieError {
+        self.error(span, ConstEvalErrorKind::NonConstant, format!("{} cannot be evaluated at compile time", what))
+    }
+
+    /// Type checking rules out mismatched operands; reaching one is a compiler bug
+    fn mismatch(&self, span: SourceSpan, value: &HirLiteral) -> OvieError {
+        OvieError::compile_error(format!("Unexpected value {:?} while evaluating a constant at {}:{}", value, span.line, span.column))
+    }
+
+    fn error(&self, span: SourceSpan, kind: ConstEvalErrorKind, message: impl Into<String>) -> OvieError {
+        let operation = self.in_progress.first().or(self.checking.as_ref()).map(String::as_str).unwrap_or_default();
+        OvieError::kind_error(span.line, span.column, kind, operation, message)
+    }
+}
+
+fn literal_text(literal: &HirLiteral) -> String {
+    match literal {
+        HirLiteral::String(s) => format!("{:?}", s),
+        HirLiteral::Number(n) => n.to_string(),
+        HirLiteral::Integer(n) => n.to_string(),
+        HirLiteral::Boolean(b) => b.to_string(),
+        HirLiteral::Unit => "()".to_string(),
+    }
+}
+
+fn operator_text(op: &HirBinaryOp) -> &'static str {
+    match op {
+        HirBinaryOp::Add => "+",
+        HirBinaryOp::Sub => "-",
+        HirBinaryOp::Mul => "*",
+        HirBinaryOp::Div => "/",
+        HirBinaryOp::Mod => "%",
+        HirBinaryOp::Eq => "==",
+        HirBinaryOp::Ne => "!=",
+        HirBinaryOp::Lt => "<",
+        HirBinaryOp::Le => "<=",
+        HirBinaryOp::Gt => ">",
+        HirBinaryOp::Ge => ">=",
+        HirBinaryOp::And => "&&",
+        HirBinaryOp::Or => "||",
+    }
+}
+
+/// Types whose values can be computed at compile time
+pub fn is_const_type(ty: &HirType) -> bool {
+    matches!(ty, HirType::Int | HirType::Number | HirType::String | HirType::Boolean)
+}
+
+fn describe_statement(kind: &HirStatementKind) -> String {
+    match kind {
+        HirStatementKind::Print(_) => "Printing",
+        HirStatementKind::Destructure { .. } => "Destructuring",
+        HirStatementKind::Unsafe { .. } => "An unsafe block",
+        HirStatementKind::For { .. } => "A loop over anything but an Int range",
+        _ => "This statement",
+    }.to_string()
+}
+
+fn describe_expression(kind: &HirExpressionKind) -> String {
+    match kind {
+        HirExpressionKind::FieldAccess { .. } => "Field access",
+        HirExpressionKind::StructInit { .. } => "A struct",
+        HirExpressionKind::Range { .. } => "A range",
+        HirExpressionKind::EnumVariant { .. } => "An enum variant",
+        HirExpressionKind::ArrayLiteral { .. } => "An array",
+        HirExpressionKind::Tuple { .. } => "A tuple",
+        HirExpressionKind::Match { .. } => "A match expression",
+        HirExpressionKind::Closure { .. } => "A closure",
+        HirExpressionKind::IndirectCall { .. } => "A call through a function value",
+        HirExpressionKind::Try { .. } => "Error propagation with '?'",
+        _ => "This expression",
+    }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hir::HirBuilder;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    /// Value the program `source` gives its constant `X`
+    fn evaluate(source: &str) -> OvieResult<HirLiteral> {
+        let tokens = Lexer::new(source).tokenize()?;
+        let ast = Parser::new(tokens).parse()?;
+        let hir = HirBuilder::new().transform_ast(&ast)?;
+        hir.items.into_iter()
+            .find_map(|item| match item {
+                HirItem::Global(global) if global.name == "X" => global.initializer,
+                _ => None,
+            })
+            .and_then(|initializer| match initializer.kind {
+                HirExpressionKind::Literal(value) => Some(value),
+                _ => None,
+            })
+            .ok_or_else(|| OvieError::compile_error("X was not evaluated"))
+    }
+
+    const DEPTH: &str = "const fn depth(n: Int) -> Int {\n\
+        if n == 0 { return 0 }\n\
+        return depth(n - 1) + 1\n\
+        }\n";
+
+    const SPIN: &str = "const fn spin(n: Int) -> Int {\n\
+        mut total = 0\n\
+        for i in 0..n { total += 1 }\n\
+        return total\n\
+        }\n";
+
+    #[test]
+    fn test_calls_nest_up_to_the_depth_limit() {
+        // depth(n) runs in n + 1 nested frames
+        let limit = CALL_DEPTH_LIMIT as i64;
+        let source = format!("{}const X: Int = depth({})", DEPTH, limit - 1);
+        assert!(matches!(evaluate(&source).unwrap(), HirLiteral::Integer(n) if n == limit - 1));
+
+        let source = format!("{}const X: Int = depth({})", DEPTH, limit);
+        let diagnostic = evaluate(&source).unwrap_err().to_diagnostic();
+        assert_eq!(diagnostic.code, "E4006");
+        assert!(diagnostic.message.contains(&format!("more than {} deep", CALL_DEPTH_LIMIT)), "{}", diagnostic.message);
+    }
+
+    #[test]
+    fn test_step_limit_stops_endless_loops() {
+        let source = "const fn forever() -> Int { mut n = 0\nwhile true { n += 1 }\nreturn n }\nconst X: Int = forever()";
+        let diagnostic = evaluate(source).unwrap_err().to_diagnostic();
+        assert_eq!(diagnostic.code, "E4006");
+        assert!(diagnostic.message.contains(&format!("more than {} steps", STEP_LIMIT)), "{}", diagnostic.message);
+    }
+
+    #[test]
+    fn test_each_constant_has_its_own_step_budget() {
+        // An iteration of the loop is two steps, so each call takes two thirds of the budget
+        let iterations = STEP_LIMIT / 3;
+        let source = format!("{}const A: Int = spin({})\nconst X: Int = spin({}) + A", SPIN, iterations, iterations);
+        assert!(matches!(evaluate(&source).unwrap(), HirLiteral::Integer(n) if n == 2 * iterations as i64));
+
+        let source = format!("{}const X: Int = spin({})", SPIN, 2 * iterations);
+        assert_eq!(evaluate(&source).unwrap_err().to_diagnostic().code, "E4006");
+    }
+}
@@ -0,0 +1,801 @@
+//! Dataflow analysis of MIR
+//!
+//! An analysis describes what is known at each point of a function as a value of
+//! a lattice, and how each statement and terminator changes it. `solve` runs it
+//! over the control flow graph with a worklist, forward from the entry block or
+//! backward from the blocks that return, joining the states of paths where they
+//! meet, until no block's state changes.
+//!
+//! Liveness, reaching definitions, definite initialization and constant
+//! propagation are provided; the borrow checker is built on the same framework.
+
+use crate::mir::{
+    BasicBlockId, FunctionId, LocalId, MirBasicBlock, MirBinOp, MirConstantValue, MirFunction, MirOperand,
+    MirPlace, MirProjectionElem, MirRvalue, MirStatement, MirStatementKind, MirTerminator, MirUnOp,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+/// Direction facts flow through the control flow graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the entry block along the edges, like initialization
+    Forward,
+    /// From the returns back against the edges, like liveness
+    Backward,
+}
+
+/// States ordered by what they allow, with the least state allowing both of two
+pub trait JoinSemiLattice: Clone + PartialEq {
+    /// Join `other` into `self`, returning whether `self` changed
+    fn join(&mut self, other: &Self) -> bool;
+}
+
+/// Sets grow by union: a fact holds where it holds on any path
+impl<T: Ord + Clone> JoinSemiLattice for BTreeSet<T> {
+    fn join(&mut self, other: &Self) -> bool {
+        let before = self.len();
+        self.extend(other.iter().cloned());
+        self.len() != before
+    }
+}
+
+/// A point of a function: before statement `statement_index` of `block`, or
+/// before its terminator when the index is the number of statements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Location {
+    pub block: BasicBlockId,
+    pub statement_index: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "bb{}[{}]", self.block, self.statement_index)
+    }
+}
+
+/// A dataflow analysis: its lattice, direction, and transfer functions
+pub trait Analysis {
+    type Domain: JoinSemiLattice;
+
+    const DIRECTION: Direction;
+
+    /// State of a block no path has reached yet
+    fn bottom(&self, function: &MirFunction) -> Self::Domain;
+
+    /// State on entry to the function, or on return from it for a backward analysis
+    fn boundary(&self, function: &MirFunction) -> Self::Domain;
+
+    /// Carry `state` across `statement` at `location`, in the analysis' direction
+    fn apply_statement(&self, state: &mut Self::Domain, statement: &MirStatement, location: Location);
+
+    /// Carry `state` across the terminator of `block` at `location`, in the analysis' direction
+    fn apply_terminator(&self, state: &mut Self::Domain, block: &MirBasicBlock, location: Location);
+}
+
+/// Fixed point of an analysis over one function
+pub struct Results<A: Analysis> {
+    pub analysis: A,
+    /// State at the start of each block, in program order
+    pub entry: HashMap<BasicBlockId, A::Domain>,
+    /// State at the end of each block, after its terminator, in program order
+    pub exit: HashMap<BasicBlockId, A::Domain>,
+}
+
+impl<A: Analysis> Results<A> {
+    /// State at each point of a block: before each of its statements, then
+    /// before its terminator
+    pub fn states_in_block(&self, function: &MirFunction, block_id: BasicBlockId) -> Vec<A::Domain> {
+        let block = &function.basic_blocks[&block_id];
+        let terminator = Location { block: block_id, statement_index: block.statements.len() };
+        let mut states = Vec::with_capacity(block.statements.len() + 1);
+        match A::DIRECTION {
+            Direction::Forward => {
+                let mut state = self.entry[&block_id].clone();
+                for (index, statement) in block.statements.iter().enumerate() {
+                    states.push(state.clone());
+                    self.analysis.apply_statement(&mut state, statement, Location { block: block_id, statement_index: index });
+                }
+                states.push(state);
+            }
+            Direction::Backward => {
+                let mut state = self.exit[&block_id].clone();
+                self.analysis.apply_terminator(&mut state, block, terminator);
+                states.push(state.clone());
+                for (index, statement) in block.statements.iter().enumerate().rev() {
+                    self.analysis.apply_statement(&mut state, statement, Location { block: block_id, statement_index: index });
+                    states.push(state.clone());
+                }
+                states.reverse();
+            }
+        }
+        states
+    }
+}
+
+/// Solve `analysis` over `function` to a fixed point
+pub fn solve<A: Analysis>(analysis: A, function: &MirFunction) -> Results<A> {
+    let mut blocks: Vec<BasicBlockId> = function.basic_blocks.keys().copied().collect();
+    blocks.sort();
+    let mut predecessors: HashMap<BasicBlockId, Vec<BasicBlockId>> = HashMap::new();
+    for &block_id in &blocks {
+        for successor in function.basic_blocks[&block_id].terminator.successors() {
+            predecessors.entry(successor).or_default().push(block_id);
+        }
+    }
+
+    let bottom = analysis.bottom(function);
+    let mut entry: HashMap<BasicBlockId, A::Domain> = blocks.iter().map(|&block_id| (block_id, bottom.clone())).collect();
+    let mut exit = entry.clone();
+    match A::DIRECTION {
+        Direction::Forward => {
+            if let Some(state) = entry.get_mut(&function.entry_block) {
+                *state = analysis.boundary(function);
+            }
+        }
+        Direction::Backward => {
+            for &block_id in &blocks {
+                if function.basic_blocks[&block_id].terminator.successors().is_empty() {
+                    exit.insert(block_id, analysis.boundary(function));
+                }
+            }
+        }
+    }
+
+    // Every block is visited once; after that, only those whose input changed
+    let mut worklist: VecDeque<BasicBlockId> = match A::DIRECTION {
+        Direction::Forward => blocks.iter().copied().collect(),
+        Direction::Backward => blocks.iter().rev().copied().collect(),
+    };
+    let mut queued: BTreeSet<BasicBlockId> = blocks.iter().copied().collect();
+    while let Some(block_id) = worklist.pop_front() {
+        queued.remove(&block_id);
+        let block = &function.basic_blocks[&block_id];
+        let terminator = Location { block: block_id, statement_index: block.statements.len() };
+        match A::DIRECTION {
+            Direction::Forward => {
+                let mut state = entry[&block_id].clone();
+                for (index, statement) in block.statements.iter().enumerate() {
+                    analysis.apply_statement(&mut state, statement, Location { block: block_id, statement_index: index });
+                }
+                analysis.apply_terminator(&mut state, block, terminator);
+                for successor in block.terminator.successors() {
+                    if let Some(successor_entry) = entry.get_mut(&successor) {
+                        if successor_entry.join(&state) && queued.insert(successor) {
+                            worklist.push_back(successor);
+                        }
+                    }
+                }
+                exit.insert(block_id, state);
+            }
+            Direction::Backward => {
+                let mut state = exit[&block_id].clone();
+                analysis.apply_terminator(&mut state, block, terminator);
+                for (index, statement) in block.statements.iter().enumerate().rev() {
+                    analysis.apply_statement(&mut state, statement, Location { block: block_id, statement_index: index });
+                }
+                for &predecessor in predecessors.get(&block_id).into_iter().flatten() {
+                    if exit.get_mut(&predecessor).is_some_and(|predecessor_exit| predecessor_exit.join(&state))
+                        && queued.insert(predecessor)
+                    {
+                        worklist.push_back(predecessor);
+                    }
+                }
+                entry.insert(block_id, state);
+            }
+        }
+    }
+
+    Results { analysis, entry, exit }
+}
+
+/// Locals an operand reads, including the locals indexing its place
+pub fn operand_locals(operand: &MirOperand, locals: &mut Vec<LocalId>) {
+    if let MirOperand::Copy(place) | MirOperand::Move(place, _) = operand {
+        place_locals(place, locals);
+    }
+}
+
+/// The local of a place and the locals indexing it
+pub fn place_locals(place: &MirPlace, locals: &mut Vec<LocalId>) {
+    locals.push(place.local);
+    index_locals(place, locals);
+}
+
+/// The locals indexing a place
+pub fn index_locals(place: &MirPlace, locals: &mut Vec<LocalId>) {
+    locals.extend(place.projection.iter().filter_map(|element| match element {
+        MirProjectionElem::Index(index) => Some(*index),
+        _ => None,
+    }));
+}
+
+/// Operands an rvalue reads
+pub fn rvalue_operands(rvalue: &MirRvalue) -> Vec<&MirOperand> {
+    match rvalue {
+        MirRvalue::Use(operand) | MirRvalue::Repeat { operand, .. }
+        | MirRvalue::Cast { operand, .. } | MirRvalue::UnaryOp { operand, .. } => vec![operand],
+        MirRvalue::BinaryOp { left, right, .. } => vec![left, right],
+        MirRvalue::Aggregate { operands, .. } => operands.iter().collect(),
+        MirRvalue::Ref { .. } | MirRvalue::Len(_) | MirRvalue::Discriminant(_) => Vec::new(),
+    }
+}
+
+/// Locals an rvalue reads
+pub fn rvalue_locals(rvalue: &MirRvalue) -> Vec<LocalId> {
+    let mut locals = Vec::new();
+    for operand in rvalue_operands(rvalue) {
+        operand_locals(operand, &mut locals);
+    }
+    if let MirRvalue::Ref { place, .. } | MirRvalue::Len(place) | MirRvalue::Discriminant(place) = rvalue {
+        place_locals(place, &mut locals);
+    }
+    locals
+}
+
+/// Locals a terminator reads
+pub fn terminator_locals(terminator: &MirTerminator) -> Vec<LocalId> {
+    let mut locals = Vec::new();
+    match terminator {
+        MirTerminator::Return { value: Some(operand) }
+        | MirTerminator::SwitchInt { discriminant: operand, .. }
+        | MirTerminator::Assert { condition: operand, .. } => operand_locals(operand, &mut locals),
+        MirTerminator::Call { func, args, destination, .. } => {
+            operand_locals(func, &mut locals);
+            for arg in args {
+                operand_locals(arg, &mut locals);
+            }
+            // Writing a projection of the destination keeps the rest of it
+            if !destination.projection.is_empty() {
+                place_locals(destination, &mut locals);
+            }
+        }
+        MirTerminator::Drop { place, .. } => place_locals(place, &mut locals),
+        MirTerminator::Return { value: None } | MirTerminator::Goto { .. } | MirTerminator::Unreachable => {}
+    }
+    locals
+}
+
+/// The place a statement or terminator writes, if it writes one
+fn written_place<'a>(kind: Written<'a>) -> Option<&'a MirPlace> {
+    match kind {
+        Written::Statement(MirStatementKind::Assign { place, .. }) => Some(place),
+        Written::Terminator(MirTerminator::Call { destination, .. }) => Some(destination),
+        _ => None,
+    }
+}
+
+/// A statement or terminator, for the analyses that treat them alike
+#[derive(Clone, Copy)]
+enum Written<'a> {
+    Statement(&'a MirStatementKind),
+    Terminator(&'a MirTerminator),
+}
+
+/// Locals whose value may still be read: live before a point if some path from
+/// it reads the local before assigning all of it
+pub struct Liveness;
+
+impl Analysis for Liveness {
+    type Domain = BTreeSet<LocalId>;
+
+    const DIRECTION: Direction = Direction::Backward;
+
+    fn bottom(&self, _function: &MirFunction) -> Self::Domain {
+        BTreeSet::new()
+    }
+
+    fn boundary(&self, _function: &MirFunction) -> Self::Domain {
+        BTreeSet::new()
+    }
+
+    fn apply_statement(&self, live: &mut Self::Domain, statement: &MirStatement, _location: Location) {
+        match &statement.kind {
+            MirStatementKind::Assign { place, rvalue } => {
+                if place.projection.is_empty() {
+                    live.remove(&place.local);
+                } else {
+                    live.insert(place.local);
+                    let mut indexes = Vec::new();
+                    index_locals(place, &mut indexes);
+                    live.extend(indexes);
+                }
+                live.extend(rvalue_locals(rvalue));
+            }
+            MirStatementKind::StorageDead(local) => {
+                live.remove(local);
+            }
+            MirStatementKind::StorageLive(_) | MirStatementKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator(&self, live: &mut Self::Domain, block: &MirBasicBlock, _location: Location) {
+        let terminator = &block.terminator;
+        if let MirTerminator::Call { destination, .. } = terminator {
+            if destination.projection.is_empty() {
+                live.remove(&destination.local);
+            }
+        }
+        live.extend(terminator_locals(terminator));
+    }
+}
+
+/// Assignments whose value may still be in the local they assign: a definition
+/// reaches a point if some path from it gets there without assigning all of the
+/// local again. Definitions are the locations of assignments and calls.
+pub struct ReachingDefinitions {
+    /// Definitions of each local
+    definitions: BTreeMap<LocalId, BTreeSet<Location>>,
+}
+
+impl ReachingDefinitions {
+    pub fn new(function: &MirFunction) -> Self {
+        let mut definitions: BTreeMap<LocalId, BTreeSet<Location>> = BTreeMap::new();
+        for (&block_id, block) in &function.basic_blocks {
+            let written = block.statements.iter()
+                .map(|statement| Written::Statement(&statement.kind))
+                .chain(std::iter::once(Written::Terminator(&block.terminator)));
+            for (index, kind) in written.enumerate() {
+                if let Some(place) = written_place(kind) {
+                    definitions.entry(place.local).or_default().insert(Location { block: block_id, statement_index: index });
+                }
+            }
+        }
+        Self { definitions }
+    }
+
+    /// The local the definition at `location` assigns
+    pub fn defined_local(&self, location: Location) -> Option<LocalId> {
+        self.definitions.iter()
+            .find(|(_, locations)| locations.contains(&location))
+            .map(|(local, _)| *local)
+    }
+
+    fn define(&self, reaching: &mut BTreeSet<Location>, place: &MirPlace, location: Location) {
+        // Assigning part of a local keeps the rest of it, and the definitions of that
+        if place.projection.is_empty() {
+            self.kill(reaching, place.local);
+        }
+        reaching.insert(location);
+    }
+
+    fn kill(&self, reaching: &mut BTreeSet<Location>, local: LocalId) {
+        if let Some(definitions) = self.definitions.get(&local) {
+            reaching.retain(|location| !definitions.contains(location));
+        }
+    }
+}
+
+impl Analysis for ReachingDefinitions {
+    type Domain = BTreeSet<Location>;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom(&self, _function: &MirFunction) -> Self::Domain {
+        BTreeSet::new()
+    }
+
+    fn boundary(&self, _function: &MirFunction) -> Self::Domain {
+        BTreeSet::new()
+    }
+
+    fn apply_statement(&self, reaching: &mut Self::Domain, statement: &MirStatement, location: Location) {
+        match &statement.kind {
+            MirStatementKind::Assign { place, .. } => self.define(reaching, place, location),
+            MirStatementKind::StorageDead(local) => self.kill(reaching, *local),
+            MirStatementKind::StorageLive(_) | MirStatementKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator(&self, reaching: &mut Self::Domain, block: &MirBasicBlock, location: Location) {
+        if let Some(place) = written_place(Written::Terminator(&block.terminator)) {
+            self.define(reaching, place, location);
+        }
+    }
+}
+
+/// Locals assigned on every path to a point. Paths meet at the intersection of
+/// their sets, so a block no path has reached yet has every local.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitializedLocals(pub BTreeSet<LocalId>);
+
+impl JoinSemiLattice for InitializedLocals {
+    fn join(&mut self, other: &Self) -> bool {
+        let before = self.0.len();
+        self.0.retain(|local| other.0.contains(local));
+        self.0.len() != before
+    }
+}
+
+/// Locals definitely initialized: assigned as a whole on every path from the
+/// entry, and not moved out or out of storage since. Parameters are initialized
+/// on entry.
+pub struct DefiniteInitialization;
+
+impl DefiniteInitialization {
+    fn write(initialized: &mut InitializedLocals, place: &MirPlace) {
+        if place.projection.is_empty() {
+            initialized.0.insert(place.local);
+        }
+    }
+
+    fn move_out(initialized: &mut InitializedLocals, operand: &MirOperand) {
+        if let MirOperand::Move(place, _) = operand {
+            if place.projection.is_empty() {
+                initialized.0.remove(&place.local);
+            }
+        }
+    }
+}
+
+impl Analysis for DefiniteInitialization {
+    type Domain = InitializedLocals;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom(&self, function: &MirFunction) -> Self::Domain {
+        InitializedLocals(function.locals.iter().map(|local| local.id).collect())
+    }
+
+    fn boundary(&self, function: &MirFunction) -> Self::Domain {
+        let parameters = function.signature.parameters.len();
+        InitializedLocals(function.locals.iter().take(parameters).map(|local| local.id).collect())
+    }
+
+    fn apply_statement(&self, initialized: &mut Self::Domain, statement: &MirStatement, _location: Location) {
+        match &statement.kind {
+            MirStatementKind::Assign { place, rvalue } => {
+                for operand in rvalue_operands(rvalue) {
+                    Self::move_out(initialized, operand);
+                }
+                Self::write(initialized, place);
+            }
+            MirStatementKind::StorageLive(local) | MirStatementKind::StorageDead(local) => {
+                initialized.0.remove(local);
+            }
+            MirStatementKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator(&self, initialized: &mut Self::Domain, block: &MirBasicBlock, _location: Location) {
+        match &block.terminator {
+            MirTerminator::Call { func, args, destination, .. } => {
+                for operand in std::iter::once(func).chain(args) {
+                    Self::move_out(initialized, operand);
+                }
+                Self::write(initialized, destination);
+            }
+            MirTerminator::SwitchInt { discriminant: operand, .. } | MirTerminator::Assert { condition: operand, .. } => {
+                Self::move_out(initialized, operand);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// What a local holds at a point, as far as constant propagation knows
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstantValue {
+    /// The same constant on every path assigning it
+    Constant(MirConstantValue),
+    /// Different values on different paths, or a value not known until run time
+    Varying,
+}
+
+/// Values of the locals at a point. A local without an entry is assigned on no
+/// path to the point.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Constants(pub BTreeMap<LocalId, ConstantValue>);
+
+impl Constants {
+    /// The constant `local` holds, if it holds one on every path that assigns it
+    pub fn get(&self, local: LocalId) -> Option<&MirConstantValue> {
+        match self.0.get(&local) {
+            Some(ConstantValue::Constant(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn value_of(&self, operand: &MirOperand) -> Option<MirConstantValue> {
+        match operand {
+            MirOperand::Constant(constant) => Some(constant.literal.clone()),
+            MirOperand::Copy(place) | MirOperand::Move(place, _) if place.projection.is_empty() => self.get(place.local).cloned(),
+            _ => None,
+        }
+    }
+}
+
+impl JoinSemiLattice for Constants {
+    fn join(&mut self, other: &Self) -> bool {
+        let mut changed = false;
+        for (local, value) in &other.0 {
+            match self.0.get(local) {
+                None => {
+                    self.0.insert(*local, value.clone());
+                    changed = true;
+                }
+                Some(ConstantValue::Varying) => {}
+                Some(existing) if existing != value => {
+                    self.0.insert(*local, ConstantValue::Varying);
+                    changed = true;
+                }
+                Some(_) => {}
+            }
+        }
+        changed
+    }
+}
+
+/// Locals holding a value known at compile time, folding arithmetic, comparisons
+/// and concatenation of constants the way IR lowering does. Borrowed locals may
+/// change through the reference, so they are never constant.
+pub struct ConstantPropagation {
+    borrowed: BTreeSet<LocalId>,
+}
+
+impl ConstantPropagation {
+    pub fn new(function: &MirFunction) -> Self {
+        let borrowed = function.basic_blocks.values()
+            .flat_map(|block| &block.statements)
+            .filter_map(|statement| match &statement.kind {
+                MirStatementKind::Assign { rvalue: MirRvalue::Ref { place, .. }, .. } => Some(place.local),
+                _ => None,
+            })
+            .collect();
+        Self { borrowed }
+    }
+
+    fn write(&self, constants: &mut Constants, place: &MirPlace, value: Option<MirConstantValue>) {
+        let value = match value {
+            Some(value) if place.projection.is_empty() && !self.borrowed.contains(&place.local) => ConstantValue::Constant(value),
+            _ => ConstantValue::Varying,
+        };
+        constants.0.insert(place.local, value);
+    }
+
+    fn evaluate(constants: &Constants, rvalue: &MirRvalue) -> Option<MirConstantValue> {
+        match rvalue {
+            MirRvalue::Use(operand) => constants.value_of(operand),
+            MirRvalue::BinaryOp { op, left, right } => fold_binary(op, &constants.value_of(left)?, &constants.value_of(right)?),
+            MirRvalue::UnaryOp { op, operand } => match (op, constants.value_of(operand)?) {
+                (MirUnOp::Not, MirConstantValue::Boolean(b)) => Some(MirConstantValue::Boolean(!b)),
+                (MirUnOp::Neg, MirConstantValue::Integer(n)) => Some(MirConstantValue::Integer(n.wrapping_neg())),
+                (MirUnOp::Neg, MirConstantValue::Number(n)) => Some(MirConstantValue::Number(-n)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Evaluate a binary operation on two constants; Int arithmetic wraps, and
+/// division and remainder that would trap are left to run time
+fn fold_binary(op: &MirBinOp, left: &MirConstantValue, right: &MirConstantValue) -> Option<MirConstantValue> {
+    use MirConstantValue::{Boolean, Integer, Number, String};
+    let ordering = match (left, right) {
+        (Integer(a), Integer(b)) => a.partial_cmp(b),
+        (Number(a), Number(b)) => a.partial_cmp(b),
+        (String(a), String(b)) => a.partial_cmp(b),
+        (Boolean(a), Boolean(b)) => a.partial_cmp(b),
+        _ => None,
+    };
+    match (op, left, right) {
+        (MirBinOp::Add, Integer(a), Integer(b)) => Some(Integer(a.wrapping_add(*b))),
+        (MirBinOp::Sub, Integer(a), Integer(b)) => Some(Integer(a.wrapping_sub(*b))),
+        (MirBinOp::Mul, Integer(a), Integer(b)) => Some(Integer(a.wrapping_mul(*b))),
+        (MirBinOp::Div, Integer(a), Integer(b)) => a.checked_div(*b).map(Integer),
+        (MirBinOp::Rem, Integer(a), Integer(b)) if *b != 0 => Some(Integer(a.wrapping_rem(*b))),
+        (MirBinOp::Add, Number(a), Number(b)) => Some(Number(a + b)),
+        (MirBinOp::Sub, Number(a), Number(b)) => Some(Number(a - b)),
+        (MirBinOp::Mul, Number(a), Number(b)) => Some(Number(a * b)),
+        (MirBinOp::Div, Number(a), Number(b)) => Some(Number(a / b)),
+        (MirBinOp::Add, String(a), String(b)) => Some(String(format!("{}{}", a, b))),
+        (MirBinOp::BitAnd, Boolean(a), Boolean(b)) => Some(Boolean(*a && *b)),
+        (MirBinOp::BitOr, Boolean(a), Boolean(b)) => Some(Boolean(*a || *b)),
+        (MirBinOp::Eq, _, _) => ordering.map(|ordering| Boolean(ordering.is_eq())),
+        (MirBinOp::Ne, _, _) => ordering.map(|ordering| Boolean(ordering.is_ne())),
+        (MirBinOp::Lt, _, _) => ordering.map(|ordering| Boolean(ordering.is_lt())),
+        (MirBinOp::Le, _, _) => ordering.map(|ordering| Boolean(ordering.is_le())),
+        (MirBinOp::Gt, _, _) => ordering.map(|ordering| Boolean(ordering.is_gt())),
+        (MirBinOp::Ge, _, _) => ordering.map(|ordering| Boolean(ordering.is_ge())),
+        _ => None,
+    }
+}
+
+impl Analysis for ConstantPropagation {
+    type Domain = Constants;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom(&self, _function: &MirFunction) -> Self::Domain {
+        Constants::default()
+    }
+
+    fn boundary(&self, function: &MirFunction) -> Self::Domain {
+        let parameters = function.signature.parameters.len();
+        Constants(function.locals.iter().take(parameters).map(|local| (local.id, ConstantValue::Varying)).collect())
+    }
+
+    fn apply_statement(&self, constants: &mut Self::Domain, statement: &MirStatement, _location: Location) {
+        match &statement.kind {
+            MirStatementKind::Assign { place, rvalue } => {
+                let value = Self::evaluate(constants, rvalue);
+                self.write(constants, place, value);
+            }
+            MirStatementKind::StorageDead(local) => {
+                constants.0.remove(local);
+            }
+            MirStatementKind::StorageLive(_) | MirStatementKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator(&self, constants: &mut Self::Domain, block: &MirBasicBlock, _location: Location) {
+        if let MirTerminator::Call { destination, .. } = &block.terminator {
+            self.write(constants, destination, None);
+        }
+    }
+}
+
+/// Results of the provided analyses for each function of a program
+#[derive(Debug, Clone, Default)]
+pub struct DataflowAnalysis {
+    pub function_analyses: HashMap<FunctionId, FunctionDataflow>,
+}
+
+/// Results of the provided analyses for one function, at block boundaries
+#[derive(Debug, Clone, Default)]
+pub struct FunctionDataflow {
+    /// Locals live at the start of each block
+    pub live_in: HashMap<BasicBlockId, BTreeSet<LocalId>>,
+    /// Locals live at the end of each block
+    pub live_out: HashMap<BasicBlockId, BTreeSet<LocalId>>,
+    /// Definitions reaching the start of each block
+    pub reaching_definitions: HashMap<BasicBlockId, BTreeSet<Location>>,
+    /// Locals definitely initialized at the start of each block
+    pub initialized: HashMap<BasicBlockId, BTreeSet<LocalId>>,
+    /// Locals holding a known constant at the end of each block
+    pub constants: HashMap<BasicBlockId, BTreeMap<LocalId, MirConstantValue>>,
+}
+
+impl FunctionDataflow {
+    /// Run liveness, reaching definitions, definite initialization and constant
+    /// propagation over `function`
+    pub fn analyze(function: &MirFunction) -> Self {
+        let liveness = solve(Liveness, function);
+        let reaching = solve(ReachingDefinitions::new(function), function);
+        let initialized = solve(DefiniteInitialization, function);
+        let constants = solve(ConstantPropagation::new(function), function);
+        Self {
+            live_in: liveness.entry,
+            live_out: liveness.exit,
+            reaching_definitions: reaching.entry,
+            initialized: initialized.entry.into_iter().map(|(block_id, locals)| (block_id, locals.0)).collect(),
+            constants: constants.exit.into_iter()
+                .map(|(block_id, values)| {
+                    let known = values.0.into_iter()
+                        .filter_map(|(local, value)| match value {
+                            ConstantValue::Constant(value) => Some((local, value)),
+                            ConstantValue::Varying => None,
+                        })
+                        .collect();
+                    (block_id, known)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::SourceSpan;
+    use crate::mir::{MirBorrowKind, MirConstant, MirFunctionSignature, MirLocal, MirRegion, MirType};
+
+    /// Function with `locals` Int locals, the first `parameters` of them parameters,
+    /// and a block for each of `blocks`, numbered from 0
+    fn function_of(parameters: usize, locals: u32, blocks: Vec<(Vec<MirStatementKind>, MirTerminator)>) -> MirFunction {
+        let basic_blocks = blocks.into_iter().enumerate()
+            .map(|(id, (kinds, terminator))| {
+                let id = id as u32;
+                let statements = kinds.into_iter().map(|kind| MirStatement { kind, span: SourceSpan::default() }).collect();
+                (id, MirBasicBlock { id, statements, terminator, terminator_span: SourceSpan::default() })
+            })
+            .collect();
+        MirFunction {
+            id: 0,
+            name: "f".to_string(),
+            signature: MirFunctionSignature { parameters: vec![MirType::Int; parameters], return_type: MirType::Int },
+            basic_blocks,
+            locals: (0..locals).map(|id| MirLocal {
+                id,
+                ty: MirType::Int,
+                is_mutable: true,
+                name: None,
+                span: SourceSpan::default(),
+            }).collect(),
+            entry_block: 0,
+            is_main: false,
+            span: SourceSpan::default(),
+        }
+    }
+
+    fn int(n: i64) -> MirOperand {
+        MirOperand::Constant(MirConstant { literal: MirConstantValue::Integer(n), ty: MirType::Int })
+    }
+
+    fn copy(local: LocalId) -> MirOperand {
+        MirOperand::Copy(MirPlace::from_local(local))
+    }
+
+    fn assign(local: LocalId, rvalue: MirRvalue) -> MirStatementKind {
+        MirStatementKind::Assign { place: MirPlace::from_local(local), rvalue }
+    }
+
+    fn set(locals: &[LocalId]) -> BTreeSet<LocalId> {
+        locals.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_analyses_reach_a_fixed_point_around_loops() {
+        // i = 0; while i < 10 { i = i + 1 }; return i
+        let function = function_of(0, 2, vec![
+            (vec![assign(0, MirRvalue::Use(int(0)))], MirTerminator::Goto { target: 1 }),
+            (vec![assign(1, MirRvalue::BinaryOp { op: MirBinOp::Lt, left: copy(0), right: int(10) })],
+             MirTerminator::SwitchInt { discriminant: copy(1), targets: vec![(0, 3)], otherwise: 2 }),
+            (vec![assign(0, MirRvalue::BinaryOp { op: MirBinOp::Add, left: copy(0), right: int(1) })],
+             MirTerminator::Goto { target: 1 }),
+            (vec![], MirTerminator::Return { value: Some(copy(0)) }),
+        ]);
+        let at = |block, statement_index| Location { block, statement_index };
+
+        let dataflow = FunctionDataflow::analyze(&function);
+        assert_eq!(dataflow.live_in[&0], set(&[]));
+        assert_eq!(dataflow.live_in[&1], set(&[0]));
+        assert_eq!(dataflow.live_out[&2], set(&[0]), "the back edge keeps i live");
+        // The condition of the previous iteration reaches the loop head too
+        assert_eq!(dataflow.reaching_definitions[&1], [at(0, 0), at(1, 0), at(2, 0)].into_iter().collect());
+        assert_eq!(dataflow.initialized[&1], set(&[0]));
+        assert_eq!(dataflow.initialized[&3], set(&[0, 1]));
+        assert_eq!(dataflow.constants[&0].get(&0), Some(&MirConstantValue::Integer(0)));
+        assert_eq!(dataflow.constants[&1].get(&0), None, "i changes on every iteration");
+    }
+
+    #[test]
+    fn test_definite_initialization_follows_moves_and_storage() {
+        // Parameter 0 is moved into 1; 2 is assigned, then goes out of storage
+        let function = function_of(1, 3, vec![
+            (vec![
+                assign(1, MirRvalue::Use(MirOperand::Move(MirPlace::from_local(0), SourceSpan::default()))),
+                assign(2, MirRvalue::Use(int(1))),
+                MirStatementKind::StorageDead(2),
+            ], MirTerminator::Return { value: Some(copy(1)) }),
+        ]);
+        let results = solve(DefiniteInitialization, &function);
+        let states: Vec<_> = results.states_in_block(&function, 0).into_iter().map(|state| state.0).collect();
+        assert_eq!(states, vec![set(&[0]), set(&[1]), set(&[1, 2]), set(&[1])]);
+    }
+
+    #[test]
+    fn test_constant_propagation_folds_like_ir_lowering() {
+        use MirConstantValue::{Boolean, Integer, Number, String};
+        assert_eq!(fold_binary(&MirBinOp::Add, &Integer(i64::MAX), &Integer(1)), Some(Integer(i64::MIN)));
+        assert_eq!(fold_binary(&MirBinOp::Div, &Integer(7), &Integer(0)), None, "division by zero traps at run time");
+        assert_eq!(fold_binary(&MirBinOp::Div, &Integer(i64::MIN), &Integer(-1)), None);
+        assert_eq!(fold_binary(&MirBinOp::Rem, &Integer(7), &Integer(0)), None);
+        assert_eq!(fold_binary(&MirBinOp::Div, &Number(1.0), &Number(4.0)), Some(Number(0.25)));
+        assert_eq!(fold_binary(&MirBinOp::Add, &String("ov".into()), &String("ie".into())), Some(String("ovie".into())));
+        assert_eq!(fold_binary(&MirBinOp::Lt, &Integer(1), &Integer(2)), Some(Boolean(true)));
+        assert_eq!(fold_binary(&MirBinOp::Eq, &Integer(1), &Number(1.0)), None);
+
+        // A borrowed local may change through the reference
+        let function = function_of(0, 3, vec![
+            (vec![
+                assign(0, MirRvalue::Use(int(4))),
+                assign(1, MirRvalue::Ref { region: MirRegion::Local(0), borrow_kind: MirBorrowKind::Shared, place: MirPlace::from_local(0) }),
+                assign(2, MirRvalue::BinaryOp { op: MirBinOp::Mul, left: int(4), right: int(5) }),
+            ], MirTerminator::Return { value: Some(copy(2)) }),
+        ]);
+        let constants = &FunctionDataflow::analyze(&function).constants[&0];
+        assert_eq!(constants.get(&0), None);
+        assert_eq!(constants.get(&2), Some(&Integer(20)));
+    }
+}
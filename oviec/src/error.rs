@@ -907,6 +907,197 @@ pub struct Diagnostic {
     pub help_url: Option<String>,
 }
 
+/// Why a constant expression could not be evaluated at compile time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConstEvalErrorKind {
+    /// Int division or remainder by zero
+    DivisionByZero,
+    /// Int arithmetic or conversion outside the 64-bit range
+    IntegerOverflow,
+    /// String index past the last character
+    IndexOutOfBounds,
+    /// An expression that can only be evaluated at run time
+    NonConstant,
+    /// A constant whose value depends on itself
+    CircularDependency,
+    /// Evaluation ran too many steps or nested calls too deeply
+    LimitExceeded,
+}
+
+impl ConstEvalErrorKind {
+    /// Error code in the E4000-E4999 range reserved for constant evaluation
+    pub fn code(&self) -> &'static str {
+        match self {
+            ConstEvalErrorKind::DivisionByZero => "E4001",
+            ConstEvalErrorKind::IntegerOverflow => "E4002",
+            ConstEvalErrorKind::IndexOutOfBounds => "E4003",
+            ConstEvalErrorKind::NonConstant => "E4004",
+            ConstEvalErrorKind::CircularDependency => "E4005",
+            ConstEvalErrorKind::LimitExceeded => "E4006",
+        }
+    }
+}
+
+/// What kind of name could not be resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResolutionErrorKind {
+    /// A variable, constant, or function used as a value
+    Variable,
+    /// A called function
+    Function,
+    /// A struct in a struct literal
+    Struct,
+    /// A field of a struct
+    Field,
+    /// A variant of an enum
+    Variant,
+    /// A type in a type annotation or impl block
+    Type,
+    /// A trait in a bound or impl block
+    Trait,
+    /// A module named by `use` or `mod`
+    Module,
+    /// An item imported from a module
+    Item,
+}
+
+impl ResolutionErrorKind {
+    /// Error code in the E2000-E2999 range reserved for name resolution
+    pub fn code(&self) -> &'static str {
+        match self {
+            ResolutionErrorKind::Variable => "E2001",
+            ResolutionErrorKind::Function => "E2002",
+            ResolutionErrorKind::Struct => "E2003",
+            ResolutionErrorKind::Field => "E2004",
+            ResolutionErrorKind::Variant => "E2005",
+            ResolutionErrorKind::Type => "E2006",
+            ResolutionErrorKind::Trait => "E2007",
+            ResolutionErrorKind::Module => "E2008",
+            ResolutionErrorKind::Item => "E2009",
+        }
+    }
+}
+
+/// Why an assignment was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MutabilityErrorKind {
+    /// Reassignment of a variable or parameter not declared `mut`
+    ImmutableVariable,
+    /// Assignment to a field or element of a variable not declared `mut`
+    ImmutablePlace,
+    /// Assignment to a constant
+    Constant,
+}
+
+impl MutabilityErrorKind {
+    /// Error code in the E3000-E3999 range reserved for ownership, borrowing, and mutability
+    pub fn code(&self) -> &'static str {
+        match self {
+            MutabilityErrorKind::ImmutableVariable => "E3001",
+            MutabilityErrorKind::ImmutablePlace => "E3002",
+            MutabilityErrorKind::Constant => "E3003",
+        }
+    }
+}
+
+/// Which rule of ownership and borrowing a use of a place breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BorrowErrorKind {
+    /// Use of a value after it was moved out
+    UseAfterMove,
+    /// Borrow of a value after it was moved out
+    BorrowOfMovedValue,
+    /// A second mutable borrow of a place while the first is in use
+    MultipleMutableBorrows,
+    /// A mutable borrow of a place while a shared borrow of it is in use
+    MutableBorrowWhileImmutableBorrowExists,
+    /// Use of, assignment to, or move out of a place while a borrow of it is in use
+    AccessWhileBorrowed,
+    /// A borrow in use after the storage it borrows is gone
+    DanglingReference,
+    /// A shared borrow of a place while a mutable borrow of it is in use
+    ImmutableBorrowWhileMutableBorrowExists,
+}
+
+impl BorrowErrorKind {
+    /// Error code in the E3000-E3999 range, numbered after the mutability errors
+    pub fn code(&self) -> &'static str {
+        match self {
+            BorrowErrorKind::UseAfterMove => "E3004",
+            BorrowErrorKind::BorrowOfMovedValue => "E3005",
+            BorrowErrorKind::MultipleMutableBorrows => "E3006",
+            BorrowErrorKind::MutableBorrowWhileImmutableBorrowExists => "E3007",
+            BorrowErrorKind::AccessWhileBorrowed => "E3008",
+            BorrowErrorKind::DanglingReference => "E3009",
+            BorrowErrorKind::ImmutableBorrowWhileMutableBorrowExists => "E3010",
+        }
+    }
+}
+
+/// Kind of an error that has an error code of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorKind {
+    ConstEval(ConstEvalErrorKind),
+    Resolution(ResolutionErrorKind),
+    Mutability(MutabilityErrorKind),
+    Borrow(BorrowErrorKind),
+}
+
+impl ErrorKind {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::ConstEval(kind) => kind.code(),
+            ErrorKind::Resolution(kind) => kind.code(),
+            ErrorKind::Mutability(kind) => kind.code(),
+            ErrorKind::Borrow(kind) => kind.code(),
+        }
+    }
+
+    /// Context key of what an error of this kind is about
+    fn subject(&self) -> &'static str {
+        match self {
+            ErrorKind::ConstEval(_) => "operation",
+            ErrorKind::Resolution(_) => "name",
+            ErrorKind::Mutability(_) => "variable",
+            ErrorKind::Borrow(_) => "place",
+        }
+    }
+
+    /// Name of the kind within its group, such as `UseAfterMove`
+    fn name(&self) -> String {
+        match self {
+            ErrorKind::ConstEval(kind) => format!("{:?}", kind),
+            ErrorKind::Resolution(kind) => format!("{:?}", kind),
+            ErrorKind::Mutability(kind) => format!("{:?}", kind),
+            ErrorKind::Borrow(kind) => format!("{:?}", kind),
+        }
+    }
+}
+
+impl From<ConstEvalErrorKind> for ErrorKind {
+    fn from(kind: ConstEvalErrorKind) -> Self {
+        ErrorKind::ConstEval(kind)
+    }
+}
+
+impl From<ResolutionErrorKind> for ErrorKind {
+    fn from(kind: ResolutionErrorKind) -> Self {
+        ErrorKind::Resolution(kind)
+    }
+}
+
+impl From<MutabilityErrorKind> for ErrorKind {
+    fn from(kind: MutabilityErrorKind) -> Self {
+        ErrorKind::Mutability(kind)
+    }
+}
+
+impl From<BorrowErrorKind> for ErrorKind {
+    fn from(kind: BorrowErrorKind) -> Self {
+        ErrorKind::Borrow(kind)
+    }
+}
+
 /// Main error type for the Ovie compiler
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum OvieError {
@@ -943,6 +1134,12 @@ pub enum OvieError {
         message: String,
     },
 
+    /// A `requires`, `ensures` or `invariant` clause that was false at run time
+    #[error("Assertion failed: {message}")]
+    AssertionError {
+        message: String,
+    },
+
     #[error("IO error: {message}")]
     IoError {
         message: String,
@@ -1109,6 +1306,39 @@ impl OvieError {
         Self::Diagnostic { diagnostic }
     }
 
+    /// Create an error of `kind` about `subject`: the operation being evaluated, the
+    /// name that does not resolve, the variable assigned to, or the place used
+    pub fn kind_error(
+        line: usize,
+        column: usize,
+        kind: impl Into<ErrorKind>,
+        subject: &str,
+        message: impl Into<String>
+    ) -> Self {
+        let kind = kind.into();
+        let mut context = HashMap::new();
+        context.insert(kind.subject().to_string(), subject.to_string());
+        context.insert("error_kind".to_string(), kind.name());
+
+        let diagnostic = Diagnostic {
+            code: kind.code().to_string(),
+            severity: ErrorSeverity::Error,
+            category: ErrorCategory::Semantic,
+            message: message.into(),
+            location: SourcePosition {
+                file: None,
+                line,
+                column,
+                offset: 0,
+            },
+            related_locations: Vec::new(),
+            suggestions: Vec::new(),
+            context,
+            help_url: Some(format!("https://ovie-lang.org/docs/errors/{}", kind.code())),
+        };
+        Self::Diagnostic { diagnostic }
+    }
+
     /// Legacy error constructors (for backward compatibility)
     pub fn lex_error(line: usize, column: usize, message: impl Into<String>) -> Self {
         Self::LexError {
@@ -1140,6 +1370,12 @@ impl OvieError {
         }
     }
 
+    pub fn assertion_error(message: impl Into<String>) -> Self {
+        Self::AssertionError {
+            message: message.into(),
+        }
+    }
+
     pub fn io_error(message: impl Into<String>) -> Self {
         Self::IoError {
             message: message.into(),
@@ -1170,6 +1406,56 @@ impl OvieError {
         }
     }
 
+    /// Attach a source position to an error that was created without one (line 0).
+    /// Errors that already carry a location keep it, so the innermost node wins.
+    pub fn located_at(mut self, position: SourcePosition) -> Self {
+        match &mut self {
+            Self::LexError { line, column, .. }
+            | Self::ParseError { line, column, .. }
+            | Self::SemanticError { line, column, .. } if *line == 0 => {
+                *line = position.line;
+                *column = position.column;
+            }
+            Self::Diagnostic { diagnostic } if diagnostic.location.line == 0 => {
+                diagnostic.location = position;
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Point at another place in the source that explains this error, such as
+    /// where a conflicting type came from. Only diagnostics carry related locations.
+    pub fn with_related_location(mut self, position: SourcePosition, note: impl Into<String>) -> Self {
+        if let Self::Diagnostic { diagnostic } = &mut self {
+            diagnostic.related_locations.push((position, note.into()));
+        }
+        self
+    }
+
+    /// Suggest ways to fix this error. Only diagnostics carry suggestions.
+    pub fn with_suggestions(mut self, suggestions: Vec<ErrorSuggestion>) -> Self {
+        if let Self::Diagnostic { diagnostic } = &mut self {
+            diagnostic.suggestions.extend(suggestions);
+        }
+        self
+    }
+
+    /// Source position of this error, if it has one
+    pub fn location(&self) -> Option<SourcePosition> {
+        match self {
+            Self::LexError { line, column, .. }
+            | Self::ParseError { line, column, .. }
+            | Self::SemanticError { line, column, .. } if *line > 0 => {
+                Some(SourcePosition::at_line_column(*line, *column))
+            }
+            Self::Diagnostic { diagnostic } if diagnostic.location.line > 0 => {
+                Some(diagnostic.location.clone())
+            }
+            _ => None,
+        }
+    }
+
     /// Get the diagnostic information if available
     pub fn get_diagnostic(&self) -> Option<&Diagnostic> {
         match self {
@@ -1374,6 +1660,22 @@ impl OvieError {
                 context: HashMap::new(),
                 help_url: Some("https://ovie-lang.org/docs/errors/E0014".to_string()),
             },
+            Self::AssertionError { message } => Diagnostic {
+                code: "E0015".to_string(),
+                severity: ErrorSeverity::Error,
+                category: ErrorCategory::Runtime,
+                message: format!("Assertion failed: {}", message),
+                location: SourcePosition {
+                    file: None,
+                    line: 1,
+                    column: 1,
+                    offset: 0,
+                },
+                related_locations: Vec::new(),
+                suggestions: Vec::new(),
+                context: HashMap::new(),
+                help_url: Some("https://ovie-lang.org/docs/errors/E0015".to_string()),
+            },
         }
     }
 }
@@ -1656,6 +1958,10 @@ impl ErrorReporter {
                 output.push_str(&format!("  --> line {}, column {}\n", diagnostic.location.line, diagnostic.location.column));
             }
 
+            for (location, note) in &diagnostic.related_locations {
+                output.push_str(&format!("  note: line {}, column {}: {}\n", location.line, location.column, note));
+            }
+
             for suggestion in &diagnostic.suggestions {
                 output.push_str(&format!("  help: {}\n", suggestion.message));
             }
@@ -1763,6 +2069,15 @@ impl From<std::io::Error> for OvieError {
     }
 }
 
+impl From<crate::ast::InvariantError> for OvieError {
+    fn from(error: crate::ast::InvariantError) -> Self {
+        Self::InvariantViolation {
+            stage: error.location.clone().unwrap_or_else(|| "compiler".to_string()),
+            message: error.message,
+        }
+    }
+}
+
 impl From<serde_json::Error> for OvieError {
     fn from(error: serde_json::Error) -> Self {
         Self::SerdeJson(error.to_string())
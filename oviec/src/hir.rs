@@ -3,10 +3,20 @@
 //! HIR is the first IR stage after AST, where names are resolved and types are known.
 //! This stage performs semantic analysis and type checking.
 
-use crate::ast::{AstNode, Statement, Expression, Literal, BinaryOperator, UnaryOperator};
-use crate::error::{OvieError, OvieResult};
+use crate::ast::{AstNode, Statement, Expression, Literal, BinaryOperator, UnaryOperator, Parameter, TypeAnnotation, MatchArm, Pattern, TraitMethod, TypeBound, VariantPayload, ContractClause};
+pub use crate::ast::SourceSpan;
+use crate::const_eval::{ConstEvaluator, is_const_type};
+use crate::error::{Diagnostic, ErrorSuggestion, MutabilityErrorKind, OvieError, OvieResult, ResolutionErrorKind};
+use crate::infer::{Constraint, InferenceTable, Substitution, UnifyError};
+use crate::modules::{ModuleGraph, ModulePath, ModuleScope};
+use crate::resolve::{name_span, unresolved_name};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Names of the built-in functions, which programs cannot redefine
+pub const BUILTIN_FUNCTIONS: &[&str] = &["print", "to_string", "to_number", "to_int", "abort"];
 
 /// HIR invariant validation trait
 pub trait HirInvariantValidation {
@@ -20,26 +30,6 @@ pub type NodeId = u32;
 /// Symbol table entry
 pub type Symbol = String;
 
-/// Source location information
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct SourceSpan {
-    pub start: usize,
-    pub end: usize,
-    pub line: u32,
-    pub column: u32,
-}
-
-impl Default for SourceSpan {
-    fn default() -> Self {
-        Self {
-            start: 0,
-            end: 0,
-            line: 1,
-            column: 1,
-        }
-    }
-}
-
 /// HIR Program - the complete program after semantic analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HirProgram {
@@ -63,11 +53,27 @@ pub enum HirItem {
 pub struct HirFunction {
     pub id: NodeId,
     pub name: Symbol,
+    pub type_parameters: Vec<Symbol>,
     pub parameters: Vec<HirParameter>,
     pub return_type: HirType,
     pub body: HirBlock,
     pub span: SourceSpan,
     pub is_main: bool,
+    /// Declared `const fn`, which constants can call
+    pub is_const: bool,
+    pub is_unsafe: bool,
+    /// Checked on entry, in debug builds
+    pub requires: Vec<HirContract>,
+    /// Checked on each return, with the returned value as `result`, in debug builds
+    pub ensures: Vec<HirContract>,
+}
+
+/// `requires`, `ensures` or `invariant` clause: a Boolean condition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HirContract {
+    pub condition: HirExpression,
+    /// Source text of the condition, quoted when a check fails
+    pub text: String,
 }
 
 /// Function parameter with type information
@@ -83,7 +89,10 @@ pub struct HirParameter {
 pub struct HirStruct {
     pub id: NodeId,
     pub name: Symbol,
+    pub type_parameters: Vec<Symbol>,
     pub fields: Vec<HirField>,
+    /// Checked on each value built or whose field is assigned, with the value as `self`, in debug builds
+    pub invariants: Vec<HirContract>,
     pub span: SourceSpan,
 }
 
@@ -100,15 +109,16 @@ pub struct HirField {
 pub struct HirEnum {
     pub id: NodeId,
     pub name: Symbol,
+    pub type_parameters: Vec<Symbol>,
     pub variants: Vec<HirVariant>,
     pub span: SourceSpan,
 }
 
-/// Enum variant with optional data type
+/// Enum variant with its payload fields; positional fields are named "0", "1", ...
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HirVariant {
     pub name: Symbol,
-    pub data_type: Option<HirType>,
+    pub fields: Vec<HirField>,
     pub span: SourceSpan,
 }
 
@@ -119,6 +129,8 @@ pub struct HirGlobal {
     pub name: Symbol,
     pub global_type: HirType,
     pub is_mutable: bool,
+    /// Declared with `const`; the initializer is a literal once the constant is evaluated
+    pub is_const: bool,
     pub initializer: Option<HirExpression>,
     pub span: SourceSpan,
 }
@@ -130,6 +142,17 @@ pub struct HirBlock {
     pub span: SourceSpan,
 }
 
+impl HirBlock {
+    /// The final expression statement, if it has a value. In the body of a function
+    /// that returns a value, it is the returned value.
+    pub fn tail(&self) -> Option<&HirExpression> {
+        match self.statements.last().map(|statement| &statement.kind) {
+            Some(HirStatementKind::Expression(expression)) if expression.expr_type != HirType::Unit => Some(expression),
+            _ => None,
+        }
+    }
+}
+
 /// HIR Statement with type information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HirStatement {
@@ -149,11 +172,25 @@ pub enum HirStatementKind {
         initializer: Option<HirExpression>,
     },
     
+    /// Declaration of the names in a tuple pattern, bound to the parts of the initializer
+    Destructure {
+        pattern: HirPattern,
+        is_mutable: bool,
+        initializer: HirExpression,
+    },
+    
     /// Assignment to existing variable
     Assign {
         target: HirPlace,
         value: HirExpression,
     },
+
+    /// Update of a place with an arithmetic operator: target op= value
+    CompoundAssign {
+        target: HirPlace,
+        op: HirBinaryOp,
+        value: HirExpression,
+    },
     
     /// Expression statement
     Expression(HirExpression),
@@ -173,16 +210,33 @@ pub enum HirStatementKind {
     
     /// While loop
     While {
+        label: Option<Symbol>,
         condition: HirExpression,
         body: HirBlock,
     },
     
-    /// For loop
+    /// For loop; the pattern binds each element
     For {
-        variable: Symbol,
+        label: Option<Symbol>,
+        pattern: HirPattern,
         iterable: HirExpression,
         body: HirBlock,
     },
+
+    /// Unsafe block
+    Unsafe {
+        body: HirBlock,
+    },
+
+    /// Leave the innermost loop, or the enclosing loop with the label
+    Break {
+        label: Option<Symbol>,
+    },
+
+    /// Jump to the next iteration of the innermost loop, or the enclosing loop with the label
+    Continue {
+        label: Option<Symbol>,
+    },
 }
 
 /// HIR Expression with type information
@@ -216,9 +270,10 @@ pub enum HirExpressionKind {
         operand: Box<HirExpression>,
     },
     
-    /// Function call
+    /// Function call; `type_arguments` instantiate a generic callee
     Call {
         function: Symbol,
+        type_arguments: Vec<HirType>,
         arguments: Vec<HirExpression>,
     },
     
@@ -240,11 +295,11 @@ pub enum HirExpressionKind {
         end: Box<HirExpression>,
     },
     
-    /// Enum variant construction
+    /// Enum variant construction; positional fields are named "0", "1", ...
     EnumVariant {
         enum_name: String,
         variant_name: String,
-        data: Option<Box<HirExpression>>,
+        fields: Vec<HirFieldInit>,
     },
     
     /// Array/String indexing
@@ -257,6 +312,72 @@ pub enum HirExpressionKind {
     ArrayLiteral {
         elements: Vec<HirExpression>,
     },
+
+    /// Tuple literal
+    Tuple {
+        elements: Vec<HirExpression>,
+    },
+
+    /// Match expression; arms are tried in order
+    Match {
+        scrutinee: Box<HirExpression>,
+        arms: Vec<HirMatchArm>,
+    },
+
+    /// Closure; `captures` are the enclosing locals its body uses, copied when it is created
+    Closure {
+        parameters: Vec<HirParameter>,
+        return_type: HirType,
+        body: HirBlock,
+        captures: Vec<Symbol>,
+    },
+
+    /// Call of a function value
+    IndirectCall {
+        callee: Box<HirExpression>,
+        arguments: Vec<HirExpression>,
+    },
+
+    /// `operand?`: the data of its `success` variant, or an early return of its
+    /// `failure` variant rebuilt as the enclosing function's return type
+    Try {
+        operand: Box<HirExpression>,
+        success: Symbol,
+        failure: Symbol,
+    },
+}
+
+/// Arm of a match expression
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HirMatchArm {
+    pub pattern: HirPattern,
+    pub body: HirBlock,
+    /// Value of the arm; `None` for block arms, which produce unit
+    pub value: Option<HirExpression>,
+    pub span: SourceSpan,
+}
+
+/// HIR Pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HirPattern {
+    /// Matches anything
+    Wildcard,
+
+    /// Matches anything and binds it
+    Binding(Symbol),
+
+    /// Matches a value equal to the literal
+    Literal(HirLiteral),
+
+    /// Matches one variant of the scrutinee's enum, binding payload fields to names
+    /// as (field, name) pairs
+    Variant {
+        variant_name: Symbol,
+        bindings: Vec<(Symbol, Symbol)>,
+    },
+
+    /// Matches a tuple element by element
+    Tuple(Vec<HirPattern>),
 }
 
 /// HIR Place (assignable location)
@@ -267,6 +388,16 @@ pub struct HirPlace {
     pub span: SourceSpan,
 }
 
+impl fmt::Display for HirPlace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            HirPlaceKind::Local(name) => write!(f, "{}", name),
+            HirPlaceKind::Field { object, field } => write!(f, "{}.{}", object, field),
+            HirPlaceKind::Index { object, .. } => write!(f, "{}[..]", object),
+        }
+    }
+}
+
 /// HIR Place kinds
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum HirPlaceKind {
@@ -278,6 +409,12 @@ pub enum HirPlaceKind {
         object: Box<HirPlace>,
         field: Symbol,
     },
+
+    /// Array element
+    Index {
+        object: Box<HirPlace>,
+        index: Box<HirExpression>,
+    },
 }
 
 /// HIR Literal values
@@ -285,6 +422,7 @@ pub enum HirPlaceKind {
 pub enum HirLiteral {
     String(String),
     Number(f64),
+    Integer(i64),
     Boolean(bool),
     Unit,
 }
@@ -317,14 +455,25 @@ pub enum HirType {
     /// Primitive types
     String,
     Number,
+    /// 64-bit signed integer
+    Int,
     Boolean,
     Unit,
     
-    /// User-defined struct
-    Struct(Symbol),
+    /// User-defined struct, with type arguments if it is generic
+    Struct {
+        name: Symbol,
+        arguments: Vec<HirType>,
+    },
+    
+    /// User-defined enum, with type arguments if it is generic
+    Enum {
+        name: Symbol,
+        arguments: Vec<HirType>,
+    },
     
-    /// User-defined enum
-    Enum(Symbol),
+    /// Type parameter of the enclosing generic item
+    Param(Symbol),
     
     /// Function type
     Function {
@@ -337,6 +486,9 @@ pub enum HirType {
     
     /// Array type
     Array(Box<HirType>),
+
+    /// Tuple type
+    Tuple(Vec<HirType>),
     
     /// Error type (for error recovery)
     Error,
@@ -345,6 +497,33 @@ pub enum HirType {
     Infer(u32),
 }
 
+impl fmt::Display for HirType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |types: &[HirType]| types.iter().map(|ty| ty.to_string()).collect::<Vec<_>>().join(", ");
+        match self {
+            HirType::String => write!(f, "String"),
+            HirType::Number => write!(f, "Number"),
+            HirType::Int => write!(f, "Int"),
+            HirType::Boolean => write!(f, "Boolean"),
+            HirType::Unit => write!(f, "()"),
+            HirType::Struct { name, arguments } | HirType::Enum { name, arguments } if arguments.is_empty() => {
+                write!(f, "{}", name)
+            }
+            HirType::Struct { name, arguments } | HirType::Enum { name, arguments } => {
+                write!(f, "{}<{}>", name, join(arguments))
+            }
+            HirType::Param(name) => write!(f, "{}", name),
+            HirType::Function { params, return_type } => write!(f, "fn({}) -> {}", join(params), return_type),
+            HirType::Range(inner) => write!(f, "Range<{}>", inner),
+            HirType::Array(inner) => write!(f, "[{}]", inner),
+            HirType::Tuple(elements) if elements.len() == 1 => write!(f, "({},)", elements[0]),
+            HirType::Tuple(elements) => write!(f, "({})", join(elements)),
+            HirType::Error => write!(f, "{{error}}"),
+            HirType::Infer(_) => write!(f, "_"),
+        }
+    }
+}
+
 /// Symbol table for name resolution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolTable {
@@ -372,16 +551,64 @@ pub struct SymbolInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeTable {
     types: HashMap<Symbol, TypeInfo>,
+    /// Methods and associated functions of each type, by name
+    methods: HashMap<Symbol, HashMap<Symbol, MethodInfo>>,
+    /// Traits, by name
+    traits: HashMap<Symbol, TraitInfo>,
+}
+
+/// A function defined in an impl block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodInfo {
+    /// Name of the function implementing the method (`Type::method`)
+    pub function: Symbol,
+    /// Whether the first parameter is `self`; otherwise it is an associated function
+    pub takes_self: bool,
+}
+
+/// A trait and the types implementing it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraitInfo {
+    /// Methods the trait declares, by name
+    pub methods: HashMap<Symbol, TraitMethodInfo>,
+    /// Impls of the trait, by the name of the implementing type (`Int` for the builtin types)
+    pub impls: HashMap<Symbol, ImplInfo>,
+}
+
+/// A method declared in a trait
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraitMethodInfo {
+    /// Name of the function calls through a trait bound go to (`Trait::method`), generic over
+    /// `Self` and the method's own type parameters. Only a default method has a body.
+    pub function: Symbol,
+    /// Whether the first parameter is `self`
+    pub takes_self: bool,
+    /// Whether the trait provides a body impls may leave out
+    pub has_default: bool,
+}
+
+/// An `impl Trait for Type` block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImplInfo {
+    /// Type parameters of the impl, which are the type arguments of the implementing type
+    pub type_parameters: Vec<Symbol>,
+    /// Traits the type arguments must implement for the impl to apply
+    pub bounds: HashMap<Symbol, Vec<Symbol>>,
+    /// Methods the impl defines; the others are the trait's defaults
+    pub methods: HashSet<Symbol>,
 }
 
 /// Type definition information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TypeInfo {
     Struct {
+        type_parameters: Vec<Symbol>,
         fields: HashMap<Symbol, HirType>,
     },
     Enum {
-        variants: HashMap<Symbol, Option<HirType>>,
+        type_parameters: Vec<Symbol>,
+        /// Payload fields of each variant in declaration order
+        variants: HashMap<Symbol, Vec<(Symbol, HirType)>>,
     },
 }
 
@@ -402,6 +629,46 @@ pub struct HirBuilder {
     type_table: TypeTable,
     errors: Vec<OvieError>,
     warnings: Vec<OvieError>,
+    /// Span of the statement or expression being transformed
+    current_span: SourceSpan,
+    /// Declared return type of the function being transformed, if it has one
+    return_type: Option<HirType>,
+    /// Type parameters of the item being transformed
+    type_parameters: Vec<Symbol>,
+    /// Type parameters of each generic function, used to instantiate calls
+    generic_functions: HashMap<Symbol, Vec<Symbol>>,
+    /// Traits the type parameters of each generic function must implement, checked at calls
+    function_bounds: HashMap<Symbol, HashMap<Symbol, Vec<Symbol>>>,
+    /// Traits each type parameter of the item being transformed implements
+    type_bounds: HashMap<Symbol, Vec<Symbol>>,
+    /// Functions declared `unsafe fn`, callable only from an unsafe context
+    unsafe_functions: HashSet<Symbol>,
+    /// Functions that never return, because they end by stopping the program
+    diverging_functions: HashSet<Symbol>,
+    /// Module-level constants, which closures use without capturing
+    constants: HashSet<Symbol>,
+    /// Declarations of loop and match variables, which cannot be declared `mut`
+    pattern_bindings: HashSet<SourceSpan>,
+    /// Whether the code being transformed is inside an unsafe block or unsafe function
+    in_unsafe: bool,
+    /// Type of `self` in the impl block being transformed
+    self_type: Option<HirType>,
+    /// Names visible in the module being transformed
+    module_scope: ModuleScope,
+    /// Path and file of the module being transformed, to attribute the names that do not resolve in it
+    module_origin: (ModulePath, Option<PathBuf>),
+    /// Scope and captured names of each closure being transformed, innermost last
+    closures: Vec<(usize, Vec<Symbol>)>,
+    /// Function type expected of the closure about to be transformed, from the parameter it is passed to
+    closure_signature: Option<HirType>,
+    /// Labels of the loops around the code being transformed, innermost last
+    loops: Vec<Option<Symbol>>,
+    /// Whether the function or closure being transformed returns a value anywhere
+    returns_value: bool,
+    /// Bindings of the inference variables
+    inference: InferenceTable,
+    /// Checks waiting for the types they are about to be inferred
+    constraints: Vec<Constraint>,
 }
 
 impl HirBuilder {
@@ -419,6 +686,26 @@ impl HirBuilder {
             type_table,
             errors: Vec::new(),
             warnings: Vec::new(),
+            current_span: SourceSpan::default(),
+            return_type: None,
+            type_parameters: Vec::new(),
+            generic_functions: HashMap::new(),
+            function_bounds: HashMap::new(),
+            type_bounds: HashMap::new(),
+            unsafe_functions: HashSet::new(),
+            diverging_functions: HashSet::new(),
+            constants: HashSet::new(),
+            pattern_bindings: HashSet::new(),
+            in_unsafe: false,
+            self_type: None,
+            module_scope: ModuleScope::default(),
+            module_origin: (ModulePath::root(), None),
+            closures: Vec::new(),
+            closure_signature: None,
+            loops: Vec::new(),
+            returns_value: false,
+            inference: InferenceTable::new(),
+            constraints: Vec::new(),
         }
     }
 
@@ -433,6 +720,14 @@ impl HirBuilder {
         };
         
         let _ = symbol_table.insert("print".to_string(), SymbolInfo {
+            symbol_type: print_type.clone(),
+            is_mutable: false,
+            is_function: true,
+            span: SourceSpan::default(),
+        });
+
+        // Stops the program with a message; core's panic calls it
+        let _ = symbol_table.insert("abort".to_string(), SymbolInfo {
             symbol_type: print_type,
             is_mutable: false,
             is_function: true,
@@ -451,6 +746,23 @@ impl HirBuilder {
             is_function: true,
             span: SourceSpan::default(),
         });
+
+        // Explicit numeric conversions: `to_number` is exact, `to_int` truncates toward zero
+        let numeric_conversions = [
+            ("to_number", HirType::Int, HirType::Number),
+            ("to_int", HirType::Number, HirType::Int),
+        ];
+        for (name, param, result) in numeric_conversions {
+            let _ = symbol_table.insert(name.to_string(), SymbolInfo {
+                symbol_type: HirType::Function {
+                    params: vec![param],
+                    return_type: Box::new(result),
+                },
+                is_mutable: false,
+                is_function: true,
+                span: SourceSpan::default(),
+            });
+        }
     }
 
     /// Generate next node ID
@@ -460,125 +772,325 @@ impl HirBuilder {
         id
     }
 
-    /// Transform AST to HIR with comprehensive error handling
+    /// Transform the AST of a single source file to HIR
     pub fn transform_ast(&mut self, ast: &AstNode) -> OvieResult<HirProgram> {
+        let graph = ModuleGraph::single(ast.clone())?;
+        self.transform_module_graph(&graph)
+    }
+
+    /// Transform every module of a program to one HIR program. Items of modules
+    /// other than the root are named by their qualified path (`a::b::name`).
+    pub fn transform_module_graph(&mut self, graph: &ModuleGraph) -> OvieResult<HirProgram> {
         let mut items = Vec::new();
         let mut has_main = false;
 
+        // Without an explicit main, top-level assignments are locals of the implicit main
+        let AstNode::Program(root_statements) = &graph.root().ast;
+        let has_explicit_main = root_statements.iter()
+            .any(|stmt| matches!(stmt, Statement::Function { name, .. } if name == "main"));
+
+        // Declare every type up front so definitions can refer to types defined after them
+        for module in graph.modules() {
+            let AstNode::Program(statements) = &module.ast;
+            for statement in statements {
+                let (name, info) = match statement {
+                    Statement::Struct { name, type_parameters, .. } => (name, TypeInfo::Struct {
+                        type_parameters: type_parameters.clone(),
+                        fields: HashMap::new(),
+                    }),
+                    Statement::Enum { name, type_parameters, .. } => (name, TypeInfo::Enum {
+                        type_parameters: type_parameters.clone(),
+                        variants: HashMap::new(),
+                    }),
+                    _ => continue,
+                };
+                self.type_table.types.insert(module.path.qualify(name), info);
+            }
+        }
+
         // First pass: collect type definitions and validate them
-        match &ast {
-            AstNode::Program(statements) => {
-                for statement in statements {
-                    match statement {
-                        Statement::Struct { name, fields } => {
-                            if let Err(e) = self.validate_struct_definition(name, fields) {
-                                self.errors.push(e);
-                                continue;
-                            }
-                            let hir_struct = self.transform_struct(name, fields)?;
-                            self.register_struct_type(name, fields)?;
-                            items.push(HirItem::Struct(hir_struct));
+        for module in graph.modules() {
+            self.module_scope = module.scope.clone();
+            let AstNode::Program(statements) = &module.ast;
+            for statement in statements {
+                match statement {
+                    Statement::Struct { name, type_parameters, fields, span, .. } => {
+                        let name = &module.path.qualify(name);
+                        self.type_parameters = type_parameters.clone();
+                        if let Err(e) = self.validate_struct_definition(name, fields) {
+                            self.errors.push(module.attribute_error(e.located_at(span.start_position())));
+                            continue;
                         }
-                        Statement::Enum { name, variants } => {
-                            if let Err(e) = self.validate_enum_definition(name, variants) {
-                                self.errors.push(e);
-                                continue;
-                            }
-                            let hir_enum = self.transform_enum(name, variants)?;
-                            self.register_enum_type(name, variants)?;
-                            items.push(HirItem::Enum(hir_enum));
+                        let hir_struct = self.transform_struct(name, fields, *span)
+                            .map_err(|e| module.attribute_error(e.located_at(span.start_position())))?;
+                        self.register_struct_type(name, fields)
+                            .map_err(|e| module.attribute_error(e))?;
+                        items.push(HirItem::Struct(hir_struct));
+                    }
+                    Statement::Enum { name, type_parameters, variants, span, .. } => {
+                        let name = &module.path.qualify(name);
+                        self.type_parameters = type_parameters.clone();
+                        if let Err(e) = self.validate_enum_definition(name, variants) {
+                            self.errors.push(module.attribute_error(e.located_at(span.start_position())));
+                            continue;
+                        }
+                        let hir_enum = self.transform_enum(name, variants, *span)
+                            .map_err(|e| module.attribute_error(e.located_at(span.start_position())))?;
+                        self.register_enum_type(name, variants)
+                            .map_err(|e| module.attribute_error(e))?;
+                        items.push(HirItem::Enum(hir_enum));
+                    }
+                    Statement::Trait { name, span, .. } => {
+                        let name = module.path.qualify(name);
+                        if self.type_table.traits.contains_key(&name) {
+                            self.errors.push(module.attribute_error(OvieError::semantic_error(
+                                0, 0,
+                                format!("Duplicate trait '{}'", name)
+                            ).located_at(span.start_position())));
+                            continue;
                         }
-                        _ => {}
+                        self.type_table.traits.insert(name, TraitInfo::default());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.type_parameters.clear();
+
+        // Declare the methods of every trait before impls and bounds refer to them
+        for module in graph.modules() {
+            self.module_scope = module.scope.clone();
+            let AstNode::Program(statements) = &module.ast;
+            for statement in statements {
+                if let Statement::Trait { name, methods, span, .. } = statement {
+                    let registered = self.register_trait(&module.path.qualify(name), methods);
+                    self.self_type = None;
+                    if let Err(e) = registered {
+                        self.errors.push(module.attribute_error(e.located_at(span.start_position())));
                     }
                 }
             }
         }
+        self.type_parameters.clear();
 
         // Second pass: collect function signatures and validate them
-        match &ast {
-            AstNode::Program(statements) => {
-                for statement in statements {
-                    if let Statement::Function { name, parameters, body: _ } = statement {
-                        if let Err(e) = self.validate_function_signature(name, parameters) {
-                            self.errors.push(e);
+        for module in graph.modules() {
+            self.module_scope = module.scope.clone();
+            let AstNode::Program(statements) = &module.ast;
+            for statement in statements {
+                if let Statement::Function { name, is_unsafe, type_parameters, bounds, parameters, return_type, span, .. } = statement {
+                    let name = &module.path.qualify(name);
+                    self.type_parameters = type_parameters.clone();
+                    if let Err(e) = self.validate_function_signature(name, parameters) {
+                        self.errors.push(module.attribute_error(e.located_at(span.start_position())));
+                        continue;
+                    }
+                    match self.resolve_bounds(bounds) {
+                        Ok(bounds) if bounds.is_empty() => {}
+                        Ok(bounds) => {
+                            self.function_bounds.insert(name.clone(), bounds);
+                        }
+                        Err(e) => {
+                            self.errors.push(module.attribute_error(e.located_at(span.start_position())));
                             continue;
                         }
-                        self.register_function(name, parameters)?;
+                    }
+                    if *is_unsafe {
+                        self.unsafe_functions.insert(name.clone());
+                    }
+                    self.register_function(name, parameters, return_type.as_ref(), *span)
+                        .map_err(|e| module.attribute_error(e.located_at(span.start_position())))?;
+                } else if let Statement::Const { name, type_annotation, span, .. } = statement {
+                    let name = &module.path.qualify(name);
+                    if let Err(e) = self.register_constant(name, type_annotation, *span) {
+                        self.errors.push(module.attribute_error(e.located_at(span.start_position())));
+                    }
+                } else if let Statement::Impl { trait_name, type_name, type_parameters, bounds, methods, span } = statement {
+                    let registered = self.register_impl(&module.path, trait_name.as_deref(), type_name, type_parameters, bounds, methods);
+                    self.self_type = None;
+                    if let Err(e) = registered {
+                        self.errors.push(module.attribute_error(e.located_at(span.start_position())));
                     }
                 }
             }
         }
+        self.type_parameters.clear();
+        self.collect_diverging_functions(graph);
 
         // Third pass: transform functions and other items with full context
-        match &ast {
-            AstNode::Program(statements) => {
-                for statement in statements {
-                    match statement {
-                        Statement::Function { name, parameters, body } => {
-                            match self.transform_function(name, parameters, body) {
-                                Ok(hir_function) => {
-                                    if name == "main" {
-                                        has_main = true;
-                                    }
-                                    items.push(HirItem::Function(hir_function));
-                                }
-                                Err(e) => {
-                                    self.errors.push(e);
+        for module in graph.modules() {
+            self.module_scope = module.scope.clone();
+            self.module_origin = (module.path.clone(), module.file.clone());
+            let AstNode::Program(statements) = &module.ast;
+            for statement in statements {
+                match statement {
+                    Statement::Function { name, is_const, is_unsafe, type_parameters, parameters, return_type, requires, ensures, body, span, .. } => {
+                        let name = &module.path.qualify(name);
+                        self.type_parameters = type_parameters.clone();
+                        self.type_bounds = self.function_bounds.get(name).cloned().unwrap_or_default();
+                        let hir_function = self.transform_function(name, *is_unsafe, parameters, return_type.as_ref(), body, *span)
+                            .map(|function| HirFunction { is_const: *is_const, ..function })
+                            .and_then(|function| self.transform_contracts(function, requires, ensures));
+                        self.type_parameters.clear();
+                        self.type_bounds.clear();
+                        match hir_function {
+                            Ok(hir_function) => {
+                                if name == "main" {
+                                    has_main = true;
                                 }
+                                items.push(HirItem::Function(hir_function));
+                            }
+                            Err(e) => {
+                                self.errors.push(module.attribute_error(e));
                             }
                         }
-                        Statement::Assignment { identifier, value, mutable } => {
-                            // Global variable
-                            match self.transform_global(identifier, value, *mutable) {
-                                Ok(hir_global) => {
-                                    items.push(HirItem::Global(hir_global));
-                                }
-                                Err(e) => {
-                                    self.errors.push(e);
-                                }
+                    }
+                    Statement::Assignment { identifier, value, mutable, span } if has_explicit_main => {
+                        // Global variable
+                        match self.transform_global(identifier, None, value, *mutable, *span) {
+                            Ok(hir_global) => {
+                                items.push(HirItem::Global(hir_global));
+                            }
+                            Err(e) => {
+                                self.errors.push(e);
+                            }
+                        }
+                    }
+                    Statement::VariableDeclaration { identifier, type_annotation, value, mutable, span } if has_explicit_main => {
+                        match self.transform_global(identifier, type_annotation.as_ref(), value, *mutable, *span) {
+                            Ok(hir_global) => {
+                                items.push(HirItem::Global(hir_global));
+                            }
+                            Err(e) => {
+                                self.errors.push(e);
+                            }
+                        }
+                    }
+                    Statement::Const { name, type_annotation, value, span, .. } => {
+                        let name = module.path.qualify(name);
+                        if !self.constants.contains(&name) {
+                            // Rejected in the second pass
+                            continue;
+                        }
+                        match self.transform_constant(&name, type_annotation, value, *span) {
+                            Ok(hir_global) => items.push(HirItem::Global(hir_global)),
+                            Err(e) => self.errors.push(module.attribute_error(e)),
+                        }
+                    }
+                    Statement::Impl { type_name, type_parameters, methods, .. } => {
+                        let type_name = match self.impl_target(type_name) {
+                            Ok(type_name) if self.type_table.methods.contains_key(&type_name) => type_name,
+                            // Rejected in the second pass
+                            _ => continue,
+                        };
+                        self.self_type = self.impl_self_type(&type_name, type_parameters).ok();
+                        for method in methods {
+                            let Statement::Function { name, is_const, is_unsafe, type_parameters: method_type_parameters, parameters, return_type, requires, ensures, body, span, .. } = method else {
+                                continue;
+                            };
+                            let name = format!("{}::{}", type_name, name);
+                            self.type_parameters = type_parameters.iter().chain(method_type_parameters).cloned().collect();
+                            self.type_bounds = self.function_bounds.get(&name).cloned().unwrap_or_default();
+                            let hir_function = self.transform_function(&name, *is_unsafe, parameters, return_type.as_ref(), body, *span)
+                                .map(|function| HirFunction { is_const: *is_const, ..function })
+                                .and_then(|function| self.transform_contracts(function, requires, ensures));
+                            match hir_function {
+                                Ok(hir_function) => items.push(HirItem::Function(hir_function)),
+                                Err(e) => self.errors.push(module.attribute_error(e)),
                             }
                         }
-                        Statement::Struct { .. } | Statement::Enum { .. } => {
-                            // Already handled in first pass
+                        self.type_parameters.clear();
+                        self.type_bounds.clear();
+                        self.self_type = None;
+                    }
+                    Statement::Trait { name, methods, .. } => {
+                        // Default methods are functions generic over `Self`
+                        let name = module.path.qualify(name);
+                        self.self_type = Some(HirType::Param("Self".to_string()));
+                        for method in methods {
+                            let function = format!("{}::{}", name, method.name);
+                            let (Some(body), Some(type_parameters)) = (&method.body, self.generic_functions.get(&function)) else {
+                                // Required, or rejected in the trait pass
+                                continue;
+                            };
+                            self.type_parameters = type_parameters.clone();
+                            self.type_bounds = self.function_bounds.get(&function).cloned().unwrap_or_default();
+                            let return_type = method.return_type.as_ref().unwrap_or(&TypeAnnotation::Unit);
+                            match self.transform_function(&function, false, &method.parameters, Some(return_type), body, method.span) {
+                                Ok(hir_function) => items.push(HirItem::Function(hir_function)),
+                                Err(e) => self.errors.push(module.attribute_error(e)),
+                            }
                         }
-                        _ => {
-                            // Other statements at top level - create implicit main
+                        self.type_parameters.clear();
+                        self.type_bounds.clear();
+                        self.self_type = None;
+                    }
+                    Statement::Struct { name, type_parameters, invariants, span, .. } if !invariants.is_empty() => {
+                        let name = module.path.qualify(name);
+                        let Some(hir_struct) = items.iter_mut().find_map(|item| match item {
+                            HirItem::Struct(hir_struct) if hir_struct.name == name => Some(hir_struct),
+                            _ => None,
+                        }) else {
+                            // Rejected in the first pass
+                            continue;
+                        };
+                        self.type_parameters = type_parameters.clone();
+                        let checked = self.impl_self_type(&name, type_parameters)
+                            .and_then(|self_type| self.transform_clauses(invariants, vec![("self".to_string(), self_type, *span)]));
+                        self.type_parameters.clear();
+                        match checked {
+                            Ok(invariants) => hir_struct.invariants = invariants,
+                            Err(e) => self.errors.push(module.attribute_error(e.located_at(span.start_position()))),
                         }
                     }
+                    Statement::Struct { .. } | Statement::Enum { .. } => {
+                        // Already handled in first pass
+                    }
+                    Statement::Use { .. } | Statement::Module { .. } => {
+                        // Resolved into the module scope by the module graph
+                    }
+                    _ => {
+                        // Other statements at top level - create implicit main
+                    }
                 }
             }
         }
 
-        // If no main function found, create an implicit one from top-level statements
+        // If no main function found, create an implicit one from the root module's top-level statements
         if !has_main {
-            match &ast {
-                AstNode::Program(statements) => {
-                    let main_statements: Vec<_> = statements.iter()
-                        .filter(|stmt| !matches!(stmt, 
-                            Statement::Function { .. } | 
-                            Statement::Struct { .. } | 
-                            Statement::Enum { .. } |
-                            Statement::Assignment { .. }
-                        ))
-                        .collect();
-
-                    if !main_statements.is_empty() {
-                        match self.create_implicit_main(&main_statements) {
-                            Ok(main_function) => {
-                                items.push(HirItem::Function(main_function));
-                                has_main = true;
-                            }
-                            Err(e) => {
-                                self.errors.push(e);
-                            }
-                        }
+            self.module_scope = graph.root().scope.clone();
+            self.module_origin = (graph.root().path.clone(), graph.root().file.clone());
+            let main_statements: Vec<_> = root_statements.iter()
+                .filter(|stmt| !matches!(stmt, 
+                    Statement::Function { .. } | 
+                    Statement::Const { .. } |
+                    Statement::Struct { .. } | 
+                    Statement::Enum { .. } |
+                    Statement::Impl { .. } |
+                    Statement::Trait { .. } |
+                    Statement::Use { .. } |
+                    Statement::Module { .. }
+                ))
+                .collect();
+
+            if !main_statements.is_empty() {
+                match self.create_implicit_main(&main_statements) {
+                    Ok(main_function) => {
+                        items.push(HirItem::Function(main_function));
+                        has_main = true;
+                    }
+                    Err(e) => {
+                        self.errors.push(e);
                     }
                 }
             }
         }
+        self.module_scope = ModuleScope::default();
+        self.module_origin = (ModulePath::root(), None);
 
         // Perform final validation and type inference
         self.perform_type_inference(&mut items)?;
+        self.evaluate_constants(&mut items);
         self.validate_program_semantics(&items)?;
 
         let metadata = HirMetadata {
@@ -602,8 +1114,29 @@ impl HirBuilder {
         })
     }
 
+    /// Transform every module of a program to one HIR program, collecting every error
+    /// instead of stopping at the first
+    ///
+    /// Returns the program only if there were no errors. The diagnostics list the
+    /// errors in source order, so tools like `ovie check` and `ovie fix` see every
+    /// name that does not resolve, with its suggestions.
+    pub fn transform_with_diagnostics(&mut self, graph: &ModuleGraph) -> (Option<HirProgram>, Vec<Diagnostic>) {
+        match self.transform_module_graph(graph) {
+            Ok(hir) => (Some(hir), Vec::new()),
+            Err(error) => {
+                let mut errors = std::mem::take(&mut self.errors);
+                if !errors.contains(&error) {
+                    errors.push(error);
+                }
+                let mut diagnostics: Vec<Diagnostic> = errors.iter().map(OvieError::to_diagnostic).collect();
+                diagnostics.sort_by_key(|diagnostic| (diagnostic.location.line, diagnostic.location.column));
+                (None, diagnostics)
+            }
+        }
+    }
+
     /// Transform a struct definition
-    fn transform_struct(&mut self, name: &str, fields: &[crate::ast::StructField]) -> OvieResult<HirStruct> {
+    fn transform_struct(&mut self, name: &str, fields: &[crate::ast::StructField], span: SourceSpan) -> OvieResult<HirStruct> {
         let mut hir_fields = Vec::new();
         
         for field in fields {
@@ -611,148 +1144,525 @@ impl HirBuilder {
             hir_fields.push(HirField {
                 name: field.name.clone(),
                 field_type,
-                span: SourceSpan::default(),
+                span,
             });
         }
 
         Ok(HirStruct {
             id: self.next_id(),
             name: name.to_string(),
+            type_parameters: self.type_parameters.clone(),
             fields: hir_fields,
-            span: SourceSpan::default(),
+            // Transformed once every function signature is known
+            invariants: Vec::new(),
+            span,
         })
     }
 
     /// Transform an enum definition
-    fn transform_enum(&mut self, name: &str, variants: &[crate::ast::EnumVariant]) -> OvieResult<HirEnum> {
+    fn transform_enum(&mut self, name: &str, variants: &[crate::ast::EnumVariant], span: SourceSpan) -> OvieResult<HirEnum> {
         let mut hir_variants = Vec::new();
         
         for variant in variants {
-            let data_type = if let Some(ref type_name) = variant.data_type {
-                Some(self.resolve_type(type_name)?)
-            } else {
-                None
-            };
+            let mut fields = Vec::new();
+            for (field_name, type_name) in variant.payload.fields() {
+                fields.push(HirField {
+                    name: field_name,
+                    field_type: self.resolve_type(type_name)?,
+                    span,
+                });
+            }
             
             hir_variants.push(HirVariant {
                 name: variant.name.clone(),
-                data_type,
-                span: SourceSpan::default(),
+                fields,
+                span,
             });
         }
 
         Ok(HirEnum {
             id: self.next_id(),
             name: name.to_string(),
+            type_parameters: self.type_parameters.clone(),
             variants: hir_variants,
-            span: SourceSpan::default(),
+            span,
         })
     }
 
-    /// Transform a function definition
-    fn transform_function(&mut self, name: &str, parameters: &[String], body: &[Statement]) -> OvieResult<HirFunction> {
+    /// Transform a function definition; callers mark `const fn`s and attach contracts
+    fn transform_function(&mut self, name: &str, is_unsafe: bool, parameters: &[Parameter], return_type: Option<&TypeAnnotation>, body: &[Statement], span: SourceSpan) -> OvieResult<HirFunction> {
+        let declared_return = match return_type {
+            Some(annotation) => Some(self.resolve_type(annotation)
+                .map_err(|e| e.located_at(span.start_position()))?),
+            None => None,
+        };
+
+        // The body is checked against the signature callers see, inference variables included
+        let signature = match self.symbol_table.lookup(name) {
+            Ok(SymbolInfo { symbol_type: HirType::Function { params, return_type }, is_function: true, .. })
+                if params.len() == parameters.len() => Some((params, *return_type)),
+            _ => None,
+        };
+        let is_declared = declared_return.is_some();
+        let return_type = match (declared_return, &signature) {
+            (Some(declared), _) => declared,
+            (None, Some((_, return_type))) => return_type.clone(),
+            (None, None) => HirType::Infer(self.next_id()),
+        };
+
         self.symbol_table.enter_scope();
 
         // Add parameters to scope
         let mut hir_params = Vec::new();
-        for param in parameters {
-            let param_type = HirType::Infer(self.next_id()); // Type inference
-            self.symbol_table.insert(param.clone(), SymbolInfo {
+        for (index, param) in parameters.iter().enumerate() {
+            let param_type = match &signature {
+                Some((params, _)) => params[index].clone(),
+                None => self.resolve_parameter_type(param)?,
+            };
+            self.symbol_table.insert(param.name.clone(), SymbolInfo {
                 symbol_type: param_type.clone(),
-                is_mutable: false,
+                is_mutable: param.mutable,
                 is_function: false,
-                span: SourceSpan::default(),
-            })?;
+                span: param.span,
+            }).map_err(|e| e.located_at(param.span.start_position()))?;
             
             hir_params.push(HirParameter {
-                name: param.clone(),
+                name: param.name.clone(),
                 param_type,
-                span: SourceSpan::default(),
+                span: param.span,
             });
         }
 
-        // Transform function body
-        let hir_body = self.transform_block(body)?;
+        // Transform function body, unifying the type of each returned value with the return type
+        self.in_unsafe = is_unsafe;
+        let hir_body = self.transform_body(body, span, &return_type, is_declared);
+        self.in_unsafe = false;
         
         self.symbol_table.exit_scope();
+        let hir_body = hir_body?;
 
         Ok(HirFunction {
             id: self.next_id(),
             name: name.to_string(),
+            type_parameters: self.type_parameters.clone(),
             parameters: hir_params,
-            return_type: HirType::Infer(self.next_id()), // Type inference
+            return_type,
             body: hir_body,
-            span: SourceSpan::default(),
+            span,
             is_main: name == "main",
+            is_const: false,
+            is_unsafe,
+            requires: Vec::new(),
+            ensures: Vec::new(),
         })
     }
 
-    /// Transform a global variable
-    fn transform_global(&mut self, name: &str, value: &Expression, is_mutable: bool) -> OvieResult<HirGlobal> {
-        let initializer = self.transform_expression(value)?;
-        let global_type = initializer.expr_type.clone();
+    /// Transform the body of a function or closure returning `return_type`. Without a
+    /// declared return type, a body that never returns a value returns unit.
+    fn transform_body(&mut self, body: &[Statement], span: SourceSpan, return_type: &HirType, is_declared: bool) -> OvieResult<HirBlock> {
+        let outer_return = self.return_type.replace(return_type.clone());
+        let outer_returns_value = std::mem::replace(&mut self.returns_value, false);
+        let outer_loops = std::mem::take(&mut self.loops);
+        let hir_body = self.transform_block(body, span);
+        let returns_value = std::mem::replace(&mut self.returns_value, outer_returns_value);
+        self.loops = outer_loops;
+        self.return_type = outer_return;
+
+        let mut hir_body = hir_body?;
+        if !returns_value && !is_declared {
+            self.unify(return_type, &HirType::Unit, span)?;
+        }
+        if matches!(self.inference.resolve(return_type), HirType::Unit | HirType::Error) {
+            return Ok(hir_body);
+        }
 
-        Ok(HirGlobal {
-            id: self.next_id(),
-            name: name.to_string(),
-            global_type,
-            is_mutable,
-            initializer: Some(initializer),
-            span: SourceSpan::default(),
-        })
+        // A final expression is the returned value; without one, every path has to return
+        let has_tail = matches!(hir_body.statements.last().map(|statement| &statement.kind),
+            Some(HirStatementKind::Expression(tail)) if self.known_type(&tail.expr_type) != HirType::Unit);
+        if has_tail {
+            if let Some(HirStatement { kind: HirStatementKind::Expression(tail), .. }) = hir_body.statements.pop() {
+                let tail_span = tail.span;
+                let tail = self.coerce(tail, return_type).map_err(|e| e.located_at(tail_span.start_position()))?;
+                hir_body.statements.push(HirStatement {
+                    id: self.next_id(),
+                    kind: HirStatementKind::Expression(tail),
+                    span: tail_span,
+                });
+            }
+        } else if self.can_complete(&hir_body) {
+            let return_type = self.inference.resolve(return_type).to_string();
+            let error = OvieError::type_error(0, 0, &return_type, "Unit", vec![ErrorSuggestion::type_fix(
+                format!("Return a value of type {} at the end of the body, or stop the program with `abort`", return_type),
+                0.8,
+                None,
+            )]);
+            return Err(error.located_at(span.start_position()));
+        }
+        Ok(hir_body)
     }
 
-    /// Create implicit main function from top-level statements
-    fn create_implicit_main(&mut self, statements: &[&Statement]) -> OvieResult<HirFunction> {
-        let mut hir_statements = Vec::new();
-        
-        for statement in statements {
-            let hir_stmt = self.transform_statement(statement)?;
-            hir_statements.push(hir_stmt);
+    /// Record the functions that never return: those without a return type whose body ends
+    /// by calling `abort` or another function that never returns
+    fn collect_diverging_functions(&mut self, graph: &ModuleGraph) {
+        loop {
+            let mut found = false;
+            for module in graph.modules() {
+                let AstNode::Program(statements) = &module.ast;
+                for statement in statements {
+                    let Statement::Function { name, return_type: None, body, .. } = statement else {
+                        continue;
+                    };
+                    let ends_by_stopping = matches!(body.last(), Some(Statement::Expression { expression: Expression::Call { function, .. }, .. })
+                        if function == "abort" || module.scope.resolve(function).is_ok_and(|function| self.diverging_functions.contains(&function)));
+                    if ends_by_stopping && self.diverging_functions.insert(module.path.qualify(name)) {
+                        found = true;
+                    }
+                }
+            }
+            if !found {
+                break;
+            }
         }
+    }
 
-        Ok(HirFunction {
-            id: self.next_id(),
-            name: "main".to_string(),
-            parameters: Vec::new(),
-            return_type: HirType::Unit,
-            body: HirBlock {
-                statements: hir_statements,
-                span: SourceSpan::default(),
-            },
-            span: SourceSpan::default(),
-            is_main: true,
+    /// Whether running `block` can reach its end, rather than always returning or stopping the program
+    fn can_complete(&self, block: &HirBlock) -> bool {
+        !block.statements.iter().any(|statement| match &statement.kind {
+            HirStatementKind::Return(_) => true,
+            HirStatementKind::Expression(expression) => self.diverges(expression),
+            HirStatementKind::If { then_block, else_block: Some(else_block), .. } => {
+                !self.can_complete(then_block) && !self.can_complete(else_block)
+            }
+            HirStatementKind::Unsafe { body } => !self.can_complete(body),
+            HirStatementKind::While { condition, body, .. } => {
+                matches!(condition.kind, HirExpressionKind::Literal(HirLiteral::Boolean(true))) && !Self::breaks(body)
+            }
+            _ => false,
         })
     }
 
-    /// Transform a block of statements
-    fn transform_block(&mut self, statements: &[Statement]) -> OvieResult<HirBlock> {
-        let mut hir_statements = Vec::new();
-        
-        for statement in statements {
-            let hir_stmt = self.transform_statement(statement)?;
-            hir_statements.push(hir_stmt);
+    /// Whether evaluating `expression` always stops the program or returns from the function
+    fn diverges(&self, expression: &HirExpression) -> bool {
+        match &expression.kind {
+            HirExpressionKind::Call { function, .. } => function == "abort" || self.diverging_functions.contains(function),
+            HirExpressionKind::Match { arms, .. } => arms.iter().all(|arm| match &arm.value {
+                Some(value) => self.diverges(value),
+                None => !self.can_complete(&arm.body),
+            }),
+            _ => false,
         }
+    }
 
-        Ok(HirBlock {
-            statements: hir_statements,
-            span: SourceSpan::default(),
+    /// Whether `block` contains a `break`, which may leave the loop around it
+    fn breaks(block: &HirBlock) -> bool {
+        block.statements.iter().any(|statement| match &statement.kind {
+            HirStatementKind::Break { .. } => true,
+            HirStatementKind::If { then_block, else_block, .. } => {
+                Self::breaks(then_block) || else_block.as_ref().is_some_and(Self::breaks)
+            }
+            HirStatementKind::While { body, .. } | HirStatementKind::For { body, .. } | HirStatementKind::Unsafe { body } => Self::breaks(body),
+            HirStatementKind::Expression(HirExpression { kind: HirExpressionKind::Match { arms, .. }, .. }) => {
+                arms.iter().any(|arm| Self::breaks(&arm.body))
+            }
+            _ => false,
         })
     }
 
-    /// Transform a statement
+    /// Transform the `requires` and `ensures` clauses of a function. Both see its
+    /// parameters; `ensures` also sees the returned value as `result`.
+    fn transform_contracts(&mut self, mut function: HirFunction, requires: &[ContractClause], ensures: &[ContractClause]) -> OvieResult<HirFunction> {
+        let parameters: Vec<_> = function.parameters.iter()
+            .map(|param| (param.name.clone(), param.param_type.clone(), param.span))
+            .collect();
+        function.requires = self.transform_clauses(requires, parameters.clone())
+            .map_err(|e| e.located_at(function.span.start_position()))?;
+
+        if !ensures.is_empty() {
+            if let HirType::Infer(_) = function.return_type {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("Function '{}' has 'ensures' clauses, so it must declare its return type", function.name)
+                ).located_at(function.span.start_position()));
+            }
+            let mut names = parameters;
+            names.push(("result".to_string(), function.return_type.clone(), function.span));
+            function.ensures = self.transform_clauses(ensures, names)
+                .map_err(|e| e.located_at(function.span.start_position()))?;
+        }
+        Ok(function)
+    }
+
+    /// Transform contract clauses in a scope of their own holding `names`
+    fn transform_clauses(&mut self, clauses: &[ContractClause], names: Vec<(String, HirType, SourceSpan)>) -> OvieResult<Vec<HirContract>> {
+        self.symbol_table.enter_scope();
+        let contracts = names.into_iter()
+            .try_for_each(|(name, symbol_type, span)| self.symbol_table.insert(name, SymbolInfo {
+                symbol_type,
+                is_mutable: false,
+                is_function: false,
+                span,
+            }))
+            .and_then(|_| clauses.iter().map(|clause| self.transform_clause(clause)).collect());
+        self.symbol_table.exit_scope();
+        contracts
+    }
+
+    /// Transform the condition of a contract clause, which must be a Boolean
+    fn transform_clause(&mut self, clause: &ContractClause) -> OvieResult<HirContract> {
+        let condition = self.transform_expression(&clause.condition)?;
+        match self.inference.unify(&HirType::Boolean, &condition.expr_type, condition.span) {
+            Ok(()) => Ok(HirContract { condition, text: clause.text.clone() }),
+            Err(_) => Err(OvieError::semantic_error(
+                0, 0,
+                format!("Contract condition '{}' must be a Boolean, found {}", clause.text, self.inference.resolve(&condition.expr_type))
+            ).located_at(clause.condition.span().start_position())),
+        }
+    }
+
+    /// Declared type of a parameter, or a fresh inference variable
+    fn resolve_parameter_type(&mut self, param: &Parameter) -> OvieResult<HirType> {
+        match (&param.type_annotation, &self.self_type) {
+            (Some(annotation), _) => self.resolve_type(annotation)
+                .map_err(|e| e.located_at(param.span.start_position())),
+            // `self` in a method is the type the impl block is for
+            (None, Some(self_type)) if param.name == "self" => Ok(self_type.clone()),
+            (None, _) => Ok(HirType::Infer(self.next_id())),
+        }
+    }
+
+    /// Transform a global variable
+    fn transform_global(&mut self, name: &str, type_annotation: Option<&TypeAnnotation>, value: &Expression, is_mutable: bool, span: SourceSpan) -> OvieResult<HirGlobal> {
+        let initializer = self.transform_expression(value)?;
+        let (global_type, initializer) = self.declared_type(type_annotation, initializer)
+            .map_err(|e| e.located_at(span.start_position()))?;
+
+        Ok(HirGlobal {
+            id: self.next_id(),
+            name: name.to_string(),
+            global_type,
+            is_mutable,
+            is_const: false,
+            initializer: Some(initializer),
+            span,
+        })
+    }
+
+    /// Declare a module-level constant so that code anywhere in the program can use it
+    fn register_constant(&mut self, name: &str, type_annotation: &TypeAnnotation, span: SourceSpan) -> OvieResult<()> {
+        let const_type = self.resolve_type(type_annotation)?;
+        if !is_const_type(&const_type) {
+            return Err(OvieError::semantic_error(
+                0, 0,
+                format!("Constant '{}' has type {}; constants must be Int, Number, String or Boolean", name, const_type)
+            ));
+        }
+        self.symbol_table.insert(name.to_string(), SymbolInfo {
+            symbol_type: const_type,
+            is_mutable: false,
+            is_function: false,
+            span,
+        })?;
+        self.constants.insert(name.to_string());
+        Ok(())
+    }
+
+    /// Transform the initializer of a constant; it is evaluated once the whole program is checked
+    fn transform_constant(&mut self, name: &str, type_annotation: &TypeAnnotation, value: &Expression, span: SourceSpan) -> OvieResult<HirGlobal> {
+        let initializer = self.transform_expression(value)?;
+        let (global_type, initializer) = self.declared_type(Some(type_annotation), initializer)
+            .map_err(|e| e.located_at(span.start_position()))?;
+
+        Ok(HirGlobal {
+            id: self.next_id(),
+            name: name.to_string(),
+            global_type,
+            is_mutable: false,
+            is_const: true,
+            initializer: Some(initializer),
+            span,
+        })
+    }
+
+    /// Check each `const fn` and replace the initializer of each constant with its value
+    fn evaluate_constants(&mut self, items: &mut [HirItem]) {
+        let mut evaluator = ConstEvaluator::new(items);
+        for item in items.iter() {
+            if let HirItem::Function(function) = item {
+                if function.is_const {
+                    if let Err(e) = evaluator.check_function(function) {
+                        self.errors.push(e);
+                    }
+                }
+            }
+        }
+        let values: Vec<_> = items.iter().enumerate()
+            .filter_map(|(index, item)| match item {
+                HirItem::Global(global) if global.is_const => Some((index, evaluator.evaluate(&global.name))),
+                _ => None,
+            })
+            .collect();
+
+        for (index, value) in values {
+            let HirItem::Global(global) = &mut items[index] else { continue };
+            match (value, &mut global.initializer) {
+                (Ok(value), Some(initializer)) => {
+                    initializer.kind = HirExpressionKind::Literal(value);
+                    initializer.expr_type = global.global_type.clone();
+                }
+                (Err(e), _) => self.errors.push(e),
+                _ => {}
+            }
+        }
+    }
+
+    /// Create implicit main function from top-level statements
+    fn create_implicit_main(&mut self, statements: &[&Statement]) -> OvieResult<HirFunction> {
+        let span = Self::statements_span(statements.iter().copied());
+        let mut hir_statements = Vec::new();
+        
+        for statement in statements {
+            let hir_stmt = self.transform_statement(statement)?;
+            hir_statements.push(hir_stmt);
+        }
+
+        Ok(HirFunction {
+            id: self.next_id(),
+            name: "main".to_string(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: HirType::Unit,
+            body: HirBlock {
+                statements: hir_statements,
+                span,
+            },
+            span,
+            is_main: true,
+            is_const: false,
+            is_unsafe: false,
+            requires: Vec::new(),
+            ensures: Vec::new(),
+        })
+    }
+
+    /// Span covering a sequence of statements
+    fn statements_span<'a>(mut statements: impl Iterator<Item = &'a Statement>) -> SourceSpan {
+        match statements.next() {
+            Some(first) => statements.fold(first.span(), |span, stmt| span.to(&stmt.span())),
+            None => SourceSpan::default(),
+        }
+    }
+
+    /// Transform a block of statements; `enclosing` is used for empty blocks
+    fn transform_block(&mut self, statements: &[Statement], enclosing: SourceSpan) -> OvieResult<HirBlock> {
+        let mut hir_statements = Vec::new();
+        
+        for statement in statements {
+            let hir_stmt = self.transform_statement(statement)?;
+            hir_statements.push(hir_stmt);
+        }
+
+        let span = if statements.is_empty() {
+            enclosing
+        } else {
+            Self::statements_span(statements.iter())
+        };
+
+        Ok(HirBlock {
+            statements: hir_statements,
+            span,
+        })
+    }
+
+    /// Transform a statement, locating any error at the statement
     fn transform_statement(&mut self, statement: &Statement) -> OvieResult<HirStatement> {
+        let outer = std::mem::replace(&mut self.current_span, statement.span());
+        let result = self.build_statement(statement)
+            .map_err(|e| e.located_at(self.current_span.start_position()));
+        self.current_span = outer;
+        result
+    }
+
+    fn build_statement(&mut self, statement: &Statement) -> OvieResult<HirStatement> {
         let kind = match statement {
-            Statement::Assignment { identifier, value, mutable } => {
+            Statement::Assignment { identifier, value, mutable, .. } => {
                 let hir_value = self.transform_expression(value)?;
                 let var_type = hir_value.expr_type.clone();
+
+                // Assigning to a variable that is already in scope updates it in place
+                if !*mutable {
+                    if let Ok(existing) = self.symbol_table.lookup(identifier) {
+                        if !existing.is_function {
+                            if self.is_captured(identifier) {
+                                return Err(OvieError::semantic_error(
+                                    0, 0,
+                                    format!("Closures cannot assign to captured variable '{}'", identifier)
+                                ));
+                            }
+                            let span = hir_value.span;
+                            let hir_value = self.coerce(hir_value, &existing.symbol_type)
+                                .map_err(|e| e.located_at(span.start_position()))?;
+                            let target = HirPlace {
+                                kind: HirPlaceKind::Local(identifier.clone()),
+                                place_type: existing.symbol_type,
+                                span: self.current_span,
+                            };
+                            self.check_place_mutable(&target);
+                            return Ok(HirStatement {
+                                id: self.next_id(),
+                                kind: HirStatementKind::Assign { target, value: hir_value },
+                                span: self.current_span,
+                            });
+                        }
+                    }
+                }
                 
                 // Add to symbol table
                 self.symbol_table.insert(identifier.clone(), SymbolInfo {
                     symbol_type: var_type.clone(),
                     is_mutable: *mutable,
                     is_function: false,
-                    span: SourceSpan::default(),
+                    span: self.current_span,
+                })?;
+
+                HirStatementKind::Local {
+                    name: identifier.clone(),
+                    var_type,
+                    is_mutable: *mutable,
+                    initializer: Some(hir_value),
+                }
+            }
+            Statement::PlaceAssignment { target, operator, value, .. } => {
+                let hir_value = self.transform_expression(value)?;
+                let place = self.transform_place(target)?;
+                self.check_place_mutable(&place);
+
+                let span = hir_value.span;
+                match operator {
+                    None => {
+                        let hir_value = self.coerce(hir_value, &place.place_type)
+                            .map_err(|e| e.located_at(span.start_position()))?;
+                        HirStatementKind::Assign { target: place, value: hir_value }
+                    }
+                    Some(operator) => {
+                        let op = self.transform_binary_op(operator);
+
+                        // The result is stored back, so it must keep the type of the place
+                        let result_type = self.check_binary_op_type(&place.place_type, &op, &hir_value.expr_type, span)?;
+                        self.unify(&place.place_type, &result_type, span)?;
+                        HirStatementKind::CompoundAssign { target: place, op, value: hir_value }
+                    }
+                }
+            }
+            Statement::VariableDeclaration { identifier, type_annotation, value, mutable, .. } => {
+                let hir_value = self.transform_expression(value)?;
+                let (var_type, hir_value) = self.declared_type(type_annotation.as_ref(), hir_value)?;
+
+                // `let` always introduces a new binding, shadowing any outer one
+                self.symbol_table.insert(identifier.clone(), SymbolInfo {
+                    symbol_type: var_type.clone(),
+                    is_mutable: *mutable,
+                    is_function: false,
+                    span: self.current_span,
                 })?;
 
                 HirStatementKind::Local {
@@ -762,27 +1672,49 @@ impl HirBuilder {
                     initializer: Some(hir_value),
                 }
             }
-            Statement::Print { expression } => {
+            Statement::Destructure { mutable, pattern, type_annotation, value, .. } => {
+                let hir_value = self.transform_expression(value)?;
+                let (value_type, hir_value) = self.declared_type(type_annotation.as_ref(), hir_value)?;
+                let pattern = self.transform_binding_pattern(pattern, &value_type, *mutable)?;
+
+                HirStatementKind::Destructure {
+                    pattern,
+                    is_mutable: *mutable,
+                    initializer: hir_value,
+                }
+            }
+            Statement::Print { expression, .. } => {
                 let hir_expr = self.transform_expression(expression)?;
                 HirStatementKind::Print(hir_expr)
             }
-            Statement::Return { value } => {
-                let hir_value = if let Some(expr) = value {
+            Statement::Return { value, .. } => {
+                let mut hir_value = if let Some(expr) = value {
                     Some(self.transform_expression(expr)?)
                 } else {
                     None
                 };
+
+                if let Some(expected) = self.return_type.clone() {
+                    match hir_value {
+                        Some(value) => {
+                            self.returns_value = true;
+                            hir_value = Some(self.coerce(value, &expected)?);
+                        }
+                        None => self.check_assignable(&expected, &HirType::Unit)?,
+                    }
+                }
+
                 HirStatementKind::Return(hir_value)
             }
-            Statement::Expression { expression } => {
+            Statement::Expression { expression, .. } => {
                 let hir_expr = self.transform_expression(expression)?;
                 HirStatementKind::Expression(hir_expr)
             }
-            Statement::If { condition, then_block, else_block } => {
-                let hir_condition = self.transform_expression(condition)?;
-                let hir_then = self.transform_block(then_block)?;
+            Statement::If { condition, then_block, else_block, .. } => {
+                let hir_condition = self.transform_condition(condition)?;
+                let hir_then = self.transform_block(then_block, self.current_span)?;
                 let hir_else = if let Some(else_stmts) = else_block {
-                    Some(self.transform_block(else_stmts)?)
+                    Some(self.transform_block(else_stmts, self.current_span)?)
                 } else {
                     None
                 };
@@ -793,25 +1725,81 @@ impl HirBuilder {
                     else_block: hir_else,
                 }
             }
-            Statement::While { condition, body } => {
-                let hir_condition = self.transform_expression(condition)?;
-                let hir_body = self.transform_block(body)?;
+            Statement::While { label, condition, body, .. } => {
+                let hir_condition = self.transform_condition(condition)?;
+                self.enter_loop(label)?;
+                let hir_body = self.transform_block(body, self.current_span);
+                self.loops.pop();
                 
                 HirStatementKind::While {
+                    label: label.clone(),
                     condition: hir_condition,
-                    body: hir_body,
+                    body: hir_body?,
                 }
             }
-            Statement::For { identifier, iterable, body } => {
+            Statement::For { label, pattern, iterable, body, .. } => {
                 let hir_iterable = self.transform_expression(iterable)?;
-                let hir_body = self.transform_block(body)?;
+                let element_type = match self.known_type(&hir_iterable.expr_type) {
+                    HirType::Range(inner) | HirType::Array(inner) => *inner,
+                    HirType::Error => HirType::Error,
+                    HirType::Infer(_) => {
+                        // Inferred once the iterable's type is
+                        let element = HirType::Infer(self.next_id());
+                        self.constraints.push(Constraint::Iterate {
+                            iterable: hir_iterable.expr_type.clone(),
+                            element: element.clone(),
+                            span: hir_iterable.span,
+                        });
+                        element
+                    }
+                    _ => return Err(self.inferred_mismatch("array or range", &hir_iterable.expr_type, hir_iterable.span)),
+                };
+
+                // The loop variables are only visible inside the body
+                self.symbol_table.enter_scope();
+                let hir_pattern = match self.transform_binding_pattern(pattern, &element_type, false) {
+                    Ok(hir_pattern) => hir_pattern,
+                    Err(e) => {
+                        self.symbol_table.exit_scope();
+                        return Err(e);
+                    }
+                };
+                for name in pattern.bindings() {
+                    if let Ok(info) = self.symbol_table.lookup(name) {
+                        self.pattern_bindings.insert(info.span);
+                    }
+                }
+                self.enter_loop(label)?;
+                let hir_body = self.transform_block(body, self.current_span);
+                self.loops.pop();
+                self.symbol_table.exit_scope();
+                let hir_body = hir_body?;
                 
                 HirStatementKind::For {
-                    variable: identifier.clone(),
+                    label: label.clone(),
+                    pattern: hir_pattern,
                     iterable: hir_iterable,
                     body: hir_body,
                 }
             }
+            Statement::Unsafe { body, .. } => {
+                let outer = std::mem::replace(&mut self.in_unsafe, true);
+                let hir_body = self.transform_block(body, self.current_span);
+                self.in_unsafe = outer;
+
+                HirStatementKind::Unsafe { body: hir_body? }
+            }
+            Statement::Break { label, .. } => {
+                self.check_loop_exit("break", label)?;
+                HirStatementKind::Break { label: label.clone() }
+            }
+            Statement::Continue { label, .. } => {
+                self.check_loop_exit("continue", label)?;
+                HirStatementKind::Continue { label: label.clone() }
+            }
+            Statement::Error { .. } => {
+                return Err(OvieError::parse_error(0, 0, "Cannot lower a statement that failed to parse"));
+            }
             _ => {
                 return Err(OvieError::SemanticError {
                     line: 0,
@@ -824,32 +1812,70 @@ impl HirBuilder {
         Ok(HirStatement {
             id: self.next_id(),
             kind,
-            span: SourceSpan::default(),
+            span: self.current_span,
         })
     }
 
-    /// Transform an expression
+    /// Transform the condition of an `if` or `while`; one of a type not known yet is a Boolean
+    fn transform_condition(&mut self, condition: &Expression) -> OvieResult<HirExpression> {
+        let hir_condition = self.transform_expression(condition)?;
+        if let HirType::Infer(_) = self.inference.shallow(&hir_condition.expr_type) {
+            self.unify(&HirType::Boolean, &hir_condition.expr_type, hir_condition.span)?;
+        }
+        Ok(hir_condition)
+    }
+
+    /// Transform an expression, locating any error at the innermost expression
     fn transform_expression(&mut self, expression: &Expression) -> OvieResult<HirExpression> {
+        let outer = std::mem::replace(&mut self.current_span, expression.span());
+        let result = self.build_expression(expression)
+            .map_err(|e| e.located_at(self.current_span.start_position()));
+        self.current_span = outer;
+        result
+    }
+
+    fn build_expression(&mut self, expression: &Expression) -> OvieResult<HirExpression> {
         let (kind, expr_type) = match expression {
-            Expression::Literal(literal) => {
+            Expression::Literal(literal, _) => {
                 let (hir_literal, hir_type) = match literal {
                     Literal::String(s) => (HirLiteral::String(s.clone()), HirType::String),
                     Literal::Number(n) => (HirLiteral::Number(*n), HirType::Number),
+                    Literal::Integer(n) => (HirLiteral::Integer(*n), HirType::Int),
                     Literal::Boolean(b) => (HirLiteral::Boolean(*b), HirType::Boolean),
                 };
                 (HirExpressionKind::Literal(hir_literal), hir_type)
             }
-            Expression::Identifier(name) => {
-                let symbol_info = self.symbol_table.lookup(name)?;
-                (HirExpressionKind::Variable(name.clone()), symbol_info.symbol_type)
+            Expression::Identifier(written, span) => {
+                let name = self.resolve_name(written)?;
+                let Ok(symbol_info) = self.symbol_table.lookup(&name) else {
+                    let candidates = self.names_in_scope(false);
+                    let message = format!("Undefined variable '{}'", written);
+                    return Ok(self.unresolved(unresolved_name(
+                        ResolutionErrorKind::Variable, written, message, *span, candidates.iter().map(String::as_str)
+                    )));
+                };
+                if symbol_info.is_function && self.generic_functions.contains_key(&name) {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Generic function '{}' must be called; it cannot be used as a value", name)
+                    ));
+                }
+                if symbol_info.is_function && self.unsafe_functions.contains(&name) {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Unsafe function '{}' must be called; it cannot be used as a value", name)
+                    ));
+                }
+                self.capture(&name);
+                (HirExpressionKind::Variable(name), symbol_info.symbol_type)
             }
-            Expression::Binary { left, operator, right } => {
+            Expression::Binary { left, operator, right, .. } => {
                 let hir_left = self.transform_expression(left)?;
                 let hir_right = self.transform_expression(right)?;
                 let hir_op = self.transform_binary_op(operator);
                 
-                // Type checking for binary operations
-                let result_type = self.check_binary_op_type(&hir_left.expr_type, &hir_op, &hir_right.expr_type)?;
+                // Type checking for binary operations; mixing Int and Number needs an explicit conversion
+                let result_type = self.check_binary_op_type(&hir_left.expr_type, &hir_op, &hir_right.expr_type, self.current_span)?;
                 
                 (HirExpressionKind::Binary {
                     left: Box::new(hir_left),
@@ -857,7 +1883,7 @@ impl HirBuilder {
                     right: Box::new(hir_right),
                 }, result_type)
             }
-            Expression::Unary { operator, operand } => {
+            Expression::Unary { operator, operand, .. } => {
                 let hir_operand = self.transform_expression(operand)?;
                 let hir_op = self.transform_unary_op(operator);
                 
@@ -869,55 +1895,210 @@ impl HirBuilder {
                     operand: Box::new(hir_operand),
                 }, result_type)
             }
-            Expression::Call { function, arguments } => {
+            Expression::Call { function: written, arguments, span } => {
+                let function = &self.resolve_name(written)?;
+                if self.unsafe_functions.contains(function) && !self.in_unsafe {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Call to unsafe function '{}' must be inside an unsafe block or unsafe function", function)
+                    ));
+                }
                 let mut hir_args = Vec::new();
-                for arg in arguments {
+                for (index, arg) in arguments.iter().enumerate() {
+                    if let Expression::Closure { .. } = arg {
+                        self.closure_signature = self.expected_argument_type(function, index, &hir_args);
+                    }
                     hir_args.push(self.transform_expression(arg)?);
                 }
                 
-                // Look up function type
-                let func_info = self.symbol_table.lookup(function)?;
-                let return_type = if let HirType::Function { return_type, .. } = &func_info.symbol_type {
-                    (**return_type).clone()
-                } else {
-                    HirType::Infer(self.next_id())
+                // Look up function type and check arguments against declared parameter types
+                let Ok(func_info) = self.symbol_table.lookup(function) else {
+                    let candidates = self.names_in_scope(true);
+                    let message = format!("Undefined function '{}'", written);
+                    return Ok(self.unresolved(unresolved_name(
+                        ResolutionErrorKind::Function, written, message, name_span(*span, 0, written.len()),
+                        candidates.iter().map(String::as_str)
+                    )));
                 };
-                
-                (HirExpressionKind::Call {
-                    function: function.clone(),
-                    arguments: hir_args,
-                }, return_type)
+                if !func_info.is_function {
+                    // A local holding a function value
+                    self.capture(function);
+                    let callee = HirExpression {
+                        id: self.next_id(),
+                        kind: HirExpressionKind::Variable(function.clone()),
+                        expr_type: func_info.symbol_type,
+                        span: self.current_span,
+                    };
+                    let (kind, return_type) = self.indirect_call(callee, hir_args)?;
+                    return Ok(HirExpression {
+                        id: self.next_id(),
+                        kind,
+                        expr_type: return_type,
+                        span: self.current_span,
+                    });
+                }
+                self.direct_call(function, &func_info, hir_args)?
+            }
+            Expression::MethodCall { receiver, method, arguments, .. } => {
+                let mut hir_args = Vec::new();
+
+                // `Type.f(...)` calls an associated function; otherwise the receiver is passed as `self`
+                // A value of a type parameter has the methods of the traits bounding it
+                let (type_name, has_receiver, bound_method) = match self.associated_type(receiver) {
+                    Some(type_name) => (type_name, false, None),
+                    None => {
+                        let hir_receiver = self.transform_expression(receiver)?;
+                        let (type_name, bound_method) = match &self.known_type(&hir_receiver.expr_type) {
+                            HirType::Param(param) => (param.clone(), Some(self.bound_method(param, method)?)),
+                            HirType::Infer(_) => {
+                                return Err(OvieError::semantic_error(
+                                    0, 0,
+                                    format!("Cannot infer the type of the value method '{}' is called on; add a type annotation", method)
+                                ));
+                            }
+                            // Already reported; the arguments are still checked
+                            HirType::Error => {
+                                for argument in arguments {
+                                    self.transform_expression(argument)?;
+                                }
+                                return Ok(self.error_expression());
+                            }
+                            other => match Self::impl_key(other) {
+                                Some(type_name) => (type_name, None),
+                                None => {
+                                    return Err(OvieError::semantic_error(
+                                        0, 0,
+                                        format!("Cannot call method '{}' on a value of type {}", method, other)
+                                    ));
+                                }
+                            },
+                        };
+                        hir_args.push(hir_receiver);
+                        (type_name, true, bound_method)
+                    }
+                };
+
+                let info = match bound_method {
+                    Some(info) => info,
+                    None => self.type_table.method(&type_name, method).cloned().ok_or_else(|| OvieError::semantic_error(
+                        0, 0,
+                        format!("Type '{}' has no method '{}'", type_name, method)
+                    ))?,
+                };
+                if has_receiver && !info.takes_self {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("'{}' is an associated function of '{}' and must be called as {}.{}(...)", method, type_name, type_name, method)
+                    ));
+                }
+                if self.unsafe_functions.contains(&info.function) && !self.in_unsafe {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Call to unsafe function '{}' must be inside an unsafe block or unsafe function", info.function)
+                    ));
+                }
+
+                for arg in arguments {
+                    if let Expression::Closure { .. } = arg {
+                        self.closure_signature = self.expected_argument_type(&info.function, hir_args.len(), &hir_args);
+                    }
+                    hir_args.push(self.transform_expression(arg)?);
+                }
+
+                let func_info = self.symbol_table.lookup(&info.function)?;
+                if let HirType::Function { params, .. } = &func_info.symbol_type {
+                    if params.len() != hir_args.len() {
+                        let offset = usize::from(has_receiver);
+                        return Err(OvieError::semantic_error(
+                            0, 0,
+                            format!("Method '{}' of '{}' expects {} argument(s), found {}", method, type_name, params.len() - offset, hir_args.len() - offset)
+                        ));
+                    }
+                }
+                self.direct_call(&info.function, &func_info, hir_args)?
+            }
+            Expression::Tuple { elements, .. } => {
+                let elements = elements.iter()
+                    .map(|element| self.transform_expression(element))
+                    .collect::<OvieResult<Vec<_>>>()?;
+                let element_types = elements.iter().map(|element| element.expr_type.clone()).collect();
+                (HirExpressionKind::Tuple { elements }, HirType::Tuple(element_types))
             }
-            Expression::FieldAccess { object, field } => {
+            Expression::FieldAccess { object, field, span } => {
                 let hir_object = self.transform_expression(object)?;
-                let field_type = self.get_field_type(&hir_object.expr_type, field)?;
+                if let Some(error) = self.missing_field(&hir_object.expr_type, field, *span) {
+                    return Ok(self.unresolved(error));
+                }
+                let field_type = self.field_type(&hir_object.expr_type, field, *span)?;
                 
                 (HirExpressionKind::FieldAccess {
                     object: Box::new(hir_object),
                     field: field.clone(),
                 }, field_type)
             }
-            Expression::StructInstantiation { struct_name, fields } => {
+            Expression::StructInstantiation { struct_name: written, fields, span } => {
+                let struct_name = &self.module_scope.resolve(written)?;
                 let mut hir_fields = Vec::new();
                 for field_init in fields {
                     let hir_value = self.transform_expression(&field_init.value)?;
                     hir_fields.push(HirFieldInit {
                         name: field_init.name.clone(),
                         value: hir_value,
-                        span: SourceSpan::default(),
+                        span: field_init.value.span(),
                     });
                 }
+
+                // Type arguments of a generic struct are bound by its field values
+                let (type_parameters, declared_fields) = match self.type_table.types.get(struct_name) {
+                    Some(TypeInfo::Struct { type_parameters, fields }) => (type_parameters.clone(), fields.clone()),
+                    _ => {
+                        let candidates = self.struct_names();
+                        let message = format!("Unknown struct '{}'", written);
+                        return Ok(self.unresolved(unresolved_name(
+                            ResolutionErrorKind::Struct, written, message, name_span(*span, 0, written.len()),
+                            candidates.iter().map(String::as_str)
+                        )));
+                    }
+                };
+                for field_init in fields.iter().filter(|field_init| !declared_fields.contains_key(&field_init.name)) {
+                    let message = format!("Field '{}' not found in struct '{}'", field_init.name, struct_name);
+                    self.report(unresolved_name(
+                        ResolutionErrorKind::Field, &field_init.name, message, field_init.span,
+                        declared_fields.keys().map(String::as_str)
+                    ));
+                }
+                let (expected, found): (Vec<HirType>, Vec<HirType>) = hir_fields.iter()
+                    .filter_map(|field| declared_fields.get(&field.name)
+                        .map(|declared| (declared.clone(), field.value.expr_type.clone())))
+                    .unzip();
+                let bindings = self.instantiate(&type_parameters, &expected, &found);
+                let hir_fields = hir_fields.into_iter()
+                    .map(|field| match declared_fields.get(&field.name) {
+                        Some(declared) => Ok(HirFieldInit {
+                            value: self.coerce(field.value, &Self::substitute(declared, &bindings))
+                                .map_err(|e| e.located_at(field.span.start_position()))?,
+                            name: field.name,
+                            span: field.span,
+                        }),
+                        None => Ok(field),
+                    })
+                    .collect::<OvieResult<Vec<_>>>()?;
+                let struct_type = HirType::Struct {
+                    name: struct_name.clone(),
+                    arguments: type_parameters.iter().map(|param| bindings[param].clone()).collect(),
+                };
                 
                 (HirExpressionKind::StructInit {
                     struct_name: struct_name.clone(),
                     fields: hir_fields,
-                }, HirType::Struct(struct_name.clone()))
+                }, struct_type)
             }
-            Expression::Range { start, end } => {
+            Expression::Range { start, end, .. } => {
                 let hir_start = self.transform_expression(start)?;
                 let hir_end = self.transform_expression(end)?;
                 
                 // Both start and end should be the same type
+                self.check_assignable(&hir_start.expr_type, &hir_end.expr_type)?;
                 let range_type = HirType::Range(Box::new(hir_start.expr_type.clone()));
                 
                 (HirExpressionKind::Range {
@@ -925,32 +2106,75 @@ impl HirBuilder {
                     end: Box::new(hir_end),
                 }, range_type)
             }
-            Expression::EnumVariantConstruction { enum_name, variant_name, data } => {
-                // Transform data if present
-                let hir_data = if let Some(data_expr) = data {
-                    Some(Box::new(self.transform_expression(data_expr)?))
-                } else {
-                    None
-                };
+            Expression::EnumVariantConstruction { enum_name: written, variant_name, payload, span } => {
+                let enum_name = &self.module_scope.resolve(written)?;
+                let mut hir_fields = Vec::new();
+                for (name, value) in payload.fields() {
+                    let hir_value = self.transform_expression(value)?;
+                    hir_fields.push(HirFieldInit {
+                        name,
+                        value: hir_value,
+                        span: value.span(),
+                    });
+                }
                 
-                // Type is the enum type
-                let enum_type = HirType::Enum(enum_name.clone());
+                // Type is the enum type; the variant's fields bind type arguments of a generic enum
+                let (type_parameters, declared) = match self.type_table.types.get(enum_name) {
+                    Some(TypeInfo::Enum { type_parameters, variants }) => match variants.get(variant_name) {
+                        Some(fields) => (type_parameters.clone(), Some(fields.clone())),
+                        None => {
+                            let message = format!("Enum '{}' has no variant '{}'", enum_name, variant_name);
+                            let variant_span = name_span(*span, written.len() + 1, variant_name.len());
+                            let error = unresolved_name(ResolutionErrorKind::Variant, variant_name, message, variant_span, variants.keys().map(String::as_str));
+                            return Ok(self.unresolved(error));
+                        }
+                    },
+                    _ => (Vec::new(), None),
+                };
+                let declared_types = match &declared {
+                    Some(declared) => self.variant_field_types(&format!("{}.{}", enum_name, variant_name), declared, payload, false)?,
+                    None => hir_fields.iter().map(|field| field.value.expr_type.clone()).collect(),
+                };
+                let found: Vec<HirType> = hir_fields.iter().map(|field| field.value.expr_type.clone()).collect();
+                let bindings = self.instantiate(&type_parameters, &declared_types, &found);
+                let hir_fields = hir_fields.into_iter().zip(&declared_types)
+                    .map(|(field, declared)| Ok(HirFieldInit {
+                        value: self.coerce(field.value, &Self::substitute(declared, &bindings))
+                            .map_err(|e| e.located_at(field.span.start_position()))?,
+                        ..field
+                    }))
+                    .collect::<OvieResult<Vec<_>>>()?;
+                let enum_type = HirType::Enum {
+                    name: enum_name.clone(),
+                    arguments: type_parameters.iter().map(|param| bindings[param].clone()).collect(),
+                };
                 
                 (HirExpressionKind::EnumVariant {
                     enum_name: enum_name.clone(),
                     variant_name: variant_name.clone(),
-                    data: hir_data,
+                    fields: hir_fields,
                 }, enum_type)
             }
-            Expression::Index { object, index } => {
+            Expression::Index { object, index, .. } => {
                 let hir_object = self.transform_expression(object)?;
                 let hir_index = self.transform_expression(index)?;
                 
                 // Determine result type based on object type
-                let result_type = match &hir_object.expr_type {
-                    HirType::Array(elem_type) => (**elem_type).clone(),
+                let result_type = match self.known_type(&hir_object.expr_type) {
+                    HirType::Array(elem_type) => *elem_type,
                     HirType::String => HirType::String, // String indexing returns a single character string
-                    _ => HirType::Infer(self.next_id()), // Unknown, will be inferred
+                    HirType::Error => HirType::Error,
+                    HirType::Infer(_) => {
+                        // Inferred once the object's type is
+                        let element = HirType::Infer(self.next_id());
+                        self.constraints.push(Constraint::Index {
+                            object: hir_object.expr_type.clone(),
+                            element: element.clone(),
+                            span: self.current_span,
+                        });
+                        element
+                    }
+                    _ => return Err(self.inferred_mismatch("array or String", &hir_object.expr_type, hir_object.span)),
                 };
                 
                 (HirExpressionKind::Index {
@@ -958,7 +2182,7 @@ impl HirBuilder {
                     index: Box::new(hir_index),
                 }, result_type)
             }
-            Expression::ArrayLiteral { elements } => {
+            Expression::ArrayLiteral { elements, .. } => {
                 let mut hir_elements = Vec::new();
                 let mut element_type = HirType::Infer(self.next_id());
                 
@@ -970,24 +2194,385 @@ impl HirBuilder {
                     }
                     hir_elements.push(hir_element);
                 }
+                let hir_elements = hir_elements.into_iter()
+                    .map(|element| {
+                        let span = element.span;
+                        self.coerce(element, &element_type).map_err(|e| e.located_at(span.start_position()))
+                    })
+                    .collect::<OvieResult<Vec<_>>>()?;
                 
                 (HirExpressionKind::ArrayLiteral {
                     elements: hir_elements,
                 }, HirType::Array(Box::new(element_type)))
             }
+            Expression::Match { scrutinee, arms, .. } => {
+                let hir_scrutinee = self.transform_expression(scrutinee)?;
+
+                // A scrutinee of not-yet-inferred type takes the type of the enum its patterns name
+                if let HirType::Infer(_) = self.known_type(&hir_scrutinee.expr_type) {
+                    let named_enum = arms.iter().find_map(|arm| match &arm.pattern {
+                        Pattern::Variant { enum_name, .. } => Some(enum_name),
+                        _ => None,
+                    });
+                    if let Some(enum_name) = named_enum {
+                        let enum_name = &self.module_scope.resolve(enum_name)?;
+                        if let Some(TypeInfo::Enum { type_parameters, .. }) = self.type_table.types.get(enum_name) {
+                            let type_parameters = type_parameters.clone();
+                            let bindings = self.instantiate(&type_parameters, &[], &[]);
+                            let enum_type = HirType::Enum {
+                                name: enum_name.clone(),
+                                arguments: type_parameters.iter().map(|param| bindings[param].clone()).collect(),
+                            };
+                            self.unify(&enum_type, &hir_scrutinee.expr_type, hir_scrutinee.span)?;
+                        }
+                    }
+                }
+
+                let mut hir_arms = Vec::new();
+                for arm in arms {
+                    // Names bound by the pattern are only visible inside the arm
+                    self.symbol_table.enter_scope();
+                    let hir_arm = self.transform_match_arm(arm, &hir_scrutinee.expr_type);
+                    self.symbol_table.exit_scope();
+                    hir_arms.push(hir_arm?);
+                }
+                let scrutinee_type = self.inference.resolve(&hir_scrutinee.expr_type);
+                self.check_exhaustive(&scrutinee_type, &hir_arms)?;
+
+                // Expression arms must agree on a type; a match with block arms produces unit
+                let mut match_type = HirType::Unit;
+                if !hir_arms.is_empty() && hir_arms.iter().all(|arm| arm.value.is_some()) {
+                    for value in hir_arms.iter().filter_map(|arm| arm.value.as_ref()) {
+                        if matches!(self.inference.shallow(&match_type), HirType::Unit | HirType::Infer(_)) {
+                            match_type = value.expr_type.clone();
+                        }
+                    }
+                    for arm in &mut hir_arms {
+                        if let Some(value) = arm.value.take() {
+                            let span = value.span;
+                            arm.value = Some(self.coerce(value, &match_type)
+                                .map_err(|e| e.located_at(span.start_position()))?);
+                        }
+                    }
+                }
+
+                (HirExpressionKind::Match {
+                    scrutinee: Box::new(hir_scrutinee),
+                    arms: hir_arms,
+                }, match_type)
+            }
+            Expression::Closure { parameters, return_type, body, .. } => {
+                let expected = match self.closure_signature.take() {
+                    Some(HirType::Function { params, return_type }) if params.len() == parameters.len() => Some((params, *return_type)),
+                    _ => None,
+                };
+                let declared_return = match (return_type, &expected) {
+                    (Some(annotation), _) => Some(self.resolve_type(annotation)?),
+                    (None, Some((_, expected_return))) if !matches!(self.inference.shallow(expected_return), HirType::Infer(_)) => {
+                        Some(expected_return.clone())
+                    }
+                    (None, _) => None,
+                };
+
+                self.symbol_table.enter_scope();
+                self.closures.push((self.symbol_table.current_scope, Vec::new()));
+                let closure = self.transform_closure(parameters, expected.map(|(params, _)| params), declared_return, body);
+                let (_, captures) = self.closures.pop().unwrap_or_default();
+                self.symbol_table.exit_scope();
+                let (hir_params, return_type, hir_body) = closure?;
+
+                // Names an enclosing closure does not define must be captured by it as well
+                for name in &captures {
+                    self.capture(name);
+                }
+
+                let closure_type = HirType::Function {
+                    params: hir_params.iter().map(|param| param.param_type.clone()).collect(),
+                    return_type: Box::new(return_type.clone()),
+                };
+                (HirExpressionKind::Closure {
+                    parameters: hir_params,
+                    return_type,
+                    body: hir_body,
+                    captures,
+                }, closure_type)
+            }
+            Expression::IndirectCall { callee, arguments, .. } => {
+                let hir_callee = self.transform_expression(callee)?;
+                let mut hir_args = Vec::new();
+                for (index, arg) in arguments.iter().enumerate() {
+                    if let (Expression::Closure { .. }, HirType::Function { params, .. }) = (arg, &hir_callee.expr_type) {
+                        self.closure_signature = params.get(index).cloned();
+                    }
+                    hir_args.push(self.transform_expression(arg)?);
+                }
+                self.indirect_call(hir_callee, hir_args)?
+            }
+            Expression::Try { operand, .. } => {
+                let hir_operand = self.transform_expression(operand)?;
+                self.propagate(hir_operand)?
+            }
         };
 
         Ok(HirExpression {
             id: self.next_id(),
             kind,
             expr_type,
-            span: SourceSpan::default(),
+            span: self.current_span,
         })
     }
 
-    /// Transform binary operator
-    fn transform_binary_op(&self, op: &BinaryOperator) -> HirBinaryOp {
-        match op {
+    /// Transform a match arm, binding its pattern's names in the current scope
+    fn transform_match_arm(&mut self, arm: &MatchArm, scrutinee_type: &HirType) -> OvieResult<HirMatchArm> {
+        let pattern = self.transform_pattern(&arm.pattern, scrutinee_type)
+            .map_err(|e| e.located_at(arm.pattern.span().start_position()))?;
+        let body = self.transform_block(&arm.body, arm.span)?;
+        let value = match &arm.value {
+            Some(value) => Some(self.transform_expression(value)?),
+            None => None,
+        };
+
+        Ok(HirMatchArm {
+            pattern,
+            body,
+            value,
+            span: arm.span,
+        })
+    }
+
+    /// Check a pattern against the type of the value it matches
+    fn transform_pattern(&mut self, pattern: &Pattern, scrutinee_type: &HirType) -> OvieResult<HirPattern> {
+        let scrutinee_type = &self.inference.shallow(scrutinee_type);
+        match pattern {
+            Pattern::Wildcard(_) => Ok(HirPattern::Wildcard),
+            Pattern::Binding(name, span) => {
+                self.bind_pattern_variable(name, scrutinee_type.clone(), *span)?;
+                Ok(HirPattern::Binding(name.clone()))
+            }
+            Pattern::Literal(literal, _) => {
+                let (hir_literal, literal_type) = match literal {
+                    Literal::String(s) => (HirLiteral::String(s.clone()), HirType::String),
+                    Literal::Number(n) => (HirLiteral::Number(*n), HirType::Number),
+                    // An integer pattern matches a Number scrutinee by value
+                    Literal::Integer(n) if *scrutinee_type == HirType::Number => (HirLiteral::Number(*n as f64), HirType::Number),
+                    Literal::Integer(n) => (HirLiteral::Integer(*n), HirType::Int),
+                    Literal::Boolean(b) => (HirLiteral::Boolean(*b), HirType::Boolean),
+                };
+                self.check_assignable(scrutinee_type, &literal_type)?;
+                Ok(HirPattern::Literal(hir_literal))
+            }
+            Pattern::Variant { enum_name: written, variant_name, payload, span } => {
+                let enum_name = &self.module_scope.resolve(written)?;
+                let (type_parameters, variants) = match self.type_table.types.get(enum_name) {
+                    Some(TypeInfo::Enum { type_parameters, variants }) => (type_parameters.clone(), variants.clone()),
+                    _ => return Err(OvieError::kind_error(0, 0, ResolutionErrorKind::Type, enum_name, format!("Unknown enum '{}' in pattern", enum_name))),
+                };
+                let declared = match variants.get(variant_name) {
+                    Some(fields) => fields.clone(),
+                    None => {
+                        let message = format!("Enum '{}' has no variant '{}'", enum_name, variant_name);
+                        let variant_span = name_span(*span, written.len() + 1, variant_name.len());
+                        self.report(unresolved_name(
+                            ResolutionErrorKind::Variant, variant_name, message, variant_span, variants.keys().map(String::as_str)
+                        ));
+                        // The arm is still checked, with its bindings of the error type
+                        for binding in pattern.bindings() {
+                            self.bind_pattern_variable(binding, HirType::Error, *span)?;
+                        }
+                        return Ok(HirPattern::Wildcard);
+                    }
+                };
+
+                let arguments = match scrutinee_type {
+                    HirType::Enum { name, arguments } if name == enum_name => arguments.clone(),
+                    _ => {
+                        let bindings = self.instantiate(&type_parameters, &[], &[]);
+                        let arguments: Vec<HirType> = type_parameters.iter().map(|param| bindings[param].clone()).collect();
+                        self.check_assignable(scrutinee_type, &HirType::Enum {
+                            name: enum_name.clone(),
+                            arguments: arguments.clone(),
+                        })?;
+                        arguments
+                    }
+                };
+
+                let declared_types = self.variant_field_types(&format!("{}.{}", enum_name, variant_name), &declared, payload, true)?;
+                let type_bindings = type_parameters.into_iter().zip(arguments).collect();
+                let mut bindings = Vec::new();
+                for ((field, pattern), declared_type) in payload.fields().into_iter().zip(&declared_types) {
+                    match pattern {
+                        Pattern::Wildcard(_) => {}
+                        Pattern::Binding(name, span) => {
+                            self.bind_pattern_variable(name, Self::substitute(declared_type, &type_bindings), *span)?;
+                            bindings.push((field, name.clone()));
+                        }
+                        _ => return Err(OvieError::semantic_error(
+                            0, 0,
+                            "Only a name or '_' is supported as a variant payload pattern"
+                        )),
+                    }
+                }
+
+                Ok(HirPattern::Variant {
+                    variant_name: variant_name.clone(),
+                    bindings,
+                })
+            }
+            Pattern::Tuple(..) => Err(OvieError::semantic_error(
+                0, 0,
+                "Tuple patterns are only supported in 'let' and 'for'; match on the tuple's elements instead"
+            )),
+        }
+    }
+
+    /// Declared types of the fields `payload` gives for `variant` (written `Enum.Variant`), in
+    /// the payload's order. A construction gives every field; a pattern may leave out named
+    /// fields, or the whole payload
+    fn variant_field_types<T>(
+        &self,
+        variant: &str,
+        declared: &[(Symbol, HirType)],
+        payload: &VariantPayload<T>,
+        is_pattern: bool,
+    ) -> OvieResult<Vec<HirType>> {
+        let named = declared.first().is_some_and(|(name, _)| name != "0");
+        match payload {
+            VariantPayload::Unit if declared.is_empty() || is_pattern => Ok(Vec::new()),
+            _ if declared.is_empty() => Err(OvieError::semantic_error(0, 0, format!(
+                "Variant '{}' has no payload", variant
+            ))),
+            VariantPayload::Unit | VariantPayload::Tuple(_) if named => Err(OvieError::semantic_error(0, 0, format!(
+                "Variant '{}' has named fields; write '{} {{ field: ... }}'", variant, variant
+            ))),
+            VariantPayload::Struct(_) if !named => Err(OvieError::semantic_error(0, 0, format!(
+                "Variant '{}' has positional fields; write '{}(...)'", variant, variant
+            ))),
+            VariantPayload::Unit => Err(OvieError::semantic_error(0, 0, format!(
+                "Variant '{}' expects {} value(s), found 0", variant, declared.len()
+            ))),
+            VariantPayload::Tuple(values) if values.len() != declared.len() => Err(OvieError::semantic_error(0, 0, format!(
+                "Variant '{}' expects {} value(s), found {}", variant, declared.len(), values.len()
+            ))),
+            VariantPayload::Tuple(_) => Ok(declared.iter().map(|(_, field_type)| field_type.clone()).collect()),
+            VariantPayload::Struct(fields) => {
+                let mut field_types = Vec::new();
+                for (index, (name, _)) in fields.iter().enumerate() {
+                    if fields[..index].iter().any(|(other, _)| other == name) {
+                        return Err(OvieError::semantic_error(0, 0, format!(
+                            "Field '{}' of variant '{}' is given more than once", name, variant
+                        )));
+                    }
+                    match declared.iter().find(|(field, _)| field == name) {
+                        Some((_, field_type)) => field_types.push(field_type.clone()),
+                        None => return Err(OvieError::semantic_error(0, 0, format!(
+                            "Variant '{}' has no field '{}'", variant, name
+                        ))),
+                    }
+                }
+                let missing = declared.iter().find(|(field, _)| !fields.iter().any(|(name, _)| name == field));
+                match missing {
+                    Some((field, _)) if !is_pattern => Err(OvieError::semantic_error(0, 0, format!(
+                        "Missing field '{}' in variant '{}'", field, variant
+                    ))),
+                    _ => Ok(field_types),
+                }
+            }
+        }
+    }
+
+    /// Declare the names of a `let` or `for` pattern for a value of type `value_type`
+    fn transform_binding_pattern(&mut self, pattern: &Pattern, value_type: &HirType, is_mutable: bool) -> OvieResult<HirPattern> {
+        match pattern {
+            Pattern::Wildcard(_) => Ok(HirPattern::Wildcard),
+            Pattern::Binding(name, span) => {
+                self.symbol_table.insert(name.clone(), SymbolInfo {
+                    symbol_type: value_type.clone(),
+                    is_mutable,
+                    is_function: false,
+                    span: *span,
+                })?;
+                Ok(HirPattern::Binding(name.clone()))
+            }
+            Pattern::Tuple(patterns, span) => {
+                let element_types = match self.inference.shallow(value_type) {
+                    HirType::Tuple(element_types) if element_types.len() == patterns.len() => element_types,
+                    HirType::Error => patterns.iter().map(|_| HirType::Error).collect(),
+                    HirType::Infer(_) => {
+                        let element_types: Vec<HirType> = patterns.iter().map(|_| HirType::Infer(self.next_id())).collect();
+                        self.unify(&HirType::Tuple(element_types.clone()), value_type, *span)?;
+                        element_types
+                    }
+                    other => {
+                        let expected = HirType::Tuple(patterns.iter().map(|_| HirType::Infer(0)).collect());
+                        return Err(OvieError::type_error(0, 0, &expected.to_string(), &self.inference.resolve(&other).to_string(), vec![])
+                            .located_at(span.start_position()));
+                    }
+                };
+                let patterns = patterns.iter().zip(&element_types)
+                    .map(|(pattern, element_type)| self.transform_binding_pattern(pattern, element_type, is_mutable))
+                    .collect::<OvieResult<Vec<_>>>()?;
+                Ok(HirPattern::Tuple(patterns))
+            }
+            Pattern::Literal(_, span) | Pattern::Variant { span, .. } => Err(OvieError::semantic_error(
+                0, 0,
+                "Only names, '_' and tuples of them can be used to destructure a value"
+            ).located_at(span.start_position())),
+        }
+    }
+
+    /// Declare a name bound by a pattern
+    fn bind_pattern_variable(&mut self, name: &str, symbol_type: HirType, span: SourceSpan) -> OvieResult<()> {
+        self.pattern_bindings.insert(span);
+        self.symbol_table.insert(name.to_string(), SymbolInfo {
+            symbol_type,
+            is_mutable: false,
+            is_function: false,
+            span,
+        })
+    }
+
+    /// Report a match whose arms do not cover every value of the scrutinee
+    fn check_exhaustive(&self, scrutinee_type: &HirType, arms: &[HirMatchArm]) -> OvieResult<()> {
+        if arms.iter().any(|arm| matches!(arm.pattern, HirPattern::Wildcard | HirPattern::Binding(_))) {
+            return Ok(());
+        }
+
+        let mut missing: Vec<String> = match scrutinee_type {
+            HirType::Enum { name, .. } => match self.type_table.types.get(name) {
+                Some(TypeInfo::Enum { variants, .. }) => variants.keys()
+                    .filter(|variant| !arms.iter().any(|arm| matches!(
+                        &arm.pattern, HirPattern::Variant { variant_name, .. } if variant_name == *variant
+                    )))
+                    .map(|variant| format!("{}.{}", name, variant))
+                    .collect(),
+                _ => Vec::new(),
+            },
+            HirType::Boolean => [true, false].into_iter()
+                .filter(|value| !arms.iter().any(|arm| matches!(
+                    arm.pattern, HirPattern::Literal(HirLiteral::Boolean(b)) if b == *value
+                )))
+                .map(|value| value.to_string())
+                .collect(),
+            HirType::Error => Vec::new(),
+            other => {
+                return Err(OvieError::semantic_error(0, 0, format!(
+                    "Non-exhaustive match: values of type {} need a '_' arm", other
+                )));
+            }
+        };
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+        missing.sort();
+        Err(OvieError::semantic_error(0, 0, format!(
+            "Non-exhaustive match: {} not covered", missing.join(", ")
+        )))
+    }
+
+    /// Transform binary operator
+    fn transform_binary_op(&self, op: &BinaryOperator) -> HirBinaryOp {
+        match op {
             BinaryOperator::Add => HirBinaryOp::Add,
             BinaryOperator::Subtract => HirBinaryOp::Sub,
             BinaryOperator::Multiply => HirBinaryOp::Mul,
@@ -1012,102 +2597,939 @@ impl HirBuilder {
         }
     }
 
-    /// Check binary operation type compatibility
-    fn check_binary_op_type(&self, left: &HirType, op: &HirBinaryOp, right: &HirType) -> OvieResult<HirType> {
-        match (left, op, right) {
-            // Arithmetic operations
-            (HirType::Number, HirBinaryOp::Add | HirBinaryOp::Sub | HirBinaryOp::Mul | HirBinaryOp::Div | HirBinaryOp::Mod, HirType::Number) => {
-                Ok(HirType::Number)
-            }
-            // String concatenation
-            (HirType::String, HirBinaryOp::Add, HirType::String) => {
-                Ok(HirType::String)
-            }
-            // Comparison operations
-            (HirType::Number, HirBinaryOp::Lt | HirBinaryOp::Le | HirBinaryOp::Gt | HirBinaryOp::Ge, HirType::Number) => {
-                Ok(HirType::Boolean)
-            }
-            // Equality operations
-            (left_ty, HirBinaryOp::Eq | HirBinaryOp::Ne, right_ty) if left_ty == right_ty => {
-                Ok(HirType::Boolean)
+    /// Check binary operation type compatibility. Both operands of an arithmetic, comparison
+    /// or equality operator have one type; whether an arithmetic or comparison operand is a
+    /// number waits until its type is known.
+    fn check_binary_op_type(&mut self, left: &HirType, op: &HirBinaryOp, right: &HirType, span: SourceSpan) -> OvieResult<HirType> {
+        match op {
+            HirBinaryOp::And | HirBinaryOp::Or => {
+                self.unify(&HirType::Boolean, left, span)?;
+                self.unify(&HirType::Boolean, right, span)?;
+                Ok(HirType::Boolean)
+            }
+            HirBinaryOp::Eq | HirBinaryOp::Ne => {
+                self.unify(left, right, span)?;
+                Ok(HirType::Boolean)
+            }
+            HirBinaryOp::Add | HirBinaryOp::Sub | HirBinaryOp::Mul | HirBinaryOp::Div | HirBinaryOp::Mod => {
+                self.unify(left, right, span)?;
+                self.check_numeric(left, matches!(op, HirBinaryOp::Add), span)?;
+                Ok(left.clone())
+            }
+            HirBinaryOp::Lt | HirBinaryOp::Le | HirBinaryOp::Gt | HirBinaryOp::Ge => {
+                self.unify(left, right, span)?;
+                self.check_numeric(left, false, span)?;
+                Ok(HirType::Boolean)
+            }
+        }
+    }
+
+    /// Check unary operation type compatibility
+    fn check_unary_op_type(&mut self, op: &HirUnaryOp, operand: &HirType) -> OvieResult<HirType> {
+        match op {
+            HirUnaryOp::Not => {
+                self.check_assignable(&HirType::Boolean, operand)?;
+                Ok(HirType::Boolean)
+            }
+            HirUnaryOp::Neg => {
+                self.check_numeric(operand, false, self.current_span)?;
+                Ok(operand.clone())
+            }
+        }
+    }
+
+    /// Check that an operand is an Int or Number, or a String if `allows_string`,
+    /// once its type is known
+    fn check_numeric(&mut self, operand: &HirType, allows_string: bool, span: SourceSpan) -> OvieResult<()> {
+        let constraint = Constraint::Numeric {
+            operand: operand.clone(),
+            allows_string,
+            span,
+        };
+        match self.inference.shallow(operand) {
+            HirType::Infer(_) => {
+                self.constraints.push(constraint);
+                Ok(())
+            }
+            _ => self.check_constraint(constraint),
+        }
+    }
+
+    /// Type of field `field_name` of a value of type `object`, accessed at `span`; if
+    /// that type is not known yet, the field's type is inferred once it is
+    fn field_type(&mut self, object: &HirType, field_name: &str, span: SourceSpan) -> OvieResult<HirType> {
+        match self.known_type(object) {
+            HirType::Infer(_) => {
+                let field_type = HirType::Infer(self.next_id());
+                self.constraints.push(Constraint::Field {
+                    object: object.clone(),
+                    field: field_name.to_string(),
+                    field_type: field_type.clone(),
+                    span,
+                });
+                Ok(field_type)
+            }
+            // Already reported
+            HirType::Error => Ok(HirType::Error),
+            _ => self.get_field_type(&self.inference.resolve(object), field_name),
+        }
+    }
+
+    /// Error for a field access at `span` naming a field the struct `object` does not
+    /// have, suggesting the fields it has. The field name ends the span.
+    fn missing_field(&self, object: &HirType, field: &str, span: SourceSpan) -> Option<OvieError> {
+        let HirType::Struct { name, .. } = self.inference.shallow(object) else { return None };
+        let Some(TypeInfo::Struct { fields, .. }) = self.type_table.types.get(&name) else { return None };
+        if fields.contains_key(field) {
+            return None;
+        }
+        let message = format!("Field '{}' not found in struct '{}'", field, name);
+        let field_span = name_span(span, span.len() - field.len(), field.len());
+        Some(unresolved_name(ResolutionErrorKind::Field, field, message, field_span, fields.keys().map(String::as_str)))
+    }
+
+    /// Get field type from struct type
+    fn get_field_type(&self, struct_type: &HirType, field_name: &str) -> OvieResult<HirType> {
+        if let HirType::Tuple(element_types) = struct_type {
+            return field_name.parse::<usize>().ok()
+                .and_then(|index| element_types.get(index).cloned())
+                .ok_or_else(|| OvieError::semantic_error(
+                    0, 0,
+                    format!("Tuple '{}' has no field '{}'", struct_type, field_name)
+                ));
+        }
+        if let HirType::Struct { name: struct_name, arguments } = struct_type {
+            if let Some(TypeInfo::Struct { type_parameters, fields }) = self.type_table.types.get(struct_name) {
+                if let Some(field_type) = fields.get(field_name) {
+                    let bindings = type_parameters.iter().cloned().zip(arguments.iter().cloned()).collect();
+                    Ok(Self::substitute(field_type, &bindings))
+                } else {
+                    Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Field '{}' not found in struct '{}'", field_name, struct_name)
+                    ))
+                }
+            } else {
+                Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("Struct '{}' not found", struct_name)
+                ))
+            }
+        } else {
+            Err(OvieError::type_error(
+                0, 0,
+                "struct",
+                &format!("{:?}", struct_type),
+                vec![]
+            ))
+        }
+    }
+
+    /// Parameters, return type and body of a closure whose scope has been entered.
+    /// Unannotated parameters take the types `expected` by the closure's use, if known.
+    fn transform_closure(&mut self, parameters: &[Parameter], expected: Option<Vec<HirType>>, declared_return: Option<HirType>, body: &[Statement]) -> OvieResult<(Vec<HirParameter>, HirType, HirBlock)> {
+        let mut hir_params = Vec::new();
+        for (index, param) in parameters.iter().enumerate() {
+            let param_type = match (&param.type_annotation, expected.as_ref().and_then(|params| params.get(index))) {
+                (None, Some(expected)) => expected.clone(),
+                _ => self.resolve_parameter_type(param)?,
+            };
+            self.symbol_table.insert(param.name.clone(), SymbolInfo {
+                symbol_type: param_type.clone(),
+                is_mutable: param.mutable,
+                is_function: false,
+                span: param.span,
+            }).map_err(|e| e.located_at(param.span.start_position()))?;
+
+            hir_params.push(HirParameter {
+                name: param.name.clone(),
+                param_type,
+                span: param.span,
+            });
+        }
+
+        // A closure body is a function of its own: loops around the closure are out of reach
+        let is_declared = declared_return.is_some();
+        let return_type = declared_return.unwrap_or_else(|| HirType::Infer(self.next_id()));
+        let hir_body = self.transform_body(body, self.current_span, &return_type, is_declared)?;
+
+        Ok((hir_params, return_type, hir_body))
+    }
+
+    /// Check a call of a function value against its type, returning the call and its result type
+    fn indirect_call(&mut self, callee: HirExpression, mut arguments: Vec<HirExpression>) -> OvieResult<(HirExpressionKind, HirType)> {
+        let return_type = match &self.known_type(&callee.expr_type) {
+            HirType::Function { params, return_type } => {
+                if params.len() != arguments.len() {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Function value of type {} expects {} argument(s), found {}", self.inference.resolve(&callee.expr_type), params.len(), arguments.len())
+                    ));
+                }
+                let params = params.clone();
+                let return_type = (**return_type).clone();
+                arguments = arguments.into_iter().zip(&params)
+                    .map(|(arg, param_type)| {
+                        let span = arg.span;
+                        self.coerce(arg, param_type).map_err(|e| e.located_at(span.start_position()))
+                    })
+                    .collect::<OvieResult<Vec<_>>>()?;
+                return_type
+            }
+            // A value of a type not known yet is a function taking arguments of these types
+            HirType::Infer(_) => {
+                let return_type = HirType::Infer(self.next_id());
+                let function_type = HirType::Function {
+                    params: arguments.iter().map(|arg| arg.expr_type.clone()).collect(),
+                    return_type: Box::new(return_type.clone()),
+                };
+                self.unify(&function_type, &callee.expr_type, callee.span)?;
+                return_type
+            }
+            HirType::Error => HirType::Error,
+            other => {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("Cannot call a value of type {}", self.inference.resolve(other))
+                ));
+            }
+        };
+
+        Ok((HirExpressionKind::IndirectCall {
+            callee: Box::new(callee),
+            arguments,
+        }, return_type))
+    }
+
+    /// Check `operand?` against the enclosing function's return type, returning the
+    /// expression and the type of the success variant's data
+    fn propagate(&mut self, mut operand: HirExpression) -> OvieResult<(HirExpressionKind, HirType)> {
+        self.known_type(&operand.expr_type);
+        operand.expr_type = self.inference.resolve(&operand.expr_type);
+        let (success, failure) = Self::propagation_variants(&operand.expr_type)
+            .filter(|(success, failure)| {
+                // Each variant carries at most one value
+                [success, failure].iter().all(|variant| self.variant_data(&operand.expr_type, variant)
+                    .is_some_and(|fields| fields.len() <= 1))
+            })
+            .ok_or_else(|| OvieError::semantic_error(
+                0, 0,
+                format!("'?' expects a Result or Option, found {}", operand.expr_type)
+            ))?;
+        let family = if success == "Ok" { "Result" } else { "Option" };
+
+        let return_type = self.return_type.as_ref()
+            .map(|return_type| self.inference.resolve(return_type))
+            .filter(|return_type| Self::propagation_variants(return_type) == Some((success, failure)))
+            .ok_or_else(|| OvieError::semantic_error(
+                0, 0,
+                format!("'?' can only be used in a function that returns {}", family)
+            ))?;
+        // The returned variant carries the operand's failure data
+        match (self.variant_data(&return_type, failure), self.variant_data(&operand.expr_type, failure)) {
+            (Some(expected), Some(found)) => match (expected.as_slice(), found.as_slice()) {
+                ([expected], [found]) => self.check_assignable(expected, found)?,
+                ([], []) => {}
+                _ => return Err(OvieError::type_error(0, 0, &return_type.to_string(), &operand.expr_type.to_string(), vec![])),
+            },
+            _ => return Err(OvieError::semantic_error(
+                0, 0,
+                format!("'?' cannot return {} from a function that returns {}", failure, return_type)
+            )),
+        }
+
+        let value_type = self.variant_data(&operand.expr_type, success)
+            .and_then(|fields| fields.into_iter().next())
+            .unwrap_or(HirType::Unit);
+        Ok((HirExpressionKind::Try {
+            operand: Box::new(operand),
+            success: success.to_string(),
+            failure: failure.to_string(),
+        }, value_type))
+    }
+
+    /// Success and failure variants `?` splits a value of type `ty` into: `Ok` and `Err`
+    /// of a Result, `Some` and `None` of an Option
+    fn propagation_variants(ty: &HirType) -> Option<(&'static str, &'static str)> {
+        let HirType::Enum { name, .. } = ty else { return None };
+        match name.rsplit("::").next() {
+            Some("Result") => Some(("Ok", "Err")),
+            Some("Option") => Some(("Some", "None")),
+            _ => None,
+        }
+    }
+
+    /// Declared field types of `variant` in the enum type `ty` with its type arguments substituted;
+    /// `None` if the enum has no such variant
+    fn variant_data(&self, ty: &HirType, variant: &str) -> Option<Vec<HirType>> {
+        let HirType::Enum { name, arguments } = ty else { return None };
+        match self.type_table.types.get(name) {
+            Some(TypeInfo::Enum { type_parameters, variants }) => {
+                let bindings = type_parameters.iter().cloned().zip(arguments.iter().cloned()).collect();
+                variants.get(variant).map(|fields| fields.iter()
+                    .map(|(_, field_type)| Self::substitute(field_type, &bindings))
+                    .collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// Call of the function item `function`, instantiating a generic callee with the types its arguments bind
+    fn direct_call(&mut self, function: &str, func_info: &SymbolInfo, mut arguments: Vec<HirExpression>) -> OvieResult<(HirExpressionKind, HirType)> {
+        let mut type_arguments = Vec::new();
+        let return_type = if let HirType::Function { params, return_type } = &func_info.symbol_type {
+            let type_parameters = self.generic_functions.get(function).cloned().unwrap_or_default();
+            let argument_types: Vec<HirType> = arguments.iter().map(|arg| arg.expr_type.clone()).collect();
+            let bindings = self.instantiate(&type_parameters, params, &argument_types);
+            type_arguments = type_parameters.iter().map(|param| bindings[param].clone()).collect();
+            if let Some(bounds) = self.function_bounds.get(function).cloned() {
+                for param in &type_parameters {
+                    for trait_name in bounds.get(param).into_iter().flatten() {
+                        let constraint = Constraint::Bound {
+                            ty: bindings[param].clone(),
+                            trait_name: trait_name.clone(),
+                            function: function.to_string(),
+                            span: self.current_span,
+                        };
+                        match self.inference.shallow(&bindings[param]) {
+                            HirType::Infer(_) => self.constraints.push(constraint),
+                            _ => self.check_constraint(constraint)?,
+                        }
+                    }
+                }
+            }
+
+            let mut checked = Vec::new();
+            let mut remaining = arguments.into_iter();
+            for (param_type, arg) in params.iter().zip(remaining.by_ref()) {
+                let span = arg.span;
+                checked.push(self.coerce(arg, &Self::substitute(param_type, &bindings))
+                    .map_err(|e| e.located_at(span.start_position()))?);
+            }
+            checked.extend(remaining);
+            arguments = checked;
+            Self::substitute(return_type, &bindings)
+        } else {
+            HirType::Infer(self.next_id())
+        };
+
+        Ok((HirExpressionKind::Call {
+            function: function.to_string(),
+            type_arguments,
+            arguments,
+        }, return_type))
+    }
+
+    /// Qualified name of the type a method call receiver names, when it is a type rather than a value
+    fn associated_type(&self, receiver: &Expression) -> Option<Symbol> {
+        let Expression::Identifier(name, _) = receiver else {
+            return None;
+        };
+        if matches!(self.symbol_table.lookup(name), Ok(info) if !info.is_function) {
+            return None;
+        }
+        let type_name = self.module_scope.resolve(name).ok()?;
+        self.type_table.types.contains_key(&type_name).then_some(type_name)
+    }
+
+    /// Function type expected of the closure passed as argument `index` of `function`,
+    /// with the type parameters of a generic callee bound by the arguments before it
+    fn expected_argument_type(&mut self, function: &str, index: usize, previous: &[HirExpression]) -> Option<HirType> {
+        let params = match self.symbol_table.lookup(function) {
+            Ok(SymbolInfo { symbol_type: HirType::Function { params, .. }, is_function: true, .. }) => params,
+            _ => return None,
+        };
+        let type_parameters = self.generic_functions.get(function).cloned().unwrap_or_default();
+        let found: Vec<HirType> = previous.iter().map(|arg| arg.expr_type.clone()).collect();
+        let bindings = self.instantiate(&type_parameters, &params, &found);
+        params.get(index).map(|param| Self::substitute(param, &bindings))
+    }
+
+    /// Enter the body of a loop, which a label must not share with an enclosing loop
+    fn enter_loop(&mut self, label: &Option<Symbol>) -> OvieResult<()> {
+        if label.is_some() && self.loops.contains(label) {
+            return Err(OvieError::semantic_error(
+                0, 0,
+                format!("Loop label '{}' is already used by an enclosing loop", label.as_deref().unwrap_or_default())
+            ));
+        }
+        self.loops.push(label.clone());
+        Ok(())
+    }
+
+    /// Check that a `break` or `continue` is inside a loop, and that its label names an enclosing loop
+    fn check_loop_exit(&self, keyword: &str, label: &Option<Symbol>) -> OvieResult<()> {
+        match label {
+            _ if self.loops.is_empty() => Err(OvieError::semantic_error(
+                0, 0,
+                format!("'{}' outside of a loop", keyword)
+            )),
+            Some(name) if !self.loops.contains(label) => Err(OvieError::semantic_error(
+                0, 0,
+                format!("No enclosing loop is labeled '{}' for '{}'", name, keyword)
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record `name` as captured by every closure being transformed that it is defined outside of
+    fn capture(&mut self, name: &str) {
+        let scope = match self.symbol_table.scope_of(name) {
+            Some((scope, info)) if !info.is_function && !self.is_constant(name) => scope,
+            _ => return,
+        };
+        for (closure_scope, captures) in &mut self.closures {
+            if scope < *closure_scope && !captures.iter().any(|captured| captured == name) {
+                captures.push(name.to_string());
+            }
+        }
+    }
+
+    /// Whether `name` is a variable defined outside the innermost closure being transformed
+    fn is_captured(&self, name: &str) -> bool {
+        match (self.symbol_table.scope_of(name), self.closures.last()) {
+            (Some((scope, info)), Some((closure_scope, _))) => !info.is_function && !self.is_constant(name) && scope < *closure_scope,
+            _ => false,
+        }
+    }
+
+    /// Transform the target of an assignment into a place
+    fn transform_place(&mut self, target: &Expression) -> OvieResult<HirPlace> {
+        let span = target.span();
+        let (kind, place_type) = match target {
+            Expression::Identifier(name, span) => {
+                let Ok(info) = self.symbol_table.lookup(name) else {
+                    // Reported with the other unresolved names; the place has the error type
+                    let candidates = self.names_in_scope(false);
+                    let message = format!("Undefined variable '{}'", name);
+                    self.report(unresolved_name(ResolutionErrorKind::Variable, name, message, *span, candidates.iter().map(String::as_str)));
+                    return Ok(HirPlace { kind: HirPlaceKind::Local(name.clone()), place_type: HirType::Error, span: *span });
+                };
+                if info.is_function {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Cannot assign to function '{}'", name)
+                    ));
+                }
+                if self.is_captured(name) {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Closures cannot assign to captured variable '{}'", name)
+                    ));
+                }
+                (HirPlaceKind::Local(name.clone()), info.symbol_type)
+            }
+            Expression::FieldAccess { object, field, span } => {
+                let object = self.transform_place(object)?;
+                let field_type = match self.missing_field(&object.place_type, field, *span) {
+                    Some(error) => {
+                        self.report(error);
+                        HirType::Error
+                    }
+                    None => self.field_type(&object.place_type, field, *span)?,
+                };
+                (HirPlaceKind::Field { object: Box::new(object), field: field.clone() }, field_type)
+            }
+            Expression::Index { object, index, .. } => {
+                let object = self.transform_place(object)?;
+                let element_type = match self.known_type(&object.place_type) {
+                    HirType::Array(element_type) => *element_type,
+                    // Already reported
+                    HirType::Error => HirType::Error,
+                    HirType::String => {
+                        return Err(OvieError::semantic_error(0, 0, "Strings are immutable; characters cannot be assigned by index"));
+                    }
+                    // Only an array has elements that can be assigned
+                    HirType::Infer(_) => {
+                        let element_type = HirType::Infer(self.next_id());
+                        self.unify(&HirType::Array(Box::new(element_type.clone())), &object.place_type, span)?;
+                        element_type
+                    }
+                    other => return Err(OvieError::type_error(0, 0, "array", &format!("{:?}", other), vec![])),
+                };
+                let index = self.transform_expression(index)?;
+                match self.inference.shallow(&index.expr_type) {
+                    HirType::Int | HirType::Number => {}
+                    HirType::Infer(_) => self.unify(&HirType::Int, &index.expr_type, index.span)?,
+                    other => return Err(OvieError::type_error(0, 0, "Int", &format!("{:?}", other), vec![])
+                        .located_at(index.span.start_position())),
+                }
+                (HirPlaceKind::Index { object: Box::new(object), index: Box::new(index) }, element_type)
+            }
+            _ => {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    "Invalid assignment target; expected a variable, field or array element"
+                ));
+            }
+        };
+        Ok(HirPlace { kind, place_type, span })
+    }
+
+    /// Assigning to a place changes the variable it starts from, which must be declared `mut`.
+    /// A violation is reported at the assignment, with a note at the declaration.
+    fn check_place_mutable(&mut self, place: &HirPlace) {
+        let mut root = place;
+        while let HirPlaceKind::Field { object, .. } | HirPlaceKind::Index { object, .. } = &root.kind {
+            root = object;
+        }
+        let HirPlaceKind::Local(name) = &root.kind else { return };
+        let Ok(declaration) = self.symbol_table.lookup(name) else { return };
+
+        let (kind, message, note) = if self.is_constant(name) {
+            (
+                MutabilityErrorKind::Constant,
+                format!("Cannot assign to constant '{}'", name),
+                format!("constant '{}' is declared here", name),
+            )
+        } else if declaration.is_mutable {
+            return;
+        } else {
+            let (help, note) = if self.pattern_bindings.contains(&declaration.span) {
+                ("copy it into a 'mut' variable to change it", format!("'{}' is bound here", name))
+            } else {
+                ("declare it with 'mut'", format!("'{}' is declared here without 'mut'", name))
+            };
+            if std::ptr::eq(root, place) {
+                (
+                    MutabilityErrorKind::ImmutableVariable,
+                    format!("Cannot assign to '{}' because it is not mutable; {}", name, help),
+                    note,
+                )
+            } else {
+                (
+                    MutabilityErrorKind::ImmutablePlace,
+                    format!("Cannot assign to '{}' because '{}' is not mutable; {}", place, name, help),
+                    note,
+                )
+            }
+        };
+        let error = OvieError::kind_error(0, 0, kind, name, message)
+            .located_at(place.span.start_position())
+            .with_related_location(declaration.span.start_position(), note);
+        self.report(error);
+    }
+
+    /// Qualified name of the item `name` refers to, unless a local variable shadows it
+    fn resolve_name(&self, name: &str) -> OvieResult<String> {
+        match self.symbol_table.lookup(name) {
+            Ok(info) if !info.is_function && !self.is_constant(name) => Ok(name.to_string()),
+            _ => self.module_scope.resolve(name),
+        }
+    }
+
+    /// Whether `name` refers to a module-level constant rather than a local variable shadowing it
+    fn is_constant(&self, name: &str) -> bool {
+        self.constants.contains(name) && matches!(self.symbol_table.scope_of(name), Some((0, _)))
+    }
+
+    /// Report an error that leaves the rest of the program checkable, such as a name
+    /// that does not resolve, and carry on checking it
+    fn report(&mut self, error: OvieError) {
+        let (path, file) = &self.module_origin;
+        let error = path.attribute_error(file.as_deref(), error);
+        self.errors.push(error);
+    }
+
+    /// Report a name that does not resolve, and stand in for the expression using it
+    /// with one of the error type
+    fn unresolved(&mut self, error: OvieError) -> HirExpression {
+        self.report(error);
+        self.error_expression()
+    }
+
+    /// Expression of the error type, standing in for one already reported
+    fn error_expression(&mut self) -> HirExpression {
+        HirExpression {
+            id: self.next_id(),
+            kind: HirExpressionKind::Literal(HirLiteral::Unit),
+            expr_type: HirType::Error,
+            span: self.current_span,
+        }
+    }
+
+    /// Names of the values in scope, which a name that does not resolve may have been
+    /// meant as; `callable` keeps only functions and values of function type
+    fn names_in_scope(&self, callable: bool) -> Vec<String> {
+        let keep = |info: &SymbolInfo| !callable || info.is_function
+            || matches!(self.inference.shallow(&info.symbol_type), HirType::Function { .. });
+        let mut names: Vec<String> = self.symbol_table.visible_symbols()
+            .filter(|(name, info)| !name.contains("::") && keep(info))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        // Items of other modules are in the symbol table by their qualified names
+        names.extend(self.module_scope.names()
+            .filter(|(_, qualified)| matches!(self.symbol_table.lookup(qualified), Ok(info) if keep(&info)))
+            .map(|(name, _)| name.to_string()));
+        names
+    }
+
+    /// Names of the structs in scope, which a struct name that does not resolve may have been meant as
+    fn struct_names(&self) -> Vec<String> {
+        let is_struct = |name: &str| matches!(self.type_table.types.get(name), Some(TypeInfo::Struct { .. }));
+        let mut names: Vec<String> = self.type_table.types.keys()
+            .filter(|name| !name.contains("::") && is_struct(name))
+            .cloned()
+            .collect();
+        names.extend(self.module_scope.names()
+            .filter(|(_, qualified)| is_struct(qualified))
+            .map(|(name, _)| name.to_string()));
+        names
+    }
+
+    /// Resolve a type annotation to HIR type
+    fn resolve_type(&self, annotation: &TypeAnnotation) -> OvieResult<HirType> {
+        match annotation {
+            TypeAnnotation::Unit => Ok(HirType::Unit),
+            TypeAnnotation::Array(element) => Ok(HirType::Array(Box::new(self.resolve_type(element)?))),
+            TypeAnnotation::Tuple(elements) => Ok(HirType::Tuple(elements.iter()
+                .map(|element| self.resolve_type(element))
+                .collect::<OvieResult<Vec<_>>>()?)),
+            TypeAnnotation::Function { parameters, return_type } => {
+                let params = parameters.iter()
+                    .map(|param| self.resolve_type(param))
+                    .collect::<OvieResult<Vec<_>>>()?;
+                Ok(HirType::Function {
+                    params,
+                    return_type: Box::new(self.resolve_type(return_type)?),
+                })
+            }
+            TypeAnnotation::Named { name, arguments } if arguments.is_empty() && self.type_parameters.contains(name) => {
+                Ok(HirType::Param(name.clone()))
+            }
+            TypeAnnotation::Named { name, arguments } if arguments.is_empty() && name == "Self" => {
+                self.self_type.clone().ok_or_else(|| OvieError::semantic_error(
+                    0, 0,
+                    "'Self' can only be used inside an impl block"
+                ))
+            }
+            TypeAnnotation::Named { name, arguments } => {
+                let name = &self.module_scope.resolve(name)?;
+                let primitive = match name.as_str() {
+                    "String" => Some(HirType::String),
+                    "Number" => Some(HirType::Number),
+                    "Int" => Some(HirType::Int),
+                    "Boolean" => Some(HirType::Boolean),
+                    "Unit" => Some(HirType::Unit),
+                    _ => None,
+                };
+                if let Some(primitive) = primitive {
+                    return self.apply_type_arguments(name, &[], arguments).map(|_| primitive);
+                }
+
+                // Check if it's a user-defined type
+                match self.type_table.types.get(name) {
+                    Some(TypeInfo::Struct { type_parameters, .. }) => Ok(HirType::Struct {
+                        name: name.clone(),
+                        arguments: self.apply_type_arguments(name, type_parameters, arguments)?,
+                    }),
+                    Some(TypeInfo::Enum { type_parameters, .. }) => Ok(HirType::Enum {
+                        name: name.clone(),
+                        arguments: self.apply_type_arguments(name, type_parameters, arguments)?,
+                    }),
+                    None => Err(OvieError::kind_error(
+                        0, 0,
+                        ResolutionErrorKind::Type,
+                        name,
+                        format!("Unknown type: {}", name)
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Resolve the type arguments written for `name`, checking there is one per type parameter
+    fn apply_type_arguments(&self, name: &str, type_parameters: &[Symbol], arguments: &[TypeAnnotation]) -> OvieResult<Vec<HirType>> {
+        if arguments.len() != type_parameters.len() {
+            return Err(OvieError::semantic_error(
+                0, 0,
+                format!("Type '{}' expects {} type argument(s), found {}", name, type_parameters.len(), arguments.len())
+            ));
+        }
+        arguments.iter().map(|argument| self.resolve_type(argument)).collect()
+    }
+
+    /// Bind `type_parameters` by matching `expected` types against the `found` types
+    /// at a use site. Parameters nothing binds become fresh inference variables.
+    fn instantiate(&mut self, type_parameters: &[Symbol], expected: &[HirType], found: &[HirType]) -> HashMap<Symbol, HirType> {
+        let mut bindings = HashMap::new();
+        for (expected, found) in expected.iter().zip(found) {
+            Self::bind_type_parameters(expected, &self.inference.resolve(found), type_parameters, &mut bindings);
+        }
+        for param in type_parameters {
+            if !bindings.contains_key(param) {
+                let fresh = HirType::Infer(self.next_id());
+                bindings.insert(param.clone(), fresh);
+            }
+        }
+        bindings
+    }
+
+    /// Record what each type parameter in `expected` stands for in `found`; the first binding wins
+    fn bind_type_parameters(expected: &HirType, found: &HirType, type_parameters: &[Symbol], bindings: &mut HashMap<Symbol, HirType>) {
+        match (expected, found) {
+            (_, HirType::Infer(_) | HirType::Error) => {}
+            (HirType::Param(name), _) if type_parameters.contains(name) => {
+                bindings.entry(name.clone()).or_insert_with(|| found.clone());
+            }
+            (HirType::Struct { name, arguments: expected_args }, HirType::Struct { name: found_name, arguments: found_args })
+            | (HirType::Enum { name, arguments: expected_args }, HirType::Enum { name: found_name, arguments: found_args })
+                if name == found_name =>
+            {
+                for (expected, found) in expected_args.iter().zip(found_args) {
+                    Self::bind_type_parameters(expected, found, type_parameters, bindings);
+                }
+            }
+            (
+                HirType::Function { params: expected_params, return_type: expected_return },
+                HirType::Function { params: found_params, return_type: found_return },
+            ) => {
+                for (expected, found) in expected_params.iter().zip(found_params) {
+                    Self::bind_type_parameters(expected, found, type_parameters, bindings);
+                }
+                Self::bind_type_parameters(expected_return, found_return, type_parameters, bindings);
+            }
+            (HirType::Range(expected), HirType::Range(found))
+            | (HirType::Array(expected), HirType::Array(found)) => {
+                Self::bind_type_parameters(expected, found, type_parameters, bindings);
+            }
+            (HirType::Tuple(expected), HirType::Tuple(found)) => {
+                for (expected, found) in expected.iter().zip(found) {
+                    Self::bind_type_parameters(expected, found, type_parameters, bindings);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replace bound type parameters in `ty`
+    fn substitute(ty: &HirType, bindings: &HashMap<Symbol, HirType>) -> HirType {
+        let substitute_all = |types: &[HirType]| types.iter().map(|ty| Self::substitute(ty, bindings)).collect();
+        match ty {
+            HirType::Param(name) => bindings.get(name).cloned().unwrap_or_else(|| ty.clone()),
+            HirType::Struct { name, arguments } => HirType::Struct {
+                name: name.clone(),
+                arguments: substitute_all(arguments),
+            },
+            HirType::Enum { name, arguments } => HirType::Enum {
+                name: name.clone(),
+                arguments: substitute_all(arguments),
+            },
+            HirType::Function { params, return_type } => HirType::Function {
+                params: substitute_all(params),
+                return_type: Box::new(Self::substitute(return_type, bindings)),
+            },
+            HirType::Range(inner) => HirType::Range(Box::new(Self::substitute(inner, bindings))),
+            HirType::Array(inner) => HirType::Array(Box::new(Self::substitute(inner, bindings))),
+            HirType::Tuple(elements) => HirType::Tuple(substitute_all(elements)),
+            _ => ty.clone(),
+        }
+    }
+
+    /// Type of a variable declaration: the annotation if present (checked
+    /// against the initializer), otherwise the initializer's type
+    fn declared_type(&mut self, annotation: Option<&TypeAnnotation>, initializer: HirExpression) -> OvieResult<(HirType, HirExpression)> {
+        match annotation {
+            Some(annotation) => {
+                let declared = self.resolve_type(annotation)?;
+                let span = initializer.span;
+                let initializer = self.coerce(initializer, &declared)
+                    .map_err(|e| e.located_at(span.start_position()))?;
+                Ok((declared, initializer))
+            }
+            None => Ok((initializer.expr_type.clone(), initializer)),
+        }
+    }
+
+    /// `expr` checked against the `expected` type
+    fn coerce(&mut self, expr: HirExpression, expected: &HirType) -> OvieResult<HirExpression> {
+        self.unify(expected, &expr.expr_type, expr.span)?;
+        Ok(expr)
+    }
+
+    /// Check that a value of type `found` can be used where `expected` is declared
+    fn check_assignable(&mut self, expected: &HirType, found: &HirType) -> OvieResult<()> {
+        self.unify(expected, found, self.current_span)
+    }
+
+    /// Unify two types for the constraint at `span`. A mismatch with an inferred type
+    /// also points at where that type was inferred.
+    fn unify(&mut self, expected: &HirType, found: &HirType, span: SourceSpan) -> OvieResult<()> {
+        let error = match self.inference.unify(expected, found, span) {
+            Ok(()) => return Ok(()),
+            Err(UnifyError::Mismatch { origin, .. }) => {
+                let (expected, found) = (self.inference.resolve(expected), self.inference.resolve(found));
+                // Int and Number never convert implicitly
+                let suggestions = match (&expected, &found) {
+                    (HirType::Number, HirType::Int) => vec![ErrorSuggestion::type_fix(
+                        "Convert the Int with `to_number`".to_string(), 0.9, None,
+                    )],
+                    (HirType::Int, HirType::Number) => vec![ErrorSuggestion::type_fix(
+                        "Convert the Number with `to_int`, which truncates toward zero".to_string(), 0.9, None,
+                    )],
+                    _ => vec![],
+                };
+                let error = OvieError::type_error(0, 0, &expected.to_string(), &found.to_string(), suggestions);
+                match origin {
+                    Some((origin, ty)) if origin != span => error.with_related_location(
+                        origin.start_position(),
+                        format!("type {} was inferred here", self.inference.resolve(&ty))
+                    ),
+                    _ => error,
+                }
+            }
+            Err(UnifyError::Occurs { ty, .. }) => OvieError::semantic_error(
+                0, 0,
+                format!("Cannot infer a type that contains itself: _ = {}", self.inference.resolve(&ty))
+            ),
+        };
+        Err(error.located_at(span.start_position()))
+    }
+
+    /// A type error for a value of type `found` used where `expected` describes what is
+    /// needed, pointing also at where `found` was inferred
+    fn inferred_mismatch(&self, expected: &str, found: &HirType, span: SourceSpan) -> OvieError {
+        let resolved = self.inference.resolve(found);
+        let error = OvieError::type_error(0, 0, expected, &resolved.to_string(), vec![])
+            .located_at(span.start_position());
+        match self.inference.origin(found) {
+            Some(origin) if origin != span => error.with_related_location(
+                origin.start_position(),
+                format!("type {} was inferred here", resolved)
+            ),
+            _ => error,
+        }
+    }
+
+    /// `ty` as far as it is known now, after checking the constraints that can be
+    fn known_type(&mut self, ty: &HirType) -> HirType {
+        self.solve_constraints(false);
+        self.inference.shallow(ty)
+    }
+
+    /// Check the waiting constraints whose types are known, until no more are. With
+    /// `settle`, the others are then settled by defaulting: a numeric operand to Int,
+    /// a value whose field is accessed to the one struct that has the field.
+    fn solve_constraints(&mut self, settle: bool) {
+        loop {
+            let mut progress = false;
+            for constraint in std::mem::take(&mut self.constraints) {
+                if let HirType::Infer(_) = self.inference.shallow(constraint.subject()) {
+                    self.constraints.push(constraint);
+                    continue;
+                }
+                progress = true;
+                if let Err(e) = self.check_constraint(constraint) {
+                    self.errors.push(e);
+                }
             }
-            // Logical operations
-            (HirType::Boolean, HirBinaryOp::And | HirBinaryOp::Or, HirType::Boolean) => {
-                Ok(HirType::Boolean)
+            if progress {
+                continue;
             }
-            _ => {
-                Err(OvieError::type_error(
-                    0, 0,
-                    &format!("{:?}", left),
-                    &format!("{:?}", right),
-                    vec![]
-                ))
+            if !settle {
+                return;
             }
-        }
-    }
 
-    /// Check unary operation type compatibility
-    fn check_unary_op_type(&self, op: &HirUnaryOp, operand: &HirType) -> OvieResult<HirType> {
-        match (op, operand) {
-            (HirUnaryOp::Not, HirType::Boolean) => Ok(HirType::Boolean),
-            (HirUnaryOp::Neg, HirType::Number) => Ok(HirType::Number),
-            _ => {
-                Err(OvieError::type_error(
-                    0, 0,
-                    "Number",
-                    &format!("{:?}", operand),
-                    vec![]
-                ))
+            let Some(index) = self.constraints.iter()
+                .position(|constraint| matches!(constraint, Constraint::Numeric { .. } | Constraint::Field { .. }))
+            else {
+                // What is left leaves a type undetermined, which is reported where it is used
+                self.constraints.clear();
+                return;
+            };
+            let defaulted = match self.constraints.remove(index) {
+                Constraint::Numeric { operand, span, .. } => self.unify(&HirType::Int, &operand, span),
+                Constraint::Field { object, field, field_type, span } => {
+                    let constraint = Constraint::Field { object: object.clone(), field: field.clone(), field_type, span };
+                    self.default_struct(&object, &field, span).map(|_| self.constraints.push(constraint))
+                }
+                _ => Ok(()),
+            };
+            if let Err(e) = defaulted {
+                self.errors.push(e);
             }
         }
     }
 
-    /// Get field type from struct type
-    fn get_field_type(&self, struct_type: &HirType, field_name: &str) -> OvieResult<HirType> {
-        if let HirType::Struct(struct_name) = struct_type {
-            if let Some(TypeInfo::Struct { fields }) = self.type_table.types.get(struct_name) {
-                if let Some(field_type) = fields.get(field_name) {
-                    Ok(field_type.clone())
+    /// Check a constraint whose subject type is known
+    fn check_constraint(&mut self, constraint: Constraint) -> OvieResult<()> {
+        match constraint {
+            Constraint::Numeric { operand, allows_string, span } => {
+                match self.inference.shallow(&operand) {
+                    HirType::Int | HirType::Number | HirType::Error => Ok(()),
+                    HirType::String if allows_string => Ok(()),
+                    _ => Err(self.inferred_mismatch(
+                        if allows_string { "Int, Number or String" } else { "Int or Number" },
+                        &operand,
+                        span
+                    )),
+                }
+            }
+            Constraint::Field { object, field, field_type, span } => {
+                if let Some(error) = self.missing_field(&object, &field, span) {
+                    return Err(error);
+                }
+                let found = self.get_field_type(&self.inference.resolve(&object), &field)
+                    .map_err(|e| e.located_at(span.start_position()))?;
+                self.unify(&field_type, &found, span)
+            }
+            Constraint::Index { object, element, span } => match self.inference.shallow(&object) {
+                HirType::Array(found) => self.unify(&element, &found, span),
+                HirType::String => self.unify(&element, &HirType::String, span),
+                HirType::Error => Ok(()),
+                _ => Err(self.inferred_mismatch("array or String", &object, span)),
+            },
+            Constraint::Iterate { iterable, element, span } => match self.inference.shallow(&iterable) {
+                HirType::Array(found) | HirType::Range(found) => self.unify(&element, &found, span),
+                HirType::Error => Ok(()),
+                _ => Err(self.inferred_mismatch("array or range", &iterable, span)),
+            },
+            Constraint::Bound { ty, trait_name, function, span } => {
+                let ty = self.inference.resolve(&ty);
+                if self.implements(&ty, &trait_name) {
+                    Ok(())
                 } else {
                     Err(OvieError::semantic_error(
                         0, 0,
-                        format!("Field '{}' not found in struct '{}'", field_name, struct_name)
-                    ))
+                        format!("Type '{}' does not implement trait '{}' required by '{}'", ty, trait_name, function)
+                    ).located_at(span.start_position()))
                 }
-            } else {
-                Err(OvieError::semantic_error(
-                    0, 0,
-                    format!("Struct '{}' not found", struct_name)
-                ))
             }
-        } else {
-            Err(OvieError::type_error(
-                0, 0,
-                "struct",
-                &format!("{:?}", struct_type),
-                vec![]
-            ))
         }
     }
 
-    /// Resolve type name to HIR type
-    fn resolve_type(&self, type_name: &str) -> OvieResult<HirType> {
-        match type_name {
-            "String" => Ok(HirType::String),
-            "Number" => Ok(HirType::Number),
-            "Boolean" => Ok(HirType::Boolean),
-            "Unit" => Ok(HirType::Unit),
-            _ => {
-                // Check if it's a user-defined type
-                if self.type_table.types.contains_key(type_name) {
-                    Ok(HirType::Struct(type_name.to_string()))
-                } else {
-                    Err(OvieError::semantic_error(
-                        0, 0,
-                        format!("Unknown type: {}", type_name)
-                    ))
+    /// Infer the type of a value whose field `field` is accessed as the one struct with that field
+    fn default_struct(&mut self, object: &HirType, field: &str, span: SourceSpan) -> OvieResult<()> {
+        let mut candidates: Vec<(Symbol, Vec<Symbol>)> = self.type_table.types.iter()
+            .filter_map(|(name, info)| match info {
+                TypeInfo::Struct { type_parameters, fields } if fields.contains_key(field) => {
+                    Some((name.clone(), type_parameters.clone()))
                 }
+                _ => None,
+            })
+            .collect();
+        candidates.sort();
+        match candidates.as_slice() {
+            [(name, type_parameters)] => {
+                let bindings = self.instantiate(type_parameters, &[], &[]);
+                let struct_type = HirType::Struct {
+                    name: name.clone(),
+                    arguments: type_parameters.iter().map(|param| bindings[param].clone()).collect(),
+                };
+                self.unify(&struct_type, object, span)
             }
+            [] => Err(OvieError::semantic_error(
+                0, 0,
+                format!("No struct has a field '{}'", field)
+            ).located_at(span.start_position())),
+            _ => Err(OvieError::semantic_error(
+                0, 0,
+                format!(
+                    "Cannot infer which struct has the field '{}' ({}); add a type annotation",
+                    field,
+                    candidates.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", ")
+                )
+            ).located_at(span.start_position())),
         }
     }
 
@@ -1120,6 +3542,7 @@ impl HirBuilder {
         }
         
         self.type_table.types.insert(name.to_string(), TypeInfo::Struct {
+            type_parameters: self.type_parameters.clone(),
             fields: field_types,
         });
         
@@ -1130,15 +3553,15 @@ impl HirBuilder {
     fn register_enum_type(&mut self, name: &str, variants: &[crate::ast::EnumVariant]) -> OvieResult<()> {
         let mut variant_types = HashMap::new();
         for variant in variants {
-            let data_type = if let Some(ref type_name) = variant.data_type {
-                Some(self.resolve_type(type_name)?)
-            } else {
-                None
-            };
-            variant_types.insert(variant.name.clone(), data_type);
+            let mut field_types = Vec::new();
+            for (field_name, type_name) in variant.payload.fields() {
+                field_types.push((field_name, self.resolve_type(type_name)?));
+            }
+            variant_types.insert(variant.name.clone(), field_types);
         }
         
         self.type_table.types.insert(name.to_string(), TypeInfo::Enum {
+            type_parameters: self.type_parameters.clone(),
             variants: variant_types,
         });
         
@@ -1146,25 +3569,348 @@ impl HirBuilder {
     }
 
     /// Register function in symbol table
-    fn register_function(&mut self, name: &str, parameters: &[String]) -> OvieResult<()> {
-        let param_types = vec![HirType::Infer(self.next_id()); parameters.len()];
-        let return_type = HirType::Infer(self.next_id());
+    fn register_function(&mut self, name: &str, parameters: &[Parameter], return_type: Option<&TypeAnnotation>, span: SourceSpan) -> OvieResult<()> {
+        let mut param_types = Vec::new();
+        for param in parameters {
+            param_types.push(self.resolve_parameter_type(param)?);
+        }
+        let return_type = match return_type {
+            Some(annotation) => self.resolve_type(annotation)?,
+            None => HirType::Infer(self.next_id()),
+        };
         
         let func_type = HirType::Function {
             params: param_types,
             return_type: Box::new(return_type),
         };
+        if !self.type_parameters.is_empty() {
+            self.generic_functions.insert(name.to_string(), self.type_parameters.clone());
+        }
         
         self.symbol_table.insert(name.to_string(), SymbolInfo {
             symbol_type: func_type,
             is_mutable: false,
             is_function: true,
-            span: SourceSpan::default(),
+            span,
         })?;
         
         Ok(())
     }
 
+    /// Register the methods of trait `name`, each as a function generic over `Self`
+    fn register_trait(&mut self, name: &str, methods: &[TraitMethod]) -> OvieResult<()> {
+        if self.type_table.types.contains_key(name) {
+            return Err(OvieError::semantic_error(
+                0, 0,
+                format!("'{}' is already defined as a type", name)
+            ));
+        }
+        self.self_type = Some(HirType::Param("Self".to_string()));
+
+        for method in methods {
+            let function = format!("{}::{}", name, method.name);
+            let located = |e: OvieError| e.located_at(method.span.start_position());
+
+            if self.type_table.traits[name].methods.contains_key(&method.name) {
+                return Err(located(OvieError::semantic_error(
+                    0, 0,
+                    format!("Duplicate method '{}' in trait '{}'", method.name, name)
+                )));
+            }
+            if let Some(param) = method.parameters.iter().skip(1).find(|param| param.name == "self") {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("'self' must be the first parameter of method '{}'", method.name)
+                ).located_at(param.span.start_position()));
+            }
+
+            self.type_parameters = std::iter::once("Self".to_string()).chain(method.type_parameters.iter().cloned()).collect();
+            let mut bounds = self.resolve_bounds(&method.bounds)?;
+            bounds.entry("Self".to_string()).or_default().push(name.to_string());
+            self.validate_function_signature(&function, &method.parameters).map_err(located)?;
+            // A trait method without a declared return type returns nothing
+            let return_type = method.return_type.as_ref().unwrap_or(&TypeAnnotation::Unit);
+            self.register_function(&function, &method.parameters, Some(return_type), method.span).map_err(located)?;
+            self.function_bounds.insert(function.clone(), bounds);
+
+            let takes_self = method.parameters.first().is_some_and(|param| param.name == "self");
+            self.type_table.traits.get_mut(name).expect("trait registered in the first pass").methods
+                .insert(method.name.clone(), TraitMethodInfo { function, takes_self, has_default: method.body.is_some() });
+        }
+        Ok(())
+    }
+
+    /// Register the methods of an impl block for `type_name`. An inherent impl must be in the
+    /// module `path` that defines the type; an impl of `trait_name` must be in the module of
+    /// the trait or of the type.
+    fn register_impl(&mut self, path: &ModulePath, trait_name: Option<&str>, type_name: &str, type_parameters: &[String], bounds: &[TypeBound], methods: &[Statement]) -> OvieResult<()> {
+        let resolved = self.impl_target(type_name)?;
+        let builtin = Self::builtin_type(&resolved).is_some();
+        // Builtin types have no impl blocks of their own, only trait impls
+        let known = self.type_table.types.contains_key(&resolved) || (builtin && trait_name.is_some());
+        if !known {
+            return Err(OvieError::kind_error(0, 0, ResolutionErrorKind::Type, type_name, format!("Unknown type: {}", type_name)));
+        }
+        let local = |name: &str| path.qualify(name.rsplit("::").next().unwrap_or(name)) == name;
+
+        let trait_name = match trait_name {
+            Some(trait_name) => {
+                let qualified = self.module_scope.resolve(trait_name)?;
+                let info = self.type_table.traits.get(&qualified).ok_or_else(|| OvieError::kind_error(
+                    0, 0,
+                    ResolutionErrorKind::Trait,
+                    trait_name,
+                    format!("Unknown trait: {}", trait_name)
+                ))?;
+                if info.impls.contains_key(&resolved) {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Conflicting implementations of trait '{}' for type '{}'", trait_name, type_name)
+                    ));
+                }
+                if !local(&qualified) && (builtin || !local(&resolved)) {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Impl of trait '{}' for '{}' must be in the module that defines the trait or the type", trait_name, type_name)
+                    ));
+                }
+                Some(qualified)
+            }
+            None if !local(&resolved) => {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("Methods of '{}' must be defined in the module that defines it", type_name)
+                ));
+            }
+            None => None,
+        };
+        self.self_type = Some(self.impl_self_type(&resolved, type_parameters)?);
+        let impl_bounds = self.resolve_bounds(bounds)?;
+        self.type_table.methods.entry(resolved.clone()).or_default();
+
+        let mut defined = HashSet::new();
+        for method in methods {
+            let Statement::Function { name, is_unsafe, type_parameters: method_type_parameters, bounds: method_bounds, parameters, return_type, span, .. } = method else {
+                continue;
+            };
+            let function = format!("{}::{}", resolved, name);
+            let located = |e: OvieError| e.located_at(span.start_position());
+
+            if self.type_table.method(&resolved, name).is_some() {
+                return Err(located(OvieError::semantic_error(
+                    0, 0,
+                    format!("Duplicate method '{}' for type '{}'", name, type_name)
+                )));
+            }
+            if let Some(param) = parameters.iter().skip(1).find(|param| param.name == "self") {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("'self' must be the first parameter of method '{}'", name)
+                ).located_at(param.span.start_position()));
+            }
+            if let Some(trait_name) = &trait_name {
+                if !self.type_table.traits[trait_name].methods.contains_key(name) {
+                    return Err(located(OvieError::semantic_error(
+                        0, 0,
+                        format!("Method '{}' is not a member of trait '{}'", name, trait_name)
+                    )));
+                }
+            }
+
+            self.type_parameters = type_parameters.iter().chain(method_type_parameters).cloned().collect();
+            self.validate_function_signature(&function, parameters).map_err(located)?;
+            if *is_unsafe {
+                self.unsafe_functions.insert(function.clone());
+            }
+            self.register_function(&function, parameters, return_type.as_ref(), *span).map_err(located)?;
+            let mut bounds = impl_bounds.clone();
+            for (param, traits) in self.resolve_bounds(method_bounds).map_err(located)? {
+                bounds.entry(param).or_default().extend(traits);
+            }
+            if !bounds.is_empty() {
+                self.function_bounds.insert(function.clone(), bounds);
+            }
+            if let Some(trait_name) = &trait_name {
+                self.check_impl_method(trait_name, name, &function, method_type_parameters).map_err(located)?;
+            }
+
+            let takes_self = parameters.first().is_some_and(|param| param.name == "self");
+            self.type_table.methods.entry(resolved.clone()).or_default()
+                .insert(name.clone(), MethodInfo { function, takes_self });
+            defined.insert(name.clone());
+        }
+
+        if let Some(trait_name) = trait_name {
+            let mut declared: Vec<(Symbol, TraitMethodInfo)> = self.type_table.traits[&trait_name].methods.iter()
+                .filter(|(name, _)| !defined.contains(*name))
+                .map(|(name, info)| (name.clone(), info.clone()))
+                .collect();
+            declared.sort_by(|a, b| a.0.cmp(&b.0));
+            if let Some((name, _)) = declared.iter().find(|(_, info)| !info.has_default) {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("Missing method '{}' in impl of trait '{}' for '{}'", name, trait_name, type_name)
+                ));
+            }
+            // The type uses the defaults of the methods it does not define
+            for (name, info) in declared {
+                if self.type_table.method(&resolved, &name).is_some() {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Duplicate method '{}' for type '{}'", name, type_name)
+                    ));
+                }
+                self.type_table.methods.entry(resolved.clone()).or_default()
+                    .insert(name, MethodInfo { function: info.function, takes_self: info.takes_self });
+            }
+            self.type_table.traits.get_mut(&trait_name).expect("trait checked above").impls.insert(resolved, ImplInfo {
+                type_parameters: type_parameters.to_vec(),
+                bounds: impl_bounds,
+                methods: defined,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that impl method `function` has the signature `method` of the trait declares,
+    /// with `Self` standing for the implementing type
+    fn check_impl_method(&mut self, trait_name: &str, method: &str, function: &str, type_parameters: &[String]) -> OvieResult<()> {
+        let declared = &self.type_table.traits[trait_name].methods[method].function;
+        let trait_type_parameters = &self.generic_functions[declared][1..];
+        if trait_type_parameters.len() != type_parameters.len() {
+            return Err(OvieError::semantic_error(
+                0, 0,
+                format!("Method '{}' has {} type parameter(s) but its declaration in trait '{}' has {}", method, type_parameters.len(), trait_name, trait_type_parameters.len())
+            ));
+        }
+
+        let mut bindings: HashMap<Symbol, HirType> = trait_type_parameters.iter().cloned()
+            .zip(type_parameters.iter().map(|param| HirType::Param(param.clone())))
+            .collect();
+        bindings.insert("Self".to_string(), self.self_type.clone().unwrap_or(HirType::Error));
+        let expected = Self::substitute(&self.symbol_table.lookup(declared)?.symbol_type, &bindings);
+        let found = self.symbol_table.lookup(function)?;
+        // Unannotated parameters of the method take the types the trait declares
+        if self.inference.unify(&expected, &found.symbol_type, found.span).is_err() {
+            return Err(OvieError::semantic_error(
+                0, 0,
+                format!("Method '{}' does not match its declaration in trait '{}': expected {}, found {}", method, trait_name, expected, self.inference.resolve(&found.symbol_type))
+            ));
+        }
+        Ok(())
+    }
+
+    /// Qualified name of the type an impl block is for; builtin types keep their name
+    fn impl_target(&self, type_name: &str) -> OvieResult<Symbol> {
+        match Self::builtin_type(type_name) {
+            Some(_) => Ok(type_name.to_string()),
+            None => self.module_scope.resolve(type_name),
+        }
+    }
+
+    /// Builtin type named `name` that traits can be implemented for
+    fn builtin_type(name: &str) -> Option<HirType> {
+        match name {
+            "Int" => Some(HirType::Int),
+            "Number" => Some(HirType::Number),
+            "String" => Some(HirType::String),
+            "Boolean" => Some(HirType::Boolean),
+            _ => None,
+        }
+    }
+
+    /// Name impls and methods of values of type `ty` are registered under
+    fn impl_key(ty: &HirType) -> Option<Symbol> {
+        match ty {
+            HirType::Struct { name, .. } | HirType::Enum { name, .. } => Some(name.clone()),
+            HirType::Int | HirType::Number | HirType::String | HirType::Boolean => Some(ty.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Resolve the trait bounds on type parameters, by parameter
+    fn resolve_bounds(&self, bounds: &[TypeBound]) -> OvieResult<HashMap<Symbol, Vec<Symbol>>> {
+        let mut resolved: HashMap<Symbol, Vec<Symbol>> = HashMap::new();
+        for bound in bounds {
+            for trait_name in &bound.traits {
+                let qualified = self.module_scope.resolve(trait_name)?;
+                if !self.type_table.traits.contains_key(&qualified) {
+                    return Err(OvieError::kind_error(
+                        0, 0,
+                        ResolutionErrorKind::Trait,
+                        trait_name,
+                        format!("Unknown trait: {}", trait_name)
+                    ).located_at(bound.span.start_position()));
+                }
+                let traits = resolved.entry(bound.parameter.clone()).or_default();
+                if !traits.contains(&qualified) {
+                    traits.push(qualified);
+                }
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Whether values of type `ty` implement trait `trait_name`: a type parameter through its
+    /// bounds, other types through an impl whose own bounds their type arguments meet
+    fn implements(&self, ty: &HirType, trait_name: &str) -> bool {
+        match ty {
+            HirType::Param(param) => self.type_bounds.get(param).is_some_and(|traits| traits.iter().any(|t| t == trait_name)),
+            HirType::Infer(_) | HirType::Error => true,
+            _ => {
+                let Some(info) = Self::impl_key(ty).and_then(|key| self.type_table.traits.get(trait_name)?.impls.get(&key)) else {
+                    return false;
+                };
+                let arguments = match ty {
+                    HirType::Struct { arguments, .. } | HirType::Enum { arguments, .. } => arguments.as_slice(),
+                    _ => &[],
+                };
+                info.type_parameters.iter().zip(arguments).all(|(param, argument)| {
+                    info.bounds.get(param).into_iter().flatten().all(|bound| self.implements(argument, bound))
+                })
+            }
+        }
+    }
+
+    /// Trait method `method` of a value of type parameter `param`, found in its trait bounds
+    fn bound_method(&self, param: &str, method: &str) -> OvieResult<MethodInfo> {
+        let candidates: Vec<&TraitMethodInfo> = self.type_bounds.get(param).into_iter().flatten()
+            .filter_map(|trait_name| self.type_table.traits.get(trait_name)?.methods.get(method))
+            .collect();
+        match candidates[..] {
+            [info] => Ok(MethodInfo { function: info.function.clone(), takes_self: info.takes_self }),
+            [] => Err(OvieError::semantic_error(
+                0, 0,
+                format!("Type parameter '{}' has no method '{}'; none of its trait bounds declares it", param, method)
+            )),
+            _ => Err(OvieError::semantic_error(
+                0, 0,
+                format!("Method '{}' of type parameter '{}' is declared by more than one of its trait bounds", method, param)
+            )),
+        }
+    }
+
+    /// Type of `self` in an impl block for `type_name` written with `type_parameters`
+    fn impl_self_type(&self, type_name: &str, type_parameters: &[String]) -> OvieResult<HirType> {
+        let arguments: Vec<HirType> = type_parameters.iter().map(|param| HirType::Param(param.clone())).collect();
+        let expected = match self.type_table.types.get(type_name) {
+            Some(TypeInfo::Struct { type_parameters, .. }) | Some(TypeInfo::Enum { type_parameters, .. }) => type_parameters.len(),
+            None if Self::builtin_type(type_name).is_some() => 0,
+            None => return Err(OvieError::kind_error(0, 0, ResolutionErrorKind::Type, type_name, format!("Unknown type: {}", type_name))),
+        };
+        if expected != arguments.len() {
+            return Err(OvieError::semantic_error(
+                0, 0,
+                format!("Type '{}' expects {} type argument(s), found {}", type_name, expected, arguments.len())
+            ));
+        }
+        Ok(match self.type_table.types.get(type_name) {
+            Some(TypeInfo::Struct { .. }) => HirType::Struct { name: type_name.to_string(), arguments },
+            Some(TypeInfo::Enum { .. }) => HirType::Enum { name: type_name.to_string(), arguments },
+            None => Self::builtin_type(type_name).unwrap_or(HirType::Error),
+        })
+    }
+
     /// Validate struct definition
     fn validate_struct_definition(&self, name: &str, fields: &[crate::ast::StructField]) -> OvieResult<()> {
         // Check for duplicate field names
@@ -1185,7 +3931,7 @@ impl HirBuilder {
         }
 
         // Check for reserved names
-        if matches!(name, "String" | "Number" | "Boolean" | "Unit") {
+        if matches!(name, "String" | "Number" | "Int" | "Boolean" | "Unit") {
             return Err(OvieError::SemanticError {
                 line: 0,
                 column: 0,
@@ -1210,15 +3956,23 @@ impl HirBuilder {
             }
         }
 
-        // Validate variant data types exist
+        // Check for duplicate field names and validate field types exist
         for variant in variants {
-            if let Some(ref type_name) = variant.data_type {
+            let mut field_names = std::collections::HashSet::new();
+            for (field_name, type_name) in variant.payload.fields() {
+                if !field_names.insert(field_name.clone()) {
+                    return Err(OvieError::SemanticError {
+                        line: 0,
+                        column: 0,
+                        message: format!("Duplicate field '{}' in variant '{}.{}'", field_name, name, variant.name),
+                    });
+                }
                 self.validate_type_name(type_name)?;
             }
         }
 
         // Check for reserved names
-        if matches!(name, "String" | "Number" | "Boolean" | "Unit") {
+        if matches!(name, "String" | "Number" | "Int" | "Boolean" | "Unit") {
             return Err(OvieError::SemanticError {
                 line: 0,
                 column: 0,
@@ -1239,21 +3993,21 @@ impl HirBuilder {
     }
 
     /// Validate function signature
-    fn validate_function_signature(&self, name: &str, parameters: &[String]) -> OvieResult<()> {
+    fn validate_function_signature(&self, name: &str, parameters: &[Parameter]) -> OvieResult<()> {
         // Check for duplicate parameter names
         let mut param_names = std::collections::HashSet::new();
         for param in parameters {
-            if !param_names.insert(param) {
+            if !param_names.insert(&param.name) {
                 return Err(OvieError::SemanticError {
-                    line: 0,
-                    column: 0,
-                    message: format!("Duplicate parameter '{}' in function '{}'", param, name),
+                    line: param.span.line,
+                    column: param.span.column,
+                    message: format!("Duplicate parameter '{}' in function '{}'", param.name, name),
                 });
             }
         }
 
         // Check for reserved function names
-        if matches!(name, "print" | "to_string") {
+        if BUILTIN_FUNCTIONS.contains(&name) {
             return Err(OvieError::SemanticError {
                 line: 0,
                 column: 0,
@@ -1264,70 +4018,34 @@ impl HirBuilder {
         Ok(())
     }
 
-    /// Validate type name exists
-    fn validate_type_name(&self, type_name: &str) -> OvieResult<()> {
-        match type_name {
-            "String" | "Number" | "Boolean" | "Unit" => Ok(()),
-            _ => {
-                if self.type_table.types.contains_key(type_name) {
-                    Ok(())
-                } else {
-                    Err(OvieError::semantic_error(
-                        0, 0,
-                        format!("Unknown type: {}", type_name)
-                    ))
-                }
-            }
-        }
+    /// Validate that a type annotation names known types
+    fn validate_type_name(&self, annotation: &TypeAnnotation) -> OvieResult<()> {
+        self.resolve_type(annotation).map(|_| ())
     }
 
-    /// Perform type inference on HIR items
+    /// Finish type inference: settle the waiting constraints, then replace the inference
+    /// variables in every type of the program with the types they stand for. A type the
+    /// program leaves undetermined is reported once, where it is first met.
     fn perform_type_inference(&mut self, items: &mut [HirItem]) -> OvieResult<()> {
-        // Simple type inference - replace Infer types with concrete types where possible
+        self.solve_constraints(true);
+
+        let mut substitution = Substitution::new(&self.inference);
         for item in items {
-            match item {
-                HirItem::Function(func) => {
-                    self.infer_function_types(func)?;
-                }
-                HirItem::Global(global) => {
-                    if let Some(ref init) = global.initializer {
-                        global.global_type = init.expr_type.clone();
-                    }
-                }
-                _ => {}
-            }
+            substitution.apply_to_item(item);
         }
-        Ok(())
-    }
-
-    /// Infer types for a function
-    fn infer_function_types(&mut self, func: &mut HirFunction) -> OvieResult<()> {
-        // Infer return type from return statements
-        let mut return_type = HirType::Unit;
-        self.infer_block_return_type(&func.body, &mut return_type)?;
-        
-        if matches!(return_type, HirType::Infer(_)) {
-            return_type = HirType::Unit;
+        let mut reported = HashSet::new();
+        for unresolved in substitution.unresolved {
+            if reported.insert(unresolved.var) {
+                self.errors.push(OvieError::semantic_error(
+                    0, 0,
+                    format!("Cannot infer the type of {}; add a type annotation", unresolved.subject)
+                ).located_at(unresolved.span.start_position()));
+            }
         }
-        
-        func.return_type = return_type;
-        Ok(())
-    }
 
-    /// Infer return type from block
-    fn infer_block_return_type(&self, block: &HirBlock, return_type: &mut HirType) -> OvieResult<()> {
-        for stmt in &block.statements {
-            if let HirStatementKind::Return(Some(ref expr)) = stmt.kind {
-                if matches!(*return_type, HirType::Unit | HirType::Infer(_)) {
-                    *return_type = expr.expr_type.clone();
-                } else if *return_type != expr.expr_type {
-                    return Err(OvieError::type_error(
-                        0, 0,
-                        &format!("{:?}", return_type),
-                        &format!("{:?}", expr.expr_type),
-                        vec![]
-                    ));
-                }
+        for scope in &mut self.symbol_table.scopes {
+            for info in scope.symbols.values_mut() {
+                info.symbol_type = self.inference.settle(&info.symbol_type);
             }
         }
         Ok(())
@@ -1370,10 +4088,10 @@ impl HirBuilder {
                 HirStatementKind::Return(Some(expr)) => {
                     self.validate_expression_calls(expr)?;
                 }
-                HirStatementKind::Local { initializer: Some(expr), .. } => {
+                HirStatementKind::Local { initializer: Some(expr), .. } | HirStatementKind::Destructure { initializer: expr, .. } => {
                     self.validate_expression_calls(expr)?;
                 }
-                HirStatementKind::Assign { value: expr, .. } => {
+                HirStatementKind::Assign { value: expr, .. } | HirStatementKind::CompoundAssign { value: expr, .. } => {
                     self.validate_expression_calls(expr)?;
                 }
                 HirStatementKind::If { condition, then_block, else_block } => {
@@ -1383,7 +4101,7 @@ impl HirBuilder {
                         self.validate_function_calls(else_blk)?;
                     }
                 }
-                HirStatementKind::While { condition, body } => {
+                HirStatementKind::While { condition, body, .. } => {
                     self.validate_expression_calls(condition)?;
                     self.validate_function_calls(body)?;
                 }
@@ -1391,6 +4109,9 @@ impl HirBuilder {
                     self.validate_expression_calls(iterable)?;
                     self.validate_function_calls(body)?;
                 }
+                HirStatementKind::Unsafe { body } => {
+                    self.validate_function_calls(body)?;
+                }
                 _ => {}
             }
         }
@@ -1400,12 +4121,12 @@ impl HirBuilder {
     /// Validate function calls in an expression
     fn validate_expression_calls(&self, expr: &HirExpression) -> OvieResult<()> {
         match &expr.kind {
-            HirExpressionKind::Call { function, arguments } => {
+            HirExpressionKind::Call { function, arguments, .. } => {
                 // Check if function exists
                 if let Err(_) = self.symbol_table.lookup(function) {
                     return Err(OvieError::SemanticError {
-                        line: 0,
-                        column: 0,
+                        line: expr.span.line,
+                        column: expr.span.column,
                         message: format!("Function '{}' not found", function),
                     });
                 }
@@ -1419,7 +4140,7 @@ impl HirBuilder {
                 self.validate_expression_calls(left)?;
                 self.validate_expression_calls(right)?;
             }
-            HirExpressionKind::Unary { operand, .. } => {
+            HirExpressionKind::Unary { operand, .. } | HirExpressionKind::Try { operand, .. } => {
                 self.validate_expression_calls(operand)?;
             }
             HirExpressionKind::FieldAccess { object, .. } => {
@@ -1434,6 +4155,24 @@ impl HirBuilder {
                 self.validate_expression_calls(start)?;
                 self.validate_expression_calls(end)?;
             }
+            HirExpressionKind::Match { scrutinee, arms } => {
+                self.validate_expression_calls(scrutinee)?;
+                for arm in arms {
+                    self.validate_function_calls(&arm.body)?;
+                    if let Some(value) = &arm.value {
+                        self.validate_expression_calls(value)?;
+                    }
+                }
+            }
+            HirExpressionKind::Closure { body, .. } => {
+                self.validate_function_calls(body)?;
+            }
+            HirExpressionKind::IndirectCall { callee, arguments } => {
+                self.validate_expression_calls(callee)?;
+                for arg in arguments {
+                    self.validate_expression_calls(arg)?;
+                }
+            }
             _ => {}
         }
         Ok(())
@@ -1452,10 +4191,10 @@ impl HirBuilder {
                 HirStatementKind::Return(Some(expr)) => {
                     self.validate_expression_field_accesses(expr)?;
                 }
-                HirStatementKind::Local { initializer: Some(expr), .. } => {
+                HirStatementKind::Local { initializer: Some(expr), .. } | HirStatementKind::Destructure { initializer: expr, .. } => {
                     self.validate_expression_field_accesses(expr)?;
                 }
-                HirStatementKind::Assign { value: expr, .. } => {
+                HirStatementKind::Assign { value: expr, .. } | HirStatementKind::CompoundAssign { value: expr, .. } => {
                     self.validate_expression_field_accesses(expr)?;
                 }
                 HirStatementKind::If { condition, then_block, else_block } => {
@@ -1465,7 +4204,7 @@ impl HirBuilder {
                         self.validate_field_accesses(else_blk)?;
                     }
                 }
-                HirStatementKind::While { condition, body } => {
+                HirStatementKind::While { condition, body, .. } => {
                     self.validate_expression_field_accesses(condition)?;
                     self.validate_field_accesses(body)?;
                 }
@@ -1473,6 +4212,9 @@ impl HirBuilder {
                     self.validate_expression_field_accesses(iterable)?;
                     self.validate_field_accesses(body)?;
                 }
+                HirStatementKind::Unsafe { body } => {
+                    self.validate_field_accesses(body)?;
+                }
                 _ => {}
             }
         }
@@ -1490,7 +4232,7 @@ impl HirBuilder {
                 self.validate_expression_field_accesses(left)?;
                 self.validate_expression_field_accesses(right)?;
             }
-            HirExpressionKind::Unary { operand, .. } => {
+            HirExpressionKind::Unary { operand, .. } | HirExpressionKind::Try { operand, .. } => {
                 self.validate_expression_field_accesses(operand)?;
             }
             HirExpressionKind::Call { arguments, .. } => {
@@ -1507,6 +4249,24 @@ impl HirBuilder {
                 self.validate_expression_field_accesses(start)?;
                 self.validate_expression_field_accesses(end)?;
             }
+            HirExpressionKind::Match { scrutinee, arms } => {
+                self.validate_expression_field_accesses(scrutinee)?;
+                for arm in arms {
+                    self.validate_field_accesses(&arm.body)?;
+                    if let Some(value) = &arm.value {
+                        self.validate_expression_field_accesses(value)?;
+                    }
+                }
+            }
+            HirExpressionKind::Closure { body, .. } => {
+                self.validate_field_accesses(body)?;
+            }
+            HirExpressionKind::IndirectCall { callee, arguments } => {
+                self.validate_expression_field_accesses(callee)?;
+                for arg in arguments {
+                    self.validate_expression_field_accesses(arg)?;
+                }
+            }
             _ => {}
         }
         Ok(())
@@ -1583,6 +4343,25 @@ impl SymbolTable {
     }
 }
 
+impl SymbolTable {
+    /// Symbols visible from the current scope, innermost scope first
+    pub fn visible_symbols(&self) -> impl Iterator<Item = (&str, &SymbolInfo)> {
+        std::iter::successors(Some(self.current_scope), |&scope| self.scopes[scope].parent)
+            .flat_map(|scope| self.scopes[scope].symbols.iter().map(|(name, info)| (name.as_str(), info)))
+    }
+
+    /// Index of the innermost scope defining `name`, with the symbol it defines
+    fn scope_of(&self, name: &str) -> Option<(usize, &SymbolInfo)> {
+        let mut current = self.current_scope;
+        loop {
+            if let Some(info) = self.scopes[current].symbols.get(name) {
+                return Some((current, info));
+            }
+            current = self.scopes[current].parent?;
+        }
+    }
+}
+
 impl Scope {
     fn new() -> Self {
         Self {
@@ -1597,8 +4376,27 @@ impl TypeTable {
     pub fn new() -> Self {
         Self {
             types: HashMap::new(),
+            methods: HashMap::new(),
+            traits: HashMap::new(),
         }
     }
+
+    /// Method or associated function `method` of the type `type_name`
+    pub fn method(&self, type_name: &str, method: &str) -> Option<&MethodInfo> {
+        self.methods.get(type_name)?.get(method)
+    }
+
+    /// Trait `name`
+    pub fn trait_info(&self, name: &str) -> Option<&TraitInfo> {
+        self.traits.get(name)
+    }
+
+    /// Trait and method name of `function` when it is a `Trait::method` function
+    pub fn trait_method<'a>(&self, function: &'a str) -> Option<(&TraitInfo, &'a str)> {
+        let (trait_name, method) = function.rsplit_once("::")?;
+        let info = self.traits.get(trait_name)?;
+        info.methods.contains_key(method).then_some((info, method))
+    }
 }
 
 impl HirProgram {
@@ -1687,8 +4485,8 @@ impl HirProgram {
             HirItem::Enum(enum_def) => {
                 // All variant data types must be resolved
                 for variant in &enum_def.variants {
-                    if let Some(ref data_type) = variant.data_type {
-                        if matches!(data_type, HirType::Error | HirType::Infer(_)) {
+                    for field in &variant.fields {
+                        if matches!(field.field_type, HirType::Error | HirType::Infer(_)) {
                             return Err(crate::ast::InvariantError {
                                 message: format!("Enum '{}' has unresolved variant type", enum_def.name),
                                 location: Some(format!("enum:{}:variant:{}", enum_def.name, variant.name)),
@@ -1738,7 +4536,10 @@ impl HirProgram {
                     self.validate_expression_invariants(init)?;
                 }
             }
-            HirStatementKind::Assign { target, value } => {
+            HirStatementKind::Destructure { initializer, .. } => {
+                self.validate_expression_invariants(initializer)?;
+            }
+            HirStatementKind::Assign { target, value } | HirStatementKind::CompoundAssign { target, value, .. } => {
                 self.validate_place_invariants(target)?;
                 self.validate_expression_invariants(value)?;
             }
@@ -1760,7 +4561,7 @@ impl HirProgram {
                     self.validate_block_invariants(else_blk)?;
                 }
             }
-            HirStatementKind::While { condition, body } => {
+            HirStatementKind::While { condition, body, .. } => {
                 self.validate_expression_invariants(condition)?;
                 self.validate_block_invariants(body)?;
             }
@@ -1768,6 +4569,12 @@ impl HirProgram {
                 self.validate_expression_invariants(iterable)?;
                 self.validate_block_invariants(body)?;
             }
+            HirStatementKind::Unsafe { body } => {
+                self.validate_block_invariants(body)?;
+            }
+            HirStatementKind::Break { .. } | HirStatementKind::Continue { .. } => {
+                // Loop labels were resolved when the HIR was built
+            }
         }
         Ok(())
     }
@@ -1793,7 +4600,7 @@ impl HirProgram {
                 self.validate_expression_invariants(left)?;
                 self.validate_expression_invariants(right)?;
             }
-            HirExpressionKind::Unary { operand, .. } => {
+            HirExpressionKind::Unary { operand, .. } | HirExpressionKind::Try { operand, .. } => {
                 self.validate_expression_invariants(operand)?;
             }
             HirExpressionKind::Call { arguments, .. } => {
@@ -1813,20 +4620,38 @@ impl HirProgram {
                 self.validate_expression_invariants(start)?;
                 self.validate_expression_invariants(end)?;
             }
-            HirExpressionKind::EnumVariant { data, .. } => {
-                if let Some(data_expr) = data {
-                    self.validate_expression_invariants(data_expr)?;
+            HirExpressionKind::EnumVariant { fields, .. } => {
+                for field_init in fields {
+                    self.validate_expression_invariants(&field_init.value)?;
                 }
             }
             HirExpressionKind::Index { object, index } => {
                 self.validate_expression_invariants(object)?;
                 self.validate_expression_invariants(index)?;
             }
-            HirExpressionKind::ArrayLiteral { elements } => {
+            HirExpressionKind::ArrayLiteral { elements } | HirExpressionKind::Tuple { elements } => {
                 for element in elements {
                     self.validate_expression_invariants(element)?;
                 }
             }
+            HirExpressionKind::Match { scrutinee, arms } => {
+                self.validate_expression_invariants(scrutinee)?;
+                for arm in arms {
+                    self.validate_block_invariants(&arm.body)?;
+                    if let Some(value) = &arm.value {
+                        self.validate_expression_invariants(value)?;
+                    }
+                }
+            }
+            HirExpressionKind::Closure { body, .. } => {
+                self.validate_block_invariants(body)?;
+            }
+            HirExpressionKind::IndirectCall { callee, arguments } => {
+                self.validate_expression_invariants(callee)?;
+                for arg in arguments {
+                    self.validate_expression_invariants(arg)?;
+                }
+            }
         }
         Ok(())
     }
@@ -1848,6 +4673,10 @@ impl HirProgram {
             HirPlaceKind::Field { object, .. } => {
                 self.validate_place_invariants(object)?;
             }
+            HirPlaceKind::Index { object, index } => {
+                self.validate_place_invariants(object)?;
+                self.validate_expression_invariants(index)?;
+            }
         }
         Ok(())
     }
@@ -1877,8 +4706,8 @@ impl HirProgram {
                 }
                 HirItem::Enum(enum_def) => {
                     for variant in &enum_def.variants {
-                        if let Some(ref data_type) = variant.data_type {
-                            if self.type_contains_error(data_type) {
+                        for field in &variant.fields {
+                            if self.type_contains_error(&field.field_type) {
                                 return Err(crate::ast::InvariantError {
                                     message: format!("Enum '{}' contains unresolved types", enum_def.name),
                                     location: Some(format!("enum:{}", enum_def.name)),
@@ -0,0 +1,480 @@
+//! Type inference by unification
+//!
+//! Types the HIR builder cannot know when it meets them (an unannotated parameter
+//! or return type, the element type of an empty array, the type arguments of a
+//! generic call) start out as inference variables, `HirType::Infer`. Each use of a
+//! value constrains its type to the type expected there, and the builder solves
+//! the constraint on the spot by unifying the two types. Checks that can only be
+//! made once a type is known, such as a field access on a value whose type is not
+//! inferred yet, wait as [`Constraint`]s. When the whole program is transformed
+//! the bindings are applied to every type in it, so no variable reaches MIR.
+
+use crate::hir::{
+    HirBlock, HirExpression, HirExpressionKind, HirFunction, HirItem, HirPlace, HirPlaceKind,
+    HirStatement, HirStatementKind, HirType, SourceSpan, Symbol,
+};
+use std::collections::HashMap;
+
+/// Bindings of the inference variables of one program
+#[derive(Debug, Default)]
+pub struct InferenceTable {
+    /// Type each bound variable stands for, with the span of the constraint that bound it
+    bindings: HashMap<u32, (HirType, SourceSpan)>,
+}
+
+/// Why two types could not be unified
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnifyError {
+    /// The types differ; `origin` is where a variable standing for one of the
+    /// differing types was bound, and the type on that side of the unification.
+    /// `in_expected` tells whether the variable was on the expected side.
+    Mismatch { origin: Option<(SourceSpan, HirType)>, in_expected: bool },
+    /// Binding `var` to `ty` would make a type that contains itself
+    Occurs { var: u32, ty: HirType },
+}
+
+/// A check that waits until the type it is about is inferred
+#[derive(Debug, Clone)]
+pub enum Constraint {
+    /// An arithmetic or comparison operand must be an Int or Number; `+` also takes Strings
+    Numeric { operand: HirType, allows_string: bool, span: SourceSpan },
+    /// `object.field` has type `field_type`
+    Field { object: HirType, field: Symbol, field_type: HirType, span: SourceSpan },
+    /// Indexing the array or String `object` gives an `element`
+    Index { object: HirType, element: HirType, span: SourceSpan },
+    /// Iterating over the array or range `iterable` gives an `element`
+    Iterate { iterable: HirType, element: HirType, span: SourceSpan },
+    /// `ty` must implement trait `trait_name`, as a bound of `function` requires
+    Bound { ty: HirType, trait_name: Symbol, function: Symbol, span: SourceSpan },
+}
+
+impl Constraint {
+    /// Type that must be known before the constraint can be checked
+    pub fn subject(&self) -> &HirType {
+        match self {
+            Constraint::Numeric { operand, .. } => operand,
+            Constraint::Field { object, .. } | Constraint::Index { object, .. } => object,
+            Constraint::Iterate { iterable, .. } => iterable,
+            Constraint::Bound { ty, .. } => ty,
+        }
+    }
+}
+
+impl InferenceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `ty` with any bound variables at its top replaced, so its outermost type is known
+    /// unless it is an unbound variable
+    pub fn shallow(&self, ty: &HirType) -> HirType {
+        self.shallow_with_origin(ty).0
+    }
+
+    /// Where the variables at the top of `ty` got the type they stand for, if `ty` is one
+    pub fn origin(&self, ty: &HirType) -> Option<SourceSpan> {
+        self.shallow_with_origin(ty).1
+    }
+
+    /// `ty` with any bound variables at its top replaced, and where the last of them was bound
+    fn shallow_with_origin(&self, ty: &HirType) -> (HirType, Option<SourceSpan>) {
+        let mut ty = ty;
+        let mut origin = None;
+        while let HirType::Infer(var) = ty {
+            match self.bindings.get(var) {
+                Some((bound, span)) => {
+                    ty = bound;
+                    origin = Some(*span);
+                }
+                None => break,
+            }
+        }
+        (ty.clone(), origin)
+    }
+
+    /// `ty` with every bound variable in it replaced
+    pub fn resolve(&self, ty: &HirType) -> HirType {
+        let resolve_all = |types: &[HirType]| types.iter().map(|ty| self.resolve(ty)).collect();
+        match self.shallow(ty) {
+            HirType::Struct { name, arguments } => HirType::Struct { name, arguments: resolve_all(&arguments) },
+            HirType::Enum { name, arguments } => HirType::Enum { name, arguments: resolve_all(&arguments) },
+            HirType::Function { params, return_type } => HirType::Function {
+                params: resolve_all(&params),
+                return_type: Box::new(self.resolve(&return_type)),
+            },
+            HirType::Range(inner) => HirType::Range(Box::new(self.resolve(&inner))),
+            HirType::Array(inner) => HirType::Array(Box::new(self.resolve(&inner))),
+            HirType::Tuple(elements) => HirType::Tuple(resolve_all(&elements)),
+            other => other,
+        }
+    }
+
+    /// `ty` resolved, with the variables the program leaves unbound replaced by the error type
+    pub fn settle(&self, ty: &HirType) -> HirType {
+        let mut ty = self.resolve(ty);
+        erase_variables(&mut ty);
+        ty
+    }
+
+    /// Make `expected` and `found` the same type by binding the variables in them.
+    /// `span` is the source of the constraint, recorded for each variable it binds.
+    /// Nothing is bound if the types cannot be unified.
+    pub fn unify(&mut self, expected: &HirType, found: &HirType, span: SourceSpan) -> Result<(), UnifyError> {
+        let mut bound = Vec::new();
+        let result = self.unify_recording(expected, found, span, &mut bound);
+        if result.is_err() {
+            for var in bound {
+                self.bindings.remove(&var);
+            }
+        }
+        result
+    }
+
+    /// Unify two types, recording the variables bound in `bound`
+    fn unify_recording(&mut self, expected: &HirType, found: &HirType, span: SourceSpan, bound: &mut Vec<u32>) -> Result<(), UnifyError> {
+        let (expected, expected_origin) = self.shallow_with_origin(expected);
+        let (found, found_origin) = self.shallow_with_origin(found);
+        let result = match (&expected, &found) {
+            (HirType::Infer(a), HirType::Infer(b)) if a == b => Ok(()),
+            // A variable unified with the type of an erroneous expression is bound to the
+            // error type too, so the error is not reported again as a type that cannot be inferred
+            (HirType::Infer(var), other) | (other, HirType::Infer(var)) => {
+                if self.occurs(*var, other) {
+                    return Err(UnifyError::Occurs { var: *var, ty: other.clone() });
+                }
+                self.bindings.insert(*var, (other.clone(), span));
+                bound.push(*var);
+                Ok(())
+            }
+            (HirType::Error, _) | (_, HirType::Error) => Ok(()),
+            (HirType::Range(expected), HirType::Range(found))
+            | (HirType::Array(expected), HirType::Array(found)) => self.unify_recording(expected, found, span, bound),
+            (HirType::Tuple(expected), HirType::Tuple(found)) if expected.len() == found.len() => {
+                self.unify_all(expected, found, span, bound)
+            }
+            (HirType::Struct { name, arguments: expected_args }, HirType::Struct { name: found_name, arguments: found_args })
+            | (HirType::Enum { name, arguments: expected_args }, HirType::Enum { name: found_name, arguments: found_args })
+                if name == found_name && expected_args.len() == found_args.len() =>
+            {
+                self.unify_all(expected_args, found_args, span, bound)
+            }
+            (
+                HirType::Function { params: expected_params, return_type: expected_return },
+                HirType::Function { params: found_params, return_type: found_return },
+            ) if expected_params.len() == found_params.len() => {
+                self.unify_all(expected_params, found_params, span, bound)
+                    .and_then(|_| self.unify_recording(expected_return, found_return, span, bound))
+            }
+            _ if expected == found => Ok(()),
+            _ => Err(UnifyError::Mismatch { origin: None, in_expected: false }),
+        };
+
+        // A mismatch inside these types is explained by where they were inferred,
+        // and described by the whole type on that side rather than the part that differs
+        result.map_err(|error| match error {
+            UnifyError::Mismatch { origin: None, .. } => match (expected_origin, found_origin) {
+                (Some(span), _) => UnifyError::Mismatch { origin: Some((span, expected.clone())), in_expected: true },
+                (None, Some(span)) => UnifyError::Mismatch { origin: Some((span, found.clone())), in_expected: false },
+                (None, None) => error,
+            },
+            UnifyError::Mismatch { origin: Some((span, _)), in_expected } => UnifyError::Mismatch {
+                origin: Some((span, if in_expected { expected.clone() } else { found.clone() })),
+                in_expected,
+            },
+            other => other,
+        })
+    }
+
+    fn unify_all(&mut self, expected: &[HirType], found: &[HirType], span: SourceSpan, bound: &mut Vec<u32>) -> Result<(), UnifyError> {
+        expected.iter().zip(found)
+            .try_for_each(|(expected, found)| self.unify_recording(expected, found, span, bound))
+    }
+
+    /// Whether variable `var` appears in `ty`
+    fn occurs(&self, var: u32, ty: &HirType) -> bool {
+        match self.shallow(ty) {
+            HirType::Infer(other) => other == var,
+            HirType::Struct { arguments, .. } | HirType::Enum { arguments, .. } => {
+                arguments.iter().any(|argument| self.occurs(var, argument))
+            }
+            HirType::Function { params, return_type } => {
+                params.iter().any(|param| self.occurs(var, param)) || self.occurs(var, &return_type)
+            }
+            HirType::Range(inner) | HirType::Array(inner) => self.occurs(var, &inner),
+            HirType::Tuple(elements) => elements.iter().any(|element| self.occurs(var, element)),
+            _ => false,
+        }
+    }
+}
+
+/// A type the program leaves undetermined
+#[derive(Debug, Clone)]
+pub struct Unresolved {
+    /// A variable still unbound in the type
+    pub var: u32,
+    /// What has the type, such as "parameter 'a' of 'add'"
+    pub subject: String,
+    pub span: SourceSpan,
+}
+
+/// Replaces the inference variables in the types of a program with what they stand for
+pub struct Substitution<'a> {
+    table: &'a InferenceTable,
+    /// Types left with unbound variables, in the order they were met; the variables
+    /// are replaced by the error type
+    pub unresolved: Vec<Unresolved>,
+}
+
+impl<'a> Substitution<'a> {
+    pub fn new(table: &'a InferenceTable) -> Self {
+        Self { table, unresolved: Vec::new() }
+    }
+
+    pub fn apply_to_item(&mut self, item: &mut HirItem) {
+        match item {
+            HirItem::Function(function) => self.apply_to_function(function),
+            HirItem::Struct(hir_struct) => {
+                for invariant in &mut hir_struct.invariants {
+                    self.apply_to_expression(&mut invariant.condition);
+                }
+            }
+            HirItem::Enum(_) => {}
+            HirItem::Global(global) => {
+                let name = global.name.clone();
+                self.apply(&mut global.global_type, global.span, || format!("'{}'", name));
+                if let Some(initializer) = &mut global.initializer {
+                    self.apply_to_expression(initializer);
+                }
+            }
+        }
+    }
+
+    fn apply_to_function(&mut self, function: &mut HirFunction) {
+        let name = function.name.clone();
+        for param in &mut function.parameters {
+            let param_name = param.name.clone();
+            self.apply(&mut param.param_type, param.span, || format!("parameter '{}' of '{}'", param_name, name));
+        }
+        self.apply(&mut function.return_type, function.span, || format!("the return type of '{}'", name));
+        self.apply_to_block(&mut function.body);
+        for contract in function.requires.iter_mut().chain(&mut function.ensures) {
+            self.apply_to_expression(&mut contract.condition);
+        }
+    }
+
+    fn apply_to_block(&mut self, block: &mut HirBlock) {
+        for statement in &mut block.statements {
+            self.apply_to_statement(statement);
+        }
+    }
+
+    fn apply_to_statement(&mut self, statement: &mut HirStatement) {
+        match &mut statement.kind {
+            HirStatementKind::Local { name, var_type, initializer, .. } => {
+                let name = name.clone();
+                self.apply(var_type, statement.span, || format!("'{}'", name));
+                if let Some(initializer) = initializer {
+                    self.apply_to_expression(initializer);
+                }
+            }
+            HirStatementKind::Destructure { initializer, .. } => self.apply_to_expression(initializer),
+            HirStatementKind::Assign { target, value } | HirStatementKind::CompoundAssign { target, value, .. } => {
+                self.apply_to_place(target);
+                self.apply_to_expression(value);
+            }
+            HirStatementKind::Expression(expression) | HirStatementKind::Print(expression) => {
+                self.apply_to_expression(expression);
+            }
+            HirStatementKind::Return(value) => {
+                if let Some(value) = value {
+                    self.apply_to_expression(value);
+                }
+            }
+            HirStatementKind::If { condition, then_block, else_block } => {
+                self.apply_to_expression(condition);
+                self.apply_to_block(then_block);
+                if let Some(else_block) = else_block {
+                    self.apply_to_block(else_block);
+                }
+            }
+            HirStatementKind::While { condition, body, .. } => {
+                self.apply_to_expression(condition);
+                self.apply_to_block(body);
+            }
+            HirStatementKind::For { iterable, body, .. } => {
+                self.apply_to_expression(iterable);
+                self.apply_to_block(body);
+            }
+            HirStatementKind::Unsafe { body } => self.apply_to_block(body),
+            HirStatementKind::Break { .. } | HirStatementKind::Continue { .. } => {}
+        }
+    }
+
+    fn apply_to_place(&mut self, place: &mut HirPlace) {
+        let target = place.to_string();
+        self.apply(&mut place.place_type, place.span, || format!("'{}'", target));
+        match &mut place.kind {
+            HirPlaceKind::Local(_) => {}
+            HirPlaceKind::Field { object, .. } => self.apply_to_place(object),
+            HirPlaceKind::Index { object, index } => {
+                self.apply_to_place(object);
+                self.apply_to_expression(index);
+            }
+        }
+    }
+
+    fn apply_to_expression(&mut self, expression: &mut HirExpression) {
+        let span = expression.span;
+        match &mut expression.kind {
+            HirExpressionKind::Literal(_) | HirExpressionKind::Variable(_) => {}
+            HirExpressionKind::Binary { left, right, .. } => {
+                self.apply_to_expression(left);
+                self.apply_to_expression(right);
+            }
+            HirExpressionKind::Unary { operand, .. } | HirExpressionKind::Try { operand, .. } => {
+                self.apply_to_expression(operand);
+            }
+            HirExpressionKind::Call { function, type_arguments, arguments } => {
+                let function = function.clone();
+                for type_argument in type_arguments {
+                    self.apply(type_argument, span, || format!("a type argument of '{}'", function));
+                }
+                for argument in arguments {
+                    self.apply_to_expression(argument);
+                }
+            }
+            HirExpressionKind::FieldAccess { object, .. } => self.apply_to_expression(object),
+            HirExpressionKind::StructInit { fields, .. } | HirExpressionKind::EnumVariant { fields, .. } => {
+                for field in fields {
+                    self.apply_to_expression(&mut field.value);
+                }
+            }
+            HirExpressionKind::Range { start, end } => {
+                self.apply_to_expression(start);
+                self.apply_to_expression(end);
+            }
+            HirExpressionKind::Index { object, index } => {
+                self.apply_to_expression(object);
+                self.apply_to_expression(index);
+            }
+            HirExpressionKind::ArrayLiteral { elements } | HirExpressionKind::Tuple { elements } => {
+                for element in elements {
+                    self.apply_to_expression(element);
+                }
+            }
+            HirExpressionKind::Match { scrutinee, arms } => {
+                self.apply_to_expression(scrutinee);
+                for arm in arms {
+                    self.apply_to_block(&mut arm.body);
+                    if let Some(value) = &mut arm.value {
+                        self.apply_to_expression(value);
+                    }
+                }
+            }
+            HirExpressionKind::Closure { parameters, return_type, body, .. } => {
+                for param in parameters {
+                    let name = param.name.clone();
+                    self.apply(&mut param.param_type, param.span, || format!("closure parameter '{}'", name));
+                }
+                self.apply(return_type, span, || "the return type of this closure".to_string());
+                self.apply_to_block(body);
+            }
+            HirExpressionKind::IndirectCall { callee, arguments } => {
+                self.apply_to_expression(callee);
+                for argument in arguments {
+                    self.apply_to_expression(argument);
+                }
+            }
+        }
+        self.apply(&mut expression.expr_type, span, || "this expression".to_string());
+    }
+
+    /// Resolve `ty` in place, recording it if the program leaves part of it undetermined
+    fn apply(&mut self, ty: &mut HirType, span: SourceSpan, subject: impl FnOnce() -> String) {
+        *ty = self.table.resolve(ty);
+        if let Some(var) = first_variable(ty) {
+            self.unresolved.push(Unresolved { var, subject: subject(), span });
+            erase_variables(ty);
+        }
+    }
+}
+
+/// First inference variable in `ty`, if any
+fn first_variable(ty: &HirType) -> Option<u32> {
+    match ty {
+        HirType::Infer(var) => Some(*var),
+        HirType::Struct { arguments, .. } | HirType::Enum { arguments, .. } => arguments.iter().find_map(first_variable),
+        HirType::Function { params, return_type } => params.iter().find_map(first_variable).or_else(|| first_variable(return_type)),
+        HirType::Range(inner) | HirType::Array(inner) => first_variable(inner),
+        HirType::Tuple(elements) => elements.iter().find_map(first_variable),
+        _ => None,
+    }
+}
+
+/// Replace the inference variables in `ty` with the error type
+fn erase_variables(ty: &mut HirType) {
+    match ty {
+        HirType::Infer(_) => *ty = HirType::Error,
+        HirType::Struct { arguments, .. } | HirType::Enum { arguments, .. } => arguments.iter_mut().for_each(erase_variables),
+        HirType::Function { params, return_type } => {
+            params.iter_mut().for_each(erase_variables);
+            erase_variables(return_type);
+        }
+        HirType::Range(inner) | HirType::Array(inner) => erase_variables(inner),
+        HirType::Tuple(elements) => elements.iter_mut().for_each(erase_variables),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(line: usize) -> SourceSpan {
+        SourceSpan::new(0, 1, line, 1)
+    }
+
+    fn array(element: HirType) -> HirType {
+        HirType::Array(Box::new(element))
+    }
+
+    #[test]
+    fn test_unify_binds_variables_through_each_other() {
+        let mut table = InferenceTable::new();
+        table.unify(&HirType::Infer(0), &HirType::Infer(1), span(1)).unwrap();
+        table.unify(&array(HirType::Infer(1)), &array(HirType::Int), span(2)).unwrap();
+
+        assert_eq!(table.resolve(&HirType::Infer(0)), HirType::Int);
+        assert_eq!(table.resolve(&array(HirType::Infer(0))), array(HirType::Int));
+        assert_eq!(table.origin(&HirType::Infer(0)).map(|origin| origin.line), Some(2));
+        assert_eq!(table.settle(&HirType::Tuple(vec![HirType::Infer(0), HirType::Infer(2)])),
+            HirType::Tuple(vec![HirType::Int, HirType::Error]));
+    }
+
+    #[test]
+    fn test_occurs_check_rejects_infinite_types() {
+        let mut table = InferenceTable::new();
+        let error = table.unify(&HirType::Infer(0), &array(HirType::Infer(0)), span(1)).unwrap_err();
+        assert_eq!(error, UnifyError::Occurs { var: 0, ty: array(HirType::Infer(0)) });
+
+        // Through the binding of another variable
+        table.unify(&HirType::Infer(1), &array(HirType::Infer(0)), span(2)).unwrap();
+        let error = table.unify(&HirType::Infer(0), &HirType::Infer(1), span(3)).unwrap_err();
+        assert!(matches!(error, UnifyError::Occurs { var: 0, .. }), "{:?}", error);
+        assert_eq!(table.resolve(&HirType::Infer(0)), HirType::Infer(0));
+
+        // With the variable on the found side
+        let error = table.unify(&array(HirType::Infer(2)), &HirType::Infer(2), span(4)).unwrap_err();
+        assert!(matches!(error, UnifyError::Occurs { var: 2, .. }), "{:?}", error);
+    }
+
+    #[test]
+    fn test_failed_unification_binds_nothing() {
+        let mut table = InferenceTable::new();
+        let expected = HirType::Tuple(vec![HirType::Infer(0), HirType::Int]);
+        let found = HirType::Tuple(vec![HirType::String, HirType::Number]);
+        let error = table.unify(&expected, &found, span(1)).unwrap_err();
+        assert_eq!(error, UnifyError::Mismatch { origin: None, in_expected: false });
+        assert_eq!(table.resolve(&HirType::Infer(0)), HirType::Infer(0));
+    }
+}
@@ -1,18 +1,28 @@
 //! Interpreter for executing Ovie AST
 
-use crate::ast::{AstNode, Statement, Expression, Literal, BinaryOperator, UnaryOperator};
+use crate::ast::{AstNode, Statement, Expression, Literal, BinaryOperator, UnaryOperator, MatchArm, Pattern, VariantPayload, ContractClause};
 use crate::error::{OvieError, OvieResult};
-use std::collections::HashMap;
+use crate::hir::{HirBuilder, HirExpression, HirExpressionKind, HirItem, HirLiteral};
+use crate::modules::{ModuleGraph, ModulePath, ModuleScope};
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
 
 /// Runtime value types
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     String(String),
     Number(f64),
+    Int(i64),
     Boolean(bool),
     Array(Vec<Value>),
-    Struct(HashMap<String, Value>),
-    Enum { variant: String, data: Option<Box<Value>> },
+    Tuple(Vec<Value>),
+    /// Struct value, with the qualified name of its type
+    Struct { name: String, fields: HashMap<String, Value> },
+    /// Enum value, with the qualified name of its type and the variant's payload fields;
+    /// positional fields are named "0", "1", ...
+    Enum { name: String, variant: String, fields: Vec<(String, Value)> },
+    /// Function value with the variables it captured when it was created
+    Function { function: Box<Function>, captures: HashMap<String, Value> },
     Null,
 }
 
@@ -28,25 +38,39 @@ impl Value {
                     format!("{}", n)
                 }
             }
+            Value::Int(n) => n.to_string(),
             Value::Boolean(b) => b.to_string(),
             Value::Array(arr) => {
                 let elements: Vec<String> = arr.iter().map(|v| v.to_string()).collect();
                 format!("[{}]", elements.join(", "))
             }
-            Value::Struct(fields) => {
+            Value::Tuple(elements) if elements.len() == 1 => format!("({},)", elements[0].to_string()),
+            Value::Tuple(elements) => {
+                let elements: Vec<String> = elements.iter().map(|v| v.to_string()).collect();
+                format!("({})", elements.join(", "))
+            }
+            Value::Struct { fields, .. } => {
                 let field_strs: Vec<String> = fields
                     .iter()
                     .map(|(k, v)| format!("{}: {}", k, v.to_string()))
                     .collect();
                 format!("{{ {} }}", field_strs.join(", "))
             }
-            Value::Enum { variant, data } => {
-                if let Some(d) = data {
-                    format!("{}({})", variant, d.to_string())
-                } else {
-                    variant.clone()
+            Value::Enum { variant, fields, .. } => match fields.first() {
+                None => variant.clone(),
+                Some((first, _)) if first == "0" => {
+                    let values: Vec<String> = fields.iter().map(|(_, v)| v.to_string()).collect();
+                    format!("{}({})", variant, values.join(", "))
                 }
-            }
+                Some(_) => {
+                    let field_strs: Vec<String> = fields
+                        .iter()
+                        .map(|(k, v)| format!("{}: {}", k, v.to_string()))
+                        .collect();
+                    format!("{} {{ {} }}", variant, field_strs.join(", "))
+                }
+            },
+            Value::Function { function, .. } => format!("<fn {}>", function.name),
             Value::Null => "null".to_string(),
         }
     }
@@ -57,20 +81,29 @@ impl Value {
             Value::Boolean(b) => *b,
             Value::Null => false,
             Value::Number(n) => *n != 0.0,
+            Value::Int(n) => *n != 0,
             Value::String(s) => !s.is_empty(),
             Value::Array(arr) => !arr.is_empty(),
-            Value::Struct(_) => true,
+            Value::Tuple(_) => true,
+            Value::Struct { .. } => true,
             Value::Enum { .. } => true,
+            Value::Function { .. } => true,
         }
     }
 }
 
 /// Function definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Function {
     pub name: String,
     pub parameters: Vec<String>,
     pub body: Vec<Statement>,
+    /// Index of the module defining the function, whose names its body sees
+    pub module: usize,
+    /// Checked before the body runs, over the parameters
+    pub requires: Vec<ContractClause>,
+    /// Checked once the body returns, over the parameters and the returned value `result`
+    pub ensures: Vec<ContractClause>,
 }
 
 /// Environment for variable and function storage
@@ -118,6 +151,16 @@ impl Environment {
         }
     }
 
+    /// Every variable visible from this environment, inner definitions shadowing outer ones
+    pub fn visible_variables(&self) -> HashMap<String, Value> {
+        let mut variables = match &self.parent {
+            Some(parent) => parent.visible_variables(),
+            None => HashMap::new(),
+        };
+        variables.extend(self.variables.iter().map(|(name, value)| (name.clone(), value.clone())));
+        variables
+    }
+
     pub fn define_function(&mut self, function: Function) {
         self.functions.insert(function.name.clone(), function);
     }
@@ -161,131 +204,284 @@ impl Environment {
     }
 }
 
+/// One step from a variable to the place an assignment updates
+#[derive(Debug)]
+enum PlaceStep {
+    Field(String),
+    Index(usize),
+}
+
+/// How a statement left the code around it instead of falling through to the next statement
+#[derive(Debug)]
+enum Exit {
+    Return(Value),
+    /// `break`, with the label of the loop it leaves
+    Break(Option<String>),
+    /// `continue`, with the label of the loop it restarts
+    Continue(Option<String>),
+}
+
+impl Exit {
+    /// Error for a `break` or `continue` that reached a function body or the top level
+    fn outside_loop(&self) -> OvieError {
+        let (keyword, label) = match self {
+            Exit::Break(label) => ("break", label),
+            Exit::Continue(label) => ("continue", label),
+            Exit::Return(_) => ("return", &None),
+        };
+        match label {
+            Some(label) => OvieError::runtime_error(format!("No enclosing loop is labeled '{}' for '{}'", label, keyword)),
+            None => OvieError::runtime_error(format!("'{}' outside of a loop", keyword)),
+        }
+    }
+
+    /// Whether this exit ends (`break`) or restarts (`continue`) the loop labeled `loop_label`
+    fn targets(label: &Option<String>, loop_label: &Option<String>) -> bool {
+        label.is_none() || label == loop_label
+    }
+}
+
 /// Interpreter for Ovie programs
 pub struct Interpreter {
     environment: Environment,
+    /// Path and scope of each module of the program
+    modules: Vec<(ModulePath, ModuleScope)>,
+    /// Index of the module whose code is executing
+    current_module: usize,
+    /// `Err` or `None` a `?` is returning from the function being called
+    early_return: Option<Value>,
+    /// Default methods of each trait, copied into the impls that do not define them
+    trait_defaults: HashMap<String, Vec<Function>>,
+    /// Declared field names of each variant with named fields, keyed by `Enum.Variant`
+    variant_fields: HashMap<String, Vec<String>>,
+    /// Invariants of each struct that has any, by qualified name
+    struct_invariants: HashMap<String, Vec<ContractClause>>,
+    /// Whether `requires`, `ensures` and `invariant` clauses are checked, as in debug builds
+    check_contracts: bool,
+    /// Variables the running closure captured and has not declared again. Captures are
+    /// copies, so assigning to them is an error rather than a change nobody sees
+    captured: HashSet<String>,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
         Self {
             environment: Environment::new(),
+            modules: vec![(ModulePath::root(), ModuleScope::default())],
+            current_module: 0,
+            early_return: None,
+            trait_defaults: HashMap::new(),
+            variant_fields: HashMap::new(),
+            struct_invariants: HashMap::new(),
+            check_contracts: true,
+            captured: HashSet::new(),
         }
     }
 
+    /// Check or skip contract clauses; release builds skip them
+    pub fn set_check_contracts(&mut self, enabled: bool) {
+        self.check_contracts = enabled;
+    }
+
     /// Interpret an AST
     pub fn interpret(&mut self, ast: &AstNode) -> OvieResult<()> {
-        match ast {
-            AstNode::Program(statements) => {
-                for statement in statements {
-                    self.execute_statement(statement)?;
+        let graph = ModuleGraph::single(ast.clone())?;
+        self.interpret_modules(&graph)
+    }
+
+    /// Interpret a program of several modules: the items of every module are
+    /// defined first, then the other statements of the root module run in order.
+    /// Constants are evaluated at compile time, as for the other backends, so they
+    /// may refer to constants and `const fn`s defined after them.
+    pub fn interpret_modules(&mut self, graph: &ModuleGraph) -> OvieResult<()> {
+        self.modules = graph.modules().iter()
+            .map(|module| (module.path.clone(), module.scope.clone()))
+            .collect();
+
+        let has_constants = graph.modules().iter().any(|module| {
+            let AstNode::Program(statements) = &module.ast;
+            statements.iter().any(|statement| matches!(statement, Statement::Const { .. }))
+        });
+        if has_constants {
+            let hir = HirBuilder::new().transform_module_graph(graph)?;
+            for item in &hir.items {
+                let HirItem::Global(global) = item else { continue };
+                if let (true, Some(HirExpression { kind: HirExpressionKind::Literal(literal), .. })) = (global.is_const, &global.initializer) {
+                    self.environment.define_variable(global.name.clone(), Self::literal_value(literal)?);
                 }
-                Ok(())
             }
         }
+
+        for (index, module) in graph.modules().iter().enumerate() {
+            self.current_module = index;
+            let AstNode::Program(statements) = &module.ast;
+            for statement in statements.iter().filter(|statement| statement.is_item()) {
+                self.execute_statement(statement)?;
+            }
+        }
+        let AstNode::Program(statements) = &graph.root().ast;
+        for statement in statements.iter().filter(|statement| !statement.is_item()) {
+            if let Some(exit @ (Exit::Break(_) | Exit::Continue(_))) = self.execute_statement(statement)? {
+                return Err(exit.outside_loop());
+            }
+        }
+        Ok(())
+    }
+
+    /// Value of an evaluated constant
+    fn literal_value(literal: &HirLiteral) -> OvieResult<Value> {
+        match literal {
+            HirLiteral::Integer(n) => Ok(Value::Int(*n)),
+            HirLiteral::Number(n) => Ok(Value::Number(*n)),
+            HirLiteral::String(s) => Ok(Value::String(s.clone())),
+            HirLiteral::Boolean(b) => Ok(Value::Boolean(*b)),
+            other => Err(OvieError::runtime_error(format!("Constant value {:?} is not supported", other))),
+        }
+    }
+
+    /// Qualified name of an item defined in the executing module
+    fn qualify(&self, name: &str) -> String {
+        self.modules[self.current_module].0.qualify(name)
     }
 
-    /// Execute a statement
-    fn execute_statement(&mut self, statement: &Statement) -> OvieResult<Option<Value>> {
+    /// Qualified name of the item `name` refers to in the executing module
+    fn resolve(&self, name: &str) -> OvieResult<String> {
+        self.modules[self.current_module].1.resolve(name)
+    }
+
+    /// Execute a statement, returning how it left the enclosing code, if it did
+    fn execute_statement(&mut self, statement: &Statement) -> OvieResult<Option<Exit>> {
         match statement {
-            Statement::Print { expression } => {
+            Statement::Print { expression, .. } => {
                 let value = self.evaluate_expression(expression)?;
                 println!("{}", value.to_string());
                 Ok(None)
             }
 
-            Statement::Assignment { identifier, value, .. } => {
+            Statement::Assignment { identifier, value, mutable, .. } => {
+                if !*mutable {
+                    self.check_not_captured(identifier)?;
+                }
                 let evaluated_value = self.evaluate_expression(value)?;
+                self.captured.remove(identifier);
                 self.environment.define_variable(identifier.clone(), evaluated_value);
                 Ok(None)
             }
 
+            Statement::PlaceAssignment { target, operator, value, .. } => {
+                let evaluated_value = self.evaluate_expression(value)?;
+                self.assign_place(target, operator.as_ref(), evaluated_value)?;
+                Ok(None)
+            }
+
             Statement::VariableDeclaration { identifier, value, .. } => {
                 let evaluated_value = self.evaluate_expression(value)?;
+                self.captured.remove(identifier);
                 self.environment.define_variable(identifier.clone(), evaluated_value);
                 Ok(None)
             }
 
-            Statement::Function { name, parameters, body } => {
+            Statement::Destructure { pattern, value, .. } => {
+                let evaluated_value = self.evaluate_expression(value)?;
+                self.destructure(pattern, evaluated_value)?;
+                Ok(None)
+            }
+
+            // Defined with the value compile-time evaluation gave it
+            Statement::Const { .. } => Ok(None),
+
+            Statement::Function { name, parameters, requires, ensures, body, .. } => {
                 let function = Function {
-                    name: name.clone(),
-                    parameters: parameters.clone(),
+                    name: self.qualify(name),
+                    parameters: parameters.iter().map(|p| p.name.clone()).collect(),
                     body: body.clone(),
+                    module: self.current_module,
+                    requires: requires.clone(),
+                    ensures: ensures.clone(),
                 };
                 self.environment.define_function(function);
                 Ok(None)
             }
 
-            Statement::FunctionDeclaration { name, parameters, body } => {
+            Statement::FunctionDeclaration { name, parameters, body, .. } => {
                 let function = Function {
-                    name: name.clone(),
-                    parameters: parameters.clone(),
+                    name: self.qualify(name),
+                    parameters: parameters.iter().map(|p| p.name.clone()).collect(),
                     body: body.clone(),
+                    module: self.current_module,
+                    requires: Vec::new(),
+                    ensures: Vec::new(),
                 };
                 self.environment.define_function(function);
                 Ok(None)
             }
 
-            Statement::If { condition, then_block, else_block } => {
+            Statement::If { condition, then_block, else_block, .. } => {
                 let condition_value = self.evaluate_expression(condition)?;
                 
                 if condition_value.is_truthy() {
                     for stmt in then_block {
-                        if let Some(return_value) = self.execute_statement(stmt)? {
-                            return Ok(Some(return_value));
+                        if let Some(exit) = self.execute_statement(stmt)? {
+                            return Ok(Some(exit));
                         }
                     }
                 } else if let Some(else_stmts) = else_block {
                     for stmt in else_stmts {
-                        if let Some(return_value) = self.execute_statement(stmt)? {
-                            return Ok(Some(return_value));
+                        if let Some(exit) = self.execute_statement(stmt)? {
+                            return Ok(Some(exit));
                         }
                     }
                 }
                 Ok(None)
             }
 
-            Statement::While { condition, body } => {
+            Statement::Unsafe { body, .. } => {
+                for stmt in body {
+                    if let Some(exit) = self.execute_statement(stmt)? {
+                        return Ok(Some(exit));
+                    }
+                }
+                Ok(None)
+            }
+
+            Statement::While { label, condition, body, .. } => {
                 while self.evaluate_expression(condition)?.is_truthy() {
-                    for stmt in body {
-                        if let Some(return_value) = self.execute_statement(stmt)? {
-                            return Ok(Some(return_value));
-                        }
+                    if let ControlFlow::Break(exit) = self.execute_iteration(label, body)? {
+                        return Ok(exit);
                     }
                 }
                 Ok(None)
             }
 
-            Statement::For { identifier, iterable, body } => {
+            Statement::For { label, pattern, iterable, body, .. } => {
                 let iterable_value = self.evaluate_expression(iterable)?;
                 
                 match iterable_value {
                     Value::Array(arr) => {
                         for value in arr {
-                            self.environment.define_variable(
-                                identifier.clone(),
-                                value
-                            );
+                            self.destructure(pattern, value)?;
                             
-                            for stmt in body {
-                                if let Some(return_value) = self.execute_statement(stmt)? {
-                                    return Ok(Some(return_value));
-                                }
+                            if let ControlFlow::Break(exit) = self.execute_iteration(label, body)? {
+                                return Ok(exit);
+                            }
+                        }
+                    }
+                    Value::Int(end) => {
+                        for i in 0..end {
+                            self.destructure(pattern, Value::Int(i))?;
+
+                            if let ControlFlow::Break(exit) = self.execute_iteration(label, body)? {
+                                return Ok(exit);
                             }
                         }
                     }
                     Value::Number(end) => {
                         // Legacy support for simple numeric ranges
                         for i in 0..(end as i32) {
-                            self.environment.define_variable(
-                                identifier.clone(),
-                                Value::Number(i as f64)
-                            );
+                            self.destructure(pattern, Value::Number(i as f64))?;
                             
-                            for stmt in body {
-                                if let Some(return_value) = self.execute_statement(stmt)? {
-                                    return Ok(Some(return_value));
-                                }
+                            if let ControlFlow::Break(exit) = self.execute_iteration(label, body)? {
+                                return Ok(exit);
                             }
                         }
                     }
@@ -298,69 +494,498 @@ impl Interpreter {
                 Ok(None)
             }
 
-            Statement::Return { value } => {
+            Statement::Return { value, .. } => {
                 let return_value = if let Some(expr) = value {
                     self.evaluate_expression(expr)?
                 } else {
                     Value::Null
                 };
-                Ok(Some(return_value))
+                Ok(Some(Exit::Return(return_value)))
+            }
+
+            Statement::Break { label, .. } => Ok(Some(Exit::Break(label.clone()))),
+
+            Statement::Continue { label, .. } => Ok(Some(Exit::Continue(label.clone()))),
+
+            Statement::Expression { expression: Expression::Match { scrutinee, arms, .. }, .. } => {
+                // A match statement may return from the enclosing function
+                let (_, exit) = self.execute_match(scrutinee, arms)?;
+                Ok(exit)
             }
 
-            Statement::Expression { expression } => {
+            Statement::Expression { expression, .. } => {
                 self.evaluate_expression(expression)?;
                 Ok(None)
             }
 
-            Statement::Struct { name, fields } => {
+            Statement::Struct { name, fields, invariants, .. } => {
                 // Register struct type with field names
                 let field_names: Vec<String> = fields.iter().map(|f| f.name.clone()).collect();
-                self.environment.define_struct_type(name.clone(), field_names);
+                if !invariants.is_empty() {
+                    self.struct_invariants.insert(self.qualify(name), invariants.clone());
+                }
+                self.environment.define_struct_type(self.qualify(name), field_names);
                 Ok(None)
             }
 
-            Statement::Enum { name, variants } => {
+            Statement::Enum { name, variants, .. } => {
                 // Register enum type with variant names
                 let variant_names: Vec<String> = variants.iter().map(|v| v.name.clone()).collect();
-                self.environment.define_enum_type(name.clone(), variant_names);
+                for variant in variants {
+                    if let VariantPayload::Struct(fields) = &variant.payload {
+                        let field_names = fields.iter().map(|(field, _)| field.clone()).collect();
+                        self.variant_fields.insert(format!("{}.{}", self.qualify(name), variant.name), field_names);
+                    }
+                }
+                self.environment.define_enum_type(self.qualify(name), variant_names);
                 Ok(None)
             }
+
+            Statement::Impl { trait_name, type_name, methods, .. } => {
+                // Methods are functions named after their type; builtin types have no module
+                let type_name = match type_name.as_str() {
+                    "Int" | "Number" | "String" | "Boolean" => type_name.clone(),
+                    _ => self.qualify(type_name),
+                };
+                let mut defined = Vec::new();
+                for method in methods {
+                    if let Statement::Function { name, parameters, requires, ensures, body, .. } = method {
+                        let function = Function {
+                            name: format!("{}::{}", type_name, name),
+                            parameters: parameters.iter().map(|p| p.name.clone()).collect(),
+                            body: body.clone(),
+                            module: self.current_module,
+                            requires: requires.clone(),
+                            ensures: ensures.clone(),
+                        };
+                        self.environment.define_function(function);
+                        defined.push(name);
+                    }
+                }
+                if let Some(trait_name) = trait_name {
+                    let defaults = self.trait_defaults.get(&self.resolve(trait_name)?).cloned().unwrap_or_default();
+                    for default in defaults.into_iter().filter(|default| !defined.contains(&&default.name)) {
+                        self.environment.define_function(Function {
+                            name: format!("{}::{}", type_name, default.name),
+                            ..default
+                        });
+                    }
+                }
+                Ok(None)
+            }
+
+            Statement::Trait { name, methods, .. } => {
+                let defaults = methods.iter()
+                    .filter_map(|method| method.body.as_ref().map(|body| Function {
+                        name: method.name.clone(),
+                        parameters: method.parameters.iter().map(|p| p.name.clone()).collect(),
+                        body: body.clone(),
+                        module: self.current_module,
+                        requires: Vec::new(),
+                        ensures: Vec::new(),
+                    }))
+                    .collect();
+                self.trait_defaults.insert(self.qualify(name), defaults);
+                Ok(None)
+            }
+
+            Statement::Use { .. } | Statement::Module { .. } => {
+                // Imports were resolved into the module scopes
+                Ok(None)
+            }
+
+            Statement::Error { .. } => {
+                Err(OvieError::runtime_error("Cannot execute a statement that failed to parse"))
+            }
         }
     }
 
+    /// Execute one pass through the body of the loop labeled `label`. Breaks when
+    /// the loop must stop, with the exit that also leaves the loop, if any.
+    fn execute_iteration(&mut self, label: &Option<String>, body: &[Statement]) -> OvieResult<ControlFlow<Option<Exit>>> {
+        for stmt in body {
+            match self.execute_statement(stmt)? {
+                None => {}
+                Some(Exit::Break(target)) if Exit::targets(&target, label) => return Ok(ControlFlow::Break(None)),
+                Some(Exit::Continue(target)) if Exit::targets(&target, label) => return Ok(ControlFlow::Continue(())),
+                Some(exit) => return Ok(ControlFlow::Break(Some(exit))),
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Store `value` into the place named by `target`, combining it with the
+    /// current value first when the assignment has an operator (`+=` and so on)
+    fn assign_place(&mut self, target: &Expression, operator: Option<&BinaryOperator>, value: Value) -> OvieResult<()> {
+        let mut steps = Vec::new();
+        let name = self.place_steps(target, &mut steps)?;
+        self.check_not_captured(&name)?;
+        let current = self.environment.get_variable(&name)
+            .ok_or_else(|| OvieError::runtime_error(format!("Undefined variable: {}", name)))?;
+        let updated = self.update_place(current, &steps, operator, value)?;
+
+        // The struct whose field was assigned must still satisfy its invariants
+        if let Some((PlaceStep::Field(_), owner_steps)) = steps.split_last() {
+            if let Some(owner) = self.value_at(&updated, owner_steps) {
+                let owner = owner.clone();
+                self.check_invariants(&owner)?;
+            }
+        }
+        self.environment.define_variable(name, updated);
+        Ok(())
+    }
+
+    /// Reject an assignment to a variable the running closure captured, as the checker does
+    fn check_not_captured(&self, name: &str) -> OvieResult<()> {
+        if self.captured.contains(name) {
+            return Err(OvieError::runtime_error(format!("Closures cannot assign to captured variable '{}'", name)));
+        }
+        Ok(())
+    }
+
+    /// The part of `value` at the end of `steps`
+    fn value_at<'a>(&self, value: &'a Value, steps: &[PlaceStep]) -> Option<&'a Value> {
+        let Some((step, rest)) = steps.split_first() else {
+            return Some(value);
+        };
+        let inner = match (step, value) {
+            (PlaceStep::Field(field), Value::Struct { fields, .. }) => fields.get(&self.field_key(fields, field)?)?,
+            (PlaceStep::Field(field), Value::Tuple(elements)) => elements.get(field.parse::<usize>().ok()?)?,
+            (PlaceStep::Index(index), Value::Array(elements)) => elements.get(*index)?,
+            _ => return None,
+        };
+        self.value_at(inner, rest)
+    }
+
+    /// Check the invariants of the struct `value` is, if it has any
+    fn check_invariants(&mut self, value: &Value) -> OvieResult<()> {
+        let Value::Struct { name, .. } = value else {
+            return Ok(());
+        };
+        let Some(invariants) = self.struct_invariants.get(name).filter(|_| self.check_contracts).cloned() else {
+            return Ok(());
+        };
+        let mut scope = Environment::with_parent(self.environment.clone());
+        scope.define_variable("self".to_string(), value.clone());
+        self.check_clauses(&invariants, &format!("invariant of '{}'", name), scope)
+    }
+
+    /// Evaluate contract clauses in `scope`, failing with an assertion error naming
+    /// `subject` at the first one that does not hold
+    fn check_clauses(&mut self, clauses: &[ContractClause], subject: &str, scope: Environment) -> OvieResult<()> {
+        let saved_env = std::mem::replace(&mut self.environment, scope);
+        let mut checked = Ok(());
+        for clause in clauses {
+            checked = match self.evaluate_expression(&clause.condition) {
+                Ok(Value::Boolean(true)) => Ok(()),
+                Ok(Value::Boolean(false)) => Err(OvieError::assertion_error(format!("{}: {}", subject, clause.text))),
+                Ok(other) => Err(OvieError::runtime_error(format!(
+                    "Contract condition '{}' is {}, not a Boolean", clause.text, other.to_string()
+                ))),
+                Err(error) => Err(error),
+            };
+            if checked.is_err() {
+                break;
+            }
+        }
+        self.environment = saved_env;
+        checked
+    }
+
+    /// Evaluate the indices of a place expression, collecting the steps from its
+    /// variable to the place. Returns the name of the variable.
+    fn place_steps(&mut self, target: &Expression, steps: &mut Vec<PlaceStep>) -> OvieResult<String> {
+        match target {
+            Expression::Identifier(name, _) => Ok(name.clone()),
+            Expression::FieldAccess { object, field, .. } => {
+                let name = self.place_steps(object, steps)?;
+                steps.push(PlaceStep::Field(field.clone()));
+                Ok(name)
+            }
+            Expression::Index { object, index, .. } => {
+                let name = self.place_steps(object, steps)?;
+                let index = match self.evaluate_expression(index)? {
+                    Value::Int(i) if i >= 0 => i as usize,
+                    Value::Number(n) if n >= 0.0 => n as usize,
+                    other => {
+                        return Err(OvieError::runtime_error(format!("Invalid array index: {}", other.to_string())));
+                    }
+                };
+                steps.push(PlaceStep::Index(index));
+                Ok(name)
+            }
+            _ => Err(OvieError::runtime_error("Invalid assignment target")),
+        }
+    }
+
+    /// Rebuild `current` with the place at the end of `steps` replaced
+    fn update_place(&self, current: Value, steps: &[PlaceStep], operator: Option<&BinaryOperator>, value: Value) -> OvieResult<Value> {
+        let Some((step, rest)) = steps.split_first() else {
+            return match operator {
+                Some(operator) => self.apply_binary_operator(&current, operator, &value),
+                None => Ok(value),
+            };
+        };
+
+        match (step, current) {
+            (PlaceStep::Field(field), Value::Struct { name, mut fields }) => {
+                let key = self.field_key(&fields, field).ok_or_else(|| OvieError::runtime_error(format!(
+                    "Field '{}' not found in struct '{}'", field, name
+                )))?;
+                let field_value = fields.remove(&key).unwrap_or(Value::Null);
+                let field_value = self.update_place(field_value, rest, operator, value)?;
+                fields.insert(key, field_value);
+                Ok(Value::Struct { name, fields })
+            }
+            (PlaceStep::Field(field), Value::Tuple(mut elements)) => {
+                let index = field.parse::<usize>().ok().filter(|index| *index < elements.len()).ok_or_else(|| {
+                    OvieError::runtime_error(format!("Tuple of {} elements has no field '{}'", elements.len(), field))
+                })?;
+                let element = std::mem::replace(&mut elements[index], Value::Null);
+                elements[index] = self.update_place(element, rest, operator, value)?;
+                Ok(Value::Tuple(elements))
+            }
+            (PlaceStep::Index(index), Value::Array(mut elements)) => {
+                if *index >= elements.len() {
+                    return Err(OvieError::runtime_error(format!(
+                        "Array index out of bounds: {} (length: {})",
+                        index, elements.len()
+                    )));
+                }
+                let element = std::mem::replace(&mut elements[*index], Value::Null);
+                elements[*index] = self.update_place(element, rest, operator, value)?;
+                Ok(Value::Array(elements))
+            }
+            (PlaceStep::Field(field), _) => Err(OvieError::runtime_error(format!(
+                "Cannot assign to field '{}' of non-struct value",
+                field
+            ))),
+            (PlaceStep::Index(_), _) => Err(OvieError::runtime_error("Only array elements can be assigned by index")),
+        }
+    }
+
+    /// Run the first arm whose pattern fits the scrutinee. Returns the arm's value
+    /// and any `return`, `break` or `continue` executed by a block arm.
+    fn execute_match(&mut self, scrutinee: &Expression, arms: &[MatchArm]) -> OvieResult<(Value, Option<Exit>)> {
+        let value = self.evaluate_expression(scrutinee)?;
+
+        for arm in arms {
+            if !self.bind_pattern(&arm.pattern, &value) {
+                continue;
+            }
+
+            for stmt in &arm.body {
+                if let Some(exit) = self.execute_statement(stmt)? {
+                    return Ok((Value::Null, Some(exit)));
+                }
+            }
+            let result = match &arm.value {
+                Some(expr) => self.evaluate_expression(expr)?,
+                None => Value::Null,
+            };
+            return Ok((result, None));
+        }
+
+        Err(OvieError::runtime_error(format!("No match arm matches value {}", value.to_string())))
+    }
+
+    /// Whether `value` fits `pattern`, defining any variables the pattern binds
+    fn bind_pattern(&mut self, pattern: &Pattern, value: &Value) -> bool {
+        match pattern {
+            Pattern::Wildcard(_) => true,
+            Pattern::Binding(name, _) => {
+                self.captured.remove(name);
+                self.environment.define_variable(name.clone(), value.clone());
+                true
+            }
+            Pattern::Literal(literal, _) => match (literal, value) {
+                (Literal::String(expected), Value::String(found)) => expected == found,
+                (Literal::Number(expected), Value::Number(found)) => expected == found,
+                (Literal::Integer(expected), Value::Int(found)) => expected == found,
+                (Literal::Integer(expected), Value::Number(found)) => *expected as f64 == *found,
+                (Literal::Boolean(expected), Value::Boolean(found)) => expected == found,
+                _ => false,
+            },
+            Pattern::Variant { variant_name, payload, .. } => match value {
+                Value::Enum { variant, fields, .. } if variant == variant_name => payload.fields().into_iter()
+                    .all(|(name, pattern)| match fields.iter().find(|(field, _)| *field == name) {
+                        Some((_, field_value)) => self.bind_pattern(pattern, field_value),
+                        None => false,
+                    }),
+                _ => false,
+            },
+            Pattern::Tuple(patterns, _) => match value {
+                Value::Tuple(elements) if elements.len() == patterns.len() => patterns.iter()
+                    .zip(elements)
+                    .all(|(pattern, element)| self.bind_pattern(pattern, element)),
+                _ => false,
+            },
+        }
+    }
+
+    /// Bind the names of a `let` or `for` pattern, which must fit `value`
+    fn destructure(&mut self, pattern: &Pattern, value: Value) -> OvieResult<()> {
+        if self.bind_pattern(pattern, &value) {
+            Ok(())
+        } else {
+            Err(OvieError::runtime_error(format!(
+                "Cannot destructure {} {} with a pattern of a different shape",
+                self.value_type_name(&value),
+                value.to_string()
+            )))
+        }
+    }
+
+    /// Call a function value
+    fn call_value(&mut self, callee: Value, arg_values: Vec<Value>) -> OvieResult<Value> {
+        match callee {
+            Value::Function { function, captures } => {
+                if arg_values.len() != function.parameters.len() {
+                    return Err(OvieError::runtime_error(format!(
+                        "Function '{}' expects {} arguments, got {}",
+                        function.name,
+                        function.parameters.len(),
+                        arg_values.len()
+                    )));
+                }
+                self.call_function(&function, &captures, arg_values)
+            }
+            other => Err(OvieError::runtime_error(format!(
+                "Cannot call non-function value {}",
+                other.to_string()
+            ))),
+        }
+    }
+
+    /// Execute a function body with its parameters bound to `arg_values`
+    fn call_function(&mut self, func: &Function, captures: &HashMap<String, Value>, arg_values: Vec<Value>) -> OvieResult<Value> {
+        // Create new environment for function execution
+        let mut func_env = Environment::with_parent(self.environment.clone());
+
+        for (name, value) in captures {
+            func_env.define_variable(name.clone(), value.clone());
+        }
+
+        // Bind parameters to arguments
+        for (param, arg_value) in func.parameters.iter().zip(arg_values) {
+            func_env.define_variable(param.clone(), arg_value);
+        }
+
+        // Contract clauses see the parameters as they were passed
+        let contract_scope = (self.check_contracts && !(func.requires.is_empty() && func.ensures.is_empty()))
+            .then(|| func_env.clone());
+
+        // Save current environment and switch to function environment
+        let saved_env = std::mem::replace(&mut self.environment, func_env);
+        let captured = captures.keys().filter(|name| !func.parameters.contains(name)).cloned().collect();
+        let saved_captured = std::mem::replace(&mut self.captured, captured);
+        let saved_module = std::mem::replace(&mut self.current_module, func.module);
+
+        let mut result = match &contract_scope {
+            Some(scope) => self.check_clauses(&func.requires, &format!("precondition of '{}'", func.name), scope.clone())
+                .map(|_| Value::Null),
+            None => Ok(Value::Null),
+        };
+
+        // Execute function body, unless a precondition failed
+        let body: &[Statement] = if result.is_ok() { &func.body } else { &[] };
+        for (index, stmt) in body.iter().enumerate() {
+            // The value of a final expression is the returned value; a match with block arms has none
+            let executed = match stmt {
+                Statement::Expression { expression, .. } if index + 1 == body.len()
+                    && !matches!(expression, Expression::Match { arms, .. } if arms.iter().any(|arm| arm.value.is_none())) =>
+                {
+                    self.evaluate_expression(expression).map(|value| Some(Exit::Return(value)))
+                }
+                _ => self.execute_statement(stmt),
+            };
+            match executed {
+                Ok(Some(Exit::Return(return_value))) => {
+                    result = Ok(return_value);
+                    break;
+                }
+                Ok(Some(exit)) => {
+                    result = Err(exit.outside_loop());
+                    break;
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    // A `?` unwinds to here as an error carrying no diagnostic of its own
+                    result = match self.early_return.take() {
+                        Some(value) => Ok(value),
+                        None => Err(error),
+                    };
+                    break;
+                }
+            }
+        }
+
+        if let (Some(mut scope), Ok(value)) = (contract_scope, &result) {
+            if !func.ensures.is_empty() {
+                scope.define_variable("result".to_string(), value.clone());
+                if let Err(error) = self.check_clauses(&func.ensures, &format!("postcondition of '{}'", func.name), scope) {
+                    result = Err(error);
+                }
+            }
+        }
+
+        // Restore environment
+        self.environment = saved_env;
+        self.current_module = saved_module;
+        self.captured = saved_captured;
+
+        result
+    }
+
     /// Evaluate an expression
     fn evaluate_expression(&mut self, expression: &Expression) -> OvieResult<Value> {
         match expression {
-            Expression::Literal(literal) => {
+            Expression::Literal(literal, _) => {
                 match literal {
                     Literal::String(s) => Ok(Value::String(s.clone())),
                     Literal::Number(n) => Ok(Value::Number(*n)),
+                    Literal::Integer(n) => Ok(Value::Int(*n)),
                     Literal::Boolean(b) => Ok(Value::Boolean(*b)),
                 }
             }
 
-            Expression::Identifier(name) => {
+            Expression::Identifier(name, _) => {
                 if let Some(value) = self.environment.get_variable(name) {
                     Ok(value)
+                } else if let Some(value) = self.environment.get_variable(&self.resolve(name)?) {
+                    // A constant imported from another module
+                    Ok(value)
+                } else if let Some(function) = self.environment.get_function(&self.resolve(name)?) {
+                    // A function item used as a value
+                    Ok(Value::Function { function: Box::new(function), captures: HashMap::new() })
                 } else {
                     Err(OvieError::runtime_error(format!("Undefined variable: {}", name)))
                 }
             }
 
-            Expression::Binary { left, operator, right } => {
+            Expression::Binary { left, operator, right, .. } => {
                 let left_value = self.evaluate_expression(left)?;
                 let right_value = self.evaluate_expression(right)?;
                 
                 self.apply_binary_operator(&left_value, operator, &right_value)
             }
 
-            Expression::Unary { operator, operand } => {
+            Expression::Unary { operator, operand, .. } => {
                 let operand_value = self.evaluate_expression(operand)?;
                 self.apply_unary_operator(operator, &operand_value)
             }
 
-            Expression::Call { function, arguments } => {
-                if let Some(func) = self.environment.get_function(function) {
+            Expression::Call { function, arguments, .. } => {
+                // A local variable shadows function items
+                if let Some(value) = self.environment.get_variable(function) {
+                    let mut arg_values = Vec::new();
+                    for arg in arguments {
+                        arg_values.push(self.evaluate_expression(arg)?);
+                    }
+                    return self.call_value(value, arg_values);
+                }
+
+                if let Some(func) = self.environment.get_function(&self.resolve(function)?) {
                     if arguments.len() != func.parameters.len() {
                         return Err(OvieError::runtime_error(format!(
                             "Function '{}' expects {} arguments, got {}",
@@ -376,55 +1001,123 @@ impl Interpreter {
                         arg_values.push(self.evaluate_expression(arg)?);
                     }
 
-                    // Create new environment for function execution
-                    let mut func_env = Environment::with_parent(self.environment.clone());
-                    
-                    // Bind parameters to arguments
-                    for (param, arg_value) in func.parameters.iter().zip(arg_values.iter()) {
-                        func_env.define_variable(param.clone(), arg_value.clone());
+                    self.call_function(&func, &HashMap::new(), arg_values)
+                } else if function == "to_int" || function == "to_number" {
+                    let mut arg_values = Vec::new();
+                    for arg in arguments {
+                        arg_values.push(self.evaluate_expression(arg)?);
                     }
+                    self.convert_numeric(function, arg_values)
+                } else if function == "abort" {
+                    let message = match arguments.first() {
+                        Some(argument) => self.evaluate_expression(argument)?.to_string(),
+                        None => String::new(),
+                    };
+                    Err(OvieError::runtime_error(format!("Program aborted: {}", message)))
+                } else {
+                    Err(OvieError::runtime_error(format!("Undefined function: {}", function)))
+                }
+            }
 
-                    // Save current environment and switch to function environment
-                    let saved_env = std::mem::replace(&mut self.environment, func_env);
+            Expression::MethodCall { receiver, method, arguments, .. } => {
+                let mut arg_values = Vec::new();
 
-                    // Execute function body
-                    let mut result = Value::Null;
-                    for stmt in &func.body {
-                        if let Some(return_value) = self.execute_statement(stmt)? {
-                            result = return_value;
-                            break;
+                // `Type.f(...)` calls an associated function without a receiver
+                let associated_type = match receiver.as_ref() {
+                    Expression::Identifier(name, _) if self.environment.get_variable(name).is_none() => {
+                        let type_name = self.resolve(name)?;
+                        if self.environment.get_struct_type(&type_name).is_some()
+                            || self.environment.get_enum_type(&type_name).is_some()
+                        {
+                            Some(type_name)
+                        } else {
+                            None
                         }
                     }
+                    _ => None,
+                };
 
-                    // Restore environment
-                    self.environment = saved_env;
+                let type_name = match associated_type {
+                    Some(type_name) => type_name,
+                    None => {
+                        let receiver_value = self.evaluate_expression(receiver)?;
+                        let type_name = match &receiver_value {
+                            Value::Struct { name, .. } | Value::Enum { name, .. } => name.clone(),
+                            Value::Int(_) => "Int".to_string(),
+                            Value::Number(_) => "Number".to_string(),
+                            Value::String(_) => "String".to_string(),
+                            Value::Boolean(_) => "Boolean".to_string(),
+                            other => {
+                                return Err(OvieError::runtime_error(format!(
+                                    "Cannot call method '{}' on a {}",
+                                    method,
+                                    self.value_type_name(other)
+                                )));
+                            }
+                        };
+                        arg_values.push(receiver_value);
+                        type_name
+                    }
+                };
 
-                    Ok(result)
-                } else {
-                    Err(OvieError::runtime_error(format!("Undefined function: {}", function)))
+                let func = self.environment.get_function(&format!("{}::{}", type_name, method))
+                    .ok_or_else(|| OvieError::runtime_error(format!(
+                        "Type '{}' has no method '{}'", type_name, method
+                    )))?;
+
+                for arg in arguments {
+                    arg_values.push(self.evaluate_expression(arg)?);
+                }
+                if arg_values.len() != func.parameters.len() {
+                    return Err(OvieError::runtime_error(format!(
+                        "Method '{}' expects {} arguments, got {}",
+                        method,
+                        func.parameters.len(),
+                        arg_values.len()
+                    )));
+                }
+
+                self.call_function(&func, &HashMap::new(), arg_values)
+            }
+
+            Expression::Closure { parameters, body, .. } => {
+                let function = Function {
+                    name: "closure".to_string(),
+                    parameters: parameters.iter().map(|p| p.name.clone()).collect(),
+                    body: body.clone(),
+                    module: self.current_module,
+                    requires: Vec::new(),
+                    ensures: Vec::new(),
+                };
+                // Closures capture the variables they see by value, and cannot assign to them
+                Ok(Value::Function {
+                    function: Box::new(function),
+                    captures: self.environment.visible_variables(),
+                })
+            }
+
+            Expression::IndirectCall { callee, arguments, .. } => {
+                let callee_value = self.evaluate_expression(callee)?;
+                let mut arg_values = Vec::new();
+                for arg in arguments {
+                    arg_values.push(self.evaluate_expression(arg)?);
                 }
+                self.call_value(callee_value, arg_values)
             }
 
-            Expression::FieldAccess { object, field } => {
+            Expression::FieldAccess { object, field, .. } => {
                 let object_value = self.evaluate_expression(object)?;
                 
                 match object_value {
-                    Value::Struct(fields) => {
-                        // Try exact match first
-                        if let Some(value) = fields.get(field) {
-                            return Ok(value.clone());
-                        }
-                        
-                        // Convert camelCase to snake_case for lookup
-                        let field_snake = self.camel_to_snake(field);
-                        if let Some(value) = fields.get(&field_snake) {
-                            return Ok(value.clone());
-                        }
-                        
-                        // Convert snake_case to camelCase for lookup
-                        let field_camel = self.snake_to_camel(field);
-                        if let Some(value) = fields.get(&field_camel) {
-                            return Ok(value.clone());
+                    Value::Tuple(elements) => field.parse::<usize>().ok()
+                        .and_then(|index| elements.get(index).cloned())
+                        .ok_or_else(|| OvieError::runtime_error(format!(
+                            "Tuple of {} elements has no field '{}'",
+                            elements.len(), field
+                        ))),
+                    Value::Struct { fields, .. } => {
+                        if let Some(key) = self.field_key(&fields, field) {
+                            return Ok(fields[&key].clone());
                         }
                         
                         // Debug: show available fields
@@ -441,9 +1134,10 @@ impl Interpreter {
                 }
             }
 
-            Expression::StructInstantiation { struct_name, fields } => {
+            Expression::StructInstantiation { struct_name, fields, .. } => {
                 // Verify struct type exists
-                if self.environment.get_struct_type(struct_name).is_none() {
+                let name = self.resolve(struct_name)?;
+                if self.environment.get_struct_type(&name).is_none() {
                     return Err(OvieError::runtime_error(format!(
                         "Undefined struct type: {}",
                         struct_name
@@ -457,49 +1151,85 @@ impl Interpreter {
                     field_values.insert(field_init.name.clone(), value);
                 }
 
-                Ok(Value::Struct(field_values))
+                let value = Value::Struct { name, fields: field_values };
+                self.check_invariants(&value)?;
+                Ok(value)
             }
 
-            Expression::Range { start, end } => {
+            Expression::Range { start, end, .. } => {
                 let start_val = self.evaluate_expression(start)?;
                 let end_val = self.evaluate_expression(end)?;
                 
                 match (start_val, end_val) {
-                    (Value::Number(s), Value::Number(e)) => {
-                        let start_int = s as i32;
-                        let end_int = e as i32;
-                        let range_values: Vec<Value> = (start_int..end_int)
-                            .map(|i| Value::Number(i as f64))
-                            .collect();
-                        Ok(Value::Array(range_values))
+                    (Value::Int(s), Value::Int(e)) => {
+                        Ok(Value::Array((s..e).map(Value::Int).collect()))
+                    }
+                    (s, e) => match (Self::as_number(&s), Self::as_number(&e)) {
+                        (Some(s), Some(e)) => {
+                            let start_int = s as i32;
+                            let end_int = e as i32;
+                            let range_values: Vec<Value> = (start_int..end_int)
+                                .map(|i| Value::Number(i as f64))
+                                .collect();
+                            Ok(Value::Array(range_values))
+                        }
+                        _ => Err(OvieError::runtime_error("Range expressions require numeric values"))
                     }
-                    _ => Err(OvieError::runtime_error("Range expressions require numeric values"))
                 }
             }
 
-            Expression::EnumVariantConstruction { enum_name, variant_name, data } => {
+            Expression::EnumVariantConstruction { enum_name, variant_name, payload, .. } => {
                 // Verify enum type exists
-                if self.environment.get_enum_type(enum_name).is_none() {
+                let name = self.resolve(enum_name)?;
+                if self.environment.get_enum_type(&name).is_none() {
                     return Err(OvieError::runtime_error(format!(
                         "Undefined enum type: {}",
                         enum_name
                     )));
                 }
 
-                // Evaluate data if present
-                let variant_data = if let Some(data_expr) = data {
-                    Some(Box::new(self.evaluate_expression(data_expr)?))
-                } else {
-                    None
-                };
+                // Fields are evaluated in source order and kept in declaration order
+                let mut fields = Vec::new();
+                for (field, value) in payload.fields() {
+                    fields.push((field, self.evaluate_expression(value)?));
+                }
+                if let Some(declared) = self.variant_fields.get(&format!("{}.{}", name, variant_name)) {
+                    fields.sort_by_key(|(field, _)| declared.iter().position(|name| name == field));
+                }
 
                 Ok(Value::Enum {
+                    name,
                     variant: variant_name.clone(),
-                    data: variant_data,
+                    fields,
                 })
             }
 
-            Expression::ArrayLiteral { elements } => {
+            Expression::Try { operand, .. } => {
+                match self.evaluate_expression(operand)? {
+                    Value::Enum { variant, fields, .. } if variant == "Ok" || variant == "Some" => {
+                        Ok(fields.into_iter().next().map(|(_, value)| value).unwrap_or(Value::Null))
+                    }
+                    value @ Value::Enum { .. } => {
+                        self.early_return = Some(value);
+                        Err(OvieError::runtime_error("'?' can only be used inside a function"))
+                    }
+                    other => Err(OvieError::runtime_error(format!(
+                        "'?' expects a Result or Option, found {}",
+                        other.to_string()
+                    ))),
+                }
+            }
+
+            Expression::Match { scrutinee, arms, .. } => {
+                match self.execute_match(scrutinee, arms)? {
+                    (value, None) => Ok(value),
+                    (_, Some(_)) => Err(OvieError::runtime_error(
+                        "'return', 'break' and 'continue' inside a match are only supported when the match is used as a statement"
+                    )),
+                }
+            }
+
+            Expression::ArrayLiteral { elements, .. } => {
                 let mut array_values = Vec::new();
                 for element in elements {
                     array_values.push(self.evaluate_expression(element)?);
@@ -507,9 +1237,20 @@ impl Interpreter {
                 Ok(Value::Array(array_values))
             }
 
-            Expression::Index { object, index } => {
+            Expression::Tuple { elements, .. } => {
+                let mut values = Vec::new();
+                for element in elements {
+                    values.push(self.evaluate_expression(element)?);
+                }
+                Ok(Value::Tuple(values))
+            }
+
+            Expression::Index { object, index, .. } => {
                 let object_value = self.evaluate_expression(object)?;
-                let index_value = self.evaluate_expression(index)?;
+                let index_value = match self.evaluate_expression(index)? {
+                    Value::Int(idx) if idx >= 0 => Value::Number(idx as f64),
+                    other => other,
+                };
                 
                 match (object_value, index_value) {
                     (Value::Array(arr), Value::Number(idx)) => {
@@ -555,6 +1296,32 @@ impl Interpreter {
         right: &Value,
     ) -> OvieResult<Value> {
         match (left, operator, right) {
+            // Int arithmetic wraps on overflow
+            (Value::Int(a), BinaryOperator::Add, Value::Int(b)) => Ok(Value::Int(a.wrapping_add(*b))),
+            (Value::Int(a), BinaryOperator::Subtract, Value::Int(b)) => Ok(Value::Int(a.wrapping_sub(*b))),
+            (Value::Int(a), BinaryOperator::Multiply, Value::Int(b)) => Ok(Value::Int(a.wrapping_mul(*b))),
+            (Value::Int(a), BinaryOperator::Divide, Value::Int(b)) => {
+                if *b == 0 {
+                    Err(OvieError::runtime_error("Division by zero"))
+                } else {
+                    a.checked_div(*b)
+                        .map(Value::Int)
+                        .ok_or_else(|| OvieError::runtime_error("Integer overflow in division"))
+                }
+            }
+            (Value::Int(a), BinaryOperator::Modulo, Value::Int(b)) => {
+                if *b == 0 {
+                    Err(OvieError::runtime_error("Modulo by zero"))
+                } else {
+                    Ok(Value::Int(a.wrapping_rem(*b)))
+                }
+            }
+            (Value::Int(a), BinaryOperator::Equal, Value::Int(b)) => Ok(Value::Boolean(a == b)),
+            (Value::Int(a), BinaryOperator::Less, Value::Int(b)) => Ok(Value::Boolean(a < b)),
+            (Value::Int(a), BinaryOperator::LessEqual, Value::Int(b)) => Ok(Value::Boolean(a <= b)),
+            (Value::Int(a), BinaryOperator::Greater, Value::Int(b)) => Ok(Value::Boolean(a > b)),
+            (Value::Int(a), BinaryOperator::GreaterEqual, Value::Int(b)) => Ok(Value::Boolean(a >= b)),
+
             // Arithmetic operations
             (Value::Number(a), BinaryOperator::Add, Value::Number(b)) => {
                 Ok(Value::Number(a + b))
@@ -658,6 +1425,7 @@ impl Interpreter {
     fn apply_unary_operator(&self, operator: &UnaryOperator, operand: &Value) -> OvieResult<Value> {
         match (operator, operand) {
             (UnaryOperator::Negate, Value::Number(n)) => Ok(Value::Number(-n)),
+            (UnaryOperator::Negate, Value::Int(n)) => Ok(Value::Int(n.wrapping_neg())),
             (UnaryOperator::Not, operand) => Ok(Value::Boolean(!operand.is_truthy())),
             _ => Err(OvieError::runtime_error(format!(
                 "Invalid unary operation: {} {}",
@@ -672,14 +1440,48 @@ impl Interpreter {
         match value {
             Value::String(_) => "string",
             Value::Number(_) => "number",
+            Value::Int(_) => "int",
             Value::Boolean(_) => "boolean",
             Value::Array(_) => "array",
-            Value::Struct(_) => "struct",
+            Value::Tuple(_) => "tuple",
+            Value::Struct { .. } => "struct",
             Value::Enum { .. } => "enum",
+            Value::Function { .. } => "function",
             Value::Null => "null",
         }
     }
 
+    /// Numeric value of an Int or Number
+    fn as_number(value: &Value) -> Option<f64> {
+        match value {
+            Value::Number(n) => Some(*n),
+            Value::Int(n) => Some(*n as f64),
+            _ => None,
+        }
+    }
+
+    /// Evaluate the `to_int` and `to_number` builtins
+    fn convert_numeric(&self, function: &str, arguments: Vec<Value>) -> OvieResult<Value> {
+        let converted = match (function, arguments.as_slice()) {
+            ("to_int", [Value::Number(n)]) => Some(Value::Int(number_to_int(*n)?)),
+            ("to_int", [Value::Int(n)]) => Some(Value::Int(*n)),
+            ("to_number", [value]) => Self::as_number(value).map(Value::Number),
+            _ => None,
+        };
+        converted.ok_or_else(|| OvieError::runtime_error(format!(
+            "'{}' expects a single numeric argument",
+            function
+        )))
+    }
+
+    /// Key under which a struct stores `field`: an exact match first, then the
+    /// snake_case or camelCase spelling of the name
+    fn field_key(&self, fields: &HashMap<String, Value>, field: &str) -> Option<String> {
+        [field.to_string(), self.camel_to_snake(field), self.snake_to_camel(field)]
+            .into_iter()
+            .find(|key| fields.contains_key(key))
+    }
+
     /// Convert camelCase to snake_case
     fn camel_to_snake(&self, input: &str) -> String {
         let mut result = String::new();
@@ -721,6 +1523,15 @@ impl Default for Interpreter {
     }
 }
 
+/// Convert a Number to an Int, truncating toward zero.
+/// NaN and values outside the range of Int are a runtime error.
+fn number_to_int(n: f64) -> OvieResult<i64> {
+    if n.is_nan() || n < i64::MIN as f64 || n >= i64::MAX as f64 {
+        return Err(OvieError::runtime_error(format!("Cannot convert {} to Int", n)));
+    }
+    Ok(n.trunc() as i64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -748,6 +1559,260 @@ mod tests {
         interpret_source("result = 10 + 5; seeAm result;").unwrap();
     }
 
+    #[test]
+    fn test_int_arithmetic() {
+        let interpreter = Interpreter::new();
+        let int = |a: i64, operator: BinaryOperator, b: i64| {
+            interpreter.apply_binary_operator(&Value::Int(a), &operator, &Value::Int(b))
+        };
+        assert_eq!(int(7, BinaryOperator::Divide, 2).unwrap(), Value::Int(3));
+        assert_eq!(int(-7, BinaryOperator::Divide, 2).unwrap(), Value::Int(-3));
+        assert_eq!(int(-7, BinaryOperator::Modulo, 2).unwrap(), Value::Int(-1));
+        assert_eq!(int(i64::MAX, BinaryOperator::Add, 1).unwrap(), Value::Int(i64::MIN));
+        assert_eq!(int(i64::MIN, BinaryOperator::Modulo, -1).unwrap(), Value::Int(0));
+        assert!(int(1, BinaryOperator::Divide, 0).is_err());
+        assert!(int(1, BinaryOperator::Modulo, 0).is_err());
+        assert!(int(i64::MIN, BinaryOperator::Divide, -1).is_err());
+
+        // Int and Number never mix without an explicit conversion
+        assert!(interpreter.apply_binary_operator(&Value::Int(1), &BinaryOperator::Add, &Value::Number(0.5)).is_err());
+        assert!(interpreter.apply_binary_operator(&Value::Number(0.5), &BinaryOperator::Less, &Value::Int(1)).is_err());
+
+        assert_eq!(number_to_int(-3.9).unwrap(), -3);
+        assert!(number_to_int(f64::NAN).is_err());
+        assert!(number_to_int(1e19).is_err());
+    }
+
+    #[test]
+    fn test_break_and_continue() {
+        let source = r#"
+            fn first_multiple(n) {
+                found = 0;
+                outer: for i in 1..10 {
+                    for j in 1..10 {
+                        if j > i { continue outer; }
+                        if i * j == n { found = i; break outer; }
+                    }
+                }
+                return found;
+            }
+            result = first_multiple(12);
+        "#;
+        let ast = Parser::new(Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.interpret(&ast).unwrap();
+        assert_eq!(interpreter.environment.get_variable("result"), Some(Value::Int(4)));
+
+        assert!(interpret_source("break;").is_err());
+        assert!(interpret_source("fn f() { continue; }\nf();").is_err());
+    }
+
+    #[test]
+    fn test_place_assignment() {
+        let source = r#"
+            struct Point { x: Int, y: Int }
+            mut points = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+            let original = points
+            points[1].y += 10
+            points[0] = Point { x: 0, y: 0 }
+            mut total = 0
+            for p in points { total += p.x + p.y }
+            unchanged = original[1].y
+        "#;
+        let ast = Parser::new(Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.interpret(&ast).unwrap();
+        assert_eq!(interpreter.environment.get_variable("total"), Some(Value::Int(17)));
+        assert_eq!(interpreter.environment.get_variable("unchanged"), Some(Value::Int(4)));
+
+        assert!(interpret_source("mut items = [1]\nitems[1] = 2").is_err());
+        assert!(interpret_source("mut n = 1\nn.x = 2").is_err());
+    }
+
+    #[test]
+    fn test_tuples_and_destructuring() {
+        let source = r#"
+            fn min_max(items: [Int]) -> (Int, Int) {
+                mut low = items[0]
+                mut high = items[0]
+                for item in items {
+                    if item < low { low = item }
+                    if item > high { high = item }
+                }
+                return (low, high)
+            }
+            let (low, high) = min_max([3, 9, 1])
+            mut pair = (1, ("a", 2.5))
+            pair.1.1 += 1.0
+            mut sum = 0
+            for (key, _) in [(1, "one"), (2, "two")] { sum += key }
+            nested = pair.1
+        "#;
+        let ast = Parser::new(Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.interpret(&ast).unwrap();
+        assert_eq!(interpreter.environment.get_variable("low"), Some(Value::Int(1)));
+        assert_eq!(interpreter.environment.get_variable("high"), Some(Value::Int(9)));
+        assert_eq!(interpreter.environment.get_variable("sum"), Some(Value::Int(3)));
+        assert_eq!(
+            interpreter.environment.get_variable("nested"),
+            Some(Value::Tuple(vec![Value::String("a".to_string()), Value::Number(3.5)]))
+        );
+
+        assert!(interpret_source("let (a, b) = (1, 2, 3)").is_err());
+        assert!(interpret_source("let t = (1, 2)\nseeAm t.2").is_err());
+    }
+
+    #[test]
+    fn test_error_propagation() {
+        let source = r#"
+            enum Result<T, E> { Ok(T), Err(E) }
+            fn check(n: Int) -> Result<Int, String> {
+                if n > 2 { return Result.Err("too big") }
+                return Result.Ok(n)
+            }
+            fn sum(limit: Int) -> Result<Int, String> {
+                mut total = 0
+                for i in 0..limit { total += check(i)? }
+                return Result.Ok(total)
+            }
+            small = sum(3)
+            large = sum(5)
+        "#;
+        let ast = Parser::new(Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.interpret(&ast).unwrap();
+        let result = |variant: &str, data: Value| Some(Value::Enum {
+            name: "Result".to_string(),
+            variant: variant.to_string(),
+            fields: vec![("0".to_string(), data)],
+        });
+        assert_eq!(interpreter.environment.get_variable("small"), result("Ok", Value::Int(3)));
+        assert_eq!(interpreter.environment.get_variable("large"), result("Err", Value::String("too big".to_string())));
+
+        assert!(interpret_source("seeAm 1?").is_err());
+    }
+
+    #[test]
+    fn test_multi_field_variants() {
+        let source = r#"
+            enum Shape { Rect { w: Int, h: Int }, Move(Int, Int) }
+            rect = Shape.Rect { h: 3, w: 2 }
+            area = match rect { Shape.Rect { w, h } => w * h, _ => 0 }
+            step = match Shape.Move(4, 5) { Shape.Move(_, y) => y, _ => 0 }
+        "#;
+        let ast = Parser::new(Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.interpret(&ast).unwrap();
+        // Named fields are kept in declaration order
+        let rect = interpreter.environment.get_variable("rect").unwrap();
+        assert_eq!(rect.to_string(), "Rect { w: 2, h: 3 }");
+        assert_eq!(interpreter.environment.get_variable("area"), Some(Value::Int(6)));
+        assert_eq!(interpreter.environment.get_variable("step"), Some(Value::Int(5)));
+    }
+
+    #[test]
+    fn test_trait_methods() {
+        let source = r#"
+            trait Show {
+                fn show(self) -> String;
+                fn describe(self) -> String { return "<" + self.show() + ">" }
+            }
+            struct Point { x: Int }
+            impl Show for Point { fn show(self) -> String { return "point" } }
+            impl Show for Int {
+                fn show(self) -> String { return "int" }
+                fn describe(self) -> String { return "number" }
+            }
+            fn all<T: Show>(item: T) -> String { return item.describe() }
+            point = all(Point { x: 1 })
+            int = all(7)
+        "#;
+        let ast = Parser::new(Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.interpret(&ast).unwrap();
+        // Default methods run for types that do not define them
+        assert_eq!(interpreter.environment.get_variable("point"), Some(Value::String("<point>".to_string())));
+        assert_eq!(interpreter.environment.get_variable("int"), Some(Value::String("number".to_string())));
+    }
+
+    #[test]
+    fn test_contracts() {
+        let contracts = r#"
+            struct Interval { lo: Int, hi: Int, invariant self.lo <= self.hi }
+            fn half(n: Int) -> Int requires n >= 0 ensures result * 2 <= n { return n / 2 }
+        "#;
+        let run = |body: &str| interpret_source(&format!("{}{}", contracts, body));
+        run("mut r = Interval { lo: 1, hi: half(4) }\nr.lo = 2").unwrap();
+
+        let failure = |body: &str| match run(body) {
+            Err(OvieError::AssertionError { message }) => message,
+            other => panic!("Expected an assertion error, found {:?}", other),
+        };
+        assert_eq!(failure("seeAm half(0 - 2)"), "precondition of 'half': n >= 0");
+        assert_eq!(failure("r = Interval { lo: 2, hi: 1 }"), "invariant of 'Interval': self.lo <= self.hi");
+        assert_eq!(failure("mut r = Interval { lo: 1, hi: 2 }\nr.hi = 0"), "invariant of 'Interval': self.lo <= self.hi");
+        assert_eq!(
+            failure("fn bad(n: Int) -> Int ensures result > n { return n }\nseeAm bad(1)"),
+            "postcondition of 'bad': result > n"
+        );
+
+        // Release builds leave the checks out
+        let ast = Parser::new(Lexer::new(&format!("{}seeAm half(0 - 2)", contracts)).tokenize().unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_check_contracts(false);
+        interpreter.interpret(&ast).unwrap();
+    }
+
+    #[test]
+    fn test_constants_and_later_items() {
+        let source = r#"
+            seeAm area(2.0)
+            r = RATIO
+            const RATIO: Number = SCALE / 4.0
+            const SCALE: Number = to_number(twice(2))
+            const INFINITY: Number = 1.0 / 0.0
+            const fn twice(n: Int) -> Int { return n * 2 }
+            fn area(side: Number) -> Number { return side * side * RATIO }
+            big = INFINITY > 1000000.0
+        "#;
+        let ast = Parser::new(Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.interpret(&ast).unwrap();
+        // Constants and functions are defined before the statements that use them run
+        assert_eq!(interpreter.environment.get_variable("r"), Some(Value::Number(1.0)));
+        assert_eq!(interpreter.environment.get_variable("big"), Some(Value::Boolean(true)));
+    }
+
+    #[test]
+    fn test_closures_cannot_assign_to_captures() {
+        // The closure only has a copy of `count`, so the assignment is rejected, not lost
+        for source in [
+            "mut count = 0\nlet bump = fn() { count = count + 1 }\nbump()",
+            "mut count = 0\nlet bump = fn() { count += 1 }\nbump()",
+            "mut p = (1, 2)\nlet bump = fn() { p.0 = 5 }\nbump()",
+        ] {
+            let error = interpret_source(source).unwrap_err();
+            assert!(error.to_string().contains("Closures cannot assign to captured variable"), "{:?}: {}", source, error);
+        }
+
+        // Parameters and variables declared in the closure are its own
+        let source = r#"
+            count = 10
+            let add = fn(mut count: Int) -> Int { count = count + 1; return count }
+            let twice = fn(n: Int) -> Int { let mut total = count; total += n; mut extra = n; extra = extra * 0; return total + n + extra }
+            a = add(1)
+            b = twice(5)
+            unchanged = count
+        "#;
+        let ast = Parser::new(Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.interpret(&ast).unwrap();
+        assert_eq!(interpreter.environment.get_variable("a"), Some(Value::Int(2)));
+        assert_eq!(interpreter.environment.get_variable("b"), Some(Value::Int(20)));
+        assert_eq!(interpreter.environment.get_variable("unchanged"), Some(Value::Int(10)));
+    }
+
     #[test]
     fn test_function_call() {
         interpret_source(r#"
@@ -792,7 +1857,7 @@ mod tests {
     }
 }
 /// IR Interpreter for executing IR programs
-use crate::ir::{Program, Function as IrFunction, BasicBlock, Instruction, Terminator, Opcode, Value as IrValue, Constant};
+use crate::ir::{Program, Instruction, Terminator, Opcode, Value as IrValue, Constant, IrType};
 
 /// IR Interpreter state
 pub struct IrInterpreter {
@@ -809,7 +1874,6 @@ pub struct IrInterpreter {
 /// Call frame for function calls
 #[derive(Debug, Clone)]
 struct CallFrame {
-    function_id: u32,
     locals: HashMap<u32, Value>, // ValueId -> Value
     return_address: Option<(u32, u32, usize)>, // (function_id, block_id, instruction_index)
 }
@@ -837,9 +1901,17 @@ impl IrInterpreter {
             OvieError::RuntimeError { message: "Entry function not found".to_string() }
         })?;
 
+        // Initialize globals
+        for (name, global) in &program.globals {
+            let value = match &global.initializer {
+                Some(constant) => self.evaluate_ir_value(&IrValue::Constant(constant.clone()))?,
+                None => Value::Null,
+            };
+            self.globals.insert(name.clone(), value);
+        }
+
         // Initialize call frame for main function
         let call_frame = CallFrame {
-            function_id: entry_function_id,
             locals: HashMap::new(),
             return_address: None,
         };
@@ -878,8 +1950,13 @@ impl IrInterpreter {
         // Execute instruction if within bounds
         if self.instruction_pointer < block.instructions.len() {
             let instruction = &block.instructions[self.instruction_pointer];
-            self.execute_instruction(instruction)?;
-            self.instruction_pointer += 1;
+            if let Opcode::Call | Opcode::CallIndirect = instruction.opcode {
+                // Execution continues in the callee; the return resumes after this instruction
+                self.enter_function(instruction, program)?;
+            } else {
+                self.execute_instruction(instruction)?;
+                self.instruction_pointer += 1;
+            }
         } else {
             // Execute terminator
             self.execute_terminator(&block.terminator, program)?;
@@ -888,8 +1965,56 @@ impl IrInterpreter {
         Ok(())
     }
 
+    /// Push a call frame for a call instruction and jump to the callee's entry block
+    fn enter_function(&mut self, instruction: &Instruction, program: &Program) -> OvieResult<()> {
+        let callee_name = match (&instruction.opcode, instruction.operands.first()) {
+            (Opcode::Call, Some(IrValue::Function(name))) => name.clone(),
+            (Opcode::CallIndirect, Some(function)) => match self.evaluate_ir_value(function)? {
+                Value::Function { function, .. } => function.name,
+                other => {
+                    return Err(OvieError::runtime_error(format!(
+                        "Cannot call non-function value {}",
+                        other.to_string()
+                    )));
+                }
+            },
+            _ => {
+                return Err(OvieError::RuntimeError {
+                    message: "Call instruction without a function operand".to_string()
+                });
+            }
+        };
+
+        let callee = program.functions.values()
+            .find(|function| function.name == *callee_name)
+            .ok_or_else(|| OvieError::RuntimeError {
+                message: format!("Function '{}' not found", callee_name)
+            })?;
+
+        let mut locals = HashMap::new();
+        for (param, argument) in callee.parameters.iter().zip(&instruction.operands[1..]) {
+            locals.insert(param.value_id, self.evaluate_ir_value(argument)?);
+        }
+
+        let caller_function = self.current_function.unwrap_or_default();
+        let caller_block = self.current_block.unwrap_or_default();
+        self.call_stack.push(CallFrame {
+            locals,
+            return_address: Some((caller_function, caller_block, self.instruction_pointer)),
+        });
+
+        self.current_function = Some(callee.id);
+        self.current_block = Some(callee.entry_block);
+        self.instruction_pointer = 0;
+        Ok(())
+    }
+
     /// Execute an instruction
     fn execute_instruction(&mut self, instruction: &Instruction) -> OvieResult<()> {
+        let operand = |index: usize| instruction.operands.get(index).ok_or_else(|| OvieError::RuntimeError {
+            message: format!("Instruction {} is missing operand {}", instruction.id, index)
+        });
+
         let result = match instruction.opcode {
             Opcode::Print => {
                 if let Some(operand) = instruction.operands.first() {
@@ -898,27 +2023,180 @@ impl IrInterpreter {
                 }
                 Value::Null
             }
+            Opcode::Assert => {
+                if let Value::Boolean(false) = self.evaluate_ir_value(operand(0)?)? {
+                    let message = self.evaluate_ir_value(operand(1)?)?;
+                    return Err(OvieError::assertion_error(message.to_string()));
+                }
+                Value::Null
+            }
+            Opcode::Abort => {
+                let message = self.evaluate_ir_value(operand(0)?)?;
+                return Err(OvieError::runtime_error(format!("Program aborted: {}", message.to_string())));
+            }
             Opcode::Add => {
-                let left = self.evaluate_ir_value(&instruction.operands[0])?;
-                let right = self.evaluate_ir_value(&instruction.operands[1])?;
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
                 self.add_values(left, right)?
             }
             Opcode::Sub => {
-                let left = self.evaluate_ir_value(&instruction.operands[0])?;
-                let right = self.evaluate_ir_value(&instruction.operands[1])?;
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
                 self.subtract_values(left, right)?
             }
             Opcode::Mul => {
-                let left = self.evaluate_ir_value(&instruction.operands[0])?;
-                let right = self.evaluate_ir_value(&instruction.operands[1])?;
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
                 self.multiply_values(left, right)?
             }
             Opcode::Div => {
-                let left = self.evaluate_ir_value(&instruction.operands[0])?;
-                let right = self.evaluate_ir_value(&instruction.operands[1])?;
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
                 self.divide_values(left, right)?
             }
-            _ => Value::Null, // Placeholder for other opcodes
+            Opcode::Mod => {
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
+                match (left, right) {
+                    (Value::Number(_), Value::Number(0.0)) => {
+                        return Err(OvieError::runtime_error("Modulo by zero"));
+                    }
+                    (Value::Number(a), Value::Number(b)) => Value::Number(a % b),
+                    (Value::Int(_), Value::Int(0)) => {
+                        return Err(OvieError::runtime_error("Modulo by zero"));
+                    }
+                    (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_rem(b)),
+                    _ => return Err(OvieError::runtime_error("Cannot take the remainder of these types")),
+                }
+            }
+            Opcode::Neg => match self.evaluate_ir_value(operand(0)?)? {
+                Value::Number(n) => Value::Number(-n),
+                Value::Int(n) => Value::Int(n.wrapping_neg()),
+                _ => return Err(OvieError::runtime_error("Cannot negate this type")),
+            },
+            Opcode::Eq | Opcode::Ne => {
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
+                let equal = left == right;
+                Value::Boolean(if let Opcode::Eq = instruction.opcode { equal } else { !equal })
+            }
+            Opcode::Lt | Opcode::Le | Opcode::Gt | Opcode::Ge => {
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
+                match (left, right) {
+                    (Value::Number(a), Value::Number(b)) => Value::Boolean(match instruction.opcode {
+                        Opcode::Lt => a < b,
+                        Opcode::Le => a <= b,
+                        Opcode::Gt => a > b,
+                        _ => a >= b,
+                    }),
+                    (Value::Int(a), Value::Int(b)) => Value::Boolean(match instruction.opcode {
+                        Opcode::Lt => a < b,
+                        Opcode::Le => a <= b,
+                        Opcode::Gt => a > b,
+                        _ => a >= b,
+                    }),
+                    _ => return Err(OvieError::runtime_error("Cannot compare these types")),
+                }
+            }
+            Opcode::And => {
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
+                Value::Boolean(left.is_truthy() && right.is_truthy())
+            }
+            Opcode::Or => {
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
+                Value::Boolean(left.is_truthy() || right.is_truthy())
+            }
+            Opcode::Not => Value::Boolean(!self.evaluate_ir_value(operand(0)?)?.is_truthy()),
+            // A slot holds its current contents under the alloca's own id
+            Opcode::Alloca => Value::Null,
+            Opcode::Load => self.evaluate_ir_value(operand(0)?)?,
+            Opcode::Store => {
+                let value = self.evaluate_ir_value(operand(1)?)?;
+                match (operand(0)?, self.call_stack.last_mut()) {
+                    (IrValue::Instruction(slot), Some(call_frame)) => {
+                        call_frame.locals.insert(*slot, value);
+                    }
+                    (IrValue::Global(name), _) => {
+                        self.globals.insert(name.clone(), value);
+                    }
+                    _ => return Err(OvieError::runtime_error("Invalid store target")),
+                }
+                return Ok(());
+            }
+            Opcode::Call | Opcode::CallIndirect => {
+                return Err(OvieError::runtime_error("Calls must be entered through the call stack"));
+            }
+            Opcode::Cast => {
+                let value = self.evaluate_ir_value(operand(0)?)?;
+                match (&instruction.result_type, value) {
+                    (IrType::String, value) => Value::String(value.to_string()),
+                    (IrType::Number, Value::Int(n)) => Value::Number(n as f64),
+                    (IrType::Int, Value::Number(n)) => Value::Int(number_to_int(n)?),
+                    (_, value) => value,
+                }
+            }
+            Opcode::StringConcat => {
+                let left = self.evaluate_ir_value(operand(0)?)?;
+                let right = self.evaluate_ir_value(operand(1)?)?;
+                Value::String(format!("{}{}", left.to_string(), right.to_string()))
+            }
+            Opcode::MakeStruct | Opcode::MakeArray => {
+                let mut elements = Vec::new();
+                for element in &instruction.operands {
+                    elements.push(self.evaluate_ir_value(element)?);
+                }
+                Value::Array(elements)
+            }
+            Opcode::MakeEnum => {
+                let discriminant = self.evaluate_ir_value(operand(0)?)?;
+                let mut fields = Vec::new();
+                for (index, field) in instruction.operands.iter().skip(1).enumerate() {
+                    fields.push((index.to_string(), self.evaluate_ir_value(field)?));
+                }
+                let name = match &instruction.result_type {
+                    IrType::Enum(name) => name.clone(),
+                    _ => String::new(),
+                };
+                Value::Enum { name, variant: discriminant.to_string(), fields }
+            }
+            Opcode::GetField | Opcode::GetElement => {
+                let aggregate = self.evaluate_ir_value(operand(0)?)?;
+                let index = self.evaluate_index(operand(1)?)?;
+                match aggregate {
+                    Value::Array(elements) => elements.get(index).cloned().ok_or_else(|| {
+                        OvieError::runtime_error(format!("Index {} out of bounds for length {}", index, elements.len()))
+                    })?,
+                    Value::Enum { mut fields, .. } if index < fields.len() => fields.swap_remove(index).1,
+                    _ => return Err(OvieError::runtime_error("Cannot index this type")),
+                }
+            }
+            Opcode::SetField | Opcode::SetElement => {
+                let aggregate = self.evaluate_ir_value(operand(0)?)?;
+                let index = self.evaluate_index(operand(1)?)?;
+                let value = self.evaluate_ir_value(operand(2)?)?;
+                match aggregate {
+                    Value::Array(mut elements) if index < elements.len() => {
+                        elements[index] = value;
+                        Value::Array(elements)
+                    }
+                    Value::Array(elements) => {
+                        return Err(OvieError::runtime_error(format!("Index {} out of bounds for length {}", index, elements.len())));
+                    }
+                    _ => return Err(OvieError::runtime_error("Cannot index this type")),
+                }
+            }
+            Opcode::Length => match self.evaluate_ir_value(operand(0)?)? {
+                Value::Array(elements) => Value::Int(elements.len() as i64),
+                Value::String(s) => Value::Int(s.chars().count() as i64),
+                _ => return Err(OvieError::runtime_error("Cannot take the length of this type")),
+            },
+            Opcode::Discriminant => match self.evaluate_ir_value(operand(0)?)? {
+                Value::Enum { variant, .. } => Value::Number(variant.parse().unwrap_or(0.0)),
+                _ => return Err(OvieError::runtime_error("Cannot take the discriminant of this type")),
+            },
         };
 
         // Store result in current call frame
@@ -929,24 +2207,47 @@ impl IrInterpreter {
         Ok(())
     }
 
+    /// Evaluate an IR value used as an index
+    fn evaluate_index(&self, ir_value: &IrValue) -> OvieResult<usize> {
+        match self.evaluate_ir_value(ir_value)? {
+            Value::Number(n) if n >= 0.0 && n.fract() == 0.0 => Ok(n as usize),
+            Value::Int(n) if n >= 0 => Ok(n as usize),
+            other => Err(OvieError::runtime_error(format!("Invalid index {}", other.to_string()))),
+        }
+    }
+
     /// Execute a terminator
-    fn execute_terminator(&mut self, terminator: &Terminator, _program: &Program) -> OvieResult<()> {
+    fn execute_terminator(&mut self, terminator: &Terminator, program: &Program) -> OvieResult<()> {
         match terminator {
-            Terminator::Return { value: _ } => {
+            Terminator::Return { value } => {
+                let return_value = match value {
+                    Some(value) => self.evaluate_ir_value(value)?,
+                    None => Value::Null,
+                };
+
                 // Pop call frame
-                self.call_stack.pop();
+                let finished_frame = self.call_stack.pop();
                 
-                if let Some(call_frame) = self.call_stack.last() {
-                    // Restore execution context
-                    self.current_function = Some(call_frame.function_id);
-                    if let Some((_, block_id, ip)) = call_frame.return_address {
+                match finished_frame.and_then(|frame| frame.return_address) {
+                    Some((function_id, block_id, ip)) => {
+                        // Restore execution context and hand the result to the call instruction
+                        let call_id = program.functions.get(&function_id)
+                            .and_then(|function| function.basic_blocks.get(&block_id))
+                            .and_then(|block| block.instructions.get(ip))
+                            .map(|instruction| instruction.id);
+                        if let (Some(call_id), Some(call_frame)) = (call_id, self.call_stack.last_mut()) {
+                            call_frame.locals.insert(call_id, return_value);
+                        }
+
+                        self.current_function = Some(function_id);
                         self.current_block = Some(block_id);
-                        self.instruction_pointer = ip;
+                        self.instruction_pointer = ip + 1;
+                    }
+                    None => {
+                        // Program finished
+                        self.current_function = None;
+                        self.current_block = None;
                     }
-                } else {
-                    // Program finished
-                    self.current_function = None;
-                    self.current_block = None;
                 }
             }
             Terminator::Branch { target } => {
@@ -980,11 +2281,12 @@ impl IrInterpreter {
                 Ok(match constant {
                     Constant::String(s) => Value::String(s.clone()),
                     Constant::Number(n) => Value::Number(*n),
+                    Constant::Integer(n) => Value::Int(*n),
                     Constant::Boolean(b) => Value::Boolean(*b),
                     Constant::Void => Value::Null,
                 })
             }
-            IrValue::Instruction(value_id) => {
+            IrValue::Instruction(value_id) | IrValue::Parameter(value_id) => {
                 if let Some(call_frame) = self.call_stack.last() {
                     call_frame.locals.get(value_id).cloned().ok_or_else(|| {
                         OvieError::RuntimeError { 
@@ -1004,10 +2306,18 @@ impl IrInterpreter {
                     }
                 })
             }
-            IrValue::Parameter(_) => {
-                // For now, return null for parameters
-                Ok(Value::Null)
-            }
+            // A reference to an IR function, called by name
+            IrValue::Function(name) => Ok(Value::Function {
+                function: Box::new(Function {
+                    name: name.clone(),
+                    parameters: Vec::new(),
+                    body: Vec::new(),
+                    module: 0,
+                    requires: Vec::new(),
+                    ensures: Vec::new(),
+                }),
+                captures: HashMap::new(),
+            }),
         }
     }
 
@@ -1015,6 +2325,7 @@ impl IrInterpreter {
     fn add_values(&self, left: Value, right: Value) -> OvieResult<Value> {
         match (left, right) {
             (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_add(b))),
             (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{}{}", a, b))),
             _ => Err(OvieError::RuntimeError { 
                 message: "Cannot add these types".to_string() 
@@ -1026,6 +2337,7 @@ impl IrInterpreter {
     fn subtract_values(&self, left: Value, right: Value) -> OvieResult<Value> {
         match (left, right) {
             (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_sub(b))),
             _ => Err(OvieError::RuntimeError { 
                 message: "Cannot subtract these types".to_string() 
             }),
@@ -1036,6 +2348,7 @@ impl IrInterpreter {
     fn multiply_values(&self, left: Value, right: Value) -> OvieResult<Value> {
         match (left, right) {
             (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_mul(b))),
             _ => Err(OvieError::RuntimeError { 
                 message: "Cannot multiply these types".to_string() 
             }),
@@ -1054,6 +2367,12 @@ impl IrInterpreter {
                     Ok(Value::Number(a / b))
                 }
             }
+            (Value::Int(_), Value::Int(0)) => Err(OvieError::RuntimeError {
+                message: "Division by zero".to_string()
+            }),
+            (Value::Int(a), Value::Int(b)) => a.checked_div(b).map(Value::Int).ok_or_else(|| OvieError::RuntimeError {
+                message: "Integer overflow in division".to_string()
+            }),
             _ => Err(OvieError::RuntimeError { 
                 message: "Cannot divide these types".to_string() 
             }),
@@ -73,7 +73,7 @@ pub struct Instruction {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Opcode {
     // Arithmetic
-    Add, Sub, Mul, Div, Mod,
+    Add, Sub, Mul, Div, Mod, Neg,
     
     // Comparison
     Eq, Ne, Lt, Le, Gt, Ge,
@@ -84,8 +84,8 @@ pub enum Opcode {
     // Memory
     Load, Store, Alloca,
     
-    // Function calls
-    Call,
+    // Function calls; an indirect call takes the function reference first
+    Call, CallIndirect,
     
     // Type conversions
     Cast,
@@ -93,8 +93,19 @@ pub enum Opcode {
     // String operations
     StringConcat,
     
+    // Aggregates: structs, enums (discriminant first) and arrays
+    MakeStruct, MakeEnum, MakeArray,
+    GetField, SetField, GetElement, SetElement,
+    Length, Discriminant,
+    
     // Print operation (Ovie-specific)
     Print,
+
+    // Contract check: stops the program with the message (a string constant) unless the condition holds
+    Assert,
+
+    // Stops the program with the message (a string value)
+    Abort,
 }
 
 /// Block terminator (control flow)
@@ -131,6 +142,9 @@ pub enum Value {
     
     /// Global variable
     Global(String),
+    
+    /// Function referenced by name
+    Function(String),
 }
 
 /// Constant values
@@ -138,6 +152,7 @@ pub enum Value {
 pub enum Constant {
     String(String),
     Number(f64),
+    Integer(i64),
     Boolean(bool),
     Void,
 }
@@ -147,6 +162,8 @@ pub enum Constant {
 pub enum IrType {
     String,
     Number,
+    /// 64-bit signed integer
+    Int,
     Boolean,
     Void,
     Pointer(Box<IrType>),
@@ -154,6 +171,9 @@ pub enum IrType {
         params: Vec<IrType>,
         return_type: Box<IrType>,
     },
+    Struct(String),
+    Enum(String),
+    Array(Box<IrType>),
 }
 
 /// Metadata for the IR program
@@ -175,6 +195,14 @@ pub struct IrBuilder {
     next_block_id: BlockId,
     next_function_id: FunctionId,
     deterministic_mode: bool,
+    /// Stack slot (alloca) holding each MIR local of the function being lowered
+    local_slots: HashMap<u32, ValueId>,
+    /// Types of the MIR locals of the function being lowered
+    local_types: HashMap<u32, crate::mir::MirType>,
+    /// IR block for each MIR block of the function being lowered
+    block_map: HashMap<u32, BlockId>,
+    /// Struct and enum layouts of the MIR program being lowered
+    type_definitions: HashMap<String, crate::mir::MirTypeDef>,
 }
 
 impl IrBuilder {
@@ -199,6 +227,10 @@ impl IrBuilder {
             next_block_id: 1,
             next_function_id: 1,
             deterministic_mode: false,
+            local_slots: HashMap::new(),
+            local_types: HashMap::new(),
+            block_map: HashMap::new(),
+            type_definitions: HashMap::new(),
         }
     }
 
@@ -384,10 +416,14 @@ impl BackendInvariantValidation for Program {
                 for instruction in &block.instructions {
                     match &instruction.opcode {
                         Opcode::Add | Opcode::Sub | Opcode::Mul | Opcode::Div => {
-                            // Check if both operands are constants (should have been folded)
+                            // Check if both operands are constants (should have been folded);
+                            // Int division that would trap is left for run time
                             if instruction.operands.len() == 2 {
-                                if let (Value::Constant(_), Value::Constant(_)) = 
+                                if let (left @ Value::Constant(_), right @ Value::Constant(_)) = 
                                     (&instruction.operands[0], &instruction.operands[1]) {
+                                    if IrBuilder::fold_constants(&instruction.opcode, left, right).is_none() {
+                                        continue;
+                                    }
                                     return Err(OvieError::InvariantViolation {
                                         stage: "Backend".to_string(),
                                         message: format!("Constant folding not applied to instruction {} in function {}", 
@@ -500,6 +536,14 @@ impl BackendInvariantValidation for Program {
                                     });
                                 }
                             }
+                            // Check that the referenced function exists
+                            Value::Function(name) if !self.functions.values().any(|f| f.name == *name) => {
+                                return Err(OvieError::InvariantViolation {
+                                    stage: "Backend".to_string(),
+                                    message: format!("Unresolved function reference '{}' in function {}", 
+                                                   name, function.name),
+                                });
+                            }
                             _ => {} // Constants are always resolved
                         }
                     }
@@ -608,8 +652,9 @@ impl Program {
     /// Helper method to check if a type is concrete (fully resolved)
     fn is_concrete_type(&self, ir_type: &IrType) -> bool {
         match ir_type {
-            IrType::String | IrType::Number | IrType::Boolean | IrType::Void => true,
-            IrType::Pointer(inner) => self.is_concrete_type(inner),
+            IrType::String | IrType::Number | IrType::Int | IrType::Boolean | IrType::Void => true,
+            IrType::Struct(_) | IrType::Enum(_) => true,
+            IrType::Pointer(inner) | IrType::Array(inner) => self.is_concrete_type(inner),
             IrType::Function { params, return_type } => {
                 params.iter().all(|p| self.is_concrete_type(p)) && 
                 self.is_concrete_type(return_type)
@@ -644,7 +689,7 @@ impl IrBuilder {
     /// Transform a statement to IR (simplified version)
     fn transform_statement(&mut self, statement: &Statement) -> OvieResult<()> {
         match statement {
-            Statement::Print { expression } => {
+            Statement::Print { expression, .. } => {
                 // Transform the expression to get its value
                 let value = self.transform_expression(expression)?;
                 
@@ -664,7 +709,7 @@ impl IrBuilder {
                     }
                 }
             }
-            Statement::Assignment { identifier: _, value, mutable: _ } => {
+            Statement::Assignment { identifier: _, value, mutable: _, .. } => {
                 // Transform the value expression
                 let _value = self.transform_expression(value)?;
                 // In a full implementation, we'd handle variable storage
@@ -680,15 +725,16 @@ impl IrBuilder {
     /// Transform an expression to IR (simplified version)
     fn transform_expression(&mut self, expression: &Expression) -> OvieResult<Value> {
         match expression {
-            Expression::Literal(literal) => {
+            Expression::Literal(literal, _) => {
                 let constant = match literal {
                     Literal::String(s) => Constant::String(s.clone()),
                     Literal::Number(n) => Constant::Number(*n),
+                    Literal::Integer(n) => Constant::Integer(*n),
                     Literal::Boolean(b) => Constant::Boolean(*b),
                 };
                 Ok(Value::Constant(constant))
             }
-            Expression::Identifier(_name) => {
+            Expression::Identifier(_name, _) => {
                 // For now, return a placeholder
                 Ok(Value::Constant(Constant::Void))
             }
@@ -698,4 +744,699 @@ impl IrBuilder {
             }
         }
     }
-}
\ No newline at end of file
+}
+/// MIR to IR transformation
+use crate::mir::{
+    MirProgram, MirFunction, MirStatementKind, MirTerminator, MirPlace, MirProjectionElem,
+    MirRvalue, MirOperand, MirConstant, MirConstantValue, MirType, MirTypeDef, MirBinOp, MirUnOp,
+    MirAggregateKind, MirBuilder, ARRAY_TYPE, RANGE_TYPE,
+};
+
+impl IrBuilder {
+    /// Lower a MIR program into IR
+    pub fn transform_mir(&mut self, mir: &MirProgram) -> OvieResult<()> {
+        self.program.metadata.source_file = mir.metadata.source_file.clone();
+        self.type_definitions = mir.type_definitions.clone();
+
+        let mut global_names: Vec<_> = mir.globals.keys().collect();
+        global_names.sort();
+        for name in global_names {
+            let global = &mir.globals[name];
+            let ir_global = Global {
+                name: global.name.clone(),
+                global_type: self.lower_type(&global.ty)?,
+                is_mutable: global.is_mutable,
+                initializer: global.initializer.as_ref().map(|c| Self::lower_constant(&c.literal)),
+            };
+            self.program.globals.insert(ir_global.name.clone(), ir_global);
+        }
+
+        // Lower functions in id order so value and block ids are reproducible
+        let mut function_ids: Vec<_> = mir.functions.keys().copied().collect();
+        function_ids.sort();
+        for function_id in function_ids {
+            let ir_function = self.transform_mir_function(&mir.functions[&function_id])?;
+            if mir.entry_point == Some(function_id) {
+                self.set_entry_point(ir_function);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lower a single MIR function
+    fn transform_mir_function(&mut self, mir_function: &MirFunction) -> OvieResult<FunctionId> {
+        let mut params = Vec::new();
+        for (index, param_type) in mir_function.signature.parameters.iter().enumerate() {
+            let name = mir_function.locals.get(index)
+                .and_then(|local| local.name.clone())
+                .unwrap_or_else(|| format!("arg{}", index));
+            params.push(Parameter {
+                name,
+                param_type: self.lower_type(param_type)?,
+                value_id: self.next_value(),
+            });
+        }
+        let return_type = self.lower_type(&mir_function.signature.return_type)?;
+
+        let function_id = self.create_function(&mir_function.name, params.clone(), return_type.clone());
+        self.current_function = Some(function_id);
+        let entry_block = self.program.functions[&function_id].entry_block;
+
+        self.local_slots.clear();
+        self.local_types.clear();
+        self.block_map.clear();
+
+        let mut block_ids: Vec<_> = mir_function.basic_blocks.keys().copied().collect();
+        block_ids.sort();
+        for &block_id in &block_ids {
+            let ir_block = if block_id == mir_function.entry_block {
+                entry_block
+            } else {
+                self.new_block(&format!("bb{}", block_id))?
+            };
+            self.block_map.insert(block_id, ir_block);
+        }
+
+        // Prologue: one stack slot per local that carries a value
+        self.current_block = Some(entry_block);
+        for local in &mir_function.locals {
+            self.local_types.insert(local.id, local.ty.clone());
+            let local_type = self.lower_type(&local.ty)?;
+            if local_type == IrType::Void {
+                continue;
+            }
+
+            let slot = self.push_instruction(Opcode::Alloca, Vec::new(), IrType::Pointer(Box::new(local_type)))?;
+            if let Value::Instruction(slot_id) = slot {
+                self.local_slots.insert(local.id, slot_id);
+                if let Some(ref name) = local.name {
+                    self.current_function_mut()?.local_variables.insert(name.clone(), slot_id);
+                }
+            }
+        }
+        for (index, param) in params.iter().enumerate() {
+            if let Some(&slot_id) = self.local_slots.get(&(index as u32)) {
+                self.push_instruction(
+                    Opcode::Store,
+                    vec![Value::Instruction(slot_id), Value::Parameter(param.value_id)],
+                    IrType::Void,
+                )?;
+            }
+        }
+
+        for block_id in block_ids {
+            let mir_block = &mir_function.basic_blocks[&block_id];
+            self.current_block = Some(self.block_map[&block_id]);
+
+            for statement in &mir_block.statements {
+                if let MirStatementKind::Assign { place, rvalue } = &statement.kind {
+                    let value = self.lower_rvalue(rvalue)?;
+                    self.write_place(place, value)?;
+                }
+            }
+
+            self.lower_terminator(&mir_block.terminator, &return_type)?;
+        }
+
+        Ok(function_id)
+    }
+
+    /// Lower a MIR block terminator into the current block
+    fn lower_terminator(&mut self, terminator: &MirTerminator, return_type: &IrType) -> OvieResult<()> {
+        let lowered = match terminator {
+            MirTerminator::Return { value } => {
+                let value = match value {
+                    Some(operand) if *return_type != IrType::Void => Some(self.lower_operand(operand)?),
+                    _ => None,
+                };
+                Terminator::Return { value }
+            }
+            MirTerminator::Goto { target } | MirTerminator::Drop { target, .. } => {
+                Terminator::Branch { target: self.map_block(*target)? }
+            }
+            MirTerminator::SwitchInt { discriminant, targets, otherwise } => {
+                return self.lower_switch(discriminant, targets, *otherwise);
+            }
+            MirTerminator::Call { func, args, destination, target, .. } => {
+                self.lower_call(func, args, destination)?;
+                match target {
+                    Some(target) => Terminator::Branch { target: self.map_block(*target)? },
+                    None => Terminator::Unreachable,
+                }
+            }
+            MirTerminator::Assert { condition, message, target } => {
+                let condition = self.lower_operand(condition)?;
+                self.push_instruction(
+                    Opcode::Assert,
+                    vec![condition, Value::Constant(Constant::String(message.clone()))],
+                    IrType::Void,
+                )?;
+                Terminator::Branch { target: self.map_block(*target)? }
+            }
+            MirTerminator::Unreachable => Terminator::Unreachable,
+        };
+
+        self.current_block_mut()?.terminator = lowered;
+        Ok(())
+    }
+
+    /// Lower a switch: booleans branch directly, other values compare against each target in turn
+    fn lower_switch(
+        &mut self,
+        discriminant: &MirOperand,
+        targets: &[(u128, u32)],
+        otherwise: u32,
+    ) -> OvieResult<()> {
+        let value = self.lower_operand(discriminant)?;
+        let otherwise = self.map_block(otherwise)?;
+
+        if self.operand_type(discriminant)? == MirType::Boolean && targets.len() == 1 {
+            let (expected, target) = targets[0];
+            let target = self.map_block(target)?;
+            let (true_target, false_target) = if expected == 0 { (otherwise, target) } else { (target, otherwise) };
+            self.current_block_mut()?.terminator = Terminator::ConditionalBranch {
+                condition: value,
+                true_target,
+                false_target,
+            };
+            return Ok(());
+        }
+
+        if targets.is_empty() {
+            self.current_block_mut()?.terminator = Terminator::Branch { target: otherwise };
+            return Ok(());
+        }
+
+        for (index, &(expected, target)) in targets.iter().enumerate() {
+            let condition = self.push_instruction(
+                Opcode::Eq,
+                vec![value.clone(), Value::Constant(Constant::Number(expected as f64))],
+                IrType::Boolean,
+            )?;
+            let true_target = self.map_block(target)?;
+            let false_target = if index + 1 == targets.len() {
+                otherwise
+            } else {
+                self.new_block("switch")?
+            };
+
+            self.current_block_mut()?.terminator = Terminator::ConditionalBranch {
+                condition,
+                true_target,
+                false_target,
+            };
+            self.current_block = Some(false_target);
+        }
+
+        Ok(())
+    }
+
+    /// Lower a MIR call; `print`, `abort` and the conversions become dedicated instructions
+    fn lower_call(&mut self, func: &MirOperand, args: &[MirOperand], destination: &MirPlace) -> OvieResult<()> {
+        let (name, return_type) = match func {
+            MirOperand::Constant(MirConstant { literal: MirConstantValue::String(name), ty }) => {
+                let return_type = match ty {
+                    MirType::FnPtr { return_type, .. } => self.lower_type(return_type)?,
+                    _ => IrType::Void,
+                };
+                (name.clone(), return_type)
+            }
+            _ => return self.lower_indirect_call(func, args, destination),
+        };
+
+        let mut operands = Vec::new();
+        for arg in args {
+            operands.push(self.lower_operand(arg)?);
+        }
+
+        let result = match name.as_str() {
+            "print" => {
+                self.push_instruction(Opcode::Print, operands, IrType::Void)?;
+                None
+            }
+            "abort" => {
+                self.push_instruction(Opcode::Abort, operands, IrType::Void)?;
+                None
+            }
+            "to_string" => Some(self.push_instruction(Opcode::Cast, operands, IrType::String)?),
+            "to_number" => Some(self.push_instruction(Opcode::Cast, operands, IrType::Number)?),
+            "to_int" => Some(self.push_instruction(Opcode::Cast, operands, IrType::Int)?),
+            _ => {
+                operands.insert(0, Value::Function(name));
+                let result = self.push_instruction(Opcode::Call, operands, return_type.clone())?;
+                if return_type == IrType::Void { None } else { Some(result) }
+            }
+        };
+
+        if let Some(result) = result {
+            self.write_place(destination, result)?;
+        }
+        Ok(())
+    }
+
+    /// Lower a call of a function value: the function referenced by the first field
+    /// of the closure record is called with the record before the arguments
+    fn lower_indirect_call(&mut self, func: &MirOperand, args: &[MirOperand], destination: &MirPlace) -> OvieResult<()> {
+        let function_type = self.lower_type(&self.operand_type(func)?)?;
+        let return_type = match &function_type {
+            IrType::Function { return_type, .. } => (**return_type).clone(),
+            other => return Err(OvieError::ir_error(format!("Cannot call a value of type {:?}", other))),
+        };
+
+        let closure = self.lower_operand(func)?;
+        let function = self.push_instruction(
+            Opcode::GetField,
+            vec![closure.clone(), Value::Constant(Constant::Number(0.0))],
+            function_type,
+        )?;
+
+        let mut operands = vec![function, closure];
+        for arg in args {
+            operands.push(self.lower_operand(arg)?);
+        }
+
+        let result = self.push_instruction(Opcode::CallIndirect, operands, return_type.clone())?;
+        if return_type != IrType::Void {
+            self.write_place(destination, result)?;
+        }
+        Ok(())
+    }
+
+    /// Lower a MIR rvalue, returning the value it computes
+    fn lower_rvalue(&mut self, rvalue: &MirRvalue) -> OvieResult<Value> {
+        match rvalue {
+            MirRvalue::Use(operand) => self.lower_operand(operand),
+            MirRvalue::Repeat { operand, count } => {
+                let element_type = self.lower_type(&self.operand_type(operand)?)?;
+                let value = self.lower_operand(operand)?;
+                let elements = vec![value; *count as usize];
+                self.push_instruction(Opcode::MakeArray, elements, IrType::Array(Box::new(element_type)))
+            }
+            MirRvalue::Ref { .. } => Err(OvieError::ir_error("References are not supported by the IR yet")),
+            MirRvalue::Len(place) => {
+                let array = self.read_place(place)?;
+                self.push_instruction(Opcode::Length, vec![array], IrType::Int)
+            }
+            MirRvalue::Cast { operand, ty, .. } => {
+                let value = self.lower_operand(operand)?;
+                let target_type = self.lower_type(ty)?;
+                self.push_instruction(Opcode::Cast, vec![value], target_type)
+            }
+            MirRvalue::BinaryOp { op, left, right } => {
+                let operand_type = self.operand_type(left)?;
+                let left = self.lower_operand(left)?;
+                let right = self.lower_operand(right)?;
+                self.lower_binary_op(op, left, right, &operand_type)
+            }
+            MirRvalue::UnaryOp { op, operand } => {
+                let operand_type = self.lower_type(&self.operand_type(operand)?)?;
+                let value = self.lower_operand(operand)?;
+                match op {
+                    MirUnOp::Not => self.push_instruction(Opcode::Not, vec![value], IrType::Boolean),
+                    MirUnOp::Neg => self.push_instruction(Opcode::Neg, vec![value], operand_type),
+                }
+            }
+            MirRvalue::Discriminant(place) => {
+                let value = self.read_place(place)?;
+                self.push_instruction(Opcode::Discriminant, vec![value], IrType::Number)
+            }
+            MirRvalue::Aggregate { kind, operands } => {
+                let mut values = Vec::new();
+                for operand in operands {
+                    values.push(self.lower_operand(operand)?);
+                }
+
+                match kind {
+                    MirAggregateKind::Array(element_type) => {
+                        let element_type = self.lower_type(element_type)?;
+                        self.push_instruction(Opcode::MakeArray, values, IrType::Array(Box::new(element_type)))
+                    }
+                    MirAggregateKind::Adt { name, variant: None } => {
+                        self.push_instruction(Opcode::MakeStruct, values, IrType::Struct(name.clone()))
+                    }
+                    MirAggregateKind::Adt { name, variant: Some(variant) } => {
+                        values.insert(0, Value::Constant(Constant::Number(*variant as f64)));
+                        self.push_instruction(Opcode::MakeEnum, values, IrType::Enum(name.clone()))
+                    }
+                    MirAggregateKind::Tuple => {
+                        let element_types = operands.iter()
+                            .map(|operand| self.operand_type(operand))
+                            .collect::<OvieResult<Vec<_>>>()?;
+                        let tuple_type = self.lower_type(&MirType::Tuple(element_types))?;
+                        self.push_instruction(Opcode::MakeStruct, values, tuple_type)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lower a binary operation, folding it when both operands are constants
+    fn lower_binary_op(&mut self, op: &MirBinOp, left: Value, right: Value, operand_type: &MirType) -> OvieResult<Value> {
+        // Arithmetic on Ints stays in Int
+        let numeric = if *operand_type == MirType::Int { IrType::Int } else { IrType::Number };
+        let (opcode, result_type) = match op {
+            MirBinOp::Add if *operand_type == MirType::String => (Opcode::StringConcat, IrType::String),
+            MirBinOp::Add => (Opcode::Add, numeric),
+            MirBinOp::Sub => (Opcode::Sub, numeric),
+            MirBinOp::Mul => (Opcode::Mul, numeric),
+            MirBinOp::Div => (Opcode::Div, numeric),
+            MirBinOp::Rem => (Opcode::Mod, numeric),
+            MirBinOp::BitAnd => (Opcode::And, IrType::Boolean),
+            MirBinOp::BitOr => (Opcode::Or, IrType::Boolean),
+            MirBinOp::Eq => (Opcode::Eq, IrType::Boolean),
+            MirBinOp::Ne => (Opcode::Ne, IrType::Boolean),
+            MirBinOp::Lt => (Opcode::Lt, IrType::Boolean),
+            MirBinOp::Le => (Opcode::Le, IrType::Boolean),
+            MirBinOp::Gt => (Opcode::Gt, IrType::Boolean),
+            MirBinOp::Ge => (Opcode::Ge, IrType::Boolean),
+            MirBinOp::BitXor | MirBinOp::Shl | MirBinOp::Shr => {
+                return Err(OvieError::ir_error(format!("Binary operator {:?} is not supported by the IR yet", op)));
+            }
+        };
+
+        if let Some(folded) = Self::fold_constants(&opcode, &left, &right) {
+            return Ok(Value::Constant(folded));
+        }
+
+        self.push_instruction(opcode, vec![left, right], result_type)
+    }
+
+    /// Evaluate arithmetic and concatenation on two constants at compile time
+    fn fold_constants(opcode: &Opcode, left: &Value, right: &Value) -> Option<Constant> {
+        match (opcode, left, right) {
+            (_, Value::Constant(Constant::Number(a)), Value::Constant(Constant::Number(b))) => match opcode {
+                Opcode::Add => Some(Constant::Number(a + b)),
+                Opcode::Sub => Some(Constant::Number(a - b)),
+                Opcode::Mul => Some(Constant::Number(a * b)),
+                Opcode::Div => Some(Constant::Number(a / b)),
+                _ => None,
+            },
+            // Int arithmetic wraps; division and remainder that would trap are left to run time
+            (_, Value::Constant(Constant::Integer(a)), Value::Constant(Constant::Integer(b))) => match opcode {
+                Opcode::Add => Some(Constant::Integer(a.wrapping_add(*b))),
+                Opcode::Sub => Some(Constant::Integer(a.wrapping_sub(*b))),
+                Opcode::Mul => Some(Constant::Integer(a.wrapping_mul(*b))),
+                Opcode::Div => a.checked_div(*b).map(Constant::Integer),
+                Opcode::Mod if *b != 0 => Some(Constant::Integer(a.wrapping_rem(*b))),
+                _ => None,
+            },
+            (Opcode::StringConcat, Value::Constant(Constant::String(a)), Value::Constant(Constant::String(b))) => {
+                Some(Constant::String(format!("{}{}", a, b)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Lower a MIR operand
+    fn lower_operand(&mut self, operand: &MirOperand) -> OvieResult<Value> {
+        match operand {
+            MirOperand::Constant(MirConstant { literal: MirConstantValue::Function(name), .. }) => Ok(Value::Function(name.clone())),
+            MirOperand::Constant(constant) => Ok(Value::Constant(Self::lower_constant(&constant.literal))),
+            MirOperand::Copy(place) | MirOperand::Move(place, _) => self.read_place(place),
+        }
+    }
+
+    /// Lower a MIR constant
+    fn lower_constant(literal: &MirConstantValue) -> Constant {
+        match literal {
+            MirConstantValue::String(s) => Constant::String(s.clone()),
+            MirConstantValue::Number(n) => Constant::Number(*n),
+            MirConstantValue::Integer(n) => Constant::Integer(*n),
+            MirConstantValue::Boolean(b) => Constant::Boolean(*b),
+            MirConstantValue::Unit | MirConstantValue::Function(_) => Constant::Void,
+        }
+    }
+
+    /// Load the value stored at a MIR place
+    fn read_place(&mut self, place: &MirPlace) -> OvieResult<Value> {
+        // Unit-typed locals have no slot and carry no value
+        let slot_id = match self.local_slots.get(&place.local) {
+            Some(&slot_id) => slot_id,
+            None => return Ok(Value::Constant(Constant::Void)),
+        };
+
+        let mut place_type = self.local_type(place.local)?;
+        let mut value = self.push_instruction(
+            Opcode::Load,
+            vec![Value::Instruction(slot_id)],
+            self.lower_type(&place_type)?,
+        )?;
+
+        // A downcast selects the variant whose payload the next field projection reads
+        let mut variant = None;
+        for elem in &place.projection {
+            if let MirProjectionElem::Downcast(index) = elem {
+                variant = Some(*index);
+                continue;
+            }
+            let (projected, projected_type) = self.project(value, &place_type, variant.take(), elem)?;
+            value = projected;
+            place_type = projected_type;
+        }
+
+        Ok(value)
+    }
+
+    /// Store a value into a MIR place.
+    ///
+    /// Aggregates are updated functionally: every level of the projection is
+    /// rebuilt from the innermost element outwards and stored back into the slot.
+    /// Structs are rebuilt from their fields; arrays are updated with `SetElement`,
+    /// which yields a new array.
+    fn write_place(&mut self, place: &MirPlace, value: Value) -> OvieResult<()> {
+        let slot_id = match self.local_slots.get(&place.local) {
+            Some(&slot_id) => slot_id,
+            None => return Ok(()),
+        };
+
+        let mut new_value = value;
+        if let Some((last, outer)) = place.projection.split_last() {
+            let mut place_type = self.local_type(place.local)?;
+            let mut current = self.push_instruction(
+                Opcode::Load,
+                vec![Value::Instruction(slot_id)],
+                self.lower_type(&place_type)?,
+            )?;
+
+            let mut levels = Vec::new();
+            for elem in outer {
+                let (projected, projected_type) = self.project(current.clone(), &place_type, None, elem)?;
+                levels.push((current, place_type, elem));
+                current = projected;
+                place_type = projected_type;
+            }
+            levels.push((current, place_type, last));
+
+            for (base, base_type, elem) in levels.into_iter().rev() {
+                new_value = match elem {
+                    MirProjectionElem::Field(index) => self.rebuild_struct(base, &base_type, *index, new_value)?,
+                    MirProjectionElem::Index(index_local) => {
+                        let index = self.read_place(&MirPlace::from_local(*index_local))?;
+                        let result_type = self.lower_type(&base_type)?;
+                        self.push_instruction(Opcode::SetElement, vec![base, index, new_value], result_type)?
+                    }
+                    other => {
+                        return Err(OvieError::ir_error(format!("Projection {:?} is not supported by the IR yet", other)));
+                    }
+                };
+            }
+        }
+
+        self.push_instruction(Opcode::Store, vec![Value::Instruction(slot_id), new_value], IrType::Void)?;
+        Ok(())
+    }
+
+    /// A copy of a struct or tuple value with the field at `index` replaced
+    fn rebuild_struct(&mut self, value: Value, struct_type: &MirType, index: u32, field_value: Value) -> OvieResult<Value> {
+        let field_count = match struct_type {
+            MirType::Tuple(element_types) => element_types.len() as u32,
+            MirType::Adt { name, .. } => match self.type_definitions.get(name) {
+                Some(MirTypeDef::Struct { fields }) => fields.len() as u32,
+                _ => return Err(OvieError::ir_error(format!("Cannot assign to a field of type {:?}", struct_type))),
+            },
+            _ => return Err(OvieError::ir_error(format!("Cannot assign to a field of type {:?}", struct_type))),
+        };
+
+        let mut fields = Vec::new();
+        for field_index in 0..field_count {
+            if field_index == index {
+                fields.push(field_value.clone());
+            } else {
+                let (field, _) = self.project(value.clone(), struct_type, None, &MirProjectionElem::Field(field_index))?;
+                fields.push(field);
+            }
+        }
+        let struct_type = self.lower_type(struct_type)?;
+        self.push_instruction(Opcode::MakeStruct, fields, struct_type)
+    }
+
+    /// Apply one projection element to an aggregate value; `variant` is set when
+    /// the value was downcast to one of its enum variants
+    fn project(&mut self, value: Value, value_type: &MirType, variant: Option<u32>, elem: &MirProjectionElem) -> OvieResult<(Value, MirType)> {
+        match elem {
+            MirProjectionElem::Field(index) => {
+                let field_type = self.field_type(value_type, variant, *index)?;
+                let field = self.push_instruction(
+                    Opcode::GetField,
+                    vec![value, Value::Constant(Constant::Number(*index as f64))],
+                    self.lower_type(&field_type)?,
+                )?;
+                Ok((field, field_type))
+            }
+            MirProjectionElem::Index(index_local) => {
+                let element_type = Self::element_type(value_type)?;
+                let index = self.read_place(&MirPlace::from_local(*index_local))?;
+                let element = self.push_instruction(
+                    Opcode::GetElement,
+                    vec![value, index],
+                    self.lower_type(&element_type)?,
+                )?;
+                Ok((element, element_type))
+            }
+            other => Err(OvieError::ir_error(format!("Projection {:?} is not supported by the IR yet", other))),
+        }
+    }
+
+    /// Type of a struct field, or of a field of the given enum variant
+    fn field_type(&self, struct_type: &MirType, variant: Option<u32>, index: u32) -> OvieResult<MirType> {
+        let field_type = match struct_type {
+            MirType::Adt { name, substs } if name == RANGE_TYPE => substs.first().cloned(),
+            MirType::Tuple(element_types) => element_types.get(index as usize).cloned(),
+            MirType::Adt { name, .. } => match (self.type_definitions.get(name), variant) {
+                (Some(MirTypeDef::Struct { fields }), None) => fields.get(index as usize).map(|field| field.ty.clone()),
+                (Some(MirTypeDef::Enum { variants }), Some(variant)) => variants.get(variant as usize)
+                    .and_then(|variant| variant.fields.get(index as usize))
+                    .map(|field| field.ty.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        field_type.ok_or_else(|| OvieError::ir_error(format!("Field {} not found on type {:?}", index, struct_type)))
+    }
+
+    /// Element type of an array
+    fn element_type(array_type: &MirType) -> OvieResult<MirType> {
+        match array_type {
+            MirType::Adt { name, substs } if name == ARRAY_TYPE && substs.len() == 1 => Ok(substs[0].clone()),
+            MirType::Array { element_type, .. } | MirType::Slice(element_type) => Ok((**element_type).clone()),
+            _ => Err(OvieError::ir_error(format!("Cannot index a value of type {:?}", array_type))),
+        }
+    }
+
+    /// Type of a MIR operand
+    fn operand_type(&self, operand: &MirOperand) -> OvieResult<MirType> {
+        match operand {
+            MirOperand::Constant(constant) => Ok(constant.ty.clone()),
+            MirOperand::Copy(place) | MirOperand::Move(place, _) => {
+                let mut place_type = self.local_type(place.local)?;
+                let mut variant = None;
+                for elem in &place.projection {
+                    place_type = match elem {
+                        MirProjectionElem::Downcast(index) => {
+                            variant = Some(*index);
+                            continue;
+                        }
+                        MirProjectionElem::Field(index) => self.field_type(&place_type, variant.take(), *index)?,
+                        MirProjectionElem::Index(_) => Self::element_type(&place_type)?,
+                        other => {
+                            return Err(OvieError::ir_error(format!("Projection {:?} is not supported by the IR yet", other)));
+                        }
+                    };
+                }
+                Ok(place_type)
+            }
+        }
+    }
+
+    /// Declared type of a MIR local in the function being lowered
+    fn local_type(&self, local: u32) -> OvieResult<MirType> {
+        self.local_types.get(&local)
+            .cloned()
+            .ok_or_else(|| OvieError::ir_error(format!("Unknown MIR local {}", local)))
+    }
+
+    /// Lower a MIR type
+    fn lower_type(&self, mir_type: &MirType) -> OvieResult<IrType> {
+        match mir_type {
+            MirType::String => Ok(IrType::String),
+            MirType::Number => Ok(IrType::Number),
+            MirType::Int => Ok(IrType::Int),
+            MirType::Boolean => Ok(IrType::Boolean),
+            MirType::Unit => Ok(IrType::Void),
+            MirType::Adt { name, substs } if name == ARRAY_TYPE && substs.len() == 1 => {
+                Ok(IrType::Array(Box::new(self.lower_type(&substs[0])?)))
+            }
+            MirType::Adt { name, .. } => match self.type_definitions.get(name) {
+                Some(MirTypeDef::Enum { .. }) => Ok(IrType::Enum(name.clone())),
+                _ => Ok(IrType::Struct(name.clone())),
+            },
+            MirType::Ref { ty, .. } => Ok(IrType::Pointer(Box::new(self.lower_type(ty)?))),
+            MirType::FnPtr { params, return_type } => Ok(IrType::Function {
+                params: params.iter().map(|p| self.lower_type(p)).collect::<OvieResult<Vec<_>>>()?,
+                return_type: Box::new(self.lower_type(return_type)?),
+            }),
+            MirType::Array { element_type, .. } | MirType::Slice(element_type) => {
+                Ok(IrType::Array(Box::new(self.lower_type(element_type)?)))
+            }
+            // A tuple is laid out like a struct with one field per element
+            MirType::Tuple(_) => Ok(IrType::Struct(MirBuilder::type_name(mir_type))),
+        }
+    }
+
+    /// Map a MIR block id to the IR block created for it
+    fn map_block(&self, block: u32) -> OvieResult<BlockId> {
+        self.block_map.get(&block)
+            .copied()
+            .ok_or_else(|| OvieError::ir_error(format!("Unknown MIR block {}", block)))
+    }
+
+    /// Allocate a fresh value id
+    fn next_value(&mut self) -> ValueId {
+        let value_id = self.next_value_id;
+        self.next_value_id += 1;
+        value_id
+    }
+
+    /// Add an empty block to the current function
+    fn new_block(&mut self, label: &str) -> OvieResult<BlockId> {
+        let block_id = self.next_block_id;
+        self.next_block_id += 1;
+
+        self.current_function_mut()?.basic_blocks.insert(block_id, BasicBlock {
+            id: block_id,
+            label: label.to_string(),
+            instructions: Vec::new(),
+            terminator: Terminator::Unreachable,
+        });
+
+        Ok(block_id)
+    }
+
+    /// Append an instruction to the current block and return its result
+    fn push_instruction(&mut self, opcode: Opcode, operands: Vec<Value>, result_type: IrType) -> OvieResult<Value> {
+        let id = self.next_value();
+        self.current_block_mut()?.instructions.push(Instruction {
+            id,
+            opcode,
+            operands,
+            result_type,
+        });
+        Ok(Value::Instruction(id))
+    }
+
+    fn current_function_mut(&mut self) -> OvieResult<&mut Function> {
+        let function_id = self.current_function
+            .ok_or_else(|| OvieError::ir_error("No current function"))?;
+        self.program.functions.get_mut(&function_id)
+            .ok_or_else(|| OvieError::ir_error(format!("Function {} not found", function_id)))
+    }
+
+    fn current_block_mut(&mut self) -> OvieResult<&mut BasicBlock> {
+        let block_id = self.current_block
+            .ok_or_else(|| OvieError::ir_error("No current block"))?;
+        self.current_function_mut()?.basic_blocks.get_mut(&block_id)
+            .ok_or_else(|| OvieError::ir_error(format!("Block {} not found", block_id)))
+    }
+}
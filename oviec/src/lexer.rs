@@ -7,7 +7,7 @@ use std::fmt;
 /// Token types for the Ovie language
 #[derive(Logos, Debug, Clone, Copy, PartialEq)]
 pub enum TokenType {
-    // Keywords (exactly 13 as per spec)
+    // Keywords (see spec/grammar.md)
     #[token("fn")]
     Fn,
     #[token("mut")]
@@ -36,6 +36,30 @@ pub enum TokenType {
     SeeAm,
     #[token("let")]
     Let,
+    #[token("match")]
+    Match,
+    #[token("use")]
+    Use,
+    #[token("mod")]
+    Mod,
+    #[token("pub")]
+    Pub,
+    #[token("impl")]
+    Impl,
+    #[token("break")]
+    Break,
+    #[token("continue")]
+    Continue,
+    #[token("const")]
+    Const,
+    #[token("trait")]
+    Trait,
+    #[token("requires")]
+    Requires,
+    #[token("ensures")]
+    Ensures,
+    #[token("invariant")]
+    Invariant,
 
     // Identifiers and literals
     #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*")]
@@ -90,9 +114,25 @@ pub enum TokenType {
     OrOr,
     #[token("!")]
     Bang,
+
+    // Postfix error propagation
+    #[token("?")]
+    Question,
     
     #[token("=")]
     Equal,
+
+    // Compound assignment operators
+    #[token("+=")]
+    PlusEqual,
+    #[token("-=")]
+    MinusEqual,
+    #[token("*=")]
+    StarEqual,
+    #[token("/=")]
+    SlashEqual,
+    #[token("%=")]
+    PercentEqual,
     
     // Assignment operator (alias for Equal)
     Assign,
@@ -117,6 +157,12 @@ pub enum TokenType {
     Semicolon,
     #[token(":")]
     Colon,
+    #[token("::")]
+    ColonColon,
+    #[token("->")]
+    Arrow,
+    #[token("=>")]
+    FatArrow,
     #[token(".")]
     Dot,
     #[token("..")]
@@ -175,8 +221,18 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    /// Tokenize the entire source code
+    /// Tokenize the entire source code, failing at the first unexpected character
     pub fn tokenize(&mut self) -> OvieResult<Vec<Token>> {
+        let tokens = self.tokenize_recovering();
+        match tokens.iter().find(|token| token.token_type == TokenType::Error) {
+            Some(token) => Err(unexpected_character(token)),
+            None => Ok(tokens),
+        }
+    }
+
+    /// Tokenize the entire source code, turning each unexpected character into
+    /// an `Error` token so the parser can report it and carry on
+    pub fn tokenize_recovering(&mut self) -> Vec<Token> {
         let mut tokens = Vec::new();
         let mut lexer = TokenType::lexer(self.source);
         
@@ -187,33 +243,21 @@ impl<'a> Lexer<'a> {
             // Calculate line and column from span
             let location = self.calculate_location(span.start);
             
-            match token_type {
-                Ok(token_type) => {
-                    // Handle special cases for keywords vs identifiers
-                    let final_token_type = match token_type {
-                        TokenType::Identifier => {
-                            self.classify_identifier(&lexeme)
-                        }
-                        _ => token_type,
-                    };
-                    
-                    tokens.push(Token::new(final_token_type, lexeme, location));
-                }
-                Err(_) => {
-                    return Err(OvieError::lex_error(
-                        location.line,
-                        location.column,
-                        format!("Unexpected character: '{}'", lexeme),
-                    ));
-                }
-            }
+            // Handle special cases for keywords vs identifiers
+            let final_token_type = match token_type {
+                Ok(TokenType::Identifier) => self.classify_identifier(&lexeme),
+                Ok(token_type) => token_type,
+                Err(_) => TokenType::Error,
+            };
+            
+            tokens.push(Token::new(final_token_type, lexeme, location));
         }
 
         // Add EOF token
         let eof_location = self.calculate_location(self.source.len());
         tokens.push(Token::new(TokenType::Eof, String::new(), eof_location));
 
-        Ok(tokens)
+        tokens
     }
 
     /// Calculate source location from byte offset
@@ -243,6 +287,18 @@ impl<'a> Lexer<'a> {
             "fn" => TokenType::Fn,
             "mut" => TokenType::Mut,
             "let" => TokenType::Let,
+            "match" => TokenType::Match,
+            "use" => TokenType::Use,
+            "mod" => TokenType::Mod,
+            "pub" => TokenType::Pub,
+            "impl" => TokenType::Impl,
+            "break" => TokenType::Break,
+            "continue" => TokenType::Continue,
+            "const" => TokenType::Const,
+            "trait" => TokenType::Trait,
+            "requires" => TokenType::Requires,
+            "ensures" => TokenType::Ensures,
+            "invariant" => TokenType::Invariant,
             "if" => TokenType::If,
             "else" => TokenType::Else,
             "for" => TokenType::For,
@@ -266,6 +322,18 @@ impl fmt::Display for TokenType {
             TokenType::Fn => "fn",
             TokenType::Mut => "mut",
             TokenType::Let => "let",
+            TokenType::Match => "match",
+            TokenType::Use => "use",
+            TokenType::Mod => "mod",
+            TokenType::Pub => "pub",
+            TokenType::Impl => "impl",
+            TokenType::Break => "break",
+            TokenType::Continue => "continue",
+            TokenType::Const => "const",
+            TokenType::Trait => "trait",
+            TokenType::Requires => "requires",
+            TokenType::Ensures => "ensures",
+            TokenType::Invariant => "invariant",
             TokenType::If => "if",
             TokenType::Else => "else",
             TokenType::For => "for",
@@ -299,6 +367,11 @@ impl fmt::Display for TokenType {
             TokenType::OrOr => "||",
             TokenType::Bang => "!",
             TokenType::Equal => "=",
+            TokenType::PlusEqual => "+=",
+            TokenType::MinusEqual => "-=",
+            TokenType::StarEqual => "*=",
+            TokenType::SlashEqual => "/=",
+            TokenType::PercentEqual => "%=",
             TokenType::Assign => "=",
             TokenType::LeftParen => "(",
             TokenType::RightParen => ")",
@@ -309,8 +382,12 @@ impl fmt::Display for TokenType {
             TokenType::Comma => ",",
             TokenType::Semicolon => ";",
             TokenType::Colon => ":",
+            TokenType::ColonColon => "::",
+            TokenType::Arrow => "->",
+            TokenType::FatArrow => "=>",
             TokenType::Dot => ".",
             TokenType::DotDot => "..",
+            TokenType::Question => "?",
             TokenType::In => "in",
             TokenType::Whitespace => "whitespace",
             TokenType::Eof => "EOF",
@@ -320,6 +397,15 @@ impl fmt::Display for TokenType {
     }
 }
 
+/// Error for an `Error` token: a character that starts no token
+pub fn unexpected_character(token: &Token) -> OvieError {
+    OvieError::lex_error(
+        token.location.line,
+        token.location.column,
+        format!("Unexpected character: '{}'", token.lexeme),
+    )
+}
+
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -374,7 +460,7 @@ mod tests {
 
     #[test]
     fn test_operators() {
-        let mut lexer = Lexer::new("+ - * / % == != < <= > >= && || ! =");
+        let mut lexer = Lexer::new("+ - * / % == != < <= > >= && || ! = += -= *= /= %= ?");
         let tokens = lexer.tokenize().unwrap();
         
         let expected = vec![
@@ -393,6 +479,12 @@ mod tests {
             TokenType::OrOr,
             TokenType::Bang,
             TokenType::Equal,
+            TokenType::PlusEqual,
+            TokenType::MinusEqual,
+            TokenType::StarEqual,
+            TokenType::SlashEqual,
+            TokenType::PercentEqual,
+            TokenType::Question,
             TokenType::Eof,
         ];
         
@@ -483,7 +575,9 @@ mod tests {
                     !matches!(s.as_str(), 
                         "fn" | "mut" | "if" | "else" | "for" | "while" | 
                         "struct" | "enum" | "unsafe" | "return" | "true" | 
-                        "false" | "seeAm" | "in"
+                        "false" | "seeAm" | "in" | "let" | "match" |
+                        "use" | "mod" | "pub" | "impl" | "break" | "continue" | "const" | "trait" |
+                        "requires" | "ensures" | "invariant"
                     )
                 })
         }
@@ -519,6 +613,12 @@ mod tests {
                 Just("true".to_string()),
                 Just("false".to_string()),
                 Just("seeAm".to_string()),
+                Just("let".to_string()),
+                Just("match".to_string()),
+                Just("use".to_string()),
+                Just("mod".to_string()),
+                Just("pub".to_string()),
+                Just("impl".to_string()),
             ]
         }
 
@@ -6,8 +6,14 @@
 pub mod lexer;
 pub mod parser;
 pub mod ast;
+pub mod modules;
 pub mod hir;
+pub mod const_eval;
+pub mod infer;
+pub mod resolve;
 pub mod mir;
+pub mod dataflow;
+pub mod borrowck;
 pub mod error;
 pub mod normalizer;
 pub mod ir;
@@ -27,6 +33,7 @@ pub mod runtime_environment;
 pub mod stdlib;
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 use sha2::{Sha256, Digest};
 
@@ -181,11 +188,12 @@ impl BuildMetadata {
         self.build_flags.push(flag);
     }
 }
-pub use error::{OvieError, OvieResult, Diagnostic, ErrorReporter, ErrorSeverity, ErrorCategory, ErrorSuggestion, CodeFix, TextReplacement, SourcePosition, SourceLocation};
+pub use error::{OvieError, OvieResult, Diagnostic, ErrorReporter, ErrorSeverity, ErrorCategory, ErrorSuggestion, CodeFix, TextReplacement, SourcePosition, SourceLocation, ErrorKind, ResolutionErrorKind, MutabilityErrorKind, BorrowErrorKind};
 // pub use self::{BuildConfig, BuildMetadata}; // Remove duplicate export
 pub use lexer::{Lexer, Token, TokenType};
 pub use parser::{Parser, ParseResult};
 pub use ast::{AstNode, Statement, Expression, AstInvariantValidation};
+pub use modules::{ModuleGraph, ModuleLoader, ModulePath, ModuleScope};
 pub use hir::{HirProgram, HirBuilder, HirItem, HirFunction, HirStatement, HirExpression, HirType, HirInvariantValidation};
 pub use mir::{MirProgram, MirBuilder, MirFunction, MirBasicBlock, MirStatement, MirTerminator, MirType, MirInvariantValidation};
 pub use interpreter::{Interpreter, IrInterpreter};
@@ -195,7 +203,7 @@ pub use normalizer::Normalizer;
 pub use codegen::CodegenBackend;
 pub use codegen::WasmBackend;
 pub use package::{PackageRegistry, PackageId, PackageMetadata, PackageLock, DependencyResolver, ProjectConfig, DependencySpec, IntegrityManifest, PackageSignature, OfflineMetadata};
-pub use security::{NetworkMonitor, CryptographicVerifier, SupplyChainSecurity, SecurityPolicies, SecurityReport, UnsafeOperationAnalyzer, UnsafeOperation, UnsafeAuditEntry, TelemetryMonitor, TelemetryAttempt, PrivacySettings, PrivacyComplianceReport, NetworkSecurityReport, ComprehensiveSecurityReport};
+pub use security::{NetworkMonitor, CryptographicVerifier, SupplyChainSecurity, SecurityPolicies, SecurityReport, UnsafeOperationAnalyzer, UnsafeOperation, UnsafeAuditEntry, UnsafeBlockEntry, TelemetryMonitor, TelemetryAttempt, PrivacySettings, PrivacyComplianceReport, NetworkSecurityReport, ComprehensiveSecurityReport};
 pub use self_hosting::{SelfHostingManager, SelfHostingStage, BootstrapVerifier, BootstrapConfig, BootstrapVerificationResult, BootstrapIntegration, IntegrationMode, IntegrationVerificationResult};
 pub use branding::{BrandingConfig, ProjectTemplate, ProjectMetadata};
 pub use release::{ReleaseManager, SecurityLevel, ReleaseMetadata, DistributionConfig, DistributionManager, ReleasePackage, SignatureResult, VerificationResult};
@@ -210,7 +218,8 @@ pub use runtime_environment::{OvieRuntimeEnvironment, OreError, HealthReport, He
 pub use codegen::LlvmBackend;
 
 #[cfg(test)]
-mod tests {
+#[path = "tests"]
+mod compiler_tests {
     pub mod property_tests;
     pub mod grammar_validation_tests;
     pub mod hir_tests;
@@ -281,6 +290,9 @@ pub struct Compiler {
     pub security_manager: SupplyChainSecurity,
     /// Enable strict invariant checking (panic on violation)
     pub strict_invariants: bool,
+    /// Check `requires`, `ensures` and `invariant` clauses at run time; on in debug
+    /// builds, off in release builds
+    pub check_contracts: bool,
 }
 
 impl Compiler {
@@ -292,6 +304,7 @@ impl Compiler {
             build_config: DeterministicBuildConfig::new(),
             security_manager: SupplyChainSecurity::new(),
             strict_invariants: false,
+            check_contracts: true,
         }
     }
 
@@ -303,6 +316,7 @@ impl Compiler {
             build_config: DeterministicBuildConfig::new(),
             security_manager: SupplyChainSecurity::new(),
             strict_invariants: false,
+            check_contracts: true,
         }
     }
 
@@ -314,6 +328,7 @@ impl Compiler {
             build_config: DeterministicBuildConfig::new(),
             security_manager: SupplyChainSecurity::new(),
             strict_invariants: false,
+            check_contracts: true,
         }
     }
 
@@ -325,6 +340,7 @@ impl Compiler {
             build_config: DeterministicBuildConfig::new_deterministic(),
             security_manager: SupplyChainSecurity::new(),
             strict_invariants: false,
+            check_contracts: true,
         }
     }
 
@@ -336,6 +352,7 @@ impl Compiler {
             build_config: DeterministicBuildConfig::new(),
             security_manager: SupplyChainSecurity::new(),
             strict_invariants: true,
+            check_contracts: true,
         }
     }
 
@@ -344,6 +361,11 @@ impl Compiler {
         self.strict_invariants = enabled;
     }
 
+    /// Check contract clauses at run time (debug builds) or leave them out (release builds)
+    pub fn set_check_contracts(&mut self, enabled: bool) {
+        self.check_contracts = enabled;
+    }
+
     /// Set build configuration
     pub fn with_build_config(mut self, config: DeterministicBuildConfig) -> Self {
         self.build_config = config;
@@ -425,11 +447,17 @@ impl Compiler {
             
             let security_report = unsafe_analyzer.generate_security_report();
             println!("Security report: {} total unsafe operations", security_report.total_unsafe_operations);
+            for block in &security_report.unsafe_blocks {
+                println!("  Unsafe block at {}:{} ({} operations)", block.span.line, block.span.column, block.operation_count);
+            }
             for recommendation in &security_report.recommendations {
                 println!("  Recommendation: {}", recommendation);
             }
         }
 
+        // Operations outside an unsafe block or unsafe function are rejected
+        unsafe_analyzer.check_explicit_unsafe(&unsafe_operations)?;
+
         // Step 5: Privacy and telemetry monitoring
         // Monitor any potential telemetry attempts during compilation
         let telemetry_blocked = self.security_manager.monitor_network_call(
@@ -477,14 +505,71 @@ impl Compiler {
         Ok(normalized_ast)
     }
 
+    /// Parse Ovie source code, collecting every syntax error instead of stopping at the first
+    ///
+    /// Returns the partial AST (with `Statement::Error` nodes where parsing failed) so
+    /// tools like `ovie check` and Aproko can still look at the rest of the file.
+    pub fn parse_with_diagnostics(&mut self, source: &str) -> OvieResult<(AstNode, Vec<Diagnostic>)> {
+        let mut normalizer = Normalizer::new();
+        let (normalized_source, _) = normalizer.normalize_source(source);
+
+        let mut lexer = Lexer::new(&normalized_source);
+        let tokens = lexer.tokenize_recovering();
+
+        let mut parser = Parser::new(tokens);
+        Ok(parser.parse_with_diagnostics())
+    }
+
+    /// Load the module graph of the program whose root module is the file `entry`.
+    ///
+    /// Every module is parsed with `compile_to_ast`; imported modules are found in
+    /// the `src/` directory of the project (the nearest `ovie.toml`) or the standard library.
+    pub fn load_modules(&mut self, entry: &Path) -> OvieResult<ModuleGraph> {
+        ModuleGraph::load(entry, |source| self.compile_to_ast(source))
+    }
+
     /// Compile Ovie source code to HIR (High-level IR)
     pub fn compile_to_hir(&mut self, source: &str) -> OvieResult<HirProgram> {
         let ast = self.compile_to_ast(source)?;
-        
+        self.lower_to_hir(&ModuleGraph::single(ast)?)
+    }
+
+    /// Compile the program rooted at the file `entry`, with its imports, to HIR
+    pub fn compile_file_to_hir(&mut self, entry: &Path) -> OvieResult<HirProgram> {
+        let graph = self.load_modules(entry)?;
+        self.lower_to_hir(&graph)
+    }
+
+    /// Build and validate the HIR of every module in `graph`
+    fn lower_to_hir(&mut self, graph: &ModuleGraph) -> OvieResult<HirProgram> {
         // Step 6: HIR generation (semantic analysis and type checking)
         let mut hir_builder = HirBuilder::new();
-        let hir = hir_builder.transform_ast(&ast)?;
-        
+        let hir = hir_builder.transform_module_graph(graph)?;
+        self.validate_hir(hir)
+    }
+
+    /// Check the program rooted at the file `entry`, with its imports, collecting every
+    /// semantic error instead of stopping at the first
+    ///
+    /// The diagnostics keep their suggestions, so `ovie fix` can apply their code fixes.
+    /// A program free of semantic errors is lowered to MIR and borrow checked.
+    /// Errors loading the modules are returned as errors, as by `compile_file_to_hir`.
+    pub fn check_file_with_diagnostics(&mut self, entry: &Path) -> OvieResult<Vec<Diagnostic>> {
+        let graph = self.load_modules(entry)?;
+        match HirBuilder::new().transform_with_diagnostics(&graph) {
+            (Some(hir), _) => {
+                let hir = self.validate_hir(hir)?;
+                let mut mir_builder = MirBuilder::new();
+                mir_builder.set_check_contracts(self.check_contracts);
+                let mir = mir_builder.transform_hir(&hir)?;
+                Ok(crate::borrowck::check_program(&mir).iter().map(OvieError::to_diagnostic).collect())
+            }
+            (None, diagnostics) => Ok(diagnostics),
+        }
+    }
+
+    /// Validate the invariants of freshly built HIR
+    fn validate_hir(&self, hir: HirProgram) -> OvieResult<HirProgram> {
         // Step 7: HIR invariant validation
         if let Err(e) = hir.validate() {
             let error = OvieError::InvariantViolation {
@@ -518,10 +603,21 @@ impl Compiler {
     /// Compile Ovie source code to MIR (Mid-level IR)
     pub fn compile_to_mir(&mut self, source: &str) -> OvieResult<MirProgram> {
         let hir = self.compile_to_hir(source)?;
-        
+        self.lower_to_mir(&hir)
+    }
+
+    /// Compile the program rooted at the file `entry` to MIR
+    pub fn compile_file_to_mir(&mut self, entry: &Path) -> OvieResult<MirProgram> {
+        let hir = self.compile_file_to_hir(entry)?;
+        self.lower_to_mir(&hir)
+    }
+
+    /// Build and validate MIR from HIR
+    fn lower_to_mir(&mut self, hir: &HirProgram) -> OvieResult<MirProgram> {
         // Step 7: MIR generation (control flow explicit)
         let mut mir_builder = MirBuilder::new();
-        let mir = mir_builder.transform_hir(&hir)?;
+        mir_builder.set_check_contracts(self.check_contracts);
+        let mir = mir_builder.transform_hir(hir)?;
         
         // Step 8: MIR invariant validation
         if let Err(e) = mir.validate() {
@@ -545,6 +641,11 @@ impl Compiler {
             }
         }
         
+        // Step 9: borrow checking
+        if let Some(error) = crate::borrowck::check_program(&mir).into_iter().next() {
+            return Err(error);
+        }
+
         if self.debug {
             println!("MIR: {}", mir.to_json().unwrap_or_else(|_| "Failed to serialize MIR".to_string()));
             println!("MIR invariants validated successfully");
@@ -553,18 +654,27 @@ impl Compiler {
         Ok(mir)
     }
 
-    /// Compile Ovie source code to IR (legacy - now uses MIR)
+    /// Compile Ovie source code to IR, lowered from MIR
     pub fn compile_to_ir(&mut self, source: &str) -> OvieResult<IR> {
         let mir = self.compile_to_mir(source)?;
-        
+        self.lower_to_ir(&mir)
+    }
+
+    /// Compile the program rooted at the file `entry` to IR
+    pub fn compile_file_to_ir(&mut self, entry: &Path) -> OvieResult<IR> {
+        let mir = self.compile_file_to_mir(entry)?;
+        self.lower_to_ir(&mir)
+    }
+
+    /// Build and validate IR from MIR
+    fn lower_to_ir(&mut self, mir: &MirProgram) -> OvieResult<IR> {
         // Convert MIR to legacy IR format for backward compatibility
         let mut ir_builder = IrBuilder::new();
         if self.build_config.deterministic_output {
             ir_builder.set_deterministic_mode(true);
         }
         
-        // For now, create a simple IR from MIR
-        // In a full implementation, this would be a proper MIR to IR conversion
+        ir_builder.transform_mir(mir)?;
         let ir = ir_builder.build();
         
         // Step 9: Backend invariant validation
@@ -610,8 +720,12 @@ impl Compiler {
     /// Compile and interpret Ovie source code using AST interpreter
     pub fn compile_and_run(&mut self, source: &str) -> OvieResult<()> {
         let ast = self.compile_to_ast(source)?;
+        // The interpreter walks the AST, so run the checks of `ovie check` first
+        let hir = self.lower_to_hir(&ModuleGraph::single(ast.clone())?)?;
+        self.lower_to_mir(&hir)?;
         
         let mut interpreter = Interpreter::new();
+        interpreter.set_check_contracts(self.check_contracts);
         interpreter.interpret(&ast)?;
         
         Ok(())
@@ -619,19 +733,23 @@ impl Compiler {
 
     /// Compile Ovie source code to WebAssembly
     pub fn compile_to_wasm(&mut self, source: &str) -> OvieResult<Vec<u8>> {
-        // Compile to MIR first (validates all invariants)
-        let _mir = self.compile_to_mir(source)?;
-        
-        // Convert MIR to legacy IR for WASM backend (temporary)
-        // Note: This recompiles from source, which is inefficient but necessary
-        // until we have proper MIR to IR conversion
+        // Lower through MIR to IR (validates all invariants)
         let ir = self.compile_to_ir(source)?;
-        
+        self.generate_wasm(&ir)
+    }
+
+    /// Compile the program rooted at the file `entry` to WebAssembly
+    pub fn compile_file_to_wasm(&mut self, entry: &Path) -> OvieResult<Vec<u8>> {
+        let ir = self.compile_file_to_ir(entry)?;
+        self.generate_wasm(&ir)
+    }
+
+    fn generate_wasm(&mut self, ir: &IR) -> OvieResult<Vec<u8>> {
         let mut wasm_backend = crate::codegen::WasmBackend::new();
         if self.build_config.deterministic_output {
             wasm_backend.set_deterministic_mode(true);
         }
-        let wasm_bytes = wasm_backend.generate(&ir)?;
+        let wasm_bytes = wasm_backend.generate(ir)?;
         
         if self.debug {
             println!("Generated WASM module: {} bytes", wasm_bytes.len());
@@ -643,20 +761,26 @@ impl Compiler {
     /// Compile Ovie source code to LLVM IR
     #[cfg(feature = "llvm")]
     pub fn compile_to_llvm(&mut self, source: &str) -> OvieResult<String> {
-        // Compile to MIR first (validates all invariants)
-        let _mir = self.compile_to_mir(source)?;
-        
-        // Convert MIR to legacy IR for LLVM backend (temporary)
-        // Note: This recompiles from source, which is inefficient but necessary
-        // until we have proper MIR to IR conversion
+        // Lower through MIR to IR (validates all invariants)
         let ir = self.compile_to_ir(source)?;
-        
+        self.generate_llvm(&ir)
+    }
+
+    /// Compile the program rooted at the file `entry` to LLVM IR
+    #[cfg(feature = "llvm")]
+    pub fn compile_file_to_llvm(&mut self, entry: &Path) -> OvieResult<String> {
+        let ir = self.compile_file_to_ir(entry)?;
+        self.generate_llvm(&ir)
+    }
+
+    #[cfg(feature = "llvm")]
+    fn generate_llvm(&mut self, ir: &IR) -> OvieResult<String> {
         let context = inkwell::context::Context::create();
         let mut llvm_backend = crate::codegen::LlvmBackend::new(&context, "ovie_module");
         if self.build_config.deterministic_output {
             llvm_backend.set_deterministic_mode(true);
         }
-        let llvm_ir = llvm_backend.generate(&ir)?;
+        let llvm_ir = llvm_backend.generate(ir)?;
         
         if self.debug {
             println!("Generated LLVM IR: {} lines", llvm_ir.lines().count());
@@ -696,6 +820,48 @@ impl Compiler {
         }
     }
 
+    /// Compile and run the program rooted at the file `entry`, with its imports
+    pub fn compile_file_and_run_with_backend(&mut self, entry: &Path, backend: Backend) -> OvieResult<()> {
+        match backend {
+            Backend::Interpreter => {
+                let graph = self.load_modules(entry)?;
+                // The interpreter walks the AST, so run the checks of `ovie check` first
+                let hir = self.lower_to_hir(&graph)?;
+                self.lower_to_mir(&hir)?;
+                let mut interpreter = Interpreter::new();
+                interpreter.set_check_contracts(self.check_contracts);
+                interpreter.interpret_modules(&graph)
+            }
+            Backend::IrInterpreter => {
+                let ir = self.compile_file_to_ir(entry)?;
+                crate::interpreter::IrInterpreter::new().execute(&ir)
+            }
+            Backend::Wasm => {
+                let _wasm_bytes = self.compile_file_to_wasm(entry)?;
+                println!("WASM compilation successful (execution not implemented)");
+                Ok(())
+            }
+            #[cfg(feature = "llvm")]
+            Backend::Llvm => {
+                let _llvm_ir = self.compile_file_to_llvm(entry)?;
+                println!("LLVM compilation successful (execution not implemented)");
+                Ok(())
+            }
+            Backend::Hir => {
+                let hir = self.compile_file_to_hir(entry)?;
+                println!("HIR compilation successful:");
+                println!("{}", hir.to_json().unwrap_or_else(|_| "Failed to serialize HIR".to_string()));
+                Ok(())
+            }
+            Backend::Mir => {
+                let mir = self.compile_file_to_mir(entry)?;
+                println!("MIR compilation successful:");
+                println!("{}", mir.to_json().unwrap_or_else(|_| "Failed to serialize MIR".to_string()));
+                Ok(())
+            }
+        }
+    }
+
     /// Compile and run using the default backend
     pub fn compile_and_run_default(&mut self, source: &str) -> OvieResult<()> {
         self.compile_and_run_with_backend(source, self.default_backend.clone())
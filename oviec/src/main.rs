@@ -272,23 +272,23 @@ fn build_project(args: CliArgs) -> OvieResult<()> {
     
     println!("Building project...");
     
-    let source = read_source_file(&main_file)?;
+    let entry = Path::new(&main_file);
     let backend = args.backend.unwrap_or(Backend::Wasm);
     let mut compiler = create_compiler(Some(backend.clone()), args.debug);
     
     match backend {
         Backend::Wasm => {
-            let _wasm_bytes = compiler.compile_to_wasm(&source)?;
+            let _wasm_bytes = compiler.compile_file_to_wasm(entry)?;
             println!("✓ Build successful (WASM)");
         }
         #[cfg(feature = "llvm")]
         Backend::Llvm => {
-            let _llvm_ir = compiler.compile_to_llvm(&source)?;
+            let _llvm_ir = compiler.compile_file_to_llvm(entry)?;
             println!("✓ Build successful (LLVM)");
         }
         _ => {
             // For other backends, just validate compilation
-            let _hir = compiler.compile_to_hir(&source)?;
+            let _hir = compiler.compile_file_to_hir(entry)?;
             println!("✓ Build successful");
         }
     }
@@ -464,31 +464,31 @@ fn compile_file(args: CliArgs) -> OvieResult<()> {
         oviec::OvieError::io_error("No input file specified".to_string())
     })?;
     
-    let source = read_source_file(&input_file)?;
+    let entry = Path::new(&input_file);
     let backend = args.backend.unwrap_or(Backend::Interpreter);
     let mut compiler = create_compiler(Some(backend.clone()), args.debug);
     
     // Compile to specified backend or default
     match backend {
         Backend::Wasm => {
-            let _wasm_bytes = compiler.compile_to_wasm(&source)?;
+            let _wasm_bytes = compiler.compile_file_to_wasm(entry)?;
             println!("WASM compilation successful");
         }
         #[cfg(feature = "llvm")]
         Backend::Llvm => {
-            let _llvm_ir = compiler.compile_to_llvm(&source)?;
+            let _llvm_ir = compiler.compile_file_to_llvm(entry)?;
             println!("LLVM compilation successful");
         }
         Backend::Hir => {
-            let _hir = compiler.compile_to_hir(&source)?;
+            let _hir = compiler.compile_file_to_hir(entry)?;
             println!("HIR compilation successful");
         }
         Backend::Mir => {
-            let _mir = compiler.compile_to_mir(&source)?;
+            let _mir = compiler.compile_file_to_mir(entry)?;
             println!("MIR compilation successful");
         }
         _ => {
-            compiler.compile_and_run_with_backend(&source, backend)?;
+            compiler.compile_file_and_run_with_backend(entry, backend)?;
         }
     }
     
@@ -501,10 +501,9 @@ fn run_file(args: CliArgs) -> OvieResult<()> {
         oviec::OvieError::io_error("No input file specified".to_string())
     })?;
     
-    let source = read_source_file(&input_file)?;
     let mut compiler = create_compiler(args.backend, args.debug);
     
-    compiler.compile_and_run(&source)?;
+    compiler.compile_file_and_run_with_backend(Path::new(&input_file), Backend::Interpreter)?;
     Ok(())
 }
 
@@ -528,10 +527,9 @@ fn dump_hir(args: CliArgs) -> OvieResult<()> {
         oviec::OvieError::io_error("No input file specified".to_string())
     })?;
     
-    let source = read_source_file(&input_file)?;
     let mut compiler = create_compiler(None, args.debug);
     
-    let hir = compiler.compile_to_hir(&source)?;
+    let hir = compiler.compile_file_to_hir(Path::new(&input_file))?;
     let output = format_hir_output(&hir, &args.format)?;
     
     write_output(output, args.output_file)?;
@@ -543,10 +541,9 @@ fn dump_mir(args: CliArgs) -> OvieResult<()> {
         oviec::OvieError::io_error("No input file specified".to_string())
     })?;
     
-    let source = read_source_file(&input_file)?;
     let mut compiler = create_compiler(None, args.debug);
     
-    let mir = compiler.compile_to_mir(&source)?;
+    let mir = compiler.compile_file_to_mir(Path::new(&input_file))?;
     let output = format_mir_output(&mir, &args.format)?;
     
     write_output(output, args.output_file)?;
@@ -631,8 +628,8 @@ fn analyze_cfg(args: CliArgs) -> OvieResult<()> {
             if !func_analysis.loops.is_empty() {
                 report.push_str("  Loops:\n");
                 for (i, loop_info) in func_analysis.loops.iter().enumerate() {
-                    report.push_str(&format!("    Loop {}: Header={}, Back Edge Source={}\n", 
-                                           i, loop_info.header, loop_info.back_edge_source));
+                    report.push_str(&format!("    Loop {}: Header={}, Back Edge Sources={:?}, Body={:?}, Exits={:?}\n",
+                                           i, loop_info.header, loop_info.back_edge_sources, loop_info.body, loop_info.exits));
                 }
             }
             
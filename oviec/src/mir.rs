@@ -3,10 +3,11 @@
 //! MIR is the second IR stage after HIR, where control flow is made explicit
 //! and the representation is suitable for optimization and code generation.
 
-use crate::hir::{HirProgram, HirItem, HirFunction, HirStatement, HirStatementKind, HirExpression, HirExpressionKind, HirType, HirBinaryOp, HirUnaryOp, HirLiteral};
+use crate::hir::{HirProgram, HirItem, HirFunction, HirStatement, HirStatementKind, HirExpression, HirExpressionKind, HirPlaceKind, HirType, HirBinaryOp, HirUnaryOp, HirLiteral, HirMatchArm, HirPattern, HirFieldInit, HirContract, SourceSpan, TypeTable};
 use crate::error::{OvieError, OvieResult};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::dataflow::{DataflowAnalysis, FunctionDataflow};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 /// MIR invariant validation trait
 pub trait MirInvariantValidation {
@@ -43,6 +44,7 @@ pub struct MirFunction {
     pub locals: Vec<MirLocal>,
     pub entry_block: BasicBlockId,
     pub is_main: bool,
+    pub span: SourceSpan,
 }
 
 /// Function signature in MIR
@@ -58,12 +60,15 @@ pub struct MirBasicBlock {
     pub id: BasicBlockId,
     pub statements: Vec<MirStatement>,
     pub terminator: MirTerminator,
+    /// Source of the terminator (the return, branch condition or call)
+    pub terminator_span: SourceSpan,
 }
 
 /// MIR Statement - operations within a basic block
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MirStatement {
     pub kind: MirStatementKind,
+    pub span: SourceSpan,
 }
 
 /// MIR Statement kinds
@@ -107,6 +112,8 @@ pub enum MirTerminator {
     
     /// Function call
     Call {
+        /// Name of the function called, or a function value (closure record),
+        /// which is passed to its function as an implicit first argument
         func: MirOperand,
         args: Vec<MirOperand>,
         destination: MirPlace,
@@ -114,6 +121,14 @@ pub enum MirTerminator {
         cleanup: Option<BasicBlockId>,
     },
     
+    /// Stop the program with an assertion error carrying `message` unless `condition`
+    /// is true, then continue at `target`
+    Assert {
+        condition: MirOperand,
+        message: String,
+        target: BasicBlockId,
+    },
+
     /// Unreachable code
     Unreachable,
     
@@ -125,15 +140,57 @@ pub enum MirTerminator {
     },
 }
 
+impl MirTerminator {
+    /// Blocks control may transfer to from this terminator
+    pub fn successors(&self) -> Vec<BasicBlockId> {
+        match self {
+            MirTerminator::Return { .. } | MirTerminator::Unreachable => Vec::new(),
+            MirTerminator::Goto { target } | MirTerminator::Assert { target, .. } => vec![*target],
+            MirTerminator::SwitchInt { targets, otherwise, .. } => {
+                let mut successors: Vec<_> = targets.iter().map(|(_, target)| *target).collect();
+                successors.push(*otherwise);
+                successors
+            }
+            MirTerminator::Call { target, cleanup, .. } => {
+                let mut successors = Vec::new();
+                if let Some(t) = target {
+                    successors.push(*t);
+                }
+                if let Some(c) = cleanup {
+                    successors.push(*c);
+                }
+                successors
+            }
+            MirTerminator::Drop { target, unwind, .. } => {
+                let mut successors = vec![*target];
+                if let Some(u) = unwind {
+                    successors.push(*u);
+                }
+                successors
+            }
+        }
+    }
+}
+
 /// MIR Place - memory location that can be assigned to
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MirPlace {
     pub local: LocalId,
     pub projection: Vec<MirProjectionElem>,
 }
 
+impl MirPlace {
+    /// Place naming a whole local with no projections
+    pub fn from_local(local: LocalId) -> Self {
+        Self {
+            local,
+            projection: Vec::new(),
+        }
+    }
+}
+
 /// MIR Projection element for complex places
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MirProjectionElem {
     /// Dereference: *place
     Deref,
@@ -146,6 +203,9 @@ pub enum MirProjectionElem {
     
     /// Subslice: place[from..to]
     Subslice { from: u32, to: u32 },
+
+    /// Enum variant view: (place as variant), followed by a Field of its payload
+    Downcast(u32),
 }
 
 /// MIR Right-value - computations that produce values
@@ -206,8 +266,8 @@ pub enum MirOperand {
     /// Copy a place
     Copy(MirPlace),
     
-    /// Move from a place
-    Move(MirPlace),
+    /// Move from a place, with the source of the operand the value is moved out by
+    Move(MirPlace, SourceSpan),
     
     /// Constant value
     Constant(MirConstant),
@@ -221,12 +281,15 @@ pub struct MirConstant {
 }
 
 /// MIR Constant value literals
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MirConstantValue {
     String(String),
     Number(f64),
+    Integer(i64),
     Boolean(bool),
     Unit,
+    /// Reference to a function, stored in the first field of a closure record
+    Function(String),
 }
 
 /// MIR Local variable
@@ -236,6 +299,8 @@ pub struct MirLocal {
     pub ty: MirType,
     pub is_mutable: bool,
     pub name: Option<String>,
+    /// Where the local was declared (the enclosing expression for temporaries)
+    pub span: SourceSpan,
 }
 
 /// MIR Global variable
@@ -247,12 +312,21 @@ pub struct MirGlobal {
     pub initializer: Option<MirConstant>,
 }
 
+/// ADT name of a range of integers, with the element type as its one substitution.
+/// `#` cannot start an identifier, so no user-defined type has this name.
+pub const RANGE_TYPE: &str = "#Range";
+
+/// ADT name of an array, with the element type as its one substitution
+pub const ARRAY_TYPE: &str = "#Array";
+
 /// MIR Type system (simplified from HIR)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MirType {
     /// Primitive types
     String,
     Number,
+    /// 64-bit signed integer
+    Int,
     Boolean,
     Unit,
     
@@ -269,7 +343,8 @@ pub enum MirType {
         substs: Vec<MirType>,
     },
     
-    /// Function pointer
+    /// Function value: a closure record whose first field refers to a function
+    /// taking the record itself before `params`, followed by the captured values
     FnPtr {
         params: Vec<MirType>,
         return_type: Box<MirType>,
@@ -403,8 +478,12 @@ pub struct FunctionCfgAnalysis {
 #[derive(Debug, Clone)]
 pub struct LoopInfo {
     pub header: BasicBlockId,
-    pub back_edge_source: BasicBlockId,
+    /// Blocks jumping back to the header: the end of the body and every `continue`
+    pub back_edge_sources: Vec<BasicBlockId>,
+    /// Blocks of the loop, header included, in ascending order
     pub body: Vec<BasicBlockId>,
+    /// Edges leaving the loop as (block inside, block outside): the failed condition and every `break`
+    pub exits: Vec<(BasicBlockId, BasicBlockId)>,
 }
 
 impl CfgAnalysis {
@@ -434,6 +513,74 @@ pub struct MirBuilder {
     current_function: Option<FunctionId>,
     current_block: Option<BasicBlockId>,
     local_map: HashMap<String, LocalId>,
+    /// Statements of the block currently being built
+    current_statements: Vec<MirStatement>,
+    /// Finished blocks of the function currently being built
+    basic_blocks: HashMap<BasicBlockId, MirBasicBlock>,
+    /// Locals of the function currently being built
+    locals: Vec<MirLocal>,
+    /// Signatures of all user functions, used to type call destinations
+    function_signatures: HashMap<String, MirFunctionSignature>,
+    /// Functions whose first parameter is `self`, whose calls read the receiver without moving it
+    methods: HashSet<String>,
+    /// Struct and enum layouts, used to resolve field and variant indices
+    type_definitions: HashMap<String, MirTypeDef>,
+    /// Span of the HIR node being lowered, attached to emitted statements
+    current_span: SourceSpan,
+    /// Generic structs and enums, laid out once per list of type arguments they are used with
+    generic_types: HashMap<String, HirItem>,
+    /// Generic functions, lowered once per list of type arguments they are called with
+    generic_functions: HashMap<String, HirFunction>,
+    /// Function instances referenced but not lowered yet: (instance name, generic function, type arguments)
+    pending_instances: Vec<(String, String, Vec<MirType>)>,
+    /// Concrete types of the type parameters of the instance being lowered
+    type_arguments: HashMap<String, MirType>,
+    /// Name of the function being built, which closures defined in it are named after
+    current_function_name: String,
+    /// Number of closures lifted so far, keeping their names unique
+    next_closure_id: u32,
+    /// Closure bodies referenced but not lowered yet
+    pending_closures: Vec<PendingClosure>,
+    /// Functions used as values whose wrapper taking a closure record is not lowered yet
+    pending_shims: Vec<String>,
+    /// Loops around the code being lowered, innermost last
+    loops: Vec<LoopTargets>,
+    /// Values of the program's constants, used in place of the constant wherever it is named
+    constants: HashMap<String, MirConstant>,
+    /// Return type of the function being built, which `?` rebuilds the value it returns as
+    return_type: MirType,
+    /// Traits and their impls, which calls through a trait bound are resolved against
+    type_table: TypeTable,
+    /// Generic struct and enum instances, by name: the generic type and its type arguments
+    type_instances: HashMap<String, (String, Vec<MirType>)>,
+    /// Whether `requires`, `ensures` and `invariant` clauses are checked at run time, as in debug builds
+    check_contracts: bool,
+    /// Invariants of each struct that has any, by struct name
+    struct_invariants: HashMap<String, Vec<HirContract>>,
+    /// `ensures` clauses of the function being built, checked at each return
+    ensures: Vec<HirContract>,
+    /// Locals of the parameters of the function being built, the names its contract clauses see
+    parameter_locals: HashMap<String, LocalId>,
+}
+
+/// Where `break` and `continue` jump to in a loop being lowered
+struct LoopTargets {
+    label: Option<String>,
+    /// Start of the next iteration: the condition of a while loop, the increment of a for loop
+    continue_block: BasicBlockId,
+    /// Block just after the loop
+    break_block: BasicBlockId,
+    /// Variables of a for loop, whose storage ends when the loop is left
+    variables: Vec<LocalId>,
+}
+
+/// Closure body waiting to be lowered as a function of its own
+struct PendingClosure {
+    function: HirFunction,
+    /// Captured variables, in the order of the closure record fields after the function
+    captures: Vec<(String, MirType)>,
+    /// Type arguments of the instance the closure was defined in
+    type_arguments: HashMap<String, MirType>,
 }
 
 impl MirBuilder {
@@ -446,21 +593,106 @@ impl MirBuilder {
             current_function: None,
             current_block: None,
             local_map: HashMap::new(),
+            current_statements: Vec::new(),
+            basic_blocks: HashMap::new(),
+            locals: Vec::new(),
+            function_signatures: HashMap::new(),
+            methods: HashSet::new(),
+            type_definitions: HashMap::new(),
+            current_span: SourceSpan::default(),
+            generic_types: HashMap::new(),
+            generic_functions: HashMap::new(),
+            pending_instances: Vec::new(),
+            type_arguments: HashMap::new(),
+            current_function_name: String::new(),
+            next_closure_id: 0,
+            pending_closures: Vec::new(),
+            pending_shims: Vec::new(),
+            loops: Vec::new(),
+            constants: HashMap::new(),
+            return_type: MirType::Unit,
+            type_table: TypeTable::new(),
+            type_instances: HashMap::new(),
+            check_contracts: true,
+            struct_invariants: HashMap::new(),
+            ensures: Vec::new(),
+            parameter_locals: HashMap::new(),
         }
     }
 
+    /// Check or leave out contract clauses at run time; release builds leave them out
+    pub fn set_check_contracts(&mut self, enabled: bool) {
+        self.check_contracts = enabled;
+    }
+
     /// Transform HIR program to MIR
     pub fn transform_hir(&mut self, hir: &HirProgram) -> OvieResult<MirProgram> {
         let mut functions = HashMap::new();
         let mut globals = HashMap::new();
-        let mut type_definitions = HashMap::new();
         let mut entry_point = None;
+        self.type_table = hir.type_table.clone();
+
+        // Generic items are only lowered once their type arguments are known
+        for item in &hir.items {
+            if let HirItem::Struct(hir_struct) = item {
+                if self.check_contracts && !hir_struct.invariants.is_empty() {
+                    self.struct_invariants.insert(hir_struct.name.clone(), hir_struct.invariants.clone());
+                }
+            }
+            if let HirItem::Function(hir_func) = item {
+                if hir_func.parameters.first().is_some_and(|parameter| parameter.name == "self") {
+                    self.methods.insert(hir_func.name.clone());
+                }
+            }
+            match item {
+                HirItem::Struct(hir_struct) if !hir_struct.type_parameters.is_empty() => {
+                    self.generic_types.insert(hir_struct.name.clone(), item.clone());
+                }
+                HirItem::Enum(hir_enum) if !hir_enum.type_parameters.is_empty() => {
+                    self.generic_types.insert(hir_enum.name.clone(), item.clone());
+                }
+                HirItem::Function(hir_func) if !hir_func.type_parameters.is_empty() => {
+                    self.generic_functions.insert(hir_func.name.clone(), hir_func.clone());
+                }
+                _ => {}
+            }
+        }
+
+        // Collect type layouts and signatures first so that bodies can refer to
+        // items declared later in the file
+        for item in &hir.items {
+            match item {
+                HirItem::Struct(hir_struct) if !hir_struct.type_parameters.is_empty() => {}
+                HirItem::Enum(hir_enum) if !hir_enum.type_parameters.is_empty() => {}
+                HirItem::Function(hir_func) if !hir_func.type_parameters.is_empty() => {}
+                HirItem::Struct(hir_struct) => {
+                    let mir_typedef = self.transform_struct(hir_struct)?;
+                    self.type_definitions.insert(hir_struct.name.clone(), mir_typedef);
+                }
+                HirItem::Enum(hir_enum) => {
+                    let mir_typedef = self.transform_enum(hir_enum)?;
+                    self.type_definitions.insert(hir_enum.name.clone(), mir_typedef);
+                }
+                HirItem::Function(hir_func) => {
+                    let signature = self.transform_signature(hir_func)?;
+                    self.function_signatures.insert(hir_func.name.clone(), signature);
+                }
+                HirItem::Global(hir_global) if hir_global.is_const => {
+                    if let Some(HirExpression { kind: HirExpressionKind::Literal(lit), expr_type, .. }) = &hir_global.initializer {
+                        let constant = self.transform_literal(lit, expr_type)?;
+                        self.constants.insert(hir_global.name.clone(), constant);
+                    }
+                }
+                HirItem::Global(_) => {}
+            }
+        }
 
         // Transform all items
         for item in &hir.items {
             match item {
+                HirItem::Function(hir_func) if !hir_func.type_parameters.is_empty() => {}
                 HirItem::Function(hir_func) => {
-                    let mir_func = self.transform_function(hir_func)?;
+                    let mir_func = self.transform_function(hir_func, None)?;
                     if mir_func.is_main {
                         entry_point = Some(mir_func.id);
                     }
@@ -470,17 +702,31 @@ impl MirBuilder {
                     let mir_global = self.transform_global(hir_global)?;
                     globals.insert(mir_global.name.clone(), mir_global);
                 }
-                HirItem::Struct(hir_struct) => {
-                    let mir_typedef = self.transform_struct(hir_struct)?;
-                    type_definitions.insert(hir_struct.name.clone(), mir_typedef);
-                }
-                HirItem::Enum(hir_enum) => {
-                    let mir_typedef = self.transform_enum(hir_enum)?;
-                    type_definitions.insert(hir_enum.name.clone(), mir_typedef);
-                }
+                HirItem::Struct(_) | HirItem::Enum(_) => {}
             }
         }
 
+        // Lower each generic function instance, closure body and function value wrapper
+        // reached from the code above
+        loop {
+            let mir_func = if let Some((instance, function, type_arguments)) = self.pending_instances.pop() {
+                let mut hir_func = self.generic_functions[&function].clone();
+                hir_func.name = instance;
+                self.type_arguments = hir_func.type_parameters.iter().cloned().zip(type_arguments).collect();
+                self.transform_function(&hir_func, None)
+            } else if let Some(closure) = self.pending_closures.pop() {
+                self.type_arguments = closure.type_arguments;
+                self.transform_function(&closure.function, Some(&closure.captures))
+            } else if let Some(function) = self.pending_shims.pop() {
+                self.transform_shim(&function)
+            } else {
+                break;
+            };
+            self.type_arguments.clear();
+            let mir_func = mir_func?;
+            functions.insert(mir_func.id, mir_func);
+        }
+
         let metadata = MirMetadata {
             source_file: hir.metadata.source_file.clone(),
             compiler_version: hir.metadata.compiler_version.clone(),
@@ -491,344 +737,1257 @@ impl MirBuilder {
         Ok(MirProgram {
             functions,
             globals,
-            type_definitions,
+            type_definitions: self.type_definitions.clone(),
             metadata,
             entry_point,
         })
     }
 
-    /// Transform HIR function to MIR
-    fn transform_function(&mut self, hir_func: &HirFunction) -> OvieResult<MirFunction> {
+    /// Transform HIR function signature to MIR
+    fn transform_signature(&mut self, hir_func: &HirFunction) -> OvieResult<MirFunctionSignature> {
+        Ok(MirFunctionSignature {
+            parameters: hir_func.parameters.iter()
+                .map(|p| self.transform_type(&p.param_type))
+                .collect::<Result<Vec<_>, _>>()?,
+            return_type: self.transform_type(&hir_func.return_type)?,
+        })
+    }
+
+    /// Reset the per-function state to start building function `name`, returning its id
+    fn start_function(&mut self, name: &str, span: SourceSpan) -> FunctionId {
         let function_id = self.next_function_id;
         self.next_function_id += 1;
         self.current_function = Some(function_id);
+        self.current_function_name = name.to_string();
 
         // Reset local state for new function
         self.next_local_id = 0;
+        self.next_block_id = 0;
         self.local_map.clear();
+        self.locals.clear();
+        self.basic_blocks.clear();
+        self.current_statements.clear();
+        self.loops.clear();
+        self.ensures.clear();
+        self.parameter_locals.clear();
+        self.current_span = span;
+
+        function_id
+    }
+
+    /// Transform HIR function to MIR.
+    ///
+    /// A lifted closure body takes its closure record first and reads the variables
+    /// it `captures` out of the record's fields.
+    fn transform_function(&mut self, hir_func: &HirFunction, captures: Option<&[(String, MirType)]>) -> OvieResult<MirFunction> {
+        let function_id = self.start_function(&hir_func.name, hir_func.span);
+
+        let mut signature = self.transform_signature(hir_func)?;
+        self.return_type = signature.return_type.clone();
+        let environment = captures.map(|_| {
+            let ty = MirType::Adt { name: hir_func.name.clone(), substs: Vec::new() };
+            signature.parameters.insert(0, ty.clone());
+            self.push_local(ty, false, Some("env".to_string()))
+        });
 
         // Create locals for parameters
-        let mut locals = Vec::new();
         for param in &hir_func.parameters {
-            let local_id = self.next_local_id;
-            self.next_local_id += 1;
-            
-            locals.push(MirLocal {
-                id: local_id,
-                ty: self.transform_type(&param.param_type)?,
-                is_mutable: false,
-                name: Some(param.name.clone()),
-            });
-            
+            let ty = self.transform_type(&param.param_type)?;
+            let local_id = self.push_local(ty, false, Some(param.name.clone()));
             self.local_map.insert(param.name.clone(), local_id);
         }
 
         // Transform function body with proper control flow graph construction
-        let mut basic_blocks = HashMap::new();
-        let entry_block = self.build_cfg(&hir_func.body, &mut basic_blocks, &mut locals)?;
+        let entry_block = self.new_block();
+        self.switch_to_block(entry_block);
 
-        let signature = MirFunctionSignature {
-            parameters: hir_func.parameters.iter()
-                .map(|p| self.transform_type(&p.param_type))
-                .collect::<Result<Vec<_>, _>>()?,
-            return_type: self.transform_type(&hir_func.return_type)?,
-        };
+        if let (Some(environment), Some(captures)) = (environment, captures) {
+            for (index, (name, ty)) in captures.iter().enumerate() {
+                let local_id = self.push_local(ty.clone(), false, Some(name.clone()));
+                self.local_map.insert(name.clone(), local_id);
+                let mut field = MirPlace::from_local(environment);
+                field.projection.push(MirProjectionElem::Field(index as u32 + 1));
+                self.push_assign(MirPlace::from_local(local_id), MirRvalue::Use(MirOperand::Copy(field)));
+            }
+        }
+
+        if self.check_contracts {
+            self.parameter_locals = self.local_map.clone();
+            self.ensures = hir_func.ensures.clone();
+            for clause in &hir_func.requires {
+                self.check_contract(clause, &format!("precondition of '{}'", hir_func.name), self.parameter_locals.clone())?;
+            }
+        }
+
+        match hir_func.body.tail() {
+            // The final expression of a body that returns a value is moved into the return place
+            Some(tail) if self.return_type != MirType::Unit => {
+                let statements = &hir_func.body.statements;
+                for hir_stmt in &statements[..statements.len() - 1] {
+                    self.transform_statement(hir_stmt)?;
+                }
+                self.current_span = tail.span;
+                let value = self.transform_expression_to_moved_operand(tail)
+                    .map_err(|e| e.located_at(tail.span.start_position()))?;
+                self.emit_return(Some(value))?;
+            }
+            _ => {
+                self.build_cfg(&hir_func.body)?;
+
+                // Falling off the end of the body returns unit
+                self.current_span = hir_func.span;
+                self.emit_return(None)?;
+            }
+        }
+
+        let basic_blocks = self.take_reachable_blocks(entry_block);
 
         Ok(MirFunction {
             id: function_id,
             name: hir_func.name.clone(),
             signature,
             basic_blocks,
-            locals,
+            locals: std::mem::take(&mut self.locals),
             entry_block,
             is_main: hir_func.is_main,
+            span: hir_func.span,
         })
     }
 
-    /// Build Control Flow Graph from HIR block
-    fn build_cfg(
-        &mut self,
-        hir_block: &crate::hir::HirBlock,
-        basic_blocks: &mut HashMap<BasicBlockId, MirBasicBlock>,
-        locals: &mut Vec<MirLocal>
-    ) -> OvieResult<BasicBlockId> {
-        let entry_block = self.next_block_id;
-        self.next_block_id += 1;
-        self.current_block = Some(entry_block);
+    /// Lower the wrapper that lets `function` be called as a function value: it takes
+    /// a closure record without captures before the parameters of `function` and calls it
+    fn transform_shim(&mut self, function: &str) -> OvieResult<MirFunction> {
+        let shim = Self::shim_name(function);
+        let signature = self.function_signatures[function].clone();
+        let function_id = self.start_function(&shim, SourceSpan::default());
+
+        let environment = MirType::Adt { name: shim.clone(), substs: Vec::new() };
+        self.push_local(environment.clone(), false, Some("env".to_string()));
+        let mut args = Vec::new();
+        for (index, ty) in signature.parameters.iter().enumerate() {
+            let local_id = self.push_local(ty.clone(), false, Some(format!("arg{}", index)));
+            args.push(MirOperand::Copy(MirPlace::from_local(local_id)));
+        }
 
-        let mut current_statements = Vec::new();
-        let mut current_block_id = entry_block;
+        let entry_block = self.new_block();
+        self.switch_to_block(entry_block);
+        let result = self.emit_call(function, args, signature.parameters.clone(), signature.return_type.clone());
+        self.terminate_block(MirTerminator::Return { value: Some(MirOperand::Copy(result)) });
 
-        for hir_stmt in &hir_block.statements {
-            match &hir_stmt.kind {
-                HirStatementKind::If { condition, then_block, else_block } => {
-                    // Finish current block with conditional branch
-                    let condition_operand = self.transform_expression_to_operand(condition)?;
-                    
-                    // Create blocks for then and else branches
-                    let then_block_id = self.next_block_id;
-                    self.next_block_id += 1;
-                    let else_block_id = self.next_block_id;
-                    self.next_block_id += 1;
-                    let merge_block_id = self.next_block_id;
-                    self.next_block_id += 1;
-
-                    // Create switch terminator for current block
-                    let terminator = MirTerminator::SwitchInt {
-                        discriminant: condition_operand,
-                        targets: vec![(1, then_block_id)], // true -> then block
-                        otherwise: else_block_id, // false -> else block
-                    };
-
-                    basic_blocks.insert(current_block_id, MirBasicBlock {
-                        id: current_block_id,
-                        statements: current_statements,
-                        terminator,
-                    });
+        let mut parameters = signature.parameters;
+        parameters.insert(0, environment);
+        let basic_blocks = self.take_reachable_blocks(entry_block);
 
-                    // Build then block
-                    self.build_cfg(then_block, basic_blocks, locals)?;
-                    basic_blocks.insert(then_block_id, MirBasicBlock {
-                        id: then_block_id,
-                        statements: Vec::new(), // Simplified for now
-                        terminator: MirTerminator::Goto { target: merge_block_id },
-                    });
+        Ok(MirFunction {
+            id: function_id,
+            name: shim,
+            signature: MirFunctionSignature {
+                parameters,
+                return_type: signature.return_type,
+            },
+            basic_blocks,
+            locals: std::mem::take(&mut self.locals),
+            entry_block,
+            is_main: false,
+            span: SourceSpan::default(),
+        })
+    }
 
-                    // Build else block
-                    if let Some(else_hir_block) = else_block {
-                        self.build_cfg(else_hir_block, basic_blocks, locals)?;
-                    }
-                    basic_blocks.insert(else_block_id, MirBasicBlock {
-                        id: else_block_id,
-                        statements: Vec::new(), // Simplified for now
-                        terminator: MirTerminator::Goto { target: merge_block_id },
-                    });
+    /// Name of the wrapper calling `function` as a function value
+    fn shim_name(function: &str) -> String {
+        format!("{}::{{fn}}", function)
+    }
 
-                    // Continue with merge block
-                    current_block_id = merge_block_id;
-                    current_statements = Vec::new();
-                }
-                HirStatementKind::While { condition, body } => {
-                    // Create loop header, body, and exit blocks
-                    let loop_header_id = self.next_block_id;
-                    self.next_block_id += 1;
-                    let loop_body_id = self.next_block_id;
-                    self.next_block_id += 1;
-                    let loop_exit_id = self.next_block_id;
-                    self.next_block_id += 1;
-
-                    // Finish current block with goto to loop header
-                    basic_blocks.insert(current_block_id, MirBasicBlock {
-                        id: current_block_id,
-                        statements: current_statements,
-                        terminator: MirTerminator::Goto { target: loop_header_id },
-                    });
+    /// Closure record for function item `function` used as a value, queueing its wrapper
+    fn function_value(&mut self, function: &str) -> OvieResult<MirRvalue> {
+        let signature = match self.function_signatures.get(function) {
+            Some(signature) => signature.clone(),
+            None => {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("Built-in function '{}' cannot be used as a value", function)
+                ));
+            }
+        };
+        let function_type = MirType::FnPtr {
+            params: signature.parameters,
+            return_type: Box::new(signature.return_type),
+        };
 
-                    // Create loop header with condition check
-                    let condition_operand = self.transform_expression_to_operand(condition)?;
-                    basic_blocks.insert(loop_header_id, MirBasicBlock {
-                        id: loop_header_id,
-                        statements: Vec::new(),
-                        terminator: MirTerminator::SwitchInt {
-                            discriminant: condition_operand,
-                            targets: vec![(1, loop_body_id)], // true -> body
-                            otherwise: loop_exit_id, // false -> exit
-                        },
-                    });
+        let shim = Self::shim_name(function);
+        if !self.type_definitions.contains_key(&shim) {
+            self.type_definitions.insert(shim.clone(), MirTypeDef::Struct {
+                fields: vec![MirFieldDef { name: "function".to_string(), ty: function_type.clone() }],
+            });
+            self.pending_shims.push(function.to_string());
+        }
 
-                    // Build loop body
-                    self.build_cfg(body, basic_blocks, locals)?;
-                    basic_blocks.insert(loop_body_id, MirBasicBlock {
-                        id: loop_body_id,
-                        statements: Vec::new(), // Simplified for now
-                        terminator: MirTerminator::Goto { target: loop_header_id },
-                    });
+        Ok(MirRvalue::Aggregate {
+            kind: MirAggregateKind::Adt { name: shim.clone(), variant: None },
+            operands: vec![MirOperand::Constant(MirConstant {
+                literal: MirConstantValue::Function(shim),
+                ty: function_type,
+            })],
+        })
+    }
 
-                    // Continue with exit block
-                    current_block_id = loop_exit_id;
-                    current_statements = Vec::new();
-                }
-                HirStatementKind::Return(value) => {
-                    // Return terminates the current block
-                    let return_operand = if let Some(expr) = value {
-                        Some(self.transform_expression_to_operand(expr)?)
-                    } else {
-                        None
-                    };
-
-                    basic_blocks.insert(current_block_id, MirBasicBlock {
-                        id: current_block_id,
-                        statements: current_statements,
-                        terminator: MirTerminator::Return { value: return_operand },
-                    });
+    /// Closure record holding the lifted closure body and the values it captures,
+    /// queueing the body to be lowered as a function of its own
+    fn transform_closure(&mut self, expr: &HirExpression, parameters: &[crate::hir::HirParameter], return_type: &HirType, body: &crate::hir::HirBlock, captures: &[String]) -> OvieResult<MirRvalue> {
+        let name = format!("{}::{{closure#{}}}", self.current_function_name, self.next_closure_id);
+        self.next_closure_id += 1;
+
+        let function_type = self.transform_type(&expr.expr_type)?;
+        let mut fields = vec![MirFieldDef { name: "function".to_string(), ty: function_type.clone() }];
+        let mut operands = vec![MirOperand::Constant(MirConstant {
+            literal: MirConstantValue::Function(name.clone()),
+            ty: function_type,
+        })];
+        let mut capture_types = Vec::new();
+        for capture in captures {
+            let local_id = self.lookup_local(capture)?;
+            let ty = self.locals[local_id as usize].ty.clone();
+            fields.push(MirFieldDef { name: capture.clone(), ty: ty.clone() });
+            operands.push(MirOperand::Copy(MirPlace::from_local(local_id)));
+            capture_types.push((capture.clone(), ty));
+        }
+        self.type_definitions.insert(name.clone(), MirTypeDef::Struct { fields });
 
-                    // Create unreachable block for any remaining statements
-                    current_block_id = self.next_block_id;
-                    self.next_block_id += 1;
-                    current_statements = Vec::new();
-                }
-                _ => {
-                    // Regular statements
-                    self.transform_statement(hir_stmt, &mut current_statements, locals)?;
-                }
-            }
+        self.pending_closures.push(PendingClosure {
+            function: HirFunction {
+                id: expr.id,
+                name: name.clone(),
+                type_parameters: Vec::new(),
+                parameters: parameters.to_vec(),
+                return_type: return_type.clone(),
+                body: body.clone(),
+                span: expr.span,
+                is_main: false,
+                is_const: false,
+                is_unsafe: false,
+                requires: Vec::new(),
+                ensures: Vec::new(),
+            },
+            captures: capture_types,
+            type_arguments: self.type_arguments.clone(),
+        });
+
+        Ok(MirRvalue::Aggregate {
+            kind: MirAggregateKind::Adt { name, variant: None },
+            operands,
+        })
+    }
+
+    /// Build Control Flow Graph from HIR block into the current function
+    fn build_cfg(&mut self, hir_block: &crate::hir::HirBlock) -> OvieResult<()> {
+        for hir_stmt in &hir_block.statements {
+            self.transform_statement(hir_stmt)?;
         }
+        Ok(())
+    }
+
+    /// Allocate a new basic block id
+    fn new_block(&mut self) -> BasicBlockId {
+        let block_id = self.next_block_id;
+        self.next_block_id += 1;
+        block_id
+    }
+
+    /// Continue emitting statements into `block_id`
+    fn switch_to_block(&mut self, block_id: BasicBlockId) {
+        self.current_block = Some(block_id);
+        self.current_statements.clear();
+    }
 
-        // Finish the final block
-        if !basic_blocks.contains_key(&current_block_id) {
-            let terminator = MirTerminator::Return { value: None };
-            basic_blocks.insert(current_block_id, MirBasicBlock {
-                id: current_block_id,
-                statements: current_statements,
+    /// Finish the current block with `terminator`.
+    ///
+    /// Statements emitted afterwards (dead code after a `return`) land in a fresh
+    /// block that nothing jumps to; it is pruned when the function is finished.
+    fn terminate_block(&mut self, terminator: MirTerminator) {
+        if let Some(block_id) = self.current_block.take() {
+            self.basic_blocks.insert(block_id, MirBasicBlock {
+                id: block_id,
+                statements: std::mem::take(&mut self.current_statements),
                 terminator,
+                terminator_span: self.current_span,
             });
         }
+    }
 
-        Ok(entry_block)
+    /// Append a statement to the current block
+    fn push_statement(&mut self, kind: MirStatementKind) {
+        if self.current_block.is_none() {
+            let dead_block = self.new_block();
+            self.switch_to_block(dead_block);
+        }
+        self.current_statements.push(MirStatement { kind, span: self.current_span });
     }
 
-    /// Transform HIR statement to MIR
-    fn transform_statement(
-        &mut self, 
-        hir_stmt: &HirStatement, 
-        statements: &mut Vec<MirStatement>,
-        locals: &mut Vec<MirLocal>
-    ) -> OvieResult<()> {
+    /// Append `place = rvalue` to the current block
+    fn push_assign(&mut self, place: MirPlace, rvalue: MirRvalue) {
+        self.push_statement(MirStatementKind::Assign { place, rvalue });
+    }
+
+    /// Declare a new local in the current function
+    fn push_local(&mut self, ty: MirType, is_mutable: bool, name: Option<String>) -> LocalId {
+        let local_id = self.next_local_id;
+        self.next_local_id += 1;
+
+        self.locals.push(MirLocal {
+            id: local_id,
+            ty,
+            is_mutable,
+            name,
+            span: self.current_span,
+        });
+
+        local_id
+    }
+
+    /// Declare a compiler temporary and mark its storage live
+    fn new_temp(&mut self, ty: MirType) -> MirPlace {
+        let local_id = self.push_local(ty, true, None);
+        self.locals[local_id as usize].name = Some(format!("tmp{}", local_id));
+        self.push_statement(MirStatementKind::StorageLive(local_id));
+        MirPlace::from_local(local_id)
+    }
+
+    /// End the current block returning `value`, once the `ensures` clauses of the
+    /// function hold for it
+    fn emit_return(&mut self, value: Option<MirOperand>) -> OvieResult<()> {
+        if self.ensures.is_empty() || self.current_block.is_none() {
+            self.terminate_block(MirTerminator::Return { value });
+            return Ok(());
+        }
+
+        let result = self.new_temp(self.return_type.clone());
+        let value = value.map(|value| {
+            self.push_assign(result.clone(), MirRvalue::Use(value));
+            MirOperand::Copy(result.clone())
+        });
+        let mut names = self.parameter_locals.clone();
+        names.insert("result".to_string(), result.local);
+        let subject = format!("postcondition of '{}'", self.current_function_name);
+        for clause in self.ensures.clone() {
+            self.check_contract(&clause, &subject, names.clone())?;
+        }
+        self.terminate_block(MirTerminator::Return { value });
+        Ok(())
+    }
+
+    /// Check the invariants of struct layout `layout_name` on the value at `value`
+    fn check_invariants(&mut self, layout_name: &str, value: MirPlace) -> OvieResult<()> {
+        // An instance of a generic struct is checked against the generic struct's invariants
+        let (struct_name, type_arguments) = match self.type_instances.get(layout_name) {
+            Some((generic, type_arguments)) => (generic.clone(), type_arguments.clone()),
+            None => (layout_name.to_string(), Vec::new()),
+        };
+        let Some(invariants) = self.struct_invariants.get(&struct_name).cloned() else {
+            return Ok(());
+        };
+
+        let value = if value.projection.is_empty() {
+            value
+        } else {
+            let copy = self.new_temp(MirType::Adt { name: layout_name.to_string(), substs: Vec::new() });
+            self.push_assign(copy.clone(), MirRvalue::Use(MirOperand::Copy(value)));
+            copy
+        };
+        let type_parameters = match self.generic_types.get(&struct_name) {
+            Some(HirItem::Struct(hir_struct)) => hir_struct.type_parameters.clone(),
+            _ => Vec::new(),
+        };
+        let outer = std::mem::replace(&mut self.type_arguments, type_parameters.into_iter().zip(type_arguments).collect());
+
+        let subject = format!("invariant of '{}'", struct_name);
+        let names = HashMap::from([("self".to_string(), value.local)]);
+        let checked = invariants.iter().try_for_each(|clause| self.check_contract(clause, &subject, names.clone()));
+        self.type_arguments = outer;
+        checked
+    }
+
+    /// Evaluate a contract clause with only `names` in scope and stop the program with an
+    /// assertion error naming `subject` unless it holds
+    fn check_contract(&mut self, clause: &HirContract, subject: &str, names: HashMap<String, LocalId>) -> OvieResult<()> {
+        let outer_names = std::mem::replace(&mut self.local_map, names);
+        let outer_span = std::mem::replace(&mut self.current_span, clause.condition.span);
+        let condition = self.transform_expression_to_operand(&clause.condition);
+        self.local_map = outer_names;
+
+        let target = self.new_block();
+        self.terminate_block(MirTerminator::Assert {
+            condition: condition?,
+            message: format!("{}: {}", subject, clause.text),
+            target,
+        });
+        self.switch_to_block(target);
+        self.current_span = outer_span;
+        Ok(())
+    }
+
+    /// Remove blocks that cannot be reached from the entry block
+    fn take_reachable_blocks(&mut self, entry_block: BasicBlockId) -> HashMap<BasicBlockId, MirBasicBlock> {
+        let mut blocks = std::mem::take(&mut self.basic_blocks);
+        let mut reachable = HashMap::new();
+        let mut worklist = vec![entry_block];
+
+        while let Some(block_id) = worklist.pop() {
+            if let Some(block) = blocks.remove(&block_id) {
+                worklist.extend(block.terminator.successors());
+                reachable.insert(block_id, block);
+            }
+        }
+
+        reachable
+    }
+
+    /// Transform HIR statement to MIR, attributing emitted code to the statement
+    fn transform_statement(&mut self, hir_stmt: &HirStatement) -> OvieResult<()> {
+        let outer = std::mem::replace(&mut self.current_span, hir_stmt.span);
+        let result = self.lower_statement(hir_stmt)
+            .map_err(|e| e.located_at(self.current_span.start_position()));
+        self.current_span = outer;
+        result
+    }
+
+    fn lower_statement(&mut self, hir_stmt: &HirStatement) -> OvieResult<()> {
         match &hir_stmt.kind {
             HirStatementKind::Local { name, var_type, is_mutable, initializer } => {
-                // Create local variable
-                let local_id = self.next_local_id;
-                self.next_local_id += 1;
-                
-                locals.push(MirLocal {
-                    id: local_id,
-                    ty: self.transform_type(var_type)?,
-                    is_mutable: *is_mutable,
-                    name: Some(name.clone()),
-                });
-                
+                // Evaluate the initializer before binding the name so that
+                // shadowing declarations still see the previous binding
+                let rvalue = match initializer {
+                    Some(init_expr) => Some(self.transform_expression_to_rvalue(init_expr)?),
+                    None => None,
+                };
+
+                let ty = match (var_type, initializer) {
+                    (HirType::Infer(_) | HirType::Error, Some(init_expr)) => self.expression_type(init_expr)?,
+                    _ => self.transform_type(var_type)?,
+                };
+
+                let local_id = self.push_local(ty, *is_mutable, Some(name.clone()));
                 self.local_map.insert(name.clone(), local_id);
 
                 // Storage allocation
-                statements.push(MirStatement {
-                    kind: MirStatementKind::StorageLive(local_id),
-                });
+                self.push_statement(MirStatementKind::StorageLive(local_id));
 
                 // Initialize if there's an initializer
-                if let Some(init_expr) = initializer {
-                    let rvalue = self.transform_expression_to_rvalue(init_expr)?;
-                    let place = MirPlace {
-                        local: local_id,
-                        projection: Vec::new(),
-                    };
-                    
-                    statements.push(MirStatement {
-                        kind: MirStatementKind::Assign { place, rvalue },
-                    });
+                if let Some(rvalue) = rvalue {
+                    self.push_assign(MirPlace::from_local(local_id), rvalue);
                 }
             }
             HirStatementKind::Assign { target, value } => {
-                let place = self.transform_place(target)?;
                 let rvalue = self.transform_expression_to_rvalue(value)?;
-                
-                statements.push(MirStatement {
-                    kind: MirStatementKind::Assign { place, rvalue },
-                });
+                let place = self.transform_place(target)?;
+                self.push_assign(place.clone(), rvalue);
+                self.check_assigned_field(target, place)?;
+            }
+            HirStatementKind::CompoundAssign { target, op, value } => {
+                // The place is resolved once, so its indices are evaluated once
+                let right = self.transform_expression_to_operand(value)?;
+                let place = self.transform_place(target)?;
+                let rvalue = MirRvalue::BinaryOp {
+                    op: self.transform_binary_op(op),
+                    left: MirOperand::Copy(place.clone()),
+                    right,
+                };
+                self.push_assign(place.clone(), rvalue);
+                self.check_assigned_field(target, place)?;
             }
             HirStatementKind::Expression(expr) => {
-                // For expression statements, we might need to evaluate for side effects
-                let _rvalue = self.transform_expression_to_rvalue(expr)?;
-                // For now, just add a nop
-                statements.push(MirStatement {
-                    kind: MirStatementKind::Nop,
-                });
+                // Evaluated for its side effects only
+                self.transform_expression_to_operand(expr)?;
             }
             HirStatementKind::Print(expr) => {
-                // Transform print into a function call
                 let operand = self.transform_expression_to_operand(expr)?;
-                
-                // Create a temporary local for the result
-                let temp_local = self.next_local_id;
-                self.next_local_id += 1;
-                
-                locals.push(MirLocal {
-                    id: temp_local,
-                    ty: MirType::Unit,
-                    is_mutable: false,
-                    name: None,
+                let arg_type = self.expression_type(expr)?;
+                self.emit_call("print", vec![operand], vec![arg_type], MirType::Unit);
+            }
+            HirStatementKind::Return(value) => {
+                let return_operand = match value {
+                    Some(expr) => Some(self.transform_expression_to_moved_operand(expr)?),
+                    None => None,
+                };
+                self.emit_return(return_operand)?;
+            }
+            HirStatementKind::If { condition, then_block, else_block } => {
+                let condition_operand = self.transform_expression_to_operand(condition)?;
+
+                let then_block_id = self.new_block();
+                let else_block_id = else_block.as_ref().map(|_| self.new_block());
+                let merge_block_id = self.new_block();
+
+                self.terminate_block(MirTerminator::SwitchInt {
+                    discriminant: condition_operand,
+                    targets: vec![(1, then_block_id)], // true -> then block
+                    otherwise: else_block_id.unwrap_or(merge_block_id), // false -> else block
                 });
 
-                // Storage allocation for temporary
-                statements.push(MirStatement {
-                    kind: MirStatementKind::StorageLive(temp_local),
-                });
+                self.switch_to_block(then_block_id);
+                self.build_cfg(then_block)?;
+                self.terminate_block(MirTerminator::Goto { target: merge_block_id });
+
+                if let (Some(else_hir_block), Some(else_block_id)) = (else_block, else_block_id) {
+                    self.switch_to_block(else_block_id);
+                    self.build_cfg(else_hir_block)?;
+                    self.terminate_block(MirTerminator::Goto { target: merge_block_id });
+                }
+
+                self.switch_to_block(merge_block_id);
+            }
+            HirStatementKind::While { label, condition, body } => {
+                let loop_header_id = self.new_block();
+                let loop_body_id = self.new_block();
+                let loop_exit_id = self.new_block();
+
+                self.terminate_block(MirTerminator::Goto { target: loop_header_id });
+
+                // The condition is re-evaluated in the header on every iteration
+                self.switch_to_block(loop_header_id);
+                let condition_operand = self.transform_expression_to_operand(condition)?;
+                self.terminate_block(MirTerminator::SwitchInt {
+                    discriminant: condition_operand,
+                    targets: vec![(1, loop_body_id)], // true -> body
+                    otherwise: loop_exit_id, // false -> exit
+                });
+
+                self.switch_to_block(loop_body_id);
+                self.loops.push(LoopTargets {
+                    label: label.clone(),
+                    continue_block: loop_header_id,
+                    break_block: loop_exit_id,
+                    variables: Vec::new(),
+                });
+                let built = self.build_cfg(body);
+                self.loops.pop();
+                built?;
+                self.terminate_block(MirTerminator::Goto { target: loop_header_id });
+
+                self.switch_to_block(loop_exit_id);
+            }
+            HirStatementKind::Destructure { pattern, is_mutable, initializer } => {
+                // The names are bound after the whole initializer is evaluated
+                let ty = self.expression_type(initializer)?;
+                let place = self.transform_expression_to_place(initializer)?;
+                self.bind_pattern(pattern, place, ty, *is_mutable)?;
+            }
+            HirStatementKind::For { label, pattern, iterable, body } => {
+                self.transform_for_loop(label, pattern, iterable, body)?;
+            }
+            HirStatementKind::Unsafe { body } => {
+                // Unsafety is checked in HIR; the block lowers to its statements
+                self.build_cfg(body)?;
+            }
+            HirStatementKind::Break { label } => {
+                let depth = self.loop_depth(label)?;
+                // Leaving a for loop ends its variables, as the increment block would
+                let variables: Vec<LocalId> = self.loops[depth..].iter().rev()
+                    .flat_map(|targets| targets.variables.iter().rev().copied())
+                    .collect();
+                for local_id in variables {
+                    self.push_statement(MirStatementKind::StorageDead(local_id));
+                }
+                let target = self.loops[depth].break_block;
+                self.terminate_block(MirTerminator::Goto { target });
+            }
+            HirStatementKind::Continue { label } => {
+                let depth = self.loop_depth(label)?;
+                let variables: Vec<LocalId> = self.loops[depth + 1..].iter().rev()
+                    .flat_map(|targets| targets.variables.iter().rev().copied())
+                    .collect();
+                for local_id in variables {
+                    self.push_statement(MirStatementKind::StorageDead(local_id));
+                }
+                let target = self.loops[depth].continue_block;
+                self.terminate_block(MirTerminator::Goto { target });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Index in `loops` of the loop a `break` or `continue` with `label` refers to
+    fn loop_depth(&self, label: &Option<String>) -> OvieResult<usize> {
+        self.loops.iter()
+            .rposition(|targets| label.is_none() || &targets.label == label)
+            .ok_or_else(|| OvieError::IrError {
+                message: match label {
+                    Some(label) => format!("No enclosing loop is labeled '{}'", label),
+                    None => "'break' or 'continue' outside of a loop".to_string(),
+                },
+            })
+    }
+
+    /// Lower a `for` loop over a range or an array into a counting loop
+    fn transform_for_loop(
+        &mut self,
+        label: &Option<String>,
+        pattern: &HirPattern,
+        iterable: &HirExpression,
+        body: &crate::hir::HirBlock,
+    ) -> OvieResult<()> {
+        let iterable_type = match &iterable.kind {
+            HirExpressionKind::Range { .. } => None,
+            _ => Some(self.expression_type(iterable)?),
+        };
+        // Arrays are counted with an Int index, ranges with the type of their bounds
+        let counter_type = match (&iterable.kind, &iterable_type) {
+            (HirExpressionKind::Range { start, .. }, _) => match self.expression_type(start)? {
+                MirType::Number => MirType::Number,
+                _ => MirType::Int,
+            },
+            (_, Some(MirType::Adt { name, substs })) if name == RANGE_TYPE && substs.first() == Some(&MirType::Number) => MirType::Number,
+            _ => MirType::Int,
+        };
+        let one = MirOperand::Constant(match counter_type {
+            MirType::Number => MirConstant { literal: MirConstantValue::Number(1.0), ty: MirType::Number },
+            _ => MirConstant { literal: MirConstantValue::Integer(1), ty: MirType::Int },
+        });
+
+        // (counter, end, array being iterated)
+        let (counter, end, array) = match &iterable.kind {
+            HirExpressionKind::Range { start, end } => {
+                let start_operand = self.transform_expression_to_operand(start)?;
+                let end_operand = self.transform_expression_to_operand(end)?;
+
+                let end_place = self.new_temp(counter_type.clone());
+                self.push_assign(end_place.clone(), MirRvalue::Use(end_operand));
+
+                let counter = self.new_temp(counter_type.clone());
+                self.push_assign(counter.clone(), MirRvalue::Use(start_operand));
+
+                (counter, end_place, None)
+            }
+            _ if matches!(&iterable_type, Some(MirType::Adt { name, .. }) if name == RANGE_TYPE) => {
+                // A range value holds its start and end as fields 0 and 1
+                let range_operand = self.transform_expression_to_operand(iterable)?;
+                let range_place = self.new_temp(iterable_type.clone().unwrap_or(MirType::Unit));
+                self.push_assign(range_place.clone(), MirRvalue::Use(range_operand));
+                let bound = |index: u32| {
+                    let mut field = range_place.clone();
+                    field.projection.push(MirProjectionElem::Field(index));
+                    MirRvalue::Use(MirOperand::Copy(field))
+                };
+
+                let end_place = self.new_temp(counter_type.clone());
+                self.push_assign(end_place.clone(), bound(1));
+
+                let counter = self.new_temp(counter_type.clone());
+                self.push_assign(counter.clone(), bound(0));
+
+                (counter, end_place, None)
+            }
+            _ => {
+                let array_type = iterable_type.unwrap_or(MirType::Unit);
+                let element_type = match &array_type {
+                    MirType::Adt { name, substs } if name == ARRAY_TYPE && substs.len() == 1 => substs[0].clone(),
+                    _ => {
+                        return Err(OvieError::semantic_error(
+                            0, 0,
+                            format!("Cannot iterate over a value of type {:?}", array_type)
+                        ));
+                    }
+                };
+
+                let array_operand = self.transform_expression_to_operand(iterable)?;
+                let array_place = self.new_temp(array_type);
+                self.push_assign(array_place.clone(), MirRvalue::Use(array_operand));
+
+                let end_place = self.new_temp(MirType::Int);
+                self.push_assign(end_place.clone(), MirRvalue::Len(array_place.clone()));
+
+                let counter = self.new_temp(MirType::Int);
+                self.push_assign(counter.clone(), MirRvalue::Use(MirOperand::Constant(MirConstant {
+                    literal: MirConstantValue::Integer(0),
+                    ty: MirType::Int,
+                })));
+
+                (counter, end_place, Some((array_place, element_type)))
+            }
+        };
+
+        let loop_header_id = self.new_block();
+        let loop_body_id = self.new_block();
+        let loop_latch_id = self.new_block();
+        let loop_exit_id = self.new_block();
+
+        self.terminate_block(MirTerminator::Goto { target: loop_header_id });
+
+        self.switch_to_block(loop_header_id);
+        let in_bounds = self.new_temp(MirType::Boolean);
+        self.push_assign(in_bounds.clone(), MirRvalue::BinaryOp {
+            op: MirBinOp::Lt,
+            left: MirOperand::Copy(counter.clone()),
+            right: MirOperand::Copy(end),
+        });
+        self.terminate_block(MirTerminator::SwitchInt {
+            discriminant: MirOperand::Copy(in_bounds),
+            targets: vec![(1, loop_body_id)],
+            otherwise: loop_exit_id,
+        });
+
+        // Bind the loop variables for the body, restoring any outer bindings afterwards.
+        // A tuple pattern takes its parts from an unnamed element variable.
+        self.switch_to_block(loop_body_id);
+        let variable_type = array.as_ref().map(|(_, ty)| ty.clone()).unwrap_or(counter_type);
+        let variable_name = match pattern {
+            HirPattern::Binding(name) => Some(name.clone()),
+            _ => None,
+        };
+        let variable_local = self.push_local(variable_type.clone(), false, variable_name.clone());
+        self.push_statement(MirStatementKind::StorageLive(variable_local));
+        let value = match &array {
+            Some((array_place, _)) => {
+                let mut element = array_place.clone();
+                element.projection.push(MirProjectionElem::Index(counter.local));
+                MirOperand::Copy(element)
+            }
+            None => MirOperand::Copy(counter.clone()),
+        };
+        self.push_assign(MirPlace::from_local(variable_local), MirRvalue::Use(value));
+
+        let bindings = match variable_name {
+            Some(name) => {
+                let outer_binding = self.local_map.insert(name.clone(), variable_local);
+                vec![(name, variable_local, outer_binding)]
+            }
+            None => self.bind_pattern(pattern, MirPlace::from_local(variable_local), variable_type, false)?,
+        };
+        let mut variables = vec![variable_local];
+        variables.extend(bindings.iter().map(|(_, local_id, _)| *local_id).filter(|local_id| *local_id != variable_local));
+        self.loops.push(LoopTargets {
+            label: label.clone(),
+            continue_block: loop_latch_id,
+            break_block: loop_exit_id,
+            variables: variables.clone(),
+        });
+        let built = self.build_cfg(body);
+        self.loops.pop();
+        built?;
+        for (name, _, outer_binding) in bindings.into_iter().rev() {
+            match outer_binding {
+                Some(local_id) => self.local_map.insert(name, local_id),
+                None => self.local_map.remove(&name),
+            };
+        }
+        self.terminate_block(MirTerminator::Goto { target: loop_latch_id });
+
+        // The increment block, where every iteration and every `continue` ends
+        self.switch_to_block(loop_latch_id);
+        for local_id in variables.into_iter().rev() {
+            self.push_statement(MirStatementKind::StorageDead(local_id));
+        }
+        self.push_assign(counter.clone(), MirRvalue::BinaryOp {
+            op: MirBinOp::Add,
+            left: MirOperand::Copy(counter),
+            right: one,
+        });
+        self.terminate_block(MirTerminator::Goto { target: loop_header_id });
+
+        self.switch_to_block(loop_exit_id);
+        Ok(())
+    }
+
+    /// Bind the names of a `let` or `for` pattern to the parts of the value in `place`.
+    /// Returns each name with its new local and the local it shadowed, if any.
+    fn bind_pattern(
+        &mut self,
+        pattern: &HirPattern,
+        place: MirPlace,
+        ty: MirType,
+        is_mutable: bool,
+    ) -> OvieResult<Vec<(String, LocalId, Option<LocalId>)>> {
+        match (pattern, ty) {
+            (HirPattern::Wildcard, _) => Ok(Vec::new()),
+            (HirPattern::Binding(name), ty) => {
+                let local_id = self.push_local(ty, is_mutable, Some(name.clone()));
+                self.push_statement(MirStatementKind::StorageLive(local_id));
+                self.push_assign(MirPlace::from_local(local_id), MirRvalue::Use(MirOperand::Copy(place)));
+                Ok(vec![(name.clone(), local_id, self.local_map.insert(name.clone(), local_id))])
+            }
+            (HirPattern::Tuple(patterns), MirType::Tuple(element_types)) if patterns.len() == element_types.len() => {
+                let mut bindings = Vec::new();
+                for (index, (pattern, element_type)) in patterns.iter().zip(element_types).enumerate() {
+                    let mut element = place.clone();
+                    element.projection.push(MirProjectionElem::Field(index as u32));
+                    bindings.extend(self.bind_pattern(pattern, element, element_type, is_mutable)?);
+                }
+                Ok(bindings)
+            }
+            (pattern, ty) => Err(OvieError::semantic_error(
+                0, 0,
+                format!("Cannot destructure a value of type {:?} with pattern {:?}", ty, pattern)
+            )),
+        }
+    }
+
+    /// Lower `operand?` into a switch on the operand's discriminant, returning the place
+    /// holding the data of its `success` variant.
+    ///
+    /// Any other variant goes to a block that returns the `failure` variant of the
+    /// function's return type, carrying the operand's failure data.
+    fn transform_try(
+        &mut self,
+        expr: &HirExpression,
+        operand: &HirExpression,
+        success: &str,
+        failure: &str,
+    ) -> OvieResult<MirPlace> {
+        let operand_type = self.expression_type(operand)?;
+        let operand_enum = self.enum_name(&operand_type)?;
+        let return_enum = self.enum_name(&self.return_type.clone())?;
+        let success_index = self.resolve_variant_index(&operand_enum, success)?;
+        let failure_index = self.resolve_variant_index(&operand_enum, failure)?;
+        let returned_index = self.resolve_variant_index(&return_enum, failure)?;
+        let operand_place = self.transform_expression_to_place(operand)?;
+
+        let discriminant = self.new_temp(MirType::Number);
+        self.push_assign(discriminant.clone(), MirRvalue::Discriminant(operand_place.clone()));
+        let success_block = self.new_block();
+        let failure_block = self.new_block();
+        self.terminate_block(MirTerminator::SwitchInt {
+            discriminant: MirOperand::Copy(discriminant),
+            targets: vec![(success_index as u128, success_block)],
+            otherwise: failure_block,
+        });
+
+        let payload = |variant: u32| {
+            let mut payload = operand_place.clone();
+            payload.projection.push(MirProjectionElem::Downcast(variant));
+            payload.projection.push(MirProjectionElem::Field(0));
+            payload
+        };
+
+        self.switch_to_block(failure_block);
+        let operands = if self.variant_has_data(&operand_enum, failure_index) {
+            vec![MirOperand::Copy(payload(failure_index))]
+        } else {
+            Vec::new()
+        };
+        let returned = self.new_temp(self.return_type.clone());
+        self.push_assign(returned.clone(), MirRvalue::Aggregate {
+            kind: MirAggregateKind::Adt { name: return_enum, variant: Some(returned_index) },
+            operands,
+        });
+        self.emit_return(Some(MirOperand::Copy(returned)))?;
+
+        self.switch_to_block(success_block);
+        let value_type = self.transform_type(&expr.expr_type)?;
+        let value = self.new_temp(value_type);
+        if self.variant_has_data(&operand_enum, success_index) {
+            self.push_assign(value.clone(), MirRvalue::Use(MirOperand::Copy(payload(success_index))));
+        }
+        Ok(value)
+    }
+
+    /// Name of the enum layout of `ty`
+    fn enum_name(&self, ty: &MirType) -> OvieResult<String> {
+        match ty {
+            MirType::Adt { name, .. } if matches!(self.type_definitions.get(name), Some(MirTypeDef::Enum { .. })) => Ok(name.clone()),
+            other => Err(OvieError::semantic_error(
+                0, 0,
+                format!("Expected an enum type, found {:?}", other)
+            )),
+        }
+    }
+
+    /// Whether `variant` of the enum `enum_name` carries data
+    fn variant_has_data(&self, enum_name: &str, variant: u32) -> bool {
+        match self.type_definitions.get(enum_name) {
+            Some(MirTypeDef::Enum { variants }) => variants.get(variant as usize).is_some_and(|v| !v.fields.is_empty()),
+            _ => false,
+        }
+    }
+
+    /// Lower a match into a switch on the scrutinee, returning the place holding the match's value.
+    ///
+    /// Enum scrutinees switch on their discriminant; other scrutinees compare against
+    /// each literal pattern in turn. Every arm ends by jumping to a shared join block.
+    fn transform_match(
+        &mut self,
+        expr: &HirExpression,
+        scrutinee: &HirExpression,
+        arms: &[HirMatchArm],
+    ) -> OvieResult<MirPlace> {
+        let result_type = self.transform_type(&expr.expr_type)?;
+        let result = self.new_temp(result_type.clone());
+        let scrutinee_type = self.expression_type(scrutinee)?;
+        let scrutinee_place = self.transform_expression_to_place(scrutinee)?;
+
+        let arm_blocks: Vec<BasicBlockId> = arms.iter().map(|_| self.new_block()).collect();
+        let join_block = self.new_block();
+        let catch_all = arms.iter()
+            .position(|arm| matches!(arm.pattern, HirPattern::Wildcard | HirPattern::Binding(_)));
+
+        // Payloads are bound through the variant they belong to
+        let variants = match &scrutinee_type {
+            MirType::Adt { name, .. } => match self.type_definitions.get(name) {
+                Some(MirTypeDef::Enum { variants }) => Some((name.clone(), variants.clone())),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some((enum_name, _)) = &variants {
+            let discriminant = self.new_temp(MirType::Number);
+            self.push_assign(discriminant.clone(), MirRvalue::Discriminant(scrutinee_place.clone()));
+
+            // Arms after the first catch-all can never be reached
+            let mut targets: Vec<(u128, BasicBlockId)> = Vec::new();
+            for (arm, &block) in arms.iter().zip(&arm_blocks).take(catch_all.unwrap_or(arms.len())) {
+                if let HirPattern::Variant { variant_name, .. } = &arm.pattern {
+                    let variant = self.resolve_variant_index(enum_name, variant_name)? as u128;
+                    if !targets.iter().any(|(value, _)| *value == variant) {
+                        targets.push((variant, block));
+                    }
+                }
+            }
+
+            let otherwise = match catch_all {
+                Some(index) => arm_blocks[index],
+                None => {
+                    let unreachable_block = self.new_block();
+                    self.basic_blocks.insert(unreachable_block, MirBasicBlock {
+                        id: unreachable_block,
+                        statements: Vec::new(),
+                        terminator: MirTerminator::Unreachable,
+                        terminator_span: self.current_span,
+                    });
+                    unreachable_block
+                }
+            };
+            self.terminate_block(MirTerminator::SwitchInt {
+                discriminant: MirOperand::Copy(discriminant),
+                targets,
+                otherwise,
+            });
+        } else {
+            for (arm, &block) in arms.iter().zip(&arm_blocks) {
+                match &arm.pattern {
+                    HirPattern::Literal(literal) => {
+                        let constant = self.transform_literal(literal, &HirType::Unit)?;
+                        let is_equal = self.new_temp(MirType::Boolean);
+                        self.push_assign(is_equal.clone(), MirRvalue::BinaryOp {
+                            op: MirBinOp::Eq,
+                            left: MirOperand::Copy(scrutinee_place.clone()),
+                            right: MirOperand::Constant(constant),
+                        });
+                        let next_test = self.new_block();
+                        self.terminate_block(MirTerminator::SwitchInt {
+                            discriminant: MirOperand::Copy(is_equal),
+                            targets: vec![(1, block)],
+                            otherwise: next_test,
+                        });
+                        self.switch_to_block(next_test);
+                    }
+                    _ => break,
+                }
+            }
+            match catch_all {
+                Some(index) => self.terminate_block(MirTerminator::Goto { target: arm_blocks[index] }),
+                None => self.terminate_block(MirTerminator::Unreachable),
+            }
+        }
+
+        for (arm, &block) in arms.iter().zip(&arm_blocks) {
+            self.switch_to_block(block);
+
+            // Bind the pattern's names for the arm, restoring any outer bindings afterwards
+            let mut places = Vec::new();
+            match &arm.pattern {
+                HirPattern::Binding(name) => places.push((name, scrutinee_type.clone(), scrutinee_place.clone())),
+                HirPattern::Variant { variant_name, bindings } if !bindings.is_empty() => {
+                    let (enum_name, variant_defs) = variants.as_ref().ok_or_else(|| OvieError::semantic_error(
+                        0, 0,
+                        format!("Cannot match variant '{}' against a value of type {:?}", variant_name, scrutinee_type)
+                    ))?;
+                    let variant = self.resolve_variant_index(enum_name, variant_name)?;
+                    let field_defs = &variant_defs[variant as usize].fields;
+                    for (field, name) in bindings {
+                        let index = field_defs.iter().position(|def| def.name == *field).ok_or_else(|| OvieError::semantic_error(
+                            0, 0,
+                            format!("Field '{}' not found in variant '{}'", field, variant_name)
+                        ))?;
+                        let mut payload = scrutinee_place.clone();
+                        payload.projection.push(MirProjectionElem::Downcast(variant));
+                        payload.projection.push(MirProjectionElem::Field(index as u32));
+                        places.push((name, field_defs[index].ty.clone(), payload));
+                    }
+                }
+                _ => {}
+            }
+            let mut bound = Vec::new();
+            for (name, ty, place) in places {
+                let local_id = self.push_local(ty, false, Some(name.clone()));
+                self.push_statement(MirStatementKind::StorageLive(local_id));
+                self.push_assign(MirPlace::from_local(local_id), MirRvalue::Use(MirOperand::Copy(place)));
+                bound.push((name, local_id, self.local_map.insert(name.clone(), local_id)));
+            }
+
+            self.build_cfg(&arm.body)?;
+            if let Some(value) = &arm.value {
+                let operand = self.transform_expression_to_operand(value)?;
+                if result_type != MirType::Unit {
+                    self.push_assign(result.clone(), MirRvalue::Use(operand));
+                }
+            }
+
+            for (name, local_id, outer_binding) in bound.into_iter().rev() {
+                match outer_binding {
+                    Some(outer_id) => self.local_map.insert(name.clone(), outer_id),
+                    None => self.local_map.remove(name),
+                };
+                self.push_statement(MirStatementKind::StorageDead(local_id));
+            }
+            self.terminate_block(MirTerminator::Goto { target: join_block });
+        }
+
+        self.switch_to_block(join_block);
+        Ok(result)
+    }
+
+    /// Emit a call terminator and continue in a fresh block, returning the destination place
+    fn emit_call(
+        &mut self,
+        function: &str,
+        args: Vec<MirOperand>,
+        params: Vec<MirType>,
+        return_type: MirType,
+    ) -> MirPlace {
+        let func = MirOperand::Constant(MirConstant {
+            literal: MirConstantValue::String(function.to_string()),
+            ty: MirType::FnPtr {
+                params,
+                return_type: Box::new(return_type.clone()),
+            },
+        });
+
+        self.emit_call_operand(func, args, return_type)
+    }
+
+    /// Emit a call of `func` and continue in a fresh block, returning the destination place
+    fn emit_call_operand(&mut self, func: MirOperand, args: Vec<MirOperand>, return_type: MirType) -> MirPlace {
+        let destination = self.new_temp(return_type);
+        let target = self.new_block();
+
+        self.terminate_block(MirTerminator::Call {
+            func,
+            args,
+            destination: destination.clone(),
+            target: Some(target),
+            cleanup: None,
+        });
+        self.switch_to_block(target);
+
+        destination
+    }
+
+    /// Lower a call expression, returning the place holding its result
+    fn transform_call(&mut self, function: &str, type_arguments: &[HirType], arguments: &[HirExpression]) -> OvieResult<MirPlace> {
+        let takes_self = self.methods.contains(function)
+            || self.type_table.trait_method(function).is_some_and(|(info, method)| info.methods[method].takes_self);
+        let function = &self.instantiate_function(function, type_arguments)?;
+        // Arguments move into user functions; builtins and method receivers only read them
+        let moves = self.function_signatures.contains_key(function);
+        let mut args = Vec::new();
+        let mut arg_types = Vec::new();
+        for (index, argument) in arguments.iter().enumerate() {
+            args.push(if moves && !(takes_self && index == 0) {
+                self.transform_expression_to_moved_operand(argument)?
+            } else {
+                self.transform_expression_to_operand(argument)?
+            });
+            arg_types.push(self.expression_type(argument)?);
+        }
+
+        let (params, return_type) = match self.function_signatures.get(function) {
+            Some(signature) => (signature.parameters.clone(), signature.return_type.clone()),
+            None => (arg_types, Self::builtin_return_type(function)),
+        };
+
+        Ok(self.emit_call(function, args, params, return_type))
+    }
+
+    /// Lower a call of a function value, returning the place holding its result
+    fn transform_indirect_call(&mut self, callee: &HirExpression, arguments: &[HirExpression]) -> OvieResult<MirPlace> {
+        let func = self.transform_expression_to_operand(callee)?;
+        let return_type = match self.expression_type(callee)? {
+            MirType::FnPtr { return_type, .. } => *return_type,
+            other => {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("Cannot call a value of type {:?}", other)
+                ));
+            }
+        };
+
+        let mut args = Vec::new();
+        for argument in arguments {
+            args.push(self.transform_expression_to_moved_operand(argument)?);
+        }
+
+        Ok(self.emit_call_operand(func, args, return_type))
+    }
+
+    /// Return type of a built-in function
+    fn builtin_return_type(function: &str) -> MirType {
+        match function {
+            "to_string" => MirType::String,
+            "to_number" => MirType::Number,
+            "to_int" => MirType::Int,
+            _ => MirType::Unit,
+        }
+    }
+
+    /// MIR type of the value produced by an HIR expression
+    fn expression_type(&mut self, expr: &HirExpression) -> OvieResult<MirType> {
+        match &expr.kind {
+            HirExpressionKind::Call { function, type_arguments, .. } => {
+                let function = self.instantiate_function(function, type_arguments)?;
+                Ok(self.function_signatures.get(&function)
+                    .map(|signature| signature.return_type.clone())
+                    .unwrap_or_else(|| Self::builtin_return_type(&function)))
+            }
+            HirExpressionKind::IndirectCall { callee, .. } => match self.expression_type(callee)? {
+                MirType::FnPtr { return_type, .. } => Ok(*return_type),
+                _ => self.transform_type(&expr.expr_type),
+            },
+            HirExpressionKind::Variable(name) => match self.local_map.get(name) {
+                Some(&local_id) => Ok(self.locals[local_id as usize].ty.clone()),
+                None => self.transform_type(&expr.expr_type),
+            },
+            _ => self.transform_type(&expr.expr_type),
+        }
+    }
+
+    /// Resolve a struct field name to its index in the struct layout, or a tuple field to its position
+    fn resolve_field_index(&mut self, object_type: &HirType, field: &str) -> OvieResult<u32> {
+        let struct_name = match object_type {
+            HirType::Tuple(elements) => {
+                return field.parse::<u32>().ok()
+                    .filter(|index| (*index as usize) < elements.len())
+                    .ok_or_else(|| OvieError::semantic_error(
+                        0, 0,
+                        format!("Field '{}' not found in tuple {}", field, object_type)
+                    ));
+            }
+            HirType::Struct { .. } => self.adt_name(object_type)?,
+            other => {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("Field '{}' accessed on non-struct type {:?}", field, other)
+                ));
+            }
+        };
 
-                // Create function operand for print
-                let print_func = MirOperand::Constant(MirConstant {
-                    literal: MirConstantValue::String("print".to_string()),
-                    ty: MirType::FnPtr {
-                        params: vec![MirType::String],
-                        return_type: Box::new(MirType::Unit),
-                    },
-                });
+        match self.type_definitions.get(&struct_name) {
+            Some(MirTypeDef::Struct { fields }) => fields.iter()
+                .position(|f| f.name == field)
+                .map(|index| index as u32)
+                .ok_or_else(|| OvieError::semantic_error(
+                    0, 0,
+                    format!("Field '{}' not found in struct '{}'", field, struct_name)
+                )),
+            _ => Err(OvieError::semantic_error(
+                0, 0,
+                format!("Struct '{}' not found in MIR transformation", struct_name)
+            )),
+        }
+    }
 
-                // This would become a Call terminator in a real implementation
-                // For now, just assign the result
-                statements.push(MirStatement {
-                    kind: MirStatementKind::Assign {
-                        place: MirPlace {
-                            local: temp_local,
-                            projection: Vec::new(),
-                        },
-                        rvalue: MirRvalue::Use(operand),
-                    },
-                });
-            }
-            HirStatementKind::Return(value) => {
-                // Return statements become terminators, not regular statements
-                // For now, just add a nop
-                statements.push(MirStatement {
-                    kind: MirStatementKind::Nop,
-                });
-            }
-            _ => {
-                // Other statement types would be handled here
-                statements.push(MirStatement {
-                    kind: MirStatementKind::Nop,
-                });
+    /// Name of the layout of a struct or enum type, instantiating a generic one
+    fn adt_name(&mut self, hir_type: &HirType) -> OvieResult<String> {
+        match self.transform_type(hir_type)? {
+            MirType::Adt { name, .. } => Ok(name),
+            other => Err(OvieError::semantic_error(
+                0, 0,
+                format!("Expected a struct or enum type, found {:?}", other)
+            )),
+        }
+    }
+
+    /// Resolve an enum variant name to its index in the enum layout
+    fn resolve_variant_index(&self, enum_name: &str, variant_name: &str) -> OvieResult<u32> {
+        match self.type_definitions.get(enum_name) {
+            Some(MirTypeDef::Enum { variants }) => variants.iter()
+                .position(|v| v.name == variant_name)
+                .map(|index| index as u32)
+                .ok_or_else(|| OvieError::semantic_error(
+                    0, 0,
+                    format!("Variant '{}' not found in enum '{}'", variant_name, enum_name)
+                )),
+            _ => Err(OvieError::semantic_error(
+                0, 0,
+                format!("Enum '{}' not found in MIR transformation", enum_name)
+            )),
+        }
+    }
+
+    /// Operands for the field initializers of a struct or enum variant. Initializers run in
+    /// source order; operands follow declaration order
+    fn field_operands(&mut self, fields: &[HirFieldInit], field_defs: &[MirFieldDef], owner: &str) -> OvieResult<Vec<MirOperand>> {
+        let mut values = HashMap::new();
+        for field_init in fields {
+            let operand = self.transform_expression_to_moved_operand(&field_init.value)?;
+            if !field_defs.iter().any(|def| def.name == field_init.name) {
+                return Err(OvieError::semantic_error(
+                    0, 0,
+                    format!("Field '{}' not found in {}", field_init.name, owner)
+                ));
+            }
+            values.insert(field_init.name.clone(), operand);
+        }
+
+        let mut operands = Vec::new();
+        for field_def in field_defs {
+            match values.remove(&field_def.name) {
+                Some(operand) => operands.push(operand),
+                None => {
+                    return Err(OvieError::semantic_error(
+                        0, 0,
+                        format!("Missing field '{}' in initializer of {}", field_def.name, owner)
+                    ));
+                }
             }
         }
-        
-        Ok(())
+        Ok(operands)
+    }
+
+    /// Look up the local bound to a variable name
+    fn lookup_local(&self, name: &str) -> OvieResult<LocalId> {
+        self.local_map.get(name).copied().ok_or_else(|| OvieError::SemanticError {
+            line: 0,
+            column: 0,
+            message: format!("Variable '{}' not found in MIR transformation", name),
+        })
     }
 
-    /// Transform HIR expression to MIR rvalue
+    /// Transform HIR expression to MIR rvalue, attributing emitted code to the expression
     fn transform_expression_to_rvalue(&mut self, expr: &HirExpression) -> OvieResult<MirRvalue> {
+        let outer = std::mem::replace(&mut self.current_span, expr.span);
+        let result = self.lower_rvalue(expr)
+            .map_err(|e| e.located_at(self.current_span.start_position()));
+        self.current_span = outer;
+        result
+    }
+
+    fn lower_rvalue(&mut self, expr: &HirExpression) -> OvieResult<MirRvalue> {
         match &expr.kind {
             HirExpressionKind::Literal(lit) => {
                 let constant = self.transform_literal(lit, &expr.expr_type)?;
                 Ok(MirRvalue::Use(MirOperand::Constant(constant)))
             }
-            HirExpressionKind::Variable(name) => {
-                if let Some(&local_id) = self.local_map.get(name) {
-                    let place = MirPlace {
-                        local: local_id,
-                        projection: Vec::new(),
-                    };
-                    Ok(MirRvalue::Use(MirOperand::Copy(place)))
-                } else {
-                    Err(OvieError::SemanticError {
-                        line: 0,
-                        column: 0,
-                        message: format!("Variable '{}' not found in MIR transformation", name),
-                    })
-                }
+            HirExpressionKind::Variable(name) if self.is_constant(name) => {
+                Ok(MirRvalue::Use(MirOperand::Constant(self.constants[name].clone())))
+            }
+            HirExpressionKind::Variable(_) | HirExpressionKind::FieldAccess { .. } | HirExpressionKind::Index { .. } => {
+                Ok(MirRvalue::Use(self.transform_expression_to_moved_operand(expr)?))
             }
             HirExpressionKind::Binary { left, op, right } => {
                 let left_operand = self.transform_expression_to_operand(left)?;
                 let right_operand = self.transform_expression_to_operand(right)?;
                 let mir_op = self.transform_binary_op(op);
-                
+
                 Ok(MirRvalue::BinaryOp {
                     op: mir_op,
                     left: left_operand,
@@ -838,110 +1997,116 @@ impl MirBuilder {
             HirExpressionKind::Unary { op, operand } => {
                 let mir_operand = self.transform_expression_to_operand(operand)?;
                 let mir_op = self.transform_unary_op(op);
-                
+
                 Ok(MirRvalue::UnaryOp {
                     op: mir_op,
                     operand: mir_operand,
                 })
             }
-            HirExpressionKind::FieldAccess { object, field } => {
-                let object_place = self.transform_expression_to_place(object)?;
-                // For now, assume field access is by index (would need field resolution)
-                let field_place = MirPlace {
-                    local: object_place.local,
-                    projection: {
-                        let mut proj = object_place.projection;
-                        proj.push(MirProjectionElem::Field(0)); // Simplified field index
-                        proj
-                    },
-                };
-                Ok(MirRvalue::Use(MirOperand::Copy(field_place)))
-            }
             HirExpressionKind::StructInit { struct_name, fields } => {
-                let mut operands = Vec::new();
-                for field_init in fields {
-                    operands.push(self.transform_expression_to_operand(&field_init.value)?);
-                }
-                
-                Ok(MirRvalue::Aggregate {
+                let layout_name = self.adt_name(&expr.expr_type)?;
+                let field_defs = match self.type_definitions.get(&layout_name) {
+                    Some(MirTypeDef::Struct { fields }) => fields.clone(),
+                    _ => {
+                        return Err(OvieError::semantic_error(
+                            0, 0,
+                            format!("Struct '{}' not found in MIR transformation", struct_name)
+                        ));
+                    }
+                };
+
+                let operands = self.field_operands(fields, &field_defs, &format!("struct '{}'", struct_name))?;
+
+                let value = MirRvalue::Aggregate {
                     kind: MirAggregateKind::Adt {
-                        name: struct_name.clone(),
+                        name: layout_name.clone(),
                         variant: None,
                     },
                     operands,
-                })
+                };
+                if self.struct_invariants.is_empty() {
+                    return Ok(value);
+                }
+                // A value with invariants is checked as soon as it is built
+                let place = self.new_temp(MirType::Adt { name: layout_name.clone(), substs: Vec::new() });
+                self.push_assign(place.clone(), value);
+                self.check_invariants(&layout_name, place.clone())?;
+                Ok(MirRvalue::Use(MirOperand::Copy(place)))
             }
             HirExpressionKind::Range { start, end } => {
                 let start_operand = self.transform_expression_to_operand(start)?;
                 let end_operand = self.transform_expression_to_operand(end)?;
-                
+
                 Ok(MirRvalue::Aggregate {
                     kind: MirAggregateKind::Adt {
-                        name: "Range".to_string(),
+                        name: RANGE_TYPE.to_string(),
                         variant: None,
                     },
                     operands: vec![start_operand, end_operand],
                 })
             }
-            HirExpressionKind::EnumVariant { enum_name, variant_name: _, data } => {
-                let mut operands = Vec::new();
-                if let Some(data_expr) = data {
-                    operands.push(self.transform_expression_to_operand(data_expr)?);
-                }
-                
-                // TODO: Resolve variant name to index
+            HirExpressionKind::EnumVariant { variant_name, fields, .. } => {
+                let enum_name = self.adt_name(&expr.expr_type)?;
+                let variant = self.resolve_variant_index(&enum_name, variant_name)?;
+                let field_defs = match self.type_definitions.get(&enum_name) {
+                    Some(MirTypeDef::Enum { variants }) => variants[variant as usize].fields.clone(),
+                    _ => Vec::new(),
+                };
+                let operands = self.field_operands(fields, &field_defs, &format!("variant '{}'", variant_name))?;
+
                 Ok(MirRvalue::Aggregate {
                     kind: MirAggregateKind::Adt {
-                        name: enum_name.clone(),
-                        variant: Some(0), // Placeholder - should resolve variant name to index
+                        name: enum_name,
+                        variant: Some(variant),
                     },
                     operands,
                 })
             }
-            HirExpressionKind::Index { object, index } => {
-                // For now, treat indexing as a simple operation
-                // In a full implementation, this would need proper MIR lowering
-                let _object_place = self.transform_expression_to_place(object)?;
-                let _index_operand = self.transform_expression_to_operand(index)?;
-                
-                // Return a placeholder - proper implementation would create indexed place
-                Ok(MirRvalue::Use(MirOperand::Constant(MirConstant {
-                    literal: MirConstantValue::Unit,
-                    ty: MirType::Unit,
-                })))
+            HirExpressionKind::Tuple { elements } => {
+                let mut operands = Vec::new();
+                for element in elements {
+                    operands.push(self.transform_expression_to_moved_operand(element)?);
+                }
+
+                Ok(MirRvalue::Aggregate {
+                    kind: MirAggregateKind::Tuple,
+                    operands,
+                })
             }
             HirExpressionKind::ArrayLiteral { elements } => {
-                // Lower array literal to aggregate construction
+                let element_type = match &expr.expr_type {
+                    HirType::Array(element_type) => self.transform_type(element_type)?,
+                    _ => MirType::Unit,
+                };
+
                 let mut operands = Vec::new();
-                let mut element_type = MirType::Unit; // Default type
-                
                 for element in elements {
-                    let operand = self.transform_expression_to_operand(element)?;
-                    // Use the first element's type
-                    if operands.is_empty() {
-                        element_type = match &operand {
-                            MirOperand::Constant(c) => c.ty.clone(),
-                            MirOperand::Copy(place) | MirOperand::Move(place) => {
-                                // Get type from place - simplified
-                                MirType::Unit
-                            }
-                        };
-                    }
-                    operands.push(operand);
+                    operands.push(self.transform_expression_to_moved_operand(element)?);
                 }
-                
+
                 Ok(MirRvalue::Aggregate {
                     kind: MirAggregateKind::Array(element_type),
                     operands,
                 })
             }
-            HirExpressionKind::Call { function, arguments } => {
-                // Function calls need special handling - they become terminators
-                // For now, return a placeholder
-                Ok(MirRvalue::Use(MirOperand::Constant(MirConstant {
-                    literal: MirConstantValue::Unit,
-                    ty: MirType::Unit,
-                })))
+            HirExpressionKind::Call { function, type_arguments, arguments } => {
+                let destination = self.transform_call(function, type_arguments, arguments)?;
+                Ok(MirRvalue::Use(MirOperand::Copy(destination)))
+            }
+            HirExpressionKind::Match { scrutinee, arms } => {
+                let result = self.transform_match(expr, scrutinee, arms)?;
+                Ok(MirRvalue::Use(MirOperand::Copy(result)))
+            }
+            HirExpressionKind::Closure { parameters, return_type, body, captures } => {
+                self.transform_closure(expr, parameters, return_type, body, captures)
+            }
+            HirExpressionKind::IndirectCall { callee, arguments } => {
+                let destination = self.transform_indirect_call(callee, arguments)?;
+                Ok(MirRvalue::Use(MirOperand::Copy(destination)))
+            }
+            HirExpressionKind::Try { operand, success, failure } => {
+                let value = self.transform_try(expr, operand, success, failure)?;
+                Ok(MirRvalue::Use(MirOperand::Copy(value)))
             }
         }
     }
@@ -953,79 +2118,120 @@ impl MirBuilder {
                 let constant = self.transform_literal(lit, &expr.expr_type)?;
                 Ok(MirOperand::Constant(constant))
             }
-            HirExpressionKind::Variable(name) => {
-                if let Some(&local_id) = self.local_map.get(name) {
-                    let place = MirPlace {
-                        local: local_id,
-                        projection: Vec::new(),
-                    };
-                    Ok(MirOperand::Copy(place))
-                } else {
-                    Err(OvieError::SemanticError {
-                        line: 0,
-                        column: 0,
-                        message: format!("Variable '{}' not found in MIR transformation", name),
-                    })
-                }
+            HirExpressionKind::Variable(name) if self.is_constant(name) => {
+                Ok(MirOperand::Constant(self.constants[name].clone()))
+            }
+            HirExpressionKind::Variable(_) | HirExpressionKind::FieldAccess { .. } | HirExpressionKind::Index { .. } => {
+                let place = self.transform_expression_to_place(expr)?;
+                Ok(MirOperand::Copy(place))
+            }
+            HirExpressionKind::Call { function, type_arguments, arguments } => {
+                let destination = self.transform_call(function, type_arguments, arguments)?;
+                Ok(MirOperand::Copy(destination))
+            }
+            HirExpressionKind::Match { scrutinee, arms } => {
+                let result = self.transform_match(expr, scrutinee, arms)?;
+                Ok(MirOperand::Copy(result))
+            }
+            HirExpressionKind::IndirectCall { callee, arguments } => {
+                let destination = self.transform_indirect_call(callee, arguments)?;
+                Ok(MirOperand::Copy(destination))
+            }
+            HirExpressionKind::Try { operand, success, failure } => {
+                let value = self.transform_try(expr, operand, success, failure)?;
+                Ok(MirOperand::Copy(value))
             }
             _ => {
                 // For complex expressions, create a temporary and assign the rvalue to it
-                let temp_local = self.next_local_id;
-                self.next_local_id += 1;
-                
-                let place = MirPlace {
-                    local: temp_local,
-                    projection: Vec::new(),
-                };
-                
+                let ty = self.expression_type(expr)?;
+                let rvalue = self.transform_expression_to_rvalue(expr)?;
+                let place = self.new_temp(ty);
+                self.push_assign(place.clone(), rvalue);
                 Ok(MirOperand::Copy(place))
             }
         }
     }
 
-    /// Transform HIR expression to MIR place (for assignment targets)
+    /// Transform HIR expression to an operand handing its value to a new owner, as when it
+    /// is bound, passed, returned or stored. A variable or field whose type is not copied
+    /// is moved out of; array elements are read by value and never moved out.
+    fn transform_expression_to_moved_operand(&mut self, expr: &HirExpression) -> OvieResult<MirOperand> {
+        let operand = self.transform_expression_to_operand(expr)?;
+        match operand {
+            MirOperand::Copy(place) if Self::is_movable(expr) && !Self::is_copy(&self.expression_type(expr)?) => {
+                Ok(MirOperand::Move(place, expr.span))
+            }
+            operand => Ok(operand),
+        }
+    }
+
+    /// Whether `expr` names a variable or a field of one, which a move leaves without a value
+    fn is_movable(expr: &HirExpression) -> bool {
+        match &expr.kind {
+            HirExpressionKind::Variable(_) => true,
+            HirExpressionKind::FieldAccess { object, .. } => Self::is_movable(object),
+            _ => false,
+        }
+    }
+
+    /// Whether values of type `ty` are copied rather than moved: the primitive types other
+    /// than String, function values, shared references and tuples of such values
+    fn is_copy(ty: &MirType) -> bool {
+        match ty {
+            MirType::Number | MirType::Int | MirType::Boolean | MirType::Unit | MirType::FnPtr { .. } => true,
+            MirType::Ref { mutability, .. } => matches!(mutability, MirMutability::Not),
+            MirType::Tuple(types) => types.iter().all(Self::is_copy),
+            MirType::String | MirType::Adt { .. } | MirType::Array { .. } | MirType::Slice(_) => false,
+        }
+    }
+
+    /// Transform HIR expression to MIR place, spilling non-place values to a temporary
     fn transform_expression_to_place(&mut self, expr: &HirExpression) -> OvieResult<MirPlace> {
         match &expr.kind {
-            HirExpressionKind::Variable(name) => {
-                if let Some(&local_id) = self.local_map.get(name) {
-                    Ok(MirPlace {
-                        local: local_id,
-                        projection: Vec::new(),
-                    })
-                } else {
-                    Err(OvieError::SemanticError {
-                        line: 0,
-                        column: 0,
-                        message: format!("Variable '{}' not found in MIR transformation", name),
-                    })
-                }
+            HirExpressionKind::Variable(name) if !self.local_map.contains_key(name) && self.function_signatures.contains_key(name) => {
+                // A function item used as a value
+                let ty = self.expression_type(expr)?;
+                let rvalue = self.function_value(name)?;
+                let place = self.new_temp(ty);
+                self.push_assign(place.clone(), rvalue);
+                Ok(place)
+            }
+            HirExpressionKind::Variable(name) if !self.is_constant(name) => {
+                let local_id = self.lookup_local(name)?;
+                Ok(MirPlace::from_local(local_id))
             }
-            HirExpressionKind::FieldAccess { object, field: _ } => {
-                let object_place = self.transform_expression_to_place(object)?;
-                Ok(MirPlace {
-                    local: object_place.local,
-                    projection: {
-                        let mut proj = object_place.projection;
-                        proj.push(MirProjectionElem::Field(0)); // Simplified field index
-                        proj
-                    },
-                })
+            HirExpressionKind::FieldAccess { object, field } => {
+                let field_index = self.resolve_field_index(&object.expr_type, field)?;
+                let mut place = self.transform_expression_to_place(object)?;
+                place.projection.push(MirProjectionElem::Field(field_index));
+                Ok(place)
+            }
+            HirExpressionKind::Index { object, index } => {
+                let mut place = self.transform_expression_to_place(object)?;
+                let index_local = self.index_local(index)?;
+                place.projection.push(MirProjectionElem::Index(index_local));
+                Ok(place)
             }
             _ => {
-                Err(OvieError::SemanticError {
-                    line: 0,
-                    column: 0,
-                    message: "Invalid assignment target".to_string(),
-                })
+                match self.transform_expression_to_operand(expr)? {
+                    MirOperand::Copy(place) | MirOperand::Move(place, _) => Ok(place),
+                    operand => {
+                        let ty = self.expression_type(expr)?;
+                        let place = self.new_temp(ty);
+                        self.push_assign(place.clone(), MirRvalue::Use(operand));
+                        Ok(place)
+                    }
+                }
             }
         }
     }
 
     /// Transform HIR literal to MIR constant
-    fn transform_literal(&self, lit: &HirLiteral, ty: &HirType) -> OvieResult<MirConstant> {
+    fn transform_literal(&mut self, lit: &HirLiteral, ty: &HirType) -> OvieResult<MirConstant> {
         let (literal, mir_type) = match lit {
             HirLiteral::String(s) => (MirConstantValue::String(s.clone()), MirType::String),
             HirLiteral::Number(n) => (MirConstantValue::Number(*n), MirType::Number),
+            HirLiteral::Integer(n) => (MirConstantValue::Integer(*n), MirType::Int),
             HirLiteral::Boolean(b) => (MirConstantValue::Boolean(*b), MirType::Boolean),
             HirLiteral::Unit => (MirConstantValue::Unit, MirType::Unit),
         };
@@ -1037,20 +2243,31 @@ impl MirBuilder {
     }
 
     /// Transform HIR type to MIR type
-    fn transform_type(&self, hir_type: &HirType) -> OvieResult<MirType> {
+    fn transform_type(&mut self, hir_type: &HirType) -> OvieResult<MirType> {
         match hir_type {
             HirType::String => Ok(MirType::String),
             HirType::Number => Ok(MirType::Number),
+            HirType::Int => Ok(MirType::Int),
             HirType::Boolean => Ok(MirType::Boolean),
             HirType::Unit => Ok(MirType::Unit),
-            HirType::Struct(name) => Ok(MirType::Adt {
-                name: name.clone(),
-                substs: Vec::new(),
-            }),
-            HirType::Enum(name) => Ok(MirType::Adt {
-                name: name.clone(),
-                substs: Vec::new(),
-            }),
+            HirType::Struct { name, arguments } | HirType::Enum { name, arguments } => {
+                let name = if arguments.is_empty() {
+                    name.clone()
+                } else {
+                    let arguments = arguments.iter()
+                        .map(|argument| self.transform_type(argument))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    self.instantiate_type(name, arguments)?
+                };
+                Ok(MirType::Adt {
+                    name,
+                    substs: Vec::new(),
+                })
+            }
+            HirType::Param(name) => self.type_arguments.get(name).cloned().ok_or_else(|| OvieError::semantic_error(
+                0, 0,
+                format!("Type parameter '{}' has no type argument in MIR transformation", name)
+            )),
             HirType::Function { params, return_type } => {
                 let param_types = params.iter()
                     .map(|p| self.transform_type(p))
@@ -1065,22 +2282,146 @@ impl MirBuilder {
             HirType::Range(inner) => {
                 // For now, treat ranges as a special struct
                 Ok(MirType::Adt {
-                    name: "Range".to_string(),
+                    name: RANGE_TYPE.to_string(),
                     substs: vec![self.transform_type(inner)?],
                 })
             }
             HirType::Array(elem_type) => {
                 // Arrays are represented as ADTs with element type
                 Ok(MirType::Adt {
-                    name: "Array".to_string(),
+                    name: ARRAY_TYPE.to_string(),
                     substs: vec![self.transform_type(elem_type)?],
                 })
             }
+            HirType::Tuple(elements) => Ok(MirType::Tuple(elements.iter()
+                .map(|element| self.transform_type(element))
+                .collect::<Result<Vec<_>, _>>()?)),
             HirType::Error => Ok(MirType::Unit), // Error recovery
             HirType::Infer(_) => Ok(MirType::Unit), // Should be resolved by now
         }
     }
 
+    /// Name of the instance of generic struct or enum `name` for `type_arguments`,
+    /// laying the instance out on first use
+    fn instantiate_type(&mut self, name: &str, type_arguments: Vec<MirType>) -> OvieResult<String> {
+        let instance = Self::instance_name(name, &type_arguments);
+        if self.type_definitions.contains_key(&instance) {
+            return Ok(instance);
+        }
+
+        let template = self.generic_types.get(name).cloned().ok_or_else(|| OvieError::semantic_error(
+            0, 0,
+            format!("Generic type '{}' not found in MIR transformation", name)
+        ))?;
+
+        // A placeholder stops a recursive type from instantiating itself forever
+        self.type_definitions.insert(instance.clone(), MirTypeDef::Struct { fields: Vec::new() });
+        self.type_instances.insert(instance.clone(), (name.to_string(), type_arguments.clone()));
+        let outer = std::mem::take(&mut self.type_arguments);
+        let definition = match &template {
+            HirItem::Struct(hir_struct) => {
+                self.type_arguments = hir_struct.type_parameters.iter().cloned().zip(type_arguments).collect();
+                self.transform_struct(hir_struct)
+            }
+            HirItem::Enum(hir_enum) => {
+                self.type_arguments = hir_enum.type_parameters.iter().cloned().zip(type_arguments).collect();
+                self.transform_enum(hir_enum)
+            }
+            _ => Err(OvieError::semantic_error(0, 0, format!("'{}' is not a generic type", name))),
+        };
+        self.type_arguments = outer;
+
+        self.type_definitions.insert(instance.clone(), definition?);
+        Ok(instance)
+    }
+
+    /// Name of the instance of `function` for `type_arguments`, queueing the instance
+    /// to be lowered on first use. Non-generic functions keep their name.
+    fn instantiate_function(&mut self, function: &str, type_arguments: &[HirType]) -> OvieResult<String> {
+        if !self.generic_functions.contains_key(function) && self.type_table.trait_method(function).is_none() {
+            return Ok(function.to_string());
+        }
+        let type_arguments = type_arguments.iter()
+            .map(|argument| self.transform_type(argument))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.instantiate_function_with(function, type_arguments)
+    }
+
+    /// Name of the instance of `function` for MIR `type_arguments`. A trait method is
+    /// dispatched statically to the impl for its `Self` type argument.
+    fn instantiate_function_with(&mut self, function: &str, type_arguments: Vec<MirType>) -> OvieResult<String> {
+        if let Some((implementation, type_arguments)) = self.resolve_trait_method(function, &type_arguments)? {
+            return self.instantiate_function_with(&implementation, type_arguments);
+        }
+        let template = match self.generic_functions.get(function) {
+            Some(template) => template.clone(),
+            None => return Ok(function.to_string()),
+        };
+
+        let instance = Self::instance_name(function, &type_arguments);
+        if !self.function_signatures.contains_key(&instance) {
+            let bindings = template.type_parameters.iter().cloned().zip(type_arguments.iter().cloned()).collect();
+            let outer = std::mem::replace(&mut self.type_arguments, bindings);
+            let signature = self.transform_signature(&template);
+            self.type_arguments = outer;
+
+            self.function_signatures.insert(instance.clone(), signature?);
+            self.pending_instances.push((instance.clone(), function.to_string(), type_arguments));
+        }
+        Ok(instance)
+    }
+
+    /// Function a call of trait method `function` runs for the `Self` type first in
+    /// `type_arguments`, with that function's type arguments: the impl's method, taking the
+    /// type arguments of `Self` and then the method's own. `None` when the impl uses the
+    /// trait's default, which is instantiated like any generic function.
+    fn resolve_trait_method(&self, function: &str, type_arguments: &[MirType]) -> OvieResult<Option<(String, Vec<MirType>)>> {
+        let Some((info, method)) = self.type_table.trait_method(function) else {
+            return Ok(None);
+        };
+        let Some((self_type, method_arguments)) = type_arguments.split_first() else {
+            return Ok(None);
+        };
+        let (type_name, mut arguments) = match self_type {
+            MirType::Adt { name, .. } => self.type_instances.get(name).cloned().unwrap_or_else(|| (name.clone(), Vec::new())),
+            _ => (Self::type_name(self_type), Vec::new()),
+        };
+        let implementation = info.impls.get(&type_name).ok_or_else(|| OvieError::semantic_error(
+            0, 0,
+            format!("Type '{}' does not implement the trait of '{}'", Self::type_name(self_type), function)
+        ))?;
+        if !implementation.methods.contains(method) {
+            return Ok(None);
+        }
+        arguments.extend(method_arguments.iter().cloned());
+        Ok(Some((format!("{}::{}", type_name, method), arguments)))
+    }
+
+    /// Name of a generic item instantiated with `type_arguments`, e.g. `Pair<Number, String>`
+    fn instance_name(name: &str, type_arguments: &[MirType]) -> String {
+        let arguments: Vec<String> = type_arguments.iter().map(Self::type_name).collect();
+        format!("{}<{}>", name, arguments.join(", "))
+    }
+
+    /// Source-like spelling of a MIR type, used in instance names
+    pub(crate) fn type_name(ty: &MirType) -> String {
+        let join = |types: &[MirType]| types.iter().map(Self::type_name).collect::<Vec<_>>().join(", ");
+        match ty {
+            MirType::String => "String".to_string(),
+            MirType::Number => "Number".to_string(),
+            MirType::Int => "Int".to_string(),
+            MirType::Boolean => "Boolean".to_string(),
+            MirType::Unit => "()".to_string(),
+            MirType::Ref { ty, .. } => format!("&{}", Self::type_name(ty)),
+            MirType::Adt { name, substs } if substs.is_empty() => name.clone(),
+            MirType::Adt { name, substs } => format!("{}<{}>", name, join(substs)),
+            MirType::FnPtr { params, return_type } => format!("fn({}) -> {}", join(params), Self::type_name(return_type)),
+            MirType::Array { element_type, size } => format!("[{}; {}]", Self::type_name(element_type), size),
+            MirType::Slice(element_type) => format!("[{}]", Self::type_name(element_type)),
+            MirType::Tuple(types) => format!("({})", join(types)),
+        }
+    }
+
     /// Transform HIR binary operator to MIR
     fn transform_binary_op(&self, op: &HirBinaryOp) -> MirBinOp {
         match op {
@@ -1109,16 +2450,65 @@ impl MirBuilder {
     }
 
     /// Transform HIR place (for assignment targets)
-    fn transform_place(&self, _hir_place: &crate::hir::HirPlace) -> OvieResult<MirPlace> {
-        // Simplified implementation
-        Ok(MirPlace {
-            local: 0,
-            projection: Vec::new(),
-        })
+    fn transform_place(&mut self, hir_place: &crate::hir::HirPlace) -> OvieResult<MirPlace> {
+        match &hir_place.kind {
+            HirPlaceKind::Local(name) => {
+                let local_id = self.lookup_local(name)?;
+                Ok(MirPlace::from_local(local_id))
+            }
+            HirPlaceKind::Field { object, field } => {
+                let field_index = self.resolve_field_index(&object.place_type, field)?;
+                let mut place = self.transform_place(object)?;
+                place.projection.push(MirProjectionElem::Field(field_index));
+                Ok(place)
+            }
+            HirPlaceKind::Index { object, index } => {
+                let mut place = self.transform_place(object)?;
+                let index_local = self.index_local(index)?;
+                place.projection.push(MirProjectionElem::Index(index_local));
+                Ok(place)
+            }
+        }
+    }
+
+    /// After an assignment to `target` at `place`, check the invariants of the struct
+    /// whose field it is
+    fn check_assigned_field(&mut self, target: &crate::hir::HirPlace, mut place: MirPlace) -> OvieResult<()> {
+        let HirPlaceKind::Field { object, .. } = &target.kind else {
+            return Ok(());
+        };
+        if self.struct_invariants.is_empty() {
+            return Ok(());
+        }
+        let MirType::Adt { name, .. } = self.transform_type(&object.place_type)? else {
+            return Ok(());
+        };
+        place.projection.pop();
+        self.check_invariants(&name, place)
+    }
+
+    /// Local holding the value of an index expression, as index projections require
+    fn index_local(&mut self, index: &HirExpression) -> OvieResult<LocalId> {
+        match self.transform_expression_to_operand(index)? {
+            MirOperand::Copy(index_place) | MirOperand::Move(index_place, _) if index_place.projection.is_empty() => {
+                Ok(index_place.local)
+            }
+            operand => {
+                let index_type = self.expression_type(index)?;
+                let index_place = self.new_temp(index_type);
+                self.push_assign(index_place.clone(), MirRvalue::Use(operand));
+                Ok(index_place.local)
+            }
+        }
+    }
+
+    /// Whether `name` is a constant rather than a local shadowing it
+    fn is_constant(&self, name: &str) -> bool {
+        !self.local_map.contains_key(name) && self.constants.contains_key(name)
     }
 
     /// Transform HIR global to MIR
-    fn transform_global(&self, hir_global: &crate::hir::HirGlobal) -> OvieResult<MirGlobal> {
+    fn transform_global(&mut self, hir_global: &crate::hir::HirGlobal) -> OvieResult<MirGlobal> {
         let initializer = if let Some(ref init_expr) = hir_global.initializer {
             // For globals, initializer must be a constant
             if let HirExpressionKind::Literal(lit) = &init_expr.kind {
@@ -1139,7 +2529,7 @@ impl MirBuilder {
     }
 
     /// Transform HIR struct to MIR type definition
-    fn transform_struct(&self, hir_struct: &crate::hir::HirStruct) -> OvieResult<MirTypeDef> {
+    fn transform_struct(&mut self, hir_struct: &crate::hir::HirStruct) -> OvieResult<MirTypeDef> {
         let mut fields = Vec::new();
         
         for field in &hir_struct.fields {
@@ -1153,18 +2543,17 @@ impl MirBuilder {
     }
 
     /// Transform HIR enum to MIR type definition
-    fn transform_enum(&self, hir_enum: &crate::hir::HirEnum) -> OvieResult<MirTypeDef> {
+    fn transform_enum(&mut self, hir_enum: &crate::hir::HirEnum) -> OvieResult<MirTypeDef> {
         let mut variants = Vec::new();
         
         for variant in &hir_enum.variants {
-            let fields = if let Some(ref data_type) = variant.data_type {
-                vec![MirFieldDef {
-                    name: "data".to_string(),
-                    ty: self.transform_type(data_type)?,
-                }]
-            } else {
-                Vec::new()
-            };
+            let mut fields = Vec::new();
+            for field in &variant.fields {
+                fields.push(MirFieldDef {
+                    name: field.name.clone(),
+                    ty: self.transform_type(&field.field_type)?,
+                });
+            }
             
             variants.push(MirVariantDef {
                 name: variant.name.clone(),
@@ -1174,12 +2563,6 @@ impl MirBuilder {
 
         Ok(MirTypeDef::Enum { variants })
     }
-
-    /// Check if statements contain a terminator
-    fn has_terminator(&self, _statements: &[MirStatement]) -> bool {
-        // Simplified check - in a real implementation, we'd check the last statement
-        false
-    }
 }
 
 impl Default for MirBuilder {
@@ -1344,8 +2727,8 @@ impl MirProgram {
                 MirProjectionElem::Deref => {
                     // Dereference is always valid at MIR level
                 }
-                MirProjectionElem::Field(_) => {
-                    // Field access is always valid at MIR level
+                MirProjectionElem::Field(_) | MirProjectionElem::Downcast(_) => {
+                    // Field access and downcasts are always valid at MIR level
                 }
                 MirProjectionElem::Index(local_id) => {
                     if *local_id > 1000 {
@@ -1407,7 +2790,7 @@ impl MirProgram {
 
     fn validate_operand_invariants(&self, operand: &MirOperand) -> Result<(), crate::ast::InvariantError> {
         match operand {
-            MirOperand::Copy(place) | MirOperand::Move(place) => {
+            MirOperand::Copy(place) | MirOperand::Move(place, _) => {
                 self.validate_place_invariants(place)?;
             }
             MirOperand::Constant(_) => {
@@ -1427,7 +2810,7 @@ impl MirProgram {
             MirTerminator::Goto { .. } => {
                 // Goto is always valid at MIR level
             }
-            MirTerminator::SwitchInt { discriminant, .. } => {
+            MirTerminator::SwitchInt { discriminant, .. } | MirTerminator::Assert { condition: discriminant, .. } => {
                 self.validate_operand_invariants(discriminant)?;
             }
             MirTerminator::Call { func, args, destination, .. } => {
@@ -1461,7 +2844,7 @@ impl MirProgram {
             if let Some(block) = function.basic_blocks.get(&block_id) {
                 // Add successors to visit list
                 match &block.terminator {
-                    MirTerminator::Goto { target } => {
+                    MirTerminator::Goto { target } | MirTerminator::Assert { target, .. } => {
                         to_visit.push(*target);
                     }
                     MirTerminator::SwitchInt { targets, otherwise, .. } => {
@@ -1516,6 +2899,15 @@ impl MirProgram {
         Ok(analysis)
     }
 
+    /// Run the dataflow analyses of `crate::dataflow` over every function
+    pub fn analyze_dataflow(&self) -> DataflowAnalysis {
+        let mut analysis = DataflowAnalysis::default();
+        for (func_id, function) in &self.functions {
+            analysis.function_analyses.insert(*func_id, FunctionDataflow::analyze(function));
+        }
+        analysis
+    }
+
     /// Generate human-readable IR report
     pub fn generate_ir_report(&self) -> OvieResult<String> {
         let mut report = String::new();
@@ -1567,10 +2959,65 @@ impl MirProgram {
                 }
             }
         }
+
+        // Dataflow analysis, at the boundaries of each block
+        report.push_str("=== Dataflow Analysis ===\n\n");
+        let dataflow = self.analyze_dataflow();
+        let mut func_ids: Vec<_> = self.functions.keys().copied().collect();
+        func_ids.sort();
+        for func_id in func_ids {
+            let function = &self.functions[&func_id];
+            let func_dataflow = &dataflow.function_analyses[&func_id];
+            report.push_str(&format!("Function: {}\n", function.name));
+            let mut block_ids: Vec<_> = function.basic_blocks.keys().copied().collect();
+            block_ids.sort();
+            for block_id in block_ids {
+                let locals = |locals: Option<&BTreeSet<LocalId>>| Self::local_names(function, locals.into_iter().flatten().copied());
+                let reaching: Vec<String> = func_dataflow.reaching_definitions.get(&block_id).into_iter().flatten()
+                    .map(|location| location.to_string())
+                    .collect();
+                report.push_str(&format!("  Block {}:\n", block_id));
+                report.push_str(&format!("    Live In: [{}]\n", locals(func_dataflow.live_in.get(&block_id))));
+                report.push_str(&format!("    Live Out: [{}]\n", locals(func_dataflow.live_out.get(&block_id))));
+                report.push_str(&format!("    Initialized In: [{}]\n", locals(func_dataflow.initialized.get(&block_id))));
+                report.push_str(&format!("    Reaching Definitions In: [{}]\n", reaching.join(", ")));
+                report.push_str(&format!("    Constants Out: [{}]\n", Self::constant_values(function, func_dataflow.constants.get(&block_id))));
+            }
+            report.push('\n');
+        }
         
         Ok(report)
     }
 
+    /// Names of `locals` in `function`, temporaries as `_N`, comma separated
+    fn local_names(function: &MirFunction, locals: impl Iterator<Item = LocalId>) -> String {
+        locals
+            .map(|local| function.locals.iter()
+                .find(|candidate| candidate.id == local)
+                .and_then(|candidate| candidate.name.clone())
+                .unwrap_or_else(|| format!("_{}", local)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Known constants as `name = value`, comma separated
+    fn constant_values(function: &MirFunction, constants: Option<&BTreeMap<LocalId, MirConstantValue>>) -> String {
+        constants.into_iter().flatten()
+            .map(|(local, value)| {
+                let value = match value {
+                    MirConstantValue::String(s) => format!("{:?}", s),
+                    MirConstantValue::Number(n) => n.to_string(),
+                    MirConstantValue::Integer(n) => n.to_string(),
+                    MirConstantValue::Boolean(b) => b.to_string(),
+                    MirConstantValue::Unit => "()".to_string(),
+                    MirConstantValue::Function(name) => name.clone(),
+                };
+                format!("{} = {}", Self::local_names(function, std::iter::once(*local)), value)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     /// Generate detailed function report
     fn generate_function_report(&self, function: &MirFunction) -> OvieResult<String> {
         let mut report = String::new();
@@ -1612,7 +3059,9 @@ impl MirProgram {
         dot.push_str("  rankdir=TB;\n");
         dot.push_str("  node [shape=box];\n\n");
         
+        let dataflow = self.analyze_dataflow();
         for (func_id, function) in &self.functions {
+            let func_dataflow = &dataflow.function_analyses[func_id];
             dot.push_str(&format!("  subgraph cluster_{} {{\n", func_id));
             dot.push_str(&format!("    label=\"Function: {}\";\n", function.name));
             dot.push_str("    style=filled;\n");
@@ -1620,7 +3069,12 @@ impl MirProgram {
             
             // Add basic blocks as nodes
             for (block_id, block) in &function.basic_blocks {
-                let label = format!("Block {}\\n{} statements", block_id, block.statements.len());
+                let live_in = Self::local_names(function, func_dataflow.live_in.get(block_id).into_iter().flatten().copied());
+                let constants = Self::constant_values(function, func_dataflow.constants.get(block_id))
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"");
+                let label = format!("Block {}\\n{} statements\\nlive in: {}\\nconstants out: {}",
+                                    block_id, block.statements.len(), live_in, constants);
                 dot.push_str(&format!("    \"{}_{}\", [label=\"{}\"];\n", func_id, block_id, label));
             }
             
@@ -1668,122 +3122,136 @@ impl MirProgram {
 
     /// Get successor blocks from a terminator
     fn get_block_successors(&self, terminator: &MirTerminator) -> Vec<BasicBlockId> {
-        match terminator {
-            MirTerminator::Return { .. } | MirTerminator::Unreachable => Vec::new(),
-            MirTerminator::Goto { target } => vec![*target],
-            MirTerminator::SwitchInt { targets, otherwise, .. } => {
-                let mut successors: Vec<_> = targets.iter().map(|(_, target)| *target).collect();
-                successors.push(*otherwise);
-                successors
-            }
-            MirTerminator::Call { target, cleanup, .. } => {
-                let mut successors = Vec::new();
-                if let Some(t) = target {
-                    successors.push(*t);
-                }
-                if let Some(c) = cleanup {
-                    successors.push(*c);
-                }
-                successors
-            }
-            MirTerminator::Drop { target, unwind, .. } => {
-                let mut successors = vec![*target];
-                if let Some(u) = unwind {
-                    successors.push(*u);
-                }
-                successors
-            }
-        }
+        terminator.successors()
     }
 
-    /// Compute dominator tree for a function
+    /// Compute the immediate dominator of every block reachable from the entry.
+    ///
+    /// The entry block maps to itself. Dominator sets are solved iteratively and
+    /// each block's immediate dominator is the strict dominator closest to it.
     fn compute_dominators(
         &self, 
         function: &MirFunction, 
         cfg_analysis: &FunctionCfgAnalysis
     ) -> OvieResult<HashMap<BasicBlockId, BasicBlockId>> {
-        let mut dominators = HashMap::new();
         let entry = function.entry_block;
-        
-        // Entry block dominates itself
-        dominators.insert(entry, entry);
-        
-        // Simple dominator computation (could be optimized)
+
+        // Blocks nothing reaches have no dominators
+        let mut reachable = vec![entry];
+        let mut worklist = vec![entry];
+        while let Some(block_id) = worklist.pop() {
+            for successor in cfg_analysis.successors.get(&block_id).into_iter().flatten() {
+                if !reachable.contains(successor) {
+                    reachable.push(*successor);
+                    worklist.push(*successor);
+                }
+            }
+        }
+        reachable.sort();
+
+        let all: BTreeSet<BasicBlockId> = reachable.iter().copied().collect();
+        let mut dominator_sets: HashMap<BasicBlockId, BTreeSet<BasicBlockId>> = reachable.iter()
+            .map(|&block_id| (block_id, if block_id == entry { BTreeSet::from([entry]) } else { all.clone() }))
+            .collect();
+
         let mut changed = true;
         while changed {
             changed = false;
-            
-            for &block_id in function.basic_blocks.keys() {
+            for &block_id in &reachable {
                 if block_id == entry {
                     continue;
                 }
-                
-                if let Some(predecessors) = cfg_analysis.predecessors.get(&block_id) {
-                    if !predecessors.is_empty() {
-                        // Find common dominator of all predecessors
-                        let mut new_dom = predecessors[0];
-                        for &pred in &predecessors[1..] {
-                            new_dom = self.find_common_dominator(new_dom, pred, &dominators);
-                        }
-                        
-                        if dominators.get(&block_id) != Some(&new_dom) {
-                            dominators.insert(block_id, new_dom);
-                            changed = true;
-                        }
+
+                // Dominated by itself and by every block dominating all of its predecessors
+                let mut new_set: Option<BTreeSet<BasicBlockId>> = None;
+                for predecessor in cfg_analysis.predecessors.get(&block_id).into_iter().flatten() {
+                    if let Some(predecessor_set) = dominator_sets.get(predecessor) {
+                        new_set = Some(match new_set {
+                            Some(set) => set.intersection(predecessor_set).copied().collect(),
+                            None => predecessor_set.clone(),
+                        });
                     }
                 }
+                let mut new_set = new_set.unwrap_or_default();
+                new_set.insert(block_id);
+
+                if dominator_sets[&block_id] != new_set {
+                    dominator_sets.insert(block_id, new_set);
+                    changed = true;
+                }
             }
         }
-        
-        Ok(dominators)
-    }
 
-    /// Find common dominator of two blocks
-    fn find_common_dominator(
-        &self,
-        block1: BasicBlockId,
-        block2: BasicBlockId,
-        dominators: &HashMap<BasicBlockId, BasicBlockId>
-    ) -> BasicBlockId {
-        // Simplified implementation - in practice would use more efficient algorithm
-        if let (Some(&dom1), Some(&dom2)) = (dominators.get(&block1), dominators.get(&block2)) {
-            if dom1 == dom2 {
-                dom1
+        // Strict dominators form a chain; the closest one is dominated by all the others
+        let mut dominators = HashMap::new();
+        for (&block_id, set) in &dominator_sets {
+            let immediate = if block_id == entry {
+                entry
             } else {
-                // Find common ancestor in dominator tree
-                dom1 // Simplified
-            }
-        } else {
-            block1 // Fallback
+                *set.iter()
+                    .find(|&&candidate| candidate != block_id && dominator_sets[&candidate].len() == set.len() - 1)
+                    .unwrap_or(&entry)
+            };
+            dominators.insert(block_id, immediate);
         }
+
+        Ok(dominators)
     }
 
-    /// Identify loops in the control flow graph
+    /// Identify the natural loops of the control flow graph.
+    ///
+    /// An edge to a block that dominates its source is a back edge. The back edges
+    /// to one header (the end of the body and every `continue`) form one loop, whose
+    /// exits are the edges out of its body (the failed condition and every `break`).
     fn identify_loops(
         &self,
         function: &MirFunction,
         cfg_analysis: &FunctionCfgAnalysis
     ) -> OvieResult<Vec<LoopInfo>> {
-        let mut loops = Vec::new();
-        
-        // Find back edges (edges to dominators)
-        for (block_id, block) in &function.basic_blocks {
-            let successors = self.get_block_successors(&block.terminator);
-            
-            for successor in successors {
-                if let Some(&dom) = cfg_analysis.dominators.get(block_id) {
-                    if successor == dom || self.dominates(successor, *block_id, &cfg_analysis.dominators) {
-                        // This is a back edge - indicates a loop
-                        loops.push(LoopInfo {
-                            header: successor,
-                            back_edge_source: *block_id,
-                            body: Vec::new(), // Would compute loop body
-                        });
+        let mut block_ids: Vec<BasicBlockId> = function.basic_blocks.keys().copied().collect();
+        block_ids.sort();
+
+        let mut loops: Vec<LoopInfo> = Vec::new();
+        for &block_id in &block_ids {
+            if !cfg_analysis.dominators.contains_key(&block_id) {
+                continue;
+            }
+            for &successor in cfg_analysis.successors.get(&block_id).into_iter().flatten() {
+                if successor != block_id && !self.dominates(successor, block_id, &cfg_analysis.dominators) {
+                    continue;
+                }
+                match loops.iter_mut().find(|info| info.header == successor) {
+                    Some(info) => info.back_edge_sources.push(block_id),
+                    None => loops.push(LoopInfo {
+                        header: successor,
+                        back_edge_sources: vec![block_id],
+                        body: Vec::new(),
+                        exits: Vec::new(),
+                    }),
+                }
+            }
+        }
+
+        for info in &mut loops {
+            // The body is every block that reaches a back edge without passing the header
+            let mut body = BTreeSet::from([info.header]);
+            let mut worklist = info.back_edge_sources.clone();
+            while let Some(block_id) = worklist.pop() {
+                if body.insert(block_id) {
+                    worklist.extend(cfg_analysis.predecessors.get(&block_id).into_iter().flatten().copied());
+                }
+            }
+
+            for &block_id in &body {
+                for &successor in cfg_analysis.successors.get(&block_id).into_iter().flatten() {
+                    if !body.contains(&successor) {
+                        info.exits.push((block_id, successor));
                     }
                 }
             }
+            info.body = body.into_iter().collect();
         }
-        
+
         Ok(loops)
     }
 
@@ -1876,7 +3344,7 @@ impl MirProgram {
     /// Validate a MIR terminator
     fn validate_terminator(&self, terminator: &MirTerminator, function: &MirFunction) -> OvieResult<()> {
         match terminator {
-            MirTerminator::Goto { target } => {
+            MirTerminator::Goto { target } | MirTerminator::Assert { target, .. } => {
                 if !function.basic_blocks.contains_key(target) {
                     return Err(OvieError::IrError {
                         message: format!("Target block {} not found in function {}", target, function.name),
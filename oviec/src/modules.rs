@@ -0,0 +1,550 @@
+//! Module system for Ovie
+//!
+//! A program is a graph of file-based modules. The entry file is the root module;
+//! `use a::b::{X, Y};` loads `a::b` and imports its public items, and `mod name;`
+//! loads a child module of the current one. Module paths are always written from
+//! the project root: `a::b` is `src/a/b.ov` (or `src/a/b/mod.ov`) under the
+//! directory holding `ovie.toml`, falling back to the bundled standard library.
+//!
+//! Items of the root module keep their plain names; items of any other module are
+//! known by their qualified name (`a::b::name`) in later stages, so two modules may
+//! define items with the same name.
+
+use crate::ast::{AstNode, Statement};
+use crate::error::{OvieError, OvieResult, ResolutionErrorKind};
+use crate::runtime_environment::OvieRuntimeEnvironment;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Path of a module from the project root; empty for the root module
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ModulePath(pub Vec<String>);
+
+impl ModulePath {
+    /// Path of the root (entry) module
+    pub fn root() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Path of the child module `name` of this module
+    pub fn child(&self, name: &str) -> Self {
+        let mut segments = self.0.clone();
+        segments.push(name.to_string());
+        Self(segments)
+    }
+
+    /// Qualified name of the item `name` defined in this module
+    pub fn qualify(&self, name: &str) -> String {
+        if self.is_root() {
+            name.to_string()
+        } else {
+            format!("{}::{}", self, name)
+        }
+    }
+
+    /// Attribute an error raised while compiling this module, loaded from `file`, to
+    /// the file. Errors in the root module are returned unchanged.
+    pub fn attribute_error(&self, file: Option<&Path>, error: OvieError) -> OvieError {
+        match (file, self.is_root()) {
+            (Some(file), false) => {
+                let at = match error.location() {
+                    Some(position) => format!("{}:{}:{}", file.display(), position.line, position.column),
+                    None => file.display().to_string(),
+                };
+                OvieError::compile_error(format!("In module '{}' ({}): {}", self, at, error))
+            }
+            _ => error,
+        }
+    }
+}
+
+impl fmt::Display for ModulePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            write!(f, "main")
+        } else {
+            write!(f, "{}", self.0.join("::"))
+        }
+    }
+}
+
+/// Names visible inside one module
+#[derive(Debug, Clone, Default)]
+pub struct ModuleScope {
+    /// Unqualified names usable in the module (its own items and its imports),
+    /// mapped to their qualified names
+    names: HashMap<String, String>,
+    /// Qualified names of items other modules keep private, mapped to their module
+    private: HashMap<String, ModulePath>,
+}
+
+impl ModuleScope {
+    /// Unqualified names usable in the module, with the qualified names they stand for
+    pub fn names(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.names.iter().map(|(name, qualified)| (name.as_str(), qualified.as_str()))
+    }
+
+    /// Qualified name of the item `name` refers to in this module.
+    ///
+    /// Names that are neither items nor imports (locals, builtins, or already
+    /// qualified paths) come back unchanged.
+    pub fn resolve(&self, name: &str) -> OvieResult<String> {
+        if let Some(qualified) = self.names.get(name) {
+            return Ok(qualified.clone());
+        }
+        if let Some(module) = self.private.get(name) {
+            let item = name.rsplit("::").next().unwrap_or(name);
+            return Err(OvieError::kind_error(
+                0, 0,
+                ResolutionErrorKind::Item,
+                item,
+                format!("'{}' is private to module '{}'", item, module)
+            ));
+        }
+        Ok(name.to_string())
+    }
+}
+
+/// One parsed source file of a program
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub path: ModulePath,
+    /// File the module was loaded from; `None` for in-memory source
+    pub file: Option<PathBuf>,
+    pub ast: AstNode,
+    pub scope: ModuleScope,
+}
+
+impl Module {
+    /// Attribute an error raised while compiling this module to its file.
+    /// Errors in the root module are returned unchanged.
+    pub fn attribute_error(&self, error: OvieError) -> OvieError {
+        self.path.attribute_error(self.file.as_deref(), error)
+    }
+}
+
+/// All modules of a program, in dependency order with the root module last
+#[derive(Debug, Clone)]
+pub struct ModuleGraph {
+    modules: Vec<Module>,
+}
+
+impl ModuleGraph {
+    /// Graph of a single in-memory source with no imports
+    pub fn single(ast: AstNode) -> OvieResult<Self> {
+        let AstNode::Program(statements) = &ast;
+        if let Some(statement) = statements.iter()
+            .find(|statement| matches!(statement, Statement::Use { .. } | Statement::Module { .. }))
+        {
+            return Err(OvieError::semantic_error(
+                0, 0,
+                "'use' and 'mod' need a source file; compile the file instead of a source string"
+            ).located_at(statement.span().start_position()));
+        }
+
+        Self::link(vec![Module {
+            path: ModulePath::root(),
+            file: None,
+            ast,
+            scope: ModuleScope::default(),
+        }])
+    }
+
+    /// Load the program whose root module is `entry`, parsing each file with `parse`
+    pub fn load<F>(entry: &Path, parse: F) -> OvieResult<Self>
+    where
+        F: FnMut(&str) -> OvieResult<AstNode>,
+    {
+        ModuleLoader::for_entry(entry).load(entry, parse)
+    }
+
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
+
+    /// The entry module
+    pub fn root(&self) -> &Module {
+        self.modules.last().expect("a module graph always has a root module")
+    }
+
+    /// Build every module's scope, checking imports and visibility
+    fn link(mut modules: Vec<Module>) -> OvieResult<Self> {
+        // Qualified name of every item -> (defining module, public)
+        let mut items: HashMap<String, (ModulePath, bool)> = HashMap::new();
+        for module in &modules {
+            if !module.path.is_root() {
+                Self::check_top_level(module)?;
+            }
+            for (name, public) in Self::items(&module.ast) {
+                items.insert(module.path.qualify(name), (module.path.clone(), public));
+            }
+        }
+
+        for module in &mut modules {
+            let mut scope = ModuleScope::default();
+            for (name, _) in Self::items(&module.ast) {
+                scope.names.insert(name.to_string(), module.path.qualify(name));
+            }
+            for (qualified, (owner, public)) in &items {
+                if !public && *owner != module.path {
+                    scope.private.insert(qualified.clone(), owner.clone());
+                }
+            }
+
+            let AstNode::Program(statements) = &module.ast;
+            let mut imported = HashSet::new();
+            for statement in statements {
+                let Statement::Use { path, names, span } = statement else { continue };
+                let source = ModuleLoader::canonical_path(path);
+                let result = names.iter().try_for_each(|name| {
+                    let qualified = source.qualify(name);
+                    match items.get(&qualified) {
+                        None => Err(OvieError::kind_error(
+                            0, 0,
+                            ResolutionErrorKind::Item,
+                            name,
+                            format!("Module '{}' has no item '{}'", source, name)
+                        )),
+                        Some((_, false)) => Err(OvieError::kind_error(
+                            0, 0,
+                            ResolutionErrorKind::Item,
+                            name,
+                            format!("'{}' is private to module '{}'", name, source)
+                        )),
+                        Some(_) if scope.names.contains_key(name) && !imported.contains(name) => Err(OvieError::semantic_error(
+                            0, 0,
+                            format!("Import of '{}' conflicts with an item of the same name", name)
+                        )),
+                        Some(_) if !imported.insert(name.clone()) => Err(OvieError::semantic_error(
+                            0, 0,
+                            format!("'{}' is imported more than once", name)
+                        )),
+                        Some(_) => {
+                            scope.names.insert(name.clone(), qualified);
+                            Ok(())
+                        }
+                    }
+                });
+                result.map_err(|e| module.attribute_error(e.located_at(span.start_position())))?;
+            }
+            module.scope = scope;
+        }
+
+        Ok(Self { modules })
+    }
+
+    /// Names of the items a module defines, with whether each is `pub`
+    fn items(ast: &AstNode) -> Vec<(&str, bool)> {
+        let AstNode::Program(statements) = ast;
+        statements.iter()
+            .filter_map(|statement| match statement {
+                Statement::Function { name, public, .. }
+                | Statement::Struct { name, public, .. }
+                | Statement::Enum { name, public, .. }
+                | Statement::Trait { name, public, .. }
+                | Statement::Const { name, public, .. } => Some((name.as_str(), *public)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Only the root module may contain top-level statements
+    fn check_top_level(module: &Module) -> OvieResult<()> {
+        let AstNode::Program(statements) = &module.ast;
+        for statement in statements {
+            if !statement.is_item() {
+                return Err(module.attribute_error(OvieError::semantic_error(
+                    0, 0,
+                    format!("Only items can appear at the top level of module '{}'", module.path)
+                ).located_at(statement.span().start_position())));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Finds and parses the files of a module graph
+#[derive(Debug, Clone)]
+pub struct ModuleLoader {
+    /// Directory holding the project's modules
+    source_dir: PathBuf,
+    /// Root of the standard library, if one was found
+    std_dir: Option<PathBuf>,
+}
+
+impl ModuleLoader {
+    pub fn new(source_dir: PathBuf, std_dir: Option<PathBuf>) -> Self {
+        Self { source_dir, std_dir }
+    }
+
+    /// Loader for the project containing `entry`: modules live in `src/` next to the
+    /// nearest `ovie.toml`, or beside the entry file when there is no project
+    pub fn for_entry(entry: &Path) -> Self {
+        let entry_dir = entry.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let source_dir = entry_dir.ancestors()
+            .find(|dir| dir.join("ovie.toml").is_file())
+            .map(|root| root.join("src"))
+            .filter(|src| src.is_dir())
+            .unwrap_or(entry_dir);
+
+        Self::new(source_dir, Self::default_std_dir())
+    }
+
+    /// The standard library of the discovered ORE (`OVIE_HOME`, the directory of the
+    /// executable, or a system location)
+    #[cfg(not(test))]
+    fn default_std_dir() -> Option<PathBuf> {
+        OvieRuntimeEnvironment::discover().ok().map(|ore| ore.std_dir)
+    }
+
+    /// Unit tests fall back to the standard library in the source tree
+    #[cfg(test)]
+    fn default_std_dir() -> Option<PathBuf> {
+        OvieRuntimeEnvironment::discover().ok().map(|ore| ore.std_dir)
+            .or_else(|| Some(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../std")).to_path_buf()))
+    }
+
+    /// Module path named by a `use`, without the optional leading `std`
+    fn canonical_path(path: &[String]) -> ModulePath {
+        match path.split_first() {
+            Some((first, rest)) if first == "std" && !rest.is_empty() => ModulePath(rest.to_vec()),
+            _ => ModulePath(path.to_vec()),
+        }
+    }
+
+    /// File defining the module at `path`: project modules shadow the standard library
+    fn locate(&self, path: &ModulePath, std_only: bool) -> Option<PathBuf> {
+        let relative: PathBuf = path.0.iter().collect();
+        let project = (!std_only).then(|| self.source_dir.clone());
+        project.into_iter()
+            .chain(self.std_dir.clone())
+            .flat_map(|dir| [
+                dir.join(&relative).with_extension("ov"),
+                dir.join(&relative).join("mod.ov"),
+            ])
+            .find(|file| file.is_file())
+    }
+
+    /// Load `entry` and every module it reaches, in dependency order
+    pub fn load<F>(&self, entry: &Path, mut parse: F) -> OvieResult<ModuleGraph>
+    where
+        F: FnMut(&str) -> OvieResult<AstNode>,
+    {
+        let mut state = LoadState::default();
+        self.load_module(ModulePath::root(), entry.to_path_buf(), &mut parse, &mut state)?;
+        ModuleGraph::link(state.modules)
+    }
+
+    fn load_module<F>(&self, path: ModulePath, file: PathBuf, parse: &mut F, state: &mut LoadState) -> OvieResult<()>
+    where
+        F: FnMut(&str) -> OvieResult<AstNode>,
+    {
+        let source = fs::read_to_string(&file).map_err(|e| OvieError::io_error(
+            format!("Failed to read module '{}' from {}: {}", path, file.display(), e)
+        ))?;
+
+        let module = Module { path: path.clone(), file: Some(file), ast: AstNode::Program(Vec::new()), scope: ModuleScope::default() };
+        let ast = parse(&source).map_err(|e| module.attribute_error(e))?;
+        let module = Module { ast, ..module };
+
+        state.loading.push(path.clone());
+        let AstNode::Program(statements) = &module.ast;
+        for statement in statements {
+            let (dependency, std_only, span) = match statement {
+                Statement::Use { path: use_path, span, .. } => {
+                    let std_only = use_path.first().is_some_and(|first| first == "std") && use_path.len() > 1;
+                    (Self::canonical_path(use_path), std_only, span)
+                }
+                Statement::Module { name, span } => (path.child(name), false, span),
+                _ => continue,
+            };
+
+            if let Some(start) = state.loading.iter().position(|loading| *loading == dependency) {
+                let cycle: Vec<String> = state.loading[start..].iter()
+                    .chain(std::iter::once(&dependency))
+                    .map(|module| module.to_string())
+                    .collect();
+                return Err(module.attribute_error(OvieError::semantic_error(
+                    0, 0,
+                    format!("Import cycle between modules: {}", cycle.join(" -> "))
+                ).located_at(span.start_position())));
+            }
+            if state.loaded.contains(&dependency) {
+                continue;
+            }
+
+            let dependency_file = self.locate(&dependency, std_only).ok_or_else(|| {
+                let hint = if self.std_dir.is_none() {
+                    " (no standard library was found; set OVIE_HOME to the Ovie installation)"
+                } else {
+                    ""
+                };
+                module.attribute_error(
+                    OvieError::kind_error(0, 0, ResolutionErrorKind::Module, &dependency.to_string(),
+                        format!("Unresolved module '{}'{}", dependency, hint))
+                        .located_at(span.start_position())
+                )
+            })?;
+            self.load_module(dependency, dependency_file, parse, state)?;
+        }
+        state.loading.pop();
+
+        state.loaded.insert(path);
+        state.modules.push(module);
+        Ok(())
+    }
+}
+
+/// Progress of a depth-first module load
+#[derive(Default)]
+struct LoadState {
+    modules: Vec<Module>,
+    loaded: HashSet<ModulePath>,
+    /// Modules whose imports are being loaded, outermost first
+    loading: Vec<ModulePath>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn parse(source: &str) -> OvieResult<AstNode> {
+        let tokens = Lexer::new(source).tokenize()?;
+        Parser::new(tokens).parse()
+    }
+
+    fn project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("ovie.toml"), "[project]\nname = \"demo\"\n").unwrap();
+        for (name, source) in files {
+            let file = dir.path().join("src").join(name);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, source).unwrap();
+        }
+        dir
+    }
+
+    fn load(dir: &tempfile::TempDir) -> OvieResult<ModuleGraph> {
+        let entry = dir.path().join("src/main.ov");
+        ModuleLoader::new(dir.path().join("src"), None).load(&entry, parse)
+    }
+
+    #[test]
+    fn test_use_resolves_items_in_dependency_order() {
+        let dir = project(&[
+            ("main.ov", "use geometry::shapes::{Square, area};\nseeAm area(Square { side: 2 });"),
+            ("geometry/shapes.ov", "use geometry::{square};\npub struct Square { side: Number }\npub fn area(s: Square) -> Number { return square(s.side); }"),
+            ("geometry/mod.ov", "pub fn square(x: Number) -> Number { return x * x; }"),
+        ]);
+        let graph = load(&dir).unwrap();
+
+        let paths: Vec<String> = graph.modules().iter().map(|module| module.path.to_string()).collect();
+        assert_eq!(paths, vec!["geometry", "geometry::shapes", "main"]);
+        assert_eq!(graph.root().scope.resolve("area").unwrap(), "geometry::shapes::area");
+        assert_eq!(graph.root().scope.resolve("Square").unwrap(), "geometry::shapes::Square");
+        assert_eq!(graph.modules()[1].scope.resolve("square").unwrap(), "geometry::square");
+        // Locals and builtins are not items and resolve to themselves
+        assert_eq!(graph.root().scope.resolve("x").unwrap(), "x");
+    }
+
+    #[test]
+    fn test_private_items_and_bad_imports_are_rejected() {
+        let dir = project(&[
+            ("main.ov", "use util::{helper};\nseeAm helper();"),
+            ("util.ov", "fn helper() { return 1; }"),
+        ]);
+        assert!(load(&dir).unwrap_err().to_string().contains("'helper' is private to module 'util'"));
+
+        let dir = project(&[
+            ("main.ov", "mod util;\nseeAm util::helper();"),
+            ("util.ov", "fn helper() { return 1; }"),
+        ]);
+        let graph = load(&dir).unwrap();
+        assert!(graph.root().scope.resolve("util::helper").unwrap_err().to_string().contains("private"));
+
+        let dir = project(&[("main.ov", "use missing::{thing};")]);
+        assert!(load(&dir).unwrap_err().to_string().contains("Unresolved module 'missing' (no standard library was found"));
+        let with_std = ModuleLoader::new(dir.path().join("src"), Some(bundled_std_dir()))
+            .load(&dir.path().join("src/main.ov"), parse).unwrap_err();
+        assert!(with_std.to_string().ends_with("Unresolved module 'missing'"), "{}", with_std);
+
+        let dir = project(&[
+            ("main.ov", "use util::{nothing};"),
+            ("util.ov", "pub fn helper() { return 1; }"),
+        ]);
+        assert!(load(&dir).unwrap_err().to_string().contains("Module 'util' has no item 'nothing'"));
+    }
+
+    fn bundled_std_dir() -> PathBuf {
+        Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../std")).to_path_buf()
+    }
+
+    #[test]
+    fn test_use_loads_the_bundled_core_module() {
+        let dir = project(&[("main.ov", "use core::{Option, some};\nseeAm some(1).is_some();")]);
+        let graph = ModuleLoader::new(dir.path().join("src"), Some(bundled_std_dir()))
+            .load(&dir.path().join("src/main.ov"), parse)
+            .unwrap();
+
+        let paths: Vec<String> = graph.modules().iter().map(|module| module.path.to_string()).collect();
+        assert_eq!(paths, vec!["core", "main"]);
+        assert!(graph.modules()[0].file.as_ref().unwrap().ends_with("std/core/mod.ov"));
+        assert_eq!(graph.root().scope.resolve("Option").unwrap(), "core::Option");
+        assert_eq!(graph.root().scope.resolve("some").unwrap(), "core::some");
+    }
+
+    #[test]
+    fn test_every_std_module_loads() {
+        let imports = [
+            ("cli", "App, CliOption"),
+            ("core", "Option, Vec, panic"),
+            ("fs", "File, FileMode"),
+            ("io", "Stdin, stdout"),
+            ("log", "Logger, LogLevel"),
+            ("math", "sqrt, PI"),
+            ("testing", "TestCase, TestSuite"),
+            ("time", "Duration, Instant"),
+        ];
+        let mut modules: Vec<String> = fs::read_dir(bundled_std_dir()).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        modules.sort();
+        assert_eq!(modules, imports.iter().map(|(module, _)| module.to_string()).collect::<Vec<_>>());
+
+        for (module, names) in imports {
+            let dir = project(&[("main.ov", &format!("use std::{}::{{{}}};\nseeAm 1;", module, names))]);
+            let graph = ModuleLoader::new(dir.path().join("src"), Some(bundled_std_dir()))
+                .load(&dir.path().join("src/main.ov"), |source| crate::Compiler::new().compile_to_ast(source))
+                .unwrap_or_else(|e| panic!("std::{} should load: {}", module, e));
+            for name in names.split(", ") {
+                assert_eq!(graph.root().scope.resolve(name).unwrap(), format!("{}::{}", module, name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_import_cycles_are_reported() {
+        let dir = project(&[
+            ("main.ov", "use a::{f};\nseeAm f();"),
+            ("a.ov", "use b::{g};\npub fn f() { return g(); }"),
+            ("b.ov", "use a::{f};\npub fn g() { return 1; }"),
+        ]);
+        let error = load(&dir).unwrap_err().to_string();
+        assert!(error.contains("Import cycle between modules: a -> b -> a"), "{}", error);
+    }
+
+    #[test]
+    fn test_single_source_rejects_imports() {
+        let ast = parse("use util::{helper};\nseeAm 1;").unwrap();
+        assert!(ModuleGraph::single(ast).is_err());
+        assert!(ModuleGraph::single(parse("seeAm 1;").unwrap()).is_ok());
+    }
+}
@@ -1,5 +1,6 @@
-use crate::ast::{AstNode, Statement, Expression};
+use crate::ast::{AstNode, Statement, Expression, Pattern};
 use crate::error::OvieResult;
+use crate::hir::BUILTIN_FUNCTIONS;
 use std::collections::HashMap;
 
 /// Represents a correction made by the normalizer
@@ -43,8 +44,6 @@ impl Normalizer {
         
         // Variable declaration typos
         typo_corrections.insert("var".to_string(), "mut".to_string());
-        typo_corrections.insert("let".to_string(), "".to_string()); // Remove let, use immutable by default
-        typo_corrections.insert("const".to_string(), "".to_string());
         
         Self {
             corrections: Vec::new(),
@@ -85,36 +84,53 @@ impl Normalizer {
     /// Normalize a single statement
     fn normalize_statement(&mut self, statement: &mut Statement) -> OvieResult<()> {
         match statement {
-            Statement::Print { expression } => {
+            Statement::Print { expression, .. } => {
                 self.normalize_expression(expression)?;
             }
             Statement::Assignment { identifier, value, .. } => {
                 self.normalize_identifier(identifier)?;
                 self.normalize_expression(value)?;
             }
+            Statement::PlaceAssignment { target, value, .. } => {
+                self.normalize_expression(target)?;
+                self.normalize_expression(value)?;
+            }
             Statement::VariableDeclaration { identifier, value, .. } => {
                 self.normalize_identifier(identifier)?;
                 self.normalize_expression(value)?;
             }
-            Statement::Function { name, parameters, body } => {
+            Statement::Destructure { pattern, value, .. } => {
+                self.normalize_pattern(pattern)?;
+                self.normalize_expression(value)?;
+            }
+            Statement::Const { name, value, .. } => {
+                self.normalize_identifier(name)?;
+                self.normalize_expression(value)?;
+            }
+            Statement::Function { name, parameters, body, .. } => {
                 self.normalize_identifier(name)?;
                 for param in parameters {
-                    self.normalize_identifier(param)?;
+                    self.normalize_identifier(&mut param.name)?;
                 }
                 for stmt in body {
                     self.normalize_statement(stmt)?;
                 }
             }
-            Statement::FunctionDeclaration { name, parameters, body } => {
+            Statement::FunctionDeclaration { name, parameters, body, .. } => {
                 self.normalize_identifier(name)?;
                 for param in parameters {
-                    self.normalize_identifier(param)?;
+                    self.normalize_identifier(&mut param.name)?;
+                }
+                for stmt in body {
+                    self.normalize_statement(stmt)?;
                 }
+            }
+            Statement::Unsafe { body, .. } => {
                 for stmt in body {
                     self.normalize_statement(stmt)?;
                 }
             }
-            Statement::If { condition, then_block, else_block } => {
+            Statement::If { condition, then_block, else_block, .. } => {
                 self.normalize_expression(condition)?;
                 for stmt in then_block {
                     self.normalize_statement(stmt)?;
@@ -125,33 +141,78 @@ impl Normalizer {
                     }
                 }
             }
-            Statement::While { condition, body } => {
+            Statement::While { label, condition, body, .. } => {
+                if let Some(label) = label {
+                    self.normalize_identifier(label)?;
+                }
                 self.normalize_expression(condition)?;
                 for stmt in body {
                     self.normalize_statement(stmt)?;
                 }
             }
-            Statement::For { identifier, iterable, body } => {
-                self.normalize_identifier(identifier)?;
+            Statement::For { label, pattern, iterable, body, .. } => {
+                if let Some(label) = label {
+                    self.normalize_identifier(label)?;
+                }
+                self.normalize_pattern(pattern)?;
                 self.normalize_expression(iterable)?;
                 for stmt in body {
                     self.normalize_statement(stmt)?;
                 }
             }
-            Statement::Return { value } => {
+            Statement::Return { value, .. } => {
                 if let Some(expr) = value {
                     self.normalize_expression(expr)?;
                 }
             }
-            Statement::Expression { expression } => {
+            Statement::Break { label, .. } | Statement::Continue { label, .. } => {
+                // Labels are renamed like the loops that declare them
+                if let Some(label) = label {
+                    self.normalize_identifier(label)?;
+                }
+            }
+            Statement::Expression { expression, .. } => {
                 self.normalize_expression(expression)?;
             }
-            Statement::Struct { name, .. } => {
+            Statement::Struct { name, fields, .. } => {
                 self.normalize_identifier(name)?;
+                // Field accesses are normalized, so the declared fields must match them
+                for field in fields {
+                    self.normalize_identifier(&mut field.name)?;
+                }
             }
             Statement::Enum { name, .. } => {
                 self.normalize_identifier(name)?;
             }
+            Statement::Impl { trait_name, type_name, methods, .. } => {
+                if let Some(trait_name) = trait_name {
+                    self.normalize_identifier(trait_name)?;
+                }
+                self.normalize_identifier(type_name)?;
+                for method in methods {
+                    self.normalize_statement(method)?;
+                }
+            }
+            Statement::Trait { name, methods, .. } => {
+                self.normalize_identifier(name)?;
+                for method in methods {
+                    self.normalize_identifier(&mut method.name)?;
+                    for param in &mut method.parameters {
+                        self.normalize_identifier(&mut param.name)?;
+                    }
+                    for stmt in method.body.iter_mut().flatten() {
+                        self.normalize_statement(stmt)?;
+                    }
+                }
+            }
+            Statement::Use { names, .. } => {
+                // Imported names must match the normalized definitions
+                for name in names {
+                    self.normalize_identifier(name)?;
+                }
+            }
+            Statement::Module { .. } => {}
+            Statement::Error { .. } => {}
         }
         Ok(())
     }
@@ -159,7 +220,7 @@ impl Normalizer {
     /// Normalize an expression
     fn normalize_expression(&mut self, expression: &mut Expression) -> OvieResult<()> {
         match expression {
-            Expression::Identifier(name) => {
+            Expression::Identifier(name, _) => {
                 self.normalize_identifier(name)?;
             }
             Expression::Binary { left, right, .. } => {
@@ -169,51 +230,109 @@ impl Normalizer {
             Expression::Unary { operand, .. } => {
                 self.normalize_expression(operand)?;
             }
-            Expression::Call { function, arguments } => {
+            Expression::Call { function, arguments, .. } => {
                 self.normalize_identifier(function)?;
                 for arg in arguments {
                     self.normalize_expression(arg)?;
                 }
             }
-            Expression::FieldAccess { object, field } => {
+            Expression::FieldAccess { object, field, .. } => {
                 self.normalize_expression(object)?;
                 self.normalize_identifier(field)?;
             }
-            Expression::StructInstantiation { struct_name, fields } => {
+            Expression::StructInstantiation { struct_name, fields, .. } => {
                 self.normalize_identifier(struct_name)?;
                 for field in fields {
-                    // Note: field.name is not mutable in the current AST design
-                    // This would require AST changes to make fields mutable
+                    self.normalize_identifier(&mut field.name)?;
                     self.normalize_expression(&mut field.value)?;
                 }
             }
-            Expression::Range { start, end } => {
+            Expression::Range { start, end, .. } => {
                 self.normalize_expression(start)?;
                 self.normalize_expression(end)?;
             }
-            Expression::EnumVariantConstruction { enum_name, variant_name, data } => {
+            Expression::EnumVariantConstruction { enum_name, variant_name, payload, .. } => {
                 self.normalize_identifier(enum_name)?;
                 self.normalize_identifier(variant_name)?;
-                if let Some(data_expr) = data {
-                    self.normalize_expression(data_expr)?;
+                for value in payload.values_mut() {
+                    self.normalize_expression(value)?;
                 }
             }
-            Expression::Index { object, index } => {
+            Expression::Index { object, index, .. } => {
                 self.normalize_expression(object)?;
                 self.normalize_expression(index)?;
             }
-            Expression::ArrayLiteral { elements } => {
+            Expression::ArrayLiteral { elements, .. } | Expression::Tuple { elements, .. } => {
                 for element in elements {
                     self.normalize_expression(element)?;
                 }
             }
-            Expression::Literal(_) => {
+            Expression::Match { scrutinee, arms, .. } => {
+                self.normalize_expression(scrutinee)?;
+                for arm in arms {
+                    self.normalize_pattern(&mut arm.pattern)?;
+                    for stmt in &mut arm.body {
+                        self.normalize_statement(stmt)?;
+                    }
+                    if let Some(value) = &mut arm.value {
+                        self.normalize_expression(value)?;
+                    }
+                }
+            }
+            Expression::Closure { parameters, body, .. } => {
+                for param in parameters {
+                    self.normalize_identifier(&mut param.name)?;
+                }
+                for stmt in body {
+                    self.normalize_statement(stmt)?;
+                }
+            }
+            Expression::IndirectCall { callee, arguments, .. } => {
+                self.normalize_expression(callee)?;
+                for arg in arguments {
+                    self.normalize_expression(arg)?;
+                }
+            }
+            Expression::MethodCall { receiver, method, arguments, .. } => {
+                self.normalize_expression(receiver)?;
+                self.normalize_identifier(method)?;
+                for arg in arguments {
+                    self.normalize_expression(arg)?;
+                }
+            }
+            Expression::Try { operand, .. } => {
+                self.normalize_expression(operand)?;
+            }
+            Expression::Literal(..) => {
                 // Literals don't need normalization
             }
         }
         Ok(())
     }
 
+    /// Normalize the names a pattern refers to or binds
+    fn normalize_pattern(&mut self, pattern: &mut Pattern) -> OvieResult<()> {
+        match pattern {
+            Pattern::Binding(name, _) => {
+                self.normalize_identifier(name)?;
+            }
+            Pattern::Variant { enum_name, variant_name, payload, .. } => {
+                self.normalize_identifier(enum_name)?;
+                self.normalize_identifier(variant_name)?;
+                for field in payload.values_mut() {
+                    self.normalize_pattern(field)?;
+                }
+            }
+            Pattern::Tuple(elements, _) => {
+                for element in elements {
+                    self.normalize_pattern(element)?;
+                }
+            }
+            Pattern::Wildcard(_) | Pattern::Literal(..) => {}
+        }
+        Ok(())
+    }
+
     /// Normalize an identifier (check for typos)
     fn normalize_identifier(&mut self, identifier: &mut String) -> OvieResult<()> {
         let original = identifier.clone();
@@ -232,8 +351,8 @@ impl Normalizer {
             }
         }
 
-        // Normalize naming conventions
-        if original.contains("_") && !original.starts_with("_") {
+        // Normalize naming conventions; built-in functions keep their names
+        if original.contains("_") && !original.starts_with("_") && !BUILTIN_FUNCTIONS.contains(&original.as_str()) {
             let camel_case = self.to_camel_case(&original);
             if camel_case != original && self.is_safe_correction(&original, &camel_case) {
                 *identifier = camel_case.clone();
@@ -319,7 +438,7 @@ impl Normalizer {
         // Apply source-level corrections
         for (typo, correction) in sorted_typos {
             if normalized.contains(typo) {
-                let corrected_source = normalized.replace(typo, correction);
+                let corrected_source = Self::replace_word(&normalized, typo, correction);
                 if corrected_source != normalized {
                     corrections.push(Correction {
                         original: typo.clone(),
@@ -336,10 +455,33 @@ impl Normalizer {
         (normalized, corrections)
     }
 
+    /// Replace whole-word occurrences of `word`, so `var` is corrected but `invariant` is left alone
+    fn replace_word(source: &str, word: &str, replacement: &str) -> String {
+        let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+        let mut result = String::with_capacity(source.len());
+        let mut rest = source;
+        while let Some(index) = rest.find(word) {
+            let (before, after) = (&rest[..index], &rest[index + word.len()..]);
+            let previous = before.chars().next_back().or_else(|| result.chars().next_back());
+            let at_boundary = !matches!(previous, Some(c) if is_identifier_char(c))
+                && !matches!(after.chars().next(), Some(c) if is_identifier_char(c));
+            result.push_str(before);
+            result.push_str(if at_boundary { replacement } else { word });
+            rest = after;
+        }
+        result.push_str(rest);
+        result
+    }
+
     /// Get all corrections made during normalization
     pub fn get_corrections(&self) -> &[Correction] {
         &self.corrections
     }
+
+    /// Whether normalizing source rewrites the word `word`, so it never reaches the parser
+    pub fn rewrites(&self, word: &str) -> bool {
+        self.typo_corrections.contains_key(word)
+    }
 }
 
 impl Default for Normalizer {
@@ -73,7 +73,7 @@ pub struct PackageSignature {
 }
 
 /// Offline-first compliance metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct OfflineMetadata {
     /// Whether package requires network access
     pub requires_network: bool,
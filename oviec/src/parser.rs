@@ -2,10 +2,11 @@
 
 use crate::ast::{
     AstNode, Statement, Expression, Literal, BinaryOperator, UnaryOperator,
-    StructField, EnumVariant, FieldInitializer
+    StructField, EnumVariant, VariantPayload, FieldInitializer, SourceSpan, Parameter, TypeAnnotation,
+    MatchArm, Pattern, TypeBound, TraitMethod, ContractClause,
 };
-use crate::error::OvieError;
-use crate::lexer::{Token, TokenType};
+use crate::error::{Diagnostic, OvieError};
+use crate::lexer::{unexpected_character, Token, TokenType};
 
 /// Result type for parsing operations
 pub type ParseResult<T> = Result<T, OvieError>;
@@ -14,45 +15,161 @@ pub type ParseResult<T> = Result<T, OvieError>;
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    errors: Vec<OvieError>,
+    /// Number of enclosing `()`, `[]` and struct literal braces, inside which
+    /// line breaks never end an expression
+    delimiters: usize,
 }
 
 impl Parser {
     /// Create a new parser with the given tokens
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, current: 0 }
+        Self { tokens, current: 0, errors: Vec::new(), delimiters: 0 }
     }
 
-    /// Parse the tokens into an AST
+    /// Parse the tokens into an AST, failing with the first syntax error
     pub fn parse(&mut self) -> ParseResult<AstNode> {
+        let ast = self.program();
+
+        match self.errors.first() {
+            Some(error) => Err(error.clone()),
+            None => Ok(ast),
+        }
+    }
+
+    /// Parse the tokens into an AST, recovering from syntax errors
+    ///
+    /// Every statement that fails to parse is replaced by a `Statement::Error`
+    /// node, so the returned AST covers the rest of the file. The diagnostics
+    /// list every syntax error in source order, including an unexpected
+    /// character for each `Error` token from `Lexer::tokenize_recovering`.
+    pub fn parse_with_diagnostics(&mut self) -> (AstNode, Vec<Diagnostic>) {
+        let ast = self.program();
+        let diagnostics = self.errors.iter().map(OvieError::to_diagnostic).collect();
+        (ast, diagnostics)
+    }
+
+    /// Parse all top-level statements
+    fn program(&mut self) -> AstNode {
         let mut statements = Vec::new();
 
         while !self.is_at_end() {
-            if self.check(&TokenType::Eof) {
-                break;
+            statements.push(self.recovering_statement());
+        }
+
+        // The characters the lexer could not read stand in for the syntax errors at them
+        let lex_errors = self.tokens.iter().filter(|token| token.token_type == TokenType::Error).map(unexpected_character);
+        self.errors.extend(lex_errors);
+        self.errors.sort_by_cached_key(|error| {
+            let location = error.to_diagnostic().location;
+            (location.line, location.column)
+        });
+
+        AstNode::new(statements)
+    }
+
+    /// Parse a statement, or record the error and skip to the next statement boundary
+    fn recovering_statement(&mut self) -> Statement {
+        let start = self.current_span();
+        let first_token = self.current;
+
+        match self.statement() {
+            Ok(statement) => statement,
+            Err(error) => {
+                if self.peek().token_type != TokenType::Error {
+                    self.errors.push(error);
+                }
+
+                // Skip past any blocks the failed statement had already opened
+                let open_braces = self.tokens[first_token..self.current]
+                    .iter()
+                    .fold(0usize, |depth, token| match token.token_type {
+                        TokenType::LeftBrace => depth + 1,
+                        TokenType::RightBrace => depth.saturating_sub(1),
+                        _ => depth,
+                    });
+                self.synchronize(open_braces, start.column);
+
+                // Always make progress, even when the error was at a boundary token
+                if self.current == first_token {
+                    self.advance();
+                }
+
+                Statement::Error { span: self.span_from(start) }
             }
-            statements.push(self.statement()?);
         }
+    }
 
-        Ok(AstNode::new(statements))
+    /// Skip tokens until a statement boundary: just past a `;` or a closed
+    /// block, or just before `}`, the start of an item or the next line.
+    /// Inside a block the failed statement left open, a line indented no
+    /// further than the statement at `column` ends it too, unless it closes
+    /// the block, so a missing `}` does not swallow the rest of the file.
+    fn synchronize(&mut self, mut depth: usize, column: usize) {
+        while !self.is_at_end() {
+            if self.at_line_break()
+                && (depth == 0 || (self.peek().location.column <= column && !self.check(&TokenType::RightBrace)))
+            {
+                return;
+            }
+            match self.peek().token_type {
+                TokenType::LeftBrace => depth += 1,
+                TokenType::RightBrace if depth == 0 => return,
+                TokenType::RightBrace => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.advance();
+                        return;
+                    }
+                }
+                TokenType::Semicolon if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                TokenType::Fn | TokenType::Struct | TokenType::Enum | TokenType::Impl | TokenType::Trait | TokenType::Use
+                | TokenType::Mod | TokenType::Pub | TokenType::Unsafe | TokenType::Const if depth == 0 => return,
+                _ => {}
+            }
+            self.advance();
+        }
     }
 
     /// Parse a statement
     fn statement(&mut self) -> ParseResult<Statement> {
         match &self.peek().token_type {
-            TokenType::Fn => self.function_statement(),
+            // `fn(` starts a closure expression rather than a function definition
+            TokenType::Fn if self.tokens.get(self.current + 1)
+                .map(|t| &t.token_type) == Some(&TokenType::LeftParen) => self.expression_statement(),
+            TokenType::Fn => self.function_statement(false),
+            TokenType::Unsafe if self.tokens.get(self.current + 1)
+                .map(|t| &t.token_type) == Some(&TokenType::Fn) => self.function_statement(false),
+            TokenType::Unsafe => self.unsafe_statement(),
+            TokenType::Const if self.tokens.get(self.current + 1)
+                .map(|t| &t.token_type) == Some(&TokenType::Fn) => self.function_statement(false),
+            TokenType::Const => self.const_statement(false),
+            TokenType::Pub => self.public_item(),
+            TokenType::Use => self.use_statement(),
+            TokenType::Mod => self.module_statement(),
             TokenType::SeeAm => self.print_statement(),
             TokenType::If => self.if_statement(),
-            TokenType::While => self.while_statement(),
-            TokenType::For => self.for_statement(),
+            TokenType::While => self.while_statement(None),
+            TokenType::For => self.for_statement(None),
             TokenType::Return => self.return_statement(),
-            TokenType::Struct => self.struct_statement(),
-            TokenType::Enum => self.enum_statement(),
+            TokenType::Break | TokenType::Continue => self.loop_exit_statement(),
+            TokenType::Struct => self.struct_statement(false),
+            TokenType::Enum => self.enum_statement(false),
+            TokenType::Impl => self.impl_statement(),
+            TokenType::Trait => self.trait_statement(false),
             TokenType::Mut => self.assignment_statement(true),
+            TokenType::Let => self.let_statement(),
+            TokenType::Match => self.match_statement(),
             TokenType::Identifier => {
-                // Look ahead to see if this is an assignment
-                if self.tokens.get(self.current + 1)
-                    .map(|t| &t.token_type) == Some(&TokenType::Equal) {
+                // Look ahead to see if this is an assignment or a loop label
+                let next = self.tokens.get(self.current + 1).map(|t| &t.token_type);
+                if next == Some(&TokenType::Equal) {
                     self.assignment_statement(false)
+                } else if next == Some(&TokenType::Colon) {
+                    self.labeled_loop()
                 } else {
                     self.expression_statement()
                 }
@@ -61,18 +178,273 @@ impl Parser {
         }
     }
 
-    /// Parse a function definition
-    fn function_statement(&mut self) -> ParseResult<Statement> {
+    /// Parse an item marked `pub`: a function, struct, enum, trait or constant definition
+    fn public_item(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
+        self.consume(&TokenType::Pub, "Expected 'pub'")?;
+        let mut item = match self.peek().token_type {
+            TokenType::Const if self.tokens.get(self.current + 1)
+                .map(|t| &t.token_type) != Some(&TokenType::Fn) => self.const_statement(true)?,
+            TokenType::Fn | TokenType::Unsafe | TokenType::Const => self.function_statement(true)?,
+            TokenType::Struct => self.struct_statement(true)?,
+            TokenType::Enum => self.enum_statement(true)?,
+            TokenType::Trait => self.trait_statement(true)?,
+            _ => return Err(self.error("Expected 'fn', 'struct', 'enum', 'trait' or 'const' after 'pub'")),
+        };
+        // Widen the item's span to cover the `pub` keyword
+        if let Statement::Function { span, .. } | Statement::Struct { span, .. } | Statement::Enum { span, .. }
+            | Statement::Trait { span, .. } | Statement::Const { span, .. } = &mut item
+        {
+            *span = self.span_from(start);
+        }
+        Ok(item)
+    }
+
+    /// Parse an import: use path::name; or use path::{name, ...};
+    fn use_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
+        self.consume(&TokenType::Use, "Expected 'use'")?;
+
+        let mut path = vec![self.consume_identifier("Expected module name after 'use'")?];
+        self.consume(&TokenType::ColonColon, "Expected '::' after module name in 'use'")?;
+
+        let mut names = Vec::new();
+        loop {
+            if self.match_token(&TokenType::LeftBrace) {
+                loop {
+                    names.push(self.consume_identifier("Expected name to import")?);
+                    if !self.match_token(&TokenType::Comma) || self.check(&TokenType::RightBrace) {
+                        break;
+                    }
+                }
+                self.consume(&TokenType::RightBrace, "Expected '}' after imported names")?;
+                break;
+            }
+
+            let segment = self.consume_identifier("Expected name after '::' in 'use'")?;
+            if !self.match_token(&TokenType::ColonColon) {
+                names.push(segment);
+                break;
+            }
+            path.push(segment);
+        }
+
+        self.end_statement("Expected ';' or newline after 'use'")?;
+
+        Ok(Statement::Use { path, names, span: self.span_from(start) })
+    }
+
+    /// Parse a module declaration: mod name;
+    fn module_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
+        self.consume(&TokenType::Mod, "Expected 'mod'")?;
+        let name = self.consume_identifier("Expected module name after 'mod'")?;
+        self.end_statement("Expected ';' or newline after module declaration")?;
+
+        Ok(Statement::Module { name, span: self.span_from(start) })
+    }
+
+    /// Whether a name starts with an uppercase letter, as type and variant names do
+    fn is_capitalized(name: &str) -> bool {
+        name.chars().next().is_some_and(|c| c.is_uppercase())
+    }
+
+    /// Parse the `::`-separated tail of a path whose first segment is `first`
+    fn qualified_name(&mut self, first: String) -> ParseResult<String> {
+        let mut name = first;
+        while self.match_token(&TokenType::ColonColon) {
+            let segment = self.consume_identifier("Expected name after '::'")?;
+            name.push_str("::");
+            name.push_str(&segment);
+        }
+        Ok(name)
+    }
+
+    /// Parse a function definition, optionally marked `const` or `unsafe`
+    fn function_statement(&mut self, public: bool) -> ParseResult<Statement> {
+        let start = self.current_span();
+        let is_const = self.match_token(&TokenType::Const);
+        let is_unsafe = self.match_token(&TokenType::Unsafe);
+        if is_const && is_unsafe {
+            return Err(self.error("A function cannot be both 'const' and 'unsafe'"));
+        }
         self.consume(&TokenType::Fn, "Expected 'fn'")?;
         
         let name = self.consume_identifier("Expected function name")?;
+        let (type_parameters, bounds) = self.bounded_type_parameters()?;
         
         self.consume(&TokenType::LeftParen, "Expected '(' after function name")?;
+        let parameters = self.parameters()?;
+
+        let return_type = if self.match_token(&TokenType::Arrow) {
+            Some(self.type_annotation()?)
+        } else {
+            None
+        };
+
+        let mut requires = Vec::new();
+        let mut ensures = Vec::new();
+        loop {
+            if self.match_token(&TokenType::Requires) {
+                requires.push(self.contract_clause()?);
+            } else if self.match_token(&TokenType::Ensures) {
+                ensures.push(self.contract_clause()?);
+            } else {
+                break;
+            }
+        }
+        
+        let body = self.block_statement()?;
         
+        Ok(Statement::Function {
+            public,
+            is_const,
+            is_unsafe,
+            name,
+            type_parameters,
+            bounds,
+            parameters,
+            return_type,
+            requires,
+            ensures,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse the condition of a `requires`, `ensures` or `invariant` clause
+    fn contract_clause(&mut self) -> ParseResult<ContractClause> {
+        let first = self.current;
+        let condition = self.expression()?;
+
+        // Rebuild the text from the tokens, with any whitespace between them as one space
+        let mut text = String::new();
+        for (index, token) in self.tokens[first..self.current].iter().enumerate() {
+            if index > 0 {
+                let previous = &self.tokens[first + index - 1];
+                if token.location.offset > previous.location.offset + previous.lexeme.len() {
+                    text.push(' ');
+                }
+            }
+            text.push_str(&token.lexeme);
+        }
+
+        Ok(ContractClause { condition, text })
+    }
+
+    /// Parse a method block: impl [Trait for] Type[<T: Trait, ...>] { [const | unsafe] fn ... }
+    fn impl_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
+        self.consume(&TokenType::Impl, "Expected 'impl'")?;
+        let first = self.consume_identifier("Expected type name after 'impl'")?;
+        let first = self.qualified_name(first)?;
+        let (trait_name, type_name) = if self.match_token(&TokenType::For) {
+            (Some(first), self.consume_identifier("Expected type name after 'for'")?)
+        } else {
+            (None, first)
+        };
+        let (type_parameters, bounds) = self.bounded_type_parameters()?;
+        self.consume(&TokenType::LeftBrace, "Expected '{' after impl type")?;
+
+        let mut methods = Vec::new();
+        while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
+            if !self.check(&TokenType::Fn) && !self.check(&TokenType::Unsafe) && !self.check(&TokenType::Const) {
+                return Err(self.error("Expected method definition in impl block"));
+            }
+            methods.push(self.function_statement(false)?);
+        }
+        self.consume(&TokenType::RightBrace, "Expected '}' after impl block")?;
+
+        Ok(Statement::Impl { trait_name, type_name, type_parameters, bounds, methods, span: self.span_from(start) })
+    }
+
+    /// Parse a trait definition: trait Name { fn method(self, ...) [-> Type] [{ default body }] ... }
+    fn trait_statement(&mut self, public: bool) -> ParseResult<Statement> {
+        let start = self.current_span();
+        self.consume(&TokenType::Trait, "Expected 'trait'")?;
+        let name = self.consume_identifier("Expected trait name after 'trait'")?;
+        if self.check(&TokenType::Less) {
+            return Err(self.error("Traits cannot have type parameters"));
+        }
+        self.consume(&TokenType::LeftBrace, "Expected '{' after trait name")?;
+
+        let mut methods = Vec::new();
+        while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
+            methods.push(self.trait_method()?);
+        }
+        self.consume(&TokenType::RightBrace, "Expected '}' after trait body")?;
+
+        Ok(Statement::Trait { public, name, methods, span: self.span_from(start) })
+    }
+
+    /// Parse a trait method: fn name[<T, ...>](params) [-> Type], then a default body or the end of the line
+    fn trait_method(&mut self) -> ParseResult<TraitMethod> {
+        let start = self.current_span();
+        self.consume(&TokenType::Fn, "Expected method signature in trait")?;
+        let name = self.consume_identifier("Expected method name")?;
+        let (type_parameters, bounds) = self.bounded_type_parameters()?;
+        self.consume(&TokenType::LeftParen, "Expected '(' after method name")?;
+        let parameters = self.parameters()?;
+
+        let return_type = if self.match_token(&TokenType::Arrow) {
+            Some(self.type_annotation()?)
+        } else {
+            None
+        };
+
+        let body = if self.check(&TokenType::LeftBrace) {
+            Some(self.block_statement()?)
+        } else {
+            self.end_statement("Expected '{', ';' or newline after method signature")?;
+            None
+        };
+
+        Ok(TraitMethod {
+            name,
+            type_parameters,
+            bounds,
+            parameters,
+            return_type,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse a constant definition: const NAME: Type = expression
+    fn const_statement(&mut self, public: bool) -> ParseResult<Statement> {
+        let start = self.current_span();
+        self.consume(&TokenType::Const, "Expected 'const'")?;
+        let name = self.consume_identifier("Expected constant name after 'const'")?;
+        self.consume(&TokenType::Colon, "Expected ':' and a type after constant name")?;
+        let type_annotation = self.type_annotation()?;
+        self.consume(&TokenType::Equal, "Expected '=' in constant definition")?;
+        let value = self.expression()?;
+        self.end_statement("Expected ';' or newline after constant definition")?;
+
+        Ok(Statement::Const {
+            public,
+            name,
+            type_annotation,
+            value,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse an unsafe block: unsafe { body }
+    fn unsafe_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
+        self.consume(&TokenType::Unsafe, "Expected 'unsafe'")?;
+        let body = self.block_statement()?;
+
+        Ok(Statement::Unsafe { body, span: self.span_from(start) })
+    }
+
+    /// Parse a parameter list after its '(' up to and including the ')'
+    fn parameters(&mut self) -> ParseResult<Vec<Parameter>> {
         let mut parameters = Vec::new();
         if !self.check(&TokenType::RightParen) {
             loop {
-                parameters.push(self.consume_identifier("Expected parameter name")?);
+                parameters.push(self.parameter()?);
                 if !self.match_token(&TokenType::Comma) {
                     break;
                 }
@@ -80,37 +452,167 @@ impl Parser {
         }
         
         self.consume(&TokenType::RightParen, "Expected ')' after parameters")?;
-        
-        let body = self.block_statement()?;
-        
-        Ok(Statement::Function {
+        Ok(parameters)
+    }
+
+    /// Parse optional type parameters on an item: <T, U>
+    fn type_parameters(&mut self) -> ParseResult<Vec<String>> {
+        let (type_parameters, bounds) = self.bounded_type_parameters()?;
+        match bounds.first() {
+            Some(bound) => Err(OvieError::parse_error(
+                bound.span.line,
+                bound.span.column,
+                "Trait bounds are only allowed on functions and impl blocks",
+            )),
+            None => Ok(type_parameters),
+        }
+    }
+
+    /// Parse optional type parameters, each with optional trait bounds: <T: Trait + Trait, U>
+    fn bounded_type_parameters(&mut self) -> ParseResult<(Vec<String>, Vec<TypeBound>)> {
+        let mut type_parameters = Vec::new();
+        let mut bounds = Vec::new();
+        if self.match_token(&TokenType::Less) {
+            loop {
+                let start = self.current_span();
+                let name = self.consume_identifier("Expected type parameter name")?;
+                if type_parameters.contains(&name) {
+                    return Err(self.error(&format!("Duplicate type parameter '{}'", name)));
+                }
+                if self.match_token(&TokenType::Colon) {
+                    let mut traits = Vec::new();
+                    loop {
+                        let trait_name = self.consume_identifier("Expected trait name in bound")?;
+                        traits.push(self.qualified_name(trait_name)?);
+                        if !self.match_token(&TokenType::Plus) {
+                            break;
+                        }
+                    }
+                    bounds.push(TypeBound { parameter: name.clone(), traits, span: self.span_from(start) });
+                }
+                type_parameters.push(name);
+                if !self.match_token(&TokenType::Comma) {
+                    break;
+                }
+            }
+            self.consume(&TokenType::Greater, "Expected '>' after type parameters")?;
+        }
+        Ok((type_parameters, bounds))
+    }
+
+    /// Parse a function parameter: [mut] name[: [mut] Type]
+    fn parameter(&mut self) -> ParseResult<Parameter> {
+        let start = self.current_span();
+        let mut mutable = self.match_token(&TokenType::Mut);
+        let name = self.consume_identifier("Expected parameter name")?;
+
+        let type_annotation = if self.match_token(&TokenType::Colon) {
+            mutable |= self.match_token(&TokenType::Mut);
+            Some(self.type_annotation()?)
+        } else {
+            None
+        };
+
+        Ok(Parameter {
             name,
-            parameters,
-            body,
+            type_annotation,
+            mutable,
+            span: self.span_from(start),
         })
     }
 
+    /// Parse a type: [path::]Name, Name<Type, ...>, [Type], fn(Type, ...) -> Type or ()
+    fn type_annotation(&mut self) -> ParseResult<TypeAnnotation> {
+        if self.match_token(&TokenType::Fn) {
+            self.consume(&TokenType::LeftParen, "Expected '(' after 'fn' in function type")?;
+            let mut parameters = Vec::new();
+            if !self.check(&TokenType::RightParen) {
+                loop {
+                    parameters.push(self.type_annotation()?);
+                    if !self.match_token(&TokenType::Comma) {
+                        break;
+                    }
+                }
+            }
+            self.consume(&TokenType::RightParen, "Expected ')' after function type parameters")?;
+
+            let return_type = if self.match_token(&TokenType::Arrow) {
+                self.type_annotation()?
+            } else {
+                TypeAnnotation::Unit
+            };
+
+            return Ok(TypeAnnotation::Function {
+                parameters,
+                return_type: Box::new(return_type),
+            });
+        }
+
+        if self.match_token(&TokenType::LeftBracket) {
+            let element = self.type_annotation()?;
+            self.consume(&TokenType::RightBracket, "Expected ']' after array element type")?;
+            return Ok(TypeAnnotation::Array(Box::new(element)));
+        }
+
+        if self.match_token(&TokenType::LeftParen) {
+            if self.match_token(&TokenType::RightParen) {
+                return Ok(TypeAnnotation::Unit);
+            }
+
+            // `(T)` is just T; a comma makes a tuple type: `(T,)`, `(T, U)`
+            let mut elements = vec![self.type_annotation()?];
+            let mut is_tuple = false;
+            while self.match_token(&TokenType::Comma) {
+                is_tuple = true;
+                if self.check(&TokenType::RightParen) {
+                    break;
+                }
+                elements.push(self.type_annotation()?);
+            }
+            self.consume(&TokenType::RightParen, "Expected ')' after tuple element types")?;
+            return Ok(if is_tuple { TypeAnnotation::Tuple(elements) } else { elements.remove(0) });
+        }
+
+        let name = self.consume_identifier("Expected type")?;
+        let name = self.qualified_name(name)?;
+        let mut arguments = Vec::new();
+        if self.match_token(&TokenType::Less) {
+            loop {
+                arguments.push(self.type_annotation()?);
+                if !self.match_token(&TokenType::Comma) {
+                    break;
+                }
+            }
+            self.consume(&TokenType::Greater, "Expected '>' after type arguments")?;
+        }
+
+        Ok(TypeAnnotation::Named { name, arguments })
+    }
+
     /// Parse a print statement
     fn print_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
         self.consume(&TokenType::SeeAm, "Expected 'seeAm'")?;
         let expression = self.expression()?;
-        // Ovie doesn't require semicolons after statements
-        // Optional semicolon for compatibility
-        if self.check(&TokenType::Semicolon) {
-            self.advance();
-        }
-        
-        Ok(Statement::Print { expression })
+        self.end_statement("Expected ';' or newline after print statement")?;
+
+        Ok(Statement::Print { expression, span: self.span_from(start) })
     }
 
     /// Parse an if statement
     fn if_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
         self.consume(&TokenType::If, "Expected 'if'")?;
         let condition = self.expression()?;
         let then_block = self.block_statement()?;
         
         let else_block = if self.match_token(&TokenType::Else) {
-            Some(self.block_statement()?)
+            if self.check(&TokenType::If) {
+                // `else if` nests the next if statement as the whole else branch
+                Some(vec![self.if_statement()?])
+            } else {
+                Some(self.block_statement()?)
+            }
         } else {
             None
         };
@@ -119,60 +621,117 @@ impl Parser {
             condition,
             then_block,
             else_block,
+            span: self.span_from(start),
         })
     }
 
+    /// Parse a loop with a label: label: while ... or label: for ...
+    fn labeled_loop(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
+        let label = self.consume_identifier("Expected loop label")?;
+        self.consume(&TokenType::Colon, "Expected ':' after loop label")?;
+        let mut statement = match self.peek().token_type {
+            TokenType::While => self.while_statement(Some(label))?,
+            TokenType::For => self.for_statement(Some(label))?,
+            _ => return Err(self.error("Expected 'while' or 'for' after loop label")),
+        };
+        // Widen the loop's span to cover the label
+        if let Statement::While { span, .. } | Statement::For { span, .. } = &mut statement {
+            *span = self.span_from(start);
+        }
+        Ok(statement)
+    }
+
     /// Parse a while statement
-    fn while_statement(&mut self) -> ParseResult<Statement> {
+    fn while_statement(&mut self, label: Option<String>) -> ParseResult<Statement> {
+        let start = self.current_span();
         self.consume(&TokenType::While, "Expected 'while'")?;
         let condition = self.expression()?;
         let body = self.block_statement()?;
         
-        Ok(Statement::While { condition, body })
+        Ok(Statement::While { label, condition, body, span: self.span_from(start) })
     }
 
     /// Parse a for statement
-    fn for_statement(&mut self) -> ParseResult<Statement> {
+    fn for_statement(&mut self, label: Option<String>) -> ParseResult<Statement> {
+        let start = self.current_span();
         self.consume(&TokenType::For, "Expected 'for'")?;
-        let identifier = self.consume_identifier("Expected loop variable name")?;
+        let pattern = self.binding_pattern("Expected loop variable name")?;
         self.consume(&TokenType::In, "Expected 'in' after loop variable")?;
         let iterable = self.expression()?;
         let body = self.block_statement()?;
         
         Ok(Statement::For {
-            identifier,
+            label,
+            pattern,
             iterable,
             body,
+            span: self.span_from(start),
         })
     }
 
     /// Parse a return statement
     fn return_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
         self.consume(&TokenType::Return, "Expected 'return'")?;
         
-        let value = if self.check(&TokenType::Semicolon) {
+        // A bare `return` ends at `;`, the end of its line or the end of the block
+        let value = if self.check(&TokenType::Semicolon) || self.check(&TokenType::RightBrace)
+            || self.is_at_end() || self.at_line_break() {
             None
         } else {
             Some(self.expression()?)
         };
+
+        self.end_statement("Expected ';' or newline after return statement")?;
         
-        self.consume(&TokenType::Semicolon, "Expected ';' after return statement")?;
-        
-        Ok(Statement::Return { value })
+        Ok(Statement::Return { value, span: self.span_from(start) })
+    }
+
+    /// Parse `break [label]` or `continue [label]`
+    fn loop_exit_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
+        let is_break = self.advance().token_type == TokenType::Break;
+
+        // The label must be on the same line as the keyword
+        let label = if self.check(&TokenType::Identifier) && !self.at_line_break() {
+            Some(self.advance().lexeme.clone())
+        } else {
+            None
+        };
+
+        if is_break {
+            self.end_statement("Expected ';' or newline after 'break'")?;
+            Ok(Statement::Break { label, span: self.span_from(start) })
+        } else {
+            self.end_statement("Expected ';' or newline after 'continue'")?;
+            Ok(Statement::Continue { label, span: self.span_from(start) })
+        }
     }
 
     /// Parse a struct definition
-    fn struct_statement(&mut self) -> ParseResult<Statement> {
+    fn struct_statement(&mut self, public: bool) -> ParseResult<Statement> {
+        let start = self.current_span();
         self.consume(&TokenType::Struct, "Expected 'struct'")?;
         let name = self.consume_identifier("Expected struct name")?;
+        let type_parameters = self.type_parameters()?;
         
         self.consume(&TokenType::LeftBrace, "Expected '{' after struct name")?;
         
         let mut fields = Vec::new();
+        let mut invariants = Vec::new();
         while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
+            if self.match_token(&TokenType::Invariant) {
+                invariants.push(self.contract_clause()?);
+                if !self.match_token(&TokenType::Comma) {
+                    break;
+                }
+                continue;
+            }
+
             let field_name = self.consume_identifier("Expected field name")?;
             self.consume(&TokenType::Colon, "Expected ':' after field name")?;
-            let type_annotation = self.consume_identifier("Expected field type")?;
+            let type_annotation = self.type_annotation()?;
             
             fields.push(StructField {
                 name: field_name,
@@ -186,13 +745,15 @@ impl Parser {
         
         self.consume(&TokenType::RightBrace, "Expected '}' after struct fields")?;
         
-        Ok(Statement::Struct { name, fields })
+        Ok(Statement::Struct { public, name, type_parameters, fields, invariants, span: self.span_from(start) })
     }
 
     /// Parse an enum definition
-    fn enum_statement(&mut self) -> ParseResult<Statement> {
+    fn enum_statement(&mut self, public: bool) -> ParseResult<Statement> {
+        let start = self.current_span();
         self.consume(&TokenType::Enum, "Expected 'enum'")?;
         let name = self.consume_identifier("Expected enum name")?;
+        let type_parameters = self.type_parameters()?;
         
         self.consume(&TokenType::LeftBrace, "Expected '{' after enum name")?;
         
@@ -200,17 +761,32 @@ impl Parser {
         while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
             let variant_name = self.consume_identifier("Expected variant name")?;
             
-            let data_type = if self.match_token(&TokenType::LeftParen) {
-                let type_name = self.consume_identifier("Expected variant data type")?;
-                self.consume(&TokenType::RightParen, "Expected ')' after variant data type")?;
-                Some(type_name)
+            let payload = if self.match_token(&TokenType::LeftParen) {
+                let mut types = vec![self.type_annotation()?];
+                while self.match_token(&TokenType::Comma) && !self.check(&TokenType::RightParen) {
+                    types.push(self.type_annotation()?);
+                }
+                self.consume(&TokenType::RightParen, "Expected ')' after variant field types")?;
+                VariantPayload::Tuple(types)
+            } else if self.match_token(&TokenType::LeftBrace) {
+                let mut fields: Vec<(String, TypeAnnotation)> = Vec::new();
+                while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
+                    let field_name = self.consume_identifier("Expected field name")?;
+                    self.consume(&TokenType::Colon, "Expected ':' after field name")?;
+                    fields.push((field_name, self.type_annotation()?));
+                    if !self.match_token(&TokenType::Comma) {
+                        break;
+                    }
+                }
+                self.consume(&TokenType::RightBrace, "Expected '}' after variant fields")?;
+                VariantPayload::Struct(fields)
             } else {
-                None
+                VariantPayload::Unit
             };
             
             variants.push(EnumVariant {
                 name: variant_name,
-                data_type,
+                payload,
             });
             
             if !self.match_token(&TokenType::Comma) {
@@ -220,11 +796,12 @@ impl Parser {
         
         self.consume(&TokenType::RightBrace, "Expected '}' after enum variants")?;
         
-        Ok(Statement::Enum { name, variants })
+        Ok(Statement::Enum { public, name, type_parameters, variants, span: self.span_from(start) })
     }
 
     /// Parse an assignment statement
     fn assignment_statement(&mut self, mutable: bool) -> ParseResult<Statement> {
+        let start = self.current_span();
         if mutable {
             self.consume(&TokenType::Mut, "Expected 'mut'")?;
         }
@@ -232,34 +809,363 @@ impl Parser {
         let identifier = self.consume_identifier("Expected variable name")?;
         self.consume(&TokenType::Equal, "Expected '=' in assignment")?;
         let value = self.expression()?;
-        self.consume(&TokenType::Semicolon, "Expected ';' after assignment")?;
+        self.end_statement("Expected ';' or newline after assignment")?;
         
         Ok(Statement::Assignment {
             mutable,
             identifier,
             value,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse a variable declaration: let [mut] name[: Type] = expression;
+    /// or a destructuring one: let [mut] (a, b, ...)[: Type] = expression;
+    fn let_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
+        self.consume(&TokenType::Let, "Expected 'let'")?;
+        let mutable = self.match_token(&TokenType::Mut);
+        if self.check(&TokenType::LeftParen) {
+            let pattern = self.binding_pattern("Expected variable name")?;
+            let type_annotation = if self.match_token(&TokenType::Colon) {
+                Some(self.type_annotation()?)
+            } else {
+                None
+            };
+            self.consume(&TokenType::Equal, "Expected '=' in variable declaration")?;
+            let value = self.expression()?;
+            self.end_statement("Expected ';' or newline after variable declaration")?;
+
+            return Ok(Statement::Destructure {
+                mutable,
+                pattern,
+                type_annotation,
+                value,
+                span: self.span_from(start),
+            });
+        }
+        let identifier = self.consume_identifier("Expected variable name")?;
+
+        let type_annotation = if self.match_token(&TokenType::Colon) {
+            Some(self.type_annotation()?)
+        } else {
+            None
+        };
+
+        self.consume(&TokenType::Equal, "Expected '=' in variable declaration")?;
+        let value = self.expression()?;
+        self.end_statement("Expected ';' or newline after variable declaration")?;
+
+        Ok(Statement::VariableDeclaration {
+            mutable,
+            identifier,
+            type_annotation,
+            value,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse a match used as a statement; like a block, it needs no `;`
+    fn match_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
+        let expression = self.match_expression()?;
+        self.match_token(&TokenType::Semicolon);
+
+        Ok(Statement::Expression { expression, span: self.span_from(start) })
+    }
+
+    /// Parse a match expression: match scrutinee { pattern => expression, pattern => { statements } }
+    fn match_expression(&mut self) -> ParseResult<Expression> {
+        let start = self.current_span();
+        self.consume(&TokenType::Match, "Expected 'match'")?;
+        let scrutinee = self.expression()?;
+        self.consume(&TokenType::LeftBrace, "Expected '{' after match scrutinee")?;
+
+        let mut arms = Vec::new();
+        while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
+            let arm_start = self.current_span();
+            let pattern = self.pattern()?;
+            self.consume(&TokenType::FatArrow, "Expected '=>' after match pattern")?;
+
+            let (body, value) = if self.check(&TokenType::LeftBrace) {
+                (self.block_statement()?, None)
+            } else {
+                (Vec::new(), Some(self.expression()?))
+            };
+            let is_block = value.is_none();
+            arms.push(MatchArm {
+                pattern,
+                body,
+                value,
+                span: self.span_from(arm_start),
+            });
+
+            // Expression arms are separated by commas; after a block arm the comma is optional
+            if !self.match_token(&TokenType::Comma) && !is_block {
+                break;
+            }
+        }
+
+        self.consume(&TokenType::RightBrace, "Expected '}' after match arms")?;
+
+        Ok(Expression::Match {
+            scrutinee: Box::new(scrutinee),
+            arms,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse a match pattern: _, name, literal, [path::]EnumName.Variant or EnumName.Variant(pattern)
+    fn pattern(&mut self) -> ParseResult<Pattern> {
+        let start = self.current_span();
+        match self.peek().token_type {
+            TokenType::True | TokenType::False | TokenType::IntegerLiteral
+            | TokenType::FloatLiteral | TokenType::StringLiteral => match self.primary_base()? {
+                Expression::Literal(literal, span) => Ok(Pattern::Literal(literal, span)),
+                _ => Err(self.error("Expected a literal pattern")),
+            },
+            TokenType::Minus => {
+                self.advance();
+                match self.primary_base()? {
+                    Expression::Literal(Literal::Number(value), _) => {
+                        Ok(Pattern::Literal(Literal::Number(-value), self.span_from(start)))
+                    }
+                    Expression::Literal(Literal::Integer(value), _) => {
+                        Ok(Pattern::Literal(Literal::Integer(-value), self.span_from(start)))
+                    }
+                    _ => Err(self.error("Expected a number after '-' in pattern")),
+                }
+            }
+            TokenType::Identifier => {
+                let name = self.advance().lexeme.clone();
+                if name == "_" {
+                    return Ok(Pattern::Wildcard(start));
+                }
+                let name = self.qualified_name(name)?;
+                if !self.match_token(&TokenType::Dot) {
+                    if name.contains("::") {
+                        return Err(self.error("Expected '.' and a variant name after qualified enum name in pattern"));
+                    }
+                    return Ok(Pattern::Binding(name, start));
+                }
+
+                let variant_name = self.consume_identifier("Expected variant name after '.' in pattern")?;
+                let payload = if self.match_token(&TokenType::LeftParen) {
+                    let mut patterns = vec![self.pattern()?];
+                    while self.match_token(&TokenType::Comma) && !self.check(&TokenType::RightParen) {
+                        patterns.push(self.pattern()?);
+                    }
+                    self.consume(&TokenType::RightParen, "Expected ')' after variant payload patterns")?;
+                    VariantPayload::Tuple(patterns)
+                } else if self.match_token(&TokenType::LeftBrace) {
+                    // `field` binds the field to its own name, `field: pattern` matches it against a pattern
+                    let mut fields = Vec::new();
+                    while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
+                        let field_start = self.current_span();
+                        let field_name = self.consume_identifier("Expected field name in variant pattern")?;
+                        let pattern = if self.match_token(&TokenType::Colon) {
+                            self.pattern()?
+                        } else {
+                            Pattern::Binding(field_name.clone(), field_start)
+                        };
+                        fields.push((field_name, pattern));
+                        if !self.match_token(&TokenType::Comma) {
+                            break;
+                        }
+                    }
+                    self.consume(&TokenType::RightBrace, "Expected '}' after variant field patterns")?;
+                    VariantPayload::Struct(fields)
+                } else {
+                    VariantPayload::Unit
+                };
+
+                let pattern = Pattern::Variant {
+                    enum_name: name,
+                    variant_name,
+                    payload,
+                    span: self.span_from(start),
+                };
+                self.check_unique_bindings(&pattern)?;
+                Ok(pattern)
+            }
+            TokenType::LeftParen => Err(self.error("Tuple patterns are only supported in 'let' and 'for'; match on the tuple's elements instead")),
+            _ => Err(self.error("Expected a pattern")),
+        }
+    }
+
+    /// Parse the pattern of a `let` or `for` binding: name, _ or (pattern, ...)
+    fn binding_pattern(&mut self, message: &str) -> ParseResult<Pattern> {
+        let start = self.current_span();
+        if !self.match_token(&TokenType::LeftParen) {
+            let name = self.consume_identifier(message)?;
+            return Ok(if name == "_" { Pattern::Wildcard(start) } else { Pattern::Binding(name, start) });
+        }
+
+        // `(a)` is just a; a comma makes a tuple pattern: `(a,)`, `(a, b)`
+        let mut elements = vec![self.binding_pattern(message)?];
+        let mut is_tuple = false;
+        while self.match_token(&TokenType::Comma) {
+            is_tuple = true;
+            if self.check(&TokenType::RightParen) {
+                break;
+            }
+            elements.push(self.binding_pattern(message)?);
+        }
+        self.consume(&TokenType::RightParen, "Expected ')' after tuple pattern")?;
+        if !is_tuple {
+            return Ok(elements.remove(0));
+        }
+
+        let pattern = Pattern::Tuple(elements, self.span_from(start));
+        self.check_unique_bindings(&pattern)?;
+        Ok(pattern)
+    }
+
+    /// Reject a pattern that binds the same name twice
+    fn check_unique_bindings(&self, pattern: &Pattern) -> ParseResult<()> {
+        let names = pattern.bindings();
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].contains(name) {
+                return Err(self.error(&format!("'{}' is bound more than once in the same pattern", name)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a closure: fn(params) [-> Type] { body }
+    fn closure_expression(&mut self) -> ParseResult<Expression> {
+        let start = self.current_span();
+        self.consume(&TokenType::Fn, "Expected 'fn'")?;
+        self.consume(&TokenType::LeftParen, "Expected '(' after 'fn' in closure")?;
+        let parameters = self.parameters()?;
+
+        let return_type = if self.match_token(&TokenType::Arrow) {
+            Some(self.type_annotation()?)
+        } else {
+            None
+        };
+
+        let body = self.block_statement()?;
+
+        Ok(Expression::Closure {
+            parameters,
+            return_type,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse `field: value` initializers after the '{' up to and including the '}'
+    fn field_initializers(&mut self) -> ParseResult<Vec<FieldInitializer>> {
+        self.delimited(|parser| {
+            let mut fields = Vec::new();
+            while !parser.check(&TokenType::RightBrace) && !parser.is_at_end() {
+                let span = parser.current_span();
+                let field_name = parser.consume_identifier("Expected field name")?;
+                parser.consume(&TokenType::Colon, "Expected ':' after field name")?;
+                let value = parser.expression()?;
+
+                fields.push(FieldInitializer {
+                    name: field_name,
+                    value,
+                    span,
+                });
+
+                if !parser.match_token(&TokenType::Comma) {
+                    break;
+                }
+            }
+
+            parser.consume(&TokenType::RightBrace, "Expected '}' after struct fields")?;
+            Ok(fields)
+        })
+    }
+
+    /// Parse call arguments after the '(' up to and including the ')'
+    fn arguments(&mut self) -> ParseResult<Vec<Expression>> {
+        self.delimited(|parser| {
+            let mut arguments = Vec::new();
+            if !parser.check(&TokenType::RightParen) {
+                loop {
+                    arguments.push(parser.expression()?);
+                    if !parser.match_token(&TokenType::Comma) {
+                        break;
+                    }
+                }
+            }
+
+            parser.consume(&TokenType::RightParen, "Expected ')' after arguments")?;
+            Ok(arguments)
         })
     }
 
     /// Parse an expression statement
     fn expression_statement(&mut self) -> ParseResult<Statement> {
+        let start = self.current_span();
         let expression = self.expression()?;
-        self.consume(&TokenType::Semicolon, "Expected ';' after expression")?;
+
+        if let Some(operator) = self.assignment_operator() {
+            if !Self::is_place(&expression) {
+                return Err(self.error("Invalid assignment target; expected a variable, field or array element"));
+            }
+            self.advance();
+            let value = self.expression()?;
+            self.end_statement("Expected ';' or newline after assignment")?;
+            return Ok(Statement::PlaceAssignment {
+                target: expression,
+                operator,
+                value,
+                span: self.span_from(start),
+            });
+        }
+
+        self.end_statement("Expected ';' or newline after expression")?;
         
-        Ok(Statement::Expression { expression })
+        Ok(Statement::Expression { expression, span: self.span_from(start) })
+    }
+
+    /// The assignment operator at the current token, if any: `Some(None)` for `=`,
+    /// `Some(Some(op))` for a compound operator such as `+=`
+    fn assignment_operator(&self) -> Option<Option<BinaryOperator>> {
+        if !self.continues_line() {
+            return None;
+        }
+        match self.peek().token_type {
+            TokenType::Equal => Some(None),
+            TokenType::PlusEqual => Some(Some(BinaryOperator::Add)),
+            TokenType::MinusEqual => Some(Some(BinaryOperator::Subtract)),
+            TokenType::StarEqual => Some(Some(BinaryOperator::Multiply)),
+            TokenType::SlashEqual => Some(Some(BinaryOperator::Divide)),
+            TokenType::PercentEqual => Some(Some(BinaryOperator::Modulo)),
+            _ => None,
+        }
+    }
+
+    /// Whether an expression names an assignable place: a variable, or a field
+    /// or element of a place
+    fn is_place(expression: &Expression) -> bool {
+        match expression {
+            Expression::Identifier(..) => true,
+            Expression::FieldAccess { object, .. } | Expression::Index { object, .. } => Self::is_place(object),
+            _ => false,
+        }
     }
 
     /// Parse a block statement
     fn block_statement(&mut self) -> ParseResult<Vec<Statement>> {
         self.consume(&TokenType::LeftBrace, "Expected '{'")?;
-        
+
+        // Statements inside the block end at line breaks again, even when the
+        // block itself is nested in a delimiter (a closure argument, say)
+        let delimiters = std::mem::take(&mut self.delimiters);
         let mut statements = Vec::new();
         while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
-            statements.push(self.statement()?);
+            statements.push(self.recovering_statement());
         }
-        
+        self.delimiters = delimiters;
+
         self.consume(&TokenType::RightBrace, "Expected '}'")?;
-        
+
         Ok(statements)
     }
 
@@ -272,12 +1178,14 @@ impl Parser {
     fn logical_or(&mut self) -> ParseResult<Expression> {
         let mut expr = self.logical_and()?;
 
-        while self.match_token(&TokenType::OrOr) {
+        while self.continues_line() && self.match_token(&TokenType::OrOr) {
             let right = self.logical_and()?;
+            let span = expr.span().to(&right.span());
             expr = Expression::Binary {
                 left: Box::new(expr),
                 operator: BinaryOperator::Or,
                 right: Box::new(right),
+                span,
             };
         }
 
@@ -288,12 +1196,14 @@ impl Parser {
     fn logical_and(&mut self) -> ParseResult<Expression> {
         let mut expr = self.equality()?;
 
-        while self.match_token(&TokenType::AndAnd) {
+        while self.continues_line() && self.match_token(&TokenType::AndAnd) {
             let right = self.equality()?;
+            let span = expr.span().to(&right.span());
             expr = Expression::Binary {
                 left: Box::new(expr),
                 operator: BinaryOperator::And,
                 right: Box::new(right),
+                span,
             };
         }
 
@@ -306,10 +1216,12 @@ impl Parser {
 
         while let Some(operator) = self.match_equality_operator() {
             let right = self.comparison()?;
+            let span = expr.span().to(&right.span());
             expr = Expression::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                span,
             };
         }
 
@@ -322,10 +1234,12 @@ impl Parser {
 
         while let Some(operator) = self.match_comparison_operator() {
             let right = self.range()?;
+            let span = expr.span().to(&right.span());
             expr = Expression::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                span,
             };
         }
 
@@ -336,11 +1250,13 @@ impl Parser {
     fn range(&mut self) -> ParseResult<Expression> {
         let mut expr = self.term()?;
 
-        if self.match_token(&TokenType::DotDot) {
+        if self.continues_line() && self.match_token(&TokenType::DotDot) {
             let end = self.term()?;
+            let span = expr.span().to(&end.span());
             expr = Expression::Range {
                 start: Box::new(expr),
                 end: Box::new(end),
+                span,
             };
         }
 
@@ -353,10 +1269,12 @@ impl Parser {
 
         while let Some(operator) = self.match_term_operator() {
             let right = self.factor()?;
+            let span = expr.span().to(&right.span());
             expr = Expression::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                span,
             };
         }
 
@@ -369,10 +1287,12 @@ impl Parser {
 
         while let Some(operator) = self.match_factor_operator() {
             let right = self.unary()?;
+            let span = expr.span().to(&right.span());
             expr = Expression::Binary {
                 left: Box::new(expr),
                 operator,
                 right: Box::new(right),
+                span,
             };
         }
 
@@ -381,11 +1301,13 @@ impl Parser {
 
     /// Parse unary expression
     fn unary(&mut self) -> ParseResult<Expression> {
+        let start = self.current_span();
         if let Some(operator) = self.match_unary_operator() {
             let operand = self.unary()?;
             return Ok(Expression::Unary {
                 operator,
                 operand: Box::new(operand),
+                span: self.span_from(start),
             });
         }
 
@@ -397,45 +1319,82 @@ impl Parser {
         let mut expr = self.primary_base()?;
         
         // Handle range expressions
-        if self.match_token(&TokenType::DotDot) {
+        if self.continues_line() && self.match_token(&TokenType::DotDot) {
             let end = self.primary_base()?;
+            let span = expr.span().to(&end.span());
             expr = Expression::Range {
                 start: Box::new(expr),
                 end: Box::new(end),
+                span,
             };
         }
         
-        // Handle field access, array indexing, and enum variant construction
+        // Handle field access, method calls, array indexing, and enum variant construction
         loop {
+            // A postfix operator at the start of a line begins the next statement
+            if !self.continues_line() {
+                break;
+            }
+            let start = expr.span();
             if self.match_token(&TokenType::Dot) {
+                if let Some(fields) = self.tuple_fields() {
+                    // Tuple elements: `.0`, or `.0.1`, which the lexer reads as one number
+                    for field in fields {
+                        expr = Expression::FieldAccess {
+                            object: Box::new(expr),
+                            field,
+                            span: self.span_from(start),
+                        };
+                    }
+                    continue;
+                }
                 let field = self.consume_identifier("Expected field name after '.'")?;
                 
-                // Check if this is an enum variant construction with data
                 if self.match_token(&TokenType::LeftParen) {
-                    // This is EnumName.VariantName(data)
-                    // The expr should be an identifier (enum name)
-                    if let Expression::Identifier(enum_name) = expr {
-                        let data = self.expression()?;
-                        self.consume(&TokenType::RightParen, "Expected ')' after enum variant data")?;
+                    // EnumName.VariantName(data): both names are capitalized, like
+                    // variants without data below. Anything else is a method call.
+                    let enum_name = match &expr {
+                        Expression::Identifier(name, _) if Self::is_capitalized(name.rsplit("::").next().unwrap_or(name))
+                            && Self::is_capitalized(&field) => Some(name.clone()),
+                        _ => None,
+                    };
+                    if let Some(enum_name) = enum_name {
+                        let values = self.arguments()?;
                         expr = Expression::EnumVariantConstruction {
                             enum_name,
                             variant_name: field,
-                            data: Some(Box::new(data)),
+                            payload: VariantPayload::Tuple(values),
+                            span: self.span_from(start),
                         };
                     } else {
-                        return Err(self.error("Enum variant construction requires enum name before '.'"));
+                        let arguments = self.arguments()?;
+                        expr = Expression::MethodCall {
+                            receiver: Box::new(expr),
+                            method: field,
+                            arguments,
+                            span: self.span_from(start),
+                        };
                     }
                 } else {
                     // Check if this might be an enum variant without data
                     // We need to distinguish between field access and enum variant
                     // For now, we'll treat EnumName.VariantName as enum variant if EnumName is capitalized
-                    if let Expression::Identifier(ref name) = expr {
-                        if name.chars().next().map_or(false, |c| c.is_uppercase()) {
-                            // This looks like an enum variant construction without data
+                    if let Expression::Identifier(ref name, _) = expr {
+                        if Self::is_capitalized(name.rsplit("::").next().unwrap_or(name)) {
+                            // This looks like an enum variant construction without data,
+                            // or with named fields: EnumName.VariantName { field: value }
+                            let payload = if self.check(&TokenType::LeftBrace) && self.looks_like_struct_instantiation() {
+                                self.advance(); // consume '{'
+                                let fields = self.field_initializers()?;
+                                VariantPayload::Struct(fields.into_iter().map(|field| (field.name, field.value)).collect())
+                            } else {
+                                VariantPayload::Unit
+                            };
                             expr = Expression::EnumVariantConstruction {
                                 enum_name: name.clone(),
                                 variant_name: field,
-                                data: None,
+                                payload,
+                                span: self.span_from(start),
                             };
                             continue;
                         }
@@ -445,15 +1404,33 @@ impl Parser {
                     expr = Expression::FieldAccess {
                         object: Box::new(expr),
                         field,
+                        span: self.span_from(start),
                     };
                 }
+            } else if self.match_token(&TokenType::LeftParen) {
+                // Call of a computed function value: expr(args)
+                let arguments = self.arguments()?;
+                expr = Expression::IndirectCall {
+                    callee: Box::new(expr),
+                    arguments,
+                    span: self.span_from(start),
+                };
             } else if self.match_token(&TokenType::LeftBracket) {
                 // Array/String indexing: expr[index]
-                let index = self.expression()?;
-                self.consume(&TokenType::RightBracket, "Expected ']' after index")?;
+                let index = self.delimited(|parser| {
+                    let index = parser.expression()?;
+                    parser.consume(&TokenType::RightBracket, "Expected ']' after index")?;
+                    Ok(index)
+                })?;
                 expr = Expression::Index {
                     object: Box::new(expr),
                     index: Box::new(index),
+                    span: self.span_from(start),
+                };
+            } else if self.match_token(&TokenType::Question) {
+                expr = Expression::Try {
+                    operand: Box::new(expr),
+                    span: self.span_from(start),
                 };
             } else {
                 break;
@@ -465,117 +1442,131 @@ impl Parser {
 
     /// Parse base primary expression (without range or field access)
     fn primary_base(&mut self) -> ParseResult<Expression> {
+        let start = self.current_span();
         match &self.peek().token_type {
+            TokenType::Match => self.match_expression(),
+            TokenType::Fn => self.closure_expression(),
             TokenType::True => {
                 self.advance();
-                Ok(Expression::Literal(Literal::Boolean(true)))
+                Ok(Expression::Literal(Literal::Boolean(true), start))
             }
             TokenType::False => {
                 self.advance();
-                Ok(Expression::Literal(Literal::Boolean(false)))
+                Ok(Expression::Literal(Literal::Boolean(false), start))
             }
             TokenType::IntegerLiteral => {
-                let token = self.advance();
-                let value = token.lexeme.parse::<f64>()
-                    .map_err(|_| self.error("Invalid number literal"))?;
-                Ok(Expression::Literal(Literal::Number(value)))
+                let lexeme = self.advance().lexeme.clone();
+                let value = lexeme.parse::<i64>().map_err(|_| OvieError::parse_error(
+                    start.line,
+                    start.column,
+                    format!("Integer literal '{}' is out of range for Int", lexeme),
+                ))?;
+                Ok(Expression::Literal(Literal::Integer(value), start))
             }
             TokenType::FloatLiteral => {
                 let token = self.advance();
                 let value = token.lexeme.parse::<f64>()
                     .map_err(|_| self.error("Invalid number literal"))?;
-                Ok(Expression::Literal(Literal::Number(value)))
+                Ok(Expression::Literal(Literal::Number(value), start))
             }
             TokenType::StringLiteral => {
                 let token = self.advance();
                 // Remove quotes and handle escape sequences - clone to avoid borrow issues
                 let lexeme = token.lexeme.clone();
                 let value = self.parse_string_literal(&lexeme)?;
-                Ok(Expression::Literal(Literal::String(value)))
+                Ok(Expression::Literal(Literal::String(value), start))
             }
             TokenType::Identifier => {
                 let name = self.advance().lexeme.clone();
+                let name = self.qualified_name(name)?;
                 
                 // Check for function call
-                if self.check(&TokenType::LeftParen) {
+                if self.continues_line() && self.check(&TokenType::LeftParen) {
                     self.advance(); // consume '('
-                    
-                    let mut arguments = Vec::new();
-                    if !self.check(&TokenType::RightParen) {
-                        loop {
-                            arguments.push(self.expression()?);
-                            if !self.match_token(&TokenType::Comma) {
-                                break;
-                            }
-                        }
-                    }
-                    
-                    self.consume(&TokenType::RightParen, "Expected ')' after arguments")?;
+                    let arguments = self.arguments()?;
                     
                     Ok(Expression::Call {
                         function: name,
                         arguments,
+                        span: self.span_from(start),
                     })
                 } else if self.check(&TokenType::LeftBrace) && self.looks_like_struct_instantiation() {
                     // Struct instantiation - only if it looks like field initialization
                     // This prevents treating "if a == b {" as struct instantiation
                     self.advance(); // consume '{'
-                    
-                    let mut fields = Vec::new();
-                    while !self.check(&TokenType::RightBrace) && !self.is_at_end() {
-                        let field_name = self.consume_identifier("Expected field name")?;
-                        self.consume(&TokenType::Colon, "Expected ':' after field name")?;
-                        let value = self.expression()?;
-                        
-                        fields.push(FieldInitializer {
-                            name: field_name,
-                            value,
-                        });
-                        
-                        if !self.match_token(&TokenType::Comma) {
-                            break;
-                        }
-                    }
-                    
-                    self.consume(&TokenType::RightBrace, "Expected '}' after struct fields")?;
-                    
+                    let fields = self.field_initializers()?;
+
                     Ok(Expression::StructInstantiation {
                         struct_name: name,
                         fields,
+                        span: self.span_from(start),
                     })
                 } else {
                     // Simple identifier
-                    Ok(Expression::Identifier(name))
+                    Ok(Expression::Identifier(name, start))
                 }
             }
             TokenType::LeftParen => {
                 self.advance(); // consume '('
-                let expr = self.expression()?;
-                self.consume(&TokenType::RightParen, "Expected ')' after expression")?;
-                Ok(expr)
+                self.delimited(|parser| {
+                    let expr = parser.expression()?;
+                    if !parser.match_token(&TokenType::Comma) {
+                        parser.consume(&TokenType::RightParen, "Expected ')' after expression")?;
+                        return Ok(expr);
+                    }
+
+                    // Tuple literal: (a, b, ...) or (a,)
+                    let mut elements = vec![expr];
+                    while !parser.check(&TokenType::RightParen) {
+                        elements.push(parser.expression()?);
+                        if !parser.match_token(&TokenType::Comma) {
+                            break;
+                        }
+                    }
+                    parser.consume(&TokenType::RightParen, "Expected ')' after tuple elements")?;
+                    Ok(Expression::Tuple { elements, span: parser.span_from(start) })
+                })
             }
             TokenType::LeftBracket => {
                 // Array literal: [element1, element2, ...]
                 self.advance(); // consume '['
-                
-                let mut elements = Vec::new();
-                if !self.check(&TokenType::RightBracket) {
-                    loop {
-                        elements.push(self.expression()?);
-                        if !self.match_token(&TokenType::Comma) {
-                            break;
+
+                let elements = self.delimited(|parser| {
+                    let mut elements = Vec::new();
+                    if !parser.check(&TokenType::RightBracket) {
+                        loop {
+                            elements.push(parser.expression()?);
+                            if !parser.match_token(&TokenType::Comma) || parser.check(&TokenType::RightBracket) {
+                                break;
+                            }
                         }
                     }
-                }
-                
-                self.consume(&TokenType::RightBracket, "Expected ']' after array elements")?;
-                
-                Ok(Expression::ArrayLiteral { elements })
+
+                    parser.consume(&TokenType::RightBracket, "Expected ']' after array elements")?;
+                    Ok(elements)
+                })?;
+
+                Ok(Expression::ArrayLiteral { elements, span: self.span_from(start) })
             }
             _ => Err(self.error("Expected expression")),
         }
     }
 
+    /// Consume the element number(s) after a '.' on a tuple, if that is what follows
+    fn tuple_fields(&mut self) -> Option<Vec<String>> {
+        let fields: Vec<String> = match self.peek().token_type {
+            TokenType::IntegerLiteral | TokenType::FloatLiteral => {
+                self.peek().lexeme.split('.').map(str::to_string).collect()
+            }
+            _ => return None,
+        };
+        if fields.iter().any(|field| field.is_empty() || !field.bytes().all(|b| b.is_ascii_digit())) {
+            return None;
+        }
+        self.advance();
+        Some(fields)
+    }
+
     /// Parse string literal, handling escape sequences
     fn parse_string_literal(&self, lexeme: &str) -> ParseResult<String> {
         // Remove surrounding quotes
@@ -610,6 +1601,9 @@ impl Parser {
 
     /// Helper methods for operator matching
     fn match_equality_operator(&mut self) -> Option<BinaryOperator> {
+        if !self.continues_line() {
+            return None;
+        }
         match &self.peek().token_type {
             TokenType::EqualEqual => {
                 self.advance();
@@ -624,6 +1618,9 @@ impl Parser {
     }
 
     fn match_comparison_operator(&mut self) -> Option<BinaryOperator> {
+        if !self.continues_line() {
+            return None;
+        }
         match &self.peek().token_type {
             TokenType::Greater => {
                 self.advance();
@@ -646,6 +1643,9 @@ impl Parser {
     }
 
     fn match_term_operator(&mut self) -> Option<BinaryOperator> {
+        if !self.continues_line() {
+            return None;
+        }
         match &self.peek().token_type {
             TokenType::Plus => {
                 self.advance();
@@ -660,6 +1660,9 @@ impl Parser {
     }
 
     fn match_factor_operator(&mut self) -> Option<BinaryOperator> {
+        if !self.continues_line() {
+            return None;
+        }
         match &self.peek().token_type {
             TokenType::Star => {
                 self.advance();
@@ -713,6 +1716,56 @@ impl Parser {
         self.tokens[self.current + 2].token_type == TokenType::Colon
     }
     
+    /// Whether the current token starts a later line than the previous token
+    fn at_line_break(&self) -> bool {
+        self.current > 0 && self.peek().location.line > self.previous().location.line
+    }
+
+    /// Whether an infix or postfix operator at the current token continues the
+    /// expression before it: on the same line, or anywhere inside a delimiter
+    fn continues_line(&self) -> bool {
+        self.delimiters > 0 || !self.at_line_break()
+    }
+
+    /// Parse with line breaks made insignificant, up to the closing delimiter
+    fn delimited<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        self.delimiters += 1;
+        let result = parse(self);
+        self.delimiters -= 1;
+        result
+    }
+
+    /// End a simple statement: at a `;`, or without one when the next token is
+    /// on a later line, closes the enclosing block or ends the file
+    fn end_statement(&mut self, message: &str) -> ParseResult<()> {
+        if self.match_token(&TokenType::Semicolon) || self.at_line_break()
+            || self.check(&TokenType::RightBrace) || self.is_at_end() {
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    /// Span of the current token, used as the start of a node
+    fn current_span(&self) -> SourceSpan {
+        let token = self.peek();
+        SourceSpan::new(
+            token.location.offset,
+            token.location.offset + token.lexeme.len(),
+            token.location.line,
+            token.location.column,
+        )
+    }
+
+    /// Span from `start` through the end of the last consumed token
+    fn span_from(&self, start: SourceSpan) -> SourceSpan {
+        let last = self.previous();
+        SourceSpan {
+            end: (last.location.offset + last.lexeme.len()).max(start.end),
+            ..start
+        }
+    }
+
     fn match_token(&mut self, token_type: &TokenType) -> bool {
         if self.check(token_type) {
             self.advance();
@@ -796,9 +1849,9 @@ mod tests {
                 assert_eq!(statements.len(), 1);
                 
                 match &statements[0] {
-                    Statement::Print { expression } => {
+                    Statement::Print { expression, .. } => {
                         match expression {
-                            Expression::Literal(Literal::String(s)) => {
+                            Expression::Literal(Literal::String(s), _) => {
                                 assert_eq!(s, "Hello, World!");
                             }
                             _ => panic!("Expected string literal"),
@@ -818,11 +1871,11 @@ mod tests {
                 assert_eq!(statements.len(), 1);
                 
                 match &statements[0] {
-                    Statement::Assignment { mutable, identifier, value } => {
+                    Statement::Assignment { mutable, identifier, value, .. } => {
                         assert!(!mutable);
                         assert_eq!(identifier, "name");
                         match value {
-                            Expression::Literal(Literal::String(s)) => {
+                            Expression::Literal(Literal::String(s), _) => {
                                 assert_eq!(s, "Ovie");
                             }
                             _ => panic!("Expected string literal"),
@@ -843,12 +1896,12 @@ mod tests {
                 assert_eq!(statements.len(), 1);
                 
                 match &statements[0] {
-                    Statement::Assignment { mutable, identifier, value } => {
+                    Statement::Assignment { mutable, identifier, value, .. } => {
                         assert!(mutable);
                         assert_eq!(identifier, "counter");
                         match value {
-                            Expression::Literal(Literal::Number(n)) => {
-                                assert_eq!(*n, 42.0);
+                            Expression::Literal(Literal::Integer(n), _) => {
+                                assert_eq!(*n, 42);
                             }
                             _ => panic!("Expected number literal"),
                         }
@@ -868,10 +1921,10 @@ mod tests {
                 assert_eq!(statements.len(), 1);
                 
                 match &statements[0] {
-                    Statement::Function { name, parameters, body } => {
+                    Statement::Function { name, parameters, body, .. } => {
                         assert_eq!(name, "greet");
                         assert_eq!(parameters.len(), 1);
-                        assert_eq!(parameters[0], "name");
+                        assert_eq!(parameters[0].name, "name");
                         assert_eq!(body.len(), 1);
                     }
                     _ => panic!("Expected function statement"),
@@ -893,10 +1946,10 @@ mod tests {
                         assert_eq!(identifier, "result");
                         // Should parse as 10 + (5 * 2) due to precedence
                         match value {
-                            Expression::Binary { left, operator, right } => {
+                            Expression::Binary { left, operator, right, .. } => {
                                 assert_eq!(*operator, BinaryOperator::Add);
                                 match left.as_ref() {
-                                    Expression::Literal(Literal::Number(n)) => assert_eq!(*n, 10.0),
+                                    Expression::Literal(Literal::Integer(n), _) => assert_eq!(*n, 10),
                                     _ => panic!("Expected number literal"),
                                 }
                                 match right.as_ref() {
@@ -924,16 +1977,16 @@ mod tests {
                 assert_eq!(statements.len(), 1);
                 
                 match &statements[0] {
-                    Statement::For { identifier, iterable, body } => {
-                        assert_eq!(identifier, "i");
+                    Statement::For { pattern, iterable, body, .. } => {
+                        assert!(matches!(pattern, Pattern::Binding(name, _) if name == "i"));
                         match iterable {
-                            Expression::Range { start, end } => {
+                            Expression::Range { start, end, .. } => {
                                 match start.as_ref() {
-                                    Expression::Literal(Literal::Number(n)) => assert_eq!(*n, 1.0),
+                                    Expression::Literal(Literal::Integer(n), _) => assert_eq!(*n, 1),
                                     _ => panic!("Expected number literal for range start"),
                                 }
                                 match end.as_ref() {
-                                    Expression::Literal(Literal::Number(n)) => assert_eq!(*n, 6.0),
+                                    Expression::Literal(Literal::Integer(n), _) => assert_eq!(*n, 6),
                                     _ => panic!("Expected number literal for range end"),
                                 }
                             }
@@ -946,4 +1999,598 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_statement_and_expression_spans() {
+        let source = "seeAm 1;\n  total = count + 2;";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        let span = statements[1].span();
+        assert_eq!((span.line, span.column), (2, 3));
+        assert_eq!(&source[span.start..span.end], "total = count + 2;");
+
+        match &statements[1] {
+            Statement::Assignment { value, .. } => {
+                let value_span = value.span();
+                assert_eq!((value_span.line, value_span.column), (2, 11));
+                assert_eq!(&source[value_span.start..value_span.end], "count + 2");
+            }
+            _ => panic!("Expected assignment statement"),
+        }
+    }
+
+    fn parse_recovering(source: &str) -> (AstNode, Vec<Diagnostic>) {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize_recovering();
+        let mut parser = Parser::new(tokens);
+        parser.parse_with_diagnostics()
+    }
+
+    #[test]
+    fn test_recovers_from_multiple_errors() {
+        let (ast, diagnostics) = parse_recovering("a = (1 + ;\nseeAm a;\nb = * 2;\nseeAm b;");
+
+        let lines: Vec<usize> = diagnostics.iter().map(|d| d.location.line).collect();
+        assert_eq!(lines, vec![1, 3]);
+
+        let AstNode::Program(statements) = &ast;
+        assert_eq!(statements.len(), 4);
+        assert!(matches!(statements[0], Statement::Error { .. }));
+        assert!(matches!(statements[1], Statement::Print { .. }));
+        assert!(matches!(statements[2], Statement::Error { .. }));
+        assert!(matches!(statements[3], Statement::Print { .. }));
+    }
+
+    #[test]
+    fn test_recovers_inside_blocks() {
+        let (ast, diagnostics) = parse_recovering("fn f() {\n  x = ;\n  seeAm 1;\n}\nseeAm 2;");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].location.line, 2);
+
+        let AstNode::Program(statements) = &ast;
+        assert_eq!(statements.len(), 2);
+        match &statements[0] {
+            Statement::Function { body, .. } => {
+                assert!(matches!(body[0], Statement::Error { .. }));
+                assert!(matches!(body[1], Statement::Print { .. }));
+            }
+            _ => panic!("Expected function statement"),
+        }
+    }
+
+    #[test]
+    fn test_parse_reports_first_error() {
+        let error = parse_source("seeAm (1;\nseeAm );").unwrap_err();
+        assert_eq!(error.location().map(|p| p.line), Some(1));
+    }
+
+    #[test]
+    fn test_typed_function_signature() {
+        let ast = parse_source("fn pick(a: Number, items: mut Vec<T>, f: fn(T) -> Boolean) -> Result<T, E> { return a; }").unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        match &statements[0] {
+            Statement::Function { parameters, return_type, .. } => {
+                let types: Vec<String> = parameters.iter()
+                    .map(|p| p.type_annotation.as_ref().unwrap().to_string())
+                    .collect();
+                assert_eq!(types, vec!["Number", "Vec<T>", "fn(T) -> Boolean"]);
+                assert!(!parameters[0].mutable);
+                assert!(parameters[1].mutable);
+                assert_eq!(return_type.as_ref().map(|t| t.to_string()), Some("Result<T, E>".to_string()));
+            }
+            _ => panic!("Expected function statement"),
+        }
+    }
+
+    #[test]
+    fn test_let_with_type_annotation() {
+        let ast = parse_source("let mut total: Number = 1;").unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        match &statements[0] {
+            Statement::VariableDeclaration { mutable, identifier, type_annotation, .. } => {
+                assert!(*mutable);
+                assert_eq!(identifier, "total");
+                assert_eq!(type_annotation, &Some(TypeAnnotation::named("Number")));
+            }
+            _ => panic!("Expected variable declaration"),
+        }
+    }
+
+    #[test]
+    fn test_generic_item_type_parameters() {
+        let source = "struct Pair<A, B> { first: A, second: B }\nenum Maybe<T> { Just(T), Nothing }\nfn swap<A, B>(pair: Pair<A, B>) -> Pair<B, A> { return pair; }";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        let type_parameters: Vec<&Vec<String>> = statements.iter().map(|statement| match statement {
+            Statement::Struct { type_parameters, .. }
+            | Statement::Enum { type_parameters, .. }
+            | Statement::Function { type_parameters, .. } => type_parameters,
+            _ => panic!("Expected an item"),
+        }).collect();
+        assert_eq!(type_parameters, vec![&vec!["A".to_string(), "B".to_string()], &vec!["T".to_string()], &vec!["A".to_string(), "B".to_string()]]);
+
+        assert!(parse_source("fn f<T, T>(x: T) { return x; }").is_err());
+    }
+
+    #[test]
+    fn test_match_expression_arms() {
+        let source = "let area = match shape {\n    Shape.Circle(r) => r * r,\n    Shape.Empty => { seeAm \"empty\"; }\n    0 => -1,\n    _ => 0,\n};";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        let arms = match &statements[0] {
+            Statement::VariableDeclaration { value: Expression::Match { arms, .. }, .. } => arms,
+            other => panic!("Expected a match initializer, found {:?}", other),
+        };
+        assert_eq!(arms.len(), 4);
+        assert!(matches!(&arms[0].pattern, Pattern::Variant { variant_name, payload: VariantPayload::Tuple(payload), .. }
+            if variant_name == "Circle" && matches!(payload.as_slice(), [Pattern::Binding(name, _)] if name == "r")));
+        assert!(arms[0].value.is_some());
+        assert!(matches!(&arms[1].pattern, Pattern::Variant { payload: VariantPayload::Unit, .. }));
+        assert_eq!(arms[1].body.len(), 1);
+        assert!(arms[1].value.is_none());
+        assert!(matches!(&arms[2].pattern, Pattern::Literal(Literal::Integer(n), _) if *n == 0));
+        assert!(matches!(&arms[3].pattern, Pattern::Wildcard(_)));
+
+        assert!(parse_source("match x { 1 => 2 3 => 4 }").is_err());
+    }
+
+    #[test]
+    fn test_multi_field_and_struct_like_variants() {
+        let source = "enum Shape { Rect { w: Int, h: Int }, Move(Int, Int), Empty }
+            let shape = Shape.Rect { w: 2, h: 3 }
+            let step = Shape.Move(1, 2)
+            let area = match shape { Shape.Rect { w, h: height } => w * height, Shape.Move(x, _) => x, _ => 0 }";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        let Statement::Enum { variants, .. } = &statements[0] else { panic!("Expected an enum") };
+        let field_names: Vec<String> = variants[0].payload.fields().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(field_names, vec!["w", "h"]);
+        assert!(matches!(&variants[1].payload, VariantPayload::Tuple(types) if types.len() == 2));
+        assert_eq!(variants[2].payload, VariantPayload::Unit);
+
+        assert!(matches!(&statements[1], Statement::VariableDeclaration {
+            value: Expression::EnumVariantConstruction { payload: VariantPayload::Struct(fields), .. }, ..
+        } if fields.len() == 2));
+        assert!(matches!(&statements[2], Statement::VariableDeclaration {
+            value: Expression::EnumVariantConstruction { payload: VariantPayload::Tuple(values), .. }, ..
+        } if values.len() == 2));
+
+        let Statement::VariableDeclaration { value: Expression::Match { arms, .. }, .. } = &statements[3] else {
+            panic!("Expected a match initializer")
+        };
+        // `w` is shorthand for `w: w`
+        assert_eq!(arms[0].pattern.bindings(), vec!["w", "height"]);
+        assert_eq!(arms[1].pattern.bindings(), vec!["x"]);
+
+        assert!(parse_source("match s { Shape.Move(x, x) => x }").is_err());
+    }
+
+    #[test]
+    fn test_use_mod_and_pub_items() {
+        let source = "use core::{Result, ok};\nuse geometry::shapes::Square;\nmod util;\npub fn area(s: geometry::Square) -> Number { return util::square(s.side); }\nstruct Hidden { x: Number }";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        assert!(matches!(&statements[0], Statement::Use { path, names, .. }
+            if path == &vec!["core".to_string()] && names == &vec!["Result".to_string(), "ok".to_string()]));
+        assert!(matches!(&statements[1], Statement::Use { path, names, .. }
+            if path.join("::") == "geometry::shapes" && names == &vec!["Square".to_string()]));
+        assert!(matches!(&statements[2], Statement::Module { name, .. } if name == "util"));
+
+        match &statements[3] {
+            Statement::Function { public, parameters, body, span, .. } => {
+                assert!(*public);
+                assert_eq!(span.start, source.find("pub fn").unwrap());
+                assert!(matches!(&parameters[0].type_annotation, Some(TypeAnnotation::Named { name, .. }) if name == "geometry::Square"));
+                assert!(matches!(&body[0], Statement::Return { value: Some(Expression::Call { function, .. }), .. }
+                    if function == "util::square"));
+            }
+            other => panic!("Expected a function, found {:?}", other),
+        }
+        assert!(matches!(&statements[4], Statement::Struct { public: false, .. }));
+
+        assert!(parse_source("pub seeAm 1;").is_err());
+        assert!(parse_source("use core;").is_err());
+    }
+
+    #[test]
+    fn test_closures_and_indirect_calls() {
+        let source = "let add = fn(a: Number, b) -> Number { return a + b; };\nmake_adder(1)(2);\nfn(x) { seeAm x; }(3);";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        match &statements[0] {
+            Statement::VariableDeclaration { value: Expression::Closure { parameters, return_type, body, .. }, .. } => {
+                assert_eq!(parameters.len(), 2);
+                assert_eq!(parameters[0].type_annotation, Some(TypeAnnotation::named("Number")));
+                assert!(parameters[1].type_annotation.is_none());
+                assert_eq!(return_type, &Some(TypeAnnotation::named("Number")));
+                assert!(matches!(&body[0], Statement::Return { .. }));
+            }
+            other => panic!("Expected a closure initializer, found {:?}", other),
+        }
+        assert!(matches!(&statements[1], Statement::Expression { expression: Expression::IndirectCall { callee, arguments, .. }, .. }
+            if matches!(**callee, Expression::Call { ref function, .. } if function == "make_adder") && arguments.len() == 1));
+        assert!(matches!(&statements[2], Statement::Expression { expression: Expression::IndirectCall { callee, .. }, .. }
+            if matches!(**callee, Expression::Closure { .. })));
+
+        assert!(parse_source("let f = fn(x) x;").is_err());
+    }
+
+    #[test]
+    fn test_unsafe_blocks_and_functions() {
+        let source = "unsafe fn peek() { seeAm 1; }\npub unsafe fn poke() {}\nunsafe {\n    peek();\n}";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        assert!(matches!(&statements[0], Statement::Function { public: false, is_unsafe: true, name, .. } if name == "peek"));
+        assert!(matches!(&statements[1], Statement::Function { public: true, is_unsafe: true, span, .. } if span.column == 1));
+        match &statements[2] {
+            Statement::Unsafe { body, span } => {
+                assert_eq!(body.len(), 1);
+                assert_eq!((span.line, span.column), (3, 1));
+                assert_eq!(&source[span.start..span.end], "unsafe {\n    peek();\n}");
+            }
+            other => panic!("Expected an unsafe block, found {:?}", other),
+        }
+
+        assert!(parse_source("unsafe seeAm 1;").is_err());
+    }
+
+    #[test]
+    fn test_impl_blocks_and_method_calls() {
+        let source = "impl Box<T> {\n    fn get(self) -> T { return self.value; }\n    unsafe fn raw() {}\n}\n\
+            seeAm b.get();\nseeAm Point.new(1, 2);\nseeAm Shape.Circle(3);";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        match &statements[0] {
+            Statement::Impl { trait_name: None, type_name, type_parameters, methods, span, .. } => {
+                assert_eq!(type_name, "Box");
+                assert_eq!(type_parameters, &vec!["T".to_string()]);
+                assert!(matches!(&methods[0], Statement::Function { name, parameters, .. } if name == "get" && parameters[0].name == "self"));
+                assert!(matches!(&methods[1], Statement::Function { is_unsafe: true, .. }));
+                assert_eq!((span.line, span.column), (1, 1));
+            }
+            other => panic!("Expected an impl block, found {:?}", other),
+        }
+        assert!(matches!(&statements[1], Statement::Print { expression: Expression::MethodCall { receiver, method, arguments, .. }, .. }
+            if matches!(receiver.as_ref(), Expression::Identifier(name, _) if name == "b") && method == "get" && arguments.is_empty()));
+        assert!(matches!(&statements[2], Statement::Print { expression: Expression::MethodCall { method, arguments, .. }, .. }
+            if method == "new" && arguments.len() == 2));
+        // A capitalized member of a capitalized type is still an enum variant
+        assert!(matches!(&statements[3], Statement::Print { expression: Expression::EnumVariantConstruction { .. }, .. }));
+
+        assert!(parse_source("impl Point { x = 1; }").is_err());
+    }
+
+    #[test]
+    fn test_integer_and_number_literals() {
+        let ast = parse_source("seeAm 7;\nseeAm 7.0;\nseeAm 9223372036854775807;").unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        assert!(matches!(&statements[0], Statement::Print { expression: Expression::Literal(Literal::Integer(7), _), .. }));
+        assert!(matches!(&statements[1], Statement::Print { expression: Expression::Literal(Literal::Number(n), _), .. } if *n == 7.0));
+        assert!(matches!(&statements[2], Statement::Print { expression: Expression::Literal(Literal::Integer(i64::MAX), _), .. }));
+
+        let overflow = parse_source("seeAm 9223372036854775808;").unwrap_err();
+        assert!(overflow.to_string().contains("Integer literal '9223372036854775808' is out of range for Int"));
+    }
+
+    #[test]
+    fn test_newline_terminated_statements() {
+        let source = "use core::ok\nx = 1\nmut y = x + 2;\nlet z: [Int] = [1, 2]\nseeAm x; seeAm y\nfn f() {\n    return\n}\nfn g() { return 1 }";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        assert_eq!(statements.len(), 8);
+        assert!(matches!(&statements[0], Statement::Use { .. }));
+        assert!(matches!(&statements[2], Statement::Assignment { mutable: true, value: Expression::Binary { .. }, .. }));
+        match &statements[3] {
+            Statement::VariableDeclaration { type_annotation: Some(annotation), .. } => {
+                assert_eq!(annotation.to_string(), "[Int]");
+            }
+            _ => panic!("Expected variable declaration"),
+        }
+        assert!(matches!(&statements[5], Statement::Print { .. }));
+        match (&statements[6], &statements[7]) {
+            (Statement::Function { body: bare, .. }, Statement::Function { body: valued, .. }) => {
+                assert!(matches!(bare[..], [Statement::Return { value: None, .. }]));
+                assert!(matches!(valued[..], [Statement::Return { value: Some(_), .. }]));
+            }
+            _ => panic!("Expected function statements"),
+        }
+
+        let error = parse_source("x = 1 y = 2").unwrap_err();
+        assert!(error.to_string().contains("Expected ';' or newline after assignment"));
+        assert!(parse_source("seeAm 1 seeAm 2").is_err());
+    }
+
+    #[test]
+    fn test_line_continuation() {
+        let source = "total = 1 +\n    2\nseeAm f(1,\n    2)\nitems = [\n    1,\n    2,\n]\nsum = (1\n    + 2)\na = b\n-1\nseeAm x\n(y)";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        assert_eq!(statements.len(), 8);
+        assert!(matches!(&statements[0], Statement::Assignment { value: Expression::Binary { .. }, .. }));
+        assert!(matches!(&statements[1], Statement::Print { expression: Expression::Call { arguments, .. }, .. } if arguments.len() == 2));
+        assert!(matches!(&statements[2], Statement::Assignment { value: Expression::ArrayLiteral { elements, .. }, .. } if elements.len() == 2));
+        assert!(matches!(&statements[3], Statement::Assignment { value: Expression::Binary { .. }, .. }));
+
+        // An operator that starts a line starts a new statement
+        assert!(matches!(&statements[4], Statement::Assignment { value: Expression::Identifier(..), .. }));
+        assert!(matches!(&statements[5], Statement::Expression { expression: Expression::Unary { .. }, .. }));
+        assert!(matches!(&statements[6], Statement::Print { expression: Expression::Identifier(..), .. }));
+        assert!(matches!(&statements[7], Statement::Expression { expression: Expression::Identifier(..), .. }));
+
+        assert!(parse_source("a = b\n    .c").is_err());
+        assert!(parse_source("ok = a\n    && b").is_err());
+    }
+
+    #[test]
+    fn test_recovers_at_line_breaks() {
+        let (ast, diagnostics) = parse_recovering("x = 1 2\nseeAm 3\ny = * 4\nseeAm 5");
+        let lines: Vec<usize> = diagnostics.iter().map(|d| d.location.line).collect();
+        assert_eq!(lines, vec![1, 3]);
+
+        let AstNode::Program(statements) = &ast;
+        assert_eq!(statements.len(), 4);
+        assert!(matches!(statements[1], Statement::Print { .. }));
+        assert!(matches!(statements[3], Statement::Print { .. }));
+    }
+
+    #[test]
+    fn test_recovers_from_independent_errors_in_one_file() {
+        let source = "x = 1 @ 2\n\
+            struct Point {\n    x: Int,\n    y Int\n}\n\
+            seeAm #\n\
+            struct Open {\n    a: Int,\n\
+            fn f() {\n    z = ;\n    seeAm 1\n}\n\
+            seeAm f()";
+        let (ast, diagnostics) = parse_recovering(source);
+        let found: Vec<(&str, usize, usize)> = diagnostics.iter()
+            .map(|d| (d.code.as_str(), d.location.line, d.location.column))
+            .collect();
+        assert_eq!(found, vec![("E0001", 1, 7), ("E0002", 4, 7), ("E0001", 6, 7), ("E0002", 9, 1), ("E0002", 10, 9)]);
+        assert!(diagnostics[0].message.contains("Unexpected character: '@'"), "{}", diagnostics[0].message);
+
+        // The function after the struct left open is still parsed, and so is the rest of the file
+        let AstNode::Program(statements) = &ast;
+        assert!(matches!(&statements[..], [
+            Statement::Error { .. },
+            Statement::Error { .. },
+            Statement::Error { .. },
+            Statement::Error { .. },
+            Statement::Function { .. },
+            Statement::Print { .. },
+        ]), "{:#?}", statements);
+
+        // Without recovery the first error in the file is the one reported
+        let tokens = Lexer::new(source).tokenize_recovering();
+        let error = Parser::new(tokens).parse().unwrap_err().to_diagnostic();
+        assert_eq!((error.code.as_str(), error.location.line), ("E0001", 1));
+        assert!(Lexer::new(source).tokenize().is_err());
+    }
+
+    #[test]
+    fn test_else_if_and_semicolon_free_example() {
+        let ast = parse_source("if a {\n    seeAm 1\n} else if b {\n    seeAm 2\n} else {\n    seeAm 3\n}").unwrap();
+        let AstNode::Program(statements) = &ast;
+        match &statements[0] {
+            Statement::If { else_block: Some(else_block), .. } => {
+                assert!(matches!(&else_block[..], [Statement::If { else_block: Some(_), .. }]));
+            }
+            _ => panic!("Expected if statement"),
+        }
+
+        let example = include_str!("../../examples/natural_language.ov");
+        let ast = parse_source(example).expect("natural_language.ov should parse without semicolons");
+        let AstNode::Program(statements) = &ast;
+        let function_count = statements.iter().filter(|s| matches!(s, Statement::Function { .. })).count();
+        assert_eq!(function_count, 21);
+    }
+
+    #[test]
+    fn test_place_and_compound_assignment() {
+        let ast = parse_source("account.balance = 10\nitems[i] += 1\ngrid[0][1].cell *= 2\ncount -= 1\nx = 1").unwrap();
+        let AstNode::Program(statements) = &ast;
+        assert_eq!(statements.len(), 5);
+        assert!(matches!(&statements[0], Statement::PlaceAssignment { target: Expression::FieldAccess { .. }, operator: None, .. }));
+        assert!(matches!(&statements[1], Statement::PlaceAssignment { target: Expression::Index { .. }, operator: Some(BinaryOperator::Add), .. }));
+        assert!(matches!(&statements[2], Statement::PlaceAssignment { target: Expression::FieldAccess { .. }, operator: Some(BinaryOperator::Multiply), .. }));
+        assert!(matches!(&statements[3], Statement::PlaceAssignment { target: Expression::Identifier(..), operator: Some(BinaryOperator::Subtract), .. }));
+
+        // A plain assignment to a name still declares or rebinds it
+        assert!(matches!(&statements[4], Statement::Assignment { .. }));
+
+        assert!(parse_source("f(1) = 2").is_err());
+        assert!(parse_source("a + b += 1").is_err());
+        assert!(parse_source("x\n+= 1").is_err());
+    }
+
+    #[test]
+    fn test_break_continue_and_labels() {
+        let ast = parse_source("outer: for i in 0..3 {\n    while true {\n        break outer\n        continue\n    }\n}\nwhile x {\n    break\n    y = 1\n}").unwrap();
+        let AstNode::Program(statements) = &ast;
+        assert_eq!(statements.len(), 2);
+        match &statements[0] {
+            Statement::For { label: Some(label), body, .. } => {
+                assert_eq!(label, "outer");
+                match &body[0] {
+                    Statement::While { label: None, body, .. } => {
+                        assert!(matches!(&body[0], Statement::Break { label: Some(label), .. } if label == "outer"));
+                        assert!(matches!(&body[1], Statement::Continue { label: None, .. }));
+                    }
+                    _ => panic!("Expected inner while loop"),
+                }
+            }
+            _ => panic!("Expected labeled for loop"),
+        }
+
+        // A label on the next line is a new statement, not the break target
+        match &statements[1] {
+            Statement::While { body, .. } => {
+                assert!(matches!(&body[0], Statement::Break { label: None, .. }));
+                assert!(matches!(&body[1], Statement::Assignment { .. }));
+            }
+            _ => panic!("Expected while loop"),
+        }
+
+        assert!(parse_source("outer: seeAm 1").is_err());
+        assert!(parse_source("while x { break 1 }").is_err());
+    }
+
+    #[test]
+    fn test_tuples_and_destructuring() {
+        let ast = parse_source("let (a, _) = f()\nlet mut pair: (Int, String) = (1, \"x\")\nfor (k, v) in items { }\nseeAm nested.0.1\nseeAm (1)\nseeAm (1,)").unwrap();
+        let AstNode::Program(statements) = &ast;
+        assert_eq!(statements.len(), 6);
+        match &statements[0] {
+            Statement::Destructure { mutable: false, pattern: Pattern::Tuple(elements, _), .. } => {
+                assert!(matches!(&elements[..], [Pattern::Binding(name, _), Pattern::Wildcard(_)] if name == "a"));
+            }
+            _ => panic!("Expected destructuring let"),
+        }
+        match &statements[1] {
+            Statement::VariableDeclaration { mutable: true, type_annotation: Some(annotation), value: Expression::Tuple { .. }, .. } => {
+                assert_eq!(annotation.to_string(), "(Int, String)");
+            }
+            _ => panic!("Expected tuple declaration"),
+        }
+        assert!(matches!(&statements[2], Statement::For { pattern: Pattern::Tuple(elements, _), .. } if elements.len() == 2));
+
+        // `.0.1` lexes as a float but is two field accesses
+        match &statements[3] {
+            Statement::Print { expression: Expression::FieldAccess { object, field, .. }, .. } => {
+                assert_eq!(field, "1");
+                assert!(matches!(object.as_ref(), Expression::FieldAccess { field, .. } if field == "0"));
+            }
+            _ => panic!("Expected tuple field access"),
+        }
+
+        // Parentheses alone group; a trailing comma makes a one-element tuple
+        assert!(matches!(&statements[4], Statement::Print { expression: Expression::Literal(..), .. }));
+        assert!(matches!(&statements[5], Statement::Print { expression: Expression::Tuple { elements, .. }, .. } if elements.len() == 1));
+
+        assert!(parse_source("let (a, a) = f()").is_err());
+        assert!(parse_source("let (a, 1) = f()").is_err());
+        assert!(parse_source("seeAm t.-1").is_err());
+    }
+
+    #[test]
+    fn test_constants_and_const_fn() {
+        let ast = parse_source("pub const MAX: Int = limit() * 2
+const fn limit() -> Int { return 8 }
+pub const fn twice(n: Int) -> Int { return n * 2 }").unwrap();
+        let AstNode::Program(statements) = &ast;
+        match &statements[0] {
+            Statement::Const { public: true, name, type_annotation, value: Expression::Binary { .. }, .. } => {
+                assert_eq!(name, "MAX");
+                assert_eq!(type_annotation.to_string(), "Int");
+            }
+            _ => panic!("Expected constant definition"),
+        }
+        assert!(matches!(&statements[1], Statement::Function { public: false, is_const: true, name, .. } if name == "limit"));
+        assert!(matches!(&statements[2], Statement::Function { public: true, is_const: true, is_unsafe: false, .. }));
+
+        let error = parse_source("const MAX = 1").unwrap_err();
+        assert!(error.to_string().contains("Expected ':' and a type after constant name"));
+        assert!(parse_source("const MAX: Int").is_err());
+        assert!(parse_source("const unsafe fn f() { }").is_err());
+    }
+
+    #[test]
+    fn test_error_propagation() {
+        let ast = parse_source("let n = parse(text)?.value\nseeAm -lookup()??\nseeAm a + b?").unwrap();
+        let AstNode::Program(statements) = &ast;
+        match &statements[0] {
+            Statement::VariableDeclaration { value: Expression::FieldAccess { object, .. }, .. } => {
+                assert!(matches!(object.as_ref(), Expression::Try { operand, .. } if matches!(operand.as_ref(), Expression::Call { .. })));
+            }
+            _ => panic!("Expected field access on a propagated value"),
+        }
+
+        // `?` binds tighter than prefix and binary operators
+        match &statements[1] {
+            Statement::Print { expression: Expression::Unary { operand, .. }, .. } => match operand.as_ref() {
+                Expression::Try { operand, .. } => assert!(matches!(operand.as_ref(), Expression::Try { .. })),
+                _ => panic!("Expected nested '?'"),
+            },
+            _ => panic!("Expected negated '?'"),
+        }
+        assert!(matches!(&statements[2], Statement::Print { expression: Expression::Binary { right, .. }, .. }
+            if matches!(right.as_ref(), Expression::Try { .. })));
+
+        assert!(parse_source("seeAm ?x").is_err());
+    }
+
+    #[test]
+    fn test_traits_impls_and_bounds() {
+        let source = "pub trait Show {\n    fn show(self) -> String;\n    fn twice(self) -> String { return self.show() + self.show(); }\n}\n\
+            impl shapes::Show for Pair<A: Show + Eq, B> { fn show(self) -> String { return \"p\"; } }\n\
+            fn all<T: Show>(items: [T]) {}";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        match &statements[0] {
+            Statement::Trait { public: true, name, methods, .. } => {
+                assert_eq!(name, "Show");
+                assert!(methods[0].body.is_none());
+                assert_eq!(methods[0].parameters[0].name, "self");
+                assert!(matches!(&methods[1].body, Some(body) if body.len() == 1));
+            }
+            other => panic!("Expected a trait, found {:?}", other),
+        }
+        match &statements[1] {
+            Statement::Impl { trait_name: Some(trait_name), type_name, type_parameters, bounds, .. } => {
+                assert_eq!(trait_name, "shapes::Show");
+                assert_eq!(type_name, "Pair");
+                assert_eq!(type_parameters, &vec!["A".to_string(), "B".to_string()]);
+                assert_eq!(bounds.len(), 1);
+                assert_eq!((bounds[0].parameter.as_str(), bounds[0].traits.clone()), ("A", vec!["Show".to_string(), "Eq".to_string()]));
+            }
+            other => panic!("Expected a trait impl, found {:?}", other),
+        }
+        assert!(matches!(&statements[2], Statement::Function { bounds, .. } if bounds[0].parameter == "T"));
+
+        assert!(parse_source("trait Eq<T> { fn eq(self, other: T) -> Boolean; }").unwrap_err().to_string()
+            .contains("Traits cannot have type parameters"));
+        assert!(parse_source("struct Box<T: Show> { value: T }").unwrap_err().to_string()
+            .contains("Trait bounds are only allowed on functions and impl blocks"));
+    }
+
+    #[test]
+    fn test_contract_clauses() {
+        let source = "struct Interval { lo: Int, invariant self.lo<=self.hi, hi: Int }
+            fn half(n: Int) -> Int requires n >= 0 ensures result * 2 <= n { return n / 2; }";
+        let ast = parse_source(source).unwrap();
+
+        let AstNode::Program(statements) = &ast;
+        match &statements[0] {
+            Statement::Struct { fields, invariants, .. } => {
+                assert_eq!(fields.len(), 2);
+                // The text keeps the spacing of the source
+                assert_eq!(invariants[0].text, "self.lo<=self.hi");
+            }
+            other => panic!("Expected a struct, found {:?}", other),
+        }
+        match &statements[1] {
+            Statement::Function { requires, ensures, body, .. } => {
+                assert_eq!(requires[0].text, "n >= 0");
+                assert!(matches!(&requires[0].condition, Expression::Binary { operator: BinaryOperator::GreaterEqual, .. }));
+                assert_eq!(ensures[0].text, "result * 2 <= n");
+                assert_eq!(body.len(), 1);
+            }
+            other => panic!("Expected a function, found {:?}", other),
+        }
+
+        assert!(parse_source("fn f(n: Int) requires { }").is_err());
+    }
+}
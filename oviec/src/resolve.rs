@@ -0,0 +1,143 @@
+//! Name resolution diagnostics
+//!
+//! The HIR builder resolves every name it meets against the scopes of the
+//! `SymbolTable`, the module scope, and the type table. A name that does not
+//! resolve is reported with the names in scope it is closest to by edit
+//! distance, as "did you mean" suggestions. Each suggestion carries a
+//! `CodeFix` that replaces the misspelled name, which `ovie fix` applies.
+
+use crate::ast::SourceSpan;
+use crate::error::{CodeFix, ErrorSuggestion, OvieError, ResolutionErrorKind, SourcePosition, TextReplacement};
+use crate::normalizer::Normalizer;
+
+/// Suggestions offered for one unresolved name, at most
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Number of single-character insertions, deletions, substitutions, and
+/// transpositions of adjacent characters that turn `a` into `b`
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Three rows of the distance matrix: two rows back, the previous row, and the current one
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let substitution = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + substitution);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// The candidates close enough to `name` to be what it was meant as, closest
+/// first. A third of the name's characters may be wrong, and at least one.
+pub fn similar_names<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<(&'a str, usize)> {
+    let limit = (name.chars().count() / 3).max(1);
+    let mut similar: Vec<(&str, usize)> = candidates.into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (candidate, edit_distance(name, candidate)))
+        .filter(|(_, distance)| *distance <= limit)
+        .collect();
+    similar.sort();
+    similar.dedup();
+    similar.sort_by_key(|(_, distance)| *distance);
+    similar.truncate(MAX_SUGGESTIONS);
+    similar
+}
+
+/// Span of the name `length` bytes long that starts `offset` bytes into `span`;
+/// names are on the line their expression starts on
+pub fn name_span(span: SourceSpan, offset: usize, length: usize) -> SourceSpan {
+    SourceSpan::new(span.start + offset, span.start + offset + length, span.line, span.column + offset)
+}
+
+/// Error for the unresolved `name` at `span`, suggesting the closest `candidates`.
+/// Names the normalizer rewrites, like the builtin `print`, cannot be written in
+/// source, so they are never suggested.
+pub fn unresolved_name<'a>(
+    kind: ResolutionErrorKind,
+    name: &str,
+    message: impl Into<String>,
+    span: SourceSpan,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> OvieError {
+    let normalizer = Normalizer::new();
+    let candidates = candidates.into_iter().filter(|candidate| !normalizer.rewrites(candidate));
+    let length = name.chars().count().max(1) as f32;
+    let suggestions = similar_names(name, candidates).into_iter()
+        .map(|(candidate, distance)| {
+            let end = SourcePosition::new(None, span.line, span.column + name.len(), span.start + name.len());
+            let code_fix = CodeFix {
+                description: format!("Replace '{}' with '{}'", name, candidate),
+                replacements: vec![TextReplacement {
+                    start: span.start_position(),
+                    end,
+                    new_text: candidate.to_string(),
+                }],
+            };
+            ErrorSuggestion::syntax_fix(
+                format!("did you mean '{}'?", candidate),
+                1.0 - distance as f32 / length,
+                Some(code_fix),
+            )
+        })
+        .collect();
+    OvieError::kind_error(0, 0, kind, name, message)
+        .with_suggestions(suggestions)
+        .located_at(span.start_position())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("count", "count"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        // A swap of adjacent characters is one edit
+        assert_eq!(edit_distance("totla", "total"), 1);
+        assert_eq!(edit_distance("ab", "ba"), 1);
+    }
+
+    #[test]
+    fn test_similar_names_are_close_and_closest_first() {
+        // A third of seven characters rounds down to two edits; the name itself is not similar
+        let candidates = ["counter", "count", "winter", "coutner", "countr", "counters", "countr"];
+        assert_eq!(similar_names("counter", candidates), vec![("counters", 1), ("countr", 1), ("coutner", 1)]);
+        assert_eq!(similar_names("counter", ["count", "winter"]), vec![("count", 2)]);
+        // Short names still allow one wrong character
+        assert_eq!(similar_names("x", ["y", "xy", "zz"]), vec![("xy", 1), ("y", 1)]);
+        assert!(similar_names("count", ["width", "height"]).is_empty());
+    }
+
+    #[test]
+    fn test_unresolved_name_suggests_replacements() {
+        let span = SourceSpan::new(10, 15, 2, 7);
+        let error = unresolved_name(ResolutionErrorKind::Variable, "totla", "Undefined variable 'totla'", span, ["total", "print", "prnt"]);
+        let diagnostic = error.to_diagnostic();
+        assert_eq!(diagnostic.suggestions.len(), 1);
+        assert_eq!(diagnostic.suggestions[0].message, "did you mean 'total'?");
+
+        let replacement = &diagnostic.suggestions[0].code_fix.as_ref().unwrap().replacements[0];
+        assert_eq!((replacement.start.offset, replacement.end.offset), (10, 15));
+        assert_eq!((replacement.end.line, replacement.end.column), (2, 12));
+        assert_eq!(replacement.new_text, "total");
+
+        // Names the normalizer rewrites are never suggested
+        let error = unresolved_name(ResolutionErrorKind::Function, "prnt", "Undefined function 'prnt'", span, ["print"]);
+        assert!(error.to_diagnostic().suggestions.is_empty());
+    }
+}
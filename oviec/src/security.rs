@@ -3,12 +3,13 @@
 //! This module implements security features including network call monitoring,
 //! cryptographic verification, and supply chain isolation.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
 use crate::{OvieResult, OvieError, AstNode, Statement, Expression};
+use crate::ast::SourceSpan;
 
 /// Unsafe operation types that require explicit handling
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -27,6 +28,38 @@ pub enum UnsafeOperation {
     UnverifiedNetworkAccess,
     /// File system operations outside sandbox
     UnsafeFileAccess,
+    /// Calls to functions declared `unsafe fn`
+    UnsafeFunctionCall,
+}
+
+impl UnsafeOperation {
+    /// Classify a call to a declared unsafe function by the operation its name
+    /// denotes; other unsafe functions are plain unsafe function calls
+    pub fn classify(function: &str) -> Self {
+        match function {
+            "malloc" | "free" | "memcpy" | "memset" => UnsafeOperation::RawMemoryAccess,
+            "system" | "exec" | "fork" => UnsafeOperation::SystemCall,
+            "ptr_read" | "ptr_write" => UnsafeOperation::PointerArithmetic,
+            "cast_ptr" => UnsafeOperation::UnsafeCast,
+            "raw_socket" | "bind_socket" => UnsafeOperation::UnverifiedNetworkAccess,
+            "file_raw_read" | "file_raw_write" => UnsafeOperation::UnsafeFileAccess,
+            _ => UnsafeOperation::UnsafeFunctionCall,
+        }
+    }
+
+    /// Short description of what the operation does, for diagnostics
+    pub fn description(&self) -> &'static str {
+        match self {
+            UnsafeOperation::RawMemoryAccess => "raw memory access",
+            UnsafeOperation::ForeignFunctionCall => "a foreign function call",
+            UnsafeOperation::PointerArithmetic => "pointer arithmetic",
+            UnsafeOperation::UnsafeCast => "an unsafe cast",
+            UnsafeOperation::SystemCall => "a system call",
+            UnsafeOperation::UnverifiedNetworkAccess => "unverified network access",
+            UnsafeOperation::UnsafeFileAccess => "file access outside the sandbox",
+            UnsafeOperation::UnsafeFunctionCall => "a call to an unsafe function",
+        }
+    }
 }
 
 /// Unsafe operation audit entry
@@ -54,11 +87,28 @@ pub struct SourceLocation {
     pub column: u32,
 }
 
+/// An `unsafe { ... }` block found in the analyzed source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsafeBlockEntry {
+    /// File containing the block
+    pub file: String,
+    /// Exact span of the block, from the `unsafe` keyword to the closing brace
+    pub span: SourceSpan,
+    /// Number of unsafe operations performed inside the block
+    pub operation_count: usize,
+}
+
 /// Unsafe operation analyzer and auditor
 #[derive(Debug)]
 pub struct UnsafeOperationAnalyzer {
     /// Audit log of unsafe operations
     audit_log: Arc<Mutex<Vec<UnsafeAuditEntry>>>,
+    /// Unsafe blocks seen by the analyzer
+    unsafe_blocks: Arc<Mutex<Vec<UnsafeBlockEntry>>>,
+    /// Functions declared `unsafe fn` in the source being analyzed
+    unsafe_functions: Arc<Mutex<HashSet<String>>>,
+    /// Methods declared `unsafe fn` in impl blocks of the source being analyzed
+    unsafe_methods: Arc<Mutex<HashSet<String>>>,
     /// Whether unsafe operations are allowed
     unsafe_allowed: Arc<Mutex<bool>>,
     /// Require explicit unsafe blocks
@@ -70,6 +120,9 @@ impl UnsafeOperationAnalyzer {
     pub fn new() -> Self {
         Self {
             audit_log: Arc::new(Mutex::new(Vec::new())),
+            unsafe_blocks: Arc::new(Mutex::new(Vec::new())),
+            unsafe_functions: Arc::new(Mutex::new(HashSet::new())),
+            unsafe_methods: Arc::new(Mutex::new(HashSet::new())),
             unsafe_allowed: Arc::new(Mutex::new(false)),
             require_explicit_unsafe: Arc::new(Mutex::new(true)),
         }
@@ -114,146 +167,279 @@ impl UnsafeOperationAnalyzer {
         // Analyze all statements in the AST
         match node {
             AstNode::Program(statements) => {
+                // Calls may precede the declaration of the unsafe function they
+                // call, and declarations from an earlier analysis do not apply
+                let mut functions = HashSet::new();
+                let mut methods = HashSet::new();
+                Self::collect_unsafe_functions(statements, &mut functions, &mut methods);
+                *self.unsafe_functions.lock().unwrap() = functions;
+                *self.unsafe_methods.lock().unwrap() = methods;
                 for stmt in statements {
-                    self.analyze_statement(stmt, file_name, unsafe_ops)?;
+                    self.analyze_statement(stmt, file_name, false, unsafe_ops)?;
                 }
             }
         }
         Ok(())
     }
 
-    /// Analyze a statement for unsafe operations
-    fn analyze_statement(&self, stmt: &Statement, file_name: &str, unsafe_ops: &mut Vec<UnsafeAuditEntry>) -> OvieResult<()> {
+    /// Collect the names of functions and impl methods declared `unsafe fn`,
+    /// including those nested in other function bodies and blocks
+    fn collect_unsafe_functions(statements: &[Statement], functions: &mut HashSet<String>, methods: &mut HashSet<String>) {
+        for stmt in statements {
+            match stmt {
+                Statement::Function { name, is_unsafe, body, .. } => {
+                    if *is_unsafe {
+                        functions.insert(name.clone());
+                    }
+                    Self::collect_unsafe_functions(body, functions, methods);
+                }
+                Statement::Impl { methods: items, .. } => {
+                    for item in items {
+                        if let Statement::Function { name, is_unsafe, body, .. } = item {
+                            if *is_unsafe {
+                                methods.insert(name.clone());
+                            }
+                            Self::collect_unsafe_functions(body, functions, methods);
+                        }
+                    }
+                }
+                Statement::Trait { methods: items, .. } => {
+                    for body in items.iter().filter_map(|method| method.body.as_ref()) {
+                        Self::collect_unsafe_functions(body, functions, methods);
+                    }
+                }
+                Statement::If { then_block, else_block, .. } => {
+                    Self::collect_unsafe_functions(then_block, functions, methods);
+                    if let Some(else_stmts) = else_block {
+                        Self::collect_unsafe_functions(else_stmts, functions, methods);
+                    }
+                }
+                Statement::While { body, .. } | Statement::For { body, .. } | Statement::Unsafe { body, .. }
+                | Statement::FunctionDeclaration { body, .. } => {
+                    Self::collect_unsafe_functions(body, functions, methods);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Record a call to a declared unsafe function or method
+    fn audit_call(&self, function: &str, span: &SourceSpan, file_name: &str, in_unsafe: bool, unsafe_ops: &mut Vec<UnsafeAuditEntry>) {
+        let operation = UnsafeOperation::classify(function);
+        let description = match operation {
+            UnsafeOperation::UnsafeFunctionCall => format!("Call to unsafe function '{}'", function),
+            _ => format!("Call to '{}' performs {}", function, operation.description()),
+        };
+        unsafe_ops.push(UnsafeAuditEntry {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            operation,
+            location: SourceLocation {
+                file: file_name.to_string(),
+                line: span.line as u32,
+                column: span.column as u32,
+            },
+            description,
+            explicitly_unsafe: in_unsafe,
+            justification: None,
+        });
+    }
+
+    /// Analyze a statement for unsafe operations; `in_unsafe` is set inside
+    /// unsafe blocks and unsafe functions
+    fn analyze_statement(&self, stmt: &Statement, file_name: &str, in_unsafe: bool, unsafe_ops: &mut Vec<UnsafeAuditEntry>) -> OvieResult<()> {
         match stmt {
-            Statement::Assignment { mutable: _, identifier: _, value } => {
-                self.analyze_expression(value, file_name, unsafe_ops)?;
+            Statement::Assignment { mutable: _, identifier: _, value, .. } => {
+                self.analyze_expression(value, file_name, in_unsafe, unsafe_ops)?;
             }
-            Statement::VariableDeclaration { mutable: _, identifier: _, value } => {
-                self.analyze_expression(value, file_name, unsafe_ops)?;
+            Statement::PlaceAssignment { target, value, .. } => {
+                self.analyze_expression(target, file_name, in_unsafe, unsafe_ops)?;
+                self.analyze_expression(value, file_name, in_unsafe, unsafe_ops)?;
             }
-            Statement::Print { expression } => {
-                self.analyze_expression(expression, file_name, unsafe_ops)?;
+            Statement::VariableDeclaration { mutable: _, identifier: _, value, .. } | Statement::Destructure { value, .. }
+            | Statement::Const { value, .. } => {
+                self.analyze_expression(value, file_name, in_unsafe, unsafe_ops)?;
             }
-            Statement::If { condition, then_block, else_block } => {
-                self.analyze_expression(condition, file_name, unsafe_ops)?;
+            Statement::Print { expression, .. } => {
+                self.analyze_expression(expression, file_name, in_unsafe, unsafe_ops)?;
+            }
+            Statement::If { condition, then_block, else_block, .. } => {
+                self.analyze_expression(condition, file_name, in_unsafe, unsafe_ops)?;
                 for stmt in then_block {
-                    self.analyze_statement(stmt, file_name, unsafe_ops)?;
+                    self.analyze_statement(stmt, file_name, in_unsafe, unsafe_ops)?;
                 }
                 if let Some(else_stmts) = else_block {
                     for stmt in else_stmts {
-                        self.analyze_statement(stmt, file_name, unsafe_ops)?;
+                        self.analyze_statement(stmt, file_name, in_unsafe, unsafe_ops)?;
                     }
                 }
             }
-            Statement::While { condition, body } => {
-                self.analyze_expression(condition, file_name, unsafe_ops)?;
+            Statement::While { condition, body, .. } => {
+                self.analyze_expression(condition, file_name, in_unsafe, unsafe_ops)?;
+                for stmt in body {
+                    self.analyze_statement(stmt, file_name, in_unsafe, unsafe_ops)?;
+                }
+            }
+            Statement::Function { is_unsafe, body, .. } => {
                 for stmt in body {
-                    self.analyze_statement(stmt, file_name, unsafe_ops)?;
+                    self.analyze_statement(stmt, file_name, *is_unsafe, unsafe_ops)?;
                 }
             }
-            Statement::Function { name: _, parameters: _, body } => {
+            Statement::Unsafe { body, span } => {
+                let first_operation = unsafe_ops.len();
                 for stmt in body {
-                    self.analyze_statement(stmt, file_name, unsafe_ops)?;
+                    self.analyze_statement(stmt, file_name, true, unsafe_ops)?;
                 }
+                self.unsafe_blocks.lock().unwrap().push(UnsafeBlockEntry {
+                    file: file_name.to_string(),
+                    span: *span,
+                    operation_count: unsafe_ops.len() - first_operation,
+                });
             }
-            Statement::FunctionDeclaration { name: _, parameters: _, body } => {
+            Statement::FunctionDeclaration { name: _, parameters: _, body, .. } => {
                 for stmt in body {
-                    self.analyze_statement(stmt, file_name, unsafe_ops)?;
+                    self.analyze_statement(stmt, file_name, false, unsafe_ops)?;
                 }
             }
-            Statement::For { identifier: _, iterable, body } => {
-                self.analyze_expression(iterable, file_name, unsafe_ops)?;
+            Statement::For { pattern: _, iterable, body, .. } => {
+                self.analyze_expression(iterable, file_name, in_unsafe, unsafe_ops)?;
                 for stmt in body {
-                    self.analyze_statement(stmt, file_name, unsafe_ops)?;
+                    self.analyze_statement(stmt, file_name, in_unsafe, unsafe_ops)?;
                 }
             }
-            Statement::Return { value } => {
+            Statement::Return { value, .. } => {
                 if let Some(expr) = value {
-                    self.analyze_expression(expr, file_name, unsafe_ops)?;
+                    self.analyze_expression(expr, file_name, in_unsafe, unsafe_ops)?;
                 }
             }
-            Statement::Expression { expression } => {
-                self.analyze_expression(expression, file_name, unsafe_ops)?;
+            Statement::Break { .. } | Statement::Continue { .. } => {
+                // Jumps within a loop are safe
+            }
+            Statement::Expression { expression, .. } => {
+                self.analyze_expression(expression, file_name, in_unsafe, unsafe_ops)?;
             }
-            Statement::Struct { name: _, fields: _ } => {
+            Statement::Struct { name: _, fields: _, .. } => {
                 // Struct definitions are safe
             }
-            Statement::Enum { name: _, variants: _ } => {
+            Statement::Enum { name: _, variants: _, .. } => {
                 // Enum definitions are safe
             }
+            Statement::Impl { methods, .. } => {
+                for method in methods {
+                    self.analyze_statement(method, file_name, in_unsafe, unsafe_ops)?;
+                }
+            }
+            Statement::Trait { methods, .. } => {
+                for stmt in methods.iter().filter_map(|method| method.body.as_ref()).flatten() {
+                    self.analyze_statement(stmt, file_name, in_unsafe, unsafe_ops)?;
+                }
+            }
+            Statement::Use { .. } | Statement::Module { .. } => {
+                // Imports perform no operations
+            }
+            Statement::Error { .. } => {
+                // Nothing to analyze in a statement that failed to parse
+            }
         }
         Ok(())
     }
 
     /// Analyze an expression for unsafe operations
-    fn analyze_expression(&self, expr: &Expression, file_name: &str, unsafe_ops: &mut Vec<UnsafeAuditEntry>) -> OvieResult<()> {
+    fn analyze_expression(&self, expr: &Expression, file_name: &str, in_unsafe: bool, unsafe_ops: &mut Vec<UnsafeAuditEntry>) -> OvieResult<()> {
         match expr {
-            Expression::Call { function, arguments } => {
-                if self.is_unsafe_function(function) {
-                    let entry = UnsafeAuditEntry {
-                        timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
-                        operation: self.classify_unsafe_function(function),
-                        location: SourceLocation {
-                            file: file_name.to_string(),
-                            line: 1,
-                            column: 1,
-                        },
-                        description: format!("Call to potentially unsafe function: {}", function),
-                        explicitly_unsafe: false,
-                        justification: None,
-                    };
-                    unsafe_ops.push(entry);
+            Expression::Call { function, arguments, span } => {
+                if self.unsafe_functions.lock().unwrap().contains(function) {
+                    self.audit_call(function, span, file_name, in_unsafe, unsafe_ops);
                 }
 
                 for arg in arguments {
-                    self.analyze_expression(arg, file_name, unsafe_ops)?;
+                    self.analyze_expression(arg, file_name, in_unsafe, unsafe_ops)?;
                 }
             }
             Expression::Binary { left, right, .. } => {
-                self.analyze_expression(left, file_name, unsafe_ops)?;
-                self.analyze_expression(right, file_name, unsafe_ops)?;
+                self.analyze_expression(left, file_name, in_unsafe, unsafe_ops)?;
+                self.analyze_expression(right, file_name, in_unsafe, unsafe_ops)?;
             }
-            Expression::Unary { operand, .. } => {
-                self.analyze_expression(operand, file_name, unsafe_ops)?;
+            Expression::Unary { operand, .. } | Expression::Try { operand, .. } => {
+                self.analyze_expression(operand, file_name, in_unsafe, unsafe_ops)?;
             }
-            Expression::FieldAccess { object, field: _ } => {
-                self.analyze_expression(object, file_name, unsafe_ops)?;
+            Expression::FieldAccess { object, field: _, .. } => {
+                self.analyze_expression(object, file_name, in_unsafe, unsafe_ops)?;
             }
-            Expression::StructInstantiation { struct_name: _, fields } => {
+            Expression::StructInstantiation { struct_name: _, fields, .. } => {
                 for field in fields {
-                    self.analyze_expression(&field.value, file_name, unsafe_ops)?;
+                    self.analyze_expression(&field.value, file_name, in_unsafe, unsafe_ops)?;
+                }
+            }
+            Expression::Range { start, end, .. } => {
+                self.analyze_expression(start, file_name, in_unsafe, unsafe_ops)?;
+                self.analyze_expression(end, file_name, in_unsafe, unsafe_ops)?;
+            }
+            Expression::EnumVariantConstruction { payload, .. } => {
+                for value in payload.values() {
+                    self.analyze_expression(value, file_name, in_unsafe, unsafe_ops)?;
+                }
+            }
+            Expression::Index { object, index, .. } => {
+                self.analyze_expression(object, file_name, in_unsafe, unsafe_ops)?;
+                self.analyze_expression(index, file_name, in_unsafe, unsafe_ops)?;
+            }
+            Expression::ArrayLiteral { elements, .. } | Expression::Tuple { elements, .. } => {
+                for element in elements {
+                    self.analyze_expression(element, file_name, in_unsafe, unsafe_ops)?;
+                }
+            }
+            Expression::Match { scrutinee, arms, .. } => {
+                self.analyze_expression(scrutinee, file_name, in_unsafe, unsafe_ops)?;
+                for arm in arms {
+                    for stmt in &arm.body {
+                        self.analyze_statement(stmt, file_name, in_unsafe, unsafe_ops)?;
+                    }
+                    if let Some(value) = &arm.value {
+                        self.analyze_expression(value, file_name, in_unsafe, unsafe_ops)?;
+                    }
+                }
+            }
+            Expression::IndirectCall { callee, arguments, .. } => {
+                self.analyze_expression(callee, file_name, in_unsafe, unsafe_ops)?;
+                for arg in arguments {
+                    self.analyze_expression(arg, file_name, in_unsafe, unsafe_ops)?;
+                }
+            }
+            Expression::MethodCall { receiver, method, arguments, span } => {
+                // The receiver's type is not known here, so any method with the
+                // name of an unsafe method is audited
+                if self.unsafe_methods.lock().unwrap().contains(method) {
+                    self.audit_call(method, span, file_name, in_unsafe, unsafe_ops);
+                }
+                self.analyze_expression(receiver, file_name, in_unsafe, unsafe_ops)?;
+                for arg in arguments {
+                    self.analyze_expression(arg, file_name, in_unsafe, unsafe_ops)?;
                 }
             }
-            Expression::Range { start, end } => {
-                self.analyze_expression(start, file_name, unsafe_ops)?;
-                self.analyze_expression(end, file_name, unsafe_ops)?;
+            Expression::Closure { body, .. } => {
+                // A closure written inside an unsafe block shares its context
+                for stmt in body {
+                    self.analyze_statement(stmt, file_name, in_unsafe, unsafe_ops)?;
+                }
             }
             _ => {} // Other expressions are safe
         }
         Ok(())
     }
 
-    /// Check if a function name represents an unsafe operation
-    fn is_unsafe_function(&self, name: &str) -> bool {
-        matches!(name, 
-            "malloc" | "free" | "memcpy" | "memset" |
-            "system" | "exec" | "fork" |
-            "ptr_read" | "ptr_write" | "cast_ptr" |
-            "raw_socket" | "bind_socket" |
-            "file_raw_read" | "file_raw_write"
-        )
-    }
-
-    /// Classify the type of unsafe operation
-    fn classify_unsafe_function(&self, name: &str) -> UnsafeOperation {
-        match name {
-            "malloc" | "free" | "memcpy" | "memset" => UnsafeOperation::RawMemoryAccess,
-            "system" | "exec" | "fork" => UnsafeOperation::SystemCall,
-            "ptr_read" | "ptr_write" => UnsafeOperation::PointerArithmetic,
-            "cast_ptr" => UnsafeOperation::UnsafeCast,
-            "raw_socket" | "bind_socket" => UnsafeOperation::UnverifiedNetworkAccess,
-            "file_raw_read" | "file_raw_write" => UnsafeOperation::UnsafeFileAccess,
-            _ => UnsafeOperation::ForeignFunctionCall,
+    /// Reject the first operation that is not inside an unsafe block or unsafe
+    /// function, when explicit unsafe blocks are required
+    pub fn check_explicit_unsafe(&self, operations: &[UnsafeAuditEntry]) -> OvieResult<()> {
+        if !*self.require_explicit_unsafe.lock().unwrap() {
+            return Ok(());
+        }
+        match operations.iter().find(|entry| !entry.explicitly_unsafe) {
+            Some(entry) => Err(OvieError::semantic_error(
+                entry.location.line as usize,
+                entry.location.column as usize,
+                format!("{} and must be inside an unsafe block", entry.description),
+            )),
+            None => Ok(()),
         }
     }
 
@@ -262,9 +448,15 @@ impl UnsafeOperationAnalyzer {
         self.audit_log.lock().unwrap().clone()
     }
 
-    /// Clear the audit log
+    /// Get the unsafe blocks seen so far
+    pub fn get_unsafe_blocks(&self) -> Vec<UnsafeBlockEntry> {
+        self.unsafe_blocks.lock().unwrap().clone()
+    }
+
+    /// Clear the audit log and the recorded unsafe blocks
     pub fn clear_audit_log(&self) {
         self.audit_log.lock().unwrap().clear();
+        self.unsafe_blocks.lock().unwrap().clear();
     }
 
     /// Generate a security report
@@ -284,6 +476,7 @@ impl UnsafeOperationAnalyzer {
             explicit_unsafe_count,
             implicit_unsafe_count,
             operation_counts,
+            unsafe_blocks: self.get_unsafe_blocks(),
             recommendations: self.generate_recommendations(&audit_log),
         }
     }
@@ -337,6 +530,8 @@ pub struct SecurityReport {
     pub implicit_unsafe_count: usize,
     /// Count of each type of unsafe operation
     pub operation_counts: HashMap<UnsafeOperation, usize>,
+    /// Every unsafe block, in source order
+    pub unsafe_blocks: Vec<UnsafeBlockEntry>,
     /// Security recommendations
     pub recommendations: Vec<String>,
 }
@@ -1222,6 +1417,54 @@ mod tests {
         assert_eq!(report.network_security.unauthorized_network_calls, 1); // The blocked telemetry call
     }
 
+    #[test]
+    fn test_unsafe_blocks_in_security_report() {
+        let source = "fn f() {\n    unsafe {\n        free(1);\n    }\n}\nunsafe fn g() { system(\"ls\"); }\nunsafe fn free(address: Int) { }\nunsafe fn system(command: String) { }";
+        let ast = crate::parser::Parser::new(crate::lexer::Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+
+        let analyzer = UnsafeOperationAnalyzer::new();
+        let operations = analyzer.analyze_ast(&ast, "main.ov").unwrap();
+        assert_eq!(operations.len(), 2);
+        assert!(operations.iter().all(|entry| entry.explicitly_unsafe));
+        assert_eq!((operations[0].location.line, operations[0].location.column), (3, 9));
+        assert_eq!(operations[0].operation, UnsafeOperation::RawMemoryAccess);
+        assert_eq!(operations[1].operation, UnsafeOperation::SystemCall);
+        assert!(analyzer.check_explicit_unsafe(&operations).is_ok());
+
+        let report = analyzer.generate_security_report();
+        assert_eq!(report.implicit_unsafe_count, 0);
+        assert_eq!(report.unsafe_blocks.len(), 1);
+        let block = &report.unsafe_blocks[0];
+        assert_eq!((block.span.line, block.span.column), (2, 5));
+        assert_eq!(&source[block.span.start..block.span.end], "unsafe {\n        free(1);\n    }");
+        assert_eq!(block.operation_count, 1);
+
+        let ast = crate::parser::Parser::new(crate::lexer::Lexer::new("unsafe fn free(address: Int) { }\nfree(1);").tokenize().unwrap()).parse().unwrap();
+        let operations = analyzer.analyze_ast(&ast, "main.ov").unwrap();
+        let error = analyzer.check_explicit_unsafe(&operations).unwrap_err();
+        assert!(error.to_string().contains("Call to 'free' performs raw memory access and must be inside an unsafe block"));
+    }
+
+    #[test]
+    fn test_unsafe_functions_are_collected_per_analysis() {
+        let parse = |source: &str| crate::parser::Parser::new(crate::lexer::Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let analyzer = UnsafeOperationAnalyzer::new();
+
+        // Unsafe functions nested in a body and unsafe impl methods are found
+        let source = "fn outer() {\n    unsafe fn poke(address: Int) { }\n    unsafe { poke(1); }\n}\nstruct Buffer { size: Int }\nimpl Buffer {\n    unsafe fn raw(self) -> Int { return self.size; }\n}\nlet buffer = Buffer { size: 1 };\nseeAm buffer.raw();";
+        let operations = analyzer.analyze_ast(&parse(source), "main.ov").unwrap();
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].description, "Call to unsafe function 'poke'");
+        assert!(operations[0].explicitly_unsafe);
+        assert_eq!(operations[1].description, "Call to unsafe function 'raw'");
+        assert_eq!((operations[1].location.line, operations[1].location.column), (10, 7));
+        assert!(!operations[1].explicitly_unsafe);
+
+        // A later analysis does not see the declarations of an earlier one
+        let operations = analyzer.analyze_ast(&parse("fn poke(address: Int) { }\npoke(1);"), "other.ov").unwrap();
+        assert!(operations.is_empty());
+    }
+
     #[test]
     fn test_no_hidden_data_collection() {
         let monitor = TelemetryMonitor::new();
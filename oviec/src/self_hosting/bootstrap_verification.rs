@@ -112,7 +112,7 @@ pub struct RollbackState {
 /// Automated equivalence testing system
 pub struct EquivalenceTester {
     /// Test case generator
-    test_generator: TestCaseGenerator,
+    pub(crate) test_generator: TestCaseGenerator,
     /// Maximum number of test cases to generate
     max_test_cases: usize,
     /// Minimum complexity for generated test cases
@@ -207,7 +207,7 @@ pub struct BootstrapVerifier {
     config: BootstrapConfig,
     pub ovie_lexer_ir: Option<IR>,
     pub rollback_state: Option<RollbackState>,
-    equivalence_tester: Option<EquivalenceTester>,
+    pub(crate) equivalence_tester: Option<EquivalenceTester>,
 }
 
 impl BootstrapVerifier {
@@ -313,7 +313,7 @@ impl BootstrapVerifier {
     }
 
     /// Compute environment hash for reproducibility verification
-    fn compute_environment_hash(&self) -> String {
+    pub(crate) fn compute_environment_hash(&self) -> String {
         let mut hasher = Sha256::new();
         
         // Hash relevant environment variables
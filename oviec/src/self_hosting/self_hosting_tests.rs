@@ -9,6 +9,8 @@ use crate::error::SourceLocation;
 use std::collections::HashMap;
 use std::fs;
 use tempfile::TempDir;
+use std::path::PathBuf;
+use crate::self_hosting::bootstrap_verification::RollbackState;
 
 /// Test the bootstrap configuration system
 #[cfg(test)]
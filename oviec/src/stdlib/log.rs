@@ -2630,7 +2630,7 @@ mod tests {
             if let OvieOption::Some(level) = levels.get(i) {
                 let level_str = level.as_str();
                 let parsed = LogLevel::from_str(level_str);
-                assert_eq!(parsed, some(*level));
+                assert_eq!(parsed, some(level));
             }
         }
         
@@ -2655,8 +2655,8 @@ mod tests {
         for message in &test_messages {
             for i in 0..test_levels.len() {
                 if let OvieOption::Some(level) = test_levels.get(i) {
-                    let record = LogRecord::new(*level, message.to_string());
-                    assert_eq!(record.level, *level);
+                    let record = LogRecord::new(level, message.to_string());
+                    assert_eq!(record.level, level);
                     assert_eq!(record.message, *message);
                     assert!(record.module.is_none());
                     assert!(record.fields.is_empty());
@@ -154,6 +154,56 @@ pub fn checked_mod(a: f64, b: f64) -> OvieResult<f64, String> {
     ok(result)
 }
 
+// ===== INT ARITHMETIC =====
+//
+// Int operators wrap on overflow; these report it instead.
+
+/// Checked Int addition
+pub fn checked_int_add(a: i64, b: i64) -> OvieResult<i64, String> {
+    match a.checked_add(b) {
+        Some(result) => ok(result),
+        None => err("Integer overflow in addition".to_string()),
+    }
+}
+
+/// Checked Int subtraction
+pub fn checked_int_sub(a: i64, b: i64) -> OvieResult<i64, String> {
+    match a.checked_sub(b) {
+        Some(result) => ok(result),
+        None => err("Integer overflow in subtraction".to_string()),
+    }
+}
+
+/// Checked Int multiplication
+pub fn checked_int_mul(a: i64, b: i64) -> OvieResult<i64, String> {
+    match a.checked_mul(b) {
+        Some(result) => ok(result),
+        None => err("Integer overflow in multiplication".to_string()),
+    }
+}
+
+/// Checked Int division, truncating toward zero
+pub fn checked_int_div(a: i64, b: i64) -> OvieResult<i64, String> {
+    if b == 0 {
+        return err("Division by zero".to_string());
+    }
+    match a.checked_div(b) {
+        Some(result) => ok(result),
+        None => err("Integer overflow in division".to_string()),
+    }
+}
+
+/// Checked Int remainder, with the sign of the dividend
+pub fn checked_int_mod(a: i64, b: i64) -> OvieResult<i64, String> {
+    if b == 0 {
+        return err("Modulo by zero".to_string());
+    }
+    match a.checked_rem(b) {
+        Some(result) => ok(result),
+        None => err("Integer overflow in modulo".to_string()),
+    }
+}
+
 // ===== POWER AND ROOT FUNCTIONS =====
 
 /// Power function with overflow checking
@@ -792,6 +842,20 @@ mod tests {
         assert!(checked_mod(1.0, 0.0).is_err());
     }
 
+    #[test]
+    fn test_checked_int_arithmetic() {
+        assert_eq!(checked_int_add(2, 3).unwrap(), 5);
+        assert_eq!(checked_int_div(-7, 2).unwrap(), -3);
+        assert_eq!(checked_int_mod(-7, 2).unwrap(), -1);
+
+        assert!(checked_int_add(MAX_INT, 1).is_err());
+        assert!(checked_int_sub(MIN_INT, 1).is_err());
+        assert!(checked_int_mul(MAX_INT, 2).is_err());
+        assert!(checked_int_div(1, 0).is_err());
+        assert!(checked_int_div(MIN_INT, -1).is_err());
+        assert!(checked_int_mod(1, 0).is_err());
+    }
+
     #[test]
     fn test_power_functions() {
         // Test basic power operations
@@ -30,6 +30,7 @@ pub use self::math::{
     
     // Checked arithmetic
     checked_add, checked_sub, checked_mul, checked_div, checked_mod,
+    checked_int_add, checked_int_sub, checked_int_mul, checked_int_div, checked_int_mod,
     
     // Power and root functions
     pow, integer_pow, ovie_sqrt, cbrt,
@@ -77,7 +77,7 @@ mod grammar_validation_tests {
             arbitrary_identifier(),
             arbitrary_simple_expression()
         ).prop_map(|(mut_kw, id, expr)| {
-            format!("{}{} = {};", mut_kw.unwrap_or(""), id, expr)
+            format!("{}{} = {};", mut_kw.as_deref().unwrap_or(""), id, expr)
         })
     }
 
@@ -114,8 +114,8 @@ mod grammar_validation_tests {
     
     fn arbitrary_invalid_syntax() -> impl Strategy<Value = String> {
         prop_oneof![
-            // Missing semicolons
-            arbitrary_identifier().prop_map(|id| format!("{} = 42", id)),
+            // Statements sharing a line without a semicolon
+            arbitrary_identifier().prop_map(|id| format!("{} = 42 {} = 43", id, id)),
             // Invalid operators
             (arbitrary_literal(), arbitrary_literal())
                 .prop_map(|(left, right)| format!("{} @ {}", left, right)),
@@ -129,6 +129,21 @@ mod grammar_validation_tests {
         ]
     }
 
+    #[test]
+    fn grammar_showcase_should_parse() {
+        // The complete grammar showcase should always parse successfully
+        let showcase_content = include_str!("../../../examples/grammar_showcase.ov");
+        
+        let mut lexer = Lexer::new(showcase_content);
+        let tokens = lexer.tokenize();
+        assert!(tokens.is_ok(), "Grammar showcase should tokenize");
+        
+        let tokens = tokens.unwrap();
+        let mut parser = Parser::new(tokens);
+        let ast = parser.parse();
+        assert!(ast.is_ok(), "Grammar showcase should parse");
+    }
+
     // Property tests
 
     proptest! {
@@ -163,21 +178,6 @@ mod grammar_validation_tests {
             // If tokenization fails, that's also correct rejection
         }
 
-        #[test]
-        fn property_grammar_showcase_should_parse() {
-            // The complete grammar showcase should always parse successfully
-            let showcase_content = include_str!("../../../examples/grammar_showcase.ov");
-            
-            let mut lexer = Lexer::new(showcase_content);
-            let tokens = lexer.tokenize();
-            prop_assert!(tokens.is_ok(), "Grammar showcase should tokenize");
-            
-            let tokens = tokens.unwrap();
-            let mut parser = Parser::new(tokens);
-            let ast = parser.parse();
-            prop_assert!(ast.is_ok(), "Grammar showcase should parse");
-        }
-
         #[test]
         fn property_deterministic_parsing(program in arbitrary_valid_program()) {
             // Same program should always produce the same AST
@@ -1,8 +1,12 @@
 // HIR (High-level IR) Tests
 // Feature: ovie-programming-language-stage-2, HIR Pipeline
 
-use crate::hir::{HirBuilder, HirProgram};
+use crate::hir::{HirBuilder, HirProgram, HirItem, HirExpression, HirType, HirStatementKind, HirExpressionKind, HirPattern, HirLiteral};
 use crate::ast::*;
+use crate::error::{OvieError, OvieResult};
+use crate::lexer::Lexer;
+use crate::modules::ModuleLoader;
+use crate::parser::Parser;
 
 #[cfg(test)]
 mod hir_tests {
@@ -12,7 +16,8 @@ mod hir_tests {
     fn test_hir_simple_program() {
         let statements = vec![
             Statement::Print {
-                expression: Expression::Literal(Literal::String("Hello, HIR!".to_string())),
+                expression: Expression::Literal(Literal::String("Hello, HIR!".to_string()), SourceSpan::default()),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -31,10 +36,12 @@ mod hir_tests {
             Statement::Assignment {
                 mutable: false,
                 identifier: "x".to_string(),
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
+                span: SourceSpan::default(),
             },
             Statement::Print {
-                expression: Expression::Identifier("x".to_string()),
+                expression: Expression::Identifier("x".to_string(), SourceSpan::default()),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -49,17 +56,28 @@ mod hir_tests {
     fn test_hir_function_definition() {
         let statements = vec![
             Statement::Function {
+                public: false,
+                is_const: false,
+                is_unsafe: false,
                 name: "test_func".to_string(),
-                parameters: vec!["a".to_string(), "b".to_string()],
+                type_parameters: Vec::new(),
+                bounds: Vec::new(),
+                parameters: vec![Parameter::untyped("a", SourceSpan::default()), Parameter::untyped("b", SourceSpan::default())],
+                return_type: None,
+                requires: Vec::new(),
+                ensures: Vec::new(),
                 body: vec![
                     Statement::Return {
                         value: Some(Expression::Binary {
-                            left: Box::new(Expression::Identifier("a".to_string())),
+                            left: Box::new(Expression::Identifier("a".to_string(), SourceSpan::default())),
                             operator: BinaryOperator::Add,
-                            right: Box::new(Expression::Identifier("b".to_string())),
+                            right: Box::new(Expression::Identifier("b".to_string(), SourceSpan::default())),
+                            span: SourceSpan::default(),
                         }),
+                        span: SourceSpan::default(),
                     }
                 ],
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -74,17 +92,21 @@ mod hir_tests {
     fn test_hir_struct_definition() {
         let statements = vec![
             Statement::Struct {
+                public: false,
                 name: "Person".to_string(),
+                type_parameters: Vec::new(),
                 fields: vec![
                     StructField {
                         name: "name".to_string(),
-                        type_annotation: "String".to_string(),
+                        type_annotation: TypeAnnotation::named("String"),
                     },
                     StructField {
                         name: "age".to_string(),
-                        type_annotation: "Number".to_string(),
+                        type_annotation: TypeAnnotation::named("Number"),
                     },
                 ],
+                invariants: Vec::new(),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -99,7 +121,8 @@ mod hir_tests {
     fn test_hir_serialization() {
         let statements = vec![
             Statement::Print {
-                expression: Expression::Literal(Literal::String("Test".to_string())),
+                expression: Expression::Literal(Literal::String("Test".to_string()), SourceSpan::default()),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -122,7 +145,8 @@ mod hir_tests {
     fn test_hir_validation() {
         let statements = vec![
             Statement::Print {
-                expression: Expression::Literal(Literal::String("Valid HIR".to_string())),
+                expression: Expression::Literal(Literal::String("Valid HIR".to_string()), SourceSpan::default()),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -140,16 +164,19 @@ mod hir_tests {
             Statement::Assignment {
                 mutable: false,
                 identifier: "x".to_string(),
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
+                span: SourceSpan::default(),
             },
             Statement::Assignment {
                 mutable: false,
                 identifier: "y".to_string(),
                 value: Expression::Binary {
-                    left: Box::new(Expression::Identifier("x".to_string())),
+                    left: Box::new(Expression::Identifier("x".to_string(), SourceSpan::default())),
                     operator: BinaryOperator::Add,
-                    right: Box::new(Expression::Literal(Literal::Number(8.0))),
+                    right: Box::new(Expression::Literal(Literal::Number(8.0), SourceSpan::default())),
+                    span: SourceSpan::default(),
                 },
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -159,4 +186,1014 @@ mod hir_tests {
         
         assert!(result.is_ok(), "HIR type checking should succeed for valid types");
     }
-}
\ No newline at end of file
+
+    fn build_hir(source: &str) -> OvieResult<HirProgram> {
+        let tokens = Lexer::new(source).tokenize()?;
+        let ast = Parser::new(tokens).parse()?;
+        HirBuilder::new().transform_ast(&ast)
+    }
+
+    #[test]
+    fn test_hir_declared_types_replace_infer() {
+        let hir = build_hir("fn add(a: Number, b: Number) -> Number { return a + b; }\nseeAm add(1.0, 2.0);")
+            .expect("typed program should lower to HIR");
+
+        let add = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(func) if func.name == "add" => Some(func),
+            _ => None,
+        }).expect("add should be in HIR");
+
+        assert!(add.parameters.iter().all(|p| p.param_type == HirType::Number));
+        assert_eq!(add.return_type, HirType::Number);
+    }
+
+    #[test]
+    fn test_hir_declared_type_mismatches() {
+        let return_error = build_hir("fn f() -> Number {\n    return \"s\";\n}").unwrap_err();
+        assert_eq!(return_error.location().map(|p| p.line), Some(2));
+
+        let argument_error = build_hir("fn f(a: Boolean) { seeAm a; }\nf(1);").unwrap_err();
+        assert_eq!(argument_error.location().map(|p| p.line), Some(2));
+
+        let let_error = build_hir("let x: String = 1;").unwrap_err();
+        assert_eq!(let_error.location().map(|p| (p.line, p.column)), Some((1, 17)));
+    }
+
+    #[test]
+    fn test_hir_generic_calls_are_instantiated() {
+        let hir = build_hir("fn identity<T>(value: T) -> T { return value; }\nfn main() {\n    let n = identity(42);\n    seeAm identity(\"s\");\n}")
+            .expect("generic program should lower to HIR");
+
+        let identity = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(func) if func.name == "identity" => Some(func),
+            _ => None,
+        }).expect("identity should be in HIR");
+        assert_eq!(identity.type_parameters, vec!["T".to_string()]);
+        assert_eq!(identity.return_type, HirType::Param("T".to_string()));
+
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(func) if func.is_main => Some(func),
+            _ => None,
+        }).expect("main should be in HIR");
+        match &main.body.statements[0].kind {
+            HirStatementKind::Local { var_type, initializer: Some(init), .. } => {
+                assert_eq!(var_type, &HirType::Int);
+                assert!(matches!(&init.kind, HirExpressionKind::Call { type_arguments, .. } if type_arguments == &vec![HirType::Int]));
+            }
+            other => panic!("Expected a local, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_hir_generic_type_errors() {
+        let conflicting = build_hir("fn same<T>(a: T, b: T) { seeAm 1; }\nfn main() {\n    same(1, \"x\");\n}").unwrap_err();
+        assert_eq!(conflicting.location().map(|p| (p.line, p.column)), Some((3, 13)));
+
+        let opaque = build_hir("fn f<T>(x: T) -> Number {\n    return x;\n}").unwrap_err();
+        assert!(opaque.to_string().contains("expected Number, found T"));
+
+        let arity = build_hir("struct Pair<A, B> { first: A, second: B }\nfn f(p: Pair<Number>) { seeAm 1; }").unwrap_err();
+        assert!(arity.to_string().contains("expects 2 type argument(s), found 1"));
+    }
+
+    #[test]
+    fn test_hir_match_binds_payloads() {
+        let hir = build_hir("enum Maybe<T> { Some(T), None }\nfn main() {\n    let m = Maybe.Some(\"s\");\n    let n = match m {\n        Maybe.Some(text) => text,\n        Maybe.None => \"none\",\n    };\n}")
+            .expect("match program should lower to HIR");
+
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(func) if func.is_main => Some(func),
+            _ => None,
+        }).expect("main should be in HIR");
+        match &main.body.statements[1].kind {
+            HirStatementKind::Local { var_type, initializer: Some(init), .. } => {
+                assert_eq!(var_type, &HirType::String);
+                match &init.kind {
+                    HirExpressionKind::Match { arms, .. } => {
+                        assert!(matches!(&arms[0].pattern, HirPattern::Variant { bindings, .. }
+                            if bindings == &[("0".to_string(), "text".to_string())]));
+                        assert_eq!(arms[0].value.as_ref().map(|value| &value.expr_type), Some(&HirType::String));
+                    }
+                    other => panic!("Expected a match, found {:?}", other),
+                }
+            }
+            other => panic!("Expected a local, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_hir_multi_field_variants() {
+        let hir = build_hir("enum Pair<A, B> { Both(A, B), Named { left: A, right: B } }\n\
+            fn main() {\n\
+                let p = Pair.Named { right: \"r\", left: 1 };\n\
+                let n = match p {\n\
+                    Pair.Both(_, text) => text,\n\
+                    Pair.Named { right: text } => text,\n\
+                };\n\
+            }")
+            .expect("multi-field variants should lower to HIR");
+
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(func) if func.is_main => Some(func),
+            _ => None,
+        }).expect("main should be in HIR");
+        match &main.body.statements[0].kind {
+            HirStatementKind::Local { var_type, .. } => assert_eq!(var_type.to_string(), "Pair<Int, String>"),
+            other => panic!("Expected a local, found {:?}", other),
+        }
+        match &main.body.statements[1].kind {
+            HirStatementKind::Local { var_type, initializer: Some(init), .. } => {
+                assert_eq!(var_type, &HirType::String);
+                let HirExpressionKind::Match { arms, .. } = &init.kind else { panic!("Expected a match, found {:?}", init.kind) };
+                assert!(matches!(&arms[0].pattern, HirPattern::Variant { bindings, .. }
+                    if bindings == &[("1".to_string(), "text".to_string())]));
+                assert!(matches!(&arms[1].pattern, HirPattern::Variant { bindings, .. }
+                    if bindings == &[("right".to_string(), "text".to_string())]));
+            }
+            other => panic!("Expected a local, found {:?}", other),
+        }
+
+        let shapes = "enum Shape { Rect { w: Int, h: Int }, Move(Int, Int), Empty }\n";
+        let errors = [
+            ("let s = Shape.Rect { w: 1 };", "Missing field 'h' in variant 'Shape.Rect'"),
+            ("let s = Shape.Rect { w: 1, h: 2, d: 3 };", "Variant 'Shape.Rect' has no field 'd'"),
+            ("let s = Shape.Rect(1, 2);", "has named fields"),
+            ("let s = Shape.Move { x: 1 };", "has positional fields"),
+            ("let s = Shape.Move(1);", "Variant 'Shape.Move' expects 2 value(s), found 1"),
+            ("let s = Shape.Empty(1);", "Variant 'Shape.Empty' has no payload"),
+            ("match Shape.Empty { Shape.Move(x) => {} _ => {} }", "expects 2 value(s), found 1"),
+        ];
+        for (program, message) in errors {
+            let error = build_hir(&format!("{}{}", shapes, program)).unwrap_err();
+            assert!(error.to_string().contains(message), "{}: {}", program, error);
+        }
+    }
+
+    #[test]
+    fn test_hir_match_exhaustiveness() {
+        let missing = build_hir("enum Shape { Circle(Number), Square(Number), Empty }\nlet s = Shape.Empty;\nmatch s {\n    Shape.Circle(r) => {}\n}").unwrap_err();
+        assert!(missing.to_string().contains("Shape.Empty, Shape.Square not covered"));
+        assert_eq!(missing.location().map(|p| (p.line, p.column)), Some((3, 1)));
+
+        let numbers = build_hir("match 3 {\n    1 => {}\n}").unwrap_err();
+        assert!(numbers.to_string().contains("need a '_' arm"));
+
+        build_hir("let b = true;\nmatch b {\n    true => {}\n    false => {}\n}").expect("both booleans are covered");
+        build_hir("match 3 {\n    1 => {}\n    other => { seeAm other; }\n}").expect("a binding covers every value");
+
+        let unknown = build_hir("enum Shape { Empty }\nmatch Shape.Empty {\n    Shape.Square => {}\n}").unwrap_err();
+        assert!(unknown.to_string().contains("has no variant 'Square'"));
+    }
+
+    #[test]
+    fn test_hir_closures_capture_enclosing_locals() {
+        let hir = build_hir("fn apply(f: fn(Number) -> Number, x: Number) -> Number { return f(x); }\n\
+            fn main() {\n\
+                let n = 1.0;\n\
+                let add = fn(x: Number) -> Number { return x + n; };\n\
+                seeAm add(2.0);\n\
+                seeAm apply(fn(y) { return y * n; }, 3.0);\n\
+            }").expect("closures should type-check");
+
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.is_main => Some(function),
+            _ => None,
+        }).unwrap();
+
+        let closure = main.body.statements.iter().find_map(|stmt| match &stmt.kind {
+            HirStatementKind::Local { name, initializer: Some(value), .. } if name == "add" => Some(value),
+            _ => None,
+        }).unwrap();
+        assert!(matches!(&closure.kind, HirExpressionKind::Closure { captures, .. } if captures == &vec!["n".to_string()]));
+        assert_eq!(closure.expr_type.to_string(), "fn(Number) -> Number");
+
+        let calls: Vec<_> = main.body.statements.iter().filter_map(|stmt| match &stmt.kind {
+            HirStatementKind::Print(value) => Some(value),
+            _ => None,
+        }).collect();
+        assert!(matches!(&calls[0].kind, HirExpressionKind::IndirectCall { callee, .. } if matches!(&callee.kind, HirExpressionKind::Variable(name) if name == "add")));
+        assert_eq!(calls[0].expr_type, HirType::Number);
+
+        // The closure argument's parameter takes its type from apply's signature
+        let argument = match &calls[1].kind {
+            HirExpressionKind::Call { arguments, .. } => &arguments[0],
+            other => panic!("expected a call, found {:?}", other),
+        };
+        assert!(matches!(&argument.kind, HirExpressionKind::Closure { parameters, .. } if parameters[0].param_type == HirType::Number));
+
+        let assign = build_hir("mut count = 0;\nlet inc = fn() { count = count + 1; };").unwrap_err();
+        assert!(assign.to_string().contains("Closures cannot assign to captured variable 'count'"));
+
+        let generic = build_hir("fn id<T>(x: T) -> T { return x; }\nlet f = id;").unwrap_err();
+        assert!(generic.to_string().contains("Generic function 'id' must be called"));
+
+        let arity = build_hir("let f = fn(a: Number) -> Number { return a; };\nseeAm f(1, 2);").unwrap_err();
+        assert!(arity.to_string().contains("expects 1 argument(s), found 2"));
+    }
+
+    #[test]
+    fn test_hir_tracks_unsafe_context() {
+        let hir = build_hir("unsafe fn twice(x: Number) -> Number { return x * 2.0; }\n\
+            unsafe fn quad(x: Number) -> Number { return twice(twice(x)); }\n\
+            unsafe { seeAm quad(1.0); }").expect("unsafe calls inside unsafe contexts should type-check");
+
+        let quad = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.name == "quad" => Some(function),
+            _ => None,
+        }).unwrap();
+        assert!(quad.is_unsafe);
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.is_main => Some(function),
+            _ => None,
+        }).unwrap();
+        assert!(!main.is_unsafe);
+        assert!(matches!(&main.body.statements[0].kind, HirStatementKind::Unsafe { body } if body.statements.len() == 1));
+
+        let call = build_hir("unsafe fn twice(x: Number) -> Number { return x * 2.0; }\nseeAm twice(1.0);").unwrap_err();
+        assert!(call.to_string().contains("Call to unsafe function 'twice' must be inside an unsafe block"));
+        assert_eq!(call.location().map(|p| (p.line, p.column)), Some((2, 7)));
+
+        // Only declared unsafe functions are unsafe; other names resolve as usual
+        let undeclared = build_hir("fn run() { system(\"ls\"); }").unwrap_err();
+        assert!(undeclared.to_string().contains("Undefined function 'system'"), "{}", undeclared);
+
+        let value = build_hir("unsafe fn twice(x: Number) -> Number { return x * 2.0; }\nlet f = twice;").unwrap_err();
+        assert!(value.to_string().contains("Unsafe function 'twice' must be called"));
+    }
+
+    #[test]
+    fn test_hir_unsafe_calls_compile_and_are_audited() {
+        let source = "unsafe fn poke(slot: Int) -> Int { return slot * 8; }\n\
+            fn offset() -> Int { unsafe { return poke(2); } }\n\
+            seeAm offset();";
+        crate::Compiler::new().compile_and_run_ir(source).unwrap();
+        assert!(crate::Compiler::new().compile_to_wasm(source).is_ok());
+
+        let analyzer = crate::security::UnsafeOperationAnalyzer::new();
+        let ast = Parser::new(Lexer::new(source).tokenize().unwrap()).parse().unwrap();
+        let audit = analyzer.analyze_ast(&ast, "poke.ov").unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].operation, crate::security::UnsafeOperation::UnsafeFunctionCall);
+        assert_eq!((audit[0].location.line, audit[0].location.column), (2, 38));
+        assert!(audit[0].explicitly_unsafe);
+        assert_eq!(analyzer.generate_security_report().unsafe_blocks[0].operation_count, 1);
+    }
+
+    #[test]
+    fn test_hir_methods_resolve_through_type_table() {
+        let hir = build_hir("struct Point { x: Number, y: Number }\n\
+            impl Point {\n\
+                fn new(x: Number, y: Number) -> Self { return Point { x: x, y: y }; }\n\
+                fn sum(self) -> Number { return self.x + self.y; }\n\
+            }\n\
+            struct Cell<T> { value: T }\n\
+            impl Cell<T> { fn get(self) -> T { return self.value; } }\n\
+            let p = Point.new(1.0, 2.0);\n\
+            let n = p.sum();\n\
+            let c = Cell { value: \"s\" };\n\
+            let s = c.get();").expect("methods should type-check");
+
+        let method = hir.type_table.method("Point", "sum").unwrap();
+        assert_eq!(method.function, "Point::sum");
+        assert!(method.takes_self);
+        assert!(!hir.type_table.method("Point", "new").unwrap().takes_self);
+
+        let sum = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.name == "Point::sum" => Some(function),
+            _ => None,
+        }).unwrap();
+        assert!(matches!(&sum.parameters[0].param_type, HirType::Struct { name, .. } if name == "Point"));
+
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.is_main => Some(function),
+            _ => None,
+        }).unwrap();
+        let initializers: Vec<&HirExpression> = main.body.statements.iter().filter_map(|stmt| match &stmt.kind {
+            HirStatementKind::Local { initializer: Some(expr), .. } => Some(expr),
+            _ => None,
+        }).collect();
+        assert!(matches!(&initializers[0].kind, HirExpressionKind::Call { function, arguments, .. } if function == "Point::new" && arguments.len() == 2));
+        assert!(matches!(&initializers[1].kind, HirExpressionKind::Call { function, arguments, .. } if function == "Point::sum" && arguments.len() == 1));
+        assert_eq!(initializers[1].expr_type, HirType::Number);
+        assert!(matches!(&initializers[3].kind, HirExpressionKind::Call { type_arguments, .. } if type_arguments == &vec![HirType::String]));
+        assert_eq!(initializers[3].expr_type, HirType::String);
+
+        let missing = build_hir("struct P { x: Number }\nimpl P { fn f(self) -> Number { return self.x; } }\nlet p = P { x: 1.0 };\nseeAm p.g();").unwrap_err();
+        assert!(missing.to_string().contains("Type 'P' has no method 'g'"));
+        assert_eq!(missing.location().map(|p| (p.line, p.column)), Some((4, 7)));
+
+        let associated = build_hir("struct P { x: Number }\nimpl P { fn make() -> P { return P { x: 1.0 }; } }\nlet p = P { x: 1.0 };\nseeAm p.make();").unwrap_err();
+        assert!(associated.to_string().contains("'make' is an associated function of 'P'"));
+
+        let duplicate = build_hir("struct P { x: Number }\nimpl P { fn f(self) {} }\nimpl P { fn f(self) {} }").unwrap_err();
+        assert!(duplicate.to_string().contains("Duplicate method 'f' for type 'P'"));
+
+        let self_position = build_hir("struct P { x: Number }\nimpl P { fn f(a: Number, self) {} }").unwrap_err();
+        assert!(self_position.to_string().contains("'self' must be the first parameter of method 'f'"));
+    }
+
+    #[test]
+    fn test_hir_methods_are_defined_with_their_type() {
+        let hir = build_project_hir(&[
+            ("main.ov", "use shapes::{Square};\nlet s = Square.new(3.0);\nseeAm s.area();"),
+            ("shapes.ov", "pub struct Square { side: Number }\nimpl Square {\n    fn new(side: Number) -> Square { return Square { side: side }; }\n    fn area(self) -> Number { return self.side * self.side; }\n}"),
+        ]).expect("methods of an imported type should resolve");
+        assert_eq!(hir.type_table.method("shapes::Square", "area").unwrap().function, "shapes::Square::area");
+
+        let foreign = build_project_hir(&[
+            ("main.ov", "use shapes::{Square};\nimpl Square { fn double(self) -> Number { return 2.0; } }"),
+            ("shapes.ov", "pub struct Square { side: Number }"),
+        ]).unwrap_err();
+        assert!(foreign.to_string().contains("Methods of 'Square' must be defined in the module that defines it"));
+    }
+
+    #[test]
+    fn test_hir_int_and_number_convert_explicitly() {
+        let hir = build_hir("fn half(x: Number) -> Number { return x / 2.0; }\nfn main() {\n    let n = 7;\n    let x: Number = to_number(n);\n    seeAm half(x) + to_number(to_int(2.5));\n}")
+            .expect("explicit conversions should type-check");
+
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(func) if func.is_main => Some(func),
+            _ => None,
+        }).expect("main should be in HIR");
+        assert!(matches!(&main.body.statements[0].kind, HirStatementKind::Local { var_type: HirType::Int, .. }));
+        match &main.body.statements[1].kind {
+            HirStatementKind::Local { var_type, initializer: Some(init), .. } => {
+                assert_eq!(var_type, &HirType::Number);
+                assert!(matches!(&init.kind, HirExpressionKind::Call { function, .. } if function == "to_number"));
+            }
+            other => panic!("Expected a local, found {:?}", other),
+        }
+
+        // Mixing Int and Number is rejected wherever it happens
+        let mixed = [
+            "fn main() {\n    let x: Number = 7;\n}",
+            "fn half(x: Number) -> Number { return x / 2; }",
+            "fn f(x: Number) -> Number { return x; }\nseeAm f(1);",
+            "seeAm 1 + 2.5;",
+            "seeAm 2.5 < 3;",
+            "mut total = 0.5\ntotal += 1",
+            "let items = [1.5, 2]",
+            "let pair: (Number, Int) = (1, 2)",
+            "for i in 0..2.5 { seeAm i; }",
+            "let b = true\nseeAm match b { true => 1, false => 0.5 }",
+        ];
+        for source in mixed {
+            let error = build_hir(source).expect_err(source);
+            assert!(error.to_string().contains("Type mismatch"), "{}: {}", source, error);
+        }
+
+        let error = build_hir("fn main() {\n    let x: Number = 7;\n}").unwrap_err();
+        assert!(error.to_diagnostic().suggestions.iter().any(|suggestion| suggestion.message.contains("to_number")));
+
+        let narrowing = build_hir("fn main() {\n    let n: Int = 2.5;\n}").unwrap_err();
+        assert!(narrowing.to_string().contains("expected Int, found Number"));
+        assert!(narrowing.to_diagnostic().suggestions.iter().any(|suggestion| suggestion.message.contains("to_int")));
+
+        let argument = build_hir("fn f(x: Int) -> Int { return x; }\nseeAm f(1.5);").unwrap_err();
+        assert!(argument.to_string().contains("expected Int, found Number"));
+    }
+
+    #[test]
+    fn test_hir_loop_exits_must_target_a_loop() {
+        let hir = build_hir("outer: while true {\n    for i in 0..3 {\n        if i == 1 { continue }\n        break outer\n    }\n}")
+            .expect("labeled loop exits should lower to HIR");
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.is_main => Some(function),
+            _ => None,
+        }).expect("top-level statements should form main");
+        match &main.body.statements[0].kind {
+            HirStatementKind::While { label: Some(label), .. } => assert_eq!(label.as_str(), "outer"),
+            other => panic!("expected a labeled while loop, found {:?}", other),
+        }
+
+        let error = build_hir("break;").unwrap_err();
+        assert!(error.to_string().contains("'break' outside of a loop"));
+
+        let error = build_hir("while true { let f = fn() { continue; }; }").unwrap_err();
+        assert!(error.to_string().contains("'continue' outside of a loop"));
+
+        let error = build_hir("while true { break missing; }").unwrap_err();
+        assert!(error.to_string().contains("No enclosing loop is labeled 'missing' for 'break'"));
+
+        let error = build_hir("a: while true { a: while true { break a; } }").unwrap_err();
+        assert!(error.to_string().contains("Loop label 'a' is already used by an enclosing loop"));
+    }
+
+    #[test]
+    fn test_hir_place_assignment() {
+        let hir = build_hir("struct Account { balance: Number }\nmut accounts = [Account { balance: 1.5 }]\naccounts[0].balance += 2.0")
+            .expect("assignment through a mutable variable should lower to HIR");
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.is_main => Some(function),
+            _ => None,
+        }).unwrap();
+        match &main.body.statements[1].kind {
+            HirStatementKind::CompoundAssign { target, value, .. } => {
+                assert_eq!(target.to_string(), "accounts[..].balance");
+                assert_eq!(target.place_type, HirType::Number);
+                assert_eq!(value.expr_type, HirType::Number);
+            }
+            other => panic!("expected a compound assignment, found {:?}", other),
+        }
+
+        let error = build_hir("struct P { x: Int }\nlet p = P { x: 1 }\np.x = 2").unwrap_err();
+        assert!(error.to_string().contains("Cannot assign to 'p.x' because 'p' is not mutable"));
+
+        let error = build_hir("fn f(items: [Int]) { items[0] = 1; }").unwrap_err();
+        assert!(error.to_string().contains("Cannot assign to 'items[..]' because 'items' is not mutable"));
+
+        let error = build_hir("count = 0\ncount += 1").unwrap_err();
+        assert!(error.to_string().contains("Cannot assign to 'count' because it is not mutable"));
+
+        assert!(build_hir("mut n = 1\nn += 0.5").is_err(), "an Int place cannot hold a Number result");
+        assert!(build_hir("mut s = \"ab\"\ns[0] = \"c\"").is_err());
+        assert!(build_hir("mut n = 1\nlet f = fn() { n += 1; }").is_err());
+    }
+
+    #[test]
+    fn test_hir_tuples_and_destructuring() {
+        let hir = build_hir("fn split() -> (Int, String) { return (1, \"one\"); }\nlet (n, name) = split()\nlet mut pair: (Number, String) = (to_number(n), name)\nfor (i, _) in [(1, true)] { seeAm to_number(i) + pair.0; }")
+            .expect("tuples should lower to HIR");
+        let main = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.is_main => Some(function),
+            _ => None,
+        }).unwrap();
+        match &main.body.statements[0].kind {
+            HirStatementKind::Destructure { pattern: HirPattern::Tuple(elements), initializer, .. } => {
+                assert_eq!(elements.len(), 2);
+                assert_eq!(initializer.expr_type.to_string(), "(Int, String)");
+            }
+            other => panic!("expected a destructuring let, found {:?}", other),
+        }
+        match &main.body.statements[1].kind {
+            HirStatementKind::Local { initializer: Some(value), .. } => assert_eq!(value.expr_type.to_string(), "(Number, String)"),
+            other => panic!("expected a local, found {:?}", other),
+        }
+        assert!(matches!(&main.body.statements[2].kind, HirStatementKind::For { pattern: HirPattern::Tuple(_), .. }));
+
+        let error = build_hir("let (a, b) = (1, 2, 3)").unwrap_err();
+        assert!(error.to_string().contains("(_, _)"));
+
+        let error = build_hir("let t = (1, 2)\nseeAm t.2").unwrap_err();
+        assert!(error.to_string().contains("has no field '2'"));
+
+        let error = build_hir("let (a, b) = 1").unwrap_err();
+        assert!(error.to_string().contains("(_, _)"));
+
+        let error = build_hir("seeAm match (1, 2) { (a, b) => a }").unwrap_err();
+        assert!(error.to_string().contains("Tuple patterns are only supported in 'let' and 'for'"));
+
+        assert!(build_hir("let (a, b) = (1, 2)\na += 3").is_err(), "destructured names are immutable without 'mut'");
+        assert!(build_hir("let mut (a, b) = (1, 2)\na += 3").is_ok());
+    }
+
+    fn constant_value<'a>(hir: &'a HirProgram, name: &str) -> &'a HirExpression {
+        hir.items.iter().find_map(|item| match item {
+            HirItem::Global(global) if global.is_const && global.name == name => global.initializer.as_ref(),
+            _ => None,
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_hir_constants_are_evaluated() {
+        let hir = build_hir("const fn fib(n: Int) -> Int {\n\
+                mut a = 0\n\
+                mut b = 1\n\
+                for i in 0..n {\n\
+                    let next = a + b\n\
+                    a = b\n\
+                    b = next\n\
+                }\n\
+                return a\n\
+            }\n\
+            fn main() { seeAm RATIO + to_number(FIB); }\n\
+            const RATIO: Number = to_number(FIB) / 2.0\n\
+            const FIB: Int = fib(10)\n\
+            const NAME: String = \"ov\" + \"ie\"\n\
+            const LONG: Boolean = NAME[3] == \"e\" && FIB > 50")
+            .expect("constants should evaluate");
+
+        assert!(matches!(constant_value(&hir, "FIB").kind, HirExpressionKind::Literal(HirLiteral::Integer(55))));
+        let ratio = constant_value(&hir, "RATIO");
+        assert!(matches!(ratio.kind, HirExpressionKind::Literal(HirLiteral::Number(n)) if n == 27.5));
+        assert_eq!(ratio.expr_type, HirType::Number);
+        assert!(matches!(&constant_value(&hir, "NAME").kind, HirExpressionKind::Literal(HirLiteral::String(s)) if s == "ovie"));
+        assert!(matches!(constant_value(&hir, "LONG").kind, HirExpressionKind::Literal(HirLiteral::Boolean(true))));
+
+        let hir = build_hir("const fn pairs() -> Int {\n\
+                mut n = 0\n\
+                outer: for i in 0..5 {\n\
+                    for j in 0..5 {\n\
+                        if j == 2 { continue outer }\n\
+                        if i == 3 { break outer }\n\
+                        n += 1\n\
+                    }\n\
+                }\n\
+                return n\n\
+            }\n\
+            const PAIRS: Int = pairs()")
+            .expect("loop exits should evaluate");
+        assert!(matches!(constant_value(&hir, "PAIRS").kind, HirExpressionKind::Literal(HirLiteral::Integer(6))));
+
+        // A const fn is still an ordinary function at run time
+        assert!(build_hir("const fn twice(n: Int) -> Int { return n * 2 }\nmut x = 4\nseeAm twice(x)").is_ok());
+        // Constants are not captured by closures and cannot be assigned
+        assert!(build_hir("const X: Int = 2\nlet f = fn() -> Int { return X }\nseeAm f()").is_ok());
+        let error = build_hir("const X: Int = 2\nX += 1").unwrap_err();
+        assert!(error.to_string().contains("Cannot assign to constant 'X'"));
+        let error = build_hir("const X: [Int] = [1]").unwrap_err();
+        assert!(error.to_string().contains("constants must be Int, Number, String or Boolean"));
+    }
+
+    #[test]
+    fn test_hir_const_eval_errors() {
+        let code = |source: &str| build_hir(source).unwrap_err().to_diagnostic().code;
+
+        assert_eq!(code("const X: Int = 1 / 0"), "E4001");
+        assert_eq!(code("const X: Int = 9223372036854775807 + 1"), "E4002");
+        assert_eq!(code("const X: String = \"ab\"[2]"), "E4003");
+        assert_eq!(code("fn f() -> Int { return 1 }\nconst X: Int = f()"), "E4004");
+        assert_eq!(code("const A: Int = B\nconst B: Int = A + 1"), "E4005");
+        assert_eq!(code("const fn f(n: Int) -> Int { return f(n + 1) }\nconst X: Int = f(0)"), "E4006");
+        assert_eq!(code("const fn f() -> Int { mut n = 0\nwhile true { n += 1 }\nreturn n }\nconst X: Int = f()"), "E4006");
+
+        // A const fn is checked where it is defined, even if no constant calls it
+        let error = build_hir("fn g() -> Int { return 1 }\nconst fn f() -> Int { return g() }").unwrap_err();
+        let diagnostic = error.to_diagnostic();
+        assert_eq!(diagnostic.code, "E4004");
+        assert_eq!(diagnostic.context["operation"], "f");
+        assert_eq!((diagnostic.location.line, diagnostic.location.column), (2, 30));
+
+        let error = build_hir("const A: Int = B\nconst B: Int = A + 1").unwrap_err();
+        assert!(error.to_string().contains("A -> B -> A"), "{}", error);
+    }
+
+    const RESULT_AND_OPTION: &str = "enum Result<T, E> { Ok(T), Err(E) }\nenum Option<T> { Some(T), None }\n\
+        fn parse(s: String) -> Result<Int, String> { return Result.Ok(1) }\n\
+        fn find(n: Int) -> Option<String> { return Option.None }\n";
+
+    #[test]
+    fn test_hir_error_propagation() {
+        let hir = build_hir(&format!("{}fn total(a: String) -> Result<Int, String> {{\n\
+                let n = parse(a)? + 1\n\
+                return Result.Ok(n)\n\
+            }}\n\
+            fn name(n: Int) -> Option<Boolean> {{ return Option.Some(find(n)? == \"x\") }}", RESULT_AND_OPTION))
+            .expect("'?' should type-check");
+
+        let total = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.name == "total" => Some(function),
+            _ => None,
+        }).unwrap();
+        let sum = total.body.statements.iter().find_map(|stmt| match &stmt.kind {
+            HirStatementKind::Local { name, initializer: Some(value), .. } if name == "n" => Some(value),
+            _ => None,
+        }).unwrap();
+        assert_eq!(sum.expr_type, HirType::Int);
+        match &sum.kind {
+            HirExpressionKind::Binary { left, .. } => {
+                assert!(matches!(&left.kind, HirExpressionKind::Try { success, failure, .. } if success == "Ok" && failure == "Err"));
+                // The value of `parse(a)?` is the data of Ok
+                assert_eq!(left.expr_type, HirType::Int);
+            }
+            other => panic!("expected a sum, found {:?}", other),
+        }
+
+        let error = |body: &str| build_hir(&format!("{}{}", RESULT_AND_OPTION, body)).unwrap_err().to_string();
+        assert!(error("fn f() -> Int { return parse(\"1\")? }").contains("'?' can only be used in a function that returns Result"));
+        assert!(error("fn f() -> Option<Int> { return Option.Some(parse(\"1\")?) }").contains("'?' can only be used in a function that returns Result"));
+        assert!(error("let s = find(1)?").contains("'?' can only be used in a function that returns Option"));
+        assert!(error("fn f() -> Option<Int> { return Option.Some(1?) }").contains("'?' expects a Result or Option, found Int"));
+        // The returned Err must carry the operand's error type
+        assert!(error("fn f() -> Result<Int, Int> { return Result.Ok(parse(\"1\")?) }").contains("expected Int, found String"));
+    }
+
+    fn build_project_hir(files: &[(&str, &str)]) -> OvieResult<HirProgram> {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, source) in files {
+            std::fs::write(dir.path().join(name), source).unwrap();
+        }
+        let graph = ModuleLoader::new(dir.path().to_path_buf(), None).load(&dir.path().join("main.ov"), |source| {
+            Parser::new(Lexer::new(source).tokenize()?).parse()
+        })?;
+        HirBuilder::new().transform_module_graph(&graph)
+    }
+
+    #[test]
+    fn test_hir_items_are_qualified_by_module() {
+        let hir = build_project_hir(&[
+            ("main.ov", "use shapes::{Shape, area};\nfn area2(s: Shape) -> Number { return area(s) * 2.0; }\nseeAm area2(Shape.Square(3.0));\nseeAm shapes::describe();"),
+            ("shapes.ov", "pub enum Shape { Square(Number) }\npub fn area(s: Shape) -> Number { return match s { Shape.Square(side) => side * side }; }\npub fn describe() -> String { return helper(); }\nfn helper() -> String { return \"shape\"; }"),
+        ]).expect("imported items should resolve");
+
+        let mut names: Vec<&str> = hir.items.iter().filter_map(|item| match item {
+            HirItem::Function(function) => Some(function.name.as_str()),
+            HirItem::Enum(hir_enum) => Some(hir_enum.name.as_str()),
+            _ => None,
+        }).collect();
+        names.sort();
+        assert_eq!(names, vec!["area2", "main", "shapes::Shape", "shapes::area", "shapes::describe", "shapes::helper"]);
+
+        let area2 = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.name == "area2" => Some(function),
+            _ => None,
+        }).unwrap();
+        assert!(matches!(&area2.parameters[0].param_type, HirType::Enum { name, .. } if name == "shapes::Shape"));
+
+        let private = build_project_hir(&[
+            ("main.ov", "mod shapes;\nseeAm shapes::helper();"),
+            ("shapes.ov", "fn helper() -> Number { return 1.0; }"),
+        ]).unwrap_err();
+        assert!(private.to_string().contains("'helper' is private to module 'shapes'"));
+        assert_eq!(private.location().map(|p| (p.line, p.column)), Some((2, 7)));
+
+        let statement = build_project_hir(&[
+            ("main.ov", "mod util;"),
+            ("util.ov", "seeAm 1;"),
+        ]).unwrap_err();
+        assert!(statement.to_string().contains("Only items can appear at the top level of module 'util'"));
+    }
+
+    #[test]
+    fn test_hir_core_module_from_std_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = dir.path().join("main.ov");
+        std::fs::write(&entry, "use core::{Option, Result, some, none, ok, assert};\n\
+            let doubled = some(4).map(fn(n: Int) -> Int { return n * 2; });\n\
+            let missing: Option<Int> = none();\n\
+            let parsed: Result<Int, String> = ok(5);\n\
+            assert(doubled.unwrap_or(0) == 8, \"map\");\n\
+            assert(missing.unwrap_or(7) == 7, \"unwrap_or\");\n\
+            assert(parsed.is_ok() && !doubled.ok_or(\"none\").is_err(), \"result\");\n").unwrap();
+        crate::Compiler::new().compile_file_and_run_with_backend(&entry, crate::Backend::Interpreter).unwrap();
+        crate::Compiler::new().compile_file_and_run_with_backend(&entry, crate::Backend::IrInterpreter).unwrap();
+        assert!(crate::Compiler::new().compile_file_to_wasm(&entry).is_ok());
+
+        // A failed assertion panics, which stops the program
+        std::fs::write(&entry, "use core::{assert};\nassert(1 > 2, \"order\");\n").unwrap();
+        let error = crate::Compiler::new().compile_file_and_run_with_backend(&entry, crate::Backend::IrInterpreter).unwrap_err();
+        assert!(error.to_string().contains("Program aborted: PANIC: Assertion failed: order"), "{}", error);
+        let error = crate::Compiler::new().compile_file_and_run_with_backend(&entry, crate::Backend::Interpreter).unwrap_err();
+        assert!(error.to_string().contains("Program aborted: PANIC: Assertion failed: order"), "{}", error);
+        let wasm = crate::Compiler::new().compile_file_to_wasm(&entry).unwrap();
+        let imports: Vec<String> = wasmparser::Parser::new(0).parse_all(&wasm)
+            .filter_map(|payload| match payload {
+                Ok(wasmparser::Payload::ImportSection(reader)) => Some(reader),
+                _ => None,
+            })
+            .flat_map(|reader| reader.into_iter().map(|import| import.unwrap().name.to_string()).collect::<Vec<_>>())
+            .collect();
+        assert!(imports.contains(&"abort".to_string()), "{:?}", imports);
+    }
+
+    #[test]
+    fn test_hir_types_can_refer_to_later_types() {
+        let hir = build_hir("struct Segment { from: Point, to: Point }\nstruct Point { x: Int }\n\
+            let s = Segment { from: Point { x: 1 }, to: Point { x: 3 } };\nseeAm s.to.x - s.from.x;");
+        assert!(hir.is_ok(), "{:?}", hir.err());
+
+        // The normalizer renames declared fields the same way as the accesses to them
+        let ast = crate::Compiler::new()
+            .compile_to_ast("struct Door { is_open: Boolean }\nlet door = Door { is_open: true };\nseeAm door.is_open;")
+            .unwrap();
+        assert!(HirBuilder::new().transform_ast(&ast).is_ok());
+    }
+
+    #[test]
+    fn test_hir_math_and_time_modules_from_std_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = dir.path().join("main.ov");
+        std::fs::write(&entry, "use std::core::{assert};\n\
+            use std::math::{abs, sqrt, factorial};\n\
+            use std::time::{Duration};\n\
+            assert(abs(sqrt(16.0).unwrap() - 4.0) < 0.000001, \"sqrt\");\n\
+            assert(factorial(5.0).unwrap() == 120.0, \"factorial\");\n\
+            assert(Duration.from_millis(1500.0).as_seconds() == 1.5, \"duration\");\n").unwrap();
+        crate::Compiler::new().compile_file_and_run_with_backend(&entry, crate::Backend::IrInterpreter).unwrap();
+        // The AST interpreter takes math's INFINITY and NAN from compile-time evaluation
+        crate::Compiler::new().compile_file_and_run_with_backend(&entry, crate::Backend::Interpreter).unwrap();
+    }
+
+    const SHOW: &str = "trait Show {\n\
+            fn show(self) -> String;\n\
+            fn describe(self) -> String { return \"<\" + self.show() + \">\"; }\n\
+        }\n\
+        struct Point { x: Int }\n\
+        struct Wrapper<T> { value: T }\n\
+        impl Show for Point { fn show(self) -> String { return \"point\"; } }\n\
+        impl Show for Wrapper<T: Show> { fn show(self) -> String { return self.value.show(); } }\n";
+
+    #[test]
+    fn test_hir_traits_and_bounds() {
+        let hir = build_hir(&format!("{}fn all<T: Show>(item: T) -> String {{ return item.describe(); }}\n\
+            seeAm all(Wrapper {{ value: Point {{ x: 1 }} }});\nseeAm Point {{ x: 2 }}.describe();", SHOW))
+            .expect("trait methods and bounds should type-check");
+
+        // The default method is a function generic over Self
+        let describe = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.name == "Show::describe" => Some(function),
+            _ => None,
+        }).expect("default methods are lowered once, generic over Self");
+        assert_eq!(describe.type_parameters, vec!["Self".to_string()]);
+        assert_eq!(describe.parameters[0].param_type, HirType::Param("Self".to_string()));
+
+        // A method of a bounded type parameter calls the trait method, with Self bound to the parameter
+        let all = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.name == "all" => Some(function),
+            _ => None,
+        }).unwrap();
+        match &all.body.statements[0].kind {
+            HirStatementKind::Return(Some(HirExpression { kind: HirExpressionKind::Call { function, type_arguments, .. }, .. })) => {
+                assert_eq!(function, "Show::describe");
+                assert_eq!(type_arguments, &vec![HirType::Param("T".to_string())]);
+            }
+            other => panic!("expected a trait method call, found {:?}", other),
+        }
+        let impls = &hir.type_table.trait_info("Show").unwrap().impls;
+        assert!(impls["Point"].methods.contains("show") && !impls["Point"].methods.contains("describe"));
+        assert_eq!(impls["Wrapper"].bounds["T"], vec!["Show".to_string()]);
+
+        let error = |body: &str| build_hir(&format!("{}{}", SHOW, body)).unwrap_err().to_string();
+        assert!(error("fn f<T>(x: T) -> String { return x.show(); }").contains("Type parameter 'T' has no method 'show'"));
+        assert!(error("fn f<T: Show>(x: T) -> String { return x.show(); }\nseeAm f(1);").contains("Type 'Int' does not implement trait 'Show' required by 'f'"));
+        // The impl for Wrapper applies only when its type argument implements Show
+        assert!(error("seeAm Wrapper { value: 1 }.describe();").contains("Type 'Wrapper<Int>' does not implement trait 'Show' required by 'Show::describe'"));
+        assert!(error("fn f<T: Eq>(x: T) {}").contains("Unknown trait: Eq"));
+    }
+
+    #[test]
+    fn test_hir_trait_coherence() {
+        let error = |body: &str| build_hir(&format!("{}{}", SHOW, body)).unwrap_err().to_string();
+        assert!(error("struct Line { a: Int }\nimpl Show for Line { }").contains("Missing method 'show' in impl of trait 'Show' for 'Line'"));
+        assert!(error("impl Show for Point { fn show(self) -> String { return \"again\"; } }")
+            .contains("Conflicting implementations of trait 'Show' for type 'Point'"));
+        assert!(error("impl Show for Int { fn show(self) -> Int { return self; } }")
+            .contains("Method 'show' does not match its declaration in trait 'Show': expected fn(Int) -> String, found fn(Int) -> Int"));
+        assert!(error("impl Show for Int { fn show(self) -> String { return \"n\"; }\nfn size(self) -> Int { return 1; } }")
+            .contains("Method 'size' is not a member of trait 'Show'"));
+        assert!(error("impl Point { fn describe(self) -> String { return \"p\"; } }").contains("Duplicate method 'describe' for type 'Point'"));
+        assert!(error("impl Int { fn show(self) -> String { return \"n\"; } }").contains("Unknown type: Int"));
+
+        // An impl must be in the module of the trait or the type
+        let orphan = build_project_hir(&[
+            ("main.ov", "use shapes::{Show, Square};\nimpl Show for Square { fn show(self) -> String { return \"square\"; } }"),
+            ("shapes.ov", "pub trait Show { fn show(self) -> String; }\npub struct Square { side: Int }"),
+        ]).unwrap_err();
+        assert!(orphan.to_string().contains("Impl of trait 'Show' for 'Square' must be in the module that defines the trait or the type"));
+        build_project_hir(&[
+            ("main.ov", "use shapes::{Show};\nstruct Circle { r: Int }\nimpl Show for Circle { fn show(self) -> String { return \"circle\"; } }\nseeAm Circle { r: 1 }.show();"),
+            ("shapes.ov", "pub trait Show { fn show(self) -> String; }"),
+        ]).expect("a local type may implement an imported trait");
+    }
+
+    #[test]
+    fn test_hir_contracts_are_type_checked() {
+        let hir = build_hir("struct Interval { lo: Int, hi: Int, invariant self.lo <= self.hi }\n\
+            fn half(n: Int) -> Int requires n >= 0 ensures result * 2 <= n { return n / 2; }").unwrap();
+        let half = hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.name == "half" => Some(function),
+            _ => None,
+        }).unwrap();
+        assert_eq!(half.requires[0].text, "n >= 0");
+        assert_eq!(half.requires[0].condition.expr_type, HirType::Boolean);
+        assert_eq!(half.ensures[0].text, "result * 2 <= n");
+        let interval = hir.items.iter().find_map(|item| match item {
+            HirItem::Struct(hir_struct) => Some(hir_struct),
+            _ => None,
+        }).unwrap();
+        assert_eq!(interval.invariants.len(), 1);
+
+        let error = |source: &str| build_hir(source).unwrap_err().to_string();
+        assert!(error("fn f(n: Int) -> Int requires n + 1 { return n; }").contains("Contract condition 'n + 1' must be a Boolean, found Int"));
+        assert!(error("fn f(n: Int) ensures n > 0 { }").contains("Function 'f' has 'ensures' clauses, so it must declare its return type"));
+        // `result` is only in scope in postconditions
+        assert!(build_hir("fn f(n: Int) -> Int requires result > 0 { return n; }").is_err());
+        assert!(build_hir("struct S { a: Int, invariant self.b > 0 }").is_err());
+    }
+
+    #[test]
+    fn test_hir_types_are_inferred_by_unification() {
+        let hir = build_hir("struct Point { x: Int, y: Int }\n\
+            fn norm(p) { return p.x * p.x + p.y * p.y; }\n\
+            fn apply(f, v) { return f(v); }\n\
+            fn show(a) { seeAm a; }\n\
+            seeAm norm(Point { x: 3, y: 4 });\n\
+            seeAm apply(fn(n) { return n * 10; }, 7);\n\
+            mut xs = [];\n\
+            xs = [1, 2, 3];\n\
+            show(xs[0]);").expect("unannotated program should be inferred");
+        let function = |name: &str| hir.items.iter().find_map(|item| match item {
+            HirItem::Function(function) if function.name == name => Some(function),
+            _ => None,
+        }).unwrap();
+        let norm = function("norm");
+        assert_eq!(norm.parameters[0].param_type.to_string(), "Point");
+        assert_eq!(norm.return_type, HirType::Int);
+        let apply = function("apply");
+        assert_eq!(apply.parameters[0].param_type.to_string(), "fn(Int) -> Int");
+        assert_eq!(apply.return_type, HirType::Int);
+        assert_eq!(function("show").return_type, HirType::Unit);
+        assert!(!format!("{:?}", hir).contains("Infer("), "no inference variable survives inference");
+
+        // A conflict points at the use that fixed the type
+        let conflict = build_hir("fn add(a, b) { return a + b; }\nseeAm add(1, 2);\nseeAm add(\"x\", \"y\");").unwrap_err();
+        assert!(conflict.to_string().contains("expected Int, found String"));
+        assert_eq!(conflict.location().map(|p| (p.line, p.column)), Some((3, 11)));
+        match &conflict {
+            OvieError::Diagnostic { diagnostic } => {
+                assert_eq!(diagnostic.related_locations.len(), 1);
+                assert_eq!(diagnostic.related_locations[0].0.line, 2);
+                assert_eq!(diagnostic.related_locations[0].1, "type Int was inferred here");
+            }
+            other => panic!("Expected a diagnostic, found {:?}", other),
+        }
+
+        // A conflict inside an inferred type is described by the whole type
+        let nested = build_hir("mut xs = []\nxs = [1]\nxs = [\"a\"]").unwrap_err();
+        assert!(nested.to_string().contains("expected [Int], found [String]"));
+        match &nested {
+            OvieError::Diagnostic { diagnostic } => {
+                assert_eq!(diagnostic.related_locations[0].0.line, 2);
+                assert_eq!(diagnostic.related_locations[0].1, "type [Int] was inferred here");
+            }
+            other => panic!("Expected a diagnostic, found {:?}", other),
+        }
+
+        let error = |source: &str| build_hir(source).unwrap_err().to_string();
+        assert!(error("fn f(g) { return g(g); }").contains("Cannot infer a type that contains itself"));
+        assert!(error("fn unused(a) { }").contains("Cannot infer the type of parameter 'a' of 'unused'; add a type annotation"));
+        assert!(error("fn neg(a) { return -a; }\nneg(true);").contains("expected"));
+    }
+
+    #[test]
+    fn test_hir_unresolved_names_are_reported_with_suggestions() {
+        use crate::modules::ModuleGraph;
+        use crate::resolve::edit_distance;
+
+        assert_eq!(edit_distance("count", "coutn"), 1);
+        assert_eq!(edit_distance("Point", "Pointt"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+
+        let source = "struct Point { x: Int, y: Int }\n\
+            mut count = 1;\n\
+            seeAm coutn;\n\
+            mut p = Pointt { x: 1, yy: 2 };\n\
+            seeAm p.xx;\n\
+            seeAm undefined_thing;";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let graph = ModuleGraph::single(ast).unwrap();
+        let (hir, diagnostics) = HirBuilder::new().transform_with_diagnostics(&graph);
+        assert!(hir.is_none());
+
+        // Every unresolved name is reported, none of the errors that follow from them
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(codes, ["E2001", "E2003", "E2001"]);
+        assert_eq!(diagnostics[0].message, "Undefined variable 'coutn'");
+        assert_eq!((diagnostics[0].location.line, diagnostics[0].location.column), (3, 7));
+        assert_eq!(diagnostics[0].suggestions[0].message, "did you mean 'count'?");
+        let fix = diagnostics[0].suggestions[0].code_fix.as_ref().unwrap();
+        assert_eq!(fix.replacements[0].new_text, "count");
+        let range = fix.replacements[0].start.offset..fix.replacements[0].end.offset;
+        assert_eq!(&source[range], "coutn");
+
+        assert_eq!(diagnostics[1].message, "Unknown struct 'Pointt'");
+        assert_eq!(diagnostics[1].suggestions[0].message, "did you mean 'Point'?");
+        assert!(diagnostics[2].suggestions.is_empty(), "nothing is close to 'undefined_thing'");
+
+        // Once the struct resolves, its fields are checked
+        let fields = source.replace("Pointt", "Point");
+        let tokens = Lexer::new(&fields).tokenize().unwrap();
+        let graph = ModuleGraph::single(Parser::new(tokens).parse().unwrap()).unwrap();
+        let (_, diagnostics) = HirBuilder::new().transform_with_diagnostics(&graph);
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(messages, [
+            "Undefined variable 'coutn'",
+            "Field 'yy' not found in struct 'Point'",
+            "Field 'xx' not found in struct 'Point'",
+            "Undefined variable 'undefined_thing'",
+        ]);
+        assert_eq!(diagnostics[1].code, "E2004");
+        assert_eq!(diagnostics[2].suggestions[0].message, "did you mean 'x'?");
+        let fix = diagnostics[2].suggestions[0].code_fix.as_ref().unwrap();
+        assert_eq!(&fields[fix.replacements[0].start.offset..fix.replacements[0].end.offset], "xx");
+    }
+
+    #[test]
+    fn test_hir_field_and_variant_errors_are_reported_with_names() {
+        use crate::modules::ModuleGraph;
+
+        let source = "struct P { x: Int }\n\
+            enum Color { Red, Green }\n\
+            mut p = P { x: 1 };\n\
+            p.xx = 2;\n\
+            mut arr = [1, 2];\n\
+            ar[0] = 3;\n\
+            let c = Color.Rde;\n\
+            let g = fn(v: P) -> Int { return v.y; };\n\
+            seeAm countr;\n\
+            let t: Strng = \"t\";";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let graph = ModuleGraph::single(Parser::new(tokens).parse().unwrap()).unwrap();
+        let (hir, diagnostics) = HirBuilder::new().transform_with_diagnostics(&graph);
+        assert!(hir.is_none());
+
+        // Assignment targets no longer stop checking at the first unresolved name
+        let reported: Vec<(&str, usize)> = diagnostics.iter()
+            .map(|d| (d.code.as_str(), d.location.line))
+            .collect();
+        assert_eq!(reported, [("E2004", 4), ("E2001", 6), ("E2005", 7), ("E2004", 8), ("E2001", 9), ("E2006", 10)]);
+        assert_eq!(diagnostics[0].suggestions[0].message, "did you mean 'x'?");
+        assert_eq!(diagnostics[1].suggestions[0].message, "did you mean 'arr'?");
+        assert_eq!(diagnostics[2].suggestions[0].message, "did you mean 'Red'?");
+        assert_eq!(diagnostics[3].suggestions[0].message, "did you mean 'x'?");
+        assert_eq!(diagnostics[5].context.get("name").map(String::as_str), Some("Strng"));
+
+        // The interpreter runs nothing until the names resolve
+        let error = crate::Compiler::new().compile_and_run("mut count = 1;\nseeAm count;\nseeAm coutn;").unwrap_err();
+        assert_eq!(error.get_diagnostic().map(|d| d.code.as_str()), Some("E2001"));
+    }
+
+    #[test]
+    fn test_hir_reassignment_requires_mut() {
+        // Immutable by default, from the Mutability System section of the memory model
+        let error = build_hir("x = 42;\nx = 43;").unwrap_err();
+        match &error {
+            OvieError::Diagnostic { diagnostic } => {
+                assert_eq!(diagnostic.code, "E3001");
+                assert_eq!(diagnostic.message, "Cannot assign to 'x' because it is not mutable; declare it with 'mut'");
+                assert_eq!((diagnostic.location.line, diagnostic.location.column), (2, 1));
+                // The declaration that lacks `mut` is the second span
+                assert_eq!(diagnostic.related_locations.len(), 1);
+                let (declaration, note) = &diagnostic.related_locations[0];
+                assert_eq!((declaration.line, declaration.column), (1, 1));
+                assert_eq!(note, "'x' is declared here without 'mut'");
+            }
+            other => panic!("Expected a diagnostic, found {:?}", other),
+        }
+        assert!(build_hir("mut y = 42;\ny = 43;\nseeAm y;").is_ok());
+
+        // A name already in scope is reassigned; in a function of its own it is declared anew
+        assert!(build_hir("fn f() { x = 1; seeAm x; }\nx = 2;\nf();").is_ok());
+        let error = build_hir("x = 1;\nif true {\n    x = 2;\n}").unwrap_err();
+        assert_eq!(error.location().map(|p| (p.line, p.column)), Some((3, 5)));
+
+        let code = |source: &str| match build_hir(source).unwrap_err() {
+            OvieError::Diagnostic { diagnostic } => (diagnostic.code, diagnostic.message),
+            other => panic!("Expected a diagnostic, found {:?}", other),
+        };
+        assert_eq!(code("fn f(n: Int) { n = n + 1; }").0, "E3001");
+        assert!(build_hir("fn f(mut n: Int) { n = n + 1; seeAm n; }\nf(1);").is_ok());
+        assert_eq!(code("struct P { x: Int }\np = P { x: 1 };\np.x = 2;").0, "E3002");
+        assert_eq!(code("const PI: Int = 3;\nfn main() { PI = 4; }").0, "E3003");
+        let (code, message) = code("for i in 0..3 {\n    i = 5;\n}");
+        assert_eq!(code, "E3001");
+        assert!(message.ends_with("copy it into a 'mut' variable to change it"));
+
+        // Every violation is reported, and checking goes on past them
+        use crate::modules::ModuleGraph;
+        let tokens = Lexer::new("a = 1;\nb = 2;\na = 3;\nb += 4;\nseeAm a + b;").tokenize().unwrap();
+        let graph = ModuleGraph::single(Parser::new(tokens).parse().unwrap()).unwrap();
+        let (hir, diagnostics) = HirBuilder::new().transform_with_diagnostics(&graph);
+        assert!(hir.is_none());
+        let lines: Vec<(&str, usize)> = diagnostics.iter().map(|d| (d.code.as_str(), d.location.line)).collect();
+        assert_eq!(lines, [("E3001", 3), ("E3001", 4)]);
+    }
+
+    #[test]
+    fn test_hir_functions_return_a_value_on_every_path() {
+        let message = |source: &str| match build_hir(source).unwrap_err() {
+            OvieError::Diagnostic { diagnostic } => (diagnostic.code, diagnostic.message, diagnostic.location.line),
+            other => panic!("Expected a diagnostic for {:?}, found {:?}", source, other),
+        };
+        // Falling off the end of a function that returns a value
+        assert_eq!(message("seeAm 1;\nfn f(n: Int) -> Int {\n    if n > 0 { return 1; }\n}"),
+            ("E0003".to_string(), "Type mismatch: expected Int, found Unit".to_string(), 2));
+        assert_eq!(message("fn f(n: Int) -> Int {\n    while n > 0 { return 1; }\n}").2, 1);
+        assert_eq!(message("fn f(n: Int) -> Int {\n    match n {\n        0 => { return 1; }\n        _ => { seeAm n; }\n    }\n}").2, 1);
+        // A final expression is the returned value, so it has the return type
+        assert_eq!(message("fn f(n: Int) -> Int {\n    \"many\"\n}").1, "Type mismatch: expected Int, found String");
+        assert_eq!(message("fn f(n: Int) -> Int {\n    match n {\n        0 => \"none\",\n        _ => \"some\",\n    }\n}").1,
+            "Type mismatch: expected Int, found String");
+
+        // Returning or stopping the program on every path
+        for source in [
+            "fn f(n: Int) -> Int {\n    if n > 0 { return 1; } else { return 2; }\n}",
+            "fn f(n: Int) -> Int {\n    n + 1\n}",
+            "fn f(n: Int) -> Int {\n    match n {\n        0 => 10,\n        _ => 20,\n    }\n}",
+            "fn f(n: Int) -> Int {\n    match n {\n        0 => { return 1; }\n        _ => { abort(\"many\"); }\n    }\n}",
+            "fn f(n: Int) -> Int {\n    if n > 0 { return n; }\n    abort(\"negative\");\n}",
+            "fn fail(message: String) { abort(message); }\nfn f(n: Int) -> Int {\n    if n > 0 { return n; }\n    fail(\"negative\");\n}",
+            "fn f(n: Int) -> Int {\n    while true { if n > 0 { return n; } }\n}",
+            "fn f(n: Int) {\n    if n > 0 { return; }\n}",
+        ] {
+            assert!(build_hir(source).is_ok(), "{:?}: {:?}", source, build_hir(source).err());
+        }
+        // A loop that can be left may still fall through
+        assert_eq!(message("fn f(n: Int) -> Int {\n    while true { break; }\n}").2, 1);
+        // A tail expression also infers the return type of a function that returns elsewhere
+        let hir = build_hir("fn f(n: Int) {\n    if n > 0 { return 1; }\n    n\n}").unwrap();
+        assert!(hir.items.iter().any(|item| matches!(item, HirItem::Function(function) if function.name == "f" && function.return_type == HirType::Int)));
+    }
+}
@@ -1,9 +1,13 @@
 // MIR (Mid-level IR) Tests
 // Feature: ovie-programming-language-stage-2, MIR Pipeline
 
-use crate::mir::{MirBuilder, MirProgram};
+use crate::mir::{MirBuilder, MirProgram, MirTerminator, MirOperand, MirRvalue, MirStatementKind, MirProjectionElem, MirTypeDef, MirAggregateKind, MirConstantValue, MirType};
+use crate::mir::{MirBasicBlock, MirBinOp, MirBorrowKind, MirConstant, MirFunction, MirFunctionSignature, MirLocal, MirMutability, MirPlace, MirRegion, MirStatement};
+use crate::error::OvieError;
 use crate::hir::{HirBuilder};
 use crate::ast::*;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
 
 #[cfg(test)]
 mod mir_tests {
@@ -12,7 +16,8 @@ mod mir_tests {
     fn create_test_hir() -> crate::hir::HirProgram {
         let statements = vec![
             Statement::Print {
-                expression: Expression::Literal(Literal::String("Hello, MIR!".to_string())),
+                expression: Expression::Literal(Literal::String("Hello, MIR!".to_string()), SourceSpan::default()),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -37,13 +42,32 @@ mod mir_tests {
     fn test_mir_function_transformation() {
         let statements = vec![
             Statement::Function {
+                public: false,
+                is_const: false,
+                is_unsafe: false,
                 name: "test_func".to_string(),
-                parameters: vec!["a".to_string()],
+                type_parameters: Vec::new(),
+                bounds: Vec::new(),
+                parameters: vec![Parameter::untyped("a", SourceSpan::default())],
+                return_type: None,
+                requires: Vec::new(),
+                ensures: Vec::new(),
                 body: vec![
                     Statement::Return {
-                        value: Some(Expression::Identifier("a".to_string())),
+                        value: Some(Expression::Identifier("a".to_string(), SourceSpan::default())),
+                        span: SourceSpan::default(),
                     }
                 ],
+                span: SourceSpan::default(),
+            },
+            // The call fixes the type of the unannotated parameter
+            Statement::Expression {
+                expression: Expression::Call {
+                    function: "test_func".to_string(),
+                    arguments: vec![Expression::Literal(Literal::Number(1.0), SourceSpan::default())],
+                    span: SourceSpan::default(),
+                },
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -71,24 +95,29 @@ mod mir_tests {
             Statement::Assignment {
                 mutable: false,
                 identifier: "x".to_string(),
-                value: Expression::Literal(Literal::Number(5.0)),
+                value: Expression::Literal(Literal::Number(5.0), SourceSpan::default()),
+                span: SourceSpan::default(),
             },
             Statement::If {
                 condition: Expression::Binary {
-                    left: Box::new(Expression::Identifier("x".to_string())),
+                    left: Box::new(Expression::Identifier("x".to_string(), SourceSpan::default())),
                     operator: BinaryOperator::Greater,
-                    right: Box::new(Expression::Literal(Literal::Number(0.0))),
+                    right: Box::new(Expression::Literal(Literal::Number(0.0), SourceSpan::default())),
+                    span: SourceSpan::default(),
                 },
                 then_block: vec![
                     Statement::Print {
-                        expression: Expression::Literal(Literal::String("Positive".to_string())),
+                        expression: Expression::Literal(Literal::String("Positive".to_string()), SourceSpan::default()),
+                        span: SourceSpan::default(),
                     }
                 ],
                 else_block: Some(vec![
                     Statement::Print {
-                        expression: Expression::Literal(Literal::String("Non-positive".to_string())),
+                        expression: Expression::Literal(Literal::String("Non-positive".to_string()), SourceSpan::default()),
+                        span: SourceSpan::default(),
                     }
                 ]),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -108,10 +137,12 @@ mod mir_tests {
             Statement::Assignment {
                 mutable: false,
                 identifier: "x".to_string(),
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
+                span: SourceSpan::default(),
             },
             Statement::Print {
-                expression: Expression::Identifier("x".to_string()),
+                expression: Expression::Identifier("x".to_string(), SourceSpan::default()),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -139,12 +170,14 @@ mod mir_tests {
             Statement::Assignment {
                 mutable: true,
                 identifier: "x".to_string(),
-                value: Expression::Literal(Literal::Number(42.0)),
+                value: Expression::Literal(Literal::Number(42.0), SourceSpan::default()),
+                span: SourceSpan::default(),
             },
             Statement::Assignment {
                 mutable: false,
                 identifier: "y".to_string(),
-                value: Expression::Literal(Literal::String("test".to_string())),
+                value: Expression::Literal(Literal::String("test".to_string()), SourceSpan::default()),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -199,13 +232,23 @@ mod mir_tests {
     fn test_mir_entry_point() {
         let statements = vec![
             Statement::Function {
+                public: false,
+                is_const: false,
+                is_unsafe: false,
                 name: "main".to_string(),
+                type_parameters: Vec::new(),
+                bounds: Vec::new(),
                 parameters: vec![],
+                return_type: None,
+                requires: Vec::new(),
+                ensures: Vec::new(),
                 body: vec![
                     Statement::Print {
-                        expression: Expression::Literal(Literal::String("Main function".to_string())),
+                        expression: Expression::Literal(Literal::String("Main function".to_string()), SourceSpan::default()),
+                        span: SourceSpan::default(),
                     }
                 ],
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -230,17 +273,21 @@ mod mir_tests {
     fn test_mir_type_definitions() {
         let statements = vec![
             Statement::Struct {
+                public: false,
                 name: "Point".to_string(),
+                type_parameters: Vec::new(),
                 fields: vec![
                     StructField {
                         name: "x".to_string(),
-                        type_annotation: "Number".to_string(),
+                        type_annotation: TypeAnnotation::named("Number"),
                     },
                     StructField {
                         name: "y".to_string(),
-                        type_annotation: "Number".to_string(),
+                        type_annotation: TypeAnnotation::named("Number"),
                     },
                 ],
+                invariants: Vec::new(),
+                span: SourceSpan::default(),
             }
         ];
         let ast = AstNode::new(statements);
@@ -255,4 +302,731 @@ mod mir_tests {
         assert!(mir.type_definitions.contains_key("Point"), 
                 "MIR should contain struct type definition");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_mir_monomorphizes_generics() {
+        let source = "struct Pair<A, B> { first: A, second: B }\n\
+            fn identity<T>(value: T) -> T { return value; }\n\
+            fn main() {\n\
+                let p = Pair { first: identity(1), second: \"one\" };\n\
+                seeAm identity(p.second);\n\
+            }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        let mut names: Vec<&str> = mir.functions.values().map(|f| f.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["identity<Int>", "identity<String>", "main"]);
+
+        assert!(mir.type_definitions.contains_key("Pair<Int, String>"));
+        assert!(!mir.type_definitions.contains_key("Pair"), "generic templates are not laid out");
+    }
+
+    #[test]
+    fn test_mir_match_switches_on_discriminant() {
+        let source = "enum Shape { Circle(Number), Empty }\n\
+            fn area(shape: Shape) -> Number {\n\
+                return match shape {\n\
+                    Shape.Circle(r) => r * r,\n\
+                    Shape.Empty => 0.0,\n\
+                };\n\
+            }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        let area = mir.functions.values().find(|f| f.name == "area").expect("area should be in MIR");
+        let switch = area.basic_blocks.values().find_map(|block| match &block.terminator {
+            MirTerminator::SwitchInt { discriminant: MirOperand::Copy(place), targets, .. } => {
+                let is_discriminant = block.statements.iter().any(|stmt| matches!(&stmt.kind,
+                    MirStatementKind::Assign { place: target, rvalue: MirRvalue::Discriminant(_) } if target.local == place.local));
+                is_discriminant.then_some(targets)
+            }
+            _ => None,
+        }).expect("match should switch on the enum discriminant");
+        assert_eq!(switch.iter().map(|(value, _)| *value).collect::<Vec<_>>(), vec![0, 1]);
+
+        let downcasts_payload = area.basic_blocks.values().flat_map(|block| &block.statements).any(|stmt| matches!(&stmt.kind,
+            MirStatementKind::Assign { rvalue: MirRvalue::Use(MirOperand::Copy(place)), .. }
+                if matches!(place.projection.as_slice(), [MirProjectionElem::Downcast(0), MirProjectionElem::Field(0)])));
+        assert!(downcasts_payload, "the payload binding should read through a downcast");
+    }
+
+    #[test]
+    fn test_mir_multi_field_variants() {
+        let source = "enum Shape { Rect { w: Int, h: Int }, Move(Int, Int) }
+            fn height(shape: Shape) -> Int {
+                return match shape {
+                    Shape.Rect { h } => h,
+                    Shape.Move(_, y) => y,
+                };
+            }
+            fn main() { seeAm height(Shape.Rect { h: 2, w: 1 }); }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        match mir.type_definitions.get("Shape") {
+            Some(MirTypeDef::Enum { variants }) => {
+                let fields: Vec<Vec<&str>> = variants.iter()
+                    .map(|variant| variant.fields.iter().map(|field| field.name.as_str()).collect())
+                    .collect();
+                assert_eq!(fields, vec![vec!["w", "h"], vec!["0", "1"]]);
+            }
+            other => panic!("Expected an enum layout, found {:?}", other),
+        }
+
+        // Named fields are laid out in declaration order, whatever order they are written in
+        let main = mir.functions.values().find(|f| f.name == "main").expect("main should be in MIR");
+        let operands = main.basic_blocks.values().flat_map(|block| &block.statements).find_map(|stmt| match &stmt.kind {
+            MirStatementKind::Assign { rvalue: MirRvalue::Aggregate { operands, .. }, .. } => Some(operands),
+            _ => None,
+        }).expect("the variant should be built as an aggregate");
+        let values: Vec<i64> = operands.iter().filter_map(|operand| match operand {
+            MirOperand::Constant(constant) => match constant.literal {
+                MirConstantValue::Integer(value) => Some(value),
+                _ => None,
+            },
+            _ => None,
+        }).collect();
+        assert_eq!(values, vec![1, 2]);
+
+        // Each binding reads its own field of the variant
+        let height = mir.functions.values().find(|f| f.name == "height").expect("height should be in MIR");
+        let fields: Vec<(u32, u32)> = height.basic_blocks.values().flat_map(|block| &block.statements)
+            .filter_map(|stmt| match &stmt.kind {
+                MirStatementKind::Assign { rvalue: MirRvalue::Use(MirOperand::Copy(place)), .. } => match place.projection.as_slice() {
+                    [MirProjectionElem::Downcast(variant), MirProjectionElem::Field(field)] => Some((*variant, *field)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        assert!(fields.contains(&(0, 1)), "Rect's h is its second field");
+        assert!(fields.contains(&(1, 1)), "Move's y is its second field");
+    }
+
+    #[test]
+    fn test_mir_lifts_closures() {
+        let source = "fn double(x: Number) -> Number { return x * 2.0; }\n\
+            fn main() {\n\
+                let n = 1.0;\n\
+                let add = fn(x: Number) -> Number { return x + n; };\n\
+                let f = double;\n\
+                seeAm add(f(2.0));\n\
+            }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        let mut names: Vec<&str> = mir.functions.values().map(|f| f.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["double", "double::{fn}", "main", "main::{closure#0}"]);
+
+        // The closure record holds the lifted function followed by the captured values
+        match &mir.type_definitions["main::{closure#0}"] {
+            MirTypeDef::Struct { fields } => {
+                let field_names: Vec<&str> = fields.iter().map(|field| field.name.as_str()).collect();
+                assert_eq!(field_names, vec!["function", "n"]);
+            }
+            other => panic!("expected a closure record, found {:?}", other),
+        }
+
+        let closure = mir.functions.values().find(|f| f.name == "main::{closure#0}").unwrap();
+        assert_eq!(closure.signature.parameters.len(), 2, "the closure record is passed before the parameters");
+
+        let main = mir.functions.values().find(|f| f.is_main).unwrap();
+        let builds_record = main.basic_blocks.values().flat_map(|block| &block.statements).any(|stmt| matches!(&stmt.kind,
+            MirStatementKind::Assign { rvalue: MirRvalue::Aggregate { kind: MirAggregateKind::Adt { name, .. }, operands }, .. }
+                if name == "main::{closure#0}" && matches!(&operands[0], MirOperand::Constant(constant)
+                    if matches!(&constant.literal, MirConstantValue::Function(function) if function == name))));
+        assert!(builds_record, "main should build the closure record");
+
+        let indirect_calls = main.basic_blocks.values().filter(|block| matches!(&block.terminator,
+            MirTerminator::Call { func: MirOperand::Copy(_), .. })).count();
+        assert_eq!(indirect_calls, 2);
+    }
+
+    #[test]
+    fn test_mir_loop_exits_are_cfg_edges() {
+        let source = "fn main() {\n\
+                let mut i = 0;\n\
+                while i < 10 {\n\
+                    i = i + 1;\n\
+                    if i == 3 { continue; }\n\
+                    if i == 7 { break; }\n\
+                    seeAm i;\n\
+                }\n\
+            }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        let (main_id, main) = mir.functions.iter().find(|(_, f)| f.is_main).unwrap();
+        let analysis = mir.analyze_cfg().unwrap();
+        let loops = &analysis.function_analyses[main_id].loops;
+        assert_eq!(loops.len(), 1, "continue should not create a second loop");
+
+        let info = &loops[0];
+        assert_eq!(info.back_edge_sources.len(), 2, "the end of the body and the continue jump back to the header");
+        assert_eq!(info.exits.len(), 2, "the failed condition and the break leave the loop");
+        assert!(info.exits.iter().all(|(inside, outside)| info.body.contains(inside) && !info.body.contains(outside)));
+
+        // The block holding the break lies outside the loop and jumps to where the failed condition goes
+        let header_exit = info.exits.iter().find(|(inside, _)| *inside == info.header).unwrap().1;
+        let (_, break_block) = info.exits.iter().find(|(inside, _)| *inside != info.header).unwrap();
+        assert!(matches!(main.basic_blocks[break_block].terminator, MirTerminator::Goto { target } if target == header_exit));
+    }
+
+    #[test]
+    fn test_mir_compound_assignment_updates_projected_place() {
+        let source = "struct Point { x: Int }\n\
+            fn main() {\n\
+                mut points = [Point { x: 1 }]\n\
+                points[0].x += 2\n\
+            }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        let main = mir.functions.values().find(|f| f.is_main).unwrap();
+        let updates: Vec<_> = main.basic_blocks.values().flat_map(|block| &block.statements).filter_map(|stmt| match &stmt.kind {
+            MirStatementKind::Assign { place, rvalue: MirRvalue::BinaryOp { left: MirOperand::Copy(left), .. } } if !place.projection.is_empty() => {
+                Some((place, left))
+            }
+            _ => None,
+        }).collect();
+
+        // The place is read and written through the same projection, so its index is evaluated once
+        assert_eq!(updates.len(), 1);
+        let (place, left) = updates[0];
+        assert_eq!(format!("{:?}", place), format!("{:?}", left));
+        assert!(matches!(place.projection[..], [MirProjectionElem::Index(_), MirProjectionElem::Field(0)]));
+    }
+
+    #[test]
+    fn test_mir_constants_are_inlined() {
+        let source = "const fn cube(n: Int) -> Int { return n * n * n }\n\
+            const SIZE: Int = cube(3)\n\
+            fn main() { seeAm SIZE + 1; }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        let main = mir.functions.values().find(|f| f.is_main).unwrap();
+        let uses_size = main.basic_blocks.values().flat_map(|block| &block.statements).any(|stmt| matches!(&stmt.kind,
+            MirStatementKind::Assign { rvalue: MirRvalue::BinaryOp { left: MirOperand::Constant(constant), .. }, .. }
+                if matches!(constant.literal, MirConstantValue::Integer(27))
+        ));
+        assert!(uses_size, "the value of SIZE should be used directly");
+        assert!(mir.globals["SIZE"].initializer.is_some());
+    }
+
+    #[test]
+    fn test_mir_trapping_int_division_is_left_to_run_time() {
+        for (source, message) in [
+            ("seeAm 10 / 0", "Division by zero"),
+            ("seeAm 10 % 0", "Modulo by zero"),
+            ("seeAm (-9223372036854775807 - 1) / -1", "Integer overflow in division"),
+        ] {
+            let error = crate::Compiler::new().compile_and_run(source).unwrap_err();
+            assert!(error.to_string().contains(message), "AST interpreter on {:?}: {}", source, error);
+            let error = crate::Compiler::new().compile_and_run_ir(source).unwrap_err();
+            assert!(error.to_string().contains(message), "IR interpreter on {:?}: {}", source, error);
+            // The unfolded division compiles to i64.div_s / i64.rem_s, which trap the same way
+            assert!(crate::Compiler::new().compile_to_wasm(source).is_ok(), "WASM on {:?}", source);
+        }
+    }
+
+    #[test]
+    fn test_mir_error_propagation_returns_early() {
+        let source = "enum Option<T> { Some(T), None }\n\
+            fn half(n: Int) -> Option<Int> {\n\
+                if n % 2 == 0 { return Option.Some(n / 2) }\n\
+                return Option.None\n\
+            }\n\
+            fn quarter(n: Int) -> Option<Int> { return half(half(n)?) }\n\
+            fn main() { seeAm 1; }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        let quarter = mir.functions.values().find(|f| f.name == "quarter").unwrap();
+        // The operand's discriminant selects between continuing with Some and returning
+        let (targets, otherwise) = quarter.basic_blocks.values().find_map(|block| match &block.terminator {
+            MirTerminator::SwitchInt { targets, otherwise, .. } => Some((targets.clone(), *otherwise)),
+            _ => None,
+        }).expect("'?' should switch on the operand's variant");
+        assert!(quarter.basic_blocks.values().flat_map(|block| &block.statements)
+            .any(|stmt| matches!(&stmt.kind, MirStatementKind::Assign { rvalue: MirRvalue::Discriminant(_), .. })));
+
+        let failure = &quarter.basic_blocks[&otherwise];
+        assert!(matches!(failure.terminator, MirTerminator::Return { value: Some(_) }));
+        assert!(failure.statements.iter().any(|stmt| matches!(&stmt.kind,
+            MirStatementKind::Assign { rvalue: MirRvalue::Aggregate { kind: MirAggregateKind::Adt { variant: Some(1), .. }, operands }, .. }
+                if operands.is_empty()
+        )), "the early return should rebuild None");
+
+        // The success block reads Some's data through a downcast
+        let success = &quarter.basic_blocks[&targets[0].1];
+        assert_eq!(targets[0].0, 0);
+        assert!(success.statements.iter().any(|stmt| matches!(&stmt.kind,
+            MirStatementKind::Assign { rvalue: MirRvalue::Use(MirOperand::Copy(place)), .. }
+                if matches!(place.projection[..], [MirProjectionElem::Downcast(0), MirProjectionElem::Field(0)])
+        )));
+    }
+
+    #[test]
+    fn test_mir_tuple_destructuring_projects_fields() {
+        let source = "fn main() {\n\
+                let (a, b) = (1, \"x\")\n\
+                seeAm b\n\
+            }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        let main = mir.functions.values().find(|f| f.is_main).unwrap();
+        let statements: Vec<_> = main.basic_blocks.values().flat_map(|block| &block.statements).collect();
+        let tuple = statements.iter().find_map(|stmt| match &stmt.kind {
+            MirStatementKind::Assign { place, rvalue: MirRvalue::Aggregate { kind: MirAggregateKind::Tuple, operands } } => Some((place, operands)),
+            _ => None,
+        }).expect("the tuple literal should build a tuple aggregate");
+        assert_eq!(tuple.1.len(), 2);
+
+        // Each name is copied out of its own field of the tuple
+        let fields: Vec<_> = statements.iter().filter_map(|stmt| match &stmt.kind {
+            MirStatementKind::Assign { rvalue: MirRvalue::Use(MirOperand::Copy(source)), .. } if source.local == tuple.0.local => {
+                match source.projection[..] {
+                    [MirProjectionElem::Field(index)] => Some(index),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }).collect();
+        assert_eq!(fields, vec![0, 1]);
+        assert!(main.locals.iter().any(|local| local.name.as_deref() == Some("a") && local.ty == MirType::Int));
+    }
+
+    #[test]
+    fn test_mir_trait_methods_are_dispatched_statically() {
+        let source = "trait Show {\n\
+                fn show(self) -> String;\n\
+                fn describe(self) -> String { return self.show(); }\n\
+            }\n\
+            struct Wrapper<T> { value: T }\n\
+            impl Show for Int { fn show(self) -> String { return \"int\"; } }\n\
+            impl Show for Wrapper<T: Show> { fn show(self) -> String { return self.value.show(); } }\n\
+            fn all<T: Show>(item: T) -> String { return item.describe(); }\n\
+            fn main() { seeAm all(1); seeAm all(Wrapper { value: 2 }); }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        let callees = |name: &str| -> Vec<String> {
+            let function = mir.functions.values().find(|f| f.name == name)
+                .unwrap_or_else(|| panic!("missing instance {}", name));
+            function.basic_blocks.values().filter_map(|block| match &block.terminator {
+                MirTerminator::Call { func: MirOperand::Constant(constant), .. } => match &constant.literal {
+                    MirConstantValue::String(callee) => Some(callee.clone()),
+                    _ => None,
+                },
+                _ => None,
+            }).collect()
+        };
+
+        // Each use of a bounded function gets an instance, and the default method one per Self type
+        assert_eq!(callees("all<Int>"), vec!["Show::describe<Int>"]);
+        assert_eq!(callees("all<Wrapper<Int>>"), vec!["Show::describe<Wrapper<Int>>"]);
+        // Calls through the bound go straight to the impl for the concrete type
+        assert_eq!(callees("Show::describe<Int>"), vec!["Int::show"]);
+        assert_eq!(callees("Show::describe<Wrapper<Int>>"), vec!["Wrapper::show<Int>"]);
+        assert_eq!(callees("Wrapper::show<Int>"), vec!["Int::show"]);
+        assert!(mir.functions.values().all(|f| f.name != "Show::show" && !f.name.starts_with("Show::show<")));
+    }
+
+    #[test]
+    fn test_mir_contracts_become_assertions() {
+        let source = "struct Interval { lo: Int, hi: Int, invariant self.lo <= self.hi }\n\
+            fn half(n: Int) -> Int requires n >= 0 ensures result * 2 <= n { return n / 2; }\n\
+            fn main() { mut r = Interval { lo: 1, hi: 2 }; r.lo = half(4); }";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+
+        let assertions = |mir: &MirProgram, name: &str| -> Vec<String> {
+            let function = mir.functions.values().find(|f| f.name == name).unwrap();
+            let mut messages: Vec<String> = function.basic_blocks.values().filter_map(|block| match &block.terminator {
+                MirTerminator::Assert { message, .. } => Some(message.clone()),
+                _ => None,
+            }).collect();
+            messages.sort();
+            messages
+        };
+
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+        assert_eq!(assertions(&mir, "half"), vec!["postcondition of 'half': result * 2 <= n", "precondition of 'half': n >= 0"]);
+        // The invariant is checked when the struct is built and again after its field changes
+        assert_eq!(assertions(&mir, "main"), vec!["invariant of 'Interval': self.lo <= self.hi"; 2]);
+
+        let mut builder = MirBuilder::new();
+        builder.set_check_contracts(false);
+        let release = builder.transform_hir(&hir).unwrap();
+        assert!(assertions(&release, "half").is_empty() && assertions(&release, "main").is_empty());
+    }
+
+    /// Function `f` of the given locals and blocks, each statement and terminator
+    /// on a line of its own counting from 1
+    fn function_of(locals: &[(&str, MirType)], blocks: Vec<(Vec<MirStatementKind>, MirTerminator)>) -> MirFunction {
+        let mut line = 0;
+        let mut basic_blocks = std::collections::HashMap::new();
+        for (id, (kinds, terminator)) in blocks.into_iter().enumerate() {
+            let mut statements = Vec::new();
+            for kind in kinds {
+                line += 1;
+                statements.push(MirStatement { kind, span: SourceSpan::new(0, 0, line, 1) });
+            }
+            line += 1;
+            let id = id as u32;
+            basic_blocks.insert(id, MirBasicBlock { id, statements, terminator, terminator_span: SourceSpan::new(0, 0, line, 1) });
+        }
+        let locals = locals.iter().enumerate().map(|(id, (name, ty))| MirLocal {
+            id: id as u32,
+            ty: ty.clone(),
+            is_mutable: true,
+            name: Some(name.to_string()),
+            span: SourceSpan::default(),
+        }).collect();
+        MirFunction {
+            id: 0,
+            name: "f".to_string(),
+            signature: MirFunctionSignature { parameters: Vec::new(), return_type: MirType::Unit },
+            basic_blocks,
+            locals,
+            entry_block: 0,
+            is_main: false,
+            span: SourceSpan::default(),
+        }
+    }
+
+    /// Borrow check `function_of(locals, blocks)`, as (code, line, related line)
+    fn borrow_errors(locals: &[(&str, MirType)], blocks: Vec<(Vec<MirStatementKind>, MirTerminator)>) -> Vec<(String, usize, usize)> {
+        let mut program = MirBuilder::new().transform_hir(&create_test_hir()).unwrap();
+        program.functions.clear();
+        program.functions.insert(0, function_of(locals, blocks));
+        crate::borrowck::check_program(&program).into_iter().map(|error| match error {
+            OvieError::Diagnostic { diagnostic } => (diagnostic.code, diagnostic.location.line, diagnostic.related_locations[0].0.line),
+            other => panic!("Expected a diagnostic, found {:?}", other),
+        }).collect()
+    }
+
+    #[test]
+    fn test_mir_borrow_checker() {
+        let local = MirPlace::from_local;
+        let deref = |id| MirPlace { local: id, projection: vec![MirProjectionElem::Deref] };
+        let field = |id, index| MirPlace { local: id, projection: vec![MirProjectionElem::Field(index)] };
+        let constant = |literal, ty| MirOperand::Constant(MirConstant { literal, ty });
+        let string = |text: &str| MirRvalue::Use(constant(MirConstantValue::String(text.to_string()), MirType::String));
+        let int = |n| MirRvalue::Use(constant(MirConstantValue::Integer(n), MirType::Int));
+        let assign = |place, rvalue| MirStatementKind::Assign { place, rvalue };
+        let copy = |place| MirRvalue::Use(MirOperand::Copy(place));
+        let moved = |place, line| MirRvalue::Use(MirOperand::Move(place, SourceSpan::new(0, 0, line, 1)));
+        let borrow = |place, mutable| MirRvalue::Ref {
+            region: MirRegion::Local(0),
+            borrow_kind: if mutable { MirBorrowKind::Mut } else { MirBorrowKind::Shared },
+            place,
+        };
+        let done = || MirTerminator::Return { value: None };
+
+        // Moves: a moved value cannot be used until it is assigned again, on any path
+        let strings = [("s", MirType::String), ("t", MirType::String), ("u", MirType::String), ("c", MirType::Boolean)];
+        assert_eq!(borrow_errors(&strings, vec![
+            (vec![assign(local(0), string("a")), assign(local(1), moved(local(0), 2)), assign(local(2), copy(local(0)))], done()),
+        ]), vec![("E3004".to_string(), 3, 2)]);
+        assert!(borrow_errors(&strings, vec![
+            (vec![assign(local(0), string("a")), assign(local(1), moved(local(0), 2)), assign(local(0), string("b")), assign(local(2), copy(local(0)))], done()),
+        ]).is_empty());
+        assert_eq!(borrow_errors(&strings, vec![
+            (vec![assign(local(0), string("a"))], MirTerminator::SwitchInt { discriminant: MirOperand::Copy(local(3)), targets: vec![(0, 1)], otherwise: 2 }),
+            (vec![assign(local(1), moved(local(0), 3))], MirTerminator::Goto { target: 2 }),
+            (vec![assign(local(2), copy(local(0)))], done()),
+        ]), vec![("E3004".to_string(), 5, 3)]);
+
+        // Borrows conflict while the reference holding the first is still to be used
+        let reference = |mutability| MirType::Ref { region: MirRegion::Local(0), ty: Box::new(MirType::Int), mutability };
+        let locals = [
+            ("x", MirType::Int),
+            ("r1", reference(MirMutability::Mut)),
+            ("r2", reference(MirMutability::Mut)),
+            ("y", MirType::Int),
+            ("p", MirType::Tuple(vec![MirType::Int, MirType::Int])),
+        ];
+        let errors = |statements: Vec<MirStatementKind>| borrow_errors(&locals, vec![(statements, done())]);
+        assert_eq!(errors(vec![
+            assign(local(0), int(1)), assign(local(1), borrow(local(0), true)), assign(local(2), borrow(local(0), true)), assign(local(3), copy(deref(1))),
+        ]), vec![("E3006".to_string(), 3, 2)]);
+        assert!(errors(vec![
+            assign(local(0), int(1)), assign(local(1), borrow(local(0), true)), assign(local(3), copy(deref(1))),
+            assign(local(2), borrow(local(0), true)), assign(local(3), copy(deref(2))),
+        ]).is_empty(), "the first borrow is no longer used when the second is made");
+        assert_eq!(errors(vec![
+            assign(local(0), int(1)), assign(local(1), borrow(local(0), false)), assign(local(2), borrow(local(0), true)), assign(local(3), copy(deref(1))),
+        ]), vec![("E3007".to_string(), 3, 2)]);
+        assert_eq!(errors(vec![
+            assign(local(0), int(1)), assign(local(1), borrow(local(0), true)), assign(local(2), borrow(local(0), false)), assign(local(3), copy(deref(1))),
+        ]), vec![("E3010".to_string(), 3, 2)]);
+        assert_eq!(errors(vec![
+            assign(local(0), int(1)), assign(local(1), borrow(local(0), false)), assign(local(0), int(2)), assign(local(3), copy(deref(1))),
+        ]), vec![("E3008".to_string(), 3, 2)]);
+        assert_eq!(errors(vec![
+            assign(local(0), int(1)), assign(local(1), borrow(local(0), true)), assign(local(3), copy(local(0))), assign(local(3), copy(deref(1))),
+        ]), vec![("E3008".to_string(), 3, 2)]);
+        // A copied reference holds the borrow too
+        assert_eq!(errors(vec![
+            assign(local(0), int(1)), assign(local(1), borrow(local(0), true)), assign(local(2), copy(local(1))),
+            assign(local(0), int(2)), assign(local(3), copy(deref(2))),
+        ]), vec![("E3008".to_string(), 4, 2)]);
+        // Different fields of a tuple can be borrowed mutably at once
+        assert!(errors(vec![
+            assign(local(4), MirRvalue::Aggregate { kind: MirAggregateKind::Tuple, operands: vec![MirOperand::Copy(local(0)), MirOperand::Copy(local(0))] }),
+            assign(local(1), borrow(field(4, 0), true)), assign(local(2), borrow(field(4, 1), true)),
+            assign(local(3), copy(deref(1))), assign(local(3), copy(deref(2))),
+        ]).is_empty());
+
+        // Borrows must not outlive the storage they borrow
+        assert_eq!(errors(vec![
+            MirStatementKind::StorageLive(0), assign(local(0), int(1)), assign(local(1), borrow(local(0), false)),
+            MirStatementKind::StorageDead(0), assign(local(3), copy(deref(1))),
+        ]), vec![("E3009".to_string(), 3, 4)]);
+        assert_eq!(borrow_errors(&locals, vec![
+            (vec![assign(local(0), int(1)), assign(local(1), borrow(local(0), false))], MirTerminator::Return { value: Some(MirOperand::Copy(local(1))) }),
+        ]), vec![("E3009".to_string(), 3, 2)]);
+
+        // MIR built from source borrow checks cleanly
+        let program = MirBuilder::new().transform_hir(&create_test_hir()).unwrap();
+        assert!(crate::borrowck::check_program(&program).is_empty());
+    }
+
+    #[test]
+    fn test_mir_moves_from_source_are_checked() {
+        let moved = |source: &str| match crate::Compiler::new().compile_to_mir(source) {
+            Ok(_) => None,
+            Err(OvieError::Diagnostic { diagnostic }) => Some((
+                format!("{} [{}]", diagnostic.message, diagnostic.code),
+                Some((diagnostic.location.line, diagnostic.location.column)),
+                diagnostic.related_locations.first().map(|(p, _)| (p.line, p.column)),
+            )),
+            Err(other) => panic!("Expected a diagnostic for {:?}, found {:?}", source, other),
+        };
+
+        let (message, at, moved_at) = moved("let x = \"hello\"\nlet y = x\nseeAm x").unwrap();
+        assert_eq!(message, "Use of moved value 'x' [E3004]");
+        assert_eq!((at, moved_at), (Some((3, 1)), Some((2, 9))));
+
+        // Arguments move into the function, and a field can be moved out on its own
+        let (message, at, _) = moved("fn consume(s: String) { seeAm s; }\nlet text = \"hi\"\nconsume(text)\nseeAm text").unwrap();
+        assert!(message.contains("Use of moved value 'text'"), "{}", message);
+        assert_eq!(at, Some((4, 1)));
+        let (message, at, _) = moved("struct Person { name: String, age: Int }\n\
+            let p = Person { name: \"Ada\", age: 36 }\nlet n = p.name\nseeAm p.age\nlet q = p").unwrap();
+        assert!(message.contains("Use of 'p' after 'p.name' was moved out of it"), "{}", message);
+        assert_eq!(at, Some((5, 1)));
+        let (message, _, _) = moved("let s = \"a\"\nmut flag = true\nif flag { let t = s }\nseeAm s").unwrap();
+        assert!(message.contains("Use of moved value 's'"), "moved on one path: {}", message);
+
+        // Copies, reads through operators and method receivers, and reassignment move nothing
+        for source in [
+            "let a = 1\nlet b = a\nseeAm a + b",
+            "let s = \"x\"\nseeAm s + s\nlet t = s",
+            "mut s = \"a\"\nlet t = s\ns = \"b\"\nseeAm s + t",
+            "struct Cell { value: String }\nimpl Cell { fn get(self) -> String { return self.value; } }\n\
+                let c = Cell { value: \"v\" }\nseeAm c.get()\nseeAm c.get()",
+            "let items = [\"a\", \"b\"]\nlet first = items[0]\nseeAm items[0] + first",
+        ] {
+            assert_eq!(moved(source), None, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn test_mir_dataflow_analyses() {
+        use crate::dataflow::{solve, FunctionDataflow, Liveness, Location};
+
+        let local = MirPlace::from_local;
+        let int = |n| MirOperand::Constant(MirConstant { literal: MirConstantValue::Integer(n), ty: MirType::Int });
+        let assign = |id, rvalue| MirStatementKind::Assign { place: local(id), rvalue };
+        let goto = |target| MirTerminator::Goto { target };
+
+        // x = 1; y = 2; if c { y = 3; w = 0 }; z = x + 1; return z
+        let locals = [("x", MirType::Int), ("y", MirType::Int), ("c", MirType::Boolean), ("w", MirType::Int), ("z", MirType::Int)];
+        let function = function_of(&locals, vec![
+            (vec![assign(0, MirRvalue::Use(int(1))), assign(1, MirRvalue::Use(int(2)))],
+             MirTerminator::SwitchInt { discriminant: MirOperand::Copy(local(2)), targets: vec![(0, 1)], otherwise: 2 }),
+            (vec![assign(1, MirRvalue::Use(int(3))), assign(3, MirRvalue::Use(int(0)))], goto(3)),
+            (vec![], goto(3)),
+            (vec![
+                assign(4, MirRvalue::BinaryOp { op: MirBinOp::Add, left: MirOperand::Copy(local(0)), right: int(1) }),
+                assign(1, MirRvalue::BinaryOp { op: MirBinOp::Add, left: MirOperand::Copy(local(1)), right: MirOperand::Copy(local(4)) }),
+            ], MirTerminator::Return { value: Some(MirOperand::Copy(local(1))) }),
+        ]);
+        let set = |locals: &[u32]| locals.iter().copied().collect::<std::collections::BTreeSet<_>>();
+        let at = |block, statement_index| Location { block, statement_index };
+
+        let dataflow = FunctionDataflow::analyze(&function);
+        assert_eq!(dataflow.live_in[&0], set(&[2]));
+        assert_eq!(dataflow.live_out[&0], set(&[0, 1]));
+        assert_eq!(dataflow.live_in[&1], set(&[0]), "y is assigned again before it is read");
+        assert_eq!(dataflow.live_in[&3], set(&[0, 1]));
+        assert_eq!(dataflow.reaching_definitions[&3], [at(0, 0), at(0, 1), at(1, 0), at(1, 1)].into_iter().collect());
+        assert_eq!(dataflow.initialized[&3], set(&[0, 1]), "w is assigned on one path only");
+        assert_eq!(dataflow.constants[&3].get(&0), Some(&MirConstantValue::Integer(1)));
+        assert_eq!(dataflow.constants[&3].get(&4), Some(&MirConstantValue::Integer(2)));
+        assert_eq!(dataflow.constants[&3].get(&1), None, "y is 2 or 3 depending on the path");
+
+        // States inside a block are in program order, whatever the direction
+        let liveness = solve(Liveness, &function);
+        assert_eq!(liveness.states_in_block(&function, 3), vec![set(&[0, 1]), set(&[1, 4]), set(&[1])]);
+
+        // Loops reach a fixed point, and the results are in the report and the graph
+        let mir = MirBuilder::new().transform_hir(&create_test_hir()).unwrap();
+        let analysis = mir.analyze_dataflow();
+        assert_eq!(analysis.function_analyses.len(), mir.functions.len());
+        assert!(mir.generate_ir_report().unwrap().contains("=== Dataflow Analysis ==="));
+        assert!(mir.to_dot().unwrap().contains("live in:"));
+    }
+
+    #[test]
+    fn test_mir_for_over_ranges_runs_on_every_backend() {
+        let source = "fn sum_to(n: Int) -> Int {\n\
+                let mut total = 0;\n\
+                for i in 0..n { total = total + i; }\n\
+                return total;\n\
+            }\n\
+            if sum_to(10) != 45 { abort(\"sum\"); }\n\
+            if sum_to(0) != 0 { abort(\"empty\"); }\n\
+            let mut pairs = 0;\n\
+            for i in 1..4 { for j in i..4 { pairs = pairs + 1; } }\n\
+            if pairs != 6 { abort(\"nested\"); }\n\
+            let span = 2..5;\n\
+            let mut spanned = 0;\n\
+            for i in span { spanned = spanned + i; }\n\
+            if spanned != 9 { abort(\"range value\"); }\n\
+            seeAm pairs;";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+        // A range value is a builtin ADT whose name no program can declare
+        let main = &mir.functions[&mir.entry_point.unwrap()];
+        assert!(main.locals.iter().any(|local| matches!(&local.ty,
+            MirType::Adt { name, substs } if name == crate::mir::RANGE_TYPE && substs == &vec![MirType::Int])));
+
+        crate::Compiler::new().compile_and_run(source).unwrap();
+        crate::Compiler::new().compile_and_run_ir(source).unwrap();
+        let wasm = crate::Compiler::new().compile_to_wasm(source).unwrap();
+        wasmparser::Validator::new().validate_all(&wasm).unwrap();
+
+        // The checks do fail when a loop runs the wrong number of times
+        for (expected, message) in [("!= 45", "sum"), ("!= 6", "nested"), ("!= 9", "range value")] {
+            let failing = source.replace(expected, "!= 1");
+            let error = crate::Compiler::new().compile_and_run(&failing).unwrap_err();
+            assert!(error.to_string().contains(&format!("Program aborted: {}", message)), "{}", error);
+            let error = crate::Compiler::new().compile_and_run_ir(&failing).unwrap_err();
+            assert!(error.to_string().contains(&format!("Program aborted: {}", message)), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_mir_user_struct_named_range_is_not_the_builtin_range() {
+        let source = "struct Range { lo: Int, hi: String }\n\
+            let r = Range { lo: 1, hi: \"top\" };\n\
+            if r.hi != \"top\" { abort(\"field\"); }\n\
+            let mut count = 0;\n\
+            for i in r.lo..3 { count = count + 1; }\n\
+            if count != 2 { abort(\"count\"); }\n\
+            seeAm r.hi;";
+        crate::Compiler::new().compile_and_run(source).unwrap();
+        crate::Compiler::new().compile_and_run_ir(source).unwrap();
+        let wasm = crate::Compiler::new().compile_to_wasm(source).unwrap();
+        wasmparser::Validator::new().validate_all(&wasm).unwrap();
+    }
+
+    #[test]
+    fn test_mir_final_expression_is_returned() {
+        let source = "enum Shape { Circle(Int), Square(Int) }\n\
+            fn next(n: Int) -> Int {\n\
+                n + 1\n\
+            }\n\
+            fn area(shape: Shape) -> Int {\n\
+                match shape {\n\
+                    Shape.Circle(r) => 3 * r * r,\n\
+                    Shape.Square(side) => side * side,\n\
+                }\n\
+            }\n\
+            fn name(n: Int) -> String {\n\
+                let small = n < 10;\n\
+                match small {\n\
+                    true => \"small\",\n\
+                    false => \"large\",\n\
+                }\n\
+            }\n\
+            fn clamp(n: Int) {\n\
+                if n > 100 { return 100; }\n\
+                let doubled = n * 2;\n\
+                next(doubled)\n\
+            }\n\
+            let twice = fn(x: Int) -> Int { x * 2 };\n\
+            if next(41) != 42 { abort(\"next\"); }\n\
+            if area(Shape.Circle(2)) != 12 { abort(\"circle\"); }\n\
+            if area(Shape.Square(5)) != 25 { abort(\"square\"); }\n\
+            if name(3) != \"small\" { abort(\"small\"); }\n\
+            if name(30) != \"large\" { abort(\"large\"); }\n\
+            if clamp(500) != 100 { abort(\"early return\"); }\n\
+            if clamp(4) != 9 { abort(\"tail call\"); }\n\
+            if twice(21) != 42 { abort(\"closure\"); }\n\
+            seeAm next(1);";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+        let hir = HirBuilder::new().transform_ast(&ast).unwrap();
+        let mir = MirBuilder::new().transform_hir(&hir).unwrap();
+
+        // Every return of a function with a final expression carries a value
+        for name in ["next", "area", "name", "clamp"] {
+            let function = mir.functions.values().find(|f| f.name == name).unwrap();
+            let returns: Vec<_> = function.basic_blocks.values().filter_map(|block| match &block.terminator {
+                MirTerminator::Return { value } => Some(value),
+                _ => None,
+            }).collect();
+            assert!(!returns.is_empty() && returns.iter().all(|value| value.is_some()), "{}: {:?}", name, returns);
+        }
+        let next = mir.functions.values().find(|f| f.name == "next").unwrap();
+        assert!(next.basic_blocks.values().any(|block| matches!(&block.terminator,
+            MirTerminator::Return { value: Some(MirOperand::Move(place, _) | MirOperand::Copy(place)) }
+                if next.basic_blocks.values().flat_map(|b| &b.statements).any(|stmt| matches!(&stmt.kind,
+                    MirStatementKind::Assign { place: assigned, rvalue: MirRvalue::BinaryOp { op: MirBinOp::Add, .. } } if assigned == place)))));
+
+        crate::Compiler::new().compile_and_run(source).unwrap();
+        crate::Compiler::new().compile_and_run_ir(source).unwrap();
+        let wasm = crate::Compiler::new().compile_to_wasm(source).unwrap();
+        wasmparser::Validator::new().validate_all(&wasm).unwrap();
+
+        // The checks above do see a wrong value
+        for (expected, message) in [("!= 42 { abort(\"next\")", "next"), ("!= 12", "circle"), ("!= \"large\"", "large"), ("!= 9", "tail call")] {
+            let failing = source.replacen(expected, &expected.replacen("!=", "==", 1), 1);
+            for result in [crate::Compiler::new().compile_and_run(&failing), crate::Compiler::new().compile_and_run_ir(&failing)] {
+                let error = result.unwrap_err();
+                assert!(error.to_string().contains(&format!("Program aborted: {}", message)), "{}", error);
+            }
+        }
+    }
+}
@@ -149,6 +149,7 @@ mod grammar_compliance {
                         Statement::Enum { .. } => {
                             // Valid statement type
                         }
+                        _ => {}
                     }
                 }
                     }
@@ -219,24 +220,29 @@ mod grammar_compliance {
                     prop_assert_eq!(statements.len(), 1);
                     
                     match &statements[0] {
-                Statement::Assignment { mutable, identifier, value: expr } => {
+                Statement::Assignment { mutable, identifier, value: expr, .. } => {
                     prop_assert!(!mutable);
                     prop_assert_eq!(identifier, &var_name);
                     
                     // Verify the value is preserved correctly
                     match expr {
-                        Expression::Literal(Literal::String(s)) => {
+                        Expression::Literal(Literal::String(s), _) => {
                             if value.starts_with('"') && value.ends_with('"') {
                                 let expected = &value[1..value.len()-1];
                                 prop_assert_eq!(s, expected);
                             }
                         }
-                        Expression::Literal(Literal::Number(n)) => {
+                        Expression::Literal(Literal::Number(n), _) => {
                             if let Ok(expected) = value.parse::<f64>() {
-                                prop_assert_eq!(n, expected);
+                                prop_assert_eq!(*n, expected);
+                            }
+                        }
+                        Expression::Literal(Literal::Integer(n), _) => {
+                            if let Ok(expected) = value.parse::<i64>() {
+                                prop_assert_eq!(*n, expected);
                             }
                         }
-                        Expression::Literal(Literal::Boolean(b)) => {
+                        Expression::Literal(Literal::Boolean(b), _) => {
                             if value == "true" {
                                 prop_assert!(b);
                             } else if value == "false" {
@@ -285,17 +291,20 @@ mod print_expression_correctness {
                     prop_assert_eq!(statements.len(), 1);
                     
                     match &statements[0] {
-                Statement::Print { expression } => {
+                Statement::Print { expression, .. } => {
                     // Verify the expression is parsed correctly
                     match expression {
-                        Expression::Literal(Literal::String(_)) => {
+                        Expression::Literal(Literal::String(_), _) => {
                             prop_assert!(expr.starts_with('"') && expr.ends_with('"'));
                         }
-                        Expression::Literal(Literal::Number(_)) => {
+                        Expression::Literal(Literal::Number(_), _) => {
                             prop_assert!(expr.parse::<f64>().is_ok());
                         }
-                        Expression::Literal(Literal::Boolean(b)) => {
-                            prop_assert!((expr == "true" && b) || (expr == "false" && !b));
+                        Expression::Literal(Literal::Integer(_), _) => {
+                            prop_assert!(expr.parse::<i64>().is_ok());
+                        }
+                        Expression::Literal(Literal::Boolean(b), _) => {
+                            prop_assert!((expr == "true" && *b) || (expr == "false" && !*b));
                         }
                         _ => {}
                     }
@@ -426,13 +435,14 @@ mod safe_auto_correction {
             let ast2 = parser2.parse().unwrap();
             
             // Both should have the same semantic structure
-            prop_assert_eq!(ast1.statements.len(), ast2.statements.len());
+            let (crate::ast::AstNode::Program(statements1), crate::ast::AstNode::Program(statements2)) = (&ast1, &ast2);
+            prop_assert_eq!(statements1.len(), statements2.len());
             
-            match (&ast1.statements[0], &ast2.statements[0]) {
-                (Statement::Print { expression: expr1 }, Statement::Print { expression: expr2 }) => {
+            match (&statements1[0], &statements2[0]) {
+                (Statement::Print { expression: expr1, .. }, Statement::Print { expression: expr2, .. }) => {
                     // Both should be print statements with the same content
                     match (expr1, expr2) {
-                        (Expression::Literal(lit1), Expression::Literal(lit2)) => {
+                        (Expression::Literal(lit1, _), Expression::Literal(lit2, _)) => {
                             prop_assert_eq!(lit1, lit2);
                         }
                         _ => {}
@@ -641,6 +651,7 @@ mod ir_pipeline_integrity {
                         crate::mir::MirTerminator::Goto { .. } |
                         crate::mir::MirTerminator::SwitchInt { .. } |
                         crate::mir::MirTerminator::Call { .. } |
+                        crate::mir::MirTerminator::Assert { .. } |
                         crate::mir::MirTerminator::Unreachable |
                         crate::mir::MirTerminator::Drop { .. } => {
                             // Valid terminator
@@ -1405,11 +1416,13 @@ pub mod unsafe_operation_enforcement {
     fn test_unsafe_operation_detection() {
         let analyzer = UnsafeOperationAnalyzer::new();
         
-        // Test that unsafe operations are detected
+        // Test that calls to declared unsafe functions are detected
         let unsafe_code = r#"
+            unsafe fn malloc(size: Int) -> Int { return size; }
+            unsafe fn system(command: String) -> Int { return 0; }
             seeAm malloc(1024);
-            seeAm free(ptr);
             seeAm system("rm -rf /");
+            seeAm free(ptr);
         "#;
         
         let mut lexer = crate::lexer::Lexer::new(unsafe_code);
@@ -1422,11 +1435,10 @@ pub mod unsafe_operation_enforcement {
         // Should detect unsafe operations
         assert!(!unsafe_ops.is_empty());
         
-        // Check that specific unsafe operations are detected
-        let has_malloc = unsafe_ops.iter().any(|op| matches!(op.operation, UnsafeOperation::RawMemoryAccess));
-        let has_system = unsafe_ops.iter().any(|op| matches!(op.operation, UnsafeOperation::SystemCall));
-        
-        assert!(has_malloc || has_system, "Should detect at least one unsafe operation");
+        // Only the two declared unsafe functions are audited; `free` is an ordinary call
+        assert_eq!(unsafe_ops.len(), 2);
+        assert_eq!(unsafe_ops[0].operation, UnsafeOperation::RawMemoryAccess);
+        assert_eq!(unsafe_ops[1].operation, UnsafeOperation::SystemCall);
     }
 
     #[test]
@@ -1457,6 +1469,8 @@ pub mod unsafe_operation_enforcement {
         
         // Test with some unsafe operations
         let unsafe_code = r#"
+            unsafe fn malloc(size: Int) -> Int { return size; }
+            unsafe fn ptr_read(address: Int) -> Int { return address; }
             seeAm malloc(1024);
             seeAm ptr_read(address);
         "#;
@@ -1500,8 +1514,11 @@ pub mod unsafe_operation_enforcement {
         ) {
             let analyzer = UnsafeOperationAnalyzer::new();
             
-            // Build test code with function calls
+            // Build test code with function calls; every name but `safe_func` is declared unsafe
             let mut code = String::new();
+            for op in ["malloc", "free", "system", "ptr_read"] {
+                code.push_str(&format!("unsafe fn {}() -> Int {{ return 0; }}\n", op));
+            }
             for op in &operations {
                 code.push_str(&format!("seeAm {}();\n", op));
             }
@@ -1513,6 +1530,7 @@ pub mod unsafe_operation_enforcement {
                         
                         // Report should be consistent with detected operations
                         prop_assert_eq!(report.total_unsafe_operations, unsafe_ops.len());
+                        prop_assert_eq!(unsafe_ops.len(), operations.iter().filter(|op| **op != "safe_func").count());
                         
                         // All operations should be implicit (no explicit unsafe blocks in test)
                         prop_assert_eq!(report.explicit_unsafe_count, 0);
@@ -1523,7 +1541,7 @@ pub mod unsafe_operation_enforcement {
         }
 
         #[test]
-        fn prop_unsafe_operation_types_classified_correctly(
+        fn prop_only_declared_unsafe_functions_are_audited(
             op_name in prop::sample::select(vec![
                 "malloc", "free", "memcpy", "memset",
                 "system", "exec", "fork",
@@ -1531,45 +1549,45 @@ pub mod unsafe_operation_enforcement {
                 "cast_ptr",
                 "raw_socket", "bind_socket",
                 "file_raw_read", "file_raw_write"
-            ])
+            ]),
+            declared in any::<bool>()
         ) {
             let analyzer = UnsafeOperationAnalyzer::new();
             
-            let code = format!("seeAm {}();", op_name);
-            
-            if let Ok(tokens) = crate::lexer::Lexer::new(&code).tokenize() {
-                if let Ok(ast) = crate::parser::Parser::new(tokens).parse() {
-                    if let Ok(unsafe_ops) = analyzer.analyze_ast(&ast, "test.ov") {
-                        if !unsafe_ops.is_empty() {
-                            let operation = &unsafe_ops[0].operation;
-                            
-                            // Verify correct classification
-                            match op_name {
-                                "malloc" | "free" | "memcpy" | "memset" => {
-                                    prop_assert_eq!(operation, &UnsafeOperation::RawMemoryAccess);
-                                }
-                                "system" | "exec" | "fork" => {
-                                    prop_assert_eq!(operation, &UnsafeOperation::SystemCall);
-                                }
-                                "ptr_read" | "ptr_write" => {
-                                    prop_assert_eq!(operation, &UnsafeOperation::PointerArithmetic);
-                                }
-                                "cast_ptr" => {
-                                    prop_assert_eq!(operation, &UnsafeOperation::UnsafeCast);
-                                }
-                                "raw_socket" | "bind_socket" => {
-                                    prop_assert_eq!(operation, &UnsafeOperation::UnverifiedNetworkAccess);
-                                }
-                                "file_raw_read" | "file_raw_write" => {
-                                    prop_assert_eq!(operation, &UnsafeOperation::UnsafeFileAccess);
-                                }
-                                _ => {
-                                    prop_assert_eq!(operation, &UnsafeOperation::ForeignFunctionCall);
-                                }
-                            }
-                        }
+            let declaration = if declared { "unsafe fn" } else { "fn" };
+            let code = format!("{} {}() -> Int {{ return 0; }}\nseeAm {}();", declaration, op_name, op_name);
+            
+            let tokens = crate::lexer::Lexer::new(&code).tokenize().unwrap();
+            let ast = crate::parser::Parser::new(tokens).parse().unwrap();
+            let unsafe_ops = analyzer.analyze_ast(&ast, "test.ov").unwrap();
+
+            // Only the declaration decides whether a call is audited; the name
+            // then classifies the operation
+            if declared {
+                prop_assert_eq!(unsafe_ops.len(), 1);
+                let operation = &unsafe_ops[0].operation;
+                match op_name {
+                    "malloc" | "free" | "memcpy" | "memset" => {
+                        prop_assert_eq!(operation, &UnsafeOperation::RawMemoryAccess);
+                    }
+                    "system" | "exec" | "fork" => {
+                        prop_assert_eq!(operation, &UnsafeOperation::SystemCall);
+                    }
+                    "ptr_read" | "ptr_write" => {
+                        prop_assert_eq!(operation, &UnsafeOperation::PointerArithmetic);
+                    }
+                    "cast_ptr" => {
+                        prop_assert_eq!(operation, &UnsafeOperation::UnsafeCast);
+                    }
+                    "raw_socket" | "bind_socket" => {
+                        prop_assert_eq!(operation, &UnsafeOperation::UnverifiedNetworkAccess);
+                    }
+                    _ => {
+                        prop_assert_eq!(operation, &UnsafeOperation::UnsafeFileAccess);
                     }
                 }
+            } else {
+                prop_assert!(unsafe_ops.is_empty());
             }
         }
     }
@@ -2859,9 +2877,10 @@ mod bootstrap_process_reproducibility {
 /// **Feature: ovie-programming-language-stage-2, Bootstrap Verification Properties**
 /// 
 /// Additional properties specific to the bootstrap verification system
-mod bootstrap_verification_properties {
+mod bootstrap_verifier_properties {
     use super::*;
-    use crate::self_hosting::{BootstrapVerifier, BootstrapConfig, EquivalenceTester};
+    use crate::self_hosting::{BootstrapVerifier, BootstrapConfig};
+    use crate::self_hosting::bootstrap_verification::EquivalenceTester;
 
     proptest! {
         #[test]
@@ -4,7 +4,7 @@
 //! These tests ensure that AST nodes maintain their invariants throughout
 //! the compilation pipeline.
 
-use crate::{Compiler, OvieResult, OvieError};
+use crate::{Compiler, OvieResult, OvieError, ast::AstInvariantValidation};
 
 /// Test that AST validation passes for valid AST structures
 #[test]